@@ -0,0 +1,76 @@
+//! Embeds build metadata for `phantom version`
+//!
+//! Each variable is only set when it can be determined, so a build from a
+//! source tarball without git still succeeds and reports "unknown".
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    watch_git_head();
+
+    if let Some(sha) = command_output("git", &["rev-parse", "HEAD"]) {
+        println!("cargo:rustc-env=PHANTOM_GIT_SHA={sha}");
+    }
+    if let Some(date) = build_date() {
+        println!("cargo:rustc-env=PHANTOM_BUILD_DATE={date}");
+    }
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    if let Some(version) = command_output(&rustc, &["--version"]) {
+        println!("cargo:rustc-env=PHANTOM_RUSTC_VERSION={version}");
+    }
+}
+
+/// Rebuild when HEAD moves, so the embedded commit stays current
+fn watch_git_head() {
+    let head = Path::new(".git/HEAD");
+    if !head.is_file() {
+        return;
+    }
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    if let Ok(contents) = std::fs::read_to_string(head) {
+        if let Some(reference) = contents.trim().strip_prefix("ref: ") {
+            println!("cargo:rerun-if-changed=.git/{reference}");
+        }
+    }
+}
+
+/// Trimmed stdout of a successful command
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let stdout = stdout.trim();
+    (!stdout.is_empty()).then(|| stdout.to_string())
+}
+
+/// UTC build date as YYYY-MM-DD, honouring SOURCE_DATE_EPOCH for reproducible builds
+fn build_date() -> Option<String> {
+    let seconds = match env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch.parse::<u64>().ok()?,
+        Err(_) => SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs(),
+    };
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    Some(format!("{year:04}-{month:02}-{day:02}"))
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
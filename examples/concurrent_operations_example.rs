@@ -1,6 +1,7 @@
 /// Example demonstrating concurrent operations in phantom
 /// This shows how phantom uses async concurrency to improve performance
 use phantom_rs::core::executors::MockCommandExecutor;
+use phantom_rs::core::filesystems::MockFileSystem;
 use phantom_rs::worktree::concurrent::{
     check_worktrees_status_concurrent, get_worktrees_info_concurrent, list_worktrees_concurrent,
 };
@@ -16,25 +17,27 @@ async fn main() {
     let git_root = PathBuf::from("/repo");
 
     // Mock the git worktree list command
-    mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-        "worktree /repo\n\
-             HEAD abc123\n\
-             branch refs/heads/main\n\
-             \n\
-             worktree /repo/.git/phantom/worktrees/feature-a\n\
-             HEAD def456\n\
-             branch refs/heads/feature-a\n\
-             \n\
-             worktree /repo/.git/phantom/worktrees/feature-b\n\
-             HEAD ghi789\n\
-             branch refs/heads/feature-b\n\
-             \n\
-             worktree /repo/.git/phantom/worktrees/feature-c\n\
-             HEAD jkl012\n\
-             branch refs/heads/feature-c\n",
-        "",
-        0,
-    );
+    mock.expect_command("git")
+        .with_args(&["worktree", "list", "--porcelain", "-z"])
+        .returns_output(
+            "worktree /repo\0\
+             HEAD abc123\0\
+             branch refs/heads/main\0\
+             \0\
+             worktree /repo/.git/phantom/worktrees/feature-a\0\
+             HEAD def456\0\
+             branch refs/heads/feature-a\0\
+             \0\
+             worktree /repo/.git/phantom/worktrees/feature-b\0\
+             HEAD ghi789\0\
+             branch refs/heads/feature-b\0\
+             \0\
+             worktree /repo/.git/phantom/worktrees/feature-c\0\
+             HEAD jkl012\0\
+             branch refs/heads/feature-c\0",
+            "",
+            0,
+        );
 
     // Mock status checks for each worktree (these will run concurrently)
     mock.expect_command("git")
@@ -57,7 +60,9 @@ async fn main() {
     // Example 1: List worktrees with concurrent status checks
     println!("1. Listing worktrees with concurrent status checks:");
     let start = Instant::now();
-    let result = list_worktrees_concurrent(executor.clone(), &git_root).await.unwrap();
+    let result = list_worktrees_concurrent(executor.clone(), &MockFileSystem::new(), &git_root)
+        .await
+        .unwrap();
     let duration = start.elapsed();
 
     for worktree in &result.worktrees {
@@ -0,0 +1,506 @@
+//! Library API for driving phantom from other Rust programs
+//!
+//! [`Phantom`] wraps one repository and exposes the operations behind the
+//! CLI commands. The CLI handlers call it too, so both behave the same.
+//!
+//! ```
+//! use phantom_rs::{MockCommandExecutor, MockFileSystem, Phantom};
+//!
+//! let mut executor = MockCommandExecutor::new();
+//! executor
+//!     .expect_command("git")
+//!     .with_args(&["worktree", "list", "--porcelain", "-z"])
+//!     .returns_output(
+//!         "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+//!          worktree /repo/.git/phantom/worktrees/feature\0HEAD def456\0branch refs/heads/feature\0",
+//!         "",
+//!         0,
+//!     );
+//! executor.expect_command("git").with_args(&["status", "--porcelain"]).returns_output("", "", 0);
+//!
+//! let phantom = Phantom::new("/repo", executor, MockFileSystem::new());
+//! let listed = tokio::runtime::Runtime::new().unwrap().block_on(phantom.list()).unwrap();
+//!
+//! assert_eq!(listed.worktrees[0].name, "feature");
+//! assert_eq!(serde_json::to_value(&listed).unwrap()["worktrees"][0]["is_clean"], true);
+//! ```
+
+use crate::config::provider::ConfigProvider;
+use crate::core::command_executor::{CommandExecutor, CommandOutput, OnOutputLine};
+use crate::core::env_map::EnvMap;
+use crate::core::environment::Environment;
+use crate::core::executors::RealCommandExecutor;
+use crate::core::filesystem::FileSystem;
+use crate::core::filesystems::RealFileSystem;
+use crate::core::utils::timed;
+use crate::git::backend::GitConfig;
+use crate::git::command_backend::CommandBackend;
+use crate::git::libs::changed_files::base_of_branch;
+use crate::git::libs::current_commit::current_commit;
+use crate::git::libs::get_current_branch::get_current_branch;
+use crate::git::libs::get_git_root::get_git_root;
+use crate::process::exec::{capture_in_worktree, exec_in_worktree, stream_in_worktree};
+use crate::process::spawn::SpawnSuccess;
+use crate::worktree::attach::attach_worktree;
+use crate::worktree::concurrent::list_worktrees_concurrent_with_options;
+use crate::worktree::create::{create_worktree, CreateRollback};
+use crate::worktree::delete::delete_worktree;
+use crate::worktree::file_copier::{copy_configured_files, ConfiguredCopy};
+use crate::worktree::list::{ListOptions, ListWorktreesSuccess};
+use crate::worktree::locate::{where_worktree, WhereWorktreeSuccess};
+use crate::worktree::paths::get_worktree_path;
+use crate::worktree::stack::clear_stack_parent;
+use crate::worktree::state::{remove_worktree_metadata, write_worktree_metadata, WorktreeMetadata};
+use crate::worktree::types::{
+    AttachWorktreeOptions, AttachWorktreeSuccess, CreateWorktreeOptions, CreateWorktreeSuccess,
+    DeleteWorktreeOptions, DeleteWorktreeSuccess,
+};
+use crate::Result;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Phantom operations on a single repository
+#[derive(Debug, Clone)]
+pub struct Phantom<E = RealCommandExecutor, F = RealFileSystem> {
+    git_root: PathBuf,
+    executor: E,
+    filesystem: F,
+    /// Repository configuration, read when an operation first needs a setting from it;
+    /// its environment is also the one commands run in worktrees start from
+    config: ConfigProvider,
+}
+
+impl Phantom {
+    /// Open the repository containing the current directory, using git and the real filesystem
+    pub async fn discover() -> Result<Self> {
+        Self::discover_with(RealCommandExecutor, RealFileSystem::new()).await
+    }
+}
+
+impl<E, F> Phantom<E, F>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem,
+{
+    /// Operate on the repository at `git_root`
+    pub fn new(git_root: impl Into<PathBuf>, executor: E, filesystem: F) -> Self {
+        Self { git_root: git_root.into(), executor, filesystem, config: ConfigProvider::default() }
+    }
+
+    /// Read variables from `environment` instead of the process environment
+    pub fn with_environment(mut self, environment: Arc<dyn Environment>) -> Self {
+        self.config = ConfigProvider::new(environment);
+        self
+    }
+
+    /// Open the repository containing the current directory
+    pub async fn discover_with(executor: E, filesystem: F) -> Result<Self> {
+        let git_root = get_git_root(executor.clone()).await?;
+        Ok(Self::new(git_root, executor, filesystem))
+    }
+
+    /// Root of the main worktree
+    pub fn git_root(&self) -> &Path {
+        &self.git_root
+    }
+
+    /// Create a worktree and record its creation metadata
+    pub async fn create(
+        &self,
+        name: &str,
+        options: CreateWorktreeOptions,
+    ) -> Result<CreateWorktreeSuccess> {
+        let commitish = options.commitish.clone();
+        let mut result =
+            create_worktree(self.executor.clone(), &self.git_root, name, options).await?;
+
+        let base_ref = match commitish {
+            Some(commitish) => Some(commitish),
+            None => self.resolve_head().await,
+        };
+        let metadata = WorktreeMetadata::now(base_ref);
+        if let Err(e) =
+            write_worktree_metadata(&self.filesystem, &self.git_root, name, &metadata).await
+        {
+            result.metadata_error = Some(e.to_string());
+        }
+        Ok(result)
+    }
+
+    /// What HEAD of the main worktree names now: its branch, or its commit when detached
+    ///
+    /// Recorded in place of a literal "HEAD", which would name something else by the time it is read.
+    async fn resolve_head(&self) -> Option<String> {
+        match get_current_branch(self.executor.clone(), &self.git_root).await {
+            Ok(branch) if !branch.is_empty() => Some(branch),
+            Ok(_) => current_commit(self.executor.clone(), &self.git_root).await.ok(),
+            Err(_) => None,
+        }
+    }
+
+    /// Undo a creation whose later setup failed: remove the worktree, its branch and metadata
+    ///
+    /// Returns a description of each step that could not be undone.
+    pub async fn roll_back_create(&self, name: &str, branch: &str) -> Vec<String> {
+        let config = GitConfig::with_cwd(&self.git_root)
+            .with_timeout(self.executor.git_settings().timeout.as_secs());
+        let backend = CommandBackend::new(config, self.executor.clone());
+        let rollback = CreateRollback::new(get_worktree_path(&self.git_root, name), branch);
+        let unreverted = rollback.run(&backend).await;
+        // Best effort: metadata only adds list details
+        let _ = remove_worktree_metadata(&self.filesystem, &self.git_root, name).await;
+        unreverted
+    }
+
+    /// Create a worktree for an existing local branch, named after the branch
+    pub async fn attach(
+        &self,
+        branch: &str,
+        options: AttachWorktreeOptions,
+    ) -> Result<AttachWorktreeSuccess> {
+        timed("worktree_add", attach_worktree(self.executor.clone(), &self.git_root, branch))
+            .await?;
+
+        let path = get_worktree_path(&self.git_root, branch);
+        // The worktree is usable without the copies, so a failure is reported instead
+        let source = options.copy_source.as_deref().unwrap_or(&self.git_root);
+        let copy = copy_configured_files(
+            self.executor.clone(),
+            options.copy_files.as_deref(),
+            source,
+            &path,
+            None,
+        );
+        let (copy, copy_error) = match timed("file_copy", copy).await {
+            Ok(copy) => (copy, None),
+            Err(e) => (ConfiguredCopy::default(), Some(e.to_string())),
+        };
+
+        // Best effort: without a base, show falls back to the default branch
+        let base_ref =
+            base_of_branch(self.executor.clone(), &self.git_root, branch).await.ok().flatten();
+        let metadata = WorktreeMetadata::now(base_ref);
+        let metadata_error =
+            write_worktree_metadata(&self.filesystem, &self.git_root, branch, &metadata)
+                .await
+                .err()
+                .map(|e| e.to_string());
+        Ok(AttachWorktreeSuccess {
+            name: branch.to_string(),
+            path: path.to_string_lossy().to_string(),
+            copied_files: copy.copied_files,
+            skipped_files: copy.skipped_files,
+            expanded_from: copy.expanded_from,
+            copy_error,
+            metadata_error,
+        })
+    }
+
+    /// Delete a worktree along with its stack entry and metadata
+    ///
+    /// Without `options.snapshot`, uncommitted changes are saved as the repository config says.
+    pub async fn delete(
+        &self,
+        name: &str,
+        mut options: DeleteWorktreeOptions,
+    ) -> Result<DeleteWorktreeSuccess> {
+        if options.snapshot.is_none() {
+            // An unreadable config keeps the default
+            let loaded = self.config.get(&self.git_root).await.ok().flatten();
+            options.snapshot = loaded.map(|loaded| loaded.config.delete_snapshot());
+        }
+        let result =
+            delete_worktree(self.executor.clone(), &self.git_root, name, options, &self.filesystem)
+                .await?;
+        // Best effort: a missing or unreadable entry is not worth failing the delete
+        let _ = clear_stack_parent(self.executor.clone(), &self.git_root, name).await;
+        let _ = remove_worktree_metadata(&self.filesystem, &self.git_root, name).await;
+        Ok(result)
+    }
+
+    /// List the phantom worktrees with their branch and status
+    pub async fn list(&self) -> Result<ListWorktreesSuccess> {
+        self.list_with_options(ListOptions::default()).await
+    }
+
+    /// List the phantom worktrees, skipping the status checks unless `options` asks for them
+    pub async fn list_with_options(&self, options: ListOptions) -> Result<ListWorktreesSuccess> {
+        list_worktrees_concurrent_with_options(
+            self.executor.clone(),
+            &self.filesystem,
+            &self.git_root,
+            options,
+        )
+        .await
+    }
+
+    /// Path of an existing worktree
+    pub async fn locate(&self, name: &str) -> Result<WhereWorktreeSuccess> {
+        where_worktree(&self.git_root, name, &self.filesystem).await
+    }
+
+    /// Run a command in a worktree with the phantom environment variables set
+    pub async fn exec(&self, name: &str, command: &str, args: &[String]) -> Result<SpawnSuccess> {
+        self.exec_with_env(name, command, args, &EnvMap::new(), None).await
+    }
+
+    /// Run a command in a worktree like [`Phantom::exec`], with `env` set on top of the phantom variables
+    ///
+    /// `subdir` runs it in a directory of the worktree instead of at its top.
+    pub async fn exec_with_env(
+        &self,
+        name: &str,
+        command: &str,
+        args: &[String],
+        env: &EnvMap,
+        subdir: Option<&Path>,
+    ) -> Result<SpawnSuccess> {
+        exec_in_worktree(
+            &self.git_root,
+            name,
+            command,
+            args,
+            env,
+            subdir,
+            &self.filesystem,
+            self.config.environment(),
+            Some(self.executor.clone()),
+        )
+        .await
+    }
+
+    /// Run a command in a worktree like [`Phantom::exec`], returning its output instead of printing it
+    pub async fn exec_captured(
+        &self,
+        name: &str,
+        command: &str,
+        args: &[String],
+    ) -> Result<CommandOutput> {
+        self.exec_captured_with_env(name, command, args, &EnvMap::new(), None).await
+    }
+
+    /// Capture a command's output like [`Phantom::exec_captured`], with `env` set on top of the phantom variables
+    ///
+    /// `subdir` runs it in a directory of the worktree instead of at its top.
+    pub async fn exec_captured_with_env(
+        &self,
+        name: &str,
+        command: &str,
+        args: &[String],
+        env: &EnvMap,
+        subdir: Option<&Path>,
+    ) -> Result<CommandOutput> {
+        capture_in_worktree(
+            &self.git_root,
+            name,
+            command,
+            args,
+            env,
+            subdir,
+            &self.filesystem,
+            self.config.environment(),
+            self.executor.clone(),
+        )
+        .await
+    }
+
+    /// Capture a command's output like [`Phantom::exec_captured_with_env`], passing each line to `on_line` as it is written
+    pub async fn exec_streamed_with_env(
+        &self,
+        name: &str,
+        command: &str,
+        args: &[String],
+        env: &EnvMap,
+        subdir: Option<&Path>,
+        on_line: &OnOutputLine<'_>,
+    ) -> Result<CommandOutput> {
+        stream_in_worktree(
+            &self.git_root,
+            name,
+            command,
+            args,
+            env,
+            subdir,
+            &self.filesystem,
+            self.config.environment(),
+            self.executor.clone(),
+            on_line,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+    use crate::git::git_executor_adapter::GitExecutor;
+
+    fn expect_is_dir(fs: &MockFileSystem, path: &str) {
+        fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from(path)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+    }
+
+    #[tokio::test]
+    async fn test_discover_with() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+
+        let phantom = Phantom::discover_with(mock, MockFileSystem::new()).await.unwrap();
+        assert_eq!(phantom.git_root(), Path::new("/repo"));
+    }
+
+    #[tokio::test]
+    async fn test_locate_serializes() {
+        let fs = MockFileSystem::new();
+        expect_is_dir(&fs, "/repo/.git/phantom/worktrees/feature");
+
+        let phantom = Phantom::new("/repo", MockCommandExecutor::new(), fs);
+        let located = phantom.locate("feature").await.unwrap();
+        assert_eq!(
+            serde_json::to_value(&located).unwrap(),
+            serde_json::json!({ "path": "/repo/.git/phantom/worktrees/feature" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exec_returns_exit_code() {
+        let fs = MockFileSystem::new();
+        expect_is_dir(&fs, "/repo/.git/phantom/worktrees/feature");
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("make")
+            .with_args(&["test"])
+            .in_dir("/repo/.git/phantom/worktrees/feature")
+            .returns_output("", "", 3);
+
+        let phantom = Phantom::new("/repo", mock, fs);
+        let result = phantom.exec("feature", "make", &["test".to_string()]).await.unwrap();
+        assert_eq!(result.exit_code, 3);
+    }
+
+    #[tokio::test]
+    async fn test_attach_copies_requested_files() {
+        use crate::core::executors::RealCommandExecutor;
+        use crate::core::filesystems::RealFileSystem;
+        use crate::test_utils::TestRepo;
+
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("README.md", "readme", "Initial commit").await.unwrap();
+        repo.create_branch("feature").await.unwrap();
+        let git = GitExecutor::new(RealCommandExecutor).with_cwd(repo.path());
+        git.run(&["checkout", "main"]).await.unwrap();
+        tokio::fs::write(repo.path().join(".env"), "KEY=1").await.unwrap();
+
+        let phantom = Phantom::new(repo.path(), RealCommandExecutor, RealFileSystem::new());
+        let options = AttachWorktreeOptions {
+            copy_files: Some(vec![".env".to_string(), "missing.txt".to_string()]),
+            ..Default::default()
+        };
+        let result = phantom.attach("feature", options).await.unwrap();
+
+        assert_eq!(result.copied_files, Some(vec![".env".to_string()]));
+        assert_eq!(result.skipped_files, Some(vec!["missing.txt".to_string()]));
+        assert!(Path::new(&result.path).join(".env").exists());
+    }
+
+    #[tokio::test]
+    async fn test_create_records_branch_of_head_as_base() {
+        use crate::core::executors::RealCommandExecutor;
+        use crate::core::filesystems::RealFileSystem;
+        use crate::test_utils::TestRepo;
+        use crate::worktree::state::read_worktree_metadata;
+
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("README.md", "readme", "Initial commit").await.unwrap();
+        let phantom = Phantom::new(repo.path(), RealCommandExecutor, RealFileSystem::new());
+
+        phantom.create("feature", CreateWorktreeOptions::default()).await.unwrap();
+        let metadata = read_worktree_metadata(&RealFileSystem::new(), repo.path(), "feature").await;
+        assert_eq!(metadata.base_ref.as_deref(), Some("main"));
+
+        // A detached HEAD is recorded as its commit
+        let git = GitExecutor::new(RealCommandExecutor).with_cwd(repo.path());
+        let commit = git.run(&["rev-parse", "HEAD"]).await.unwrap();
+        git.run(&["checkout", "--detach"]).await.unwrap();
+        phantom.create("detached", CreateWorktreeOptions::default()).await.unwrap();
+        let metadata =
+            read_worktree_metadata(&RealFileSystem::new(), repo.path(), "detached").await;
+        assert_eq!(metadata.base_ref, Some(commit));
+    }
+
+    #[tokio::test]
+    async fn test_attach_records_default_branch_as_base() {
+        use crate::core::executors::RealCommandExecutor;
+        use crate::core::filesystems::RealFileSystem;
+        use crate::git::libs::changed_files::{committed_changes, resolve_diff_base};
+        use crate::test_utils::TestRepo;
+        use crate::worktree::state::{read_worktree_metadata, write_worktree_metadata};
+
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("README.md", "readme", "Initial commit").await.unwrap();
+        repo.create_branch("feature").await.unwrap();
+        repo.create_file_and_commit("feature.txt", "feature", "Feature commit").await.unwrap();
+        let git = GitExecutor::new(RealCommandExecutor).with_cwd(repo.path());
+        git.run(&["checkout", "main"]).await.unwrap();
+
+        let phantom = Phantom::new(repo.path(), RealCommandExecutor, RealFileSystem::new());
+        let attached = phantom.attach("feature", AttachWorktreeOptions::default()).await.unwrap();
+        let worktree = PathBuf::from(&attached.path);
+        tokio::fs::write(worktree.join("more.txt"), "more").await.unwrap();
+        let worktree_git = GitExecutor::new(RealCommandExecutor).with_cwd(&worktree);
+        worktree_git.run(&["add", "more.txt"]).await.unwrap();
+        worktree_git.run(&["commit", "-m", "More"]).await.unwrap();
+
+        let fs = RealFileSystem::new();
+        let metadata = read_worktree_metadata(&fs, repo.path(), "feature").await;
+        assert_eq!(metadata.base_ref.as_deref(), Some("main"));
+
+        // Both commits on the branch show up against the recorded base
+        let base = resolve_diff_base(
+            RealCommandExecutor,
+            repo.path(),
+            metadata.base_ref.as_deref(),
+            Some("feature"),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let changes = committed_changes(RealCommandExecutor, &worktree, &base).await.unwrap();
+        let paths: Vec<_> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["feature.txt", "more.txt"]);
+
+        // Metadata from older versions recorded the branch itself
+        let legacy = WorktreeMetadata::now(Some("feature".to_string()));
+        write_worktree_metadata(&fs, repo.path(), "feature", &legacy).await.unwrap();
+        let base =
+            resolve_diff_base(RealCommandExecutor, repo.path(), Some("feature"), Some("feature"))
+                .await
+                .unwrap();
+        assert_eq!(base.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_options_round_trip_through_json() {
+        let options: DeleteWorktreeOptions =
+            serde_json::from_str(r#"{"force": {"dirty": true}}"#).unwrap();
+        assert!(options.force.dirty);
+        assert!(!options.force.locked);
+
+        let options: CreateWorktreeOptions =
+            serde_json::from_str(r#"{"branch": "feature"}"#).unwrap();
+        assert_eq!(options.branch.as_deref(), Some("feature"));
+        assert!(options.commitish.is_none());
+    }
+}
@@ -0,0 +1,19 @@
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct AdoptArgs {
+    /// Path of a worktree of this repository created with `git worktree add`
+    pub path: PathBuf,
+
+    /// Name for the phantom (defaults to the worktree's branch)
+    pub name: Option<String>,
+
+    /// Leave the worktree where it is instead of moving it under the phantom directory
+    #[arg(long)]
+    pub in_place: bool,
+
+    /// Output in JSON format
+    #[arg(long)]
+    pub json: bool,
+}
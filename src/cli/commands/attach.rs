@@ -1,9 +1,33 @@
 use clap::Args;
+use std::path::PathBuf;
 
 #[derive(Args, Debug)]
 pub struct AttachArgs {
-    /// Branch name to attach to
-    pub branch: String,
+    /// Branch name to attach to (can be omitted with --fzf or --from-file)
+    #[arg(required_unless_present_any = &["fzf", "from_file"])]
+    pub branch: Option<String>,
+
+    /// Attach every branch listed in a file, one per line (`-` reads stdin)
+    ///
+    /// Blank lines and lines starting with `#` are ignored. Branches that already
+    /// have a worktree are skipped.
+    #[arg(long, value_name = "PATH", conflicts_with_all = &["branch", "fzf", "exec", "shell"])]
+    pub from_file: Option<PathBuf>,
+
+    /// Select a branch without a worktree interactively with fzf
+    #[arg(long)]
+    pub fzf: bool,
+
+    /// With --fzf, also offer branches that only exist on a remote (origin by default)
+    #[arg(
+        long,
+        value_name = "REMOTE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "origin",
+        requires = "fzf"
+    )]
+    pub include_remote: Option<String>,
 
     /// Execute a command after attaching
     #[arg(short = 'e', long = "exec")]
@@ -13,6 +37,30 @@ pub struct AttachArgs {
     #[arg(short = 's', long)]
     pub shell: bool,
 
+    /// Fetch the branch from the remote before attaching
+    #[arg(long)]
+    pub fetch: bool,
+
+    /// Remote to fetch the branch from
+    #[arg(long, default_value = "origin", requires = "fetch")]
+    pub remote: String,
+
+    /// Do not make the branch track `<remote>/<branch>`, even when that exists
+    #[arg(long)]
+    pub no_track: bool,
+
+    /// Do not copy the configured copyFiles into the worktree
+    #[arg(long)]
+    pub no_copy: bool,
+
+    /// Copy files from this phantom or directory instead of the main worktree
+    #[arg(long, value_name = "NAME_OR_PATH", conflicts_with = "no_copy")]
+    pub source: Option<String>,
+
+    /// Print the git commands and file copies that would run, without attaching anything
+    #[arg(long, conflicts_with = "from_file")]
+    pub dry_run: bool,
+
     /// Output in JSON format
     #[arg(long)]
     pub json: bool,
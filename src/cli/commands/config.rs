@@ -0,0 +1,17 @@
+use clap::{Args, Subcommand};
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Validate the repository configuration file
+    Validate,
+    /// Print the effective configuration and the file each key comes from
+    Show,
+    /// Print the configuration files that are read, or "none"
+    Path,
+}
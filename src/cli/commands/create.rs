@@ -1,15 +1,25 @@
 use clap::Args;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Args, Debug)]
 pub struct CreateArgs {
-    /// Name of the worktree to create
-    pub name: String,
+    /// Names of the worktrees to create; several names are created concurrently
+    #[arg(value_name = "NAME", required = true)]
+    pub names: Vec<String>,
 
     /// Branch name (defaults to worktree name)
     #[arg(short, long)]
     pub branch: Option<String>,
 
+    /// Prefix for the new branch name, overriding the configured branchPrefix; supports {user}
+    #[arg(long, value_name = "PREFIX", conflicts_with_all = &["branch", "no_branch_prefix"])]
+    pub branch_prefix: Option<String>,
+
+    /// Do not apply the configured branchPrefix
+    #[arg(long)]
+    pub no_branch_prefix: bool,
+
     /// Execute a command after creating the worktree
     #[arg(short = 'x', long)]
     pub exec: Option<String>,
@@ -58,14 +68,62 @@ pub struct CreateArgs {
     #[arg(long = "kitty-h", hide = true)]
     pub kitty_h: bool,
 
-    /// Files to copy from the current worktree
-    #[arg(long = "copy", value_delimiter = ',')]
-    pub copy_files: Option<Vec<String>>,
+    /// Extra files to copy into the new worktree, added to the configured copyFiles
+    #[arg(long = "copy-files", visible_alias = "copy-file", alias = "copy", value_delimiter = ',')]
+    pub copy_files: Vec<String>,
+
+    /// Copy only the files given with --copy-files, ignoring the configured copyFiles
+    #[arg(long, requires = "copy_files")]
+    pub copy_files_only: bool,
+
+    /// Do not copy any files into the new worktree
+    #[arg(long, conflicts_with_all = &["copy_files", "copy_files_only"])]
+    pub no_copy: bool,
+
+    /// Copy files from this phantom or directory instead of the main worktree
+    #[arg(long, value_name = "NAME_OR_PATH", conflicts_with_all = &["no_copy", "workspace"])]
+    pub source: Option<String>,
 
     /// Base ref for the new branch (commit/branch/tag)
     #[arg(long)]
     pub base: Option<String>,
 
+    /// Stack on a phantom or branch: base on it and track it as upstream
+    #[arg(long, value_name = "NAME_OR_BRANCH", conflicts_with = "base")]
+    pub stack_on: Option<String>,
+
+    /// Check out the base commit with a detached HEAD instead of creating a branch
+    #[arg(long, conflicts_with_all = &["branch", "branch_prefix", "no_branch_prefix", "stack_on", "workspace"])]
+    pub detach: bool,
+
+    /// Add the worktree without checking out any files
+    #[arg(long, conflicts_with_all = &["sparse", "no_sparse"])]
+    pub no_checkout: bool,
+
+    /// Check out only these directories (cone-mode sparse checkout), overriding create.sparsePatterns
+    #[arg(long, value_name = "PATTERN", num_args = 1.., conflicts_with_all = &["no_sparse", "workspace"])]
+    pub sparse: Vec<String>,
+
+    /// Check out every file, ignoring the configured create.sparsePatterns
+    #[arg(long)]
+    pub no_sparse: bool,
+
+    /// Create the worktree in every repository listed in phantom-workspace.toml
+    #[arg(long, conflicts_with_all = &["stack_on", "shell", "exec", "tmux", "tmux_vertical", "tmux_v", "tmux_horizontal", "tmux_h", "kitty", "kitty_vertical", "kitty_v", "kitty_horizontal", "kitty_h"])]
+    pub workspace: bool,
+
+    /// With several names, stop starting new worktrees after the first failure
+    #[arg(long)]
+    pub fail_fast: bool,
+
+    /// Keep a partially created worktree when a setup step fails instead of rolling it back
+    #[arg(long)]
+    pub keep_on_failure: bool,
+
+    /// Print the git commands, file copies and hooks that would run, without creating anything
+    #[arg(long, conflicts_with = "workspace")]
+    pub dry_run: bool,
+
     /// Output in JSON format
     #[arg(long)]
     pub json: bool,
@@ -76,10 +134,34 @@ pub struct CreateArgs {
 pub struct CreateResult {
     pub success: bool,
     pub name: String,
+    /// Empty for a detached worktree
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub branch: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub detached: bool,
     pub path: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub copied_files: Option<Vec<String>>,
+    /// Files copied from the configured editor directories
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor_files: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scratch_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack_parent: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Milliseconds per timed section, with --timings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings_ms: Option<BTreeMap<String, u64>>,
+}
+
+/// Result of creating several worktrees for JSON output
+#[derive(Serialize)]
+pub struct CreateBatchResult {
+    pub success: bool,
+    pub worktrees: Vec<CreateResult>,
+    /// Milliseconds per timed section, with --timings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings_ms: Option<BTreeMap<String, u64>>,
 }
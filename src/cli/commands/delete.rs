@@ -1,15 +1,38 @@
-use clap::Args;
+use crate::config::types::DeleteSnapshot;
+use crate::worktree::types::DeleteOutcome;
+use clap::{Args, ValueEnum};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Args, Debug)]
 pub struct DeleteArgs {
     /// Name of the worktree to delete (optional if using --current or --fzf)
     pub name: Option<String>,
 
-    /// Force deletion even if there are uncommitted changes
+    /// Force deletion even if there are uncommitted changes, the worktree is locked or its branch is not fully merged
     #[arg(short, long)]
     pub force: bool,
 
+    /// Force deletion even if there are uncommitted changes
+    #[arg(long)]
+    pub force_dirty: bool,
+
+    /// Force deletion even if the worktree is locked
+    #[arg(long)]
+    pub force_locked: bool,
+
+    /// Keep the worktree's branch instead of deleting it
+    #[arg(long)]
+    pub keep_branch: bool,
+
+    /// Save uncommitted changes before a forced delete (default: delete.snapshot in the config)
+    #[arg(long, value_name = "MODE")]
+    pub snapshot: Option<SnapshotMode>,
+
+    /// Delete a worktree with uncommitted changes without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+
     /// Delete the current worktree
     #[arg(long)]
     pub current: bool,
@@ -18,17 +41,97 @@ pub struct DeleteArgs {
     #[arg(long)]
     pub fzf: bool,
 
+    /// Delete the worktree from every repository listed in phantom-workspace.toml
+    #[arg(long, conflicts_with_all = &["current", "fzf"])]
+    pub workspace: bool,
+
+    /// Delete every clean worktree whose branch is merged or whose upstream is gone (branches without commits of their own count as merged)
+    #[arg(long, conflicts_with_all = &["name", "current", "fzf", "workspace"])]
+    pub prune: bool,
+
+    /// Branch or ref that --prune checks against (default: the repository's default branch)
+    #[arg(long, value_name = "REF", requires = "prune")]
+    pub into: Option<String>,
+
+    /// With --prune, print what would be removed without deleting anything
+    #[arg(long, requires = "prune")]
+    pub dry_run: bool,
+
+    /// Delete the worktrees named on stdin, one per line
+    #[arg(long, conflicts_with_all = &["name", "current", "fzf", "workspace", "prune"])]
+    pub stdin_names: bool,
+
+    /// Print the repository root on stdout and everything else on stderr (implied by --current)
+    #[arg(long, conflicts_with_all = &["json", "workspace", "prune", "stdin_names"])]
+    pub print_root: bool,
+
     /// Output in JSON format
     #[arg(long)]
     pub json: bool,
 }
 
+/// Where `--snapshot` saves uncommitted changes
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SnapshotMode {
+    /// Stash them with `git stash push --include-untracked`
+    Stash,
+    /// Write a patch to .git/phantom/trash/
+    Patch,
+    /// Discard them
+    None,
+}
+
+impl From<SnapshotMode> for DeleteSnapshot {
+    fn from(mode: SnapshotMode) -> Self {
+        match mode {
+            SnapshotMode::Stash => Self::Stash,
+            SnapshotMode::Patch => Self::Patch,
+            SnapshotMode::None => Self::None,
+        }
+    }
+}
+
 /// Result of delete command for JSON output
 #[derive(Serialize, Deserialize)]
 pub struct DeleteResult {
     pub success: bool,
     pub name: String,
     pub message: String,
+    /// Whether the worktree was deleted or only its stale registration pruned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<DeleteOutcome>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Milliseconds per timed section, with --timings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings_ms: Option<BTreeMap<String, u64>>,
+}
+
+/// Result of `delete --prune` for JSON output
+#[derive(Serialize, Deserialize)]
+pub struct PruneResult {
+    pub success: bool,
+    pub into: String,
+    pub dry_run: bool,
+    pub removed: Vec<BulkDeleteEntry>,
+    pub kept: Vec<BulkDeleteEntry>,
+}
+
+/// Result of `delete --stdin-names` for JSON output
+#[derive(Serialize, Deserialize)]
+pub struct BulkDeleteResult {
+    pub success: bool,
+    pub removed: Vec<BulkDeleteEntry>,
+    pub failed: Vec<BulkDeleteEntry>,
+}
+
+/// A worktree considered by `delete --prune` or `delete --stdin-names`
+#[derive(Serialize, Deserialize)]
+pub struct BulkDeleteEntry {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Why the worktree was kept or not deleted; absent for removed worktrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
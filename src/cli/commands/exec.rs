@@ -1,18 +1,77 @@
 use clap::Args;
+use std::path::PathBuf;
 
 #[derive(Args, Debug)]
 pub struct ExecArgs {
-    /// Name of the worktree (can be omitted with --fzf)
+    /// Name of the worktree (can be omitted with --fzf or --last)
     pub name: Option<String>,
 
     /// Command and arguments to execute
-    #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+    ///
+    /// Arguments run as a program and its argv. A single argument containing
+    /// spaces or shell syntax (pipes, globs, `&&`, ...) runs through your shell
+    /// instead, as with --shell; pass --argv to prevent that.
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     pub command: Vec<String>,
 
+    /// Run the command through your shell (`$SHELL -c`), joining the arguments as given
+    #[arg(long)]
+    pub shell: bool,
+
+    /// Run the arguments as a program and its argv, never through a shell
+    #[arg(long, conflicts_with = "shell")]
+    pub argv: bool,
+
     /// Select worktree interactively with fzf
     #[arg(long)]
     pub fzf: bool,
 
+    /// Use the worktree last used by exec, shell, create or attach
+    #[arg(long, conflicts_with = "fzf")]
+    pub last: bool,
+
+    /// Run even if a rebase, merge, cherry-pick or bisect is in progress
+    #[arg(long)]
+    pub even_if_busy: bool,
+
+    /// Set an environment variable for the command, on top of the phantom ones (repeatable)
+    #[arg(long = "env", value_name = "KEY=VALUE")]
+    pub env: Vec<String>,
+
+    /// Directory inside the worktree to start the command in, relative to its top
+    #[arg(long, value_name = "PATH", conflicts_with = "workspace")]
+    pub cwd: Option<PathBuf>,
+
+    /// Run in every repository listed in phantom-workspace.toml, one at a time
+    #[arg(long, conflicts_with_all = &["fzf", "last", "tmux", "tmux_vertical", "tmux_v", "tmux_horizontal", "tmux_h", "kitty", "kitty_vertical", "kitty_v", "kitty_horizontal", "kitty_h"])]
+    pub workspace: bool,
+
+    /// With --tmux or --kitty, wait for the command to finish and exit with its status
+    #[arg(long)]
+    pub wait: bool,
+
+    /// Capture the command's output and print it with each line prefixed with the worktree name
+    #[arg(long, conflicts_with_all = &["wait", "workspace", "tmux", "tmux_vertical", "tmux_v", "tmux_horizontal", "tmux_h", "kitty", "kitty_vertical", "kitty_v", "kitty_horizontal", "kitty_h"])]
+    pub capture: bool,
+
+    /// With --capture, print the result as JSON with the command's output embedded
+    #[arg(long, requires = "capture")]
+    pub json: bool,
+
+    /// With --capture, print each line as it is written instead of after the command finishes
+    ///
+    /// This is the default when stdout is a terminal.
+    #[arg(long, requires = "capture", conflicts_with = "json")]
+    pub stream: bool,
+
+    /// With --capture, print the output only after the command finishes
+    #[arg(long, requires = "capture", conflicts_with = "stream")]
+    pub no_stream: bool,
+
+    /// Skip checking that the command exists before running it
+    #[arg(long)]
+    pub no_verify: bool,
+
     /// Execute in a new tmux window
     #[arg(short = 't', long)]
     pub tmux: bool,
@@ -33,6 +92,14 @@ pub struct ExecArgs {
     #[arg(long = "tmux-h", conflicts_with_all = &["tmux", "tmux_vertical", "tmux_v", "tmux_horizontal"])]
     pub tmux_h: bool,
 
+    /// Size of the tmux pane as a percentage of the current one (1-99)
+    #[arg(long = "tmux-size", value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(1..=99), conflicts_with = "tmux")]
+    pub tmux_size: Option<u8>,
+
+    /// tmux window or pane to split, or to open the new window after
+    #[arg(long = "tmux-target", value_name = "TARGET")]
+    pub tmux_target: Option<String>,
+
     /// Execute in a new kitty tab
     #[arg(short = 'k', long, conflicts_with_all = &["tmux", "tmux_vertical", "tmux_v", "tmux_horizontal", "tmux_h"])]
     pub kitty: bool,
@@ -52,4 +119,12 @@ pub struct ExecArgs {
     /// Execute in a horizontal kitty split (shorthand)
     #[arg(long = "kitty-h", conflicts_with_all = &["tmux", "tmux_vertical", "tmux_v", "tmux_horizontal", "tmux_h", "kitty", "kitty_vertical", "kitty_v", "kitty_horizontal"])]
     pub kitty_h: bool,
+
+    /// Keep focus on the current kitty window instead of switching to the new one
+    #[arg(long)]
+    pub no_focus: bool,
+
+    /// Run in the current terminal even when a default multiplexer is configured
+    #[arg(long, conflicts_with_all = &["tmux", "tmux_vertical", "tmux_v", "tmux_horizontal", "tmux_h", "kitty", "kitty_vertical", "kitty_v", "kitty_horizontal", "kitty_h"])]
+    pub no_multiplexer: bool,
 }
@@ -0,0 +1,12 @@
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct GcArgs {
+    /// Remove what was found instead of only listing it
+    #[arg(long)]
+    pub apply: bool,
+
+    /// Output in JSON format
+    #[arg(long)]
+    pub json: bool,
+}
@@ -0,0 +1,24 @@
+use clap::{Args, ValueEnum};
+
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    /// Format of the configuration file to write
+    #[arg(long, value_enum, default_value = "toml")]
+    pub format: InitFormat,
+
+    /// Overwrite an existing configuration file
+    #[arg(long)]
+    pub force: bool,
+
+    /// Ask which files to copy, what to run after create and which multiplexer to use
+    #[arg(short, long)]
+    pub interactive: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InitFormat {
+    /// phantom.config.toml
+    Toml,
+    /// phantom.config.json
+    Json,
+}
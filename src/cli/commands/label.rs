@@ -0,0 +1,24 @@
+use clap::{Args, Subcommand};
+
+#[derive(Args, Debug)]
+pub struct LabelArgs {
+    /// Name of the worktree
+    pub name: String,
+
+    #[command(subcommand)]
+    pub action: LabelAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LabelAction {
+    /// Add a label to the worktree
+    Add {
+        /// Lowercase letters, digits and dashes, at most 32 characters
+        label: String,
+    },
+    /// Remove a label from the worktree
+    Remove {
+        /// Label to remove
+        label: String,
+    },
+}
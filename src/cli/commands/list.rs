@@ -10,6 +10,38 @@ pub struct ListArgs {
     #[arg(long)]
     pub names: bool,
 
+    /// Also list the main worktree and worktrees created outside phantom, with their origin
+    #[arg(long, conflicts_with_all = &["fzf", "workspace"])]
+    pub all: bool,
+
+    /// Show stacked worktrees as a tree of parents and children
+    #[arg(long, conflicts_with_all = &["fzf", "names", "json"])]
+    pub stacks: bool,
+
+    /// List worktrees of every repository listed in phantom-workspace.toml
+    #[arg(long, conflicts_with_all = &["fzf", "stacks"])]
+    pub workspace: bool,
+
+    /// Only list worktrees with this label; repeat to require several
+    #[arg(long = "label", value_name = "LABEL", conflicts_with_all = &["fzf", "workspace"])]
+    pub labels: Vec<String>,
+
+    /// Match worktrees with any of the --label values instead of all of them
+    #[arg(long, requires = "labels")]
+    pub label_any: bool,
+
+    /// Skip the per-worktree git status check; dirty worktrees are not marked
+    #[arg(long, conflicts_with = "fzf")]
+    pub no_status: bool,
+
+    /// Ask the remote which branches still exist and mark worktrees whose upstream was deleted
+    #[arg(long, conflicts_with = "fzf")]
+    pub check_remote: bool,
+
+    /// Show how much disk space each worktree takes (sizes are cached between runs)
+    #[arg(long, conflicts_with_all = &["fzf", "names", "stacks", "workspace"])]
+    pub du: bool,
+
     /// Output in JSON format
     #[arg(long)]
     pub json: bool,
@@ -0,0 +1,17 @@
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct LockArgs {
+    /// Name of the worktree to lock
+    pub name: String,
+
+    /// Why the worktree is locked, shown by list and delete
+    #[arg(long)]
+    pub reason: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct UnlockArgs {
+    /// Name of the worktree to unlock
+    pub name: String,
+}
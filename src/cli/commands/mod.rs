@@ -1,9 +1,19 @@
+pub mod adopt;
 pub mod attach;
 pub mod completion;
+pub mod config;
 pub mod create;
 pub mod delete;
 pub mod exec;
+pub mod gc;
+pub mod init;
+pub mod label;
 pub mod list;
+pub mod lock;
+pub mod preview;
 pub mod shell;
+pub mod shellenv;
+pub mod show;
+pub mod status;
 pub mod version;
 pub mod where_cmd;
@@ -0,0 +1,7 @@
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct PreviewArgs {
+    /// Name of the worktree
+    pub name: String,
+}
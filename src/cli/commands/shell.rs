@@ -1,18 +1,35 @@
 use clap::Args;
+use std::path::PathBuf;
 
 #[derive(Args, Debug)]
 pub struct ShellArgs {
-    /// Name of the worktree (optional if using --fzf)
+    /// Name of the worktree (optional if using --fzf or --last)
     pub name: Option<String>,
 
     /// Select worktree interactively with fzf
     #[arg(long)]
     pub fzf: bool,
 
+    /// Use the worktree last used by exec, shell, create or attach
+    #[arg(long, conflicts_with_all = &["name", "fzf"])]
+    pub last: bool,
+
+    /// Set an environment variable for the shell, on top of the phantom ones (repeatable)
+    #[arg(long = "env", value_name = "KEY=VALUE")]
+    pub env: Vec<String>,
+
+    /// Directory inside the worktree to start the shell in, relative to its top
+    #[arg(long, value_name = "PATH")]
+    pub cwd: Option<PathBuf>,
+
     /// Open in a new tmux window
     #[arg(short = 't', long)]
     pub tmux: bool,
 
+    /// Switch to an existing tmux window for the worktree instead of opening another
+    #[arg(long, requires = "tmux")]
+    pub reuse: bool,
+
     /// Open in a vertical tmux pane
     #[arg(long = "tmux-vertical", conflicts_with = "tmux")]
     pub tmux_vertical: bool,
@@ -29,6 +46,14 @@ pub struct ShellArgs {
     #[arg(long = "tmux-h", conflicts_with_all = &["tmux", "tmux_vertical", "tmux_v", "tmux_horizontal"])]
     pub tmux_h: bool,
 
+    /// Size of the tmux pane as a percentage of the current one (1-99)
+    #[arg(long = "tmux-size", value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(1..=99), conflicts_with = "tmux")]
+    pub tmux_size: Option<u8>,
+
+    /// tmux window or pane to split, or to open the new window after
+    #[arg(long = "tmux-target", value_name = "TARGET")]
+    pub tmux_target: Option<String>,
+
     /// Open in a new kitty tab
     #[arg(short = 'k', long, conflicts_with_all = &["tmux", "tmux_vertical", "tmux_v", "tmux_horizontal", "tmux_h"])]
     pub kitty: bool,
@@ -48,4 +73,12 @@ pub struct ShellArgs {
     /// Open in a horizontal kitty split (shorthand)
     #[arg(long = "kitty-h", conflicts_with_all = &["tmux", "tmux_vertical", "tmux_v", "tmux_horizontal", "tmux_h", "kitty", "kitty_vertical", "kitty_v", "kitty_horizontal"])]
     pub kitty_h: bool,
+
+    /// Keep focus on the current kitty window instead of switching to the new one
+    #[arg(long)]
+    pub no_focus: bool,
+
+    /// Run in the current terminal even when a default multiplexer is configured
+    #[arg(long, conflicts_with_all = &["tmux", "tmux_vertical", "tmux_v", "tmux_horizontal", "tmux_h", "kitty", "kitty_vertical", "kitty_v", "kitty_horizontal", "kitty_h"])]
+    pub no_multiplexer: bool,
 }
@@ -0,0 +1,8 @@
+use crate::cli::commands::completion::Shell;
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct ShellenvArgs {
+    /// Shell to print the function for
+    pub shell: Shell,
+}
@@ -0,0 +1,38 @@
+use crate::git::parse::FileChange;
+use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+#[derive(Args, Debug)]
+pub struct ShowArgs {
+    /// Name of the worktree
+    pub name: String,
+
+    /// List files changed relative to the base branch plus uncommitted changes
+    #[arg(long)]
+    pub files_changed: bool,
+
+    /// Output in JSON format
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Result of the show command for JSON output
+#[derive(Serialize)]
+pub struct ShowResult {
+    pub name: String,
+    pub path: String,
+    pub branch: Option<String>,
+    pub base: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub labels: BTreeSet<String>,
+    /// Changes committed since `base`; absent when the base could not be resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committed: Option<Vec<FileChange>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uncommitted: Option<Vec<FileChange>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
@@ -0,0 +1,38 @@
+use clap::Args;
+use serde::Serialize;
+
+#[derive(Args, Debug)]
+pub struct StatusArgs {
+    /// Name of the worktree
+    pub name: Option<String>,
+
+    /// Select worktree interactively with fzf
+    #[arg(long)]
+    pub fzf: bool,
+
+    /// Summarize the worktree containing the current directory
+    #[arg(long)]
+    pub current: bool,
+
+    /// Output in JSON format
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Result of the status command for JSON output
+#[derive(Serialize)]
+pub struct StatusResult {
+    pub name: String,
+    pub path: String,
+    pub branch: Option<String>,
+    /// HEAD commit; absent on an unborn branch
+    pub commit: Option<String>,
+    pub subject: Option<String>,
+    pub clean: bool,
+    pub modified: usize,
+    pub unmerged: usize,
+    pub untracked: usize,
+    pub upstream: Option<String>,
+    pub ahead: Option<u32>,
+    pub behind: Option<u32>,
+}
@@ -3,13 +3,18 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Args, Debug)]
 pub struct WhereArgs {
-    /// Name of the worktree
-    pub name: Option<String>,
+    /// Names of the worktrees; several names print one path per line
+    #[arg(value_name = "NAME")]
+    pub names: Vec<String>,
 
     /// Select worktree interactively with fzf
     #[arg(long)]
     pub fzf: bool,
 
+    /// Print the path relative to the current directory
+    #[arg(long)]
+    pub relative: bool,
+
     /// Output in JSON format
     #[arg(long)]
     pub json: bool,
@@ -24,3 +29,11 @@ pub struct WhereResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 }
+
+/// One resolved name when `where` is given several names
+#[derive(Serialize, Deserialize)]
+pub struct WhereEntry {
+    pub name: String,
+    pub path: Option<String>,
+    pub exists: bool,
+}
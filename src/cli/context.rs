@@ -1,6 +1,18 @@
+use crate::cli::output::output;
+use crate::config::loader::LoadedConfig;
+use crate::config::provider::ConfigProvider;
+use crate::config::types::PhantomConfig;
 use crate::core::command_executor::CommandExecutor;
+use crate::core::environment::{Environment, RealEnvironment};
 use crate::core::exit_handler::ExitHandler;
 use crate::core::filesystem::FileSystem;
+use crate::git::git_executor_adapter::{GitSettings, GitSettingsExecutor};
+use crate::git::libs::config_probe::{probe_git_config, GitConfigProbe};
+use crate::git::libs::get_git_root::get_git_root_from;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::debug;
 
 /// Context for CLI handlers with zero-cost abstractions
 ///
@@ -19,6 +31,18 @@ where
     pub filesystem: F,
     /// Exit handler for process termination
     pub exit_handler: H,
+    /// Environment variables, for checks such as whether phantom runs inside tmux
+    pub environment: Arc<dyn Environment>,
+    /// Worktree-related git config, probed at most once per invocation
+    git_config: Arc<OnceCell<GitConfigProbe>>,
+    /// Repository configuration, loaded when a handler first asks for it
+    config: ConfigProvider,
+    /// Warn about deprecated and unknown keys when the configuration is loaded
+    config_notes: bool,
+    /// Repository given with `--repo`, looked up instead of the current directory
+    repository: Option<PathBuf>,
+    /// Printed by [`HandlerContext::exit`] before the process ends (`--timings`)
+    exit_report: Option<Arc<dyn Fn() + Send + Sync>>,
 }
 
 impl<E, F, H> HandlerContext<E, F, H>
@@ -29,7 +53,135 @@ where
 {
     /// Create a new handler context with the given executor, filesystem, and exit handler
     pub fn new(executor: E, filesystem: F, exit_handler: H) -> Self {
-        Self { executor, filesystem, exit_handler }
+        Self {
+            executor,
+            filesystem,
+            exit_handler,
+            environment: Arc::new(RealEnvironment::new()),
+            git_config: Arc::default(),
+            config: ConfigProvider::default(),
+            config_notes: true,
+            repository: None,
+            exit_report: None,
+        }
+    }
+
+    /// Look the repository up from `repository` instead of the current directory (`--repo`)
+    pub fn with_repository(mut self, repository: PathBuf) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
+    /// The directory given with `--repo`, if any
+    pub fn repository(&self) -> Option<&Path> {
+        self.repository.as_deref()
+    }
+
+    /// Run `report` when a handler ends the process through [`HandlerContext::exit`]
+    pub fn with_exit_report(mut self, report: impl Fn() + Send + Sync + 'static) -> Self {
+        self.exit_report = Some(Arc::new(report));
+        self
+    }
+
+    /// Exit with `code`, printing the exit report first as `main` never gets to
+    pub fn exit(&self, code: i32) -> ! {
+        if let Some(report) = &self.exit_report {
+            report();
+        }
+        self.exit_handler.exit(code)
+    }
+
+    /// Use `environment` instead of the process environment
+    ///
+    /// `${VAR}` references in the configuration are expanded from it too.
+    pub fn with_environment(mut self, environment: impl Environment + 'static) -> Self {
+        self.environment = Arc::new(environment);
+        self.config =
+            ConfigProvider::new(self.environment.clone()).with_strict(self.config.is_strict());
+        self
+    }
+
+    /// Fail on deprecated and unknown configuration keys (`--strict-config`)
+    pub fn with_strict_config(mut self, strict: bool) -> Self {
+        self.config = self.config.with_strict(strict);
+        self
+    }
+
+    /// Run git commands with the repository's `settings`, such as `gitTimeoutSecs`
+    pub fn with_git_settings(
+        self,
+        settings: GitSettings,
+    ) -> HandlerContext<GitSettingsExecutor<E>, F, H> {
+        HandlerContext {
+            executor: GitSettingsExecutor::new(self.executor, settings),
+            filesystem: self.filesystem,
+            exit_handler: self.exit_handler,
+            environment: self.environment,
+            git_config: self.git_config,
+            config: self.config,
+            config_notes: self.config_notes,
+            repository: self.repository,
+            exit_report: self.exit_report,
+        }
+    }
+
+    /// Leave deprecated and unknown keys to the handler, as `config validate` lists them itself
+    pub fn without_config_notes(mut self) -> Self {
+        self.config_notes = false;
+        self
+    }
+
+    /// Merged repository configuration of `git_root`, read on first use
+    ///
+    /// Deprecated and unknown keys are warned about once, whichever command loads it.
+    pub async fn config(&self, git_root: &Path) -> crate::Result<Option<&LoadedConfig>> {
+        let config_notes = self.config_notes;
+        self.config
+            .get_with(git_root, |loaded| {
+                if !config_notes {
+                    return;
+                }
+                for deprecation in &loaded.deprecations {
+                    output().warn(&deprecation.to_string());
+                }
+                for unknown in &loaded.unknown_keys {
+                    output().warn(&unknown.to_string());
+                }
+            })
+            .await
+    }
+
+    /// Settings of `git_root`: the defaults when there is no configuration or it fails to load
+    pub async fn config_or_default(&self, git_root: &Path) -> PhantomConfig {
+        match self.config(git_root).await {
+            Ok(loaded) => loaded.map(|loaded| loaded.config.clone()).unwrap_or_default(),
+            Err(e) => {
+                debug!("Config failed to load, using the defaults: {e}");
+                PhantomConfig::default()
+            }
+        }
+    }
+
+    /// Whether any handler has asked for the repository configuration yet
+    pub fn config_loaded(&self) -> bool {
+        self.config.is_loaded()
+    }
+}
+
+impl<E, F, H> HandlerContext<E, F, H>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem,
+    H: ExitHandler,
+{
+    /// Root of the main worktree of the repository phantom works on
+    pub async fn git_root(&self) -> crate::Result<PathBuf> {
+        get_git_root_from(self.executor.clone(), self.repository()).await
+    }
+
+    /// Worktree-related git config of the repository at `git_root`, read on first use
+    pub async fn git_config(&self, git_root: &Path) -> GitConfigProbe {
+        *self.git_config.get_or_init(|| probe_git_config(self.executor.clone(), git_root)).await
     }
 }
 
@@ -79,6 +231,54 @@ mod tests {
         let _ = &context.exit_handler;
     }
 
+    #[tokio::test]
+    async fn test_git_config_is_probed_once() {
+        let mut executor = MockCommandExecutor::new();
+        for key in crate::git::libs::config_probe::PROBED_KEYS {
+            executor
+                .expect_command("git")
+                .with_args(&["config", "--get-all", key])
+                .returns_output("true\n", "", 0);
+        }
+        let context = HandlerContext::new(executor, MockFileSystem::new(), MockExitHandler::new());
+
+        let probe = context.git_config(Path::new("/repo")).await;
+        assert!(probe.bare && probe.worktree_config && probe.guess_remote);
+        assert_eq!(context.clone().git_config(Path::new("/repo")).await, probe);
+        assert_eq!(context.executor.calls().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_git_root_uses_repository() {
+        let mut executor = MockCommandExecutor::new();
+        executor
+            .expect_command("git")
+            .with_args(&["rev-parse", "--git-common-dir"])
+            .in_dir("/elsewhere/repo")
+            .returns_output("/elsewhere/repo/.git", "", 0);
+        let context = HandlerContext::new(executor, MockFileSystem::new(), MockExitHandler::new())
+            .with_repository(PathBuf::from("/elsewhere/repo"));
+
+        assert_eq!(context.git_root().await.unwrap(), PathBuf::from("/elsewhere/repo"));
+        context.executor.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_strict_config_rejects_unknown_keys() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("phantom.config.toml"), "colour = true\n").unwrap();
+        let context = HandlerContext::new(
+            MockCommandExecutor::new(),
+            MockFileSystem::new(),
+            MockExitHandler::new(),
+        )
+        .with_strict_config(true)
+        .with_environment(crate::core::environment::MockEnvironment::new());
+
+        let error = context.config(dir.path()).await.unwrap_err().to_string();
+        assert!(error.contains("unknown keys are not allowed in strict mode"), "{error}");
+    }
+
     #[test]
     fn test_production_context_default() {
         let context = ProductionContext::default();
@@ -88,6 +288,24 @@ mod tests {
         let _ = &context.exit_handler;
     }
 
+    #[test]
+    fn test_exit_prints_the_exit_report_first() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let reported = Arc::new(AtomicBool::new(false));
+        let flag = reported.clone();
+        let exit_handler = MockExitHandler::new();
+        let context =
+            HandlerContext::new(MockCommandExecutor::new(), MockFileSystem::new(), exit_handler)
+                .with_exit_report(move || flag.store(true, Ordering::SeqCst));
+
+        let exited = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| context.exit(3)));
+
+        assert!(exited.is_err());
+        assert!(reported.load(Ordering::SeqCst));
+        assert!(context.exit_handler.was_exit_called(3));
+    }
+
     #[test]
     fn test_test_context() {
         let context: TestContext = HandlerContext::new(
@@ -0,0 +1,171 @@
+use crate::cli::output::output;
+use crate::core::command_executor::{CommandConfig, CommandExecutor};
+use crate::core::executors::DryRunExecutor;
+use crate::core::filesystem::FileSystem;
+use crate::worktree::file_copier::plan_configured_copies;
+use crate::worktree::hooks::Hook;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// A command that would run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+}
+
+impl PlannedCommand {
+    fn from_config(config: CommandConfig) -> Self {
+        Self {
+            program: config.program,
+            args: config.args.into_vec(),
+            cwd: config.cwd.map(|cwd| cwd.to_string_lossy().to_string()),
+        }
+    }
+
+    fn line(&self) -> String {
+        let line = std::iter::once(&self.program).chain(&self.args).cloned().collect::<Vec<_>>();
+        match &self.cwd {
+            Some(cwd) => format!("{}  (in {cwd})", line.join(" ")),
+            None => line.join(" "),
+        }
+    }
+}
+
+/// A file that would be copied into the new worktree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedCopy {
+    pub from: String,
+    pub to: String,
+}
+
+/// What create or attach would do for one worktree, gathered by --dry-run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunPlan {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub path: String,
+    pub commands: Vec<PlannedCommand>,
+    pub copies: Vec<PlannedCopy>,
+    pub hooks: Vec<PlannedCommand>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DryRunOutput<'a> {
+    dry_run: bool,
+    worktrees: &'a [DryRunPlan],
+}
+
+impl DryRunPlan {
+    /// A plan for the worktree `name` at `path`, with the commands `executor` recorded
+    pub fn new<E>(
+        name: &str,
+        branch: Option<&str>,
+        path: &Path,
+        executor: &DryRunExecutor<E>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            branch: branch.map(String::from),
+            path: path.to_string_lossy().to_string(),
+            commands: executor.recorded().into_iter().map(PlannedCommand::from_config).collect(),
+            copies: Vec::new(),
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Add the copies of `files` from `source_dir` into the worktree
+    pub async fn add_copies<E>(
+        &mut self,
+        executor: E,
+        files: Option<&[String]>,
+        source_dir: &Path,
+    ) -> Result<()>
+    where
+        E: CommandExecutor + Clone + 'static,
+    {
+        let copies = plan_configured_copies(executor, files, source_dir, Path::new(&self.path))
+            .await
+            .with_context(|| "Failed to resolve the files to copy")?;
+        self.copies.extend(copies.into_iter().map(|(from, to)| PlannedCopy {
+            from: from.to_string_lossy().to_string(),
+            to: to.to_string_lossy().to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Add `hook` if the repository has an executable one
+    pub async fn add_hook(&mut self, filesystem: &dyn FileSystem, git_root: &Path, hook: Hook) {
+        let path = hook.path(git_root);
+        if filesystem.is_executable(&path).await.unwrap_or(false) {
+            self.hooks.push(PlannedCommand {
+                program: path.to_string_lossy().to_string(),
+                args: vec![self.name.clone(), self.path.clone()],
+                cwd: Some(self.path.clone()),
+            });
+        }
+    }
+}
+
+/// Print the plans of an `action` such as "create", as one JSON document with `json`
+pub fn print_plans(plans: &[DryRunPlan], action: &str, json: bool) -> Result<()> {
+    if json {
+        let document = DryRunOutput { dry_run: true, worktrees: plans };
+        output().log(
+            &serde_json::to_string_pretty(&document)
+                .with_context(|| "Failed to serialize JSON output")?,
+        );
+        return Ok(());
+    }
+
+    for plan in plans {
+        output().log(&format!("Would {action} worktree '{}' at {}", plan.name, plan.path));
+        for command in &plan.commands {
+            output().log(&format!("  run   {}", command.line()));
+        }
+        for copy in &plan.copies {
+            output().log(&format!("  copy  {} -> {}", copy.from, copy.to));
+        }
+        for hook in &plan.hooks {
+            output().log(&format!("  hook  {}", hook.line()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::command_executor::CommandExecutor;
+    use crate::core::executors::MockCommandExecutor;
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    async fn test_dry_run_plan_serializes_recorded_commands() {
+        let executor = DryRunExecutor::new(MockCommandExecutor::new());
+        executor
+            .execute(
+                CommandConfig::new("git")
+                    .with_args(vec!["worktree".into(), "add".into(), "/repo/wt".into()])
+                    .with_cwd(PathBuf::from("/repo")),
+            )
+            .await
+            .unwrap();
+
+        let plan = DryRunPlan::new("wt", Some("wt"), Path::new("/repo/wt"), &executor);
+        assert_eq!(plan.commands[0].line(), "git worktree add /repo/wt  (in /repo)");
+
+        let json =
+            serde_json::to_value(DryRunOutput { dry_run: true, worktrees: &[plan] }).unwrap();
+        assert_eq!(json["dryRun"], true);
+        assert_eq!(json["worktrees"][0]["commands"][0]["args"][1], "add");
+        assert_eq!(json["worktrees"][0]["copies"], serde_json::json!([]));
+    }
+}
@@ -13,8 +13,28 @@ impl ExitCode {
     pub const BRANCH_NOT_FOUND: i32 = 6;
     pub const CONFIG_ERROR: i32 = 7;
     pub const EXEC_ERROR: i32 = 8;
+    pub const BRANCH_EXISTS: i32 = 9;
+    pub const UNCOMMITTED_CHANGES: i32 = 10;
+    pub const GIT_NOT_FOUND: i32 = 11;
+    pub const WORKTREE_LOCKED: i32 = 12;
 }
 
+/// Exit code reference shown in `phantom --help`
+pub const EXIT_CODES_HELP: &str = "Exit codes:
+  0   Success
+  1   General error
+  2   Invalid arguments or worktree name
+  3   Not in a git repository
+  4   Worktree already exists
+  5   Worktree not found
+  6   Branch not found
+  7   Configuration error
+  8   Command execution failed
+  9   Branch already exists
+  10  Worktree has uncommitted changes
+  11  git is not installed
+  12  Worktree is locked";
+
 /// Exit with an error message and code
 pub fn exit_with_error(message: &str, code: i32) -> ! {
     eprintln!("Error: {message}");
@@ -33,19 +53,56 @@ pub fn error_to_exit_code(error: &crate::PhantomError) -> i32 {
     match error {
         PhantomError::NotInGitRepository => ExitCode::NOT_IN_GIT_REPO,
         PhantomError::WorktreeExists { .. } => ExitCode::WORKTREE_EXISTS,
+        PhantomError::WorktreeNameCollision { .. } => ExitCode::WORKTREE_EXISTS,
         PhantomError::WorktreeNotFound { .. } => ExitCode::WORKTREE_NOT_FOUND,
         PhantomError::BranchNotFound { .. } => ExitCode::BRANCH_NOT_FOUND,
+        PhantomError::BranchNotFoundAnywhere { .. } => ExitCode::BRANCH_NOT_FOUND,
+        PhantomError::BranchExists { .. } => ExitCode::BRANCH_EXISTS,
+        PhantomError::WorktreeHasUncommittedChanges { .. } => ExitCode::UNCOMMITTED_CHANGES,
+        PhantomError::GitNotInstalled => ExitCode::GIT_NOT_FOUND,
+        PhantomError::WorktreeLocked { .. } => ExitCode::WORKTREE_LOCKED,
         PhantomError::ConfigNotFound { .. } => ExitCode::CONFIG_ERROR,
         PhantomError::ConfigInvalid { .. } => ExitCode::CONFIG_ERROR,
-        PhantomError::CommandNotFound { .. } => ExitCode::EXEC_ERROR,
+        PhantomError::CommandNotFound { .. } | PhantomError::ShellNotFound { .. } => {
+            ExitCode::EXEC_ERROR
+        }
         PhantomError::ProcessFailed { .. } => ExitCode::EXEC_ERROR,
         PhantomError::ProcessExecutionError { .. } => ExitCode::EXEC_ERROR,
+        PhantomError::CommandTimeout { .. } => ExitCode::EXEC_ERROR,
         PhantomError::ValidationFailed { .. } => ExitCode::VALIDATION_ERROR,
         PhantomError::InvalidWorktreeName { .. } => ExitCode::VALIDATION_ERROR,
+        PhantomError::NoCommits => ExitCode::VALIDATION_ERROR,
+        PhantomError::CreateRolledBack { error, .. } => error_to_exit_code(error),
         _ => ExitCode::GENERAL_ERROR,
     }
 }
 
+/// Render an error and its causes, with git's own message on a line of its own
+pub fn format_error(error: &anyhow::Error) -> String {
+    let mut message =
+        if error.chain().count() > 1 { format!("{error:#}") } else { error.to_string() };
+
+    let stderr = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<crate::PhantomError>().and_then(git_stderr));
+    if let Some(stderr) = stderr.filter(|stderr| !message.contains(*stderr)) {
+        message.push('\n');
+        message.push_str(stderr);
+    }
+    message
+}
+
+/// The stderr of the git command behind `error`, if it printed any
+fn git_stderr(error: &crate::PhantomError) -> Option<&str> {
+    use crate::PhantomError;
+
+    match error {
+        PhantomError::Git { stderr, .. } if !stderr.is_empty() => Some(stderr),
+        PhantomError::CreateRolledBack { error, .. } => git_stderr(error),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,6 +119,10 @@ mod tests {
         assert_eq!(ExitCode::BRANCH_NOT_FOUND, 6);
         assert_eq!(ExitCode::CONFIG_ERROR, 7);
         assert_eq!(ExitCode::EXEC_ERROR, 8);
+        assert_eq!(ExitCode::BRANCH_EXISTS, 9);
+        assert_eq!(ExitCode::UNCOMMITTED_CHANGES, 10);
+        assert_eq!(ExitCode::GIT_NOT_FOUND, 11);
+        assert_eq!(ExitCode::WORKTREE_LOCKED, 12);
     }
 
     #[test]
@@ -76,6 +137,14 @@ mod tests {
             ExitCode::WORKTREE_EXISTS
         );
 
+        assert_eq!(
+            error_to_exit_code(&PhantomError::WorktreeNameCollision {
+                name: "test".to_string(),
+                existing: "Test".to_string(),
+            }),
+            ExitCode::WORKTREE_EXISTS
+        );
+
         assert_eq!(
             error_to_exit_code(&PhantomError::WorktreeNotFound { name: "test".to_string() }),
             ExitCode::WORKTREE_NOT_FOUND
@@ -105,10 +174,90 @@ mod tests {
             ExitCode::VALIDATION_ERROR
         );
 
+        assert_eq!(error_to_exit_code(&PhantomError::NoCommits), ExitCode::VALIDATION_ERROR);
+
+        assert_eq!(
+            error_to_exit_code(&PhantomError::BranchExists { branch: "test".to_string() }),
+            ExitCode::BRANCH_EXISTS
+        );
+
+        assert_eq!(
+            error_to_exit_code(&PhantomError::CreateRolledBack {
+                name: "test".to_string(),
+                error: Box::new(PhantomError::BranchExists { branch: "test".to_string() }),
+                unreverted: Vec::new(),
+            }),
+            ExitCode::BRANCH_EXISTS
+        );
+
+        assert_eq!(
+            error_to_exit_code(&PhantomError::WorktreeHasUncommittedChanges {
+                name: "test".to_string()
+            }),
+            ExitCode::UNCOMMITTED_CHANGES
+        );
+
+        assert_eq!(error_to_exit_code(&PhantomError::GitNotInstalled), ExitCode::GIT_NOT_FOUND);
+
+        assert_eq!(
+            error_to_exit_code(&PhantomError::WorktreeLocked {
+                name: "test".to_string(),
+                reason: None
+            }),
+            ExitCode::WORKTREE_LOCKED
+        );
+
         // Test general error fallback
         assert_eq!(
             error_to_exit_code(&PhantomError::Io(std::io::Error::other("test"))),
             ExitCode::GENERAL_ERROR
         );
     }
+
+    fn git_error(stderr: &str) -> PhantomError {
+        PhantomError::Git {
+            command: "git".to_string(),
+            args: vec!["worktree".to_string(), "add".to_string(), "/wt".to_string()],
+            exit_code: 128,
+            stderr: stderr.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_format_error_puts_git_stderr_on_its_own_line() {
+        let error =
+            anyhow::Error::new(git_error("fatal: 'main' is already checked out at '/repo'"))
+                .context("Failed to create worktree 'feature'");
+
+        assert_eq!(
+            format_error(&error),
+            "Failed to create worktree 'feature': Git command 'git worktree add /wt' failed with exit code 128\n\
+             fatal: 'main' is already checked out at '/repo'"
+        );
+    }
+
+    #[test]
+    fn test_format_error_git_stderr_inside_rollback() {
+        let error = anyhow::Error::new(PhantomError::CreateRolledBack {
+            name: "feature".to_string(),
+            error: Box::new(git_error("fatal: unable to checkout working tree")),
+            unreverted: vec![],
+        });
+
+        assert!(format_error(&error).ends_with("\nfatal: unable to checkout working tree"));
+    }
+
+    #[test]
+    fn test_format_error_without_git_stderr() {
+        let error = anyhow::Error::new(git_error("")).context("Failed to list worktrees");
+        assert_eq!(
+            format_error(&error),
+            "Failed to list worktrees: Git command 'git worktree add /wt' failed with exit code 128"
+        );
+
+        // Not repeated when a context message already quotes it
+        let error = anyhow::Error::new(git_error("fatal: bad object"))
+            .context("Failed to stack on 'base': fatal: bad object");
+        assert!(!format_error(&error).contains('\n'));
+    }
 }
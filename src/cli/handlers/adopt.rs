@@ -0,0 +1,77 @@
+use crate::cli::commands::adopt::AdoptArgs;
+use crate::cli::context::HandlerContext;
+use crate::cli::output::output;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use crate::worktree::adopt::{adopt_worktree, AdoptWorktreeOptions};
+use anyhow::{Context, Result};
+
+/// Handle the adopt command
+pub async fn handle<E, F, H>(args: AdoptArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let options = AdoptWorktreeOptions { name: args.name, in_place: args.in_place };
+    let adopted = adopt_worktree(
+        context.executor.clone(),
+        &context.filesystem,
+        &git_root,
+        &args.path,
+        options,
+    )
+    .await
+    .with_context(|| format!("Failed to adopt {}", args.path.display()))?;
+
+    if args.json {
+        output().log(
+            &serde_json::to_string_pretty(&adopted)
+                .with_context(|| "Failed to serialize JSON output")?,
+        );
+    } else if let Some(from) = &adopted.moved_from {
+        output().success(&format!(
+            "Adopted '{}', moved from {} to {}",
+            adopted.name, from, adopted.path
+        ));
+    } else {
+        output().success(&format!("Adopted '{}' in place at {}", adopted.name, adopted.path));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::exit_handler::MockExitHandler;
+    use crate::core::filesystems::MockFileSystem;
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    async fn test_adopt_missing_path_fails_before_listing_worktrees() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        let context =
+            HandlerContext::new(mock.clone(), MockFileSystem::new(), MockExitHandler::new());
+
+        let args = AdoptArgs {
+            path: PathBuf::from("/nonexistent/phantom-adopt"),
+            name: None,
+            in_place: false,
+            json: false,
+        };
+        let err = handle(args, context).await.unwrap_err();
+
+        assert!(format!("{err:#}").contains("not a worktree of this repository"), "{err:#}");
+        assert_eq!(mock.calls().len(), 1);
+    }
+}
@@ -1,19 +1,61 @@
+use crate::api::Phantom;
 use crate::cli::commands::attach::AttachArgs;
 use crate::cli::context::HandlerContext;
-use crate::cli::output::output;
+use crate::cli::dry_run::{print_plans, DryRunPlan};
+use crate::cli::handlers::create::report_copied_files;
+use crate::cli::output::{output, timings_ms};
 use crate::core::command_executor::CommandExecutor;
+use crate::core::executors::DryRunExecutor;
 use crate::core::exit_handler::ExitHandler;
 use crate::core::filesystem::FileSystem;
+use crate::core::utils::timed;
+use crate::git::libs::attach_worktree::{attach_worktree as git_attach_worktree, set_upstream};
 use crate::git::libs::branch_exists::branch_exists;
-use crate::git::libs::get_git_root::get_git_root;
+use crate::git::libs::fetch_branch::{create_tracking_branch, fetch_branch, remote_branch_exists};
 use crate::process::exec::exec_in_dir;
 use crate::process::shell::shell_in_dir;
-use crate::worktree::attach::attach_worktree;
+use crate::worktree::concurrent::run_bounded;
+use crate::worktree::last_used::record_last_worktree;
+use crate::worktree::locate::resolve_copy_source;
 use crate::worktree::paths::get_worktree_path;
+use crate::worktree::select::select_branch_with_fzf;
+use crate::worktree::types::AttachWorktreeOptions;
 use crate::worktree::validate::validate_worktree_name;
+use crate::PhantomError;
 use anyhow::{anyhow, bail, Context, Result};
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
 use tokio::fs;
+use tokio::sync::Mutex;
+
+/// Most branches attached at once by --from-file
+const MAX_CONCURRENT_ATTACHES: usize = 4;
+
+/// What happened to one branch of an --from-file batch
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum BatchStatus {
+    Attached,
+    /// The branch already had a worktree
+    Skipped,
+    Failed,
+}
+
+#[derive(Serialize)]
+struct BatchEntry {
+    branch: String,
+    status: BatchStatus,
+    path: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AttachBatchJsonOutput {
+    success: bool,
+    branches: Vec<BatchEntry>,
+}
 
 #[derive(Serialize)]
 struct AttachJsonOutput {
@@ -21,6 +63,14 @@ struct AttachJsonOutput {
     message: String,
     worktree: String,
     path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    copied_files: Option<Vec<String>>,
+    /// Remote branch the attached branch tracks, when attach set it up
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upstream: Option<String>,
+    /// Milliseconds per timed section, with --timings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timings_ms: Option<BTreeMap<String, u64>>,
 }
 
 /// Handle the attach command
@@ -30,53 +80,138 @@ where
     F: FileSystem + Clone + 'static,
     H: ExitHandler + Clone + 'static,
 {
+    if args.branch.is_some() && args.fzf {
+        bail!("Cannot specify both a branch name and --fzf option");
+    }
+    if let Some(path) = &args.from_file {
+        let branches = if path.as_os_str() == "-" {
+            read_branch_list(std::io::stdin().lock())
+                .with_context(|| "Failed to read branch names from stdin")?
+        } else {
+            let contents = fs::read_to_string(path)
+                .await
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            read_branch_list(contents.as_bytes())?
+        };
+        return attach_many(branches, args, context).await;
+    }
+
     // Validate branch name
-    validate_worktree_name(&args.branch)?;
+    if let Some(branch) = &args.branch {
+        validate_worktree_name(branch)?;
+    }
 
     // Get git root
-    let git_root = get_git_root(context.executor.clone()).await?;
+    let git_root = timed("git_root", context.git_root()).await?;
+    for warning in context.git_config(&git_root).await.warnings() {
+        output().warn(&warning);
+    }
+
+    // Branch to attach, and the remote it only exists on when picked from one
+    let (branch, picked_remote) = match args.branch.clone() {
+        Some(branch) => (branch, None),
+        None => {
+            let remote = args.include_remote.as_deref();
+            match select_branch_with_fzf(context.executor.clone(), &git_root, remote)
+                .await
+                .with_context(|| "Failed to select branch with fzf")?
+            {
+                Some(selected) => {
+                    validate_worktree_name(&selected.name)?;
+                    (selected.name, selected.remote)
+                }
+                None => {
+                    // User cancelled selection
+                    return Ok(());
+                }
+            }
+        }
+    };
 
     // Check if worktree already exists
-    let worktree_path = get_worktree_path(&git_root, &args.branch);
+    let worktree_path = get_worktree_path(&git_root, &branch);
     if fs::metadata(&worktree_path).await.is_ok() {
-        bail!("Worktree '{}' already exists at path: {}", args.branch, worktree_path.display());
+        bail!("Worktree '{branch}' already exists at path: {}", worktree_path.display());
     }
 
-    // Check if branch exists
-    if !branch_exists(context.executor.clone(), &git_root, &args.branch)
-        .await
-        .with_context(|| format!("Failed to check if branch '{}' exists", args.branch))?
-    {
-        return Err(crate::PhantomError::BranchNotFound { branch: args.branch.clone() }.into());
+    if args.dry_run {
+        let executor = DryRunExecutor::new(context.executor.clone());
+        let remote = picked_remote.as_deref();
+        let tracking = ensure_local_branch(&executor, &git_root, &branch, &args, remote).await?;
+        git_attach_worktree(executor.clone(), &git_root, &worktree_path, &branch).await?;
+        if tracking.is_none() && sets_upstream(&args, &git_root, &context).await? {
+            let remote = remote.unwrap_or(&args.remote);
+            track_upstream(&executor, &git_root, remote, &branch).await;
+        }
+        let copy_files = configured_copy_files(&args, &git_root, &context).await?;
+        let copy_source = copy_source(&args, &git_root, &context).await?;
+        let mut plan = DryRunPlan::new(&branch, Some(&branch), &worktree_path, &executor);
+        plan.add_copies(executor.clone(), copy_files.as_deref(), &copy_source).await?;
+        return print_plans(&[plan], "attach", args.json);
     }
 
+    let remote = picked_remote.as_deref();
+    let tracking =
+        ensure_local_branch(&context.executor, &git_root, &branch, &args, remote).await?;
+    let copy_files = configured_copy_files(&args, &git_root, &context).await?;
+    let copy_source = Some(copy_source(&args, &git_root, &context).await?);
+    let set_upstream_after =
+        tracking.is_none() && sets_upstream(&args, &git_root, &context).await?;
+
     // Attach the worktree
-    attach_worktree(context.executor.clone(), &git_root, &args.branch)
+    let phantom = Phantom::new(&git_root, context.executor.clone(), context.filesystem.clone());
+    let result = phantom
+        .attach(&branch, AttachWorktreeOptions { copy_files, copy_source })
         .await
-        .with_context(|| format!("Failed to attach worktree for branch '{}'", args.branch))?;
+        .with_context(|| format!("Failed to attach worktree for branch '{branch}'"))?;
+
+    if let Some(e) = &result.metadata_error {
+        output().warn(&format!("Failed to record metadata for '{branch}': {e}"));
+    }
+    record_last_worktree(&context.filesystem, &git_root, &branch).await;
+
+    let upstream = if set_upstream_after {
+        let remote = remote.unwrap_or(&args.remote);
+        track_upstream(&context.executor, &git_root, remote, &branch).await
+    } else {
+        tracking
+    };
+    let message = match &upstream {
+        Some(upstream) => format!("Attached phantom: {branch} (tracking {upstream})"),
+        None => format!("Attached phantom: {branch}"),
+    };
 
     if args.json {
         let json_output = AttachJsonOutput {
             success: true,
-            message: format!("Attached phantom: {}", args.branch),
-            worktree: args.branch.clone(),
+            message,
+            worktree: branch.clone(),
             path: worktree_path.to_string_lossy().to_string(),
+            copied_files: result.copied_files.clone(),
+            upstream,
+            timings_ms: timings_ms(),
         };
         output().log(&serde_json::to_string_pretty(&json_output)?);
     } else {
-        output().success(&format!("Attached phantom: {}", args.branch));
+        output().success(&message);
+        report_copied_files(
+            result.copied_files.as_deref(),
+            result.skipped_files.as_deref(),
+            result.expanded_from.as_ref(),
+            result.copy_error.as_deref(),
+        );
     }
 
     // Handle post-attach actions
     if args.shell {
-        shell_in_dir(&context.executor, &worktree_path)
+        shell_in_dir(&context.executor, &*context.environment, &worktree_path)
             .await
             .map_err(|e| anyhow!(e))
             .with_context(|| {
                 format!("Failed to open shell in worktree path: {}", worktree_path.display())
             })?;
     } else if let Some(exec_cmd) = args.exec {
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+        let shell = context.environment.get_var("SHELL").unwrap_or_else(|| "/bin/sh".to_string());
         exec_in_dir(&worktree_path, &shell, &["-c".to_string(), exec_cmd.clone()])
             .await
             .map_err(|e| anyhow!(e))
@@ -92,6 +227,283 @@ where
     Ok(())
 }
 
+/// Branch names from a list, one per line, without blank lines, `#` comments or repeats
+fn read_branch_list(reader: impl BufRead) -> Result<Vec<String>> {
+    let mut branches: Vec<String> = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let branch = line.trim();
+        if !branch.is_empty() && !branch.starts_with('#') && !branches.iter().any(|b| b == branch) {
+            branches.push(branch.to_string());
+        }
+    }
+    Ok(branches)
+}
+
+/// Attach each of `branches` concurrently and print a table of the outcomes
+///
+/// Every name is validated before anything is attached. A branch that already
+/// has a worktree is skipped; the command only fails when an attach did.
+async fn attach_many<E, F, H>(
+    branches: Vec<String>,
+    args: AttachArgs,
+    context: HandlerContext<E, F, H>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let invalid: Vec<String> = branches
+        .iter()
+        .filter_map(|branch| match validate_worktree_name(branch) {
+            Err(PhantomError::InvalidWorktreeName { reason, .. }) => {
+                Some(format!("'{branch}': {reason}"))
+            }
+            _ => None,
+        })
+        .collect();
+    if !invalid.is_empty() {
+        bail!("Nothing was attached:\n  {}", invalid.join("\n  "));
+    }
+
+    let git_root = context.git_root().await?;
+    let copy_files = configured_copy_files(&args, &git_root, &context).await?;
+    let copy_source = Some(copy_source(&args, &git_root, &context).await?);
+    let track = sets_upstream(&args, &git_root, &context).await?;
+    let phantom = Phantom::new(&git_root, context.executor.clone(), context.filesystem.clone());
+    // Fetching and creating tracking branches write the git config, which does not retry its lock
+    let branch_lock = Mutex::new(());
+
+    let entries = run_bounded(branches, MAX_CONCURRENT_ATTACHES, |branch| {
+        let (args, git_root, context) = (&args, &git_root, &context);
+        let (phantom, copy_files, branch_lock) = (&phantom, &copy_files, &branch_lock);
+        let copy_source = &copy_source;
+        async move {
+            let worktree_path = get_worktree_path(git_root, &branch);
+            if fs::metadata(&worktree_path).await.is_ok() {
+                return BatchEntry {
+                    branch,
+                    status: BatchStatus::Skipped,
+                    path: Some(worktree_path.to_string_lossy().to_string()),
+                    error: None,
+                };
+            }
+
+            let attached = async {
+                let tracking = {
+                    let _guard = branch_lock.lock().await;
+                    ensure_local_branch(&context.executor, git_root, &branch, args, None).await?
+                };
+                let options = AttachWorktreeOptions {
+                    copy_files: copy_files.clone(),
+                    copy_source: copy_source.clone(),
+                };
+                let result = phantom.attach(&branch, options).await?;
+                if tracking.is_none() && track {
+                    let _guard = branch_lock.lock().await;
+                    track_upstream(&context.executor, git_root, &args.remote, &branch).await;
+                }
+                Ok::<_, anyhow::Error>(result)
+            }
+            .await;
+            match attached {
+                Ok(result) => {
+                    if let Some(e) = &result.metadata_error {
+                        output().warn(&format!("Failed to record metadata for '{branch}': {e}"));
+                    }
+                    BatchEntry {
+                        branch,
+                        status: BatchStatus::Attached,
+                        path: Some(worktree_path.to_string_lossy().to_string()),
+                        error: None,
+                    }
+                }
+                Err(e) => BatchEntry {
+                    branch,
+                    status: BatchStatus::Failed,
+                    path: None,
+                    error: Some(format!("{e:#}")),
+                },
+            }
+        }
+    })
+    .await;
+
+    let count = |status| entries.iter().filter(|entry| entry.status == status).count();
+    let (attached, skipped, failed) =
+        (count(BatchStatus::Attached), count(BatchStatus::Skipped), count(BatchStatus::Failed));
+    let total = entries.len();
+
+    if args.json {
+        let json_output = AttachBatchJsonOutput { success: failed == 0, branches: entries };
+        output().log(&serde_json::to_string_pretty(&json_output)?);
+    } else {
+        output().log(&batch_table(&entries));
+        output().log(&format!("Attached {attached}, skipped {skipped}, failed {failed}"));
+    }
+
+    if failed > 0 {
+        bail!("{failed} of {total} branches failed to attach");
+    }
+    Ok(())
+}
+
+/// One row per branch: its name, status and the worktree path or error
+fn batch_table(entries: &[BatchEntry]) -> String {
+    let width = entries.iter().map(|entry| entry.branch.len()).max().unwrap_or(0);
+    entries
+        .iter()
+        .map(|entry| {
+            let status = match entry.status {
+                BatchStatus::Attached => "attached",
+                BatchStatus::Skipped => "skipped",
+                BatchStatus::Failed => "failed",
+            };
+            let detail = entry.error.as_deref().or(entry.path.as_deref()).unwrap_or_default();
+            format!("{:<width$}  {status:<8}  {detail}", entry.branch)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Make sure `branch` exists locally, creating it from the remote when it only exists there
+///
+/// With --fetch the branch is fetched first; `picked_remote` is the remote fzf found it on.
+/// Returns the upstream of a branch created here, which already tracks the remote.
+async fn ensure_local_branch<E>(
+    executor: &E,
+    git_root: &Path,
+    branch: &str,
+    args: &AttachArgs,
+    picked_remote: Option<&str>,
+) -> Result<Option<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let fetched = if args.fetch {
+        timed("fetch", fetch_branch(executor.clone(), git_root, &args.remote, branch))
+            .await
+            .with_context(|| format!("Failed to fetch branch '{branch}' from '{}'", args.remote))?
+    } else {
+        false
+    };
+
+    if branch_exists(executor.clone(), git_root, branch)
+        .await
+        .with_context(|| format!("Failed to check if branch '{branch}' exists"))?
+    {
+        return Ok(None);
+    }
+    if !args.fetch && picked_remote.is_none() {
+        return Err(PhantomError::BranchNotFound { branch: branch.to_string() }.into());
+    }
+
+    let remote = picked_remote.unwrap_or(&args.remote);
+    let on_remote = (fetched || picked_remote.is_some())
+        && remote_branch_exists(executor.clone(), git_root, remote, branch).await?;
+    if !on_remote {
+        return Err(PhantomError::BranchNotFoundAnywhere {
+            branch: branch.to_string(),
+            remote: remote.to_string(),
+        }
+        .into());
+    }
+
+    timed("branch_create", create_tracking_branch(executor.clone(), git_root, remote, branch))
+        .await
+        .with_context(|| {
+            format!("Failed to create local branch '{branch}' tracking '{remote}/{branch}'")
+        })?;
+    Ok(Some(format!("{remote}/{branch}")))
+}
+
+/// Whether to set upstreams, unless --no-track or `attach.setUpstream: false` says otherwise
+async fn sets_upstream<E, F, H>(
+    args: &AttachArgs,
+    git_root: &Path,
+    context: &HandlerContext<E, F, H>,
+) -> Result<bool>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    if args.no_track {
+        return Ok(false);
+    }
+    let config = context
+        .config(git_root)
+        .await
+        .with_context(|| format!("Failed to load config from git root: {}", git_root.display()))?;
+    Ok(config.map_or(true, |loaded| loaded.config.attach_sets_upstream()))
+}
+
+/// Make `branch` track `<remote>/<branch>` when that exists, returning the upstream that was set
+///
+/// The worktree exists by now, so a failure is only a warning.
+async fn track_upstream<E>(
+    executor: &E,
+    git_root: &Path,
+    remote: &str,
+    branch: &str,
+) -> Option<String>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    match set_upstream(executor.clone(), git_root, remote, branch).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            output().warn(&format!("Failed to set the upstream of '{branch}': {e}"));
+            None
+        }
+    }
+}
+
+/// The configured copyFiles, unless --no-copy was given
+/// Directory the copies are read from: --source, otherwise the main worktree
+async fn copy_source<E, F, H>(
+    args: &AttachArgs,
+    git_root: &Path,
+    context: &HandlerContext<E, F, H>,
+) -> Result<PathBuf>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    resolve_copy_source(
+        context.executor.clone(),
+        git_root,
+        args.source.as_deref(),
+        &context.filesystem,
+    )
+    .await
+    .with_context(|| "Failed to resolve --source")
+}
+
+async fn configured_copy_files<E, F, H>(
+    args: &AttachArgs,
+    git_root: &Path,
+    context: &HandlerContext<E, F, H>,
+) -> Result<Option<Vec<String>>>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    if args.no_copy {
+        return Ok(None);
+    }
+    let config = context
+        .config(git_root)
+        .await
+        .with_context(|| format!("Failed to load config from git root: {}", git_root.display()))?;
+    Ok(config.and_then(|loaded| {
+        loaded.config.post_create.as_ref().and_then(|pc| pc.copy_file_entries())
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,11 +541,34 @@ mod tests {
             .in_dir(&git_root_canonical)
             .returns_success();
 
-        let args =
-            AttachArgs { branch: "test-branch".to_string(), json: false, shell: false, exec: None };
+        // No upstream yet, and origin has no such branch, so none is set
+        mock.expect_command("git")
+            .with_args(&["for-each-ref", "--format=%(upstream:short)", "refs/heads/test-branch"])
+            .in_dir(&git_root_canonical)
+            .returns_output("", "", 0);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/remotes/origin/test-branch"])
+            .in_dir(&git_root_canonical)
+            .returns_output("", "", 1);
+
+        let args = AttachArgs {
+            branch: Some("test-branch".to_string()),
+            json: false,
+            shell: false,
+            exec: None,
+            fetch: false,
+            remote: "origin".to_string(),
+            no_copy: false,
+            source: None,
+            no_track: false,
+            dry_run: false,
+            fzf: false,
+            include_remote: None,
+            from_file: None,
+        };
 
         let context = HandlerContext::new(
-            mock,
+            mock.clone(),
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
         );
@@ -143,6 +578,7 @@ mod tests {
             eprintln!("Handle failed with error: {e:?}");
         }
         assert!(result.is_ok());
+        mock.verify().unwrap();
     }
 
     #[tokio::test]
@@ -166,8 +602,21 @@ mod tests {
             .in_dir(&git_root_canonical)
             .returns_output("", "fatal: bad ref for symbolic ref refs/heads/nonexistent\n", 1);
 
-        let args =
-            AttachArgs { branch: "nonexistent".to_string(), json: false, shell: false, exec: None };
+        let args = AttachArgs {
+            branch: Some("nonexistent".to_string()),
+            json: false,
+            shell: false,
+            exec: None,
+            fetch: false,
+            remote: "origin".to_string(),
+            no_copy: false,
+            source: None,
+            no_track: false,
+            dry_run: false,
+            fzf: false,
+            include_remote: None,
+            from_file: None,
+        };
 
         let context = HandlerContext::new(
             mock,
@@ -201,10 +650,19 @@ mod tests {
         );
 
         let args = AttachArgs {
-            branch: "existing-branch".to_string(),
+            branch: Some("existing-branch".to_string()),
             json: false,
             shell: false,
             exec: None,
+            fetch: false,
+            remote: "origin".to_string(),
+            no_copy: false,
+            source: None,
+            no_track: false,
+            dry_run: false,
+            fzf: false,
+            include_remote: None,
+            from_file: None,
         };
 
         let context = HandlerContext::new(
@@ -223,7 +681,21 @@ mod tests {
     async fn test_attach_invalid_worktree_name() {
         let mock = MockCommandExecutor::new();
 
-        let args = AttachArgs { branch: "".to_string(), json: false, shell: false, exec: None };
+        let args = AttachArgs {
+            branch: Some("".to_string()),
+            json: false,
+            shell: false,
+            exec: None,
+            fetch: false,
+            remote: "origin".to_string(),
+            no_copy: false,
+            source: None,
+            no_track: false,
+            dry_run: false,
+            fzf: false,
+            include_remote: None,
+            from_file: None,
+        };
 
         let context = HandlerContext::new(
             mock,
@@ -266,8 +738,21 @@ mod tests {
             .in_dir(&git_root_canonical)
             .returns_success();
 
-        let args =
-            AttachArgs { branch: "json-branch".to_string(), json: true, shell: false, exec: None };
+        let args = AttachArgs {
+            branch: Some("json-branch".to_string()),
+            json: true,
+            shell: false,
+            exec: None,
+            fetch: false,
+            remote: "origin".to_string(),
+            no_copy: false,
+            source: None,
+            no_track: false,
+            dry_run: false,
+            fzf: false,
+            include_remote: None,
+            from_file: None,
+        };
 
         let context = HandlerContext::new(
             mock,
@@ -281,4 +766,447 @@ mod tests {
         }
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_attach_copies_configured_files_unless_no_copy() {
+        for no_copy in [false, true] {
+            let temp_dir = tempdir().unwrap();
+            let git_root = temp_dir.path().canonicalize().unwrap();
+            let worktree_path =
+                git_root.join(".git").join("phantom").join("worktrees").join("feature");
+            std::fs::write(
+                git_root.join("phantom.config.json"),
+                r#"{"postCreate": {"copyFiles": [".env"]}}"#,
+            )
+            .unwrap();
+            std::fs::write(git_root.join(".env"), "KEY=1").unwrap();
+
+            let mut mock = MockCommandExecutor::new();
+            mock_git_root(&mut mock, &git_root);
+            mock.expect_command("git")
+                .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/feature"])
+                .in_dir(&git_root)
+                .returns_success();
+            mock.expect_command("git")
+                .with_args(&["worktree", "add", &worktree_path.to_string_lossy(), "feature"])
+                .in_dir(&git_root)
+                .returns_success();
+
+            let args =
+                AttachArgs { fetch: false, no_copy, no_track: true, ..fetch_args("feature") };
+            let context = HandlerContext::new(
+                mock,
+                crate::core::filesystems::MockFileSystem::new(),
+                crate::core::exit_handler::MockExitHandler::new(),
+            );
+
+            handle(args, context).await.unwrap();
+            assert_eq!(worktree_path.join(".env").exists(), !no_copy);
+        }
+    }
+
+    fn fetch_args(branch: &str) -> AttachArgs {
+        AttachArgs {
+            branch: Some(branch.to_string()),
+            json: false,
+            shell: false,
+            exec: None,
+            fetch: true,
+            remote: "origin".to_string(),
+            no_copy: false,
+            source: None,
+            no_track: false,
+            dry_run: false,
+            fzf: false,
+            include_remote: None,
+            from_file: None,
+        }
+    }
+
+    fn mock_git_root(mock: &mut MockCommandExecutor, git_root: &std::path::Path) {
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            &format!("{}/.git", git_root.to_string_lossy()),
+            "",
+            0,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_attach_fetch_creates_tracking_branch() {
+        let temp_dir = tempdir().unwrap();
+        let git_root = temp_dir.path().canonicalize().unwrap();
+        let worktree_path =
+            git_root.join(".git").join("phantom").join("worktrees").join("feature").join("foo");
+
+        let mut mock = MockCommandExecutor::new();
+        mock_git_root(&mut mock, &git_root);
+        mock.expect_command("git")
+            .with_args(&["fetch", "origin", "feature/foo"])
+            .in_dir(&git_root)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/feature/foo"])
+            .in_dir(&git_root)
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/remotes/origin/feature/foo"])
+            .in_dir(&git_root)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["branch", "--track", "feature/foo", "origin/feature/foo"])
+            .in_dir(&git_root)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["worktree", "add", &worktree_path.to_string_lossy(), "feature/foo"])
+            .in_dir(&git_root)
+            .returns_success();
+
+        let context = HandlerContext::new(
+            mock,
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let result = handle(fetch_args("feature/foo"), context).await;
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_attach_sets_upstream_unless_no_track() {
+        for no_track in [false, true] {
+            let temp_dir = tempdir().unwrap();
+            let git_root = temp_dir.path().canonicalize().unwrap();
+            let worktree_path = git_root.join(".git/phantom/worktrees/feature");
+
+            let mut mock = MockCommandExecutor::new();
+            mock_git_root(&mut mock, &git_root);
+            mock.expect_command("git")
+                .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/feature"])
+                .in_dir(&git_root)
+                .returns_success();
+            mock.expect_command("git")
+                .with_args(&["worktree", "add", &worktree_path.to_string_lossy(), "feature"])
+                .in_dir(&git_root)
+                .returns_success();
+            if !no_track {
+                mock.expect_command("git")
+                    .with_args(&[
+                        "for-each-ref",
+                        "--format=%(upstream:short)",
+                        "refs/heads/feature",
+                    ])
+                    .in_dir(&git_root)
+                    .returns_output("\n", "", 0);
+                mock.expect_command("git")
+                    .with_args(&["show-ref", "--verify", "--quiet", "refs/remotes/origin/feature"])
+                    .in_dir(&git_root)
+                    .returns_success();
+                mock.expect_command("git")
+                    .with_args(&["branch", "--set-upstream-to=origin/feature", "feature"])
+                    .in_dir(&git_root)
+                    .times(1)
+                    .returns_success();
+            }
+
+            let context = HandlerContext::new(
+                mock.clone(),
+                crate::core::filesystems::MockFileSystem::new(),
+                crate::core::exit_handler::MockExitHandler::new(),
+            );
+            let args =
+                AttachArgs { fetch: false, no_copy: true, no_track, ..fetch_args("feature") };
+
+            handle(args, context).await.unwrap();
+            mock.verify().unwrap();
+            let tracked =
+                mock.calls().iter().any(|call| call.args.first().unwrap() == "for-each-ref");
+            assert_eq!(tracked, !no_track);
+        }
+    }
+
+    fn mock_fzf_branches(mock: &mut MockCommandExecutor, git_root: &std::path::Path, pick: &str) {
+        mock.expect_command("git")
+            .with_args(&[
+                "for-each-ref",
+                "--format=%(refname)%09%(committerdate:short)",
+                "refs/heads/",
+                "refs/remotes/origin/",
+            ])
+            .in_dir(git_root)
+            .returns_output("refs/remotes/origin/review\t2026-01-02\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .in_dir(git_root)
+            .returns_output("", "", 0);
+        mock.expect_command("fzf").with_args(&["--version"]).returns_success();
+        let code = if pick.is_empty() { 130 } else { 0 };
+        mock.expect_command("fzf").returns_output(pick, "", code);
+    }
+
+    #[tokio::test]
+    async fn test_attach_fzf_remote_branch_creates_tracking_branch() {
+        let temp_dir = tempdir().unwrap();
+        let git_root = temp_dir.path().canonicalize().unwrap();
+        let worktree_path = git_root.join(".git/phantom/worktrees/review");
+
+        let mut mock = MockCommandExecutor::new();
+        mock_git_root(&mut mock, &git_root);
+        mock_fzf_branches(&mut mock, &git_root, "review (2026-01-02, origin)\n");
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/review"])
+            .in_dir(&git_root)
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/remotes/origin/review"])
+            .in_dir(&git_root)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["branch", "--track", "review", "origin/review"])
+            .in_dir(&git_root)
+            .times(1)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["worktree", "add", &worktree_path.to_string_lossy(), "review"])
+            .in_dir(&git_root)
+            .times(1)
+            .returns_success();
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = AttachArgs {
+            branch: None,
+            fzf: true,
+            include_remote: Some("origin".to_string()),
+            from_file: None,
+            fetch: false,
+            ..fetch_args("unused")
+        };
+
+        handle(args, context).await.unwrap();
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_attach_fzf_cancel_is_ok() {
+        let temp_dir = tempdir().unwrap();
+        let git_root = temp_dir.path().canonicalize().unwrap();
+
+        let mut mock = MockCommandExecutor::new();
+        mock_git_root(&mut mock, &git_root);
+        mock_fzf_branches(&mut mock, &git_root, "");
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = AttachArgs {
+            branch: None,
+            fzf: true,
+            include_remote: Some("origin".to_string()),
+            from_file: None,
+            fetch: false,
+            ..fetch_args("unused")
+        };
+
+        handle(args, context).await.unwrap();
+        assert!(mock.calls().iter().all(|call| !call.args.contains(&"add".to_string())));
+    }
+
+    #[test]
+    fn test_attach_fzf_args() {
+        use crate::cli::Cli;
+        use clap::Parser;
+
+        assert!(Cli::try_parse_from(["phantom", "attach", "--fzf", "--include-remote"]).is_ok());
+        assert!(Cli::try_parse_from(["phantom", "attach", "--fzf", "--include-remote=up"]).is_ok());
+        assert!(Cli::try_parse_from(["phantom", "attach", "--fzf", "--remote", "up"]).is_err());
+        assert!(Cli::try_parse_from(["phantom", "attach"]).is_err());
+        assert!(Cli::try_parse_from(["phantom", "attach", "main", "--include-remote"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_attach_fetch_network_failure_surfaces_stderr() {
+        let temp_dir = tempdir().unwrap();
+        let git_root = temp_dir.path().canonicalize().unwrap();
+
+        let mut mock = MockCommandExecutor::new();
+        mock_git_root(&mut mock, &git_root);
+        mock.expect_command("git")
+            .with_args(&["fetch", "origin", "feature"])
+            .in_dir(&git_root)
+            .returns_output(
+                "",
+                "fatal: unable to access 'https://example.com/repo.git/': Could not resolve host: example.com",
+                128,
+            );
+
+        let context = HandlerContext::new(
+            mock,
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let err = handle(fetch_args("feature"), context).await.unwrap_err();
+        let chain = format!("{err:#}");
+        assert!(chain.contains("Failed to fetch branch 'feature' from 'origin'"), "{chain}");
+        assert!(chain.contains("Could not resolve host: example.com"), "{chain}");
+    }
+
+    #[tokio::test]
+    async fn test_attach_worktree_add_failure_surfaces_git_stderr() {
+        let temp_dir = tempdir().unwrap();
+        let git_root = temp_dir.path().canonicalize().unwrap();
+        let worktree_path = git_root.join(".git/phantom/worktrees/main");
+
+        let mut mock = MockCommandExecutor::new();
+        mock_git_root(&mut mock, &git_root);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/main"])
+            .in_dir(&git_root)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["worktree", "add", &worktree_path.to_string_lossy(), "main"])
+            .in_dir(&git_root)
+            .returns_output("", "fatal: 'main' is already checked out at '/repo'\n", 128);
+
+        let context = HandlerContext::new(
+            mock,
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = AttachArgs { fetch: false, ..fetch_args("main") };
+
+        let err = handle(args, context).await.unwrap_err();
+        let message = crate::cli::error::format_error(&err);
+        assert!(
+            message.contains(&format!("'git worktree add {} main'", worktree_path.display())),
+            "{message}"
+        );
+        assert!(
+            message.ends_with("\nfatal: 'main' is already checked out at '/repo'"),
+            "{message}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_attach_fetch_branch_missing_everywhere() {
+        let temp_dir = tempdir().unwrap();
+        let git_root = temp_dir.path().canonicalize().unwrap();
+
+        let mut mock = MockCommandExecutor::new();
+        mock_git_root(&mut mock, &git_root);
+        mock.expect_command("git")
+            .with_args(&["fetch", "origin", "missing"])
+            .in_dir(&git_root)
+            .returns_output("", "fatal: couldn't find remote ref missing", 128);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/missing"])
+            .in_dir(&git_root)
+            .returns_output("", "", 1);
+
+        let context = HandlerContext::new(
+            mock,
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let err = handle(fetch_args("missing"), context).await.unwrap_err();
+        assert_eq!(err.to_string(), "Branch 'missing' not found locally or on remote 'origin'");
+    }
+
+    #[test]
+    fn test_read_branch_list_skips_blanks_comments_and_repeats() {
+        let list = "# release 2.4\nfix/login\n\n  fix/cache  \nfix/login\n#fix/old\n";
+        assert_eq!(read_branch_list(list.as_bytes()).unwrap(), vec!["fix/login", "fix/cache"]);
+    }
+
+    fn batch_args(list: &std::path::Path) -> AttachArgs {
+        AttachArgs {
+            branch: None,
+            fetch: false,
+            no_copy: true,
+            source: None,
+            no_track: true,
+            dry_run: false,
+            from_file: Some(list.to_path_buf()),
+            ..fetch_args("unused")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_attach_from_file_skips_existing_and_reports_failures() {
+        let temp_dir = tempdir().unwrap();
+        let git_root = temp_dir.path().canonicalize().unwrap();
+        let worktrees = git_root.join(".git/phantom/worktrees");
+        std::fs::create_dir_all(worktrees.join("done")).unwrap();
+        let list = git_root.join("branches.txt");
+        std::fs::write(&list, "# triage\ndone\nready\ngone\n").unwrap();
+
+        let mut mock = MockCommandExecutor::new();
+        mock_git_root(&mut mock, &git_root);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/ready"])
+            .in_dir(&git_root)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["worktree", "add", &worktrees.join("ready").to_string_lossy(), "ready"])
+            .in_dir(&git_root)
+            .times(1)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/gone"])
+            .in_dir(&git_root)
+            .returns_output("", "", 1);
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let err = handle(batch_args(&list), context).await.unwrap_err();
+
+        assert_eq!(err.to_string(), "1 of 3 branches failed to attach");
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_attach_from_file_validates_every_name_first() {
+        let temp_dir = tempdir().unwrap();
+        let list = temp_dir.path().join("branches.txt");
+        std::fs::write(&list, "ready\nbad..name\n").unwrap();
+
+        let mock = MockCommandExecutor::new();
+        let context = HandlerContext::new(
+            mock.clone(),
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let err = handle(batch_args(&list), context).await.unwrap_err();
+
+        assert!(err.to_string().starts_with("Nothing was attached"), "{err}");
+        assert!(err.to_string().contains("'bad..name'"), "{err}");
+        assert!(mock.calls().is_empty());
+    }
+
+    #[test]
+    fn test_batch_table_aligns_columns() {
+        let entry = |branch: &str, status, path: Option<&str>, error: Option<&str>| BatchEntry {
+            branch: branch.to_string(),
+            status,
+            path: path.map(String::from),
+            error: error.map(String::from),
+        };
+        let table = batch_table(&[
+            entry("fix/login", BatchStatus::Attached, Some("/wt/fix/login"), None),
+            entry("docs", BatchStatus::Failed, None, Some("Branch 'docs' not found")),
+        ]);
+        assert_eq!(
+            table,
+            "fix/login  attached  /wt/fix/login\ndocs       failed    Branch 'docs' not found"
+        );
+    }
 }
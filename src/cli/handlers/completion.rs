@@ -1,11 +1,14 @@
 use crate::cli::commands::completion::{CompletionArgs, Shell};
+use crate::core::filesystem::FileSystem;
+use crate::git::libs::get_git_root::discover_git_root;
+use crate::worktree::list::worktree_names_from_directory;
 use anyhow::Result;
 
 const FISH_COMPLETION: &str = r#"# Fish completion for phantom
 # Place this in ~/.config/fish/completions/phantom.fish
 
 function __phantom_list_worktrees
-    phantom list --names 2>/dev/null
+    phantom __complete-worktrees 2>/dev/null
 end
 
 function __phantom_using_command
@@ -38,8 +41,18 @@ complete -c phantom -n "__phantom_using_command" -a "where" -d "Output the files
 complete -c phantom -n "__phantom_using_command" -a "delete" -d "Delete a Git worktree (phantom)"
 complete -c phantom -n "__phantom_using_command" -a "exec" -d "Execute a command in a worktree directory"
 complete -c phantom -n "__phantom_using_command" -a "shell" -d "Open an interactive shell in a worktree directory"
+complete -c phantom -n "__phantom_using_command" -a "label" -d "Add or remove labels on a worktree"
+complete -c phantom -n "__phantom_using_command" -a "show" -d "Show details of a worktree"
+complete -c phantom -n "__phantom_using_command" -a "status" -d "Summarize the state of a worktree"
+complete -c phantom -n "__phantom_using_command" -a "config" -d "Inspect the repository configuration"
+complete -c phantom -n "__phantom_using_command" -a "gc" -d "Find and remove directories and state left behind by deleted worktrees"
+complete -c phantom -n "__phantom_using_command" -a "init" -d "Write a starter phantom.config.toml or phantom.config.json"
+complete -c phantom -n "__phantom_using_command" -a "adopt" -d "Manage a worktree created outside phantom"
+complete -c phantom -n "__phantom_using_command" -a "lock" -d "Lock a worktree so git will not prune or remove it"
+complete -c phantom -n "__phantom_using_command" -a "unlock" -d "Unlock a locked worktree"
 complete -c phantom -n "__phantom_using_command" -a "version" -d "Display phantom version information"
 complete -c phantom -n "__phantom_using_command" -a "completion" -d "Generate shell completion scripts"
+complete -c phantom -n "__phantom_using_command" -a "shellenv" -d "Print a shell function adding phantom cd"
 
 # Global options
 complete -c phantom -l help -d "Show help (-h)"
@@ -51,35 +64,134 @@ complete -c phantom -n "__phantom_using_command create" -l exec -d "Execute a co
 complete -c phantom -n "__phantom_using_command create" -l tmux -d "Open the worktree in a new tmux window (-t)"
 complete -c phantom -n "__phantom_using_command create" -l tmux-vertical -d "Open the worktree in a vertical tmux pane"
 complete -c phantom -n "__phantom_using_command create" -l tmux-horizontal -d "Open the worktree in a horizontal tmux pane"
-complete -c phantom -n "__phantom_using_command create" -l copy-file -d "Copy specified files from the current worktree" -r
+complete -c phantom -n "__phantom_using_command create" -l kitty -d "Open the worktree in a new kitty tab"
+complete -c phantom -n "__phantom_using_command create" -l kitty-vertical -d "Open the worktree in a vertical kitty split"
+complete -c phantom -n "__phantom_using_command create" -l kitty-horizontal -d "Open the worktree in a horizontal kitty split"
+complete -c phantom -n "__phantom_using_command create" -l copy-files -d "Extra files to copy, added to the configured copyFiles" -r
+complete -c phantom -n "__phantom_using_command create" -l copy-files-only -d "Copy only the files given with --copy-files"
+complete -c phantom -n "__phantom_using_command create" -l no-copy -d "Do not copy any files into the new worktree"
+complete -c phantom -n "__phantom_using_command create" -l source -d "Copy files from this phantom or directory" -r
+complete -c phantom -n "__phantom_using_command create" -l fail-fast -d "With several names, stop after the first failure"
+complete -c phantom -n "__phantom_using_command create" -l keep-on-failure -d "Keep a partially created worktree when a setup step fails"
+complete -c phantom -n "__phantom_using_command create" -l branch-prefix -d "Prefix for the new branch name, overriding the configured branchPrefix" -x
+complete -c phantom -n "__phantom_using_command create" -l no-branch-prefix -d "Do not apply the configured branchPrefix"
+complete -c phantom -n "__phantom_using_command create" -l stack-on -d "Stack on a phantom or branch and track it as upstream" -x -a "(__phantom_list_worktrees)"
+complete -c phantom -n "__phantom_using_command create" -l detach -d "Check out the base commit without creating a branch"
+complete -c phantom -n "__phantom_using_command create" -l no-checkout -d "Add the worktree without checking out any files"
+complete -c phantom -n "__phantom_using_command create" -l sparse -d "Check out only these directories (sparse checkout)" -r
+complete -c phantom -n "__phantom_using_command create" -l no-sparse -d "Check out every file, ignoring create.sparsePatterns"
+complete -c phantom -n "__phantom_using_command create" -l dry-run -d "Print what would run without creating anything"
 
 # attach command options
 complete -c phantom -n "__phantom_using_command attach" -l shell -d "Open an interactive shell in the worktree after attaching (-s)"
 complete -c phantom -n "__phantom_using_command attach" -l exec -d "Execute a command in the worktree after attaching (-x)" -x
+complete -c phantom -n "__phantom_using_command attach" -l fetch -d "Fetch the branch from the remote before attaching"
+complete -c phantom -n "__phantom_using_command attach" -l remote -d "Remote to fetch from (default: origin)" -x
+complete -c phantom -n "__phantom_using_command attach" -l no-copy -d "Do not copy the configured copyFiles"
+complete -c phantom -n "__phantom_using_command attach" -l source -d "Copy files from this phantom or directory" -r
+complete -c phantom -n "__phantom_using_command attach" -l no-track -d "Do not track the remote branch"
+complete -c phantom -n "__phantom_using_command attach" -l fzf -d "Pick the branch with fzf"
+complete -c phantom -n "__phantom_using_command attach" -l include-remote -d "Also list branches of a remote in fzf (default: origin)"
+complete -c phantom -n "__phantom_using_command attach" -l from-file -r -d "Attach every branch listed in a file (- for stdin)"
+complete -c phantom -n "__phantom_using_command attach" -l dry-run -d "Print what would run without attaching anything"
 
 # list command options
 complete -c phantom -n "__phantom_using_command list" -l fzf -d "Use fzf for interactive selection"
 complete -c phantom -n "__phantom_using_command list" -l names -d "Output only phantom names (for scripts and completion)"
+complete -c phantom -n "__phantom_using_command list" -l all -d "Also list the main and non-phantom worktrees"
+complete -c phantom -n "__phantom_using_command list" -l stacks -d "Show stacked phantoms as a tree"
+complete -c phantom -n "__phantom_using_command list" -l label -d "Only list worktrees with this label (repeatable)" -x
+complete -c phantom -n "__phantom_using_command list" -l label-any -d "Match any --label instead of all"
+complete -c phantom -n "__phantom_using_command list" -l no-status -d "Skip the dirty check for a faster listing"
+complete -c phantom -n "__phantom_using_command list" -l check-remote -d "Ask the remote which upstream branches were deleted"
+complete -c phantom -n "__phantom_using_command list" -l du -d "Show how much disk space each worktree takes"
 
 # where command options
 complete -c phantom -n "__phantom_using_command where" -l fzf -d "Use fzf for interactive selection"
+complete -c phantom -n "__phantom_using_command where" -l relative -d "Print the path relative to the current directory"
+complete -c phantom -n "__phantom_using_command where" -l json -d "Output in JSON format"
 complete -c phantom -n "__phantom_using_command where" -a "(__phantom_list_worktrees)"
 
 # delete command options
-complete -c phantom -n "__phantom_using_command delete" -l force -d "Force deletion even if worktree has uncommitted changes (-f)"
+complete -c phantom -n "__phantom_using_command delete" -l force -d "Force deletion even if worktree has uncommitted changes, is locked or its branch is unmerged (-f)"
+complete -c phantom -n "__phantom_using_command delete" -l force-dirty -d "Force deletion even if worktree has uncommitted changes"
+complete -c phantom -n "__phantom_using_command delete" -l force-locked -d "Force deletion even if worktree is locked"
+complete -c phantom -n "__phantom_using_command delete" -l keep-branch -d "Keep the worktree's branch"
+complete -c phantom -n "__phantom_using_command delete" -l snapshot -x -a "stash patch none" -d "Save uncommitted changes before a forced delete"
+complete -c phantom -n "__phantom_using_command delete" -l yes -d "Delete uncommitted changes without asking (-y)"
 complete -c phantom -n "__phantom_using_command delete" -l current -d "Delete the current worktree"
 complete -c phantom -n "__phantom_using_command delete" -l fzf -d "Use fzf for interactive selection"
+complete -c phantom -n "__phantom_using_command delete" -l prune -d "Delete every clean worktree whose branch is merged"
+complete -c phantom -n "__phantom_using_command delete" -l into -x -d "Branch that --prune checks against"
+complete -c phantom -n "__phantom_using_command delete" -l dry-run -d "Show what --prune would remove"
+complete -c phantom -n "__phantom_using_command delete" -l stdin-names -d "Delete the worktrees named on stdin"
+complete -c phantom -n "__phantom_using_command delete" -l print-root -d "Print the repository root on stdout"
 complete -c phantom -n "__phantom_using_command delete" -a "(__phantom_list_worktrees)"
 
 # exec command - accept worktree names and then any command
+complete -c phantom -n "__phantom_using_command exec" -l last -d "Use the worktree used last"
+complete -c phantom -n "__phantom_using_command exec" -l env -x -d "Set an environment variable (KEY=VALUE)"
+complete -c phantom -n "__phantom_using_command exec" -l cwd -r -d "Directory inside the worktree to run in"
+complete -c phantom -n "__phantom_using_command exec" -l no-focus -d "Keep focus on the current kitty window"
+complete -c phantom -n "__phantom_using_command exec" -l no-multiplexer -d "Run in the current terminal even if a default multiplexer is configured"
+complete -c phantom -n "__phantom_using_command exec" -l no-verify -d "Skip checking that the command exists"
 complete -c phantom -n "__phantom_using_command exec" -a "(__phantom_list_worktrees)"
 
 # shell command options
 complete -c phantom -n "__phantom_using_command shell" -l fzf -d "Use fzf for interactive selection"
+complete -c phantom -n "__phantom_using_command shell" -l last -d "Use the worktree used last"
+complete -c phantom -n "__phantom_using_command shell" -l tmux -d "Open in a new tmux window (-t)"
+complete -c phantom -n "__phantom_using_command shell" -l reuse -d "Switch to an existing tmux window for the worktree"
+complete -c phantom -n "__phantom_using_command shell" -l tmux-size -x -d "Size of the tmux pane as a percentage (1-99)"
+complete -c phantom -n "__phantom_using_command shell" -l tmux-target -x -d "tmux window or pane to split"
+complete -c phantom -n "__phantom_using_command shell" -l env -x -d "Set an environment variable (KEY=VALUE)"
+complete -c phantom -n "__phantom_using_command shell" -l cwd -r -d "Directory inside the worktree to start in"
+complete -c phantom -n "__phantom_using_command shell" -l no-focus -d "Keep focus on the current kitty window"
+complete -c phantom -n "__phantom_using_command shell" -l no-multiplexer -d "Run in the current terminal even if a default multiplexer is configured"
 complete -c phantom -n "__phantom_using_command shell" -a "(__phantom_list_worktrees)"
 
+# label command options
+complete -c phantom -n "__phantom_using_command label; and test (count (commandline -opc)) -eq 2" -a "(__phantom_list_worktrees)"
+complete -c phantom -n "__phantom_using_command label; and test (count (commandline -opc)) -eq 3" -a "add remove"
+
+# adopt command options
+complete -c phantom -n "__phantom_using_command adopt" -l in-place -d "Leave the worktree where it is instead of moving it"
+complete -c phantom -n "__phantom_using_command adopt" -l json -d "Output in JSON format"
+complete -c phantom -n "__phantom_using_command adopt" -a "(__fish_complete_directories)"
+
+# lock and unlock command options
+complete -c phantom -n "__phantom_using_command lock" -l reason -x -d "Why the worktree is locked"
+complete -c phantom -n "__phantom_using_command lock" -a "(__phantom_list_worktrees)"
+complete -c phantom -n "__phantom_using_command unlock" -a "(__phantom_list_worktrees)"
+
+# show command options
+complete -c phantom -n "__phantom_using_command show" -l files-changed -d "List files changed relative to the base branch plus uncommitted changes"
+complete -c phantom -n "__phantom_using_command show" -l json -d "Output in JSON format"
+complete -c phantom -n "__phantom_using_command show" -a "(__phantom_list_worktrees)"
+
+# status command options
+complete -c phantom -n "__phantom_using_command status" -l current -d "Summarize the current worktree"
+complete -c phantom -n "__phantom_using_command status" -l fzf -d "Use fzf for interactive selection"
+complete -c phantom -n "__phantom_using_command status" -l json -d "Output in JSON format"
+complete -c phantom -n "__phantom_using_command status" -a "(__phantom_list_worktrees)"
+
+# gc command options
+complete -c phantom -n "__phantom_using_command gc" -l apply -d "Remove what was found instead of only listing it"
+complete -c phantom -n "__phantom_using_command gc" -l json -d "Output in JSON format"
+
+# init command options
+complete -c phantom -n "__phantom_using_command init" -l format -xa "toml json" -d "Format of the configuration file to write"
+complete -c phantom -n "__phantom_using_command init" -l force -d "Overwrite an existing configuration file"
+complete -c phantom -n "__phantom_using_command init" -l interactive -d "Ask which files to copy, what to run after create and which multiplexer to use (-i)"
+
+# config command - subcommands
+complete -c phantom -n "__phantom_using_command config" -a "validate" -d "Validate the repository configuration file"
+complete -c phantom -n "__phantom_using_command config" -a "show" -d "Print the effective configuration and its sources"
+complete -c phantom -n "__phantom_using_command config" -a "path" -d "Print the configuration files that are read"
+
 # completion command - shell names
-complete -c phantom -n "__phantom_using_command completion" -a "fish zsh" -d "Shell type""#;
+complete -c phantom -n "__phantom_using_command completion" -a "fish zsh" -d "Shell type"
+complete -c phantom -n "__phantom_using_command shellenv" -a "bash fish zsh" -d "Shell type""#;
 
 const ZSH_COMPLETION: &str = r#"#compdef phantom
 # Zsh completion for phantom
@@ -97,8 +209,17 @@ _phantom() {
         'delete:Delete a Git worktree (phantom)'
         'exec:Execute a command in a worktree directory'
         'shell:Open an interactive shell in a worktree directory'
+        'label:Add or remove labels on a worktree'
+        'show:Show details of a worktree'
+        'status:Summarize the state of a worktree'
+        'gc:Find and remove directories and state left behind by deleted worktrees'
+        'init:Write a starter phantom.config.toml or phantom.config.json'
+        'adopt:Manage a worktree created outside phantom'
+        'lock:Lock a worktree so git will not prune or remove it'
+        'unlock:Unlock a locked worktree'
         'version:Display phantom version information'
         'completion:Generate shell completion scripts'
+        'shellenv:Print a shell function adding phantom cd'
     )
 
     _arguments -C \
@@ -120,47 +241,161 @@ _phantom() {
                         '--tmux[Open the worktree in a new tmux window (-t)]' \
                         '--tmux-vertical[Open the worktree in a vertical tmux pane]' \
                         '--tmux-horizontal[Open the worktree in a horizontal tmux pane]' \
-                        '*--copy-file[Copy specified files from the current worktree]:file:_files' \
-                        '1:name:'
+                        '--kitty[Open the worktree in a new kitty tab]' \
+                        '--kitty-vertical[Open the worktree in a vertical kitty split]' \
+                        '--kitty-horizontal[Open the worktree in a horizontal kitty split]' \
+                        '*--copy-files[Extra files to copy, added to the configured copyFiles]:file:_files' \
+                        '--copy-files-only[Copy only the files given with --copy-files]' \
+                        '--no-copy[Do not copy any files into the new worktree]' \
+                        '--source[Copy files from this phantom or directory]:source:_files -/' \
+                        '--fail-fast[With several names, stop after the first failure]' \
+                        '--keep-on-failure[Keep a partially created worktree when a setup step fails]' \
+                        '--branch-prefix[Prefix for the new branch name, overriding the configured branchPrefix]:prefix:' \
+                        '--no-branch-prefix[Do not apply the configured branchPrefix]' \
+                        '--stack-on[Stack on a phantom or branch and track it as upstream]:worktree:{compadd -- ${(f)"$(phantom __complete-worktrees 2>/dev/null)"}}' \
+                        '--detach[Check out the base commit without creating a branch]' \
+                        '--no-checkout[Add the worktree without checking out any files]' \
+                        '*--sparse[Check out only these directories (sparse checkout)]:directory:_directories' \
+                        '--no-sparse[Check out every file, ignoring create.sparsePatterns]' \
+                        '--dry-run[Print what would run without creating anything]' \
+                        '*:name:'
                     ;;
                 attach)
                     _arguments \
                         '--shell[Open an interactive shell in the worktree after attaching (-s)]' \
                         '--exec[Execute a command in the worktree after attaching (-x)]:command:' \
+                        '--fetch[Fetch the branch from the remote before attaching]' \
+                        '--remote[Remote to fetch from (default: origin)]:remote:' \
+                        '--no-copy[Do not copy the configured copyFiles]' \
+                        '--source[Copy files from this phantom or directory]:source:_files -/' \
+                        '--no-track[Do not track the remote branch]' \
+                        '--fzf[Pick the branch with fzf]' \
+                        '--include-remote=-[Also list branches of a remote in fzf (default: origin)]:remote:' \
+                        '--from-file[Attach every branch listed in a file (- for stdin)]:file:_files' \
+                        '--dry-run[Print what would run without attaching anything]' \
                         '1:worktree-name:' \
                         '2:branch-name:'
                     ;;
                 list)
                     _arguments \
                         '--fzf[Use fzf for interactive selection]' \
-                        '--names[Output only phantom names (for scripts and completion)]'
+                        '--names[Output only phantom names (for scripts and completion)]' \
+                        '--all[Also list the main and non-phantom worktrees]' \
+                        '--stacks[Show stacked phantoms as a tree]' \
+                        '*--label[Only list worktrees with this label]:label:' \
+                        '--label-any[Match any --label instead of all]' \
+                        '--no-status[Skip the dirty check for a faster listing]' \
+                        '--check-remote[Ask the remote which upstream branches were deleted]' \
+                        '--du[Show how much disk space each worktree takes]'
+                    ;;
+                label)
+                    local worktrees
+                    worktrees=(${(f)"$(phantom __complete-worktrees 2>/dev/null)"})
+                    _arguments \
+                        '1:worktree:(${(q)worktrees[@]})' \
+                        '2:action:(add remove)' \
+                        '3:label:'
+                    ;;
+                adopt)
+                    _arguments \
+                        '--in-place[Leave the worktree where it is instead of moving it]' \
+                        '--json[Output in JSON format]' \
+                        '1:path:_directories' \
+                        '2:name:'
+                    ;;
+                lock|unlock)
+                    local worktrees
+                    worktrees=(${(f)"$(phantom __complete-worktrees 2>/dev/null)"})
+                    if [[ ${line[1]} == "lock" ]]; then
+                        _arguments \
+                            '--reason[Why the worktree is locked]:reason:' \
+                            '1:worktree:(${(q)worktrees[@]})'
+                    else
+                        _arguments \
+                            '1:worktree:(${(q)worktrees[@]})'
+                    fi
                     ;;
-                where|delete|shell)
+                where|delete|shell|show|status)
                     local worktrees
-                    worktrees=(${(f)"$(phantom list --names 2>/dev/null)"})
-                    if [[ ${line[1]} == "where" || ${line[1]} == "shell" ]]; then
+                    worktrees=(${(f)"$(phantom __complete-worktrees 2>/dev/null)"})
+                    if [[ ${line[1]} == "where" ]]; then
                         _arguments \
                             '--fzf[Use fzf for interactive selection]' \
+                            '--relative[Print the path relative to the current directory]' \
+                            '--json[Output in JSON format]' \
+                            '*:worktree:(${(q)worktrees[@]})'
+                    elif [[ ${line[1]} == "shell" ]]; then
+                        _arguments \
+                            '--fzf[Use fzf for interactive selection]' \
+                            '--last[Use the worktree used last]' \
+                            '--tmux[Open in a new tmux window (-t)]' \
+                            '--reuse[Switch to an existing tmux window for the worktree]' \
+                            '--tmux-size[Size of the tmux pane as a percentage (1-99)]:percent:' \
+                            '--tmux-target[tmux window or pane to split]:target:' \
+                            '*--env[Set an environment variable]:KEY=VALUE:' \
+                            '--cwd[Directory inside the worktree to start in]:path:' \
+                            '--no-focus[Keep focus on the current kitty window]' \
+                            '--no-multiplexer[Run in the current terminal even if a default multiplexer is configured]' \
+                            '1:worktree:(${(q)worktrees[@]})'
+                    elif [[ ${line[1]} == "show" ]]; then
+                        _arguments \
+                            '--files-changed[List files changed relative to the base branch plus uncommitted changes]' \
+                            '--json[Output in JSON format]' \
+                            '1:worktree:(${(q)worktrees[@]})'
+                    elif [[ ${line[1]} == "status" ]]; then
+                        _arguments \
+                            '--current[Summarize the current worktree]' \
+                            '--fzf[Use fzf for interactive selection]' \
+                            '--json[Output in JSON format]' \
                             '1:worktree:(${(q)worktrees[@]})'
                     elif [[ ${line[1]} == "delete" ]]; then
                         _arguments \
-                            '--force[Force deletion even if worktree has uncommitted changes (-f)]' \
+                            '--force[Force deletion even if worktree has uncommitted changes, is locked or its branch is unmerged (-f)]' \
+                            '--force-dirty[Force deletion even if worktree has uncommitted changes]' \
+                            '--force-locked[Force deletion even if worktree is locked]' \
+                            "--keep-branch[Keep the worktree's branch]" \
+                            '--snapshot[Save uncommitted changes before a forced delete]:mode:(stash patch none)' \
+                            '--yes[Delete uncommitted changes without asking (-y)]' \
                             '--current[Delete the current worktree]' \
                             '--fzf[Use fzf for interactive selection]' \
+                            '--prune[Delete every clean worktree whose branch is merged]' \
+                            '--into[Branch that --prune checks against]:ref:' \
+                            '--dry-run[Show what --prune would remove]' \
+                            '--stdin-names[Delete the worktrees named on stdin]' \
+                            '--print-root[Print the repository root on stdout]' \
                             '1:worktree:(${(q)worktrees[@]})'
                     fi
                     ;;
                 exec)
                     local worktrees
-                    worktrees=(${(f)"$(phantom list --names 2>/dev/null)"})
+                    worktrees=(${(f)"$(phantom __complete-worktrees 2>/dev/null)"})
                     _arguments \
                         '1:worktree:(${(q)worktrees[@]})' \
                         '*:command:_command_names'
                     ;;
+                gc)
+                    _arguments \
+                        '--apply[Remove what was found instead of only listing it]' \
+                        '--json[Output in JSON format]'
+                    ;;
+                init)
+                    _arguments \
+                        '--format=[Format of the configuration file to write]:format:(toml json)' \
+                        '--force[Overwrite an existing configuration file]' \
+                        '--interactive[Ask which files to copy, what to run after create and which multiplexer to use (-i)]'
+                    ;;
+                config)
+                    _arguments \
+                        '1:subcommand:(validate show path)'
+                    ;;
                 completion)
                     _arguments \
                         '1:shell:(fish zsh)'
                     ;;
+                shellenv)
+                    _arguments \
+                        '1:shell:(bash fish zsh)'
+                    ;;
             esac
             ;;
     esac
@@ -180,7 +415,7 @@ _phantom_completions() {
     local cur prev words cword
     _init_completion || return
 
-    local commands="create attach list where delete exec shell version completion"
+    local commands="create attach list where delete exec shell label show status config gc init adopt lock unlock version completion shellenv"
 
     # Handle main command completion
     if [[ $cword -eq 1 ]]; then
@@ -194,16 +429,21 @@ _phantom_completions() {
     case "$cmd" in
         create)
             case "$prev" in
-                --exec|-x)
-                    # No completion for exec command
+                --exec|-x|--branch-prefix)
+                    # No completion for exec command or branch prefix
                     return
                     ;;
-                --copy-file)
+                --copy-files)
                     _filedir
                     return
                     ;;
+                --stack-on)
+                    local worktrees=$(phantom __complete-worktrees 2>/dev/null)
+                    COMPREPLY=($(compgen -W "$worktrees" -- "$cur"))
+                    return
+                    ;;
                 *)
-                    local opts="--shell -s --exec -x --tmux -t --tmux-vertical --tmux-horizontal --copy-file"
+                    local opts="--shell -s --exec -x --tmux -t --tmux-vertical --tmux-horizontal --kitty --kitty-vertical --kitty-horizontal --copy-files --copy-files-only --no-copy --source --fail-fast --keep-on-failure --branch-prefix --no-branch-prefix --stack-on --detach --no-checkout --sparse --no-sparse --dry-run"
                     if [[ "$cur" == -* ]]; then
                         COMPREPLY=($(compgen -W "$opts" -- "$cur"))
                     fi
@@ -212,12 +452,16 @@ _phantom_completions() {
             ;;
         attach)
             case "$prev" in
-                --exec|-x)
-                    # No completion for exec command
+                --exec|-x|--remote)
+                    # No completion for exec command or remote name
+                    return
+                    ;;
+                --from-file)
+                    COMPREPLY=($(compgen -f -- "$cur"))
                     return
                     ;;
                 *)
-                    local opts="--shell -s --exec -x"
+                    local opts="--shell -s --exec -x --fetch --remote --no-copy --source --no-track --fzf --include-remote --from-file --dry-run"
                     if [[ "$cur" == -* ]]; then
                         COMPREPLY=($(compgen -W "$opts" -- "$cur"))
                     fi
@@ -225,42 +469,114 @@ _phantom_completions() {
             esac
             ;;
         list)
-            local opts="--fzf --names"
+            if [[ "$prev" == "--label" ]]; then
+                return
+            fi
+            local opts="--fzf --names --all --stacks --label --label-any --no-status --check-remote --du"
             if [[ "$cur" == -* ]]; then
                 COMPREPLY=($(compgen -W "$opts" -- "$cur"))
             fi
             ;;
-        where|shell)
-            local opts="--fzf"
+        where)
+            local opts="--fzf --relative --json"
             if [[ "$cur" == -* ]]; then
                 COMPREPLY=($(compgen -W "$opts" -- "$cur"))
             else
                 # Complete with worktree names
-                local worktrees=$(phantom list --names 2>/dev/null)
+                local worktrees=$(phantom __complete-worktrees 2>/dev/null)
+                COMPREPLY=($(compgen -W "$worktrees" -- "$cur"))
+            fi
+            ;;
+        shell)
+            local opts="--fzf --last --tmux -t --reuse --tmux-size --tmux-target --env --cwd --no-focus --no-multiplexer"
+            if [[ "$cur" == -* ]]; then
+                COMPREPLY=($(compgen -W "$opts" -- "$cur"))
+            else
+                # Complete with worktree names
+                local worktrees=$(phantom __complete-worktrees 2>/dev/null)
+                COMPREPLY=($(compgen -W "$worktrees" -- "$cur"))
+            fi
+            ;;
+        label)
+            if [[ $cword -eq 2 ]]; then
+                local worktrees=$(phantom __complete-worktrees 2>/dev/null)
+                COMPREPLY=($(compgen -W "$worktrees" -- "$cur"))
+            elif [[ $cword -eq 3 ]]; then
+                COMPREPLY=($(compgen -W "add remove" -- "$cur"))
+            fi
+            ;;
+        adopt)
+            if [[ "$cur" == -* ]]; then
+                COMPREPLY=($(compgen -W "--in-place --json" -- "$cur"))
+            else
+                COMPREPLY=($(compgen -d -- "$cur"))
+            fi
+            ;;
+        lock|unlock)
+            if [[ "$prev" == "--reason" ]]; then
+                return
+            elif [[ "$cmd" == "lock" && "$cur" == -* ]]; then
+                COMPREPLY=($(compgen -W "--reason" -- "$cur"))
+            else
+                # Complete with worktree names
+                local worktrees=$(phantom __complete-worktrees 2>/dev/null)
+                COMPREPLY=($(compgen -W "$worktrees" -- "$cur"))
+            fi
+            ;;
+        show)
+            local opts="--files-changed --json"
+            if [[ "$cur" == -* ]]; then
+                COMPREPLY=($(compgen -W "$opts" -- "$cur"))
+            else
+                # Complete with worktree names
+                local worktrees=$(phantom __complete-worktrees 2>/dev/null)
+                COMPREPLY=($(compgen -W "$worktrees" -- "$cur"))
+            fi
+            ;;
+        status)
+            local opts="--current --fzf --json"
+            if [[ "$cur" == -* ]]; then
+                COMPREPLY=($(compgen -W "$opts" -- "$cur"))
+            else
+                # Complete with worktree names
+                local worktrees=$(phantom __complete-worktrees 2>/dev/null)
                 COMPREPLY=($(compgen -W "$worktrees" -- "$cur"))
             fi
             ;;
         delete)
-            local opts="--force -f --current --fzf"
+            local opts="--force -f --force-dirty --force-locked --keep-branch --snapshot --yes -y --current --fzf --prune --into --dry-run --stdin-names --print-root"
             if [[ "$cur" == -* ]]; then
                 COMPREPLY=($(compgen -W "$opts" -- "$cur"))
             else
                 # Complete with worktree names
-                local worktrees=$(phantom list --names 2>/dev/null)
+                local worktrees=$(phantom __complete-worktrees 2>/dev/null)
                 COMPREPLY=($(compgen -W "$worktrees" -- "$cur"))
             fi
             ;;
         exec)
             if [[ $cword -eq 2 ]]; then
                 # Complete with worktree names
-                local worktrees=$(phantom list --names 2>/dev/null)
+                local worktrees=$(phantom __complete-worktrees 2>/dev/null)
                 COMPREPLY=($(compgen -W "$worktrees" -- "$cur"))
             else
                 # Complete with commands
                 COMPREPLY=($(compgen -c -- "$cur"))
             fi
             ;;
-        completion)
+        gc)
+            COMPREPLY=($(compgen -W "--apply --json" -- "$cur"))
+            ;;
+        init)
+            if [[ "$prev" == "--format" ]]; then
+                COMPREPLY=($(compgen -W "toml json" -- "$cur"))
+            else
+                COMPREPLY=($(compgen -W "--format --force --interactive" -- "$cur"))
+            fi
+            ;;
+        config)
+            COMPREPLY=($(compgen -W "validate show path" -- "$cur"))
+            ;;
+        completion|shellenv)
             COMPREPLY=($(compgen -W "bash fish zsh" -- "$cur"))
             ;;
         *)
@@ -281,3 +597,18 @@ pub fn handle(args: CompletionArgs) -> Result<()> {
     println!("{script}");
     Ok(())
 }
+
+/// Print worktree names one per line for completion scripts
+///
+/// Prints nothing outside a repository so completion never fails. `repository`
+/// is the directory given with `--repo`.
+pub async fn complete_worktrees(filesystem: &dyn FileSystem, repository: Option<&std::path::Path>) {
+    let start =
+        repository.map(std::path::Path::to_path_buf).or_else(|| std::env::current_dir().ok());
+    let Some(git_root) = start.and_then(|start| discover_git_root(&start)) else {
+        return;
+    };
+    for name in worktree_names_from_directory(filesystem, &git_root).await {
+        println!("{name}");
+    }
+}
@@ -0,0 +1,314 @@
+use crate::cli::commands::config::{ConfigArgs, ConfigCommand};
+use crate::cli::context::HandlerContext;
+use crate::cli::output::output;
+use crate::config::loader::{config_file_paths, LoadedConfig};
+use crate::core::command_executor::CommandExecutor;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use crate::worktree::glob::{expand_glob, is_glob_pattern};
+use crate::worktree::locate::resolve_copy_source;
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Handle the config command
+pub async fn handle<E, F, H>(args: ConfigArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    match args.command {
+        ConfigCommand::Validate => validate(context).await,
+        ConfigCommand::Show => show(context).await,
+        ConfigCommand::Path => path(context).await,
+    }
+}
+
+/// Load the merged configuration, failing if there is none
+async fn load<E, F, H>(context: &HandlerContext<E, F, H>) -> Result<(PathBuf, LoadedConfig)>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let Some(loaded) = context
+        .config(&git_root)
+        .await
+        .with_context(|| format!("Configuration is invalid in {}", git_root.display()))?
+    else {
+        bail!("No configuration file found in {}", git_root.display());
+    };
+    Ok((git_root, loaded.clone()))
+}
+
+/// Load the configuration, expanding `${VAR}` references, and report what looks wrong
+///
+/// Parse, type and validation errors fail the command. Deprecated and unknown
+/// keys and copyFiles entries missing from the main worktree are only warnings.
+async fn validate<E, F, H>(context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let (git_root, loaded) = load(&context).await?;
+
+    if !loaded.deprecations.is_empty() {
+        output().log(&format!("Deprecated keys ({}):", loaded.deprecations.len()));
+        for deprecation in &loaded.deprecations {
+            output().log(&format!("  - {deprecation}"));
+        }
+    }
+    if !loaded.unknown_keys.is_empty() {
+        output().log(&format!("Unknown keys ({}):", loaded.unknown_keys.len()));
+        for unknown in &loaded.unknown_keys {
+            output().log(&format!("  - {unknown}"));
+        }
+    }
+
+    // copyFiles are copied from the main worktree, wherever phantom runs
+    let worktree =
+        resolve_copy_source(context.executor.clone(), &git_root, None, &context.filesystem).await?;
+    let copy_files = loaded.config.post_create.as_ref().and_then(|pc| pc.copy_files.as_deref());
+    for entry in missing_copy_files(&worktree, copy_files.unwrap_or_default()).await {
+        output().warn(&format!(
+            "postCreate.copyFiles: '{entry}' does not exist in {}",
+            worktree.display()
+        ));
+    }
+
+    output().success(&format!(
+        "OK (loaded from {})",
+        relative_files(&git_root, &loaded.files).join(", ")
+    ));
+    Ok(())
+}
+
+/// copyFiles entries with nothing to copy in `worktree`: missing paths and globs matching nothing
+async fn missing_copy_files(worktree: &Path, entries: &[String]) -> Vec<String> {
+    let mut missing = Vec::new();
+    for entry in entries {
+        let exists = if is_glob_pattern(entry) {
+            expand_glob(worktree, entry).await.is_ok_and(|matches| !matches.is_empty())
+        } else {
+            tokio::fs::symlink_metadata(worktree.join(entry)).await.is_ok()
+        };
+        if !exists {
+            missing.push(entry.clone());
+        }
+    }
+    missing
+}
+
+/// Print the configuration files of the repository, lowest precedence first
+async fn path<E, F, H>(context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let paths = config_file_paths(&git_root).await;
+    if paths.is_empty() {
+        output().log("none");
+    }
+    for path in paths {
+        output().log(&path.display().to_string());
+    }
+    Ok(())
+}
+
+/// Print the effective configuration, one top-level key per line
+async fn show<E, F, H>(context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let (git_root, loaded) = load(&context).await?;
+    for line in describe_config(&git_root, &loaded)? {
+        output().log(&line);
+    }
+    Ok(())
+}
+
+/// `key: value  # files` lines for each key set in the merged configuration
+fn describe_config(git_root: &Path, loaded: &LoadedConfig) -> Result<Vec<String>> {
+    let value = serde_json::to_value(&loaded.config)
+        .with_context(|| "Failed to serialize the configuration")?;
+    let Value::Object(map) = value else {
+        return Ok(Vec::new());
+    };
+
+    let mut lines = Vec::with_capacity(map.len());
+    for (key, value) in map {
+        let files = loaded.sources.get(&key).map(Vec::as_slice).unwrap_or_default();
+        lines.push(format!("{key}: {value}  # {}", relative_files(git_root, files).join(", ")));
+    }
+    Ok(lines)
+}
+
+/// File paths relative to the repository root
+fn relative_files(git_root: &Path, files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .map(|file| file.strip_prefix(git_root).unwrap_or(file).display().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::loader::load_merged_config;
+    use crate::core::environment::MockEnvironment;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::exit_handler::MockExitHandler;
+    use crate::core::filesystems::MockFileSystem;
+    use tempfile::TempDir;
+
+    fn context_for(
+        dir: &TempDir,
+    ) -> HandlerContext<MockCommandExecutor, MockFileSystem, MockExitHandler> {
+        let mut mock = MockCommandExecutor::new();
+        let git_dir = dir.path().join(".git");
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            &git_dir.to_string_lossy(),
+            "",
+            0,
+        );
+        HandlerContext::new(mock, MockFileSystem::new(), MockExitHandler::new())
+    }
+
+    /// The error `config validate` fails with for `contents` in phantom.config.json
+    async fn validate_error(contents: &str) -> String {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("phantom.config.json"), contents).unwrap();
+
+        let args = ConfigArgs { command: ConfigCommand::Validate };
+        format!("{:#}", handle(args, context_for(&dir)).await.unwrap_err())
+    }
+
+    #[tokio::test]
+    async fn test_validate_reports_invalid_multiplexer() {
+        let error = validate_error(r#"{"defaultMultiplexer": "screen"}"#).await;
+        assert!(error.starts_with("Configuration is invalid"), "{error}");
+        assert!(error.contains("phantom.config.json at 'defaultMultiplexer'"), "{error}");
+        assert!(error.contains("unknown variant `screen`"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_validate_reports_bad_types_with_their_key() {
+        let error = validate_error(r#"{"postCreate": {"copyFiles": ".env"}}"#).await;
+        assert!(error.contains("at 'postCreate.copyFiles'"), "{error}");
+        assert!(error.contains("expected a sequence"), "{error}");
+
+        let error = validate_error("{\n  \"branchPrefix\": \"me/\",\n}").await;
+        assert!(error.contains("phantom.config.json: trailing comma at line 3"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_validate_warns_about_missing_copy_files() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("phantom.config.json"),
+            r#"{"postCreate": {"copyFiles": [".env", "config/*.yml", "missing.txt", "*.nope"]}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join(".env"), "").unwrap();
+        std::fs::create_dir(dir.path().join("config")).unwrap();
+        std::fs::write(dir.path().join("config/app.yml"), "").unwrap();
+
+        let entries: Vec<String> = [".env", "config/*.yml", "missing.txt", "*.nope"]
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect();
+        assert_eq!(missing_copy_files(dir.path(), &entries).await, ["missing.txt", "*.nope"]);
+
+        // Missing sources are warnings, not errors
+        let args = ConfigArgs { command: ConfigCommand::Validate };
+        handle(args, context_for(&dir)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_config_file_paths() {
+        let dir = TempDir::new().unwrap();
+        assert!(config_file_paths(dir.path()).await.is_empty());
+        let args = ConfigArgs { command: ConfigCommand::Path };
+        handle(args, context_for(&dir)).await.unwrap();
+
+        std::fs::write(dir.path().join("phantom.config.toml"), "").unwrap();
+        std::fs::write(dir.path().join("phantom.config.json"), "{}").unwrap();
+        std::fs::write(dir.path().join(".phantom.toml"), "").unwrap();
+        assert_eq!(
+            config_file_paths(dir.path()).await,
+            [dir.path().join(".phantom.toml"), dir.path().join("phantom.config.json")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_with_deprecated_keys() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("phantom.config.json"), r#"{"copyFiles": [".env"]}"#)
+            .unwrap();
+
+        let args = ConfigArgs { command: ConfigCommand::Validate };
+        assert!(handle(args, context_for(&dir)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_strict_mode_fails() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("phantom.config.json"),
+            r#"{"strict": true, "copyFiles": [".env"]}"#,
+        )
+        .unwrap();
+
+        let args = ConfigArgs { command: ConfigCommand::Validate };
+        let error = handle(args, context_for(&dir)).await.unwrap_err();
+        assert!(format!("{error:#}").contains("strict mode"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_without_config() {
+        let dir = TempDir::new().unwrap();
+
+        let args = ConfigArgs { command: ConfigCommand::Validate };
+        let error = handle(args, context_for(&dir)).await.unwrap_err();
+        assert!(error.to_string().contains("No configuration file found"));
+    }
+
+    #[tokio::test]
+    async fn test_show_annotates_sources() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(".phantom.toml"),
+            "defaultMultiplexer = \"tmux\"\n[postCreate]\ncommands = [\"make\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("phantom.config.json"),
+            r#"{"postCreate": {"copyFiles": [".env"]}}"#,
+        )
+        .unwrap();
+
+        let loaded =
+            load_merged_config(dir.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+        assert_eq!(
+            describe_config(dir.path(), &loaded).unwrap(),
+            vec![
+                r#"defaultMultiplexer: "tmux"  # .phantom.toml"#,
+                r#"postCreate: {"commands":["make"],"copyFiles":[".env"]}  # .phantom.toml, phantom.config.json"#,
+            ]
+        );
+
+        let args = ConfigArgs { command: ConfigCommand::Show };
+        assert!(handle(args, context_for(&dir)).await.is_ok());
+    }
+}
@@ -1,18 +1,74 @@
-use crate::cli::commands::create::{CreateArgs, CreateResult};
+use crate::api::Phantom;
+use crate::cli::commands::create::{CreateArgs, CreateBatchResult, CreateResult};
 use crate::cli::context::HandlerContext;
-use crate::cli::output::output;
-use crate::config::loader::load_config;
+use crate::cli::dry_run::{print_plans, DryRunPlan};
+use crate::cli::handlers::workspace;
+use crate::cli::output::{output, timings_ms};
+use crate::cli::progress::copy_progress;
+use crate::config::loader::LoadedConfig;
 use crate::core::command_executor::CommandExecutor;
+use crate::core::executors::DryRunExecutor;
 use crate::core::exit_handler::ExitHandler;
 use crate::core::filesystem::FileSystem;
-use crate::git::libs::get_git_root::get_git_root;
+use crate::core::utils::timed;
+use crate::git::libs::add_worktree::{
+    add_detached_worktree, add_worktree, add_worktree_without_checkout,
+};
+use crate::git::libs::branch_exists::branch_exists;
+use crate::git::libs::is_head_unborn::is_head_unborn;
+use crate::git::libs::sparse_checkout::{ensure_sparse_checkout_supported, init_sparse_checkout};
 use crate::process::exec::exec_in_dir;
-use crate::process::multiplexer::{execute_in_multiplexer, MultiplexerOptions, SplitDirection};
-use crate::process::shell::shell_in_dir;
-use crate::worktree::create::create_worktree;
+use crate::process::kitty::is_inside_kitty;
+use crate::process::multiplexer::{
+    execute_with_multiplexer, Multiplexer, MultiplexerOptions, SplitDirection,
+};
+use crate::process::shell::{get_phantom_env, shell_in_dir, ShellType};
+use crate::process::tmux::is_inside_tmux;
+use crate::worktree::branch_prefix::resolve_branch_prefix;
+use crate::worktree::concurrent::run_bounded;
+use crate::worktree::hooks::{run_hook, Hook};
+use crate::worktree::last_used::record_last_worktree;
+use crate::worktree::locate::resolve_copy_source;
 use crate::worktree::paths::get_worktree_path;
-use crate::worktree::types::CreateWorktreeOptions;
-use anyhow::{Context, Result};
+use crate::worktree::setup::{copy_editor_dirs, editor_dir_patterns, setup_scratch_dir};
+use crate::worktree::stack::{record_stack_parent, resolve_stack_base, set_upstream, StackBase};
+use crate::worktree::types::{CopyFilesSelection, CreateWorktreeOptions, CreateWorktreeSuccess};
+use crate::worktree::validate::{validate_worktree_does_not_exist, validate_worktree_name};
+use crate::PhantomError;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Mutex;
+
+/// Most worktrees created at once by `phantom create <name> <name>...`
+const MAX_CONCURRENT_CREATES: usize = 4;
+
+/// Settings resolved once and shared by every worktree a create command makes
+struct CreatePlan {
+    git_root: PathBuf,
+    /// Directory files are copied from: --source, otherwise the main worktree
+    copy_source: PathBuf,
+    copy_files: Option<Vec<String>>,
+    editor_dirs: Vec<String>,
+    scratch_dir: Option<String>,
+    stack_base: Option<StackBase>,
+    hooks_enabled: bool,
+    /// Expanded branch prefix; empty when none applies
+    branch_prefix: String,
+    /// Directories of a sparse checkout; `None` checks out everything, or nothing with --no-checkout
+    sparse_patterns: Option<Vec<String>>,
+}
+
+/// A created worktree with the outcome of its optional setup steps
+struct Created {
+    path: PathBuf,
+    result: CreateWorktreeSuccess,
+    editor_files: Option<Vec<String>>,
+    scratch: Option<String>,
+    /// Setup problems that did not stop the worktree from being created
+    warnings: Vec<String>,
+}
 
 /// Handle the create command
 pub async fn handle<E, F, H>(args: CreateArgs, context: HandlerContext<E, F, H>) -> Result<()>
@@ -21,137 +77,156 @@ where
     F: FileSystem + Clone + 'static,
     H: ExitHandler + Clone + 'static,
 {
+    if args.workspace {
+        return workspace::create(args, context).await;
+    }
+    if args.names.len() > 1 {
+        validate_batch_args(&args)?;
+    }
+
+    // Check the multiplexer before creating anything, so a bad flag leaves nothing behind
+    let multiplexer = requested_multiplexer(&args);
+    match multiplexer {
+        Some((Multiplexer::Tmux, _)) if !is_inside_tmux(&*context.environment).await => {
+            bail!("The --tmux option can only be used inside a tmux session");
+        }
+        Some((Multiplexer::Kitty, _)) if !is_inside_kitty(&*context.environment).await => {
+            bail!("The --kitty option can only be used inside a kitty terminal");
+        }
+        _ => {}
+    }
+
     // Get git root
-    let git_root = match get_git_root(context.executor.clone())
+    let git_root = match timed("git_root", context.git_root())
         .await
         .with_context(|| "Failed to determine git repository root")
     {
         Ok(root) => root,
         Err(e) => {
-            if args.json {
+            if args.json && args.names.len() == 1 {
+                let name = args.names[0].clone();
                 let result = CreateResult {
                     success: false,
-                    name: args.name.clone(),
-                    branch: args.branch.clone().unwrap_or_else(|| args.name.clone()),
+                    branch: if args.detach {
+                        String::new()
+                    } else {
+                        args.branch.clone().unwrap_or_else(|| name.clone())
+                    },
+                    detached: args.detach,
+                    name,
                     path: String::new(),
                     copied_files: None,
+                    editor_files: None,
+                    scratch_dir: None,
+                    stack_parent: None,
                     error: Some(e.to_string()),
+                    timings_ms: timings_ms(),
                 };
-                output().json(&result)?;
-                return Err(e);
-            } else {
-                return Err(e);
+                output().log(
+                    &serde_json::to_string_pretty(&result)
+                        .with_context(|| "Failed to serialize JSON output")?,
+                );
             }
+            return Err(e);
         }
     };
 
-    // Load config for copy files
-    let config = load_config(&git_root)
-        .await
-        .with_context(|| format!("Failed to load config from git root: {}", git_root.display()))
-        .ok()
-        .flatten();
-    let copy_files = if let Some(files) = args.copy_files {
-        Some(files)
-    } else {
-        config.and_then(|cfg| cfg.post_create.and_then(|pc| pc.copy_files))
-    };
+    for warning in context.git_config(&git_root).await.warnings() {
+        output().warn(&warning);
+    }
 
-    // Create the worktree
-    let branch_name = args.branch.clone().unwrap_or_else(|| args.name.clone());
-    let options = CreateWorktreeOptions {
-        branch: Some(branch_name.clone()),
-        commitish: args.base.clone(),
-        copy_files: copy_files.clone(),
-    };
+    let plan = load_plan(&args, git_root, &context).await?;
+    if args.dry_run {
+        return print_plans(&plan_dry_run(&args, &plan, &context).await?, "create", args.json);
+    }
+    if args.names.len() > 1 {
+        return create_many(args, plan, context).await;
+    }
+    let name = args.names[0].clone();
 
-    let result = match create_worktree(context.executor.clone(), &git_root, &args.name, options)
-        .await
-        .with_context(|| {
-            format!("Failed to create worktree '{}' with branch '{}'", args.name, branch_name)
+    // Create the worktree
+    let branch_name = branch_for(&name, &args, &plan);
+    let created =
+        match create_one(&name, &branch_name, &args, &plan, &context).await.with_context(|| {
+            match args.detach {
+                true => format!("Failed to create worktree '{name}'"),
+                false => format!("Failed to create worktree '{name}' with branch '{branch_name}'"),
+            }
         }) {
-        Ok(success) => success,
-        Err(e) => {
-            if args.json {
-                let result = CreateResult {
-                    success: false,
-                    name: args.name.clone(),
-                    branch: branch_name,
-                    path: String::new(),
-                    copied_files: None,
-                    error: Some(e.to_string()),
-                };
-                output().json(&result).with_context(|| "Failed to serialize JSON output")?;
+            Ok(created) => created,
+            Err(e) => {
+                if args.json {
+                    let result = CreateResult {
+                        success: false,
+                        name: name.clone(),
+                        branch: branch_name,
+                        detached: args.detach,
+                        path: String::new(),
+                        copied_files: None,
+                        editor_files: None,
+                        scratch_dir: None,
+                        stack_parent: None,
+                        error: Some(e.to_string()),
+                        timings_ms: timings_ms(),
+                    };
+                    output().log(
+                        &serde_json::to_string_pretty(&result)
+                            .with_context(|| "Failed to serialize JSON output")?,
+                    );
+                }
+                return Err(e);
             }
-            return Err(e);
-        }
-    };
+        };
+    finish_setup(&name, &branch_name, &created.path, &args, &plan, &context, &Mutex::new(()))
+        .await?;
+    record_last_worktree(&context.filesystem, &plan.git_root, &name).await;
 
-    let worktree_path = get_worktree_path(&git_root, &args.name);
+    for warning in &created.warnings {
+        output().warn(warning);
+    }
 
     // Output result
     if args.json {
-        let json_result = CreateResult {
-            success: true,
-            name: args.name.clone(),
-            branch: branch_name.clone(),
-            path: worktree_path.to_string_lossy().to_string(),
-            copied_files: result.copied_files.clone(),
-            error: None,
+        let result = CreateResult {
+            timings_ms: timings_ms(),
+            ..created_result(&name, &branch_name, &created, &plan)
         };
-        output().json(&json_result).with_context(|| "Failed to serialize JSON output")?;
+        output().log(
+            &serde_json::to_string_pretty(&result)
+                .with_context(|| "Failed to serialize JSON output")?,
+        );
     } else {
-        output()
-            .success(&format!("Created worktree '{}' with branch '{}'", args.name, branch_name));
-        if let Some(copied) = &result.copied_files {
-            if !copied.is_empty() {
-                output().log(&format!("Copied {} files", copied.len()));
-            }
+        output().success(&created_message(&name, &branch_name));
+        if let Some(base) = &plan.stack_base {
+            output().log(&format!("Stacked on '{}' (upstream set)", base.branch));
+        }
+        let result = &created.result;
+        report_copied_files(
+            result.copied_files.as_deref(),
+            result.skipped_files.as_deref(),
+            result.expanded_from.as_ref(),
+            None,
+        );
+        if let Some(files) = &created.editor_files {
+            output().log(&format!("Copied {} editor settings files", files.len()));
+        }
+        if let Some(dir) = &created.scratch {
+            output().log(&format!("Scratch directory: {dir} (excluded from git status)"));
         }
     }
 
-    // Handle post-creation actions
-    if args.tmux
-        || args.tmux_vertical
-        || args.tmux_v
-        || args.tmux_horizontal
-        || args.tmux_h
-        || args.kitty
-        || args.kitty_vertical
-        || args.kitty_v
-        || args.kitty_horizontal
-        || args.kitty_h
-    {
-        // Determine split direction
-        let direction = if args.tmux_vertical || args.tmux_v || args.kitty_vertical || args.kitty_v
-        {
-            SplitDirection::Vertical
-        } else if args.tmux_horizontal || args.tmux_h || args.kitty_horizontal || args.kitty_h {
-            SplitDirection::Horizontal
-        } else {
-            SplitDirection::New
-        };
+    let worktree_path = created.path;
 
-        // Get shell command
-        let shell_cmd = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
-
-        let options = MultiplexerOptions {
-            direction,
-            command: shell_cmd,
-            args: None,
-            cwd: Some(worktree_path.to_string_lossy().to_string()),
-            env: None,
-            window_name: Some(args.name.clone()),
-        };
-
-        execute_in_multiplexer(context.executor.clone(), options)
+    // Handle post-creation actions
+    if let Some((multiplexer, direction)) = multiplexer {
+        open_in_multiplexer(&name, &worktree_path, multiplexer, direction, &context)
             .await
-            .with_context(|| format!("Failed to open multiplexer for worktree '{}'", args.name))?;
+            .with_context(|| format!("Failed to open multiplexer for worktree '{name}'"))?;
     } else if args.shell {
         // Open shell in the new worktree
-        shell_in_dir(&context.executor, &worktree_path).await.with_context(|| {
-            format!("Failed to open shell in worktree path: {}", worktree_path.display())
-        })?;
+        shell_in_dir(&context.executor, &*context.environment, &worktree_path).await.with_context(
+            || format!("Failed to open shell in worktree path: {}", worktree_path.display()),
+        )?;
     } else if let Some(exec_cmd) = args.exec {
         // Execute command in the new worktree
         exec_in_dir(&worktree_path, &exec_cmd, &[]).await.with_context(|| {
@@ -166,10 +241,657 @@ where
     Ok(())
 }
 
+/// The multiplexer and split asked for by the --tmux and --kitty flags; tmux wins if both are given
+fn requested_multiplexer(args: &CreateArgs) -> Option<(Multiplexer, SplitDirection)> {
+    if args.tmux {
+        Some((Multiplexer::Tmux, SplitDirection::New))
+    } else if args.tmux_vertical || args.tmux_v {
+        Some((Multiplexer::Tmux, SplitDirection::Vertical))
+    } else if args.tmux_horizontal || args.tmux_h {
+        Some((Multiplexer::Tmux, SplitDirection::Horizontal))
+    } else if args.kitty {
+        Some((Multiplexer::Kitty, SplitDirection::New))
+    } else if args.kitty_vertical || args.kitty_v {
+        Some((Multiplexer::Kitty, SplitDirection::Vertical))
+    } else if args.kitty_horizontal || args.kitty_h {
+        Some((Multiplexer::Kitty, SplitDirection::Horizontal))
+    } else {
+        None
+    }
+}
+
+/// Open a shell in the new worktree with the phantom variables set
+async fn open_in_multiplexer<E, F, H>(
+    name: &str,
+    path: &Path,
+    multiplexer: Multiplexer,
+    direction: SplitDirection,
+    context: &HandlerContext<E, F, H>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let shell_cmd = context.environment.get_var("SHELL").unwrap_or_else(|| "/bin/sh".to_string());
+    let shell_type = ShellType::from_path(&shell_cmd);
+    let path = path.to_string_lossy().to_string();
+
+    let options = MultiplexerOptions {
+        direction,
+        command: shell_cmd,
+        args: shell_type.prompt_args(),
+        env: Some(get_phantom_env(&*context.environment, shell_type, name, &path)),
+        cwd: Some(path),
+        window_name: (direction == SplitDirection::New).then(|| name.to_string()),
+    };
+    execute_with_multiplexer(multiplexer, context.executor.clone(), options).await?;
+    Ok(())
+}
+
+/// Reject options that only make sense for a single worktree
+fn validate_batch_args(args: &CreateArgs) -> Result<()> {
+    if args.branch.is_some() {
+        bail!("--branch can only be used when creating a single worktree");
+    }
+    if args.shell || args.exec.is_some() || requested_multiplexer(args).is_some() {
+        bail!(
+            "--shell, --exec, --tmux and --kitty can only be used when creating a single worktree"
+        );
+    }
+    Ok(())
+}
+
+/// Print the copied and skipped copyFiles and any copy failure
+pub(crate) fn report_copied_files(
+    copied: Option<&[String]>,
+    skipped: Option<&[String]>,
+    expanded_from: Option<&HashMap<String, Vec<String>>>,
+    copy_error: Option<&str>,
+) {
+    let copied = copied.map_or(0, <[String]>::len);
+    let skipped = skipped.unwrap_or_default();
+    if copied > 0 || !skipped.is_empty() {
+        output().log(&format!("Copied {copied} files, skipped {}", skipped.len()));
+        for file in skipped {
+            output().log(&format!("  skipped {file} (not found)"));
+        }
+    }
+    if let Some(error) = copy_error {
+        output().warn(&format!("Failed to copy files: {error}"));
+    }
+    if let Some(expanded_from) = expanded_from {
+        let mut patterns: Vec<_> = expanded_from.iter().collect();
+        patterns.sort();
+        for (pattern, files) in patterns {
+            output().log(&format!("  {pattern} -> {}", files.join(", ")));
+        }
+    }
+}
+
+/// Load the repository config and resolve --stack-on
+async fn load_plan<E, F, H>(
+    args: &CreateArgs,
+    git_root: PathBuf,
+    context: &HandlerContext<E, F, H>,
+) -> Result<CreatePlan>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    // Load config for copy files
+    let config = context
+        .config(&git_root)
+        .await
+        .with_context(|| format!("Failed to load config from git root: {}", git_root.display()))?;
+    let hooks_enabled = config.map_or(true, |loaded| loaded.config.hooks_enabled());
+    let branch_prefix = branch_prefix(args, config, &git_root, context).await?;
+    let (editors, scratch_dir) = config
+        .map(|loaded| (loaded.config.editors.clone(), loaded.config.scratch_dir.clone()))
+        .unwrap_or_default();
+    let copy_files = copy_files_selection(args).resolve(config.and_then(|loaded| {
+        loaded.config.post_create.as_ref().and_then(|pc| pc.copy_file_entries())
+    }));
+
+    let sparse_patterns = if args.no_checkout || args.no_sparse {
+        None
+    } else if !args.sparse.is_empty() {
+        Some(args.sparse.clone())
+    } else {
+        config.and_then(|loaded| loaded.config.create_sparse_patterns()).map(<[String]>::to_vec)
+    };
+    // Checked once here rather than failing halfway through each worktree
+    if sparse_patterns.is_some() {
+        ensure_sparse_checkout_supported(context.executor.clone()).await.map_err(|e| anyhow!(e))?;
+    }
+
+    let copy_source = resolve_copy_source(
+        context.executor.clone(),
+        &git_root,
+        args.source.as_deref(),
+        &context.filesystem,
+    )
+    .await
+    .with_context(|| "Failed to resolve --source")?;
+
+    // Resolve the stack parent, which becomes the base of the new branch
+    let stack_base = match &args.stack_on {
+        Some(target) => Some(
+            resolve_stack_base(context.executor.clone(), &git_root, target, &context.filesystem)
+                .await
+                .with_context(|| format!("Failed to resolve --stack-on '{target}'"))?,
+        ),
+        None => None,
+    };
+
+    Ok(CreatePlan {
+        git_root,
+        copy_source,
+        copy_files,
+        editor_dirs: editors.map(|editors| editors.directories()).unwrap_or_default(),
+        scratch_dir,
+        stack_base,
+        hooks_enabled,
+        branch_prefix,
+        sparse_patterns,
+    })
+}
+
+/// The prefix of new branches in `git_root`: --branch-prefix, nothing with
+/// --no-branch-prefix, otherwise the repository's configured branchPrefix
+pub(crate) async fn branch_prefix<E, F, H>(
+    args: &CreateArgs,
+    config: Option<&LoadedConfig>,
+    git_root: &Path,
+    context: &HandlerContext<E, F, H>,
+) -> Result<String>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let template = match (&args.branch_prefix, args.no_branch_prefix) {
+        (Some(prefix), _) => prefix.clone(),
+        (None, true) => return Ok(String::new()),
+        (None, false) => match config.and_then(|loaded| loaded.config.branch_prefix.clone()) {
+            Some(template) => template,
+            None => return Ok(String::new()),
+        },
+    };
+    resolve_branch_prefix(context.executor.clone(), &*context.environment, git_root, &template)
+        .await
+        .with_context(|| "Failed to resolve the branch prefix")
+}
+
+/// Branch for a new worktree: --branch as given, otherwise the name behind the branch prefix
+///
+/// Empty with --detach, which creates no branch.
+fn branch_for(name: &str, args: &CreateArgs, plan: &CreatePlan) -> String {
+    match &args.branch {
+        Some(branch) => branch.clone(),
+        None if args.detach => String::new(),
+        None => format!("{}{name}", plan.branch_prefix),
+    }
+}
+
+/// Create one worktree and run the setup steps whose failures only warn
+async fn create_one<E, F, H>(
+    name: &str,
+    branch: &str,
+    args: &CreateArgs,
+    plan: &CreatePlan,
+    context: &HandlerContext<E, F, H>,
+) -> Result<Created>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let commitish = plan.stack_base.as_ref().map(|base| base.branch.clone()).or(args.base.clone());
+    let options = CreateWorktreeOptions {
+        branch: (!args.detach).then(|| branch.to_string()),
+        commitish,
+        detach: args.detach,
+        no_checkout: args.no_checkout,
+        sparse_patterns: plan.sparse_patterns.clone(),
+        copy_files: plan.copy_files.clone(),
+        copy_source: Some(plan.copy_source.clone()),
+        keep_on_failure: args.keep_on_failure,
+        // Concurrent creates would draw over each other's progress
+        copy_progress: if args.names.len() == 1 && !args.json { copy_progress() } else { None },
+    };
+
+    let phantom =
+        Phantom::new(&plan.git_root, context.executor.clone(), context.filesystem.clone());
+    let result = phantom.create(name, options).await?;
+    let path = get_worktree_path(&plan.git_root, name);
+
+    // The worktree exists at this point, so missing metadata only costs list details
+    let mut warnings = Vec::new();
+    if let Some(e) = &result.metadata_error {
+        warnings.push(format!("Failed to record metadata for '{name}': {e}"));
+    }
+
+    // Optional setup steps; failures are reported but keep the worktree
+    let mut editor_files = None;
+    if !plan.editor_dirs.is_empty() {
+        match copy_editor_dirs(
+            context.executor.clone(),
+            &plan.copy_source,
+            &path,
+            &plan.editor_dirs,
+        )
+        .await
+        {
+            Ok(files) if files.is_empty() => {}
+            Ok(files) => editor_files = Some(files),
+            Err(e) => warnings.push(format!("Failed to copy editor settings: {e}")),
+        }
+    }
+    let mut scratch = None;
+    if let Some(dir) = &plan.scratch_dir {
+        match setup_scratch_dir(context.executor.clone(), &path, dir).await {
+            Ok(()) => scratch = Some(dir.clone()),
+            Err(e) => warnings.push(format!("Failed to set up scratch directory '{dir}': {e}")),
+        }
+    }
+
+    Ok(Created { path, result, editor_files, scratch, warnings })
+}
+
+/// What creating each worktree would run, copy and hook, worked out without changing anything
+async fn plan_dry_run<E, F, H>(
+    args: &CreateArgs,
+    plan: &CreatePlan,
+    context: &HandlerContext<E, F, H>,
+) -> Result<Vec<DryRunPlan>>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let git_root = &plan.git_root;
+    let mut plans = Vec::new();
+    for name in &args.names {
+        validate_worktree_name(name)?;
+        let path =
+            validate_worktree_does_not_exist(git_root, name, &context.filesystem).await?.path;
+        let branch = branch_for(name, args, plan);
+        let commitish =
+            plan.stack_base.as_ref().map(|base| base.branch.as_str()).or(args.base.as_deref());
+        if commitish.is_none() && is_head_unborn(context.executor.clone(), git_root).await? {
+            return Err(PhantomError::NoCommits.into());
+        }
+        if !args.detach && branch_exists(context.executor.clone(), git_root, &branch).await? {
+            return Err(PhantomError::BranchExists { branch }.into());
+        }
+
+        let executor = DryRunExecutor::new(context.executor.clone());
+        let no_checkout = args.no_checkout || plan.sparse_patterns.is_some();
+        let new_branch = (!args.detach).then_some(branch.as_str());
+        if no_checkout {
+            add_worktree_without_checkout(executor.clone(), git_root, &path, new_branch, commitish)
+                .await?;
+        } else if args.detach {
+            add_detached_worktree(executor.clone(), git_root, &path, commitish).await?;
+        } else {
+            add_worktree(executor.clone(), git_root, &path, new_branch, true, commitish).await?;
+        }
+        if let Some(patterns) = &plan.sparse_patterns {
+            init_sparse_checkout(executor.clone(), &path, patterns).await?;
+        }
+        if let Some(base) = &plan.stack_base {
+            set_upstream(executor.clone(), &path, &base.branch).await?;
+            record_stack_parent(executor.clone(), git_root, name, &base.branch).await?;
+        }
+
+        let branch = (!args.detach).then_some(branch.as_str());
+        let mut dry_run = DryRunPlan::new(name, branch, &path, &executor);
+        dry_run.add_copies(executor.clone(), plan.copy_files.as_deref(), &plan.copy_source).await?;
+        let editor_patterns = editor_dir_patterns(&plan.copy_source, &plan.editor_dirs).await;
+        dry_run.add_copies(executor.clone(), Some(&editor_patterns), &plan.copy_source).await?;
+        if plan.hooks_enabled {
+            dry_run.add_hook(&context.filesystem, git_root, Hook::PostCreate).await;
+        }
+        plans.push(dry_run);
+    }
+    Ok(plans)
+}
+
+/// Track the stack parent as upstream of the new branch; a no-op without --stack-on
+async fn apply_stack<E, F, H>(
+    name: &str,
+    branch: &str,
+    path: &Path,
+    plan: &CreatePlan,
+    context: &HandlerContext<E, F, H>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    if let Some(base) = &plan.stack_base {
+        set_upstream(context.executor.clone(), path, &base.branch).await.with_context(|| {
+            format!("Failed to set upstream of '{branch}' to '{}'", base.branch)
+        })?;
+        record_stack_parent(context.executor.clone(), &plan.git_root, name, &base.branch)
+            .await
+            .with_context(|| format!("Failed to record stack parent of '{name}'"))?;
+    }
+    Ok(())
+}
+
+/// Stack a new worktree and run its post-create hook, rolling it back if either fails
+///
+/// `config_lock` serializes the `git config` writes of concurrent creates.
+async fn finish_setup<E, F, H>(
+    name: &str,
+    branch: &str,
+    path: &Path,
+    args: &CreateArgs,
+    plan: &CreatePlan,
+    context: &HandlerContext<E, F, H>,
+    config_lock: &Mutex<()>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let stacked = {
+        let _guard = config_lock.lock().await;
+        apply_stack(name, branch, path, plan, context).await
+    };
+    let result = match stacked {
+        Ok(()) => timed("hooks", run_post_create_hook(name, path, plan, context)).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) => Err(roll_back_created(name, branch, args, plan, context, e).await),
+    }
+}
+
+/// Remove a worktree whose setup failed after it was created, unless --keep-on-failure
+async fn roll_back_created<E, F, H>(
+    name: &str,
+    branch: &str,
+    args: &CreateArgs,
+    plan: &CreatePlan,
+    context: &HandlerContext<E, F, H>,
+    error: anyhow::Error,
+) -> anyhow::Error
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    if args.keep_on_failure {
+        return error.context(format!("Kept the partially created worktree '{name}'"));
+    }
+    let phantom =
+        Phantom::new(&plan.git_root, context.executor.clone(), context.filesystem.clone());
+    let unreverted = phantom.roll_back_create(name, branch).await;
+    if unreverted.is_empty() {
+        error.context(format!("Rolled back worktree '{name}'"))
+    } else {
+        error.context(format!(
+            "Rolled back worktree '{name}', but could not undo: {}",
+            unreverted.join("; ")
+        ))
+    }
+}
+
+/// Run the repository's post-create hook, failing unless it exits successfully
+async fn run_post_create_hook<E, F, H>(
+    name: &str,
+    path: &Path,
+    plan: &CreatePlan,
+    context: &HandlerContext<E, F, H>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    if !plan.hooks_enabled {
+        return Ok(());
+    }
+    let code = run_hook(
+        context.executor.clone(),
+        &context.filesystem,
+        &*context.environment,
+        &plan.git_root,
+        Hook::PostCreate,
+        name,
+        path,
+    )
+    .await
+    .with_context(|| "Failed to run post-create hook")?;
+    match code {
+        None | Some(0) => Ok(()),
+        Some(code) => bail!("post-create hook exited with code {code}"),
+    }
+}
+
+fn created_result(name: &str, branch: &str, created: &Created, plan: &CreatePlan) -> CreateResult {
+    CreateResult {
+        success: true,
+        name: name.to_string(),
+        branch: branch.to_string(),
+        detached: branch.is_empty(),
+        path: created.path.to_string_lossy().to_string(),
+        copied_files: created.result.copied_files.clone(),
+        editor_files: created.editor_files.clone(),
+        scratch_dir: created.scratch.clone(),
+        stack_parent: plan.stack_base.as_ref().map(|base| base.branch.clone()),
+        error: None,
+        timings_ms: None,
+    }
+}
+
+/// Check every name of a batch so an obviously invalid one creates nothing
+///
+/// Branches are only checked when `branch_prefix` is set, since --detach creates none.
+async fn validate_batch_names<E, F, H>(
+    names: &[String],
+    branch_prefix: Option<&str>,
+    git_root: &Path,
+    context: &HandlerContext<E, F, H>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let mut problems = Vec::new();
+    let mut seen = HashSet::new();
+    for name in names {
+        if !seen.insert(name.as_str()) {
+            problems.push(format!("'{name}' is listed more than once"));
+            continue;
+        }
+        if let Err(PhantomError::InvalidWorktreeName { reason, .. }) = validate_worktree_name(name)
+        {
+            problems.push(format!("'{name}': {reason}"));
+            continue;
+        }
+        if let Err(e) = validate_worktree_does_not_exist(git_root, name, &context.filesystem).await
+        {
+            problems.push(e.to_string());
+            continue;
+        }
+        let Some(branch_prefix) = branch_prefix else {
+            continue;
+        };
+        let branch = format!("{branch_prefix}{name}");
+        if branch_exists(context.executor.clone(), git_root, &branch)
+            .await
+            .with_context(|| format!("Failed to check whether branch '{branch}' exists"))?
+        {
+            problems.push(format!("'{name}': branch '{branch}' already exists"));
+        }
+    }
+
+    if !problems.is_empty() {
+        bail!("Nothing was created:\n  {}", problems.join("\n  "));
+    }
+    Ok(())
+}
+
+/// Create several worktrees concurrently, each on a branch named after it behind the branch prefix
+///
+/// A failure does not stop the others; with --fail-fast the creations that have
+/// not started yet are skipped instead.
+async fn create_many<E, F, H>(
+    args: CreateArgs,
+    plan: CreatePlan,
+    context: HandlerContext<E, F, H>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let branch_prefix = (!args.detach).then_some(plan.branch_prefix.as_str());
+    validate_batch_names(&args.names, branch_prefix, &plan.git_root, &context).await?;
+
+    let any_failed = AtomicBool::new(false);
+    // `git config` does not retry its lock, so stack parents are recorded one at a time
+    let config_lock = Mutex::new(());
+
+    let results = run_bounded(&args.names, MAX_CONCURRENT_CREATES, |name| {
+        let (args, plan, context) = (&args, &plan, &context);
+        let (any_failed, config_lock) = (&any_failed, &config_lock);
+        async move {
+            let branch = branch_for(name, args, plan);
+            let result = if args.fail_fast && any_failed.load(Ordering::SeqCst) {
+                Err(anyhow!("Skipped after an earlier failure (--fail-fast)"))
+            } else {
+                match create_one(name, &branch, args, plan, context)
+                    .await
+                    .with_context(|| format!("Failed to create worktree '{name}'"))
+                {
+                    Ok(created) => {
+                        finish_setup(name, &branch, &created.path, args, plan, context, config_lock)
+                            .await
+                            .map(|()| created)
+                    }
+                    Err(e) => Err(e),
+                }
+            };
+
+            // Report each worktree as soon as it is done
+            match &result {
+                Ok(created) => {
+                    if !args.json {
+                        report_batch_entry(name, &branch, created, plan);
+                    }
+                }
+                Err(e) => {
+                    any_failed.store(true, Ordering::SeqCst);
+                    if !args.json {
+                        output().error(&format!("[{name}] {e:#}"));
+                    }
+                }
+            }
+            (name, branch, result)
+        }
+    })
+    .await;
+
+    let total = results.len();
+    let mut worktrees = Vec::with_capacity(total);
+    for (name, branch, result) in results {
+        worktrees.push(match result {
+            Ok(created) => {
+                if args.json {
+                    for warning in &created.warnings {
+                        output().warn(&format!("[{name}] {warning}"));
+                    }
+                }
+                created_result(name, &branch, &created, &plan)
+            }
+            Err(e) => CreateResult {
+                success: false,
+                name: name.clone(),
+                detached: branch.is_empty(),
+                branch,
+                path: String::new(),
+                copied_files: None,
+                editor_files: None,
+                scratch_dir: None,
+                stack_parent: None,
+                error: Some(format!("{e:#}")),
+                timings_ms: None,
+            },
+        });
+    }
+
+    let failed = worktrees.iter().filter(|worktree| !worktree.success).count();
+    if args.json {
+        let json_output =
+            CreateBatchResult { success: failed == 0, worktrees, timings_ms: timings_ms() };
+        output().log(
+            &serde_json::to_string_pretty(&json_output)
+                .with_context(|| "Failed to serialize JSON output")?,
+        );
+    } else {
+        output().log(&format!("Created {} of {total} worktrees", total - failed));
+    }
+
+    if failed > 0 {
+        bail!("{failed} of {total} worktrees failed");
+    }
+    Ok(())
+}
+
+fn created_message(name: &str, branch: &str) -> String {
+    if branch.is_empty() {
+        format!("Created detached worktree '{name}'")
+    } else {
+        format!("Created worktree '{name}' with branch '{branch}'")
+    }
+}
+
+/// Print the outcome of one worktree of a batch, prefixed with its name
+fn report_batch_entry(name: &str, branch: &str, created: &Created, plan: &CreatePlan) {
+    for warning in &created.warnings {
+        output().warn(&format!("[{name}] {warning}"));
+    }
+    output().success(&format!("[{name}] {}", created_message(name, branch)));
+    if let Some(base) = &plan.stack_base {
+        output().log(&format!("[{name}] Stacked on '{}' (upstream set)", base.branch));
+    }
+    let result = &created.result;
+    let copied = result.copied_files.as_ref().map_or(0, Vec::len);
+    let skipped = result.skipped_files.as_ref().map_or(0, Vec::len);
+    if copied > 0 || skipped > 0 {
+        output().log(&format!("[{name}] Copied {copied} files, skipped {skipped}"));
+    }
+}
+
+/// Translate the --copy-files/--copy-files-only/--no-copy flags into a selection
+pub(crate) fn copy_files_selection(args: &CreateArgs) -> CopyFilesSelection {
+    if args.no_copy {
+        CopyFilesSelection::Skip
+    } else if args.copy_files_only {
+        CopyFilesSelection::Only(args.copy_files.clone())
+    } else {
+        CopyFilesSelection::Merge(args.copy_files.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::environment::MockEnvironment;
     use crate::core::executors::MockCommandExecutor;
+    use crate::core::exit_handler::MockExitHandler;
+    use crate::core::filesystems::MockFileSystem;
+    use clap::Parser;
 
     // IMPORTANT: Create handler testing limitations
     //
@@ -198,13 +920,27 @@ mod tests {
             crate::core::exit_handler::MockExitHandler::new(),
         );
         let args = CreateArgs {
-            name: "feature".to_string(),
+            names: vec!["feature".to_string()],
             branch: None,
+            branch_prefix: None,
+            no_branch_prefix: false,
             base: None,
             shell: false,
             exec: None,
-            copy_files: None,
+            copy_files: vec![],
+            copy_files_only: false,
+            no_copy: false,
+            source: None,
             json: false,
+            stack_on: None,
+            detach: false,
+            workspace: false,
+            fail_fast: false,
+            keep_on_failure: false,
+            dry_run: false,
+            no_checkout: false,
+            sparse: vec![],
+            no_sparse: false,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
@@ -249,13 +985,27 @@ mod tests {
             crate::core::exit_handler::MockExitHandler::new(),
         );
         let args = CreateArgs {
-            name: "feature".to_string(),
+            names: vec!["feature".to_string()],
             branch: None,
+            branch_prefix: None,
+            no_branch_prefix: false,
             base: None,
             shell: false,
             exec: None,
-            copy_files: None,
+            copy_files: vec![],
+            copy_files_only: false,
+            no_copy: false,
+            source: None,
             json: true, // JSON output mode
+            stack_on: None,
+            detach: false,
+            workspace: false,
+            fail_fast: false,
+            keep_on_failure: false,
+            dry_run: false,
+            no_checkout: false,
+            sparse: vec![],
+            no_sparse: false,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
@@ -286,10 +1036,10 @@ mod tests {
 
         // Mock worktree list check - shows feature already exists
         mock.expect_command("git")
-            .with_args(&["worktree", "list", "--porcelain"])
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
             .returns_output(
-                "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n\n\
-                 worktree /repo/.git/phantom/worktrees/feature\nHEAD def456\nbranch refs/heads/feature\n",
+                "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree /repo/.git/phantom/worktrees/feature\0HEAD def456\0branch refs/heads/feature\0",
                 "",
                 0,
             );
@@ -300,13 +1050,27 @@ mod tests {
             crate::core::exit_handler::MockExitHandler::new(),
         );
         let args = CreateArgs {
-            name: "feature".to_string(),
+            names: vec!["feature".to_string()],
             branch: None,
+            branch_prefix: None,
+            no_branch_prefix: false,
             base: None,
             shell: false,
             exec: None,
-            copy_files: None,
+            copy_files: vec![],
+            copy_files_only: false,
+            no_copy: false,
+            source: None,
             json: false,
+            stack_on: None,
+            detach: false,
+            workspace: false,
+            fail_fast: false,
+            keep_on_failure: false,
+            dry_run: false,
+            no_checkout: false,
+            sparse: vec![],
+            no_sparse: false,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
@@ -347,11 +1111,9 @@ mod tests {
         );
 
         // Mock worktree list check - empty
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output("worktree /repo\0HEAD abc123\0branch refs/heads/main\0", "", 0);
 
         let context = HandlerContext::new(
             mock,
@@ -359,13 +1121,27 @@ mod tests {
             crate::core::exit_handler::MockExitHandler::new(),
         );
         let args = CreateArgs {
-            name: "feature".to_string(),
+            names: vec!["feature".to_string()],
             branch: Some("custom-feature".to_string()),
+            branch_prefix: None,
+            no_branch_prefix: false,
             base: None,
             shell: false,
             exec: None,
-            copy_files: None,
+            copy_files: vec![],
+            copy_files_only: false,
+            no_copy: false,
+            source: None,
             json: false,
+            stack_on: None,
+            detach: false,
+            workspace: false,
+            fail_fast: false,
+            keep_on_failure: false,
+            dry_run: false,
+            no_checkout: false,
+            sparse: vec![],
+            no_sparse: false,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
@@ -400,13 +1176,27 @@ mod tests {
             crate::core::exit_handler::MockExitHandler::new(),
         );
         let args = CreateArgs {
-            name: "invalid name with spaces".to_string(),
+            names: vec!["invalid name with spaces".to_string()],
             branch: None,
+            branch_prefix: None,
+            no_branch_prefix: false,
             base: None,
             shell: false,
             exec: None,
-            copy_files: None,
+            copy_files: vec![],
+            copy_files_only: false,
+            no_copy: false,
+            source: None,
             json: false,
+            stack_on: None,
+            detach: false,
+            workspace: false,
+            fail_fast: false,
+            keep_on_failure: false,
+            dry_run: false,
+            no_checkout: false,
+            sparse: vec![],
+            no_sparse: false,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
@@ -439,4 +1229,555 @@ mod tests {
     // included here because they would require mocking process operations, which
     // haven't been migrated to use CommandExecutor yet. These will be added once
     // the process operations migration is complete.
+
+    fn copy_args(copy_files: &[&str], copy_files_only: bool, no_copy: bool) -> CreateArgs {
+        let mut command = vec!["phantom", "create", "feature"];
+        for file in copy_files {
+            command.extend(["--copy-files", file]);
+        }
+        if copy_files_only {
+            command.push("--copy-files-only");
+        }
+        if no_copy {
+            command.push("--no-copy");
+        }
+        match crate::cli::Cli::try_parse_from(command).unwrap().command {
+            crate::cli::Commands::Create(args) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_copy_files_selection_merges_with_config() {
+        let configured = Some(vec![".env".to_string(), "config.json".to_string()]);
+
+        let selection = copy_files_selection(&copy_args(&[".env.staging", ".env"], false, false));
+        assert_eq!(
+            selection.resolve(configured.clone()),
+            Some(vec![".env".to_string(), "config.json".to_string(), ".env.staging".to_string()])
+        );
+
+        let selection = copy_files_selection(&copy_args(&[], false, false));
+        assert_eq!(
+            selection.resolve(configured),
+            Some(vec![".env".to_string(), "config.json".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_copy_files_selection_only_and_skip() {
+        let configured = Some(vec![".env".to_string()]);
+
+        let selection = copy_files_selection(&copy_args(&[".env.staging"], true, false));
+        assert_eq!(selection.resolve(configured.clone()), Some(vec![".env.staging".to_string()]));
+
+        let selection = copy_files_selection(&copy_args(&[], false, true));
+        assert_eq!(selection, CopyFilesSelection::Skip);
+        assert_eq!(selection.resolve(configured), None);
+    }
+
+    #[test]
+    fn test_copy_flag_conflicts() {
+        use crate::cli::Cli;
+
+        let parse = |extra: &[&str]| {
+            let mut command = vec!["phantom", "create", "feature"];
+            command.extend_from_slice(extra);
+            Cli::try_parse_from(command)
+        };
+        assert!(parse(&["--no-copy", "--copy-files", ".env"]).is_err());
+        assert!(parse(&["--copy-files-only"]).is_err());
+        assert!(parse(&["--copy-files", ".env,.env.local", "--copy-files-only"]).is_ok());
+    }
+
+    #[test]
+    fn test_detach_flag_conflicts() {
+        use crate::cli::Cli;
+
+        let parse = |extra: &[&str]| {
+            let mut command = vec!["phantom", "create", "bisect", "--detach"];
+            command.extend_from_slice(extra);
+            Cli::try_parse_from(command)
+        };
+        assert!(parse(&["--base", "v1.0"]).is_ok());
+        assert!(parse(&["--branch", "x"]).is_err());
+        assert!(parse(&["--stack-on", "feature"]).is_err());
+        assert!(parse(&["--workspace"]).is_err());
+    }
+
+    fn parse_create(extra: &[&str]) -> CreateArgs {
+        let mut command = vec!["phantom", "create"];
+        command.extend_from_slice(extra);
+        match crate::cli::Cli::try_parse_from(command).unwrap().command {
+            crate::cli::Commands::Create(args) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_validate_batch_args() {
+        assert!(validate_batch_args(&parse_create(&["a", "b", "--base", "main"])).is_ok());
+        assert!(validate_batch_args(&parse_create(&["a", "b", "--branch", "x"])).is_err());
+        assert!(validate_batch_args(&parse_create(&["a", "b", "--shell"])).is_err());
+        assert!(validate_batch_args(&parse_create(&["a", "b", "--tmux-h"])).is_err());
+        assert!(crate::cli::Cli::try_parse_from(["phantom", "create"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_copy_source_from_inside_a_phantom_worktree() {
+        use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+        use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+        use std::path::PathBuf;
+
+        // git answers like this from inside /repo/.git/phantom/worktrees/feature
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .in_dir("/repo")
+            .returns_output(
+                "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree /repo/.git/phantom/worktrees/feature\0HEAD def456\0branch refs/heads/feature\0\0",
+                "",
+                0,
+            );
+        let mock_fs = MockFileSystem::new();
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/feature")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let git_root = context.git_root().await.unwrap();
+        assert_eq!(git_root, PathBuf::from("/repo"));
+
+        // Files come from the main worktree, not the one phantom runs in
+        let plan = load_plan(&parse_create(&["topic"]), git_root.clone(), &context).await.unwrap();
+        assert_eq!(plan.copy_source, PathBuf::from("/repo"));
+
+        let args = parse_create(&["topic", "--source", "feature"]);
+        let plan = load_plan(&args, git_root.clone(), &context).await.unwrap();
+        assert_eq!(plan.copy_source, PathBuf::from("/repo/.git/phantom/worktrees/feature"));
+
+        let args = parse_create(&["topic", "--source", "gone"]);
+        let Err(error) = load_plan(&args, git_root, &context).await else {
+            panic!("a missing --source must fail");
+        };
+        assert!(format!("{error:#}").contains("'gone' is neither a phantom nor a directory"));
+    }
+
+    #[tokio::test]
+    async fn test_create_batch_validates_every_name_before_creating() {
+        use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+        use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+        use std::path::PathBuf;
+
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/taken")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/fresh"])
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/old"])
+            .returns_output("", "", 0);
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = parse_create(&["fresh", "taken", "fresh", "bad name", "old"]);
+
+        let error = handle(args, context).await.unwrap_err().to_string();
+        assert!(error.starts_with("Nothing was created"), "{error}");
+        assert!(error.contains("Worktree 'taken' already exists"), "{error}");
+        assert!(error.contains("'fresh' is listed more than once"), "{error}");
+        assert!(error.contains("'bad name': Phantom name can only contain"), "{error}");
+        assert!(error.contains("'old': branch 'old' already exists"), "{error}");
+        assert!(!mock.calls().iter().any(|call| call
+            .args
+            .first()
+            .is_some_and(|a| a == "worktree")
+            && call.args.get(1).is_some_and(|a| a == "add")));
+    }
+
+    #[tokio::test]
+    async fn test_branch_prefix_from_config_and_flags() {
+        use crate::core::executors::RealCommandExecutor;
+        use crate::core::filesystems::RealFileSystem;
+
+        let repo = crate::test_utils::TestRepo::new().await.unwrap();
+        std::fs::write(
+            repo.path().join("phantom.config.json"),
+            r#"{"branchPrefix": "user/{user}/"}"#,
+        )
+        .unwrap();
+        let context = HandlerContext::new(
+            RealCommandExecutor,
+            RealFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let branch = |extra: &'static [&'static str]| {
+            let context = context.clone();
+            let git_root = repo.path().to_path_buf();
+            async move {
+                let mut command = vec!["topic"];
+                command.extend_from_slice(extra);
+                let args = parse_create(&command);
+                let plan = load_plan(&args, git_root, &context).await.unwrap();
+                branch_for("topic", &args, &plan)
+            }
+        };
+
+        assert_eq!(branch(&[]).await, "user/test-user/topic");
+        assert_eq!(branch(&["--no-branch-prefix"]).await, "topic");
+        assert_eq!(branch(&["--branch-prefix", "wip/"]).await, "wip/topic");
+        assert_eq!(branch(&["--branch", "custom"]).await, "custom");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_post_create_hook_runs_inside_the_new_worktree() {
+        use crate::core::executors::RealCommandExecutor;
+        use crate::core::filesystems::RealFileSystem;
+        use std::os::unix::fs::PermissionsExt;
+
+        let repo = crate::test_utils::TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("README.md", "hi", "Initial").await.unwrap();
+        let hook = repo.path().join(".phantom/hooks/post-create");
+        std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        std::fs::write(&hook, "#!/bin/sh\necho \"$1 $PHANTOM_WORKTREE\" > hook-ran\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let context = HandlerContext::new(
+            RealCommandExecutor,
+            RealFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let plan = CreatePlan {
+            git_root: repo.path().to_path_buf(),
+            copy_source: repo.path().to_path_buf(),
+            copy_files: None,
+            editor_dirs: vec![],
+            scratch_dir: None,
+            stack_base: None,
+            hooks_enabled: true,
+            branch_prefix: String::new(),
+            sparse_patterns: None,
+        };
+        let args = parse_create(&["feature"]);
+
+        let created = create_one("feature", "feature", &args, &plan, &context).await.unwrap();
+        run_post_create_hook("feature", &created.path, &plan, &context).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(created.path.join("hook-ran")).unwrap(),
+            "feature feature\n"
+        );
+
+        std::fs::write(&hook, "#!/bin/sh\nexit 3\n").unwrap();
+        let error = run_post_create_hook("feature", &created.path, &plan, &context).await;
+        assert_eq!(error.unwrap_err().to_string(), "post-create hook exited with code 3");
+
+        let disabled = CreatePlan { hooks_enabled: false, ..plan };
+        run_post_create_hook("feature", &created.path, &disabled, &context).await.unwrap();
+    }
+
+    /// A context whose `/repo` has a post-create hook exiting with `code` for 'feature'
+    fn failing_hook_context(
+        code: i32,
+    ) -> (MockCommandExecutor, HandlerContext<MockCommandExecutor, MockFileSystem, MockExitHandler>)
+    {
+        use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+        use crate::core::filesystems::FileSystemExpectation;
+
+        let path = get_worktree_path(Path::new("/repo"), "feature");
+        let fs = MockFileSystem::new();
+        for (operation, path) in [
+            (FileSystemOperation::IsExecutable, PathBuf::from("/repo/.phantom/hooks/post-create")),
+            (FileSystemOperation::IsDir, path.clone()),
+        ] {
+            fs.expect(FileSystemExpectation {
+                operation,
+                path: Some(path),
+                from_path: None,
+                to_path: None,
+                contents: None,
+                result: Ok(MockResult::Bool(true)),
+            });
+        }
+        let path = path.to_string_lossy();
+        let mut mock = MockCommandExecutor::new_ordered();
+        mock.expect_command("/repo/.phantom/hooks/post-create")
+            .with_args(&["feature", &path])
+            .in_dir(&*path)
+            .returns_output("", "", code);
+        let context = HandlerContext::new(mock.clone(), fs, MockExitHandler::new());
+        (mock, context)
+    }
+
+    fn hook_plan() -> CreatePlan {
+        CreatePlan {
+            git_root: PathBuf::from("/repo"),
+            copy_source: PathBuf::from("/repo"),
+            copy_files: None,
+            editor_dirs: vec![],
+            scratch_dir: None,
+            stack_base: None,
+            hooks_enabled: true,
+            branch_prefix: String::new(),
+            sparse_patterns: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_post_create_hook_rolls_back_the_worktree() {
+        let (mut mock, context) = failing_hook_context(1);
+        let path = get_worktree_path(Path::new("/repo"), "feature");
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "--force", &path.to_string_lossy()])
+            .in_dir("/repo")
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["branch", "-D", "feature"])
+            .in_dir("/repo")
+            .returns_success();
+
+        let args = parse_create(&["feature"]);
+        let error = finish_setup(
+            "feature",
+            "feature",
+            &path,
+            &args,
+            &hook_plan(),
+            &context,
+            &Mutex::new(()),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{error:#}"),
+            "Rolled back worktree 'feature': post-create hook exited with code 1"
+        );
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_post_create_hook_keeps_the_worktree_with_keep_on_failure() {
+        let (mock, context) = failing_hook_context(1);
+        let path = get_worktree_path(Path::new("/repo"), "feature");
+
+        let args = parse_create(&["feature", "--keep-on-failure"]);
+        let error = finish_setup(
+            "feature",
+            "feature",
+            &path,
+            &args,
+            &hook_plan(),
+            &context,
+            &Mutex::new(()),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{error:#}"),
+            "Kept the partially created worktree 'feature': post-create hook exited with code 1"
+        );
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_timings_appear_in_json_result() {
+        use crate::core::executors::RealCommandExecutor;
+        use crate::core::filesystems::RealFileSystem;
+        use crate::core::utils::collect_timings;
+
+        let repo = crate::test_utils::TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("README.md", "hi", "Initial").await.unwrap();
+        let context = HandlerContext::new(
+            RealCommandExecutor,
+            RealFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let plan = CreatePlan {
+            git_root: repo.path().to_path_buf(),
+            copy_source: repo.path().to_path_buf(),
+            copy_files: None,
+            editor_dirs: vec![],
+            scratch_dir: None,
+            stack_base: None,
+            hooks_enabled: false,
+            branch_prefix: String::new(),
+            sparse_patterns: None,
+        };
+        let args = parse_create(&["feature", "--json"]);
+
+        let (result, _) = collect_timings(async {
+            let created = create_one("feature", "feature", &args, &plan, &context).await.unwrap();
+            CreateResult {
+                timings_ms: timings_ms(),
+                ..created_result("feature", "feature", &created, &plan)
+            }
+        })
+        .await;
+        let json = serde_json::to_value(&result).unwrap();
+        for key in ["worktree_add", "file_copy"] {
+            assert!(json["timings_ms"].get(key).is_some(), "missing {key} in {json}");
+        }
+
+        // Without --timings nothing is collected and the key is left out
+        let created = create_one("other", "other", &args, &plan, &context).await.unwrap();
+        let result = CreateResult {
+            timings_ms: timings_ms(),
+            ..created_result("other", "other", &created, &plan)
+        };
+        assert!(serde_json::to_value(&result).unwrap().get("timings_ms").is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_dry_run_plans_without_creating_anything() {
+        use crate::core::executors::RealCommandExecutor;
+        use crate::core::filesystems::RealFileSystem;
+        use std::os::unix::fs::PermissionsExt;
+
+        let repo = crate::test_utils::TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("README.md", "hi", "Initial").await.unwrap();
+        std::fs::write(repo.path().join(".env"), "SECRET=1").unwrap();
+        let hook = repo.path().join(".phantom/hooks/post-create");
+        std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        std::fs::write(&hook, "#!/bin/sh\ntouch hook-ran\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let context = HandlerContext::new(
+            RealCommandExecutor,
+            RealFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let plan = CreatePlan {
+            git_root: repo.path().to_path_buf(),
+            copy_source: repo.path().to_path_buf(),
+            copy_files: Some(vec![".env".to_string(), "missing".to_string()]),
+            editor_dirs: vec![],
+            scratch_dir: None,
+            stack_base: None,
+            hooks_enabled: true,
+            branch_prefix: String::new(),
+            sparse_patterns: None,
+        };
+        let args = parse_create(&["feature", "--dry-run"]);
+
+        let plans = plan_dry_run(&args, &plan, &context).await.unwrap();
+        assert_eq!(plans.len(), 1);
+        let commands = &plans[0].commands;
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].args[..3], ["worktree", "add", "-b"]);
+        assert_eq!(plans[0].copies.len(), 1);
+        assert!(plans[0].copies[0].from.ends_with(".env"));
+        assert_eq!(plans[0].hooks.len(), 1);
+
+        assert!(!Path::new(&plans[0].path).exists());
+        let exists = branch_exists(RealCommandExecutor, repo.path(), "feature").await.unwrap();
+        assert!(!exists);
+    }
+
+    #[test]
+    fn test_requested_multiplexer() {
+        assert_eq!(requested_multiplexer(&parse_create(&["a"])), None);
+        assert_eq!(
+            requested_multiplexer(&parse_create(&["a", "--tmux"])),
+            Some((Multiplexer::Tmux, SplitDirection::New))
+        );
+        assert_eq!(
+            requested_multiplexer(&parse_create(&["a", "--tmux-v"])),
+            Some((Multiplexer::Tmux, SplitDirection::Vertical))
+        );
+        assert_eq!(
+            requested_multiplexer(&parse_create(&["a", "--kitty-horizontal"])),
+            Some((Multiplexer::Kitty, SplitDirection::Horizontal))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_multiplexer_checked_before_creating() {
+        let mock = MockCommandExecutor::new();
+        let context = HandlerContext::new(
+            mock.clone(),
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        )
+        .with_environment(MockEnvironment::new());
+
+        let result = handle(parse_create(&["feature", "--tmux"]), context.clone()).await;
+        assert!(result.unwrap_err().to_string().contains("inside a tmux session"));
+        let result = handle(parse_create(&["feature", "--kitty"]), context).await;
+        assert!(result.unwrap_err().to_string().contains("inside a kitty terminal"));
+        assert!(mock.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_open_in_multiplexer_sets_phantom_env() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&[
+                "new-window",
+                "-n",
+                "feature",
+                "-c",
+                "/repo/.git/phantom/worktrees/feature",
+                "-e",
+                "PHANTOM_ACTIVE=1",
+                "-e",
+                "PHANTOM_WORKTREE=feature",
+                "-e",
+                "PHANTOM_WORKTREE_PATH=/repo/.git/phantom/worktrees/feature",
+                "/bin/zsh",
+            ])
+            .returns_output("", "", 0);
+        let context = HandlerContext::new(
+            mock.clone(),
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        )
+        .with_environment(MockEnvironment::new().with_var("SHELL", "/bin/zsh"));
+
+        open_in_multiplexer(
+            "feature",
+            Path::new("/repo/.git/phantom/worktrees/feature"),
+            Multiplexer::Tmux,
+            SplitDirection::New,
+            &context,
+        )
+        .await
+        .unwrap();
+        mock.verify().unwrap();
+    }
 }
@@ -1,15 +1,31 @@
-use crate::cli::commands::delete::{DeleteArgs, DeleteResult};
+use crate::api::Phantom;
+use crate::cli::commands::delete::{
+    BulkDeleteEntry, BulkDeleteResult, DeleteArgs, DeleteResult, PruneResult,
+};
 use crate::cli::context::HandlerContext;
-use crate::cli::output::output;
+use crate::cli::handlers::workspace;
+use crate::cli::output::{output, timings_ms, Prompt, TerminalPrompt};
+use crate::config::types::PhantomConfig;
 use crate::core::command_executor::CommandExecutor;
 use crate::core::exit_handler::ExitHandler;
 use crate::core::filesystem::FileSystem;
+use crate::core::utils::timed;
+use crate::git::libs::changed_files::default_branch;
 use crate::git::libs::get_current_worktree::get_current_worktree;
-use crate::git::libs::get_git_root::get_git_root;
-use crate::worktree::delete::delete_worktree;
+use crate::git::libs::is_branch_merged::is_branch_merged;
+use crate::git::libs::lock_worktree::{lock_state, lock_worktree, unlock_worktree, LockState};
+use crate::process::tty::is_stdout_tty;
+use crate::worktree::delete::get_worktree_status;
+use crate::worktree::hooks::{run_hook, Hook};
+use crate::worktree::list::ListOptions;
 use crate::worktree::select::select_worktree_with_fzf;
-use crate::worktree::types::DeleteWorktreeOptions;
+use crate::worktree::stack::children_of_worktree;
+use crate::worktree::state::resolve_worktree_path;
+use crate::worktree::types::{DeleteWorktreeOptions, DeleteWorktreeSuccess, ForceOptions};
+use crate::PhantomError;
 use anyhow::{bail, Context, Result};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
 
 /// Handle the delete command
 pub async fn handle<E, F, H>(args: DeleteArgs, context: HandlerContext<E, F, H>) -> Result<()>
@@ -18,6 +34,30 @@ where
     F: FileSystem + Clone + 'static,
     H: ExitHandler + Clone + 'static,
 {
+    handle_with_prompt(args, context, &TerminalPrompt).await
+}
+
+/// Handle the delete command, asking `prompt` before deleting uncommitted changes
+pub(crate) async fn handle_with_prompt<E, F, H>(
+    args: DeleteArgs,
+    context: HandlerContext<E, F, H>,
+    prompt: &dyn Prompt,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    if args.workspace {
+        return workspace::delete(args, context).await;
+    }
+    if args.prune {
+        return prune(args, context).await;
+    }
+    if args.stdin_names {
+        return delete_from_reader(std::io::stdin().lock(), args, context).await;
+    }
+
     // Validate args
     if args.name.is_none() && !args.current && !args.fzf {
         bail!(
@@ -34,9 +74,10 @@ where
     }
 
     // Get git root
-    let git_root = get_git_root(context.executor.clone())
+    let git_root = timed("git_root", context.git_root())
         .await
         .with_context(|| "Failed to determine git repository root")?;
+    let config = context.config_or_default(&git_root).await;
 
     // Get worktree name
     let worktree_name = if args.current {
@@ -52,9 +93,14 @@ where
             }
         }
     } else if args.fzf {
-        match select_worktree_with_fzf(context.executor.clone(), &git_root)
-            .await
-            .with_context(|| "Failed to select worktree with fzf")?
+        match select_worktree_with_fzf(
+            context.executor.clone(),
+            &context.filesystem,
+            &git_root,
+            &config,
+        )
+        .await
+        .with_context(|| "Failed to select worktree with fzf")?
         {
             Some(worktree) => worktree.name,
             None => {
@@ -63,50 +109,441 @@ where
             }
         }
     } else {
-        args.name.unwrap()
+        args.name.clone().unwrap()
     };
 
+    // Deleting a stack parent leaves its children pointing at a missing branch
+    if let Ok(children) =
+        children_of_worktree(context.executor.clone(), &git_root, &worktree_name).await
+    {
+        if !children.is_empty() {
+            output().warn(&format!(
+                "Worktree '{worktree_name}' is the stack parent of: {}",
+                children.join(", ")
+            ));
+        }
+    }
+
     // Delete the worktree
-    let options = DeleteWorktreeOptions { force: args.force };
+    let mut options = delete_options(&args, &config);
+    options.force.dirty |= args.yes;
+    let path = resolve_worktree_path(&context.filesystem, &git_root, &worktree_name).await;
 
-    match delete_worktree(
-        context.executor.clone(),
-        &git_root,
-        &worktree_name,
-        options,
-        &context.filesystem,
+    // Refuse a locked worktree before anything else, naming the lock reason; an unreadable
+    // state is left to the removal, which git refuses for locked worktrees anyway
+    let lock =
+        lock_state(context.executor.clone(), &git_root, &path).await.unwrap_or(LockState::Unlocked);
+    if let LockState::Locked { reason } = &lock {
+        if !options.force.locked {
+            let error = PhantomError::WorktreeLocked {
+                name: worktree_name.clone(),
+                reason: reason.clone(),
+            };
+            return report_failure(args.json, worktree_name, error.into());
+        }
+    }
+
+    // Ask before throwing away uncommitted work; with --json the deletion fails instead
+    if !options.force.dirty && !args.json {
+        let status = get_worktree_status(context.executor.clone(), &path).await;
+        if status.has_uncommitted_changes {
+            if !confirm_dirty_delete(prompt, &worktree_name, status.changed_files)? {
+                bail!(
+                    "Worktree '{worktree_name}' has uncommitted changes and was not deleted (use --yes or --force to delete anyway)"
+                );
+            }
+            options.force.dirty = true;
+        }
+    }
+
+    // Leave the directory being deleted; some platforms refuse to remove a process's cwd
+    if args.current {
+        context
+            .filesystem
+            .set_current_dir(&git_root)
+            .with_context(|| format!("Failed to change directory to {}", git_root.display()))?;
+    }
+
+    let phantom = Phantom::new(&git_root, context.executor.clone(), context.filesystem.clone());
+    let hooks = config.hooks_enabled();
+    let deleted = match timed(
+        "hooks",
+        run_pre_delete_hook(&context, &git_root, &worktree_name, hooks, args.force),
     )
     .await
-    .with_context(|| format!("Failed to delete worktree '{worktree_name}'"))
     {
+        Ok(()) => delete_unlocking(&phantom, &context, &git_root, &worktree_name, &lock, options)
+            .await
+            .with_context(|| format!("Failed to delete worktree '{worktree_name}'")),
+        Err(e) => Err(e),
+    };
+    match deleted {
         Ok(result) => {
             if args.json {
                 let json_result = DeleteResult {
                     success: true,
                     name: worktree_name,
                     message: result.message.clone(),
+                    outcome: Some(result.outcome),
                     error: None,
+                    timings_ms: timings_ms(),
                 };
-                output().json(&json_result).with_context(|| "Failed to serialize JSON output")?;
+                output().log(
+                    &serde_json::to_string_pretty(&json_result)
+                        .with_context(|| "Failed to serialize JSON output")?,
+                );
+            } else if args.current || args.print_root {
+                report_deleted_with_root(
+                    (!output().quiet).then_some(result.message.as_str()),
+                    &git_root,
+                    args.current && is_stdout_tty(),
+                    &mut io::stdout().lock(),
+                    &mut io::stderr().lock(),
+                )
+                .with_context(|| "Failed to print the repository root")?;
             } else {
                 output().log(&result.message);
             }
             Ok(())
         }
-        Err(e) => {
-            if args.json {
-                let json_result = DeleteResult {
-                    success: false,
-                    name: worktree_name,
-                    message: String::new(),
-                    error: Some(e.to_string()),
-                };
-                output().json(&json_result).with_context(|| "Failed to serialize JSON output")?;
-                Ok(())
-            } else {
-                Err(e)
+        Err(e) => report_failure(args.json, worktree_name, e),
+    }
+}
+
+/// Print `git_root` alone on stdout, for `cd "$(phantom delete --current)"`, and
+/// `message` on stderr, followed by how to use the wrapper when `hint`
+fn report_deleted_with_root(
+    message: Option<&str>,
+    git_root: &Path,
+    hint: bool,
+    stdout: &mut impl Write,
+    stderr: &mut impl Write,
+) -> io::Result<()> {
+    if let Some(message) = message {
+        writeln!(stderr, "{message}")?;
+    }
+    if hint {
+        writeln!(
+            stderr,
+            "Your shell is still in the deleted directory; next time run: cd \"$(phantom delete --current)\""
+        )?;
+    }
+    writeln!(stdout, "{}", git_root.display())
+}
+
+/// A failed delete: the JSON result with --json, otherwise the error itself
+fn report_failure(json: bool, name: String, e: anyhow::Error) -> Result<()> {
+    if !json {
+        return Err(e);
+    }
+    let json_result = DeleteResult {
+        success: false,
+        name,
+        message: String::new(),
+        outcome: None,
+        error: Some(e.to_string()),
+        timings_ms: timings_ms(),
+    };
+    output().log(
+        &serde_json::to_string_pretty(&json_result)
+            .with_context(|| "Failed to serialize JSON output")?,
+    );
+    Ok(())
+}
+
+/// Delete a worktree, unlocking it first if it is locked; the lock is restored if the delete fails
+async fn delete_unlocking<E, F, H>(
+    phantom: &Phantom<E, F>,
+    context: &HandlerContext<E, F, H>,
+    git_root: &Path,
+    name: &str,
+    lock: &LockState,
+    options: DeleteWorktreeOptions,
+) -> crate::Result<DeleteWorktreeSuccess>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let LockState::Locked { reason } = lock else {
+        return phantom.delete(name, options).await;
+    };
+
+    let path = resolve_worktree_path(&context.filesystem, git_root, name).await;
+    unlock_worktree(context.executor.clone(), git_root, &path).await?;
+    let result = phantom.delete(name, options).await;
+    if result.is_err() {
+        if let Err(e) =
+            lock_worktree(context.executor.clone(), git_root, &path, reason.as_deref()).await
+        {
+            output().warn(&format!("Could not lock '{name}' again: {e}"));
+        }
+    }
+    result
+}
+
+/// Deletion options from the force flags, --keep-branch, --snapshot and the `delete` config
+fn delete_options(args: &DeleteArgs, config: &PhantomConfig) -> DeleteWorktreeOptions {
+    DeleteWorktreeOptions {
+        force: ForceOptions {
+            dirty: args.force || args.force_dirty,
+            locked: args.force || args.force_locked,
+            unmerged: args.force,
+        },
+        keep_branch: args.keep_branch || !config.delete_removes_branch(),
+        snapshot: Some(args.snapshot.map_or_else(|| config.delete_snapshot(), Into::into)),
+    }
+}
+
+/// Ask whether to delete a worktree with uncommitted changes; without a terminal the answer is no
+fn confirm_dirty_delete(prompt: &dyn Prompt, name: &str, changed_files: usize) -> Result<bool> {
+    if !prompt.is_interactive() {
+        return Ok(false);
+    }
+    let files = if changed_files == 1 { "file" } else { "files" };
+    prompt
+        .confirm(&format!("Delete '{name}' with {changed_files} modified {files}?"))
+        .with_context(|| "Failed to read the confirmation answer")
+}
+
+/// Delete the worktrees named in `reader`, after checking that every name exists
+pub(crate) async fn delete_from_reader<R, E, F, H>(
+    reader: R,
+    args: DeleteArgs,
+    context: HandlerContext<E, F, H>,
+) -> Result<()>
+where
+    R: BufRead,
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let mut names: Vec<String> = Vec::new();
+    for line in reader.lines() {
+        let line = line.with_context(|| "Failed to read worktree names from stdin")?;
+        let name = line.trim();
+        if !name.is_empty() && !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+        }
+    }
+
+    if names.is_empty() {
+        if args.json {
+            let result = BulkDeleteResult { success: true, removed: vec![], failed: vec![] };
+            output().log(&serde_json::to_string_pretty(&result)?);
+        } else {
+            output().log("No worktree names on stdin; nothing to delete");
+        }
+        return Ok(());
+    }
+
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+    let phantom = Phantom::new(&git_root, context.executor.clone(), context.filesystem.clone());
+    let worktrees = phantom.list().await.with_context(|| "Failed to list worktrees")?.worktrees;
+
+    // Report every unknown name up front rather than stopping halfway through
+    let unknown: Vec<&str> = names
+        .iter()
+        .filter(|name| !worktrees.iter().any(|worktree| &worktree.name == *name))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        bail!("Unknown worktrees: {}. Nothing was deleted.", unknown.join(", "));
+    }
+
+    let config = context.config_or_default(&git_root).await;
+    let options = delete_options(&args, &config);
+    let hooks = config.hooks_enabled();
+    let mut removed = Vec::new();
+    let mut failed = Vec::new();
+    for name in names {
+        let branch =
+            worktrees.iter().find(|worktree| worktree.name == name).and_then(|w| w.branch.clone());
+        if let Err(e) = run_pre_delete_hook(&context, &git_root, &name, hooks, args.force).await {
+            failed.push(BulkDeleteEntry { name, branch, reason: Some(e.to_string()) });
+            continue;
+        }
+        match phantom.delete(&name, options.clone()).await {
+            Ok(_) => removed.push(BulkDeleteEntry { name, branch, reason: None }),
+            Err(e) => failed.push(BulkDeleteEntry { name, branch, reason: Some(e.to_string()) }),
+        }
+    }
+
+    if args.json {
+        let result = BulkDeleteResult { success: failed.is_empty(), removed, failed };
+        output().log(&serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    for entry in &removed {
+        output().log(&format!("Deleted '{}'", entry.name));
+    }
+    for entry in &failed {
+        output().error(&format!(
+            "Failed to delete '{}': {}",
+            entry.name,
+            entry.reason.as_deref().unwrap_or_default()
+        ));
+    }
+    if !failed.is_empty() {
+        bail!("Deleted {} of {} worktrees", removed.len(), removed.len() + failed.len());
+    }
+    Ok(())
+}
+
+/// Delete every clean worktree whose branch is merged into the target or whose upstream is gone
+async fn prune<E, F, H>(args: DeleteArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let into = match args.into.clone() {
+        Some(into) => into,
+        None => default_branch(context.executor.clone(), &git_root)
+            .await
+            .with_context(|| "Failed to determine the default branch")?
+            .context("Could not determine the default branch; pass --into <ref>")?,
+    };
+
+    let phantom = Phantom::new(&git_root, context.executor.clone(), context.filesystem.clone());
+    let list_options = ListOptions { check_gone: true, ..ListOptions::default() };
+    let worktrees = phantom
+        .list_with_options(list_options)
+        .await
+        .with_context(|| "Failed to list worktrees")?
+        .worktrees;
+    let config = context.config_or_default(&git_root).await;
+    let mut options = delete_options(&args, &config);
+    // Only branches merged into --into, or deleted upstream after a merge, are deleted, so git's own merge check is redundant
+    options.force.unmerged = true;
+
+    let hooks = config.hooks_enabled();
+    let mut removed = Vec::new();
+    let mut removed_gone = Vec::new();
+    let mut kept = Vec::new();
+    for worktree in worktrees {
+        let keep = |reason: String| BulkDeleteEntry {
+            name: worktree.name.clone(),
+            branch: worktree.branch.clone(),
+            reason: Some(reason),
+        };
+        let Some(branch) = worktree.branch.as_deref() else {
+            kept.push(keep("detached HEAD".to_string()));
+            continue;
+        };
+        if branch == into {
+            kept.push(keep(format!("is the target branch '{into}'")));
+            continue;
+        }
+        // A squash or rebase merge leaves the branch unmerged, but its upstream is gone
+        let merged = worktree.gone
+            || is_branch_merged(context.executor.clone(), &git_root, branch, &into)
+                .await
+                .with_context(|| {
+                    format!("Failed to check whether '{branch}' is merged into '{into}'")
+                })?;
+        if !merged {
+            kept.push(keep(format!("not merged into '{into}'")));
+            continue;
+        }
+        if !worktree.is_clean && !options.force.dirty {
+            kept.push(keep("uncommitted changes (use --force to delete anyway)".to_string()));
+            continue;
+        }
+        if !args.dry_run {
+            if let Err(e) =
+                run_pre_delete_hook(&context, &git_root, &worktree.name, hooks, args.force).await
+            {
+                kept.push(keep(e.to_string()));
+                continue;
+            }
+            if let Err(e) = phantom.delete(&worktree.name, options.clone()).await {
+                kept.push(keep(e.to_string()));
+                continue;
             }
         }
+        if worktree.gone {
+            removed_gone.push(worktree.name.clone());
+        }
+        removed.push(BulkDeleteEntry {
+            name: worktree.name.clone(),
+            branch: worktree.branch.clone(),
+            reason: None,
+        });
+    }
+
+    if args.json {
+        let result = PruneResult { success: true, into, dry_run: args.dry_run, removed, kept };
+        output().log(&serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    let verb = if args.dry_run { "Would remove" } else { "Removed" };
+    for entry in &removed {
+        if removed_gone.contains(&entry.name) {
+            output().log(&format!("{verb} '{}' (upstream gone)", entry.name));
+        } else {
+            output().log(&format!("{verb} '{}' (merged into '{into}')", entry.name));
+        }
+    }
+    for entry in &kept {
+        output().log(&format!(
+            "Kept '{}': {}",
+            entry.name,
+            entry.reason.as_deref().unwrap_or_default()
+        ));
+    }
+    if removed.is_empty() {
+        output().log(&format!("No merged worktrees to remove (checked against '{into}')"));
+    }
+    Ok(())
+}
+
+/// Run the repository's pre-delete hook; a failing hook stops the deletion unless `force` is set
+async fn run_pre_delete_hook<E, F, H>(
+    context: &HandlerContext<E, F, H>,
+    git_root: &Path,
+    name: &str,
+    enabled: bool,
+    force: bool,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    if !enabled {
+        return Ok(());
+    }
+    let path = resolve_worktree_path(&context.filesystem, git_root, name).await;
+    let failure = match run_hook(
+        context.executor.clone(),
+        &context.filesystem,
+        &*context.environment,
+        git_root,
+        Hook::PreDelete,
+        name,
+        &path,
+    )
+    .await
+    {
+        Ok(None) | Ok(Some(0)) => return Ok(()),
+        Ok(Some(code)) => format!("pre-delete hook exited with code {code}"),
+        // A directory removed by hand leaves nothing for the hook to run in
+        Err(_) if matches!(context.filesystem.is_dir(&path).await, Ok(false)) => return Ok(()),
+        Err(e) => format!("Failed to run pre-delete hook: {e}"),
+    };
+
+    if force {
+        output().warn(&format!("{failure}; deleting '{name}' anyway (--force)"));
+        Ok(())
+    } else {
+        bail!("{failure}; '{name}' was not deleted (use --force to delete anyway)")
     }
 }
 
@@ -115,6 +552,7 @@ mod tests {
     use super::*;
     use crate::cli::commands::delete::DeleteArgs;
     use crate::cli::context::HandlerContext;
+    use crate::cli::output::MockPrompt;
     use crate::core::executors::MockCommandExecutor;
     use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
     use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
@@ -150,14 +588,50 @@ mod tests {
             name: Some("test".to_string()),
             current: false,
             force: false,
+            force_dirty: false,
+            force_locked: false,
+            keep_branch: false,
+            snapshot: None,
             fzf: false,
             json: false,
+            workspace: false,
+            prune: false,
+            into: None,
+            dry_run: false,
+            stdin_names: false,
+            print_root: false,
+            yes: false,
         };
 
         let result = handle(args, context).await;
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_deleted_with_root_keeps_stdout_to_the_path() {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        report_deleted_with_root(
+            Some("Deleted worktree 'feature'"),
+            Path::new("/repo"),
+            true,
+            &mut stdout,
+            &mut stderr,
+        )
+        .unwrap();
+
+        // `cd "$(phantom delete --current)"` sees the root and nothing else
+        assert_eq!(String::from_utf8(stdout).unwrap(), "/repo\n");
+        let stderr = String::from_utf8(stderr).unwrap();
+        assert!(stderr.starts_with("Deleted worktree 'feature'\n"), "{stderr}");
+        assert!(stderr.contains(r#"cd "$(phantom delete --current)""#), "{stderr}");
+
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        report_deleted_with_root(None, Path::new("/repo"), false, &mut stdout, &mut stderr)
+            .unwrap();
+        assert_eq!(String::from_utf8(stdout).unwrap(), "/repo\n");
+        assert!(stderr.is_empty());
+    }
+
     #[tokio::test]
     async fn test_delete_with_current_flag_not_in_worktree() {
         let mut mock = MockCommandExecutor::new();
@@ -174,18 +648,33 @@ mod tests {
             .with_args(&["rev-parse", "--show-toplevel"])
             .returns_output("/repo", "", 0);
 
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output("worktree /repo\0HEAD abc123\0branch refs/heads/main\0", "", 0);
 
         let context = HandlerContext::new(
             mock,
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
         );
-        let args = DeleteArgs { name: None, current: true, force: false, fzf: false, json: false };
+        let args = DeleteArgs {
+            name: None,
+            current: true,
+            force: false,
+            force_dirty: false,
+            force_locked: false,
+            keep_branch: false,
+            snapshot: None,
+            fzf: false,
+            json: false,
+            workspace: false,
+            prune: false,
+            into: None,
+            dry_run: false,
+            stdin_names: false,
+            print_root: false,
+            yes: false,
+        };
 
         let result = handle(args, context).await;
         assert!(result.is_err());
@@ -205,10 +694,10 @@ mod tests {
 
         // Mock validate_worktree_exists (via list_worktrees)
         mock.expect_command("git")
-                .with_args(&["worktree", "list", "--porcelain"])
+                .with_args(&["worktree", "list", "--porcelain", "-z"])
                 .returns_output(
-                    "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n\n\
-                     worktree /repo/.git/phantom/worktrees/feature\nHEAD def456\nbranch refs/heads/feature\n",
+                    "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                     worktree /repo/.git/phantom/worktrees/feature\0HEAD def456\0branch refs/heads/feature\0",
                     "",
                     0
                 );
@@ -237,7 +726,7 @@ mod tests {
 
         // Mock delete_branch
         mock.expect_command("git")
-            .with_args(&["branch", "-D", "feature"])
+            .with_args(&["branch", "-d", "feature"])
             .in_dir("/repo")
             .returns_success();
 
@@ -247,14 +736,108 @@ mod tests {
             name: Some("feature".to_string()),
             current: false,
             force: false,
+            force_dirty: false,
+            force_locked: false,
+            keep_branch: false,
+            snapshot: None,
             fzf: false,
             json: false,
+            workspace: false,
+            prune: false,
+            into: None,
+            dry_run: false,
+            stdin_names: false,
+            print_root: false,
+            yes: false,
         };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_delete_stack_parent_warns_and_clears_metadata() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+
+        // Stack lookup: feature-2 is recorded as stacked on this worktree's branch
+        mock.expect_command("git")
+            .with_args(&["branch", "--show-current"])
+            .in_dir("/repo/.git/phantom/worktrees/feature")
+            .returns_output("feature\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["config", "--get-regexp", r"^phantom\..*\.stackparent$"])
+            .in_dir("/repo")
+            .returns_output("phantom.feature-2.stackparent feature\n", "", 0);
+
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree /repo/.git/phantom/worktrees/feature\0HEAD def456\0branch refs/heads/feature\0",
+                "",
+                0,
+            );
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/feature")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir("/repo/.git/phantom/worktrees/feature")
+            .returns_output("", "", 0);
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "/repo/.git/phantom/worktrees/feature"])
+            .in_dir("/repo")
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["branch", "-d", "feature"])
+            .in_dir("/repo")
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["config", "--unset", "phantom.feature.stackParent"])
+            .in_dir("/repo")
+            .returns_output("", "", 5);
+
+        let calls = mock.clone();
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+        let args = DeleteArgs {
+            name: Some("feature".to_string()),
+            current: false,
+            force: false,
+            force_dirty: false,
+            force_locked: false,
+            keep_branch: false,
+            snapshot: None,
+            fzf: false,
+            json: false,
+            workspace: false,
+            prune: false,
+            into: None,
+            dry_run: false,
+            stdin_names: false,
+            print_root: false,
+            yes: false,
+        };
+
+        let result = handle(args, context).await;
+        assert!(result.is_ok(), "{result:?}");
+        let commands: Vec<_> = calls.calls().into_iter().map(|call| call.args.join(" ")).collect();
+        assert!(commands.iter().any(|c| c.starts_with("config --get-regexp")), "{commands:?}");
+        assert!(commands.contains(&"config --unset phantom.feature.stackParent".to_string()));
+    }
+
     #[tokio::test]
     async fn test_delete_worktree_with_uncommitted_changes_no_force() {
         let mut mock = MockCommandExecutor::new();
@@ -269,10 +852,10 @@ mod tests {
 
         // Mock validate_worktree_exists
         mock.expect_command("git")
-                .with_args(&["worktree", "list", "--porcelain"])
+                .with_args(&["worktree", "list", "--porcelain", "-z"])
                 .returns_output(
-                    "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n\n\
-                     worktree /repo/.git/phantom/worktrees/feature\nHEAD def456\nbranch refs/heads/feature\n",
+                    "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                     worktree /repo/.git/phantom/worktrees/feature\0HEAD def456\0branch refs/heads/feature\0",
                     "",
                     0
                 );
@@ -299,11 +882,25 @@ mod tests {
             name: Some("feature".to_string()),
             current: false,
             force: false,
+            force_dirty: false,
+            force_locked: false,
+            keep_branch: false,
+            snapshot: None,
             fzf: false,
             json: false,
+            workspace: false,
+            prune: false,
+            into: None,
+            dry_run: false,
+            stdin_names: false,
+            print_root: false,
+            yes: false,
         };
 
-        let result = handle(args, context).await;
+        // Without a terminal the confirmation counts as declined
+        let prompt = MockPrompt::new(false, true);
+        let result = handle_with_prompt(args, context, &prompt).await;
+        assert!(prompt.asked().is_empty());
         assert!(result.is_err());
         match result {
             Err(e) => {
@@ -332,10 +929,10 @@ mod tests {
 
         // Mock validate_worktree_exists
         mock.expect_command("git")
-                .with_args(&["worktree", "list", "--porcelain"])
+                .with_args(&["worktree", "list", "--porcelain", "-z"])
                 .returns_output(
-                    "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n\n\
-                     worktree /repo/.git/phantom/worktrees/feature\nHEAD def456\nbranch refs/heads/feature\n",
+                    "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                     worktree /repo/.git/phantom/worktrees/feature\0HEAD def456\0branch refs/heads/feature\0",
                     "",
                     0
                 );
@@ -380,8 +977,19 @@ mod tests {
             name: Some("feature".to_string()),
             current: false,
             force: true,
+            force_dirty: false,
+            force_locked: false,
+            keep_branch: false,
+            snapshot: None,
             fzf: false,
             json: false,
+            workspace: false,
+            prune: false,
+            into: None,
+            dry_run: false,
+            stdin_names: false,
+            print_root: false,
+            yes: false,
         };
 
         let result = handle(args, context).await;
@@ -402,10 +1010,10 @@ mod tests {
 
         // Mock validate_worktree_exists
         mock.expect_command("git")
-                .with_args(&["worktree", "list", "--porcelain"])
+                .with_args(&["worktree", "list", "--porcelain", "-z"])
                 .returns_output(
-                    "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n\n\
-                     worktree /repo/.git/phantom/worktrees/feature\nHEAD def456\nbranch refs/heads/feature\n",
+                    "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                     worktree /repo/.git/phantom/worktrees/feature\0HEAD def456\0branch refs/heads/feature\0",
                     "",
                     0
                 );
@@ -434,7 +1042,7 @@ mod tests {
 
         // Mock delete_branch
         mock.expect_command("git")
-            .with_args(&["branch", "-D", "feature"])
+            .with_args(&["branch", "-d", "feature"])
             .in_dir("/repo")
             .returns_success();
 
@@ -444,12 +1052,567 @@ mod tests {
             name: Some("feature".to_string()),
             current: false,
             force: false,
+            force_dirty: false,
+            force_locked: false,
+            keep_branch: false,
+            snapshot: None,
             fzf: false,
             json: true,
+            workspace: false,
+            prune: false,
+            into: None,
+            dry_run: false,
+            stdin_names: false,
+            print_root: false,
+            yes: false,
         };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
         // In JSON mode, success is communicated via JSON output
     }
+
+    /// Mocks for deleting the dirty worktree 'feature', up to its removal
+    fn dirty_feature_mock() -> (MockCommandExecutor, MockFileSystem) {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir("/repo/.git/phantom/worktrees/feature")
+            .returns_output(" M a.rs\n M b.rs\n?? c.rs\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "--force", "/repo/.git/phantom/worktrees/feature"])
+            .in_dir("/repo")
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["branch", "-d", "feature"])
+            .in_dir("/repo")
+            .returns_success();
+
+        let mock_fs = MockFileSystem::new();
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/feature")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        (mock, mock_fs)
+    }
+
+    fn dirty_feature_args() -> DeleteArgs {
+        DeleteArgs {
+            name: Some("feature".to_string()),
+            json: false,
+            prune: false,
+            into: None,
+            ..prune_args(false)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_dirty_worktree_asks_for_confirmation() {
+        for answer in [true, false] {
+            let (mut mock, mock_fs) = dirty_feature_mock();
+            mock.expect_command("git")
+                .with_args(&["worktree", "remove", "/repo/.git/phantom/worktrees/feature"])
+                .in_dir("/repo")
+                .returns_output("", "fatal: contains modified or untracked files", 128);
+            let context = HandlerContext::new(
+                mock.clone(),
+                mock_fs,
+                crate::core::exit_handler::MockExitHandler::new(),
+            );
+
+            let prompt = MockPrompt::new(true, answer);
+            let result = handle_with_prompt(dirty_feature_args(), context, &prompt).await;
+
+            assert_eq!(prompt.asked(), ["Delete 'feature' with 3 modified files?"]);
+            if answer {
+                result.unwrap();
+                // The refused plain removal is retried with --force
+                assert_eq!(removed_worktrees(&mock).len(), 2);
+            } else {
+                let error = result.unwrap_err().to_string();
+                assert!(error.contains("was not deleted"), "{error}");
+                assert!(removed_worktrees(&mock).is_empty());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_dirty_worktree_with_yes_skips_the_prompt() {
+        let (mut mock, mock_fs) = dirty_feature_mock();
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "/repo/.git/phantom/worktrees/feature"])
+            .in_dir("/repo")
+            .returns_success();
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let prompt = MockPrompt::new(true, false);
+        let args = DeleteArgs { yes: true, ..dirty_feature_args() };
+        handle_with_prompt(args, context, &prompt).await.unwrap();
+
+        assert!(prompt.asked().is_empty());
+        assert_eq!(removed_worktrees(&mock), ["/repo/.git/phantom/worktrees/feature"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_keep_branch_skips_branch_deletion() {
+        let (mock, mock_fs) = dirty_feature_mock();
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let args = DeleteArgs { force: true, keep_branch: true, ..dirty_feature_args() };
+        handle_with_prompt(args, context, &MockPrompt::new(true, false)).await.unwrap();
+
+        let branch_deletions = mock
+            .calls()
+            .into_iter()
+            .filter(|call| call.args.first().is_some_and(|arg| arg == "branch"))
+            .filter(|call| call.args.get(1).is_some_and(|arg| arg == "-d" || arg == "-D"))
+            .count();
+        assert_eq!(branch_deletions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_delete_dirty_worktree_with_json_never_prompts() {
+        let (mock, mock_fs) = dirty_feature_mock();
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let prompt = MockPrompt::new(true, true);
+        let args = DeleteArgs { json: true, ..dirty_feature_args() };
+        // The failure is reported in the JSON output
+        handle_with_prompt(args, context, &prompt).await.unwrap();
+
+        assert!(prompt.asked().is_empty());
+        assert!(removed_worktrees(&mock).is_empty());
+    }
+
+    /// Mocks for deleting 'feature', locked with a reason and with `status` as its changes
+    fn locked_feature_mock(status: &str) -> (MockCommandExecutor, MockFileSystem) {
+        const PATH: &str = "/repo/.git/phantom/worktrees/feature";
+        let (_, mock_fs) = dirty_feature_mock();
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree /repo/.git/phantom/worktrees/feature\0HEAD def456\0branch refs/heads/feature\0locked waiting on review\0\0",
+                "",
+                0,
+            );
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir(PATH)
+            .returns_output(status, "", 0);
+        for args in [
+            vec!["worktree", "unlock", PATH],
+            vec!["worktree", "lock", "--reason", "waiting on review", PATH],
+            vec!["worktree", "remove", PATH],
+            vec!["branch", "-d", "feature"],
+        ] {
+            mock.expect_command("git").with_args(&args).in_dir("/repo").returns_success();
+        }
+        (mock, mock_fs)
+    }
+
+    /// The `git worktree` subcommands run, in order
+    fn worktree_commands(mock: &MockCommandExecutor) -> Vec<String> {
+        mock.calls()
+            .into_iter()
+            .filter(|call| call.args.first().is_some_and(|arg| arg == "worktree"))
+            .map(|call| call.args[1].clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_delete_locked_worktree_is_refused_with_its_reason() {
+        let (mock, mock_fs) = locked_feature_mock("");
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let err = handle_with_prompt(dirty_feature_args(), context, &MockPrompt::new(true, true))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<PhantomError>(),
+            Some(PhantomError::WorktreeLocked { reason: Some(reason), .. }) if reason == "waiting on review"
+        ));
+        let message = err.to_string();
+        assert!(
+            message.contains("phantom unlock feature") && message.contains("--force"),
+            "{message}"
+        );
+        assert_eq!(worktree_commands(&mock), ["list"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_locked_worktree_with_force_unlocks_then_deletes() {
+        let (mock, mock_fs) = locked_feature_mock("");
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let args = DeleteArgs { force: true, ..dirty_feature_args() };
+        handle_with_prompt(args, context, &MockPrompt::new(true, false)).await.unwrap();
+
+        assert_eq!(worktree_commands(&mock), ["list", "unlock", "remove"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_delete_of_locked_worktree_locks_it_again() {
+        // --force-locked alone still refuses uncommitted changes
+        let (mock, mock_fs) = locked_feature_mock(" M a.rs\n");
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let args = DeleteArgs { force_locked: true, json: true, ..dirty_feature_args() };
+        handle_with_prompt(args, context, &MockPrompt::new(true, true)).await.unwrap();
+
+        assert_eq!(worktree_commands(&mock), ["list", "unlock", "lock"]);
+    }
+
+    fn prune_mock() -> MockCommandExecutor {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree /repo/.git/phantom/worktrees/merged\0HEAD def456\0branch refs/heads/merged\0\0\
+                 worktree /repo/.git/phantom/worktrees/dirty\0HEAD fed654\0branch refs/heads/dirty\0\0\
+                 worktree /repo/.git/phantom/worktrees/open\0HEAD 123abc\0branch refs/heads/open\0",
+                "",
+                0,
+            );
+        for (name, status, merged_exit) in
+            [("merged", "", 0), ("dirty", " M src/lib.rs\n", 0), ("open", "", 1)]
+        {
+            mock.expect_command("git")
+                .with_args(&["status", "--porcelain"])
+                .in_dir(format!("/repo/.git/phantom/worktrees/{name}"))
+                .returns_output(status, "", 0);
+            mock.expect_command("git")
+                .with_args(&["merge-base", "--is-ancestor", name, "main"])
+                .in_dir("/repo")
+                .returns_output("", "", merged_exit);
+        }
+        mock
+    }
+
+    fn prune_args(dry_run: bool) -> DeleteArgs {
+        DeleteArgs {
+            name: None,
+            current: false,
+            force: false,
+            force_dirty: false,
+            force_locked: false,
+            keep_branch: false,
+            snapshot: None,
+            fzf: false,
+            json: true,
+            workspace: false,
+            prune: true,
+            into: Some("main".to_string()),
+            dry_run,
+            stdin_names: false,
+            print_root: false,
+            yes: false,
+        }
+    }
+
+    fn removed_worktrees(mock: &MockCommandExecutor) -> Vec<String> {
+        mock.calls()
+            .into_iter()
+            .filter(|call| call.args.starts_with(&["worktree".to_string(), "remove".to_string()]))
+            .map(|call| call.args[2].clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_prune_dry_run_deletes_nothing() {
+        let mock = prune_mock();
+        let context = HandlerContext::new(
+            mock.clone(),
+            MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        handle(prune_args(true), context).await.unwrap();
+        assert!(removed_worktrees(&mock).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prune_removes_only_merged_clean_worktrees() {
+        let mut mock = prune_mock();
+        let mock_fs = MockFileSystem::new();
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/merged")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "/repo/.git/phantom/worktrees/merged"])
+            .in_dir("/repo")
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["branch", "-D", "merged"])
+            .in_dir("/repo")
+            .returns_success();
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        handle(prune_args(false), context).await.unwrap();
+        assert_eq!(removed_worktrees(&mock), ["/repo/.git/phantom/worktrees/merged"]);
+    }
+
+    #[tokio::test]
+    async fn test_prune_removes_worktrees_whose_upstream_is_gone() {
+        let mut mock = prune_mock();
+        mock.expect_command("git")
+            .with_args(&[
+                "for-each-ref",
+                "--format=%(refname:short)%00%(upstream:remotename)%00%(upstream:remoteref)%00%(upstream:track)",
+                "refs/heads",
+            ])
+            .returns_output("open\0origin\0refs/heads/open\0[gone]\n", "", 0);
+        let mock_fs = MockFileSystem::new();
+        for name in ["merged", "open"] {
+            let path = format!("/repo/.git/phantom/worktrees/{name}");
+            mock_fs.expect(FileSystemExpectation {
+                operation: FileSystemOperation::IsDir,
+                path: Some(PathBuf::from(&path)),
+                from_path: None,
+                to_path: None,
+                contents: None,
+                result: Ok(MockResult::Bool(true)),
+            });
+            mock.expect_command("git").with_args(&["worktree", "remove", &path]).returns_success();
+            mock.expect_command("git").with_args(&["branch", "-D", name]).returns_success();
+        }
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        handle(prune_args(false), context).await.unwrap();
+        assert_eq!(
+            removed_worktrees(&mock),
+            ["/repo/.git/phantom/worktrees/merged", "/repo/.git/phantom/worktrees/open"]
+        );
+    }
+
+    fn stdin_args() -> DeleteArgs {
+        DeleteArgs { prune: false, into: None, stdin_names: true, ..prune_args(false) }
+    }
+
+    #[tokio::test]
+    async fn test_stdin_names_empty_input_is_a_no_op() {
+        let mock = MockCommandExecutor::new();
+        let context = HandlerContext::new(
+            mock.clone(),
+            MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        delete_from_reader("\n  \n".as_bytes(), stdin_args(), context).await.unwrap();
+        assert!(mock.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stdin_names_reports_unknown_names_before_deleting() {
+        let mock = prune_mock();
+        let context = HandlerContext::new(
+            mock.clone(),
+            MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let err = delete_from_reader("merged\nnope\n\ngone\n".as_bytes(), stdin_args(), context)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Unknown worktrees: nope, gone. Nothing was deleted.");
+        assert!(removed_worktrees(&mock).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stdin_names_deletes_each_listed_worktree() {
+        let mut mock = prune_mock();
+        let mock_fs = MockFileSystem::new();
+        for name in ["merged", "open"] {
+            let path = format!("/repo/.git/phantom/worktrees/{name}");
+            mock_fs.expect(FileSystemExpectation {
+                operation: FileSystemOperation::IsDir,
+                path: Some(PathBuf::from(&path)),
+                from_path: None,
+                to_path: None,
+                contents: None,
+                result: Ok(MockResult::Bool(true)),
+            });
+            mock.expect_command("git")
+                .with_args(&["worktree", "remove", &path])
+                .in_dir("/repo")
+                .returns_success();
+            mock.expect_command("git")
+                .with_args(&["branch", "-d", name])
+                .in_dir("/repo")
+                .returns_success();
+        }
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        delete_from_reader("  merged \n\nopen\nmerged\n".as_bytes(), stdin_args(), context)
+            .await
+            .unwrap();
+        assert_eq!(
+            removed_worktrees(&mock),
+            ["/repo/.git/phantom/worktrees/merged", "/repo/.git/phantom/worktrees/open"]
+        );
+    }
+
+    /// Expect an executable pre-delete hook that exits with `exit_code` for each of `names`
+    fn expect_pre_delete_hook(
+        mock: &mut MockCommandExecutor,
+        mock_fs: &MockFileSystem,
+        names: &[(&str, i32)],
+    ) {
+        for (name, exit_code) in names {
+            let path = format!("/repo/.git/phantom/worktrees/{name}");
+            for operation in [FileSystemOperation::IsExecutable, FileSystemOperation::IsDir] {
+                let path = match operation {
+                    FileSystemOperation::IsExecutable => "/repo/.phantom/hooks/pre-delete",
+                    _ => path.as_str(),
+                };
+                mock_fs.expect(FileSystemExpectation {
+                    operation,
+                    path: Some(PathBuf::from(path)),
+                    from_path: None,
+                    to_path: None,
+                    contents: None,
+                    result: Ok(MockResult::Bool(true)),
+                });
+            }
+            mock.expect_command("/repo/.phantom/hooks/pre-delete")
+                .with_args(&[name, &path])
+                .in_dir(&path)
+                .times(1)
+                .returns_output("", "", *exit_code);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pre_delete_hook_runs_before_removal_and_can_block_it() {
+        let mut mock = prune_mock();
+        let mock_fs = MockFileSystem::new();
+        expect_pre_delete_hook(&mut mock, &mock_fs, &[("merged", 1), ("open", 0)]);
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/open")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "/repo/.git/phantom/worktrees/open"])
+            .in_dir("/repo")
+            .returns_success();
+        mock.expect_command("git").with_args(&["branch", "-d", "open"]).returns_success();
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let result = delete_from_reader("merged\nopen\n".as_bytes(), stdin_args(), context).await;
+        assert!(result.is_ok(), "JSON output reports failures without erroring");
+        assert_eq!(removed_worktrees(&mock), ["/repo/.git/phantom/worktrees/open"]);
+
+        let calls = mock.calls();
+        let hook = calls
+            .iter()
+            .position(|call| call.program.ends_with("pre-delete") && call.args[0] == "open")
+            .unwrap();
+        let remove = calls
+            .iter()
+            .position(|call| call.args.starts_with(&["worktree".to_string(), "remove".to_string()]))
+            .unwrap();
+        assert!(hook < remove);
+    }
+
+    #[tokio::test]
+    async fn test_pre_delete_hook_failure_is_ignored_with_force() {
+        let mut mock = prune_mock();
+        let mock_fs = MockFileSystem::new();
+        expect_pre_delete_hook(&mut mock, &mock_fs, &[("merged", 1)]);
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/merged")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "/repo/.git/phantom/worktrees/merged"])
+            .in_dir("/repo")
+            .returns_success();
+        mock.expect_command("git").with_args(&["branch", "-D", "merged"]).returns_success();
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let args = DeleteArgs { force: true, ..stdin_args() };
+        delete_from_reader("merged\n".as_bytes(), args, context).await.unwrap();
+        assert_eq!(removed_worktrees(&mock), ["/repo/.git/phantom/worktrees/merged"]);
+    }
 }
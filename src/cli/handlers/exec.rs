@@ -1,18 +1,48 @@
+use crate::api::Phantom;
 use crate::cli::commands::exec::ExecArgs;
 use crate::cli::context::HandlerContext;
+use crate::cli::handlers::workspace;
 use crate::cli::output::output;
-use crate::core::command_executor::CommandExecutor;
+use crate::core::command_executor::{CommandExecutor, CommandOutput, OutputStream};
+use crate::core::env_map::{EnvMap, EnvSources};
 use crate::core::exit_handler::ExitHandler;
 use crate::core::filesystem::FileSystem;
-use crate::git::libs::get_git_root::get_git_root;
-use crate::process::exec::exec_in_worktree;
+use crate::process::envfile::{load_env_file, DEFAULT_ENV_FILE};
+use crate::process::exec::{ensure_command_exists, resolve_worktree_dir};
 use crate::process::kitty::{
-    execute_kitty_command, is_inside_kitty, KittyOptions, KittySplitDirection,
+    execute_kitty_command, execute_kitty_command_and_wait, is_inside_kitty, KittyOptions,
+    KittySplitDirection,
 };
-use crate::process::shell::get_phantom_env;
-use crate::process::tmux::{execute_tmux_command, is_inside_tmux, TmuxOptions, TmuxSplitDirection};
+use crate::process::multiplexer::{resolve_launch_target, LaunchTarget};
+use crate::process::shell::{detect_shell_with_config, get_phantom_env, shell_command, ShellType};
+use crate::process::title::TerminalTitle;
+use crate::process::tmux::{
+    execute_tmux_command, execute_tmux_command_and_wait, is_inside_tmux, TmuxOptions,
+    TmuxSplitDirection,
+};
+use crate::process::tty::is_stdout_tty;
+use crate::worktree::in_progress::detect_in_progress_operation;
+use crate::worktree::last_used::{read_last_worktree, record_last_worktree};
 use crate::worktree::validate::validate_worktree_exists;
 use anyhow::{anyhow, bail, Context, Result};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How often `--wait` checks whether a tmux pane has exited
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Result of `exec --capture --json`
+#[derive(Serialize)]
+struct ExecJsonOutput<'a> {
+    worktree: &'a str,
+    command: Vec<&'a str>,
+    exit_code: i32,
+    stdout: &'a str,
+    stderr: &'a str,
+    duration_ms: u64,
+}
 
 /// Handle the exec command
 pub async fn handle<E, F, H>(args: ExecArgs, context: HandlerContext<E, F, H>) -> Result<()>
@@ -22,14 +52,14 @@ where
     H: ExitHandler + Clone + 'static,
 {
     // Parse command from arguments
-    let (worktree_name_pos, command_args) = if args.fzf {
-        // With --fzf, all args are command args
-        (None, args.command)
+    let (worktree_name_pos, command_args) = if args.fzf || args.last {
+        // With --fzf or --last, all args are command args
+        (None, args.name.into_iter().chain(args.command).collect())
     } else {
-        // Without --fzf, first arg is worktree name
+        // Otherwise, first arg is worktree name
         if args.command.is_empty() {
             bail!(
-                "Usage: phantom exec <worktree-name> <command> [args...] or phantom exec --fzf <command> [args...]"
+                "Usage: phantom exec <worktree-name> <command> [args...] or phantom exec --fzf|--last <command> [args...]"
             );
         }
 
@@ -51,6 +81,39 @@ where
         bail!("No command specified");
     }
 
+    let extra_env = EnvMap::parse_assignments(&args.env).map_err(|e| anyhow!(e))?;
+
+    let via_shell = args.shell || (!args.argv && is_shell_command_line(&command_args));
+    let command_args = if via_shell {
+        // Outside a repository (e.g. a workspace root) there is no `shell` setting to honor
+        let configured = match context.git_root().await {
+            Ok(git_root) => context
+                .config(&git_root)
+                .await
+                .with_context(|| "Failed to load config")?
+                .and_then(|loaded| loaded.config.shell.clone()),
+            Err(_) => None,
+        };
+        let shell_info = detect_shell_with_config(&*context.environment, configured.as_deref())
+            .with_context(|| "Failed to detect shell")?;
+        let (program, shell_args) = shell_command(&shell_info, &command_args.join(" "));
+        std::iter::once(program).chain(shell_args).collect()
+    } else {
+        command_args
+    };
+
+    if args.workspace {
+        let worktree_name = worktree_name_pos.expect("--workspace conflicts with --fzf");
+        return workspace::exec(
+            &worktree_name,
+            &command_args,
+            &extra_env,
+            args.even_if_busy,
+            context,
+        )
+        .await;
+    }
+
     // Determine tmux direction
     let tmux_direction = if args.tmux {
         Some(TmuxSplitDirection::New)
@@ -74,25 +137,64 @@ where
     };
 
     // Validate multiplexer options
-    if tmux_direction.is_some() && !is_inside_tmux().await {
+    if tmux_direction.is_some() && !is_inside_tmux(&*context.environment).await {
         bail!("The --tmux option can only be used inside a tmux session");
     }
 
-    if kitty_direction.is_some() && !is_inside_kitty().await {
+    if kitty_direction.is_some() && !is_inside_kitty(&*context.environment).await {
         bail!("The --kitty option can only be used inside a kitty terminal");
     }
 
+    // clap already rejects --tmux-size with --tmux
+    if args.tmux_size.is_some() && tmux_direction.is_none() {
+        bail!("The --tmux-size option can only be used with a --tmux-vertical or --tmux-horizontal option");
+    }
+
+    if args.no_focus && kitty_direction.is_none() {
+        bail!("The --no-focus option can only be used with a --kitty option");
+    }
+
+    if args.tmux_target.is_some() && tmux_direction.is_none() {
+        bail!("The --tmux-target option can only be used with a --tmux option");
+    }
+
+    if args.wait && tmux_direction.is_none() && kitty_direction.is_none() {
+        bail!("The --wait option can only be used with a --tmux or --kitty option");
+    }
+
     // Get git root
-    let git_root = get_git_root(context.executor.clone())
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let config = context
+        .config(&git_root)
         .await
-        .with_context(|| "Failed to determine git repository root")?;
+        .with_context(|| "Failed to load config")?
+        .map(|loaded| loaded.config.clone())
+        .unwrap_or_default();
+
+    // Without --tmux or --kitty flags, open in the configured default multiplexer
+    let explicit = LaunchTarget::from_flags(tmux_direction, kitty_direction);
+    let target = resolve_launch_target(
+        &config,
+        &*context.environment,
+        explicit,
+        args.no_multiplexer || args.capture,
+    )
+    .await;
+    let (tmux_direction, kitty_direction) = (target.tmux(), target.kitty());
 
     // Get worktree name
     let worktree_name = if args.fzf {
         use crate::worktree::select::select_worktree_with_fzf;
-        match select_worktree_with_fzf(context.executor.clone(), &git_root)
-            .await
-            .with_context(|| "Failed to select worktree with fzf")?
+        match select_worktree_with_fzf(
+            context.executor.clone(),
+            &context.filesystem,
+            &git_root,
+            &config,
+        )
+        .await
+        .with_context(|| "Failed to select worktree with fzf")?
         {
             Some(worktree) => worktree.name,
             None => {
@@ -100,19 +202,62 @@ where
                 return Ok(());
             }
         }
+    } else if args.last {
+        read_last_worktree(&context.filesystem, &git_root)
+            .await
+            .ok_or_else(|| anyhow!("No worktree has been used yet; pick one with --fzf"))?
     } else {
         worktree_name_pos.unwrap()
     };
 
     // Validate worktree exists
-    let validation = validate_worktree_exists(&git_root, &worktree_name, &context.filesystem)
-        .await
-        .with_context(|| format!("Failed to validate worktree '{worktree_name}' exists"))?;
+    let validation =
+        match validate_worktree_exists(&git_root, &worktree_name, &context.filesystem).await {
+            Err(_) if args.last => bail!(
+                "The last used worktree '{worktree_name}' no longer exists; pick one with --fzf"
+            ),
+            result => result
+                .with_context(|| format!("Failed to validate worktree '{worktree_name}' exists"))?,
+        };
     let worktree_path = validation.path;
+    record_last_worktree(&context.filesystem, &git_root, &worktree_name).await;
+    let start_dir = resolve_worktree_dir(&worktree_path, args.cwd.as_deref(), &context.filesystem)
+        .await
+        .map_err(|e| anyhow!(e))?;
+
+    // Don't mix command output into an unfinished rebase/merge
+    if let Ok(Some(operation)) =
+        detect_in_progress_operation(&worktree_path, &context.filesystem).await
+    {
+        output().warn(&format!("Worktree '{worktree_name}' has a {operation} in progress"));
+        if !args.even_if_busy {
+            bail!(
+                "Refusing to run in worktree '{worktree_name}' while a {operation} is in progress (use --even-if-busy to run anyway)"
+            );
+        }
+    }
+
+    let extra_env = EnvSources {
+        env_files: worktree_env_file(&context, &git_root, &worktree_path).await?,
+        flags: extra_env,
+        ..Default::default()
+    }
+    .merge();
 
     // Split command into program and arguments
     let command = command_args[0].clone();
     let args_slice = &command_args[1..];
+    let env = EnvSources {
+        phantom: get_phantom_env(
+            &*context.environment,
+            ShellType::Unknown,
+            &worktree_name,
+            &worktree_path.to_string_lossy(),
+        ),
+        flags: extra_env.clone(),
+        ..Default::default()
+    }
+    .merge();
 
     // Handle tmux execution
     if let Some(direction) = tmux_direction {
@@ -126,15 +271,28 @@ where
             direction,
             command,
             args: Some(args_slice.to_vec()),
-            cwd: Some(worktree_path.to_string_lossy().to_string()),
-            env: Some(get_phantom_env(&worktree_name, &worktree_path.to_string_lossy())),
+            cwd: Some(start_dir.to_string_lossy().to_string()),
+            env: Some(env.clone()),
             window_name: if direction == TmuxSplitDirection::New {
                 Some(worktree_name.clone())
             } else {
                 None
             },
+            percentage: args.tmux_size,
+            target: args.tmux_target.clone(),
         };
 
+        if args.wait {
+            let exit_code =
+                execute_tmux_command_and_wait(&context.executor, options, WAIT_POLL_INTERVAL)
+                    .await
+                    .map_err(|e| anyhow!(e))
+                    .with_context(|| {
+                        format!("Failed to wait for command in tmux for worktree '{worktree_name}'")
+                    })?;
+            context.exit(exit_code);
+        }
+
         execute_tmux_command(&context.executor, options)
             .await
             .map_err(|e| anyhow!(e))
@@ -156,15 +314,27 @@ where
             direction,
             command,
             args: Some(args_slice.to_vec()),
-            cwd: Some(worktree_path.to_string_lossy().to_string()),
-            env: Some(get_phantom_env(&worktree_name, &worktree_path.to_string_lossy())),
+            cwd: Some(start_dir.to_string_lossy().to_string()),
+            env: Some(env),
             window_title: if direction == KittySplitDirection::New {
                 Some(worktree_name.clone())
             } else {
                 None
             },
+            focus: !args.no_focus,
+            keep_title: true,
         };
 
+        if args.wait {
+            let exit_code = execute_kitty_command_and_wait(&context.executor, options)
+                .await
+                .map_err(|e| anyhow!(e))
+                .with_context(|| {
+                    format!("Failed to wait for command in kitty for worktree '{worktree_name}'")
+                })?;
+            context.exit(exit_code);
+        }
+
         execute_kitty_command(&context.executor, options)
             .await
             .map_err(|e| anyhow!(e))
@@ -174,38 +344,159 @@ where
         return Ok(());
     }
 
-    // Normal execution
-    let result = exec_in_worktree(
-        &git_root,
-        &worktree_name,
-        &command,
-        args_slice,
-        &context.filesystem,
-        Some(context.executor.clone()),
-    )
-    .await
-    .map_err(|e| anyhow!(e))
-    .with_context(|| {
-        format!(
-            "Failed to execute command '{}' in worktree '{}' at path: {}",
-            command,
-            worktree_name,
-            worktree_path.display()
+    // Normal execution; a typo should not surface as a bare spawn failure
+    if !args.no_verify && !via_shell {
+        ensure_command_exists(
+            &command,
+            &start_dir,
+            &env,
+            &*context.environment,
+            &context.filesystem,
         )
-    })?;
+        .await
+        .map_err(|e| anyhow!(e))?;
+    }
+    let phantom = Phantom::new(&git_root, context.executor.clone(), context.filesystem.clone())
+        .with_environment(context.environment.clone());
+
+    if args.capture {
+        let started = Instant::now();
+        let streamed = !args.json && !args.no_stream && (args.stream || is_stdout_tty());
+        let captured = if streamed {
+            let on_line = |stream: OutputStream, line: &str| match stream {
+                OutputStream::Stdout => println!("[{worktree_name}] {line}"),
+                OutputStream::Stderr => eprintln!("[{worktree_name}] {line}"),
+            };
+            phantom
+                .exec_streamed_with_env(
+                    &worktree_name,
+                    &command,
+                    args_slice,
+                    &extra_env,
+                    args.cwd.as_deref(),
+                    &on_line,
+                )
+                .await
+        } else {
+            phantom
+                .exec_captured_with_env(
+                    &worktree_name,
+                    &command,
+                    args_slice,
+                    &extra_env,
+                    args.cwd.as_deref(),
+                )
+                .await
+        };
+        let captured = captured.map_err(|e| anyhow!(e)).with_context(|| {
+            format!("Failed to execute command '{command}' in worktree '{worktree_name}'")
+        })?;
+        if args.json {
+            output().log(&exec_json(&worktree_name, &command_args, &captured, started.elapsed())?);
+            // The exit handler may end the process without flushing stdout
+            let _ = std::io::stdout().flush();
+        } else if streamed {
+            let _ = std::io::stdout().flush();
+        } else {
+            print!("{}", prefix_lines(&worktree_name, &captured.stdout));
+            eprint!("{}", prefix_lines(&worktree_name, &captured.stderr));
+        }
+        context.exit(captured.exit_code);
+    }
+
+    let title =
+        TerminalTitle::set(&worktree_name, config.terminal_title_enabled(), &*context.environment);
+    let result = phantom
+        .exec_with_env(&worktree_name, &command, args_slice, &extra_env, args.cwd.as_deref())
+        .await
+        .map_err(|e| anyhow!(e))
+        .with_context(|| {
+            format!(
+                "Failed to execute command '{}' in worktree '{}' at path: {}",
+                command,
+                worktree_name,
+                worktree_path.display()
+            )
+        })?;
+    drop(title);
 
     // Exit with the same code as the executed command
-    context.exit_handler.exit(result.exit_code);
+    context.exit(result.exit_code);
+}
+
+/// Variables from the env file at the worktree root, warning about the lines it skips
+pub(crate) async fn worktree_env_file<E, F, H>(
+    context: &HandlerContext<E, F, H>,
+    git_root: &Path,
+    worktree_path: &Path,
+) -> Result<EnvMap>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let file_name = context
+        .config(git_root)
+        .await
+        .with_context(|| "Failed to load config")?
+        .map_or(DEFAULT_ENV_FILE, |loaded| loaded.config.env_file());
+    let path = worktree_path.join(file_name);
+    let Some(env_file) = load_env_file(&context.filesystem, &path)
+        .await
+        .with_context(|| format!("Failed to read env file {}", path.display()))?
+    else {
+        return Ok(EnvMap::new());
+    };
+    for warning in &env_file.warnings {
+        output().warn(&format!("{}: skipped {warning}", path.display()));
+    }
+    Ok(env_file.vars)
+}
+
+/// The `--json` document for a captured run of `command` in `worktree`
+fn exec_json(
+    worktree: &str,
+    command: &[String],
+    captured: &CommandOutput,
+    elapsed: Duration,
+) -> Result<String> {
+    let document = ExecJsonOutput {
+        worktree,
+        command: command.iter().map(String::as_str).collect(),
+        exit_code: captured.exit_code,
+        stdout: &captured.stdout,
+        stderr: &captured.stderr,
+        duration_ms: elapsed.as_millis() as u64,
+    };
+    serde_json::to_string_pretty(&document).with_context(|| "Failed to serialize JSON output")
+}
+
+/// Prefix every line of `text` with `[name] `
+fn prefix_lines(name: &str, text: &str) -> String {
+    text.lines().map(|line| format!("[{name}] {line}\n")).collect()
+}
+
+/// Whether a lone command argument needs a shell to mean what the user typed
+fn is_shell_command_line(command_args: &[String]) -> bool {
+    const SHELL_SYNTAX: &[char] =
+        &['|', '&', ';', '<', '>', '(', ')', '$', '`', '*', '?', '[', '~', '\'', '"'];
+
+    match command_args {
+        [command] => command.contains(char::is_whitespace) || command.contains(SHELL_SYNTAX),
+        _ => false,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::environment::MockEnvironment;
     use crate::core::executors::MockCommandExecutor;
+    use crate::core::exit_handler::MockExitHandler;
     use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
-    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
-    use crate::test_utils::EnvGuard;
-    use std::path::PathBuf;
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem, RealFileSystem};
+    use std::path::{Path, PathBuf};
+    use tempfile::TempDir;
 
     #[tokio::test]
     async fn test_exec_not_in_git_repo() {
@@ -226,23 +517,86 @@ mod tests {
         let args = ExecArgs {
             name: Some("test".to_string()),
             command: vec!["echo".to_string(), "hello".to_string()],
+            shell: false,
+            argv: false,
             fzf: false,
+            last: false,
+            even_if_busy: false,
+            env: vec![],
+            cwd: None,
+            workspace: false,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: false,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
         };
 
         let result = handle(args, context).await;
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_exec_wait_requires_multiplexer() {
+        let mock = MockCommandExecutor::new();
+        let context = HandlerContext::new(
+            mock.clone(),
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = ExecArgs {
+            name: Some("test".to_string()),
+            command: vec!["make".to_string(), "test".to_string()],
+            shell: false,
+            argv: false,
+            fzf: false,
+            last: false,
+            even_if_busy: false,
+            env: vec![],
+            cwd: None,
+            workspace: false,
+            tmux: false,
+            tmux_vertical: false,
+            tmux_v: false,
+            tmux_horizontal: false,
+            tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            kitty: false,
+            kitty_vertical: false,
+            kitty_v: false,
+            kitty_horizontal: false,
+            kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: true,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
+        };
+
+        let err = handle(args, context).await.unwrap_err();
+        assert!(err.to_string().contains("--wait option can only be used with"));
+        assert!(mock.calls().is_empty());
+    }
+
     #[tokio::test]
     async fn test_exec_no_command_specified() {
         let context = HandlerContext::new(
@@ -253,17 +607,34 @@ mod tests {
         let args = ExecArgs {
             name: None,
             command: vec![], // No args at all
+            shell: false,
+            argv: false,
             fzf: false,
+            last: false,
+            even_if_busy: false,
+            env: vec![],
+            cwd: None,
+            workspace: false,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: false,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
         };
 
         let result = handle(args, context).await;
@@ -282,17 +653,34 @@ mod tests {
         let args = ExecArgs {
             name: None,
             command: vec!["echo".to_string()], // Only one arg, need at least 2
+            shell: false,
+            argv: false,
             fzf: false,
+            last: false,
+            even_if_busy: false,
+            env: vec![],
+            cwd: None,
+            workspace: false,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: false,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
         };
 
         let result = handle(args, context).await;
@@ -312,40 +700,51 @@ mod tests {
             mock,
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
-        );
+        )
+        .with_environment(MockEnvironment::new());
         let args = ExecArgs {
             name: Some("test".to_string()),
             command: vec!["echo".to_string(), "hello".to_string()],
+            shell: false,
+            argv: false,
             fzf: false,
+            last: false,
+            even_if_busy: false,
+            env: vec![],
+            cwd: None,
+            workspace: false,
             tmux: true,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: false,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
         };
 
-        {
-            let _guard = EnvGuard::remove("TMUX"); // Ensure TMUX is not set
-            let result = handle(args, context).await;
-            // This will likely pass because is_inside_tmux() checks env directly
-            // In a real test environment without TMUX set, this would fail
-            if std::env::var("TMUX").is_err() {
-                assert!(result.is_err());
-                assert!(result.unwrap_err().to_string().contains("inside a tmux session"));
-            }
-        }
+        let result = handle(args, context).await;
+        assert!(result.unwrap_err().to_string().contains("inside a tmux session"));
     }
 
     #[tokio::test]
     #[should_panic(expected = "MockExitHandler::exit called with code 0")]
     async fn test_exec_success_normal() {
         let mut mock = MockCommandExecutor::new();
-        let mock_fs = MockFileSystem::new();
+        // validate_worktree_exists runs in the handler and again in exec_in_worktree
+        let mock_fs = MockFileSystem::with_default_dirs(&["/repo/.git/phantom/worktrees/test"]);
 
         // Mock git root check
         mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
@@ -354,28 +753,310 @@ mod tests {
             0,
         );
 
-        // Mock filesystem check for worktree existence
-        // Note: validate_worktree_exists is called twice - once in the handler and once in exec_in_worktree
-        mock_fs.expect(FileSystemExpectation {
-            operation: FileSystemOperation::IsDir,
-            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/test")),
-            from_path: None,
-            to_path: None,
-            contents: None,
-            result: Ok(MockResult::Bool(true)), // Directory exists
-        });
+        // Mock command execution
+        mock.expect_command("echo")
+            .with_args(&["hello"])
+            .in_dir("/repo/.git/phantom/worktrees/test")
+            .returns_output("hello\n", "", 0);
+
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+        let args = ExecArgs {
+            name: Some("test".to_string()),
+            command: vec!["echo".to_string(), "hello".to_string()],
+            shell: false,
+            argv: false,
+            fzf: false,
+            last: false,
+            even_if_busy: false,
+            env: vec![],
+            cwd: None,
+            workspace: false,
+            tmux: false,
+            tmux_vertical: false,
+            tmux_v: false,
+            tmux_horizontal: false,
+            tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            kitty: false,
+            kitty_vertical: false,
+            kitty_v: false,
+            kitty_horizontal: false,
+            kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: false,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
+        };
+
+        // This will panic with MockExitHandler
+        handle(args, context).await.unwrap();
+    }
+
+    #[test]
+    fn test_prefix_lines() {
+        assert_eq!(prefix_lines("db", "one\ntwo\n"), "[db] one\n[db] two\n");
+        assert_eq!(prefix_lines("db", "no newline"), "[db] no newline\n");
+        assert_eq!(prefix_lines("db", ""), "");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "MockExitHandler::exit called with code 3")]
+    async fn test_exec_capture_exits_with_command_status() {
+        use clap::Parser;
+
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::with_default_dirs(&["/repo/.git/phantom/worktrees/test"]);
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock.expect_command("make")
+            .with_args(&["test"])
+            .in_dir("/repo/.git/phantom/worktrees/test")
+            .returns_output("ok\n", "1 failed\n", 3);
+
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+        let args = crate::cli::Cli::try_parse_from([
+            "phantom",
+            "exec",
+            "--capture",
+            "test",
+            "make",
+            "test",
+        ])
+        .unwrap();
+        let crate::cli::Commands::Exec(args) = args.command else { unreachable!() };
+
+        handle(args, context).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "MockExitHandler::exit called with code 3")]
+    async fn test_exec_capture_stream_exits_with_command_status() {
+        use clap::Parser;
+
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::with_default_dirs(&["/repo/.git/phantom/worktrees/test"]);
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock.expect_command("make")
+            .with_args(&["test"])
+            .in_dir("/repo/.git/phantom/worktrees/test")
+            .returns_output("ok\n", "1 failed\n", 3);
+
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+        let args = crate::cli::Cli::try_parse_from([
+            "phantom",
+            "exec",
+            "--capture",
+            "--stream",
+            "test",
+            "make",
+            "test",
+        ])
+        .unwrap();
+        let crate::cli::Commands::Exec(args) = args.command else { unreachable!() };
+
+        handle(args, context).await.unwrap();
+    }
+
+    #[test]
+    fn test_exec_stream_requires_capture_without_json() {
+        use clap::Parser;
+
+        let parse = |args: &[&str]| {
+            crate::cli::Cli::try_parse_from(["phantom", "exec"].iter().chain(args)).map(|_| ())
+        };
+        assert!(parse(&["--stream", "test", "ls"]).is_err());
+        assert!(parse(&["--capture", "--stream", "--json", "test", "ls"]).is_err());
+        assert!(parse(&["--capture", "--stream", "--no-stream", "test", "ls"]).is_err());
+        assert!(parse(&["--capture", "--no-stream", "--json", "test", "ls"]).is_ok());
+    }
+
+    #[test]
+    fn test_exec_json_embeds_output_with_quotes_and_newlines() {
+        let captured = CommandOutput::from_static(
+            "say \"hi\"\n\tthen {\"nested\": true}\n",
+            "warn: a\\b\r\n",
+            2,
+        );
+        let command = vec!["sh".to_string(), "-c".to_string(), "echo \"hi\"".to_string()];
+
+        let json = exec_json("feature", &command, &captured, Duration::from_millis(1500)).unwrap();
+
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed,
+            serde_json::json!({
+                "worktree": "feature",
+                "command": ["sh", "-c", "echo \"hi\""],
+                "exit_code": 2,
+                "stdout": "say \"hi\"\n\tthen {\"nested\": true}\n",
+                "stderr": "warn: a\\b\r\n",
+                "duration_ms": 1500,
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "MockExitHandler::exit called with code 3")]
+    async fn test_exec_capture_json_still_exits_with_command_status() {
+        use clap::Parser;
+
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::with_default_dirs(&["/repo/.git/phantom/worktrees/test"]);
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock.expect_command("make")
+            .with_args(&["test"])
+            .in_dir("/repo/.git/phantom/worktrees/test")
+            .returns_output("\"ok\"\n", "1 failed\n", 3);
+
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+        let cli = crate::cli::Cli::try_parse_from([
+            "phantom",
+            "exec",
+            "--capture",
+            "--json",
+            "test",
+            "make",
+            "test",
+        ])
+        .unwrap();
+        let crate::cli::Commands::Exec(args) = cli.command else { unreachable!() };
+        assert!(args.json);
+
+        handle(args, context).await.unwrap();
+    }
+
+    #[test]
+    fn test_exec_json_requires_capture() {
+        use clap::Parser;
+
+        let result = crate::cli::Cli::try_parse_from(["phantom", "exec", "--json", "test", "ls"]);
+        let Err(err) = result else { panic!("--json without --capture was accepted") };
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+
+    /// Mock a worktree that is in the middle of a rebase
+    fn expect_rebase_in_progress(mock_fs: &MockFileSystem, worktree: &str) {
+        let git_dir = "/repo/.git/worktrees/test";
+        let expectations = [
+            (FileSystemOperation::IsDir, format!("{worktree}/.git"), MockResult::Bool(false)),
+            (
+                FileSystemOperation::ReadToString,
+                format!("{worktree}/.git"),
+                MockResult::String(format!("gitdir: {git_dir}\n")),
+            ),
+            (
+                FileSystemOperation::Exists,
+                format!("{git_dir}/rebase-merge"),
+                MockResult::Bool(true),
+            ),
+        ];
+        for (operation, path, result) in expectations {
+            mock_fs.expect(FileSystemExpectation {
+                operation,
+                path: Some(PathBuf::from(path)),
+                from_path: None,
+                to_path: None,
+                contents: None,
+                result: Ok(result),
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exec_refuses_during_rebase() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
 
-        // Second expectation for the same path (called from exec_in_worktree_with_executor)
         mock_fs.expect(FileSystemExpectation {
             operation: FileSystemOperation::IsDir,
             path: Some(PathBuf::from("/repo/.git/phantom/worktrees/test")),
             from_path: None,
             to_path: None,
             contents: None,
-            result: Ok(MockResult::Bool(true)), // Directory exists
+            result: Ok(MockResult::Bool(true)),
         });
+        expect_rebase_in_progress(&mock_fs, "/repo/.git/phantom/worktrees/test");
+
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+        let args = ExecArgs {
+            name: Some("test".to_string()),
+            command: vec!["echo".to_string(), "hello".to_string()],
+            shell: false,
+            argv: false,
+            fzf: false,
+            last: false,
+            even_if_busy: false,
+            env: vec![],
+            cwd: None,
+            workspace: false,
+            tmux: false,
+            tmux_vertical: false,
+            tmux_v: false,
+            tmux_horizontal: false,
+            tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            kitty: false,
+            kitty_vertical: false,
+            kitty_v: false,
+            kitty_horizontal: false,
+            kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: false,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
+        };
+
+        let result = handle(args, context).await;
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("rebase is in progress"), "Unexpected error: {error}");
+        assert!(error.contains("--even-if-busy"));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "MockExitHandler::exit called with code 0")]
+    async fn test_exec_even_if_busy_overrides_guard() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::with_default_dirs(&["/repo/.git/phantom/worktrees/test"]);
+
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        expect_rebase_in_progress(&mock_fs, "/repo/.git/phantom/worktrees/test");
 
-        // Mock command execution
         mock.expect_command("echo")
             .with_args(&["hello"])
             .in_dir("/repo/.git/phantom/worktrees/test")
@@ -386,17 +1067,34 @@ mod tests {
         let args = ExecArgs {
             name: Some("test".to_string()),
             command: vec!["echo".to_string(), "hello".to_string()],
+            shell: false,
+            argv: false,
             fzf: false,
+            last: false,
+            even_if_busy: true,
+            env: vec![],
+            cwd: None,
+            workspace: false,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: false,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
         };
 
         // This will panic with MockExitHandler
@@ -405,12 +1103,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_exec_tmux_new_window() {
+        let environment = MockEnvironment::new().with_var("TMUX", "/tmp/tmux-1000/default,12345,0");
         let mut mock = MockCommandExecutor::new();
         let mock_fs = MockFileSystem::new();
 
-        // Set TMUX env var to simulate being inside tmux
-        let _guard = EnvGuard::set("TMUX", "/tmp/tmux-1000/default,12345,0");
-
         // Mock git root check
         mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
             "/repo/.git",
@@ -448,27 +1144,43 @@ mod tests {
             .returns_output("", "", 0);
 
         let context =
-            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new())
+                .with_environment(environment);
         let args = ExecArgs {
             name: Some("test".to_string()),
             command: vec!["echo".to_string(), "hello".to_string()],
+            shell: false,
+            argv: false,
             fzf: false,
+            last: false,
+            even_if_busy: false,
+            env: vec![],
+            cwd: None,
+            workspace: false,
             tmux: true,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: false,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
         };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
-
-        // Guard will automatically restore env var when dropped
     }
 
     #[tokio::test]
@@ -490,21 +1202,651 @@ mod tests {
         let args = ExecArgs {
             name: None, // Name will be taken from first command arg
             command: vec!["myworktree".to_string(), "echo".to_string(), "hello".to_string()],
+            shell: false,
+            argv: false,
             fzf: false,
+            last: false,
+            even_if_busy: false,
+            env: vec![],
+            cwd: None,
+            workspace: false,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: false,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
         };
 
         // This test will fail at validate_worktree_exists due to filesystem operations
         let _result = handle(args, context).await;
         // Can't fully test without filesystem abstraction
     }
+
+    fn shell_mode_args(command: &[&str], shell: bool, argv: bool) -> ExecArgs {
+        ExecArgs {
+            name: Some("test".to_string()),
+            command: command.iter().map(|s| s.to_string()).collect(),
+            shell,
+            argv,
+            fzf: false,
+            last: false,
+            even_if_busy: false,
+            env: vec![],
+            cwd: None,
+            workspace: false,
+            tmux: false,
+            tmux_vertical: false,
+            tmux_v: false,
+            tmux_horizontal: false,
+            tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            kitty: false,
+            kitty_vertical: false,
+            kitty_v: false,
+            kitty_horizontal: false,
+            kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+            wait: false,
+            capture: false,
+            json: false,
+            stream: false,
+            no_stream: false,
+            no_verify: false,
+        }
+    }
+
+    /// Mock a repository whose worktree "test" exists, as seen by both validations
+    fn expect_test_worktree(mock: &mut MockCommandExecutor, mock_fs: &MockFileSystem) {
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock_fs.add_default_dirs(&["/repo/.git/phantom/worktrees/test"]);
+    }
+
+    #[test]
+    fn test_is_shell_command_line() {
+        let args = |command: &[&str]| command.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(is_shell_command_line(&args(&["npm test && npm run lint"])));
+        assert!(is_shell_command_line(&args(&["ls *.rs"])));
+        assert!(is_shell_command_line(&args(&["echo $HOME"])));
+        assert!(!is_shell_command_line(&args(&["make"])));
+        assert!(!is_shell_command_line(&args(&["ls", "*.rs"])));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "MockExitHandler::exit called with code 0")]
+    async fn test_exec_lone_piped_command_runs_through_shell() {
+        let environment = MockEnvironment::new().with_var("SHELL", "/bin/bash");
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+
+        mock.expect_command("/bin/bash")
+            .with_args(&["-c", "git log --oneline | head -n 1"])
+            .in_dir("/repo/.git/phantom/worktrees/test")
+            .returns_output("abc123 Initial commit\n", "", 0);
+
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new())
+                .with_environment(environment);
+        let args = shell_mode_args(&["git log --oneline | head -n 1"], false, false);
+
+        handle(args, context).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "MockExitHandler::exit called with code 0")]
+    async fn test_exec_shell_flag_joins_arguments_for_fish() {
+        let environment = MockEnvironment::new().with_var("SHELL", "/usr/local/bin/fish");
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+
+        // The glob reaches fish unexpanded so it matches files in the worktree
+        mock.expect_command("/usr/local/bin/fish")
+            .with_args(&["-c", "ls *.rs | wc -l"])
+            .in_dir("/repo/.git/phantom/worktrees/test")
+            .returns_output("3\n", "", 0);
+
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new())
+                .with_environment(environment);
+        let args = shell_mode_args(&["ls", "*.rs", "|", "wc", "-l"], true, false);
+
+        handle(args, context).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "MockExitHandler::exit called with code 0")]
+    async fn test_exec_argv_runs_lone_argument_as_program() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsExecutable,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/test/./run tests.sh")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+
+        mock.expect_command("./run tests.sh")
+            .with_args(&[])
+            .in_dir("/repo/.git/phantom/worktrees/test")
+            .returns_output("", "", 0);
+
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+        let args = shell_mode_args(&["./run tests.sh"], false, true);
+
+        handle(args, context).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_exec_missing_command_fails_before_spawning() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let err = handle(shell_mode_args(&["phantom-no-such-command"], false, false), context)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not found in PATH"), "{err}");
+        assert_eq!(mock.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "MockExitHandler::exit called with code 0")]
+    async fn test_exec_no_verify_leaves_missing_command_to_the_spawn() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+        mock.expect_command("phantom-no-such-command")
+            .with_args(&[])
+            .in_dir("/repo/.git/phantom/worktrees/test")
+            .returns_output("", "", 0);
+
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+        let args = ExecArgs {
+            no_verify: true,
+            ..shell_mode_args(&["phantom-no-such-command"], false, false)
+        };
+        handle(args, context).await.unwrap();
+    }
+
+    #[test]
+    fn test_exec_shell_conflicts_with_argv() {
+        use crate::cli::Cli;
+        use clap::Parser;
+
+        let result = Cli::try_parse_from(["phantom", "exec", "--shell", "--argv", "test", "ls"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_exec_tmux_size_parsing() {
+        use crate::cli::{Cli, Commands};
+        use clap::Parser;
+
+        let parse = |flags: &[&str]| {
+            let command = ["phantom", "exec"].iter().chain(flags).chain(&["test", "ls"]);
+            Cli::try_parse_from(command.copied())
+        };
+
+        match parse(&["--tmux-v", "--tmux-size", "30", "--tmux-target", "editor"]).unwrap().command
+        {
+            Commands::Exec(args) => {
+                assert_eq!(args.tmux_size, Some(30));
+                assert_eq!(args.tmux_target.as_deref(), Some("editor"));
+            }
+            _ => panic!("expected exec"),
+        }
+        assert!(parse(&["--tmux-v", "--tmux-size", "0"]).is_err());
+        assert!(parse(&["--tmux-v", "--tmux-size", "100"]).is_err());
+        assert!(parse(&["--tmux", "--tmux-size", "30"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exec_tmux_size_requires_split() {
+        let context = HandlerContext::new(
+            MockCommandExecutor::new(),
+            MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = ExecArgs { tmux_size: Some(30), ..shell_mode_args(&["ls"], false, true) };
+
+        let err = handle(args, context).await.unwrap_err();
+        assert!(err.to_string().contains("--tmux-size option can only be used with"));
+    }
+
+    #[tokio::test]
+    async fn test_exec_no_focus_requires_kitty() {
+        let context = HandlerContext::new(
+            MockCommandExecutor::new(),
+            MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = ExecArgs { no_focus: true, ..shell_mode_args(&["ls"], false, true) };
+
+        let err = handle(args, context).await.unwrap_err();
+        assert!(err.to_string().contains("--no-focus option can only be used with"));
+    }
+
+    #[tokio::test]
+    async fn test_exec_no_focus_reaches_kitty_argv() {
+        let environment = MockEnvironment::new().with_var("KITTY_WINDOW_ID", "1");
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+        mock.expect_command("kitty")
+            .with_args(&[
+                "@",
+                "launch",
+                "--location=vsplit",
+                "--dont-take-focus",
+                "--cwd",
+                "/repo/.git/phantom/worktrees/test",
+                "--env=PHANTOM_ACTIVE=1",
+                "--env=PHANTOM_WORKTREE=test",
+                "--env=PHANTOM_WORKTREE_PATH=/repo/.git/phantom/worktrees/test",
+                "--",
+                "make",
+                "test",
+            ])
+            .times(1)
+            .returns_success();
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        )
+        .with_environment(environment);
+        let args = ExecArgs { kitty_v: true, no_focus: true, ..env_args(&[]) };
+
+        handle(args, context).await.unwrap();
+        mock.verify().unwrap();
+    }
+
+    fn expect_fs(
+        mock_fs: &MockFileSystem,
+        operation: FileSystemOperation,
+        path: &str,
+        result: MockResult,
+    ) {
+        mock_fs.expect(FileSystemExpectation {
+            operation,
+            path: Some(PathBuf::from(path)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(result),
+        });
+    }
+
+    #[tokio::test]
+    async fn test_exec_cwd_reaches_kitty_argv() {
+        let environment = MockEnvironment::new().with_var("KITTY_WINDOW_ID", "1");
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+        let worktree = "/repo/.git/phantom/worktrees/test";
+        let web = "/repo/.git/phantom/worktrees/test/packages/web";
+        let root = MockResult::PathBuf(PathBuf::from(worktree));
+        expect_fs(&mock_fs, FileSystemOperation::Canonicalize, worktree, root);
+        let dir = MockResult::PathBuf(PathBuf::from(web));
+        expect_fs(&mock_fs, FileSystemOperation::Canonicalize, web, dir);
+        expect_fs(&mock_fs, FileSystemOperation::IsDir, web, MockResult::Bool(true));
+        mock.expect_command("kitty")
+            .with_args(&[
+                "@",
+                "launch",
+                "--location=vsplit",
+                "--cwd",
+                "/repo/.git/phantom/worktrees/test/packages/web",
+                "--env=PHANTOM_ACTIVE=1",
+                "--env=PHANTOM_WORKTREE=test",
+                "--env=PHANTOM_WORKTREE_PATH=/repo/.git/phantom/worktrees/test",
+                "--",
+                "make",
+                "test",
+            ])
+            .times(1)
+            .returns_success();
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        )
+        .with_environment(environment);
+        let args =
+            ExecArgs { kitty_v: true, cwd: Some(PathBuf::from("packages/web")), ..env_args(&[]) };
+
+        handle(args, context).await.unwrap();
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_exec_cwd_rejects_escape() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+        let worktree = "/repo/.git/phantom/worktrees/test";
+        let root = MockResult::PathBuf(PathBuf::from(worktree));
+        expect_fs(&mock_fs, FileSystemOperation::Canonicalize, worktree, root);
+        let other = MockResult::PathBuf(PathBuf::from("/repo/.git/phantom/worktrees/other"));
+        expect_fs(
+            &mock_fs,
+            FileSystemOperation::Canonicalize,
+            "/repo/.git/phantom/worktrees/test/../other",
+            other,
+        );
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = ExecArgs { cwd: Some(PathBuf::from("../other")), ..env_args(&[]) };
+
+        let err = handle(args, context).await.unwrap_err();
+        assert!(err.to_string().contains("Directory '../other' is outside the worktree"), "{err}");
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_exec_cwd_missing_directory_names_path() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+        let worktree = "/repo/.git/phantom/worktrees/test";
+        let root = MockResult::PathBuf(PathBuf::from(worktree));
+        expect_fs(&mock_fs, FileSystemOperation::Canonicalize, worktree, root);
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = ExecArgs { cwd: Some(PathBuf::from("docs")), ..env_args(&[]) };
+
+        let err = handle(args, context).await.unwrap_err();
+        assert!(err.to_string().contains("/repo/.git/phantom/worktrees/test/docs"), "{err}");
+    }
+
+    /// `phantom exec --last make test` as clap parses it: the first word lands in `name`
+    fn last_args() -> ExecArgs {
+        ExecArgs {
+            name: Some("make".to_string()),
+            command: vec!["test".to_string()],
+            last: true,
+            no_verify: true,
+            ..shell_mode_args(&[], false, true)
+        }
+    }
+
+    fn real_fs_context(
+        repo: &Path,
+        mock: MockCommandExecutor,
+    ) -> HandlerContext<MockCommandExecutor, RealFileSystem, MockExitHandler> {
+        let mut mock = mock;
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            &repo.join(".git").to_string_lossy(),
+            "",
+            0,
+        );
+        HandlerContext::new(mock, RealFileSystem::new(), MockExitHandler::new())
+    }
+
+    /// The handler ran the command and exited through `MockExitHandler` with code 0
+    fn assert_exits_with_zero(joined: std::result::Result<Result<()>, tokio::task::JoinError>) {
+        let panic = joined.unwrap_err().into_panic();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert_eq!(message, "MockExitHandler::exit called with code 0");
+    }
+
+    #[tokio::test]
+    async fn test_exec_last_reuses_the_recorded_worktree() {
+        let repo = TempDir::new().unwrap();
+        let worktree = repo.path().join(".git/phantom/worktrees/test");
+        std::fs::create_dir_all(&worktree).unwrap();
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("make").with_args(&["test"]).in_dir(&worktree).returns_success();
+
+        // Running in a worktree by name records it
+        let args = ExecArgs { no_verify: true, ..shell_mode_args(&["make", "test"], false, true) };
+        let context = real_fs_context(repo.path(), mock.clone());
+        assert_exits_with_zero(tokio::spawn(handle(args, context)).await);
+        let fs = RealFileSystem::new();
+        assert_eq!(read_last_worktree(&fs, repo.path()).await.as_deref(), Some("test"));
+
+        // --last runs there again, with every argument as the command
+        let context = real_fs_context(repo.path(), mock.clone());
+        assert_exits_with_zero(tokio::spawn(handle(last_args(), context)).await);
+        let runs = mock.calls().iter().filter(|call| call.program == "make").count();
+        assert_eq!(runs, 2);
+    }
+
+    #[tokio::test]
+    async fn test_exec_last_without_a_usable_record_suggests_fzf() {
+        let repo = TempDir::new().unwrap();
+        let fs = RealFileSystem::new();
+
+        let context = real_fs_context(repo.path(), MockCommandExecutor::new());
+        let err = handle(last_args(), context).await.unwrap_err();
+        assert!(err.to_string().contains("No worktree has been used yet"), "{err}");
+
+        record_last_worktree(&fs, repo.path(), "gone").await;
+        let context = real_fs_context(repo.path(), MockCommandExecutor::new());
+        let err = handle(last_args(), context).await.unwrap_err();
+        assert!(err.to_string().contains("'gone' no longer exists; pick one with --fzf"), "{err}");
+    }
+
+    fn env_args(env: &[&str]) -> ExecArgs {
+        ExecArgs {
+            env: env.iter().map(|s| s.to_string()).collect(),
+            ..shell_mode_args(&["make", "test"], false, true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exec_env_reaches_tmux_argv_in_key_order() {
+        let environment = MockEnvironment::new().with_var("TMUX", "/tmp/tmux-1000/default,12345,0");
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+        mock.expect_command("tmux")
+            .with_args(&[
+                "split-window",
+                "-v",
+                "-c",
+                "/repo/.git/phantom/worktrees/test",
+                "-e",
+                "API_KEY=secret",
+                "-e",
+                "PHANTOM_ACTIVE=1",
+                "-e",
+                "PHANTOM_WORKTREE=test",
+                "-e",
+                "PHANTOM_WORKTREE_PATH=/repo/.git/phantom/worktrees/test",
+                "-e",
+                "ZED_MODE=a=b",
+                "make",
+                "test",
+            ])
+            .times(1)
+            .returns_success();
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        )
+        .with_environment(environment);
+        let args = ExecArgs { tmux_v: true, ..env_args(&["ZED_MODE=a=b", "API_KEY=secret"]) };
+
+        handle(args, context).await.unwrap();
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_exec_env_file_sits_between_phantom_env_and_flags() {
+        let repo = TempDir::new().unwrap();
+        let worktree = repo.path().join(".git/phantom/worktrees/test");
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(
+            worktree.join(".phantom.env"),
+            "export API_KEY=from-file\nPORT=3001\nnot an assignment\n",
+        )
+        .unwrap();
+        let worktree = worktree.to_str().unwrap();
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&[
+                "split-window",
+                "-v",
+                "-c",
+                worktree,
+                "-e",
+                "API_KEY=secret",
+                "-e",
+                "PHANTOM_ACTIVE=1",
+                "-e",
+                "PHANTOM_WORKTREE=test",
+                "-e",
+                &format!("PHANTOM_WORKTREE_PATH={worktree}"),
+                "-e",
+                "PORT=3001",
+                "make",
+                "test",
+            ])
+            .times(1)
+            .returns_success();
+        let environment = MockEnvironment::new().with_var("TMUX", "/tmp/tmux-1000/default,12345,0");
+        let context = real_fs_context(repo.path(), mock.clone()).with_environment(environment);
+        let args = ExecArgs { tmux_v: true, ..env_args(&["API_KEY=secret"]) };
+
+        handle(args, context).await.unwrap();
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_exec_opens_configured_default_multiplexer() {
+        let environment = MockEnvironment::new().with_var("TMUX", "/tmp/tmux-1000/default,12345,0");
+        let repo = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            repo.path().join("phantom.config.json"),
+            r#"{"defaultMultiplexer": "tmux", "autoSplit": "vertical"}"#,
+        )
+        .unwrap();
+        let worktree = repo.path().join(".git/phantom/worktrees/test");
+        let worktree = worktree.to_str().unwrap();
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            &format!("{}/.git", repo.path().display()),
+            "",
+            0,
+        );
+        let mock_fs = MockFileSystem::new();
+        expect_fs(&mock_fs, FileSystemOperation::IsDir, worktree, MockResult::Bool(true));
+        mock.expect_command("tmux").times(1).returns_success();
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        )
+        .with_environment(environment);
+        handle(env_args(&[]), context).await.unwrap();
+
+        mock.verify().unwrap();
+        let tmux = mock.calls().into_iter().find(|call| call.program == "tmux").unwrap();
+        assert_eq!(tmux.args[..4], ["split-window", "-v", "-c", worktree]);
+    }
+
+    #[tokio::test]
+    async fn test_exec_env_reaches_kitty_argv_in_key_order() {
+        let environment = MockEnvironment::new().with_var("KITTY_WINDOW_ID", "1");
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        expect_test_worktree(&mut mock, &mock_fs);
+        mock.expect_command("kitty")
+            .with_args(&[
+                "@",
+                "launch",
+                "--location=hsplit",
+                "--cwd",
+                "/repo/.git/phantom/worktrees/test",
+                "--env=API_KEY=secret",
+                "--env=PHANTOM_ACTIVE=1",
+                "--env=PHANTOM_WORKTREE=override",
+                "--env=PHANTOM_WORKTREE_PATH=/repo/.git/phantom/worktrees/test",
+                "--",
+                "make",
+                "test",
+            ])
+            .times(1)
+            .returns_success();
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        )
+        .with_environment(environment);
+        // A flag overrides the phantom variable of the same name
+        let args = ExecArgs {
+            kitty_h: true,
+            ..env_args(&["PHANTOM_WORKTREE=override", "API_KEY=secret"])
+        };
+
+        handle(args, context).await.unwrap();
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_exec_rejects_malformed_env() {
+        let context = HandlerContext::new(
+            MockCommandExecutor::new(),
+            MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let err =
+            handle(env_args(&["GOOD=1", "DATABASE_URL", "=empty"]), context).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation failed: Invalid environment variable 'DATABASE_URL', '=empty', expected KEY=VALUE"
+        );
+    }
 }
@@ -0,0 +1,168 @@
+use crate::cli::commands::gc::GcArgs;
+use crate::cli::context::HandlerContext;
+use crate::cli::output::output;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use crate::worktree::gc::{find_garbage, remove_garbage, GcFindings};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize)]
+struct GcJsonOutput {
+    applied: bool,
+    #[serde(flatten)]
+    findings: GcFindings,
+}
+
+/// Handle the gc command
+pub async fn handle<E, F, H>(args: GcArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let findings = find_garbage(context.executor.clone(), &context.filesystem, &git_root)
+        .await
+        .with_context(|| "Failed to look for stale phantom data")?;
+
+    if args.apply && !findings.is_empty() {
+        remove_garbage(context.executor.clone(), &context.filesystem, &git_root, &findings)
+            .await
+            .with_context(|| "Failed to remove stale phantom data")?;
+    }
+
+    if args.json {
+        let json_output = GcJsonOutput { applied: args.apply, findings };
+        output().log(&serde_json::to_string_pretty(&json_output)?);
+        return Ok(());
+    }
+
+    if findings.is_empty() {
+        output().log("Nothing to clean up");
+        return Ok(());
+    }
+
+    let (remove, prune) =
+        if args.apply { ("Removed", "Pruned") } else { ("Would remove", "Would prune") };
+    for dir in &findings.stale_directories {
+        output().log(&format!("{remove} stale directory {}", display(&git_root, dir)));
+    }
+    for path in &findings.missing_worktrees {
+        output().log(&format!("{prune} missing worktree {}", display(&git_root, path)));
+    }
+    for file in &findings.orphaned_state_files {
+        output().log(&format!("{remove} orphaned state file {}", display(&git_root, file)));
+    }
+    if !args.apply {
+        output().log("Run 'phantom gc --apply' to clean up");
+    }
+
+    Ok(())
+}
+
+/// A path relative to the repository root when it is inside it
+fn display(git_root: &Path, path: &Path) -> String {
+    path.strip_prefix(git_root).unwrap_or(path).display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::exit_handler::MockExitHandler;
+    use crate::core::filesystems::mock_filesystem::{
+        FileSystemOperation, MockDirEntry, MockResult,
+    };
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+    use std::path::PathBuf;
+
+    fn expect(fs: &MockFileSystem, operation: FileSystemOperation, path: &str, result: MockResult) {
+        fs.expect(FileSystemExpectation {
+            operation,
+            path: Some(PathBuf::from(path)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(result),
+        });
+    }
+
+    /// `/repo` with a leftover `old` directory and no state directory
+    fn context(
+        apply: bool,
+    ) -> (MockCommandExecutor, HandlerContext<MockCommandExecutor, MockFileSystem, MockExitHandler>)
+    {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output("worktree /repo\0HEAD abc123\0branch refs/heads/main\0", "", 0);
+
+        let fs = MockFileSystem::new();
+        let worktrees = "/repo/.git/phantom/worktrees";
+        expect(&fs, FileSystemOperation::Exists, worktrees, MockResult::Bool(true));
+        expect(
+            &fs,
+            FileSystemOperation::ListDir,
+            worktrees,
+            MockResult::DirEntries(vec![MockDirEntry {
+                path: PathBuf::from(worktrees).join("old"),
+            }]),
+        );
+        expect(
+            &fs,
+            FileSystemOperation::IsDir,
+            "/repo/.git/phantom/worktrees/old",
+            MockResult::Bool(true),
+        );
+        expect(
+            &fs,
+            FileSystemOperation::Exists,
+            "/repo/.git/phantom/state",
+            MockResult::Bool(false),
+        );
+        if apply {
+            expect(
+                &fs,
+                FileSystemOperation::RemoveDirAll,
+                "/repo/.git/phantom/worktrees/old",
+                MockResult::Unit,
+            );
+        }
+
+        (mock.clone(), HandlerContext::new(mock, fs, MockExitHandler::new()))
+    }
+
+    #[tokio::test]
+    async fn test_gc_dry_run_removes_nothing() {
+        let (_, context) = context(false);
+        // A removal would fail: the filesystem mock has no expectation for it
+        handle(GcArgs { apply: false, json: false }, context.clone()).await.unwrap();
+        assert!(context
+            .filesystem
+            .exists(Path::new("/repo/.git/phantom/worktrees"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_gc_apply_removes_stale_directory() {
+        let (_, context) = context(true);
+        handle(GcArgs { apply: true, json: true }, context.clone()).await.unwrap();
+        // The expected removal was consumed
+        assert!(context
+            .filesystem
+            .remove_dir_all(Path::new("/repo/.git/phantom/worktrees/old"))
+            .await
+            .is_err());
+    }
+}
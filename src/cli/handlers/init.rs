@@ -0,0 +1,221 @@
+use crate::cli::commands::init::{InitArgs, InitFormat};
+use crate::cli::context::HandlerContext;
+use crate::cli::output::{output, Prompt, TerminalPrompt};
+use crate::config::default::{default_config, write_config, write_default_config, ConfigFormat};
+use crate::config::types::{Multiplexer, PhantomConfig, PostCreateConfig};
+use crate::core::command_executor::CommandExecutor;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use anyhow::{bail, Context, Result};
+
+/// Handle the init command
+pub async fn handle<E, F, H>(args: InitArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    handle_with_prompt(args, context, &TerminalPrompt).await
+}
+
+/// Handle the init command, asking `prompt` the --interactive questions
+pub(crate) async fn handle_with_prompt<E, F, H>(
+    args: InitArgs,
+    context: HandlerContext<E, F, H>,
+    prompt: &dyn Prompt,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    if args.interactive && !prompt.is_interactive() {
+        bail!("The --interactive option needs a terminal to ask questions on");
+    }
+
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let (format, other) = match args.format {
+        InitFormat::Toml => (ConfigFormat::Toml, ConfigFormat::Json),
+        InitFormat::Json => (ConfigFormat::Json, ConfigFormat::Toml),
+    };
+    let path = git_root.join(format.filename());
+    if context.filesystem.exists(&path).await? && !args.force {
+        bail!("{} already exists (use --force to overwrite it)", path.display());
+    }
+
+    if args.interactive {
+        let config = ask_config(prompt)?;
+        write_config(&path, &config, format).await
+    } else {
+        write_default_config(&path, format).await
+    }
+    .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    output().success(&format!("Wrote {}", path.display()));
+    // The JSON file is read first, so a TOML file next to it would be ignored
+    if format == ConfigFormat::Toml
+        && context.filesystem.exists(&git_root.join(other.filename())).await?
+    {
+        output().warn(&format!(
+            "{} also exists and takes precedence over {}",
+            other.filename(),
+            format.filename()
+        ));
+    }
+    Ok(())
+}
+
+/// The starter configuration, adjusted by the user's answers
+fn ask_config(prompt: &dyn Prompt) -> Result<PhantomConfig> {
+    let mut config = default_config();
+    let defaults = config.post_create.take().and_then(|post| post.copy_files).unwrap_or_default();
+
+    let copy_files = if prompt.confirm("Copy untracked files such as .env into new worktrees?")? {
+        let answer =
+            prompt.ask(&format!("Files to copy, comma separated [{}]:", defaults.join(", ")))?;
+        Some(if answer.is_empty() { defaults } else { split_list(&answer) })
+    } else {
+        None
+    };
+
+    let command = prompt.ask("Command to run after creating a worktree (empty for none):")?;
+    let commands = (!command.is_empty()).then(|| vec![command]);
+
+    if copy_files.is_some() || commands.is_some() {
+        config.post_create =
+            Some(PostCreateConfig { copy_files, commands, copy_files_respect_gitignore: None });
+    }
+    config.default_multiplexer = ask_multiplexer(prompt)?;
+    Ok(config)
+}
+
+/// Ask until the answer names a multiplexer; `None` when the user wants none
+fn ask_multiplexer(prompt: &dyn Prompt) -> Result<Option<Multiplexer>> {
+    loop {
+        let answer =
+            prompt.ask("Default multiplexer for shell and exec (tmux, kitty or none) [none]:")?;
+        match answer.to_ascii_lowercase().as_str() {
+            "" | "none" => return Ok(None),
+            "tmux" => return Ok(Some(Multiplexer::Tmux)),
+            "kitty" => return Ok(Some(Multiplexer::Kitty)),
+            _ => output().warn(&format!("Unknown multiplexer '{answer}'")),
+        }
+    }
+}
+
+/// Comma separated entries, trimmed and without empty ones
+fn split_list(answer: &str) -> Vec<String> {
+    answer.split(',').map(str::trim).filter(|entry| !entry.is_empty()).map(String::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::output::MockPrompt;
+    use crate::config::loader::load_config;
+    use crate::config::validate::validate_config;
+    use crate::core::environment::MockEnvironment;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::exit_handler::MockExitHandler;
+    use crate::core::filesystems::RealFileSystem;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn context(
+        git_root: &Path,
+    ) -> HandlerContext<MockCommandExecutor, RealFileSystem, MockExitHandler> {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            &git_root.join(".git").to_string_lossy(),
+            "",
+            0,
+        );
+        HandlerContext::new(mock, RealFileSystem::new(), MockExitHandler::new())
+    }
+
+    fn init_args(format: InitFormat, force: bool, interactive: bool) -> InitArgs {
+        InitArgs { format, force, interactive }
+    }
+
+    #[tokio::test]
+    async fn test_init_writes_a_config_that_loads_and_validates() {
+        let repo = TempDir::new().unwrap();
+        let prompt = MockPrompt::new(false, false);
+
+        handle_with_prompt(
+            init_args(InitFormat::Toml, false, false),
+            context(repo.path()),
+            &prompt,
+        )
+        .await
+        .unwrap();
+
+        let loaded = load_config(repo.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+        validate_config(&loaded.config).unwrap();
+        assert_eq!(loaded.config, default_config());
+        assert_eq!(loaded.files, vec![repo.path().join("phantom.config.toml")]);
+
+        // An existing file is only replaced with --force
+        let err = handle_with_prompt(
+            init_args(InitFormat::Toml, false, false),
+            context(repo.path()),
+            &prompt,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{err}");
+        handle_with_prompt(init_args(InitFormat::Toml, true, false), context(repo.path()), &prompt)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_init_interactive_fills_in_the_answers() {
+        let repo = TempDir::new().unwrap();
+        let prompt = MockPrompt::new(true, true).with_answers(&[
+            ".env, .npmrc",
+            "npm install",
+            "vim",
+            "Kitty",
+        ]);
+
+        handle_with_prompt(init_args(InitFormat::Json, false, true), context(repo.path()), &prompt)
+            .await
+            .unwrap();
+
+        let loaded = load_config(repo.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+        validate_config(&loaded.config).unwrap();
+        let post_create = loaded.config.post_create.unwrap();
+        assert_eq!(post_create.copy_files, Some(vec![".env".to_string(), ".npmrc".to_string()]));
+        assert_eq!(post_create.commands, Some(vec!["npm install".to_string()]));
+        assert_eq!(loaded.config.default_multiplexer, Some(Multiplexer::Kitty));
+        // The unknown multiplexer was asked again
+        assert_eq!(prompt.asked().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_init_interactive_without_copy_files_or_command() {
+        let config = ask_config(&MockPrompt::new(true, false)).unwrap();
+
+        assert_eq!(config.post_create, None);
+        assert_eq!(config.default_multiplexer, None);
+        validate_config(&config).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_init_interactive_needs_a_terminal() {
+        let repo = TempDir::new().unwrap();
+        let err = handle_with_prompt(
+            init_args(InitFormat::Toml, false, true),
+            context(repo.path()),
+            &MockPrompt::new(false, true),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("--interactive"), "{err}");
+        assert!(!repo.path().join("phantom.config.toml").exists());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::cli::commands::label::{LabelAction, LabelArgs};
+use crate::cli::context::HandlerContext;
+use crate::cli::output::output;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use crate::worktree::labels::{add_label, remove_label};
+use crate::worktree::validate::validate_worktree_exists;
+use anyhow::{Context, Result};
+
+/// Handle the label command
+pub async fn handle<E, F, H>(args: LabelArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    validate_worktree_exists(&git_root, &args.name, &context.filesystem)
+        .await
+        .with_context(|| format!("Failed to validate worktree '{}' exists", args.name))?;
+
+    match args.action {
+        LabelAction::Add { label } => {
+            let added = add_label(&context.filesystem, &git_root, &args.name, &label)
+                .await
+                .with_context(|| format!("Failed to add label to '{}'", args.name))?;
+            if added {
+                output().success(&format!("Added label '{label}' to '{}'", args.name));
+            } else {
+                output().log(&format!("'{}' already has label '{label}'", args.name));
+            }
+        }
+        LabelAction::Remove { label } => {
+            let removed = remove_label(&context.filesystem, &git_root, &args.name, &label)
+                .await
+                .with_context(|| format!("Failed to remove label from '{}'", args.name))?;
+            if removed {
+                output().success(&format!("Removed label '{label}' from '{}'", args.name));
+            } else {
+                output().log(&format!("'{}' has no label '{label}', nothing to remove", args.name));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::exit_handler::MockExitHandler;
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+    use crate::worktree::state::{metadata_path, WorktreeMetadata};
+    use std::path::{Path, PathBuf};
+
+    const WORKTREE: &str = "/repo/.git/phantom/worktrees/feature";
+
+    fn expectation(
+        operation: FileSystemOperation,
+        path: PathBuf,
+        contents: Option<String>,
+        result: crate::Result<MockResult>,
+    ) -> FileSystemExpectation {
+        FileSystemExpectation {
+            operation,
+            path: Some(path),
+            from_path: None,
+            to_path: None,
+            contents,
+            result,
+        }
+    }
+
+    fn metadata_json(labels: &[&str]) -> String {
+        let metadata = WorktreeMetadata {
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+            ..Default::default()
+        };
+        serde_json::to_string_pretty(&metadata).unwrap()
+    }
+
+    /// A context for a worktree whose metadata file currently holds `stored`
+    fn context_with_metadata(
+        stored: Option<String>,
+    ) -> HandlerContext<MockCommandExecutor, MockFileSystem, MockExitHandler> {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+
+        let fs = MockFileSystem::new();
+        fs.expect(expectation(
+            FileSystemOperation::IsDir,
+            PathBuf::from(WORKTREE),
+            None,
+            Ok(MockResult::Bool(true)),
+        ));
+        let read = match stored {
+            Some(contents) => Ok(MockResult::String(contents)),
+            None => Err(crate::PhantomError::FileOperationFailed {
+                operation: "read".to_string(),
+                path: PathBuf::from("feature.json"),
+                reason: "No such file or directory".to_string(),
+            }),
+        };
+        fs.expect(expectation(
+            FileSystemOperation::ReadToString,
+            metadata_path(Path::new("/repo"), "feature"),
+            None,
+            read,
+        ));
+
+        HandlerContext::new(mock, fs, MockExitHandler::new())
+    }
+
+    fn expect_write(
+        context: &HandlerContext<MockCommandExecutor, MockFileSystem, MockExitHandler>,
+        contents: String,
+    ) {
+        let path = metadata_path(Path::new("/repo"), "feature");
+        context.filesystem.expect(expectation(
+            FileSystemOperation::CreateDirAll,
+            path.parent().unwrap().to_path_buf(),
+            None,
+            Ok(MockResult::Unit),
+        ));
+        context.filesystem.expect(expectation(
+            FileSystemOperation::WriteAtomic,
+            path,
+            Some(contents),
+            Ok(MockResult::Unit),
+        ));
+    }
+
+    fn args(action: LabelAction) -> LabelArgs {
+        LabelArgs { name: "feature".to_string(), action }
+    }
+
+    #[tokio::test]
+    async fn test_label_add_then_remove_round_trip() {
+        // Adding to a worktree without metadata writes the label
+        let context = context_with_metadata(None);
+        expect_write(&context, metadata_json(&["review"]));
+        handle(args(LabelAction::Add { label: "review".to_string() }), context).await.unwrap();
+
+        // Adding a second label keeps the first
+        let context = context_with_metadata(Some(metadata_json(&["review"])));
+        expect_write(&context, metadata_json(&["review", "spike"]));
+        handle(args(LabelAction::Add { label: "spike".to_string() }), context).await.unwrap();
+
+        // Removing drops only that label
+        let context = context_with_metadata(Some(metadata_json(&["review", "spike"])));
+        expect_write(&context, metadata_json(&["spike"]));
+        handle(args(LabelAction::Remove { label: "review".to_string() }), context).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_label_remove_missing_is_noop() {
+        // No write expectation: writing would fail the mock
+        let context = context_with_metadata(Some(metadata_json(&["spike"])));
+        handle(args(LabelAction::Remove { label: "review".to_string() }), context).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_label_add_existing_is_noop() {
+        let context = context_with_metadata(Some(metadata_json(&["review"])));
+        handle(args(LabelAction::Add { label: "review".to_string() }), context).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_label_rejects_invalid_label() {
+        let context = context_with_metadata(None);
+        let err = handle(args(LabelAction::Add { label: "Needs Review".to_string() }), context)
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("lowercase letters, digits and dashes"));
+    }
+}
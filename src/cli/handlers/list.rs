@@ -1,26 +1,126 @@
+use crate::api::Phantom;
 use crate::cli::commands::list::ListArgs;
 use crate::cli::context::HandlerContext;
-use crate::cli::output::output;
+use crate::cli::handlers::workspace;
+use crate::cli::output::{output, timings_ms};
+use crate::cli::progress::format_bytes;
 use crate::core::command_executor::CommandExecutor;
 use crate::core::exit_handler::ExitHandler;
 use crate::core::filesystem::FileSystem;
-use crate::git::libs::get_git_root::get_git_root;
-use crate::worktree::concurrent::list_worktrees_concurrent;
+use crate::core::utils::timed;
+use crate::worktree::disk_usage::worktree_sizes;
+use crate::worktree::in_progress::detect_in_progress_operation;
+use crate::worktree::labels::LabelFilter;
+use crate::worktree::list::{ListOptions, WorktreeInfo, WorktreeOrigin};
 use crate::worktree::select::select_worktree_with_fzf;
+use crate::worktree::stack::{read_stack_parents, render_stacks};
+use crate::worktree::state::{format_age, read_worktree_metadata, WorktreeMetadata};
 use anyhow::{Context, Result};
 use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Serialize)]
-struct ListJsonOutput {
-    worktrees: Vec<WorktreeJsonItem>,
+pub(crate) struct ListJsonOutput {
+    pub(crate) worktrees: Vec<WorktreeJsonItem>,
+    /// Milliseconds per timed section, with --timings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) timings_ms: Option<BTreeMap<String, u64>>,
 }
 
 #[derive(Serialize)]
-struct WorktreeJsonItem {
-    name: String,
-    branch: Option<String>,
-    is_clean: bool,
-    path: String,
+pub(crate) struct WorktreeJsonItem {
+    pub(crate) name: String,
+    pub(crate) branch: Option<String>,
+    /// Omitted when the status was not checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) is_clean: Option<bool>,
+    pub(crate) path: String,
+    /// `phantom`, `external` or `main`
+    pub(crate) origin: WorktreeOrigin,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) is_locked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) locked_reason: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) is_detached: bool,
+    /// The branch's upstream was deleted
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) gone: bool,
+    /// The branch has no commits yet
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) unborn: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) created_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) base_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) phantom_version: Option<String>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub(crate) labels: BTreeSet<String>,
+    /// Bytes on disk, with --du
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) size_bytes: Option<u64>,
+}
+
+impl WorktreeJsonItem {
+    /// Combine a listed worktree with its recorded creation metadata
+    pub(crate) async fn load(
+        worktree: &WorktreeInfo,
+        git_root: &Path,
+        filesystem: &dyn FileSystem,
+        options: ListOptions,
+    ) -> Self {
+        let metadata = metadata_of(worktree, git_root, filesystem).await;
+        Self {
+            name: worktree.name.clone(),
+            branch: worktree.branch.clone(),
+            is_clean: options.include_status.then_some(worktree.is_clean),
+            path: worktree.path.clone(),
+            origin: worktree.origin,
+            is_locked: worktree.is_locked,
+            locked_reason: worktree.locked_reason.clone(),
+            is_detached: worktree.is_detached,
+            gone: worktree.gone,
+            unborn: false,
+            created_at: metadata.created_at,
+            base_ref: metadata.base_ref,
+            phantom_version: metadata.phantom_version,
+            labels: metadata.labels,
+            size_bytes: None,
+        }
+    }
+}
+
+/// JSON entries for `worktrees`, led by a main worktree whose branch has no commits yet
+pub(crate) async fn json_items(
+    unborn_main: Option<&WorktreeInfo>,
+    worktrees: &[WorktreeInfo],
+    sizes: Vec<Option<u64>>,
+    git_root: &Path,
+    filesystem: &dyn FileSystem,
+    options: ListOptions,
+) -> Vec<WorktreeJsonItem> {
+    let mut items = Vec::with_capacity(worktrees.len() + 1);
+    if let Some(main) = unborn_main {
+        let mut item = WorktreeJsonItem::load(main, git_root, filesystem, options).await;
+        item.is_clean = None;
+        item.unborn = true;
+        items.push(item);
+    }
+    for (worktree, size_bytes) in worktrees.iter().zip(sizes) {
+        let mut item = WorktreeJsonItem::load(worktree, git_root, filesystem, options).await;
+        item.size_bytes = size_bytes;
+        items.push(item);
+    }
+    items
+}
+
+/// The line standing in for a main worktree whose branch has no commits yet
+fn unborn_main_line(main: &WorktreeInfo) -> String {
+    let branch_info = main.branch.as_ref().map(|b| format!("({b}) ")).unwrap_or_default();
+    format!("{}  {branch_info}(no commits yet)", main.name)
 }
 
 /// Handle the list command
@@ -30,15 +130,38 @@ where
     F: FileSystem + Clone + 'static,
     H: ExitHandler + Clone + 'static,
 {
-    let git_root = get_git_root(context.executor.clone())
+    if args.workspace {
+        return workspace::list(args, context).await;
+    }
+
+    let label_filter = LabelFilter::new(args.labels.clone(), args.label_any)?;
+    let git_root = timed("git_root", context.git_root())
         .await
         .with_context(|| "Failed to determine git repository root")?;
 
+    let check_remote = args.check_remote
+        || context
+            .config(&git_root)
+            .await
+            .with_context(|| "Failed to load config")?
+            .is_some_and(|loaded| loaded.config.list_checks_remote());
+    let list_options = ListOptions {
+        include_status: !args.no_status,
+        check_gone: !args.no_status,
+        check_remote,
+        include_all: args.all,
+    };
+
     if args.fzf {
         // Use fzf for interactive selection
-        match select_worktree_with_fzf(context.executor.clone(), &git_root)
-            .await
-            .with_context(|| "Failed to select worktree with fzf")?
+        match select_worktree_with_fzf(
+            context.executor.clone(),
+            &context.filesystem,
+            &git_root,
+            &context.config_or_default(&git_root).await,
+        )
+        .await
+        .with_context(|| "Failed to select worktree with fzf")?
         {
             Some(worktree) => {
                 output().log(&worktree.name);
@@ -49,38 +172,80 @@ where
         }
     } else {
         // List all worktrees using concurrent operations
-        let result =
-            list_worktrees_concurrent(context.executor.clone(), &git_root).await.with_context(
+        let phantom = Phantom::new(&git_root, context.executor.clone(), context.filesystem.clone());
+        let mut result =
+            timed("list_worktrees", phantom.list_with_options(list_options)).await.with_context(
                 || format!("Failed to list worktrees in git root: {}", git_root.display()),
             )?;
 
+        if !label_filter.is_empty() {
+            let mut matching = Vec::with_capacity(result.worktrees.len());
+            for worktree in result.worktrees {
+                let metadata = metadata_of(&worktree, &git_root, &context.filesystem).await;
+                if label_filter.matches(&metadata.labels) {
+                    matching.push(worktree);
+                }
+            }
+            if matching.is_empty() {
+                result.message = Some("No worktrees match the given labels".to_string());
+            }
+            result.worktrees = matching;
+        }
+
+        // Show the main worktree so a fresh repository doesn't look empty
+        if let Some(main) = result.unborn_main.as_ref().filter(|_| !args.json && !args.names) {
+            output().log(&unborn_main_line(main));
+        }
+
         if result.worktrees.is_empty() {
             if args.json {
-                let json_output = ListJsonOutput { worktrees: vec![] };
+                let worktrees = json_items(
+                    result.unborn_main.as_ref(),
+                    &[],
+                    vec![],
+                    &git_root,
+                    &context.filesystem,
+                    list_options,
+                )
+                .await;
+                let json_output = ListJsonOutput { worktrees, timings_ms: timings_ms() };
                 output().log(
                     &serde_json::to_string_pretty(&json_output)
                         .with_context(|| "Failed to serialize JSON output")?,
                 );
-            } else if !args.names {
+            } else if !args.names && result.unborn_main.is_none() {
                 output().log(result.message.as_deref().unwrap_or("No worktrees found."));
             }
             return Ok(());
         }
 
-        if args.json {
+        if args.stacks {
+            let parents = read_stack_parents(context.executor.clone(), &git_root)
+                .await
+                .with_context(|| "Failed to read stack metadata")?;
+            for line in render_stacks(&result.worktrees, &parents) {
+                output().log(&line);
+            }
+        } else if args.json {
             // Output as JSON
-            let json_worktrees: Vec<WorktreeJsonItem> = result
-                .worktrees
-                .iter()
-                .map(|w| WorktreeJsonItem {
-                    name: w.name.clone(),
-                    branch: w.branch.clone(),
-                    is_clean: w.is_clean,
-                    path: w.path.clone(),
-                })
-                .collect();
-
-            let json_output = ListJsonOutput { worktrees: json_worktrees };
+            let sizes = if args.du {
+                timed("disk_usage", sizes_of(&result.worktrees, &git_root, &context.filesystem))
+                    .await
+            } else {
+                vec![None; result.worktrees.len()]
+            };
+            let json_worktrees = json_items(
+                result.unborn_main.as_ref(),
+                &result.worktrees,
+                sizes,
+                &git_root,
+                &context.filesystem,
+                list_options,
+            )
+            .await;
+
+            let json_output =
+                ListJsonOutput { worktrees: json_worktrees, timings_ms: timings_ms() };
 
             output().log(&serde_json::to_string_pretty(&json_output)?);
         } else if args.names {
@@ -94,11 +259,59 @@ where
 
             for worktree in &result.worktrees {
                 let padded_name = format!("{:<width$}", worktree.name, width = max_name_length + 2);
-                let branch_info =
-                    worktree.branch.as_ref().map(|b| format!("({b})")).unwrap_or_default();
+                // The origin column is only worth its width when other worktrees are listed
+                let origin = if args.all {
+                    format!("{:<10}", worktree.origin.as_str())
+                } else {
+                    String::new()
+                };
+                let branch_info = match &worktree.branch {
+                    Some(b) => format!("({b})"),
+                    None if worktree.is_detached => "(detached HEAD)".to_string(),
+                    None => String::new(),
+                };
                 let status = if !worktree.is_clean { " [dirty]" } else { "" };
+                let gone = if worktree.gone { " [gone]" } else { "" };
+                let locked = lock_marker(worktree);
+                let operation =
+                    detect_in_progress_operation(Path::new(&worktree.path), &context.filesystem)
+                        .await
+                        .ok()
+                        .flatten()
+                        .map(|op| format!(" {}", op.marker()))
+                        .unwrap_or_default();
+
+                output().log(&format!(
+                    "{padded_name}{origin}{branch_info}{gone}{status}{locked}{operation}"
+                ));
+
+                // Creation details are only shown with --verbose
+                if output().verbose {
+                    let metadata = metadata_of(worktree, &git_root, &context.filesystem).await;
+                    if let Some(details) = describe_metadata(
+                        metadata.created_at,
+                        metadata.base_ref.as_deref(),
+                        metadata.phantom_version.as_deref(),
+                        &metadata.labels,
+                    ) {
+                        output().log(&format!("  {details}"));
+                    }
+                }
+            }
 
-                output().log(&format!("{padded_name}{branch_info}{status}"));
+            // Sizes can take a while, so they follow the table instead of holding it back
+            if args.du {
+                let sizes = timed(
+                    "disk_usage",
+                    sizes_of(&result.worktrees, &git_root, &context.filesystem),
+                )
+                .await;
+                output().log("");
+                output()
+                    .log("Disk usage (cached until a worktree's top-level directories change):");
+                for line in disk_usage_lines(&result.worktrees, &sizes) {
+                    output().log(&line);
+                }
             }
         }
     }
@@ -106,10 +319,91 @@ where
     Ok(())
 }
 
+/// Recorded creation metadata, which only phantom worktrees have
+///
+/// A main or external worktree may share its name with a phantom whose state
+/// file is unrelated to it, so none is read for them.
+async fn metadata_of(
+    worktree: &WorktreeInfo,
+    git_root: &Path,
+    filesystem: &dyn FileSystem,
+) -> WorktreeMetadata {
+    if worktree.origin == WorktreeOrigin::Phantom {
+        read_worktree_metadata(filesystem, git_root, &worktree.name).await
+    } else {
+        WorktreeMetadata::default()
+    }
+}
+
+/// Size of each worktree in `worktrees`, in order
+async fn sizes_of(
+    worktrees: &[WorktreeInfo],
+    git_root: &Path,
+    filesystem: &dyn FileSystem,
+) -> Vec<Option<u64>> {
+    let paths: Vec<PathBuf> = worktrees.iter().map(|w| PathBuf::from(&w.path)).collect();
+    worktree_sizes(filesystem, git_root, &paths).await
+}
+
+/// One aligned `name  size` row per worktree, largest first, then the total
+fn disk_usage_lines(worktrees: &[WorktreeInfo], sizes: &[Option<u64>]) -> Vec<String> {
+    let mut rows: Vec<(&str, Option<u64>)> =
+        worktrees.iter().map(|w| w.name.as_str()).zip(sizes.iter().copied()).collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max("total".len());
+
+    let mut lines: Vec<String> = rows
+        .iter()
+        .map(|(name, size)| {
+            let size = size.map_or_else(|| "unreadable".to_string(), |s| format_bytes(s as f64));
+            format!("  {name:<width$}  {size}")
+        })
+        .collect();
+    let total: u64 = sizes.iter().flatten().sum();
+    lines.push(format!("  {:<width$}  {}", "total", format_bytes(total as f64)));
+    lines
+}
+
+/// ` [locked]` marker, with the lock reason when one was given
+fn lock_marker(worktree: &WorktreeInfo) -> String {
+    match (worktree.is_locked, worktree.locked_reason.as_deref()) {
+        (false, _) => String::new(),
+        (true, Some(reason)) if !reason.trim().is_empty() => {
+            format!(" [locked: {}]", reason.split_whitespace().collect::<Vec<_>>().join(" "))
+        }
+        (true, _) => " [locked]".to_string(),
+    }
+}
+
+/// One-line summary of creation metadata, or `None` when nothing was recorded
+fn describe_metadata(
+    created_at: Option<u64>,
+    base_ref: Option<&str>,
+    phantom_version: Option<&str>,
+    labels: &BTreeSet<String>,
+) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(created_at) = created_at {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        parts.push(format!("created {}", format_age(created_at, now)));
+    }
+    if let Some(base_ref) = base_ref {
+        parts.push(format!("from {base_ref}"));
+    }
+    if let Some(version) = phantom_version {
+        parts.push(format!("(phantom {version})"));
+    }
+    if !labels.is_empty() {
+        parts.push(format!("[{}]", labels.iter().cloned().collect::<Vec<_>>().join(", ")));
+    }
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::core::executors::MockCommandExecutor;
+    use std::path::PathBuf;
 
     // IMPORTANT: Mock testing lesson learned
     //
@@ -137,7 +431,19 @@ mod tests {
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
         );
-        let args = ListArgs { fzf: false, json: false, names: false };
+        let args = ListArgs {
+            fzf: false,
+            all: false,
+            json: false,
+            names: false,
+            stacks: false,
+            workspace: false,
+            labels: vec![],
+            label_any: false,
+            no_status: false,
+            check_remote: false,
+            du: false,
+        };
 
         let result = handle(args, context).await;
         assert!(result.is_err());
@@ -155,18 +461,32 @@ mod tests {
         );
 
         // Mock worktree list - empty
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /home/user/project\nHEAD abcd1234\nbranch refs/heads/main\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /home/user/project\0HEAD abcd1234\0branch refs/heads/main\0",
+                "",
+                0,
+            );
 
         let context = HandlerContext::new(
             mock,
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
         );
-        let args = ListArgs { fzf: false, json: false, names: false };
+        let args = ListArgs {
+            fzf: false,
+            all: false,
+            json: false,
+            names: false,
+            stacks: false,
+            workspace: false,
+            labels: vec![],
+            label_any: false,
+            no_status: false,
+            check_remote: false,
+            du: false,
+        };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
@@ -184,21 +504,23 @@ mod tests {
         );
 
         // Mock worktree list - with phantoms
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /home/user/project\n\
-                HEAD abcd1234\n\
-                branch refs/heads/main\n\
-                \n\
-                worktree /home/user/project/.phantom/feature-1\n\
-                HEAD efgh5678\n\
-                branch refs/heads/feature-1\n\
-                \n\
-                worktree /home/user/project/.phantom/feature-2\n\
-                HEAD ijkl9012\n\
-                branch refs/heads/feature-2\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /home/user/project\0\
+                HEAD abcd1234\0\
+                branch refs/heads/main\0\
+                \0\
+                worktree /home/user/project/.phantom/feature-1\0\
+                HEAD efgh5678\0\
+                branch refs/heads/feature-1\0\
+                \0\
+                worktree /home/user/project/.phantom/feature-2\0\
+                HEAD ijkl9012\0\
+                branch refs/heads/feature-2\0",
+                "",
+                0,
+            );
 
         // Mock status checks for each phantom worktree
         mock.expect_command("git")
@@ -216,12 +538,73 @@ mod tests {
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
         );
-        let args = ListArgs { fzf: false, json: false, names: false };
+        let args = ListArgs {
+            fzf: false,
+            all: false,
+            json: false,
+            names: false,
+            stacks: false,
+            workspace: false,
+            labels: vec![],
+            label_any: false,
+            no_status: false,
+            check_remote: false,
+            du: false,
+        };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_list_no_status_skips_status_checks() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/home/user/project/.git",
+            "",
+            0,
+        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /home/user/project\0HEAD abcd1234\0branch refs/heads/main\0\0\
+                 worktree /home/user/project/.git/phantom/worktrees/feature-1\0\
+                 HEAD efgh5678\0branch refs/heads/feature-1\0",
+                "",
+                0,
+            );
+        mock.expect_command("git").with_args(&["status", "--porcelain"]).returns_output(
+            "M README.md\n",
+            "",
+            0,
+        );
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = ListArgs {
+            fzf: false,
+            all: false,
+            json: false,
+            names: true,
+            stacks: false,
+            workspace: false,
+            labels: vec![],
+            label_any: false,
+            no_status: true,
+            check_remote: false,
+            du: false,
+        };
+
+        handle(args, context).await.unwrap();
+        assert!(mock
+            .calls()
+            .iter()
+            .all(|call| call.args.first().is_none_or(|arg| arg != "status")));
+    }
+
     #[tokio::test]
     async fn test_list_json_output() {
         let mut mock = MockCommandExecutor::new();
@@ -234,17 +617,19 @@ mod tests {
         );
 
         // Mock worktree list
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /home/user/project\n\
-                HEAD abcd1234\n\
-                branch refs/heads/main\n\
-                \n\
-                worktree /home/user/project/.phantom/feature-1\n\
-                HEAD efgh5678\n\
-                branch refs/heads/feature-1\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /home/user/project\0\
+                HEAD abcd1234\0\
+                branch refs/heads/main\0\
+                \0\
+                worktree /home/user/project/.phantom/feature-1\0\
+                HEAD efgh5678\0\
+                branch refs/heads/feature-1\0",
+                "",
+                0,
+            );
 
         // Mock status check
         mock.expect_command("git")
@@ -257,12 +642,193 @@ mod tests {
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
         );
-        let args = ListArgs { fzf: false, json: true, names: false };
+        let args = ListArgs {
+            fzf: false,
+            all: false,
+            json: true,
+            names: false,
+            stacks: false,
+            workspace: false,
+            labels: vec![],
+            label_any: false,
+            no_status: false,
+            check_remote: false,
+            du: false,
+        };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_json_item_includes_metadata() {
+        use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+        use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+
+        let fs = MockFileSystem::new();
+        fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::ReadToString,
+            path: Some(PathBuf::from("/repo/.git/phantom/state/feature.json")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::String(
+                r#"{"createdAt": 1700000000, "baseRef": "main", "phantomVersion": "0.1.0"}"#
+                    .to_string(),
+            )),
+        });
+        let worktree = WorktreeInfo {
+            name: "feature".to_string(),
+            path: "/repo/.git/phantom/worktrees/feature".to_string(),
+            branch: Some("feature".to_string()),
+            is_clean: true,
+            is_locked: false,
+            locked_reason: None,
+            is_detached: false,
+            gone: false,
+            origin: WorktreeOrigin::Phantom,
+        };
+
+        // An external worktree that shares the name does not read the phantom's state file
+        let external = WorktreeInfo { origin: WorktreeOrigin::External, ..worktree.clone() };
+        let item =
+            WorktreeJsonItem::load(&external, Path::new("/repo"), &fs, ListOptions::default())
+                .await;
+        let json = serde_json::to_value(&item).unwrap();
+        assert_eq!(json["origin"], "external");
+        assert!(json.get("created_at").is_none());
+
+        let item =
+            WorktreeJsonItem::load(&worktree, Path::new("/repo"), &fs, ListOptions::default())
+                .await;
+        let json = serde_json::to_value(&item).unwrap();
+        assert_eq!(json["origin"], "phantom");
+        assert_eq!(json["is_clean"], true);
+        assert_eq!(json["created_at"], 1_700_000_000);
+        assert_eq!(json["base_ref"], "main");
+        assert_eq!(json["phantom_version"], "0.1.0");
+
+        // Without a state file the fields are omitted rather than failing the listing, and an
+        // unchecked status is omitted rather than reported clean
+        let options = ListOptions { include_status: false, ..ListOptions::default() };
+        let item = WorktreeJsonItem::load(&worktree, Path::new("/repo"), &fs, options).await;
+        let json = serde_json::to_value(&item).unwrap();
+        assert!(json.get("is_clean").is_none());
+        assert!(json.get("created_at").is_none());
+        assert!(json.get("base_ref").is_none());
+        assert_eq!(json["name"], "feature");
+    }
+
+    #[test]
+    fn test_describe_metadata() {
+        assert_eq!(describe_metadata(None, None, None, &BTreeSet::new()), None);
+        assert_eq!(
+            describe_metadata(None, Some("main"), Some("0.1.0"), &BTreeSet::new()).as_deref(),
+            Some("from main (phantom 0.1.0)")
+        );
+        let details = describe_metadata(Some(0), Some("HEAD"), None, &BTreeSet::new()).unwrap();
+        assert!(details.starts_with("created "));
+        assert!(details.ends_with("ago from HEAD"));
+
+        let labels: BTreeSet<String> = ["spike", "review"].map(String::from).into();
+        assert_eq!(
+            describe_metadata(None, None, None, &labels).as_deref(),
+            Some("[review, spike]")
+        );
+    }
+
+    #[test]
+    fn test_lock_marker() {
+        let mut worktree = WorktreeInfo {
+            name: "feature".to_string(),
+            path: "/repo/.git/phantom/worktrees/feature".to_string(),
+            branch: Some("feature".to_string()),
+            is_clean: true,
+            is_locked: false,
+            locked_reason: None,
+            is_detached: false,
+            gone: false,
+            origin: WorktreeOrigin::Phantom,
+        };
+        assert_eq!(lock_marker(&worktree), "");
+
+        worktree.is_locked = true;
+        assert_eq!(lock_marker(&worktree), " [locked]");
+
+        worktree.locked_reason = Some("on a\nusb drive".to_string());
+        assert_eq!(lock_marker(&worktree), " [locked: on a usb drive]");
+    }
+
+    #[test]
+    fn test_disk_usage_lines_sort_largest_first_with_total() {
+        let worktree = |name: &str| WorktreeInfo {
+            name: name.to_string(),
+            path: format!("/repo/.git/phantom/worktrees/{name}"),
+            branch: Some(name.to_string()),
+            is_clean: true,
+            is_locked: false,
+            locked_reason: None,
+            is_detached: false,
+            gone: false,
+            origin: WorktreeOrigin::Phantom,
+        };
+        let worktrees = [worktree("docs"), worktree("monorepo"), worktree("gone")];
+
+        let lines = disk_usage_lines(&worktrees, &[Some(2048), Some(3 << 30), None]);
+        assert_eq!(
+            lines,
+            vec![
+                "  monorepo  3.0 GB",
+                "  docs      2.0 KB",
+                "  gone      unreadable",
+                "  total     3.0 GB",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_rejects_invalid_label() {
+        let context = HandlerContext::new(
+            MockCommandExecutor::new(),
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = ListArgs {
+            fzf: false,
+            all: false,
+            json: false,
+            names: false,
+            stacks: false,
+            workspace: false,
+            labels: vec!["Review".to_string()],
+            label_any: false,
+            no_status: false,
+            check_remote: false,
+            du: false,
+        };
+
+        let err = handle(args, context).await.unwrap_err();
+        assert!(err.to_string().contains("label 'Review'"));
+    }
+
+    #[test]
+    fn test_label_any_requires_label() {
+        use crate::cli::Cli;
+        use clap::Parser;
+
+        assert!(Cli::try_parse_from(["phantom", "list", "--label-any"]).is_err());
+        assert!(Cli::try_parse_from([
+            "phantom",
+            "list",
+            "--label",
+            "a",
+            "--label",
+            "b",
+            "--label-any"
+        ])
+        .is_ok());
+    }
+
     #[tokio::test]
     async fn test_list_names_only() {
         let mut mock = MockCommandExecutor::new();
@@ -275,17 +841,19 @@ mod tests {
         );
 
         // Mock worktree list
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /home/user/project\n\
-                HEAD abcd1234\n\
-                branch refs/heads/main\n\
-                \n\
-                worktree /home/user/project/.phantom/feature-1\n\
-                HEAD efgh5678\n\
-                branch refs/heads/feature-1\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /home/user/project\0\
+                HEAD abcd1234\0\
+                branch refs/heads/main\0\
+                \0\
+                worktree /home/user/project/.phantom/feature-1\0\
+                HEAD efgh5678\0\
+                branch refs/heads/feature-1\0",
+                "",
+                0,
+            );
 
         // Mock status check
         mock.expect_command("git")
@@ -298,9 +866,102 @@ mod tests {
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
         );
-        let args = ListArgs { fzf: false, json: false, names: true };
+        let args = ListArgs {
+            fzf: false,
+            all: false,
+            json: false,
+            names: true,
+            stacks: false,
+            workspace: false,
+            labels: vec![],
+            label_any: false,
+            no_status: false,
+            check_remote: false,
+            du: false,
+        };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_list_unborn_head() {
+        let mut mock = MockCommandExecutor::new();
+
+        // Mock git root check
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/home/user/project/.git",
+            "",
+            0,
+        );
+
+        // Mock worktree list for a repository without commits
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /home/user/project\0\
+                HEAD 0000000000000000000000000000000000000000\0\
+                branch refs/heads/main\0",
+                "",
+                0,
+            );
+
+        let filesystem = crate::core::filesystems::MockFileSystem::new();
+        let context = HandlerContext::new(
+            mock.clone(),
+            filesystem.clone(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = ListArgs {
+            fzf: false,
+            all: false,
+            json: false,
+            names: false,
+            stacks: false,
+            workspace: false,
+            labels: vec![],
+            label_any: false,
+            no_status: false,
+            check_remote: false,
+            du: false,
+        };
+
+        let result = handle(args, context).await;
+        assert!(result.is_ok());
+
+        // The listing is empty and the main worktree is rendered in its place
+        let listing = Phantom::new("/home/user/project", mock.clone(), filesystem)
+            .list_with_options(ListOptions {
+                include_status: true,
+                check_gone: true,
+                check_remote: false,
+                include_all: false,
+            })
+            .await
+            .unwrap();
+        assert!(listing.worktrees.is_empty());
+        let main = listing.unborn_main.expect("main worktree without commits");
+        assert_eq!(unborn_main_line(&main), "project  (main) (no commits yet)");
+        // --json reports it as well
+        let items = json_items(
+            Some(&main),
+            &listing.worktrees,
+            vec![],
+            Path::new("/home/user/project"),
+            &crate::core::filesystems::MockFileSystem::new(),
+            ListOptions::default(),
+        )
+        .await;
+        let json = serde_json::to_value(&items).unwrap();
+        assert_eq!(json[0]["name"], "project");
+        assert_eq!(json[0]["branch"], "main");
+        assert_eq!(json[0]["origin"], "main");
+        assert_eq!(json[0]["unborn"], true);
+        assert!(json[0].get("is_clean").is_none());
+        // No status checks run against a worktree without commits
+        assert!(!mock
+            .calls()
+            .iter()
+            .any(|call| call.args.first().is_some_and(|arg| arg == "status")));
+    }
 }
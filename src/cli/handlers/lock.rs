@@ -0,0 +1,144 @@
+use crate::cli::commands::lock::{LockArgs, UnlockArgs};
+use crate::cli::context::HandlerContext;
+use crate::cli::output::output;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use crate::git::libs::lock_worktree::{lock_worktree, unlock_worktree};
+use crate::worktree::validate::validate_worktree_exists;
+use anyhow::{Context, Result};
+
+/// Handle the lock command
+pub async fn handle<E, F, H>(args: LockArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let worktree = validate_worktree_exists(&git_root, &args.name, &context.filesystem)
+        .await
+        .with_context(|| format!("Failed to validate worktree '{}' exists", args.name))?;
+
+    let locked =
+        lock_worktree(context.executor.clone(), &git_root, &worktree.path, args.reason.as_deref())
+            .await
+            .with_context(|| format!("Failed to lock worktree '{}'", args.name))?;
+    if locked {
+        output().success(&format!("Locked '{}'", args.name));
+    } else {
+        output().log(&format!("'{}' is already locked", args.name));
+    }
+
+    Ok(())
+}
+
+/// Handle the unlock command
+pub async fn handle_unlock<E, F, H>(
+    args: UnlockArgs,
+    context: HandlerContext<E, F, H>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let worktree = validate_worktree_exists(&git_root, &args.name, &context.filesystem)
+        .await
+        .with_context(|| format!("Failed to validate worktree '{}' exists", args.name))?;
+
+    let unlocked = unlock_worktree(context.executor.clone(), &git_root, &worktree.path)
+        .await
+        .with_context(|| format!("Failed to unlock worktree '{}'", args.name))?;
+    if unlocked {
+        output().success(&format!("Unlocked '{}'", args.name));
+    } else {
+        output().log(&format!("'{}' is not locked, nothing to unlock", args.name));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::exit_handler::MockExitHandler;
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+    use std::path::PathBuf;
+
+    const WORKTREE: &str = "/repo/.git/phantom/worktrees/feature/login";
+
+    fn context(
+        mock: MockCommandExecutor,
+        exists: bool,
+    ) -> HandlerContext<MockCommandExecutor, MockFileSystem, MockExitHandler> {
+        let filesystem = MockFileSystem::new();
+        filesystem.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from(WORKTREE)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(exists)),
+        });
+        HandlerContext::new(mock, filesystem, MockExitHandler::new())
+    }
+
+    fn git_root(mock: &mut MockCommandExecutor) {
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lock_passes_the_reason_to_git() {
+        let mut mock = MockCommandExecutor::new();
+        git_root(&mut mock);
+        mock.expect_command("git")
+            .with_args(&["worktree", "lock", "--reason", "waiting on review", WORKTREE])
+            .in_dir("/repo")
+            .returns_success();
+
+        let args = LockArgs {
+            name: "feature/login".to_string(),
+            reason: Some("waiting on review".to_string()),
+        };
+        handle(args, context(mock.clone(), true)).await.unwrap();
+        assert_eq!(mock.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unlock_runs_git_worktree_unlock() {
+        let mut mock = MockCommandExecutor::new();
+        git_root(&mut mock);
+        mock.expect_command("git")
+            .with_args(&["worktree", "unlock", WORKTREE])
+            .in_dir("/repo")
+            .returns_success();
+
+        let args = UnlockArgs { name: "feature/login".to_string() };
+        handle_unlock(args, context(mock.clone(), true)).await.unwrap();
+        assert_eq!(mock.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_lock_missing_worktree_fails_before_git() {
+        let mut mock = MockCommandExecutor::new();
+        git_root(&mut mock);
+
+        let args = LockArgs { name: "feature/login".to_string(), reason: None };
+        let err = handle(args, context(mock.clone(), false)).await.unwrap_err();
+
+        assert!(format!("{err:#}").contains("not found"), "{err:#}");
+        assert_eq!(mock.calls().len(), 1);
+    }
+}
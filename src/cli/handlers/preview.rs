@@ -0,0 +1,126 @@
+use crate::cli::commands::preview::PreviewArgs;
+use crate::cli::context::HandlerContext;
+use crate::cli::output::output;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use crate::git::libs::worktree_status::{recent_commits, short_status};
+use crate::worktree::validate::{validate_worktree_exists, validate_worktree_name};
+use crate::Result;
+use tracing::debug;
+
+/// Commits shown at the top of the preview
+const PREVIEW_COMMITS: usize = 5;
+
+/// Handle the hidden `__preview` command used by the fzf preview pane
+///
+/// Never fails: anything that cannot be resolved prints nothing, so fzf shows
+/// an empty pane instead of an error.
+pub async fn handle<E, F, H>(args: PreviewArgs, context: HandlerContext<E, F, H>)
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    match preview_lines(&args.name, &context).await {
+        Ok(lines) => {
+            for line in lines {
+                output().log(&line);
+            }
+        }
+        Err(e) => debug!("No preview for '{}': {e}", args.name),
+    }
+}
+
+/// Recent commits, then the short status if the worktree has changes
+async fn preview_lines<E, F, H>(
+    name: &str,
+    context: &HandlerContext<E, F, H>,
+) -> Result<Vec<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    validate_worktree_name(name)?;
+    let git_root = context.git_root().await?;
+    let worktree_path = validate_worktree_exists(&git_root, name, &context.filesystem).await?.path;
+
+    let commits = recent_commits(context.executor.clone(), &worktree_path, PREVIEW_COMMITS).await?;
+    let status = short_status(context.executor.clone(), &worktree_path).await?;
+
+    let mut lines: Vec<String> = commits.lines().map(String::from).collect();
+    if !status.is_empty() {
+        lines.push(String::new());
+        lines.extend(status.lines().map(String::from));
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+    use std::path::PathBuf;
+
+    fn context(
+        mock: MockCommandExecutor,
+        fs: MockFileSystem,
+    ) -> HandlerContext<
+        MockCommandExecutor,
+        MockFileSystem,
+        crate::core::exit_handler::MockExitHandler,
+    > {
+        HandlerContext::new(mock, fs, crate::core::exit_handler::MockExitHandler::new())
+    }
+
+    #[tokio::test]
+    async fn test_preview_lines_commits_then_status() {
+        let worktree = "/repo/.git/phantom/worktrees/feature";
+        let mut mock = MockCommandExecutor::new();
+        let fs = MockFileSystem::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from(worktree)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        mock.expect_command("git")
+            .with_args(&["log", "--oneline", "-5"])
+            .in_dir(worktree)
+            .returns_output("abc1234 Second\ndef5678 First\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["status", "--short"])
+            .in_dir(worktree)
+            .returns_output(" M src/lib.rs\n", "", 0);
+
+        let lines = preview_lines("feature", &context(mock, fs)).await.unwrap();
+        assert_eq!(lines, vec!["abc1234 Second", "def5678 First", "", " M src/lib.rs"]);
+    }
+
+    #[tokio::test]
+    async fn test_preview_unknown_worktree_is_silent() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        let context = context(mock.clone(), MockFileSystem::new());
+
+        assert!(preview_lines("missing", &context).await.is_err());
+        assert!(preview_lines("../escape", &context).await.is_err());
+        // The handler swallows the error instead of surfacing it to fzf
+        handle(PreviewArgs { name: "missing".to_string() }, context).await;
+        assert!(!mock.calls().iter().any(|call| call.args.first().is_some_and(|a| a == "log")));
+    }
+}
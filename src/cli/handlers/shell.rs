@@ -1,16 +1,24 @@
 use crate::cli::commands::shell::ShellArgs;
 use crate::cli::context::HandlerContext;
+use crate::cli::handlers::exec::worktree_env_file;
 use crate::cli::output::output;
 use crate::core::command_executor::CommandExecutor;
+use crate::core::env_map::{EnvMap, EnvSources};
 use crate::core::exit_handler::ExitHandler;
 use crate::core::filesystem::FileSystem;
-use crate::git::libs::get_git_root::get_git_root;
-use crate::process::exec::spawn_shell_in_worktree;
+use crate::process::exec::{resolve_worktree_dir, spawn_shell_in_worktree};
 use crate::process::kitty::{
     execute_kitty_command, is_inside_kitty, KittyOptions, KittySplitDirection,
 };
-use crate::process::shell::{detect_shell, get_phantom_env};
-use crate::process::tmux::{execute_tmux_command, is_inside_tmux, TmuxOptions, TmuxSplitDirection};
+use crate::process::multiplexer::{resolve_launch_target, LaunchTarget};
+use crate::process::shell::{detect_shell_with_config, get_phantom_env};
+use crate::process::title::TerminalTitle;
+use crate::process::tmux::{
+    execute_tmux_command, find_tmux_window, is_inside_tmux, select_tmux_window, TmuxOptions,
+    TmuxSplitDirection,
+};
+use crate::worktree::in_progress::detect_in_progress_operation;
+use crate::worktree::last_used::{read_last_worktree, record_last_worktree};
 use crate::worktree::select::select_worktree_with_fzf;
 use crate::worktree::validate::validate_worktree_exists;
 use anyhow::{anyhow, bail, Context, Result};
@@ -23,14 +31,16 @@ where
     H: ExitHandler + Clone + 'static,
 {
     // Validate args
-    if args.name.is_none() && !args.fzf {
-        bail!("Usage: phantom shell <worktree-name> or phantom shell --fzf");
+    if args.name.is_none() && !args.fzf && !args.last {
+        bail!("Usage: phantom shell <worktree-name> or phantom shell --fzf|--last");
     }
 
     if args.name.is_some() && args.fzf {
         bail!("Cannot specify both a worktree name and --fzf option");
     }
 
+    let extra_env = EnvMap::parse_assignments(&args.env).map_err(|e| anyhow!(e))?;
+
     // Determine tmux direction
     let tmux_direction = if args.tmux {
         Some(TmuxSplitDirection::New)
@@ -54,24 +64,54 @@ where
     };
 
     // Validate multiplexer options
-    if tmux_direction.is_some() && !is_inside_tmux().await {
+    if tmux_direction.is_some() && !is_inside_tmux(&*context.environment).await {
         bail!("The --tmux option can only be used inside a tmux session");
     }
 
-    if kitty_direction.is_some() && !is_inside_kitty().await {
+    if kitty_direction.is_some() && !is_inside_kitty(&*context.environment).await {
         bail!("The --kitty option can only be used inside a kitty terminal");
     }
 
+    // clap already rejects --tmux-size with --tmux
+    if args.tmux_size.is_some() && tmux_direction.is_none() {
+        bail!("The --tmux-size option can only be used with a --tmux-vertical or --tmux-horizontal option");
+    }
+
+    if args.no_focus && kitty_direction.is_none() {
+        bail!("The --no-focus option can only be used with a --kitty option");
+    }
+
+    if args.tmux_target.is_some() && tmux_direction.is_none() {
+        bail!("The --tmux-target option can only be used with a --tmux option");
+    }
+
     // Get git root
-    let git_root = get_git_root(context.executor.clone())
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let config = context
+        .config(&git_root)
         .await
-        .with_context(|| "Failed to determine git repository root")?;
+        .with_context(|| "Failed to load config")?
+        .map(|loaded| loaded.config.clone())
+        .unwrap_or_default();
+
+    // Without --tmux or --kitty flags, open in the configured default multiplexer
+    let explicit = LaunchTarget::from_flags(tmux_direction, kitty_direction);
+    let target =
+        resolve_launch_target(&config, &*context.environment, explicit, args.no_multiplexer).await;
+    let (tmux_direction, kitty_direction) = (target.tmux(), target.kitty());
 
     // Get worktree name
     let worktree_name = if args.fzf {
-        let result = select_worktree_with_fzf(context.executor.clone(), &git_root)
-            .await
-            .with_context(|| "Failed to select worktree with fzf")?;
+        let result = select_worktree_with_fzf(
+            context.executor.clone(),
+            &context.filesystem,
+            &git_root,
+            &config,
+        )
+        .await
+        .with_context(|| "Failed to select worktree with fzf")?;
 
         match result {
             Some(worktree) => worktree.name,
@@ -80,22 +120,73 @@ where
                 return Ok(());
             }
         }
+    } else if args.last {
+        read_last_worktree(&context.filesystem, &git_root)
+            .await
+            .ok_or_else(|| anyhow!("No worktree has been used yet; pick one with --fzf"))?
     } else {
         args.name.unwrap()
     };
 
     // Validate worktree exists
-    let validation = validate_worktree_exists(&git_root, &worktree_name, &context.filesystem)
-        .await
-        .with_context(|| format!("Failed to validate worktree '{worktree_name}' exists"))?;
+    let validation =
+        match validate_worktree_exists(&git_root, &worktree_name, &context.filesystem).await {
+            Err(_) if args.last => bail!(
+                "The last used worktree '{worktree_name}' no longer exists; pick one with --fzf"
+            ),
+            result => result
+                .with_context(|| format!("Failed to validate worktree '{worktree_name}' exists"))?,
+        };
     let worktree_path = validation.path;
+    record_last_worktree(&context.filesystem, &git_root, &worktree_name).await;
+    let start_dir = resolve_worktree_dir(&worktree_path, args.cwd.as_deref(), &context.filesystem)
+        .await
+        .map_err(|e| anyhow!(e))?;
+
+    // Shells are interactive, so an unfinished rebase/merge only warrants a warning
+    if let Ok(Some(operation)) =
+        detect_in_progress_operation(&worktree_path, &context.filesystem).await
+    {
+        output().warn(&format!("Worktree '{worktree_name}' has a {operation} in progress"));
+    }
+
+    let extra_env = EnvSources {
+        env_files: worktree_env_file(&context, &git_root, &worktree_path).await?,
+        flags: extra_env,
+        ..Default::default()
+    }
+    .merge();
 
     // Get shell info
-    let shell_info = detect_shell().with_context(|| "Failed to detect shell")?;
-    let shell_command = shell_info.path;
+    let shell_info = detect_shell_with_config(&*context.environment, config.shell.as_deref())
+        .with_context(|| "Failed to detect shell")?;
+    let shell_command = shell_info.path.clone();
+    // fish only shows the prompt prefix through an init command
+    let prompt_args = shell_info.shell_type.prompt_args();
+    let env = EnvSources {
+        phantom: get_phantom_env(
+            &*context.environment,
+            shell_info.shell_type,
+            &worktree_name,
+            &worktree_path.to_string_lossy(),
+        ),
+        flags: extra_env.clone(),
+        ..Default::default()
+    }
+    .merge();
 
     // Handle tmux execution
     if let Some(direction) = tmux_direction {
+        if args.reuse && direction == TmuxSplitDirection::New {
+            if let Some(window) = find_tmux_window(&context.executor, &worktree_name).await? {
+                select_tmux_window(&context.executor, &window)
+                    .await
+                    .with_context(|| format!("Failed to switch to tmux window '{window}'"))?;
+                output().log(&format!("Switched to existing tmux window '{window}'"));
+                return Ok(());
+            }
+        }
+
         output().log(&format!(
             "Opening worktree '{}' in tmux {}...",
             worktree_name,
@@ -105,14 +196,16 @@ where
         let options = TmuxOptions {
             direction,
             command: shell_command,
-            args: None,
-            cwd: Some(worktree_path.to_string_lossy().to_string()),
-            env: Some(get_phantom_env(&worktree_name, &worktree_path.to_string_lossy())),
+            args: prompt_args,
+            cwd: Some(start_dir.to_string_lossy().to_string()),
+            env: Some(env.clone()),
             window_name: if direction == TmuxSplitDirection::New {
                 Some(worktree_name.clone())
             } else {
                 None
             },
+            percentage: args.tmux_size,
+            target: args.tmux_target.clone(),
         };
 
         execute_tmux_command(&context.executor, options)
@@ -133,14 +226,16 @@ where
         let options = KittyOptions {
             direction,
             command: shell_command,
-            args: None,
-            cwd: Some(worktree_path.to_string_lossy().to_string()),
-            env: Some(get_phantom_env(&worktree_name, &worktree_path.to_string_lossy())),
+            args: prompt_args,
+            cwd: Some(start_dir.to_string_lossy().to_string()),
+            env: Some(env),
             window_title: if direction == KittySplitDirection::New {
                 Some(worktree_name.clone())
             } else {
                 None
             },
+            focus: !args.no_focus,
+            keep_title: true,
         };
 
         execute_kitty_command(&context.executor, options)
@@ -151,13 +246,19 @@ where
     }
 
     // Normal shell execution
-    output().log(&format!("Entering worktree '{}' at {}", worktree_name, worktree_path.display()));
+    output().log(&format!("Entering worktree '{}' at {}", worktree_name, start_dir.display()));
     output().log("Type 'exit' to return to your original directory\n");
 
+    let title =
+        TerminalTitle::set(&worktree_name, config.terminal_title_enabled(), &*context.environment);
     let result = spawn_shell_in_worktree(
         &git_root,
         &worktree_name,
+        &shell_info,
+        &extra_env,
+        args.cwd.as_deref(),
         &context.filesystem,
+        &*context.environment,
         Some(context.executor.clone()),
     )
     .await
@@ -169,18 +270,19 @@ where
             worktree_path.display()
         )
     })?;
+    drop(title);
 
     // Exit with the same code as the shell
-    context.exit_handler.exit(result.exit_code);
+    context.exit(result.exit_code);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::environment::MockEnvironment;
     use crate::core::executors::MockCommandExecutor;
     use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
     use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
-    use crate::test_utils::EnvGuard;
     use std::path::PathBuf;
 
     #[tokio::test]
@@ -202,16 +304,24 @@ mod tests {
         let args = ShellArgs {
             name: Some("test".to_string()),
             fzf: false,
+            last: false,
+            env: vec![],
+            cwd: None,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            reuse: false,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
         };
 
         let result = handle(args, context).await;
@@ -228,16 +338,24 @@ mod tests {
         let args = ShellArgs {
             name: None,
             fzf: false,
+            last: false,
+            env: vec![],
+            cwd: None,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            reuse: false,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
         };
 
         let result = handle(args, context).await;
@@ -255,16 +373,24 @@ mod tests {
         let args = ShellArgs {
             name: Some("test".to_string()),
             fzf: true,
+            last: false,
+            env: vec![],
+            cwd: None,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            reuse: false,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
         };
 
         let result = handle(args, context).await;
@@ -278,32 +404,33 @@ mod tests {
             MockCommandExecutor::new(),
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
-        );
+        )
+        .with_environment(MockEnvironment::new());
         let args = ShellArgs {
             name: Some("test".to_string()),
             fzf: false,
+            last: false,
+            env: vec![],
+            cwd: None,
             tmux: true,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            reuse: false,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
         };
 
-        {
-            let _guard = EnvGuard::remove("TMUX"); // Ensure TMUX is not set
-            let result = handle(args, context).await;
-            // This will likely pass because is_inside_tmux() checks env directly
-            // In a real test environment without TMUX set, this would fail
-            if std::env::var("TMUX").is_err() {
-                assert!(result.is_err());
-                assert!(result.unwrap_err().to_string().contains("inside a tmux session"));
-            }
-        }
+        let result = handle(args, context).await;
+        assert!(result.unwrap_err().to_string().contains("inside a tmux session"));
     }
 
     #[tokio::test]
@@ -312,39 +439,41 @@ mod tests {
             MockCommandExecutor::new(),
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
-        );
+        )
+        .with_environment(MockEnvironment::new());
         let args = ShellArgs {
             name: Some("test".to_string()),
             fzf: false,
+            last: false,
+            env: vec![],
+            cwd: None,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            reuse: false,
             kitty: true,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
         };
 
-        {
-            let _guard = EnvGuard::remove("KITTY_WINDOW_ID"); // Ensure KITTY_WINDOW_ID is not set
-            let result = handle(args, context).await;
-            // This will likely pass because is_inside_kitty() checks env directly
-            // In a real test environment without KITTY_WINDOW_ID set, this would fail
-            if std::env::var("KITTY_WINDOW_ID").is_err() {
-                assert!(result.is_err());
-                assert!(result.unwrap_err().to_string().contains("inside a kitty terminal"));
-            }
-        }
+        let result = handle(args, context).await;
+        assert!(result.unwrap_err().to_string().contains("inside a kitty terminal"));
     }
 
     #[tokio::test]
     #[should_panic(expected = "MockExitHandler::exit called with code 0")]
     async fn test_shell_normal_execution() {
         let mut mock = MockCommandExecutor::new();
-        let mock_fs = MockFileSystem::new();
+        // validate_worktree_exists runs in the handler and again in spawn_shell_in_worktree
+        let mock_fs = MockFileSystem::with_default_dirs(&["/repo/.git/phantom/worktrees/test"]);
 
         // Mock git root check
         mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
@@ -353,27 +482,6 @@ mod tests {
             0,
         );
 
-        // Mock filesystem check for worktree existence
-        // Note: validate_worktree_exists is called twice - once in the handler and once in spawn_shell_in_worktree
-        mock_fs.expect(FileSystemExpectation {
-            operation: FileSystemOperation::IsDir,
-            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/test")),
-            from_path: None,
-            to_path: None,
-            contents: None,
-            result: Ok(MockResult::Bool(true)), // Directory exists
-        });
-
-        // Second expectation for the same path (called from spawn_shell_in_worktree)
-        mock_fs.expect(FileSystemExpectation {
-            operation: FileSystemOperation::IsDir,
-            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/test")),
-            from_path: None,
-            to_path: None,
-            contents: None,
-            result: Ok(MockResult::Bool(true)), // Directory exists
-        });
-
         // Mock shell execution
         let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
         mock.expect_command(&shell)
@@ -385,16 +493,24 @@ mod tests {
         let args = ShellArgs {
             name: Some("test".to_string()),
             fzf: false,
+            last: false,
+            env: vec![],
+            cwd: None,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            reuse: false,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
         };
 
         // This will panic with MockExitHandler
@@ -403,12 +519,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_shell_tmux_new_window() {
+        let environment = MockEnvironment::new()
+            .with_var("TMUX", "/tmp/tmux-1000/default,12345,0")
+            .with_var("SHELL", "/bin/zsh");
         let mut mock = MockCommandExecutor::new();
         let mock_fs = MockFileSystem::new();
 
-        // Set TMUX env var to simulate being inside tmux
-        let _guard = EnvGuard::set("TMUX", "/tmp/tmux-1000/default,12345,0");
-
         // Mock git root check
         mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
             "/repo/.git",
@@ -441,40 +557,181 @@ mod tests {
                 "PHANTOM_WORKTREE=test",
                 "-e",
                 "PHANTOM_WORKTREE_PATH=/repo/.git/phantom/worktrees/test",
-                std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string()).as_str(),
+                "/bin/zsh",
             ])
             .returns_output("", "", 0);
 
         let context =
-            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new())
+                .with_environment(environment);
         let args = ShellArgs {
             name: Some("test".to_string()),
             fzf: false,
+            last: false,
+            env: vec![],
+            cwd: None,
             tmux: true,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            reuse: false,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
         };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
+    }
 
-        // Guard will automatically restore env var when dropped
+    #[tokio::test]
+    async fn test_shell_tmux_keeps_awkward_worktree_path_intact() {
+        let environment = MockEnvironment::new().with_var("TMUX", "/tmp/tmux-1000/default,12345,0");
+        let root = "/home/me/Work Projects/client 'repo' #1 é";
+        let worktree = format!("{root}/.git/phantom/worktrees/test");
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            &format!("{root}/.git"),
+            "",
+            0,
+        );
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from(&worktree)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        mock.expect_command("tmux").returns_output("", "", 0);
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        )
+        .with_environment(environment);
+        handle(ShellArgs { reuse: false, ..reuse_args() }, context).await.unwrap();
+
+        let calls = mock.calls();
+        let tmux = calls.iter().find(|call| call.program == "tmux").unwrap();
+        let escaped = worktree.replace('#', "##");
+        assert!(tmux.args.windows(2).any(|pair| pair == ["-c", escaped.as_str()]));
+        let path_env = format!("PHANTOM_WORKTREE_PATH={worktree}");
+        assert!(tmux.args.windows(2).any(|pair| pair == ["-e", path_env.as_str()]));
+    }
+
+    fn reuse_args() -> ShellArgs {
+        ShellArgs {
+            name: Some("test".to_string()),
+            fzf: false,
+            last: false,
+            env: vec![],
+            cwd: None,
+            tmux: true,
+            tmux_vertical: false,
+            tmux_v: false,
+            tmux_horizontal: false,
+            tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            reuse: true,
+            kitty: false,
+            kitty_vertical: false,
+            kitty_v: false,
+            kitty_horizontal: false,
+            kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
+        }
+    }
+
+    fn expect_existing_worktree(mock: &mut MockCommandExecutor, mock_fs: &MockFileSystem) {
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/test")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
     }
 
     #[tokio::test]
-    async fn test_shell_kitty_new_tab() {
+    async fn test_shell_tmux_reuse_existing_window() {
+        let environment = MockEnvironment::new().with_var("TMUX", "/tmp/tmux-1000/default,12345,0");
         let mut mock = MockCommandExecutor::new();
         let mock_fs = MockFileSystem::new();
 
-        // Set KITTY_WINDOW_ID env var to simulate being inside kitty
-        let _guard = EnvGuard::set("KITTY_WINDOW_ID", "1");
+        expect_existing_worktree(&mut mock, &mock_fs);
+        mock.expect_command("tmux")
+            .with_args(&["list-windows", "-F", "#{window_name}"])
+            .returns_output("zsh\ntest\n", "", 0);
+        mock.expect_command("tmux")
+            .with_args(&["select-window", "-t", "test"])
+            .times(1)
+            .returns_success();
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        )
+        .with_environment(environment);
+        handle(reuse_args(), context).await.unwrap();
+
+        mock.verify().unwrap();
+        assert!(!mock
+            .calls()
+            .iter()
+            .any(|call| call.args.first().is_some_and(|a| a == "new-window")));
+    }
+
+    #[tokio::test]
+    async fn test_shell_tmux_reuse_falls_back_to_new_window() {
+        let environment = MockEnvironment::new().with_var("TMUX", "/tmp/tmux-1000/default,12345,0");
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+
+        expect_existing_worktree(&mut mock, &mock_fs);
+        mock.expect_command("tmux")
+            .with_args(&["list-windows", "-F", "#{window_name}"])
+            .returns_output("zsh\n", "", 0);
+        mock.expect_command("tmux").returns_success();
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        )
+        .with_environment(environment);
+        handle(reuse_args(), context).await.unwrap();
+
+        let calls = mock.calls();
+        let last = calls.last().unwrap();
+        assert_eq!(last.program, "tmux");
+        assert_eq!(&last.args[..3], ["new-window", "-n", "test"]);
+    }
+
+    #[tokio::test]
+    async fn test_shell_kitty_new_tab() {
+        let environment =
+            MockEnvironment::new().with_var("KITTY_WINDOW_ID", "1").with_var("SHELL", "/bin/zsh");
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
 
         // Mock git root check
         mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
@@ -500,36 +757,45 @@ mod tests {
                 "launch",
                 "--type=tab",
                 "--tab-title=test",
-                "--cwd=/repo/.git/phantom/worktrees/test",
+                "--cwd",
+                "/repo/.git/phantom/worktrees/test",
+                "--env=KITTY_WINDOW_TITLE=test",
                 "--env=PHANTOM_ACTIVE=1",
                 "--env=PHANTOM_WORKTREE=test",
                 "--env=PHANTOM_WORKTREE_PATH=/repo/.git/phantom/worktrees/test",
                 "--",
-                std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string()).as_str(),
+                "/bin/zsh",
             ])
             .returns_output("", "", 0);
 
         let context =
-            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new())
+                .with_environment(environment);
         let args = ShellArgs {
             name: Some("test".to_string()),
             fzf: false,
+            last: false,
+            env: vec![],
+            cwd: None,
             tmux: false,
             tmux_vertical: false,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            reuse: false,
             kitty: true,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
         };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
-
-        // Guard will automatically restore env var when dropped
     }
 
     #[tokio::test]
@@ -551,16 +817,24 @@ mod tests {
         let args = ShellArgs {
             name: Some("test".to_string()),
             fzf: false,
+            last: false,
+            env: vec![],
+            cwd: None,
             tmux: false,
             tmux_vertical: true,
             tmux_v: false,
             tmux_horizontal: false,
             tmux_h: false,
+            tmux_size: None,
+            tmux_target: None,
+            reuse: false,
             kitty: false,
             kitty_vertical: false,
             kitty_v: false,
             kitty_horizontal: false,
             kitty_h: false,
+            no_focus: false,
+            no_multiplexer: false,
         };
 
         // This test verifies that tmux_vertical flag is properly handled
@@ -0,0 +1,54 @@
+use crate::cli::commands::completion::Shell;
+use crate::cli::commands::shellenv::ShellenvArgs;
+use anyhow::Result;
+
+/// Shared by bash and zsh; `cd` needs the shell itself, so it cannot be a subcommand
+const POSIX_FUNCTION: &str = r#"phantom() {
+    if [ "$1" = "cd" ]; then
+        shift
+        local dir
+        dir="$(command phantom where "$@")" || return
+        # Nothing is printed when an fzf selection is cancelled
+        [ -n "$dir" ] || return 0
+        cd -- "$dir"
+    else
+        command phantom "$@"
+    fi
+}"#;
+
+const BASH_SHELLENV: &str = r#"# phantom shell integration for bash: adds `phantom cd <name>` and `phantom cd --fzf`
+# Add to ~/.bashrc: eval "$(phantom shellenv bash)"
+"#;
+
+const ZSH_SHELLENV: &str = r#"# phantom shell integration for zsh: adds `phantom cd <name>` and `phantom cd --fzf`
+# Add to ~/.zshrc: eval "$(phantom shellenv zsh)"
+"#;
+
+const FISH_SHELLENV: &str = r#"# phantom shell integration for fish: adds `phantom cd <name>` and `phantom cd --fzf`
+# Add to ~/.config/fish/config.fish: phantom shellenv fish | source
+function phantom --wraps phantom --description 'phantom, plus `phantom cd <name>`'
+    if test "$argv[1]" = cd
+        set -e argv[1]
+        set -l dir (command phantom where $argv); or return
+        # Nothing is printed when an fzf selection is cancelled
+        test -n "$dir"; or return 0
+        cd $dir
+    else
+        command phantom $argv
+    end
+end"#;
+
+/// The script `eval`ed from the rc file of `shell`
+pub(crate) fn script(shell: &Shell) -> String {
+    match shell {
+        Shell::Bash => format!("{BASH_SHELLENV}{POSIX_FUNCTION}"),
+        Shell::Zsh => format!("{ZSH_SHELLENV}{POSIX_FUNCTION}"),
+        Shell::Fish => FISH_SHELLENV.to_string(),
+    }
+}
+
+/// Handle the shellenv command
+pub fn handle(args: ShellenvArgs) -> Result<()> {
+    println!("{}", script(&args.shell));
+    Ok(())
+}
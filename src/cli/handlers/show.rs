@@ -0,0 +1,272 @@
+use crate::cli::commands::show::{ShowArgs, ShowResult};
+use crate::cli::context::HandlerContext;
+use crate::cli::output::output;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use crate::git::libs::changed_files::{committed_changes, resolve_diff_base, uncommitted_changes};
+use crate::git::libs::get_current_branch::get_current_branch;
+use crate::git::parse::FileChange;
+use crate::worktree::state::read_worktree_metadata;
+use crate::worktree::validate::validate_worktree_exists;
+use anyhow::{Context, Result};
+use tracing::debug;
+
+/// Most file entries printed before the rest are summarised
+const MAX_LISTED_CHANGES: usize = 200;
+
+/// Handle the show command
+pub async fn handle<E, F, H>(args: ShowArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let worktree_path = validate_worktree_exists(&git_root, &args.name, &context.filesystem)
+        .await
+        .with_context(|| format!("Failed to validate worktree '{}' exists", args.name))?
+        .path;
+
+    let metadata = read_worktree_metadata(&context.filesystem, &git_root, &args.name).await;
+    let branch = get_current_branch(context.executor.clone(), &worktree_path)
+        .await
+        .ok()
+        .filter(|branch| !branch.is_empty());
+
+    let mut result = ShowResult {
+        name: args.name.clone(),
+        path: worktree_path.to_string_lossy().to_string(),
+        branch,
+        base: metadata.base_ref.clone(),
+        created_at: metadata.created_at,
+        labels: metadata.labels.clone(),
+        committed: None,
+        uncommitted: None,
+        note: None,
+    };
+
+    if args.files_changed {
+        let base = resolve_diff_base(
+            context.executor.clone(),
+            &git_root,
+            metadata.base_ref.as_deref(),
+            result.branch.as_deref(),
+        )
+        .await
+        .unwrap_or_else(|e| {
+            debug!("Failed to resolve diff base: {e}");
+            None
+        });
+
+        let committed = match &base {
+            Some(base) => {
+                match committed_changes(context.executor.clone(), &worktree_path, base).await {
+                    Ok(changes) => Some(changes),
+                    Err(e) => {
+                        debug!("Failed to diff against '{base}': {e}");
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        result.note = match (&base, &committed) {
+            (None, _) => Some(
+                "Base branch could not be resolved; showing uncommitted changes only".to_string(),
+            ),
+            (Some(base), None) => {
+                Some(format!("Could not diff against '{base}'; showing uncommitted changes only"))
+            }
+            _ => None,
+        };
+        result.base = base;
+        result.committed = committed;
+        result.uncommitted = Some(
+            uncommitted_changes(context.executor.clone(), &worktree_path)
+                .await
+                .with_context(|| format!("Failed to read status of worktree '{}'", args.name))?,
+        );
+    }
+
+    if args.json {
+        output().log(&serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    output().log(&format!("Name:   {}", result.name));
+    output().log(&format!("Branch: {}", result.branch.as_deref().unwrap_or("(detached)")));
+    output().log(&format!("Path:   {}", result.path));
+    if let Some(base) = &result.base {
+        output().log(&format!("Base:   {base}"));
+    }
+    if !result.labels.is_empty() {
+        let labels: Vec<_> = result.labels.iter().map(String::as_str).collect();
+        output().log(&format!("Labels: {}", labels.join(", ")));
+    }
+
+    if let Some(uncommitted) = &result.uncommitted {
+        output().log("");
+        for line in render_file_changes(
+            result.base.as_deref(),
+            result.committed.as_deref(),
+            uncommitted,
+            MAX_LISTED_CHANGES,
+        ) {
+            output().log(&line);
+        }
+        if let Some(note) = &result.note {
+            output().warn(note);
+        }
+    }
+
+    Ok(())
+}
+
+/// Render committed and uncommitted changes as grouped lines, listing at most `limit` files
+fn render_file_changes(
+    base: Option<&str>,
+    committed: Option<&[FileChange]>,
+    uncommitted: &[FileChange],
+    limit: usize,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut remaining = limit;
+    let mut hidden = 0;
+
+    let mut section = |lines: &mut Vec<String>, title: String, changes: &[FileChange]| {
+        lines.push(title);
+        if changes.is_empty() {
+            lines.push("  (none)".to_string());
+            return;
+        }
+        let shown = changes.len().min(remaining);
+        for change in &changes[..shown] {
+            let path = match &change.old_path {
+                Some(old_path) => format!("{old_path} -> {}", change.path),
+                None => change.path.clone(),
+            };
+            lines.push(format!("  {:<2} {path}", change.status));
+        }
+        remaining -= shown;
+        hidden += changes.len() - shown;
+    };
+
+    if let (Some(base), Some(committed)) = (base, committed) {
+        section(&mut lines, format!("Committed changes (vs {base}):"), committed);
+    }
+    section(&mut lines, "Uncommitted changes:".to_string(), uncommitted);
+
+    if hidden > 0 {
+        lines.push(format!("  +{hidden} more"));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+    use std::path::PathBuf;
+
+    fn change(status: &str, path: &str) -> FileChange {
+        FileChange { status: status.to_string(), path: path.to_string(), old_path: None }
+    }
+
+    #[test]
+    fn test_render_file_changes_groups() {
+        let committed = vec![
+            change("M", "src/lib.rs"),
+            FileChange {
+                status: "R".to_string(),
+                path: "new.rs".to_string(),
+                old_path: Some("old.rs".to_string()),
+            },
+        ];
+        let uncommitted = vec![change("??", "notes.txt")];
+
+        let lines = render_file_changes(Some("main"), Some(&committed), &uncommitted, 200);
+        assert_eq!(
+            lines,
+            vec![
+                "Committed changes (vs main):",
+                "  M  src/lib.rs",
+                "  R  old.rs -> new.rs",
+                "Uncommitted changes:",
+                "  ?? notes.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_file_changes_without_base() {
+        let lines = render_file_changes(None, None, &[], 200);
+        assert_eq!(lines, vec!["Uncommitted changes:", "  (none)"]);
+    }
+
+    #[test]
+    fn test_render_file_changes_caps_entries() {
+        let committed: Vec<_> = (0..150).map(|i| change("M", &format!("c{i}"))).collect();
+        let uncommitted: Vec<_> = (0..100).map(|i| change("M", &format!("u{i}"))).collect();
+
+        let lines = render_file_changes(Some("main"), Some(&committed), &uncommitted, 200);
+        let listed = lines.iter().filter(|line| line.starts_with("  M ")).count();
+        assert_eq!(listed, 200);
+        assert_eq!(lines.last().unwrap(), "  +50 more");
+    }
+
+    #[tokio::test]
+    async fn test_show_files_changed_without_base() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        let worktree = "/repo/.git/phantom/worktrees/feature";
+
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from(worktree)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        mock.expect_command("git")
+            .with_args(&["branch", "--show-current"])
+            .in_dir(worktree)
+            .returns_output("feature\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"])
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/main"])
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/master"])
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir(worktree)
+            .times(1)
+            .returns_output(" M src/lib.rs\n", "", 0);
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+        let args = ShowArgs { name: "feature".to_string(), files_changed: true, json: true };
+
+        handle(args, context).await.unwrap();
+        mock.verify().unwrap();
+        assert!(!mock.calls().iter().any(|call| call.args.first().is_some_and(|a| a == "diff")));
+    }
+}
@@ -0,0 +1,340 @@
+use crate::cli::commands::status::{StatusArgs, StatusResult};
+use crate::cli::context::HandlerContext;
+use crate::cli::output::output;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use crate::git::libs::get_current_branch::get_current_branch;
+use crate::git::libs::get_current_worktree::get_current_worktree;
+use crate::git::libs::worktree_status::{head_subject, worktree_status};
+use crate::worktree::select::select_worktree_with_fzf;
+use crate::worktree::validate::validate_worktree_exists;
+use anyhow::{bail, Context, Result};
+use tracing::debug;
+
+/// Handle the status command
+pub async fn handle<E, F, H>(args: StatusArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    // Validate args
+    if args.name.is_none() && !args.current && !args.fzf {
+        bail!("Usage: phantom status <worktree-name>, phantom status --current or phantom status --fzf");
+    }
+
+    if (args.name.is_some() || args.fzf) && args.current {
+        bail!("Cannot specify --current with a worktree name or --fzf option");
+    }
+
+    if args.name.is_some() && args.fzf {
+        bail!("Cannot specify both a worktree name and --fzf option");
+    }
+
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    let name = if args.current {
+        match get_current_worktree(context.executor.clone(), &git_root)
+            .await
+            .with_context(|| "Failed to get current worktree")?
+        {
+            Some(name) => name,
+            None => bail!(
+                "Not in a worktree directory. The --current option can only be used from within a worktree."
+            ),
+        }
+    } else if args.fzf {
+        match select_worktree_with_fzf(
+            context.executor.clone(),
+            &context.filesystem,
+            &git_root,
+            &context.config_or_default(&git_root).await,
+        )
+        .await
+        .with_context(|| "Failed to select worktree with fzf")?
+        {
+            Some(worktree) => worktree.name,
+            None => {
+                // User cancelled selection
+                return Ok(());
+            }
+        }
+    } else {
+        args.name.unwrap()
+    };
+
+    let worktree_path = validate_worktree_exists(&git_root, &name, &context.filesystem)
+        .await
+        .with_context(|| format!("Failed to validate worktree '{name}' exists"))?
+        .path;
+
+    let branch = get_current_branch(context.executor.clone(), &worktree_path)
+        .await
+        .ok()
+        .filter(|branch| !branch.is_empty());
+    let status = worktree_status(context.executor.clone(), &worktree_path)
+        .await
+        .with_context(|| format!("Failed to read status of worktree '{name}'"))?;
+    let subject = match &status.oid {
+        Some(_) => match head_subject(context.executor.clone(), &worktree_path).await {
+            Ok(subject) => Some(subject),
+            Err(e) => {
+                debug!("Failed to read HEAD commit subject: {e}");
+                None
+            }
+        },
+        None => None,
+    };
+
+    let result = StatusResult {
+        name,
+        path: worktree_path.to_string_lossy().to_string(),
+        branch,
+        clean: status.is_clean(),
+        commit: status.oid,
+        subject,
+        modified: status.modified,
+        unmerged: status.unmerged,
+        untracked: status.untracked,
+        upstream: status.upstream,
+        ahead: status.ahead,
+        behind: status.behind,
+    };
+
+    if args.json {
+        output().log(&serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    for line in render_status(&result) {
+        output().log(&line);
+    }
+    Ok(())
+}
+
+/// Render the status summary as aligned `Label: value` lines
+fn render_status(result: &StatusResult) -> Vec<String> {
+    let head = match &result.commit {
+        Some(commit) => {
+            let short = commit.get(..7).unwrap_or(commit);
+            match &result.subject {
+                Some(subject) => format!("{short} {subject}"),
+                None => short.to_string(),
+            }
+        }
+        None => "(no commits)".to_string(),
+    };
+
+    let state = if result.clean {
+        "clean".to_string()
+    } else {
+        let mut counts = Vec::new();
+        if result.modified > 0 {
+            counts.push(format!("{} modified", result.modified));
+        }
+        if result.unmerged > 0 {
+            counts.push(format!("{} unmerged", result.unmerged));
+        }
+        if result.untracked > 0 {
+            counts.push(format!("{} untracked", result.untracked));
+        }
+        format!("dirty ({})", counts.join(", "))
+    };
+
+    let upstream = match (&result.upstream, result.ahead, result.behind) {
+        (Some(upstream), Some(ahead), Some(behind)) => {
+            format!("{upstream} (ahead {ahead}, behind {behind})")
+        }
+        (Some(upstream), _, _) => format!("{upstream} (gone)"),
+        (None, _, _) => "(none)".to_string(),
+    };
+
+    vec![
+        format!("Name:     {}", result.name),
+        format!("Branch:   {}", result.branch.as_deref().unwrap_or("(detached)")),
+        format!("HEAD:     {head}"),
+        format!("Status:   {state}"),
+        format!("Upstream: {upstream}"),
+        format!("Path:     {}", result.path),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+    use std::path::PathBuf;
+
+    fn result() -> StatusResult {
+        StatusResult {
+            name: "feature".to_string(),
+            path: "/repo/.git/phantom/worktrees/feature".to_string(),
+            branch: Some("feature".to_string()),
+            commit: Some("1234567890abcdef".to_string()),
+            subject: Some("Add status".to_string()),
+            clean: true,
+            modified: 0,
+            unmerged: 0,
+            untracked: 0,
+            upstream: None,
+            ahead: None,
+            behind: None,
+        }
+    }
+
+    fn args(name: Option<&str>, fzf: bool, current: bool) -> StatusArgs {
+        StatusArgs { name: name.map(String::from), fzf, current, json: false }
+    }
+
+    #[test]
+    fn test_render_status_clean_without_upstream() {
+        assert_eq!(
+            render_status(&result()),
+            vec![
+                "Name:     feature",
+                "Branch:   feature",
+                "HEAD:     1234567 Add status",
+                "Status:   clean",
+                "Upstream: (none)",
+                "Path:     /repo/.git/phantom/worktrees/feature",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_status_dirty_tracking_detached() {
+        let result = StatusResult {
+            branch: None,
+            clean: false,
+            modified: 2,
+            untracked: 1,
+            upstream: Some("origin/feature".to_string()),
+            ahead: Some(3),
+            behind: Some(0),
+            ..result()
+        };
+
+        let lines = render_status(&result);
+        assert_eq!(lines[1], "Branch:   (detached)");
+        assert_eq!(lines[3], "Status:   dirty (2 modified, 1 untracked)");
+        assert_eq!(lines[4], "Upstream: origin/feature (ahead 3, behind 0)");
+    }
+
+    #[test]
+    fn test_render_status_unborn_and_gone_upstream() {
+        let result = StatusResult {
+            commit: None,
+            subject: None,
+            upstream: Some("origin/feature".to_string()),
+            ..result()
+        };
+
+        let lines = render_status(&result);
+        assert_eq!(lines[2], "HEAD:     (no commits)");
+        assert_eq!(lines[4], "Upstream: origin/feature (gone)");
+    }
+
+    #[tokio::test]
+    async fn test_status_rejects_conflicting_selectors() {
+        let context = HandlerContext::new(
+            MockCommandExecutor::new(),
+            MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        for args in
+            [args(None, false, false), args(Some("a"), false, true), args(Some("a"), true, false)]
+        {
+            assert!(handle(args, context.clone()).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_by_name() {
+        let mut mock = MockCommandExecutor::new_ordered();
+        let mock_fs = MockFileSystem::new();
+        let worktree = "/repo/.git/phantom/worktrees/feature";
+
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from(worktree)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        mock.expect_command("git")
+            .with_args(&["branch", "--show-current"])
+            .in_dir(worktree)
+            .returns_output("feature\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain=v2", "--branch"])
+            .in_dir(worktree)
+            .returns_output(
+                "# branch.oid abc\n# branch.head feature\n1 .M N... 100644 100644 100644 abc abc a.rs\n",
+                "",
+                0,
+            );
+        mock.expect_command("git")
+            .with_args(&["log", "-1", "--format=%s"])
+            .in_dir(worktree)
+            .times(1)
+            .returns_output("Add status\n", "", 0);
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        handle(args(Some("feature"), false, false), context).await.unwrap();
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_status_unborn_branch_skips_subject() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+        let worktree = "/repo/.git/phantom/worktrees/feature";
+
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from(worktree)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        mock.expect_command("git")
+            .with_args(&["branch", "--show-current"])
+            .in_dir(worktree)
+            .returns_output("feature\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain=v2", "--branch"])
+            .in_dir(worktree)
+            .returns_output("# branch.oid (initial)\n# branch.head feature\n", "", 0);
+
+        let context = HandlerContext::new(
+            mock.clone(),
+            mock_fs,
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        handle(args(Some("feature"), false, false), context).await.unwrap();
+        assert!(!mock.calls().iter().any(|call| call.args.first().is_some_and(|a| a == "log")));
+    }
+}
@@ -2,36 +2,88 @@ use crate::cli::commands::version::VersionArgs;
 use crate::cli::output::output;
 use serde::Serialize;
 
+/// Reported for build metadata that build.rs could not determine
+const UNKNOWN: &str = "unknown";
+
 #[derive(Serialize)]
 struct VersionJsonOutput {
     name: String,
     version: String,
     description: String,
     authors: String,
+    commit: String,
+    build_date: String,
+    rustc: String,
+}
+
+impl VersionJsonOutput {
+    /// Package metadata plus whatever build metadata is available
+    fn new(commit: Option<&str>, build_date: Option<&str>, rustc: Option<&str>) -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            description: env!("CARGO_PKG_DESCRIPTION").to_string(),
+            authors: env!("CARGO_PKG_AUTHORS").to_string(),
+            commit: commit.unwrap_or(UNKNOWN).to_string(),
+            build_date: build_date.unwrap_or(UNKNOWN).to_string(),
+            rustc: rustc.unwrap_or(UNKNOWN).to_string(),
+        }
+    }
+
+    /// The first 7 characters of the commit, or "unknown"
+    fn short_commit(&self) -> &str {
+        self.commit.get(..7).unwrap_or(&self.commit)
+    }
 }
 
 /// Handle the version command
 pub fn handle(args: VersionArgs) {
-    let version = env!("CARGO_PKG_VERSION");
-    let name = env!("CARGO_PKG_NAME");
-    let authors = env!("CARGO_PKG_AUTHORS");
-    let description = env!("CARGO_PKG_DESCRIPTION");
+    let info = VersionJsonOutput::new(
+        option_env!("PHANTOM_GIT_SHA"),
+        option_env!("PHANTOM_BUILD_DATE"),
+        option_env!("PHANTOM_RUSTC_VERSION"),
+    );
 
     if args.json {
-        let json_output = VersionJsonOutput {
-            name: name.to_string(),
-            version: version.to_string(),
-            description: description.to_string(),
-            authors: authors.to_string(),
-        };
-
-        match serde_json::to_string_pretty(&json_output) {
+        match serde_json::to_string_pretty(&info) {
             Ok(json) => output().log(&json),
             Err(e) => output().error(&format!("Failed to serialize JSON: {e}")),
         }
     } else {
-        output().log(&format!("{name} {version}"));
-        output().log(description);
-        output().log(&format!("by {authors}"));
+        output().log(&format!("{} {} ({})", info.name, info.version, info.short_commit()));
+        output().log(&info.description);
+        output().log(&format!("by {}", info.authors));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_json_keys() {
+        let info = VersionJsonOutput::new(
+            Some("0123456789abcdef"),
+            Some("2026-01-02"),
+            Some("rustc 1.80.0"),
+        );
+        let json = serde_json::to_value(&info).unwrap();
+
+        for key in ["name", "version", "description", "authors", "commit", "build_date", "rustc"] {
+            assert!(json.get(key).is_some(), "missing {key}");
+        }
+        assert_eq!(json["commit"], "0123456789abcdef");
+        assert_eq!(info.short_commit(), "0123456");
+    }
+
+    #[test]
+    fn test_version_without_build_info() {
+        let info = VersionJsonOutput::new(None, None, None);
+
+        assert_eq!(info.commit, "unknown");
+        assert_eq!(info.build_date, "unknown");
+        assert_eq!(info.rustc, "unknown");
+        assert_eq!(info.short_commit(), "unknown");
+        assert!(serde_json::to_string(&info).is_ok());
     }
 }
@@ -1,13 +1,16 @@
-use crate::cli::commands::where_cmd::{WhereArgs, WhereResult};
+use crate::api::Phantom;
+use crate::cli::commands::where_cmd::{WhereArgs, WhereEntry, WhereResult};
 use crate::cli::context::HandlerContext;
 use crate::cli::output::output;
 use crate::core::command_executor::CommandExecutor;
 use crate::core::exit_handler::ExitHandler;
 use crate::core::filesystem::FileSystem;
-use crate::git::libs::get_git_root::get_git_root;
-use crate::worktree::locate::where_worktree;
+use crate::core::utils::normalize_path;
+use crate::worktree::paths::relative_path;
 use crate::worktree::select::select_worktree_with_fzf;
 use anyhow::{bail, Context, Result};
+use futures::future::join_all;
+use std::path::Path;
 
 /// Handle the where command
 pub async fn handle<E, F, H>(args: WhereArgs, context: HandlerContext<E, F, H>) -> Result<()>
@@ -17,24 +20,32 @@ where
     H: ExitHandler + Clone + 'static,
 {
     // Validate args
-    if args.name.is_none() && !args.fzf {
-        bail!("Usage: phantom where <worktree-name> or phantom where --fzf");
+    if args.names.is_empty() && !args.fzf {
+        bail!("Usage: phantom where <worktree-name>... or phantom where --fzf");
     }
 
-    if args.name.is_some() && args.fzf {
+    if !args.names.is_empty() && args.fzf {
         bail!("Cannot specify both a worktree name and --fzf option");
     }
 
     // Get git root
-    let git_root = get_git_root(context.executor.clone())
-        .await
-        .with_context(|| "Failed to determine git repository root")?;
+    let git_root =
+        context.git_root().await.with_context(|| "Failed to determine git repository root")?;
+
+    if args.names.len() > 1 {
+        return locate_many(&args, &git_root, &context).await;
+    }
 
     // Get worktree name
     let worktree_name = if args.fzf {
-        let result = select_worktree_with_fzf(context.executor.clone(), &git_root)
-            .await
-            .with_context(|| "Failed to select worktree with fzf")?;
+        let result = select_worktree_with_fzf(
+            context.executor.clone(),
+            &context.filesystem,
+            &git_root,
+            &context.config_or_default(&git_root).await,
+        )
+        .await
+        .with_context(|| "Failed to select worktree with fzf")?;
 
         match result {
             Some(worktree) => worktree.name,
@@ -44,25 +55,25 @@ where
             }
         }
     } else {
-        args.name.unwrap()
+        args.names[0].clone()
     };
 
     // Get the worktree path
-    match where_worktree(&git_root, &worktree_name, &context.filesystem)
+    let phantom = Phantom::new(&git_root, context.executor.clone(), context.filesystem.clone());
+    match phantom
+        .locate(&worktree_name)
         .await
         .with_context(|| format!("Failed to locate worktree '{worktree_name}'"))
     {
         Ok(result) => {
+            let path = if args.relative { relative_to_cwd(&result.path) } else { result.path };
+
             if args.json {
-                let json_result = WhereResult {
-                    success: true,
-                    name: worktree_name,
-                    path: result.path,
-                    error: None,
-                };
-                output().json(&json_result).with_context(|| "Failed to serialize JSON output")?;
+                let json_result =
+                    WhereResult { success: true, name: worktree_name, path, error: None };
+                output().log(&serde_json::to_string_pretty(&json_result)?);
             } else {
-                output().log(&result.path);
+                output().log(&path);
             }
             Ok(())
         }
@@ -74,7 +85,7 @@ where
                     path: String::new(),
                     error: Some(e.to_string()),
                 };
-                output().json(&json_result).with_context(|| "Failed to serialize JSON output")?;
+                output().log(&serde_json::to_string_pretty(&json_result)?);
                 Ok(())
             } else {
                 Err(e)
@@ -83,10 +94,66 @@ where
     }
 }
 
+/// Resolve several names at once, reporting missing worktrees without stopping
+async fn locate_many<E, F, H>(
+    args: &WhereArgs,
+    git_root: &Path,
+    context: &HandlerContext<E, F, H>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let phantom = Phantom::new(git_root, context.executor.clone(), context.filesystem.clone());
+    let results = join_all(args.names.iter().map(|name| phantom.locate(name))).await;
+
+    let mut entries = Vec::with_capacity(results.len());
+    let mut missing = 0;
+    for (name, result) in args.names.iter().zip(results) {
+        let path = match result {
+            Ok(found) => {
+                Some(if args.relative { relative_to_cwd(&found.path) } else { found.path })
+            }
+            Err(e) => {
+                missing += 1;
+                if !args.json {
+                    output().error(&format!("Failed to locate worktree '{name}': {e}"));
+                }
+                None
+            }
+        };
+        entries.push(WhereEntry { name: name.clone(), exists: path.is_some(), path });
+    }
+
+    if args.json {
+        output().log(&serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    for path in entries.iter().filter_map(|entry| entry.path.as_deref()) {
+        output().log(path);
+    }
+    if missing > 0 {
+        bail!("Could not locate {missing} of {} worktrees", entries.len());
+    }
+    Ok(())
+}
+
+/// Make a path relative to the current directory, keeping it absolute if that is not possible
+fn relative_to_cwd(path: &str) -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| relative_path(&normalize_path(Path::new(path)), &normalize_path(&cwd)))
+        .map_or_else(|| path.to_string(), |relative| relative.to_string_lossy().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::command_executor::StdinMode;
     use crate::core::executors::MockCommandExecutor;
+    use crate::core::exit_handler::MockExitHandler;
     use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
     use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
     use std::path::PathBuf;
@@ -107,7 +174,8 @@ mod tests {
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
         );
-        let args = WhereArgs { name: Some("test".to_string()), fzf: false, json: false };
+        let args =
+            WhereArgs { names: vec!["test".to_string()], fzf: false, relative: false, json: false };
 
         let result = handle(args, context).await;
         assert!(result.is_err());
@@ -120,7 +188,7 @@ mod tests {
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
         );
-        let args = WhereArgs { name: None, fzf: false, json: false };
+        let args = WhereArgs { names: vec![], fzf: false, relative: false, json: false };
 
         let result = handle(args, context).await;
         assert!(result.is_err());
@@ -134,7 +202,8 @@ mod tests {
             crate::core::filesystems::MockFileSystem::new(),
             crate::core::exit_handler::MockExitHandler::new(),
         );
-        let args = WhereArgs { name: Some("test".to_string()), fzf: true, json: false };
+        let args =
+            WhereArgs { names: vec!["test".to_string()], fzf: true, relative: false, json: false };
 
         let result = handle(args, context).await;
         assert!(result.is_err());
@@ -165,7 +234,8 @@ mod tests {
 
         let context =
             HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
-        let args = WhereArgs { name: Some("test".to_string()), fzf: false, json: false };
+        let args =
+            WhereArgs { names: vec!["test".to_string()], fzf: false, relative: false, json: false };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
@@ -185,17 +255,24 @@ mod tests {
         );
 
         // Mock worktree list - worktree doesn't exist
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /repo\n\
-                 HEAD abc123\n\
-                 branch refs/heads/main\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /repo\0\
+                 HEAD abc123\0\
+                 branch refs/heads/main\0",
+                "",
+                0,
+            );
 
         let context =
             HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
-        let args = WhereArgs { name: Some("nonexistent".to_string()), fzf: false, json: false };
+        let args = WhereArgs {
+            names: vec!["nonexistent".to_string()],
+            fzf: false,
+            relative: false,
+            json: false,
+        };
 
         let result = handle(args, context).await;
         assert!(result.is_err());
@@ -225,7 +302,8 @@ mod tests {
 
         let context =
             HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
-        let args = WhereArgs { name: Some("test".to_string()), fzf: false, json: true };
+        let args =
+            WhereArgs { names: vec!["test".to_string()], fzf: false, relative: false, json: true };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
@@ -245,17 +323,24 @@ mod tests {
         );
 
         // Mock worktree list - worktree doesn't exist
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /repo\n\
-                 HEAD abc123\n\
-                 branch refs/heads/main\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /repo\0\
+                 HEAD abc123\0\
+                 branch refs/heads/main\0",
+                "",
+                0,
+            );
 
         let context =
             HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
-        let args = WhereArgs { name: Some("nonexistent".to_string()), fzf: false, json: true };
+        let args = WhereArgs {
+            names: vec!["nonexistent".to_string()],
+            fzf: false,
+            relative: false,
+            json: true,
+        };
 
         let result = handle(args, context).await;
         // In JSON mode, errors are still reported as success with error field
@@ -275,18 +360,93 @@ mod tests {
         );
 
         // Mock worktree list for fzf selection
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /repo\n\
-                 HEAD abc123\n\
-                 branch refs/heads/main\n\
-                 \n\
-                 worktree /repo/.phantom/test\n\
-                 HEAD def456\n\
-                 branch refs/heads/test\n",
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /repo\0\
+                 HEAD abc123\0\
+                 branch refs/heads/main\0\
+                 \0\
+                 worktree /repo/.phantom/test\0\
+                 HEAD def456\0\
+                 branch refs/heads/test\0",
+                "",
+                0,
+            );
+
+        // Mock git status for main worktree
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir(PathBuf::from("/repo"))
+            .returns_output("", "", 0);
+
+        // Mock git status for test worktree
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir(PathBuf::from("/repo/.phantom/test"))
+            .returns_output("", "", 0);
+
+        // Mock fzf availability check
+        mock.expect_command("fzf").with_args(&["--version"]).returns_output("0.42.0", "", 0);
+
+        // Mock fzf selection
+        mock.expect_command("fzf")
+            .with_args(&[
+                "--prompt",
+                "Select worktree> ",
+                "--header",
+                "Git Worktrees",
+                "--preview",
+                "phantom __preview {1}",
+            ])
+            .with_stdin(StdinMode::Data("test (test)".to_string()))
+            .returns_output("test (test)\n", "", 0);
+
+        // Mock filesystem check for worktree existence
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/test")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)), // Directory exists
+        });
+
+        let context =
+            HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
+        let args = WhereArgs { names: vec![], fzf: true, relative: false, json: false };
+
+        let result = handle(args, context).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_where_with_fzf_selection_relative() {
+        let mut mock = MockCommandExecutor::new();
+        let mock_fs = MockFileSystem::new();
+
+        // Mock git root check
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
             "",
             0,
         );
 
+        // Mock worktree list for fzf selection
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /repo\0\
+                 HEAD abc123\0\
+                 branch refs/heads/main\0\
+                 \0\
+                 worktree /repo/.phantom/test\0\
+                 HEAD def456\0\
+                 branch refs/heads/test\0",
+                "",
+                0,
+            );
+
         // Mock git status for main worktree
         mock.expect_command("git")
             .with_args(&["status", "--porcelain"])
@@ -304,8 +464,15 @@ mod tests {
 
         // Mock fzf selection
         mock.expect_command("fzf")
-            .with_args(&["--prompt", "Select worktree> ", "--header", "Git Worktrees"])
-            .with_stdin_data("test (test)")
+            .with_args(&[
+                "--prompt",
+                "Select worktree> ",
+                "--header",
+                "Git Worktrees",
+                "--preview",
+                "phantom __preview {1}",
+            ])
+            .with_stdin(StdinMode::Data("test (test)".to_string()))
             .returns_output("test (test)\n", "", 0);
 
         // Mock filesystem check for worktree existence
@@ -320,7 +487,7 @@ mod tests {
 
         let context =
             HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
-        let args = WhereArgs { name: None, fzf: true, json: false };
+        let args = WhereArgs { names: vec![], fzf: true, relative: true, json: false };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
@@ -351,7 +518,8 @@ mod tests {
 
         let context =
             HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
-        let args = WhereArgs { name: Some("test".to_string()), fzf: false, json: false };
+        let args =
+            WhereArgs { names: vec!["test".to_string()], fzf: false, relative: false, json: false };
 
         let result = handle(args, context).await;
         assert!(result.is_ok());
@@ -381,7 +549,12 @@ mod tests {
 
         let context =
             HandlerContext::new(mock, mock_fs, crate::core::exit_handler::MockExitHandler::new());
-        let args = WhereArgs { name: Some("nonexistent".to_string()), fzf: false, json: false };
+        let args = WhereArgs {
+            names: vec!["nonexistent".to_string()],
+            fzf: false,
+            relative: false,
+            json: false,
+        };
 
         let result = handle(args, context).await;
         assert!(result.is_err());
@@ -391,4 +564,64 @@ mod tests {
             "Unexpected error message: {error_str}"
         );
     }
+
+    fn multi_name_context(
+        json: bool,
+    ) -> (WhereArgs, HandlerContext<MockCommandExecutor, MockFileSystem, MockExitHandler>) {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/repo/.git",
+            "",
+            0,
+        );
+        let mock_fs = MockFileSystem::new();
+        for (name, exists) in [("a", true), ("missing", false), ("b", true)] {
+            mock_fs.expect(FileSystemExpectation {
+                operation: FileSystemOperation::IsDir,
+                path: Some(PathBuf::from(format!("/repo/.git/phantom/worktrees/{name}"))),
+                from_path: None,
+                to_path: None,
+                contents: None,
+                result: Ok(MockResult::Bool(exists)),
+            });
+        }
+        let args = WhereArgs {
+            names: vec!["a".to_string(), "missing".to_string(), "b".to_string()],
+            fzf: false,
+            relative: false,
+            json,
+        };
+        (args, HandlerContext::new(mock, mock_fs, MockExitHandler::new()))
+    }
+
+    #[tokio::test]
+    async fn test_where_multiple_names_reports_missing() {
+        let (args, context) = multi_name_context(false);
+
+        let error = handle(args, context).await.unwrap_err();
+        assert_eq!(error.to_string(), "Could not locate 1 of 3 worktrees");
+    }
+
+    #[tokio::test]
+    async fn test_where_multiple_names_json_keeps_going() {
+        let (args, context) = multi_name_context(true);
+
+        assert!(handle(args, context).await.is_ok());
+    }
+
+    #[test]
+    fn test_where_entry_json_shape() {
+        let entries = [
+            WhereEntry { name: "a".to_string(), path: Some("/wt/a".to_string()), exists: true },
+            WhereEntry { name: "missing".to_string(), path: None, exists: false },
+        ];
+
+        assert_eq!(
+            serde_json::to_value(entries).unwrap(),
+            serde_json::json!([
+                { "name": "a", "path": "/wt/a", "exists": true },
+                { "name": "missing", "path": null, "exists": false },
+            ])
+        );
+    }
 }
@@ -0,0 +1,444 @@
+use crate::cli::commands::create::CreateArgs;
+use crate::cli::commands::delete::DeleteArgs;
+use crate::cli::commands::list::ListArgs;
+use crate::cli::context::HandlerContext;
+use crate::cli::handlers::create::{branch_prefix, copy_files_selection};
+use crate::cli::handlers::list::{json_items, ListJsonOutput};
+use crate::cli::output::output;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::env_map::EnvMap;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use crate::process::exec::exec_in_worktree;
+use crate::workspace::fanout::{
+    create_in_workspace, delete_in_workspace, list_in_workspace, members_with_branch,
+};
+use crate::workspace::{discover_workspace, fan_out, MemberResult, Workspace, WorkspaceMember};
+use crate::worktree::in_progress::detect_in_progress_operation;
+use crate::worktree::list::ListOptions;
+use crate::worktree::paths::get_worktree_path;
+use crate::worktree::types::{CreateWorktreeOptions, DeleteWorktreeOptions, ForceOptions};
+use crate::PhantomError;
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Per-member entry in workspace JSON output
+#[derive(Serialize)]
+struct MemberJson {
+    member: String,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct WorkspaceJsonOutput {
+    success: bool,
+    members: Vec<MemberJson>,
+}
+
+#[derive(Serialize)]
+struct WorkspaceListJsonMember {
+    member: String,
+    #[serde(flatten)]
+    list: ListJsonOutput,
+}
+
+/// Find the workspace enclosing the current directory
+async fn current_workspace() -> Result<Workspace> {
+    let cwd = std::env::current_dir().with_context(|| "Failed to get current directory")?;
+    let workspace =
+        discover_workspace(&cwd).await.with_context(|| "Failed to load workspace manifest")?;
+    Ok(workspace)
+}
+
+/// Print per-member outcomes and the combined summary, failing if any member failed
+fn report<T>(
+    results: Vec<MemberResult<T>>,
+    json: bool,
+    describe: impl Fn(&T) -> (Option<String>, String),
+) -> Result<()> {
+    let total = results.len();
+    let mut members = Vec::with_capacity(total);
+
+    for MemberResult { member, result } in results {
+        match result {
+            Ok(value) => {
+                let (path, message) = describe(&value);
+                if !json {
+                    output().log(&format!("[{}] {message}", member.name));
+                }
+                members.push(MemberJson {
+                    member: member.name,
+                    success: true,
+                    path,
+                    message: Some(message),
+                    error: None,
+                });
+            }
+            Err(e) => {
+                if !json {
+                    output().error(&format!("[{}] {e}", member.name));
+                }
+                members.push(MemberJson {
+                    member: member.name,
+                    success: false,
+                    path: None,
+                    message: None,
+                    error: Some(e.to_string()),
+                });
+            }
+        }
+    }
+
+    let failed = members.iter().filter(|m| !m.success).count();
+    if json {
+        let json_output = WorkspaceJsonOutput { success: failed == 0, members };
+        output().log(
+            &serde_json::to_string_pretty(&json_output)
+                .with_context(|| "Failed to serialize JSON output")?,
+        );
+    } else {
+        output().log(&format!("Workspace: {} succeeded, {failed} failed", total - failed));
+    }
+
+    if failed > 0 {
+        bail!("{failed} of {total} workspace members failed");
+    }
+    Ok(())
+}
+
+/// Create a worktree in every workspace member
+pub async fn create<E, F, H>(args: CreateArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let [name] = args.names.as_slice() else {
+        bail!("--workspace creates one worktree at a time");
+    };
+    let workspace = current_workspace().await?;
+    let branches = member_branches(&args, name, &workspace, &context).await?;
+    let branch_of = |member: &WorkspaceMember| branches[&member.name].clone();
+
+    // Lockstep branches are only consistent if none of them already exist
+    let existing = members_with_branch(context.executor.clone(), &workspace, branch_of).await;
+    if !existing.is_empty() && !args.json {
+        for member in &existing {
+            output().warn(&format!(
+                "[{member}] Branch '{}' already exists and may have diverged from the other members",
+                branches[member]
+            ));
+        }
+    }
+
+    let options = CreateWorktreeOptions {
+        branch: None,
+        commitish: args.base.clone(),
+        detach: false,
+        no_checkout: args.no_checkout,
+        sparse_patterns: None,
+        copy_files: None,
+        copy_source: None,
+        keep_on_failure: args.keep_on_failure,
+        copy_progress: None,
+    };
+    let results = create_in_workspace(
+        context.executor.clone(),
+        &workspace,
+        name,
+        options,
+        &copy_files_selection(&args),
+        branch_of,
+    )
+    .await;
+    let results = results.into_iter().map(|MemberResult { member, result }| MemberResult {
+        result: result.map(|success| (branches[&member.name].clone(), success)),
+        member,
+    });
+
+    report(results.collect(), args.json, |(branch, success)| {
+        (Some(success.path.clone()), format!("Created worktree '{name}' with branch '{branch}'"))
+    })
+}
+
+/// The branch each member creates for `name`, by member name
+///
+/// Like a single-repository create: --branch as given, otherwise `name` behind
+/// the branch prefix, which each member may configure for itself.
+async fn member_branches<E, F, H>(
+    args: &CreateArgs,
+    name: &str,
+    workspace: &Workspace,
+    context: &HandlerContext<E, F, H>,
+) -> Result<HashMap<String, String>>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let mut branches = HashMap::with_capacity(workspace.members.len());
+    for member in &workspace.members {
+        let branch = match &args.branch {
+            Some(branch) => branch.clone(),
+            None => {
+                let config = member
+                    .config
+                    .get(&member.path)
+                    .await
+                    .with_context(|| format!("[{}] Failed to load config", member.name))?;
+                let prefix = branch_prefix(args, config, &member.path, context)
+                    .await
+                    .with_context(|| format!("[{}] Failed to resolve the branch", member.name))?;
+                format!("{prefix}{name}")
+            }
+        };
+        branches.insert(member.name.clone(), branch);
+    }
+    Ok(branches)
+}
+
+/// Delete a worktree from every workspace member
+pub async fn delete<E, F, H>(args: DeleteArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let Some(name) = args.name.clone() else {
+        bail!("Usage: phantom delete --workspace <worktree-name>");
+    };
+
+    let workspace = current_workspace().await?;
+    let options = DeleteWorktreeOptions {
+        force: ForceOptions {
+            dirty: args.force || args.force_dirty,
+            locked: args.force || args.force_locked,
+            unmerged: args.force,
+        },
+        keep_branch: args.keep_branch,
+        snapshot: args.snapshot.map(Into::into),
+    };
+    let results = delete_in_workspace(
+        context.executor.clone(),
+        &workspace,
+        &name,
+        options,
+        &context.filesystem,
+    )
+    .await;
+
+    report(results, args.json, |success| (Some(success.path.clone()), success.message.clone()))
+}
+
+/// List worktrees of every workspace member
+pub async fn list<E, F, H>(args: ListArgs, context: HandlerContext<E, F, H>) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let workspace = current_workspace().await?;
+    let list_options = ListOptions { include_status: !args.no_status, ..ListOptions::default() };
+    let results =
+        list_in_workspace(context.executor.clone(), &workspace, list_options, &context.filesystem)
+            .await;
+
+    let mut json_members = Vec::new();
+    let mut failures = Vec::new();
+    for MemberResult { member, result } in results {
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                output().error(&format!("[{}] {e}", member.name));
+                failures.push(member.name);
+                continue;
+            }
+        };
+
+        if args.json {
+            let worktrees = json_items(
+                result.unborn_main.as_ref(),
+                &result.worktrees,
+                vec![None; result.worktrees.len()],
+                &member.path,
+                &context.filesystem,
+                list_options,
+            )
+            .await;
+            json_members.push(WorkspaceListJsonMember {
+                member: member.name,
+                list: ListJsonOutput { worktrees, timings_ms: None },
+            });
+        } else if args.names {
+            for worktree in &result.worktrees {
+                output().log(&format!("{}/{}", member.name, worktree.name));
+            }
+        } else if result.worktrees.is_empty() {
+            output().log(&format!("[{}] No worktrees found", member.name));
+        } else {
+            for worktree in &result.worktrees {
+                let branch_info =
+                    worktree.branch.as_ref().map(|b| format!(" ({b})")).unwrap_or_default();
+                let status = if !worktree.is_clean { " [dirty]" } else { "" };
+                output().log(&format!("[{}] {}{branch_info}{status}", member.name, worktree.name));
+            }
+        }
+    }
+
+    if args.json {
+        output().log(
+            &serde_json::to_string_pretty(&json_members)
+                .with_context(|| "Failed to serialize JSON output")?,
+        );
+    }
+
+    if !failures.is_empty() {
+        bail!("Failed to list worktrees in: {}", failures.join(", "));
+    }
+    Ok(())
+}
+
+/// Run a command in the named worktree of every workspace member, one member at a time
+pub async fn exec<E, F, H>(
+    name: &str,
+    command_args: &[String],
+    env: &EnvMap,
+    even_if_busy: bool,
+    context: HandlerContext<E, F, H>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let workspace = current_workspace().await?;
+    let (command, args) = command_args.split_first().expect("command is not empty");
+    let filesystem = &context.filesystem;
+    let environment = &*context.environment;
+    let executor = &context.executor;
+
+    // Sequential so the members' output is not interleaved
+    let results = fan_out(&workspace, 1, |member| async move {
+        output().log(&format!("[{}] Running '{}'", member.name, command_args.join(" ")));
+
+        let worktree_path = get_worktree_path(&member.path, name);
+        if let Ok(Some(operation)) = detect_in_progress_operation(&worktree_path, filesystem).await
+        {
+            if !even_if_busy {
+                return Err(PhantomError::ValidationFailed {
+                    reason: format!(
+                        "Refusing to run in worktree '{name}' while a {operation} is in progress (use --even-if-busy to run anyway)"
+                    ),
+                });
+            }
+        }
+
+        let result =
+            exec_in_worktree(
+                &member.path,
+                name,
+                command,
+                args,
+                env,
+                None,
+                filesystem,
+                environment,
+                Some(executor.clone()),
+            )
+                .await?;
+        match result.exit_code {
+            0 => Ok(result),
+            code => Err(PhantomError::ProcessFailed { command: command.clone(), code }),
+        }
+    })
+    .await;
+
+    report(results, false, |_| (None, "Done".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn member(name: &str) -> WorkspaceMember {
+        WorkspaceMember::new(name.to_string(), PathBuf::from(format!("/work/{name}")))
+    }
+
+    #[tokio::test]
+    async fn test_member_branches_use_each_members_prefix() {
+        use crate::core::executors::MockCommandExecutor;
+        use clap::Parser;
+
+        let root = tempfile::TempDir::new().unwrap();
+        let members: Vec<_> = ["api", "web"]
+            .iter()
+            .map(|name| {
+                let path = root.path().join(name);
+                std::fs::create_dir_all(&path).unwrap();
+                WorkspaceMember::new(name.to_string(), path)
+            })
+            .collect();
+        std::fs::write(members[0].path.join("phantom.config.json"), r#"{"branchPrefix": "api/"}"#)
+            .unwrap();
+        let workspace =
+            Workspace { manifest_path: root.path().join("phantom-workspace.toml"), members };
+        let context = HandlerContext::new(
+            MockCommandExecutor::new(),
+            crate::core::filesystems::MockFileSystem::new(),
+            crate::core::exit_handler::MockExitHandler::new(),
+        );
+
+        let branches = |extra: &'static [&'static str]| {
+            let (workspace, context) = (&workspace, &context);
+            async move {
+                let mut command = vec!["phantom", "create", "topic", "--workspace"];
+                command.extend_from_slice(extra);
+                let crate::cli::Commands::Create(args) =
+                    crate::cli::Cli::try_parse_from(command).unwrap().command
+                else {
+                    unreachable!()
+                };
+                let branches = member_branches(&args, "topic", workspace, context).await.unwrap();
+                (branches["api"].clone(), branches["web"].clone())
+            }
+        };
+
+        assert_eq!(branches(&[]).await, ("api/topic".into(), "topic".into()));
+        assert_eq!(
+            branches(&["--branch-prefix", "wip/"]).await,
+            ("wip/topic".into(), "wip/topic".into())
+        );
+        assert_eq!(branches(&["--no-branch-prefix"]).await, ("topic".into(), "topic".into()));
+        assert_eq!(branches(&["--branch", "custom"]).await, ("custom".into(), "custom".into()));
+    }
+
+    #[test]
+    fn test_report_all_succeeded() {
+        let results = vec![
+            MemberResult { member: member("api"), result: Ok("ok".to_string()) },
+            MemberResult { member: member("web"), result: Ok("ok".to_string()) },
+        ];
+        assert!(report(results, true, |value| (None, value.clone())).is_ok());
+    }
+
+    #[test]
+    fn test_report_counts_failures() {
+        let results = vec![
+            MemberResult { member: member("api"), result: Ok("ok".to_string()) },
+            MemberResult {
+                member: member("web"),
+                result: Err(PhantomError::WorktreeNotFound { name: "feature".to_string() }),
+            },
+        ];
+        let error = report(results, false, |value| (None, value.clone())).unwrap_err();
+        assert_eq!(error.to_string(), "1 of 2 workspace members failed");
+    }
+}
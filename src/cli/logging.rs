@@ -0,0 +1,66 @@
+use crate::core::command_executor::COMMAND_TRACE_TARGET;
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use std::io;
+use tracing::Subscriber;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+/// How tracing events are rendered on stderr
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human readable, one event per line
+    #[default]
+    Pretty,
+    /// One JSON object per event, for log ingestion
+    Json,
+    /// Shorter human readable lines
+    Compact,
+}
+
+/// Build the subscriber for `format` without installing it; `ansi` colors the human readable formats
+///
+/// With `commands_only` the subscriber shows the per-command events and nothing else.
+fn subscriber(format: LogFormat, ansi: bool, commands_only: bool) -> impl Subscriber + Send + Sync {
+    let filter = if commands_only {
+        EnvFilter::new(format!("{COMMAND_TRACE_TARGET}=info"))
+    } else {
+        EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| EnvFilter::new("info"))
+            .add_directive("phantom=debug".parse().unwrap())
+    };
+
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_writer(io::stderr)
+        .with_ansi(ansi)
+        .with_target(false)
+        .with_thread_ids(false)
+        .with_thread_names(false);
+    let fmt_layer = match format {
+        LogFormat::Pretty => fmt_layer.boxed(),
+        LogFormat::Json => fmt_layer.json().boxed(),
+        LogFormat::Compact => fmt_layer.compact().boxed(),
+    };
+
+    tracing_subscriber::registry().with(filter).with(fmt_layer)
+}
+
+/// Install the global tracing subscriber with environment filter
+pub fn init_tracing(format: LogFormat, ansi: bool, commands_only: bool) -> Result<()> {
+    subscriber(format, ansi, commands_only)
+        .try_init()
+        .map_err(|e| anyhow!("Failed to initialize tracing: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscriber_builds_for_each_format() {
+        for format in [LogFormat::Pretty, LogFormat::Json, LogFormat::Compact] {
+            tracing::subscriber::with_default(subscriber(format, false, false), || {
+                tracing::info!(format = ?format, "subscriber ready");
+            });
+        }
+    }
+}
@@ -1,10 +1,22 @@
 pub mod commands;
 pub mod context;
+pub mod dry_run;
 pub mod error;
 pub mod handlers;
+pub mod logging;
 pub mod output;
+pub mod progress;
 
+use crate::cli::context::HandlerContext;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::exit_handler::ExitHandler;
+use crate::core::filesystem::FileSystem;
+use crate::core::utils::timed;
+use crate::git::git_executor_adapter::GitSettings;
+use anyhow::{bail, Context};
 use clap::{Parser, Subcommand};
+use std::path::{Path, PathBuf};
+use tracing::debug;
 
 #[derive(Parser)]
 #[command(
@@ -13,7 +25,8 @@ use clap::{Parser, Subcommand};
     about = "Ephemeral Git worktrees made easy",
     long_about = "Phantom is a CLI tool that makes working with Git worktrees simple and efficient.\n\
                   It helps you manage multiple worktrees, switch between them, and maintain a clean\n\
-                  development environment."
+                  development environment.",
+    after_help = error::EXIT_CODES_HELP
 )]
 #[command(propagate_version = true)]
 pub struct Cli {
@@ -27,6 +40,34 @@ pub struct Cli {
     /// Suppress all output except errors
     #[arg(short, long, global = true)]
     pub quiet: bool,
+
+    /// Log format on stderr; enables logging even without --verbose
+    #[arg(long, global = true, value_enum, value_name = "FORMAT")]
+    pub log_format: Option<logging::LogFormat>,
+
+    /// When to color the output
+    #[arg(long, global = true, value_enum, value_name = "WHEN", default_value_t = output::ColorChoice::Auto)]
+    pub color: output::ColorChoice,
+
+    /// Report how long each step took, on stderr and as `timings_ms` in --json results
+    #[arg(long, global = true)]
+    pub timings: bool,
+
+    /// Log every command phantom runs with its exit code and duration, without the rest of --verbose
+    #[arg(long, global = true)]
+    pub trace_commands: bool,
+
+    /// Fail on deprecated and unknown configuration keys, as `"strict": true` does
+    #[arg(long, global = true)]
+    pub strict_config: bool,
+
+    /// Run against the repository at PATH instead of the current directory
+    #[arg(long, global = true, value_name = "PATH")]
+    pub repo: Option<PathBuf>,
+
+    /// Print the time spent parsing arguments, finding the repository and loading config
+    #[arg(long, global = true, hide = true)]
+    pub profile_startup: bool,
 }
 
 #[derive(Subcommand)]
@@ -52,9 +93,200 @@ pub enum Commands {
     /// Open an interactive shell in a worktree directory
     Shell(commands::shell::ShellArgs),
 
+    /// Add or remove labels on a worktree
+    Label(commands::label::LabelArgs),
+
+    /// Show details of a worktree, optionally with the files it changes
+    Show(commands::show::ShowArgs),
+
+    /// Summarize a worktree's branch, HEAD commit, changes and upstream
+    Status(commands::status::StatusArgs),
+
+    /// Inspect the repository configuration
+    Config(commands::config::ConfigArgs),
+
+    /// Find and remove directories and state left behind by deleted worktrees
+    Gc(commands::gc::GcArgs),
+
+    /// Write a starter phantom.config.toml or phantom.config.json
+    Init(commands::init::InitArgs),
+
+    /// Manage a worktree created outside phantom, moving it under the phantom directory
+    Adopt(commands::adopt::AdoptArgs),
+
+    /// Lock a worktree so git will not prune or remove it
+    Lock(commands::lock::LockArgs),
+
+    /// Unlock a worktree locked with `phantom lock` or `git worktree lock`
+    Unlock(commands::lock::UnlockArgs),
+
     /// Display phantom version information
     Version(commands::version::VersionArgs),
 
     /// Generate shell completion scripts
     Completion(commands::completion::CompletionArgs),
+
+    /// Print a shell function adding `phantom cd <name>`, for eval in your rc file
+    Shellenv(commands::shellenv::ShellenvArgs),
+
+    /// Print worktree names for shell completion
+    #[command(name = "__complete-worktrees", hide = true)]
+    CompleteWorktrees,
+
+    /// Print recent commits and changed files for the fzf preview pane
+    #[command(name = "__preview", hide = true)]
+    Preview(commands::preview::PreviewArgs),
+}
+
+/// Check the directory given with `--repo`, returning its absolute path
+pub fn resolve_repository(repo: &Path) -> anyhow::Result<PathBuf> {
+    let path =
+        repo.canonicalize().with_context(|| format!("Cannot use --repo '{}'", repo.display()))?;
+    if !path.is_dir() {
+        bail!("--repo '{}' is not a directory", repo.display());
+    }
+    Ok(path)
+}
+
+impl Commands {
+    /// Whether the command works on a repository; the others run no git and read no config
+    pub fn needs_repository(&self) -> bool {
+        !matches!(
+            self,
+            Commands::Version(_)
+                | Commands::Completion(_)
+                | Commands::Shellenv(_)
+                | Commands::CompleteWorktrees
+                | Commands::Preview(_)
+        )
+    }
+}
+
+/// Run `command` with `context`, applying the repository's git settings first when it needs them
+pub async fn run<E, F, H>(command: Commands, context: HandlerContext<E, F, H>) -> anyhow::Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    // `config validate` lists the deprecated and unknown keys itself
+    let context = match command {
+        Commands::Config(_) => context.without_config_notes(),
+        _ => context,
+    };
+    let git_settings = if command.needs_repository() {
+        git_settings(&context).await
+    } else {
+        GitSettings::default()
+    };
+    let context = context.with_git_settings(git_settings);
+
+    match command {
+        Commands::Create(args) => handlers::create::handle(args, context).await,
+        Commands::Attach(args) => handlers::attach::handle(args, context).await,
+        Commands::List(args) => handlers::list::handle(args, context).await,
+        Commands::Where(args) => handlers::where_cmd::handle(args, context).await,
+        Commands::Delete(args) => handlers::delete::handle(args, context).await,
+        Commands::Exec(args) => handlers::exec::handle(args, context).await,
+        Commands::Shell(args) => handlers::shell::handle(args, context).await,
+        Commands::Label(args) => handlers::label::handle(args, context).await,
+        Commands::Show(args) => handlers::show::handle(args, context).await,
+        Commands::Status(args) => handlers::status::handle(args, context).await,
+        Commands::Config(args) => handlers::config::handle(args, context).await,
+        Commands::Gc(args) => handlers::gc::handle(args, context).await,
+        Commands::Init(args) => handlers::init::handle(args, context).await,
+        Commands::Adopt(args) => handlers::adopt::handle(args, context).await,
+        Commands::Lock(args) => handlers::lock::handle(args, context).await,
+        Commands::Unlock(args) => handlers::lock::handle_unlock(args, context).await,
+        Commands::Version(args) => {
+            handlers::version::handle(args);
+            Ok(())
+        }
+        Commands::Completion(args) => handlers::completion::handle(args),
+        Commands::Shellenv(args) => handlers::shellenv::handle(args),
+        Commands::CompleteWorktrees => {
+            handlers::completion::complete_worktrees(&context.filesystem, context.repository())
+                .await;
+            Ok(())
+        }
+        Commands::Preview(args) => {
+            handlers::preview::handle(args, context).await;
+            Ok(())
+        }
+    }
+}
+
+/// The repository's `gitTimeoutSecs` and `git` retry settings, or the defaults when it has no config
+pub async fn git_settings<E, F, H>(context: &HandlerContext<E, F, H>) -> GitSettings
+where
+    E: CommandExecutor + Clone + 'static,
+    F: FileSystem + Clone + 'static,
+    H: ExitHandler + Clone + 'static,
+{
+    let Ok(git_root) = timed("git_root", context.git_root()).await else {
+        return GitSettings::default();
+    };
+    match context.config(&git_root).await {
+        Ok(Some(loaded)) => GitSettings::from_config(&loaded.config),
+        Ok(None) => GitSettings::default(),
+        Err(e) => {
+            debug!("Skipping git settings, config failed to load: {e}");
+            GitSettings::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::exit_handler::MockExitHandler;
+    use crate::core::filesystems::MockFileSystem;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_version_and_completion_run_no_commands() {
+        for command in [&["phantom", "version"][..], &["phantom", "completion", "bash"]] {
+            let cli = Cli::try_parse_from(command).unwrap();
+            assert!(!cli.command.needs_repository());
+            // Any git command would fail on the mock, which has no expectations
+            let context = HandlerContext::new(
+                MockCommandExecutor::new(),
+                MockFileSystem::new(),
+                MockExitHandler::new(),
+            );
+
+            run(cli.command, context.clone()).await.unwrap();
+
+            assert!(context.executor.calls().is_empty(), "{command:?} ran git");
+            assert!(!context.config_loaded(), "{command:?} loaded the config");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repository_commands_share_one_config_load() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("phantom.config.toml"),
+            "branchPrefix = \"one/\"\ngitTimeoutSecs = 120\n\n[git]\nretries = 5\n",
+        )
+        .unwrap();
+        let mut executor = MockCommandExecutor::new();
+        executor
+            .expect_command("git")
+            .with_args(&["rev-parse", "--git-common-dir"])
+            .returns_output(&dir.path().join(".git").to_string_lossy(), "", 0);
+        let context = HandlerContext::new(executor, MockFileSystem::new(), MockExitHandler::new());
+
+        let settings = git_settings(&context).await;
+        assert_eq!(settings.timeout, Duration::from_secs(120));
+        assert_eq!(settings.lock_retries, 5);
+        assert!(context.config_loaded());
+
+        // The handler sees the same load, even after the file changed
+        std::fs::write(dir.path().join("phantom.config.toml"), "branchPrefix = \"two/\"\n")
+            .unwrap();
+        let loaded = context.config(dir.path()).await.unwrap().unwrap();
+        assert_eq!(loaded.config.branch_prefix.as_deref(), Some("one/"));
+    }
 }
@@ -1,18 +1,82 @@
-use crate::process::tty::should_use_color;
+use crate::cli::logging::LogFormat;
+use crate::core::environment::{Environment, RealEnvironment};
+use crate::core::utils::{current_timings, Timings};
+use crate::process::tty::{is_stdin_tty, is_stdout_tty};
+use clap::ValueEnum;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::io::{self, Write};
+use std::time::Duration;
+
+/// When to color the output, from --color
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal, unless NO_COLOR or TERM=dumb say otherwise
+    #[default]
+    Auto,
+    /// Always color, even when piped
+    Always,
+    /// Never color
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to color, given the environment and whether stdout is a terminal
+    ///
+    /// An explicit `always` or `never` wins over the environment. With `auto`, a
+    /// non-empty `NO_COLOR` turns color off, then a non-empty `CLICOLOR_FORCE`
+    /// other than `0` (or `FORCE_COLOR`) turns it on even when piped.
+    pub fn resolve(self, env: &dyn Environment, stdout_tty: bool) -> bool {
+        let is_set = |key: &str| env.get_var(key).is_some_and(|value| !value.is_empty());
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto if is_set("NO_COLOR") => false,
+            ColorChoice::Auto
+                if is_set("CLICOLOR_FORCE")
+                    && env.get_var("CLICOLOR_FORCE").as_deref() != Some("0")
+                    || is_set("FORCE_COLOR") =>
+            {
+                true
+            }
+            ColorChoice::Auto => env.get_var("TERM").as_deref() != Some("dumb") && stdout_tty,
+        }
+    }
+}
 
 /// Output handler for the CLI
 pub struct Output {
     pub quiet: bool,
     pub verbose: bool,
     pub json: bool,
+    pub log_format: LogFormat,
+    /// Whether messages are colored with ANSI escapes
+    pub color: bool,
 }
 
 impl Output {
     /// Create a new output handler
     pub fn new(quiet: bool, verbose: bool, json: bool) -> Self {
-        Self { quiet, verbose, json }
+        Self { quiet, verbose, json, log_format: LogFormat::default(), color: false }
+    }
+
+    /// Color success, warning and error messages
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set the log format; with [`LogFormat::Json`] messages become tracing events
+    pub fn with_log_format(mut self, log_format: LogFormat) -> Self {
+        self.log_format = log_format;
+        self
+    }
+
+    /// Whether status messages go through tracing instead of the terminal
+    ///
+    /// Command results printed with [`Output::log`] stay on stdout either way.
+    pub fn emits_events(&self) -> bool {
+        self.log_format == LogFormat::Json
     }
 
     /// Internal helper for common output logic
@@ -20,6 +84,20 @@ impl Output {
         !self.json && (!self.quiet || require_verbose && self.verbose)
     }
 
+    /// `message` with an optional prefix, wrapped in `color` when color is enabled
+    fn styled(&self, message: &str, prefix: Option<&str>, color: Option<&str>) -> String {
+        let formatted = if let Some(prefix) = prefix {
+            format!("{prefix}: {message}")
+        } else {
+            message.to_string()
+        };
+
+        match color {
+            Some(color) if self.color => format!("{color}{formatted}\x1b[0m"),
+            _ => formatted,
+        }
+    }
+
     /// Internal helper for styled output
     fn print_styled(
         &self,
@@ -28,17 +106,7 @@ impl Output {
         color: Option<&str>,
         to_stderr: bool,
     ) {
-        let formatted = if let Some(prefix) = prefix {
-            format!("{prefix}: {message}")
-        } else {
-            message.to_string()
-        };
-
-        let output = if should_use_color() && color.is_some() {
-            format!("{}{}\x1b[0m", color.unwrap(), formatted)
-        } else {
-            formatted
-        };
+        let output = self.styled(message, prefix, color);
 
         if to_stderr {
             eprintln!("{output}");
@@ -57,14 +125,20 @@ impl Output {
     /// Print a verbose message
     pub fn debug(&self, message: &str) {
         if self.should_output(true) {
-            println!("{message}");
+            if self.emits_events() {
+                tracing::debug!("{message}");
+            } else {
+                println!("{message}");
+            }
         }
     }
 
     /// Print an error message
     pub fn error(&self, message: &str) {
-        if !self.json {
-            eprintln!("Error: {message}");
+        if self.emits_events() {
+            tracing::error!("{message}");
+        } else if !self.json {
+            self.print_styled(message, Some("Error"), Some("\x1b[31m"), true);
         }
     }
 
@@ -79,14 +153,22 @@ impl Output {
     /// Print a success message (green if color is enabled)
     pub fn success(&self, message: &str) {
         if self.should_output(false) {
-            self.print_styled(message, None, Some("\x1b[32m"), false);
+            if self.emits_events() {
+                tracing::info!("{message}");
+            } else {
+                self.print_styled(message, None, Some("\x1b[32m"), false);
+            }
         }
     }
 
     /// Print a warning message (yellow if color is enabled)
     pub fn warn(&self, message: &str) {
         if self.should_output(false) {
-            self.print_styled(message, Some("Warning"), Some("\x1b[33m"), true);
+            if self.emits_events() {
+                tracing::warn!("{message}");
+            } else {
+                self.print_styled(message, Some("Warning"), Some("\x1b[33m"), true);
+            }
         }
     }
 
@@ -136,14 +218,112 @@ impl Output {
     }
 }
 
+/// Questions for the user, injected so handlers can be tested without a terminal
+pub trait Prompt: Send + Sync {
+    /// Whether anyone can answer; when false, callers treat the question as declined
+    fn is_interactive(&self) -> bool;
+
+    /// Ask `question`, returning true only for an explicit yes
+    fn confirm(&self, question: &str) -> io::Result<bool>;
+
+    /// Ask `question`, returning the trimmed answer; empty when the user just pressed enter
+    fn ask(&self, question: &str) -> io::Result<String>;
+}
+
+/// Prompt on the terminal, reading the answer from stdin
+pub struct TerminalPrompt;
+
+impl Prompt for TerminalPrompt {
+    fn is_interactive(&self) -> bool {
+        is_stdin_tty()
+    }
+
+    fn confirm(&self, question: &str) -> io::Result<bool> {
+        eprint!("{question} [y/N] ");
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        Ok(is_yes(&answer))
+    }
+
+    fn ask(&self, question: &str) -> io::Result<String> {
+        eprint!("{question} ");
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        Ok(answer.trim().to_string())
+    }
+}
+
+/// Whether a typed answer means yes; anything else, including an empty line, is no
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// Prompt with a scripted answer that records the questions asked
+#[cfg(test)]
+pub struct MockPrompt {
+    pub interactive: bool,
+    pub answer: bool,
+    pub asked: std::sync::Mutex<Vec<String>>,
+    /// Answers to [`Prompt::ask`] in order; an empty answer once they run out
+    pub answers: std::sync::Mutex<std::collections::VecDeque<String>>,
+}
+
+#[cfg(test)]
+impl MockPrompt {
+    pub fn new(interactive: bool, answer: bool) -> Self {
+        Self {
+            interactive,
+            answer,
+            asked: std::sync::Mutex::new(Vec::new()),
+            answers: std::sync::Mutex::new(std::collections::VecDeque::new()),
+        }
+    }
+
+    pub fn with_answers(self, answers: &[&str]) -> Self {
+        *self.answers.lock().unwrap() = answers.iter().map(|answer| answer.to_string()).collect();
+        self
+    }
+
+    pub fn asked(&self) -> Vec<String> {
+        self.asked.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl Prompt for MockPrompt {
+    fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    fn confirm(&self, question: &str) -> io::Result<bool> {
+        self.asked.lock().unwrap().push(question.to_string());
+        Ok(self.answer)
+    }
+
+    fn ask(&self, question: &str) -> io::Result<String> {
+        self.asked.lock().unwrap().push(question.to_string());
+        Ok(self.answers.lock().unwrap().pop_front().unwrap_or_default())
+    }
+}
+
 use std::sync::OnceLock;
 
 /// Global output instance (to be set based on CLI flags)
 static OUTPUT: OnceLock<Output> = OnceLock::new();
 
 /// Initialize the global output handler
-pub fn init_output(quiet: bool, verbose: bool, json: bool) {
-    let _ = OUTPUT.set(Output::new(quiet, verbose, json));
+pub fn init_output(
+    quiet: bool,
+    verbose: bool,
+    json: bool,
+    log_format: LogFormat,
+    color: ColorChoice,
+) {
+    let color = color.resolve(&RealEnvironment, is_stdout_tty());
+    let _ =
+        OUTPUT.set(Output::new(quiet, verbose, json).with_log_format(log_format).with_color(color));
 }
 
 /// Get the global output handler
@@ -155,6 +335,55 @@ pub fn output() -> &'static Output {
     })
 }
 
+/// The `timings_ms` of a JSON result; `None` unless --timings is collecting them
+pub fn timings_ms() -> Option<BTreeMap<String, u64>> {
+    current_timings().map(|timings| timings.to_millis())
+}
+
+/// Lines of the --timings table: each section, then the whole command
+fn timings_table(timings: &Timings, total: Duration) -> Vec<String> {
+    let rows: Vec<(&str, Duration)> = timings
+        .sections()
+        .iter()
+        .map(|(label, elapsed)| (label.as_str(), *elapsed))
+        .chain([("total", total)])
+        .collect();
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(label, elapsed)| format!("{label:<width$}  {:>6} ms", elapsed.as_millis()))
+        .collect()
+}
+
+/// Print the --timings table on stderr, leaving stdout to the command's results
+pub fn report_timings(timings: &Timings, total: Duration) {
+    for line in timings_table(timings, total) {
+        eprintln!("{line}");
+    }
+}
+
+/// Lines of the --profile-startup breakdown, in fractional milliseconds
+fn startup_profile(parse: Duration, timings: &Timings, total: Duration) -> Vec<String> {
+    let section = |label: &str| {
+        timings.sections().iter().find(|(name, _)| name == label).map_or(Duration::ZERO, |s| s.1)
+    };
+    let rows = [
+        ("parse", parse),
+        ("git_root", section("git_root")),
+        ("config", section("config")),
+        ("total", total),
+    ];
+    rows.iter()
+        .map(|(label, elapsed)| format!("{label:<8}  {:>8.2} ms", elapsed.as_secs_f64() * 1000.0))
+        .collect()
+}
+
+/// Print the --profile-startup breakdown on stderr
+pub fn report_startup_profile(parse: Duration, timings: &Timings, total: Duration) {
+    for line in startup_profile(parse, timings, total) {
+        eprintln!("{line}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,6 +513,87 @@ mod tests {
         output.print("test"); // Should not print
     }
 
+    #[test]
+    fn test_json_log_format_routes_messages_through_tracing() {
+        use std::sync::{Arc, Mutex};
+
+        assert!(!Output::new(false, false, false).emits_events());
+        assert!(!Output::new(false, false, false)
+            .with_log_format(LogFormat::Compact)
+            .emits_events());
+
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(move || CaptureWriter(writer.clone()))
+            .finish();
+        let output = Output::new(false, false, false).with_log_format(LogFormat::Json);
+        assert!(output.emits_events());
+        tracing::subscriber::with_default(subscriber, || {
+            output.success("created feature");
+            output.warn("copy failed");
+            output.error("boom");
+        });
+
+        let captured = String::from_utf8(captured.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> =
+            captured.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let levels_and_messages: Vec<(&str, &str)> = events
+            .iter()
+            .map(|event| {
+                (event["level"].as_str().unwrap(), event["fields"]["message"].as_str().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            levels_and_messages,
+            vec![("INFO", "created feature"), ("WARN", "copy failed"), ("ERROR", "boom")]
+        );
+    }
+
+    struct CaptureWriter(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for CaptureWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_timings_table() {
+        let mut timings = Timings::default();
+        timings.record("git_root", Duration::from_millis(3));
+        timings.record("worktree_add", Duration::from_millis(1250));
+
+        assert_eq!(
+            timings_table(&timings, Duration::from_millis(1300)),
+            vec!["git_root           3 ms", "worktree_add    1250 ms", "total           1300 ms",]
+        );
+    }
+
+    #[test]
+    fn test_startup_profile_lists_each_phase() {
+        let mut timings = Timings::default();
+        timings.record("git_root", Duration::from_micros(2500));
+        timings.record("worktree_add", Duration::from_millis(40));
+
+        // Phases that did not run are reported as zero, other sections are left out
+        assert_eq!(
+            startup_profile(Duration::from_micros(300), &timings, Duration::from_millis(45)),
+            vec![
+                "parse         0.30 ms",
+                "git_root      2.50 ms",
+                "config        0.00 ms",
+                "total        45.00 ms",
+            ]
+        );
+    }
+
     #[test]
     fn test_init_output() {
         // Test initialization - create a new Output instance directly
@@ -305,6 +615,64 @@ mod tests {
         assert!(json_output.json);
     }
 
+    #[test]
+    fn test_is_yes() {
+        for answer in ["y\n", "Y", " yes ", "YES\n"] {
+            assert!(is_yes(answer), "{answer:?}");
+        }
+        for answer in ["", "\n", "n", "no", "yep", "y es"] {
+            assert!(!is_yes(answer), "{answer:?}");
+        }
+    }
+
+    #[test]
+    fn test_color_choice_resolve() {
+        use crate::core::environment::MockEnvironment;
+
+        let plain = MockEnvironment::new();
+        let no_color = MockEnvironment::new().with_var("NO_COLOR", "1");
+        let empty_no_color = MockEnvironment::new().with_var("NO_COLOR", "");
+        let forced = MockEnvironment::new().with_var("CLICOLOR_FORCE", "1");
+        let not_forced = MockEnvironment::new().with_var("CLICOLOR_FORCE", "0");
+        let both = MockEnvironment::new().with_var("NO_COLOR", "1").with_var("CLICOLOR_FORCE", "1");
+        let dumb = MockEnvironment::new().with_var("TERM", "dumb");
+
+        let cases: [(ColorChoice, &MockEnvironment, bool, bool); 16] = [
+            (ColorChoice::Auto, &plain, true, true),
+            (ColorChoice::Auto, &plain, false, false),
+            (ColorChoice::Auto, &no_color, true, false),
+            (ColorChoice::Auto, &empty_no_color, true, true),
+            (ColorChoice::Auto, &forced, false, true),
+            (ColorChoice::Auto, &not_forced, false, false),
+            (ColorChoice::Auto, &both, false, false),
+            (ColorChoice::Auto, &dumb, true, false),
+            (ColorChoice::Always, &plain, false, true),
+            (ColorChoice::Always, &no_color, false, true),
+            (ColorChoice::Always, &dumb, false, true),
+            (ColorChoice::Always, &plain, true, true),
+            (ColorChoice::Never, &plain, true, false),
+            (ColorChoice::Never, &forced, true, false),
+            (ColorChoice::Never, &forced, false, false),
+            (ColorChoice::Never, &plain, false, false),
+        ];
+        for (choice, env, tty, expected) in cases {
+            assert_eq!(choice.resolve(env, tty), expected, "{choice:?} tty={tty} {env:?}");
+        }
+    }
+
+    #[test]
+    fn test_styled_only_colors_when_enabled() {
+        let plain = Output::new(false, false, false);
+        assert_eq!(plain.styled("boom", Some("Error"), Some("\x1b[31m")), "Error: boom");
+
+        let colored = Output::new(false, false, false).with_color(true);
+        assert_eq!(
+            colored.styled("boom", Some("Error"), Some("\x1b[31m")),
+            "\x1b[31mError: boom\x1b[0m"
+        );
+        assert_eq!(colored.styled("done", None, None), "done");
+    }
+
     #[test]
     fn test_output_singleton() {
         // Test that output() returns a singleton
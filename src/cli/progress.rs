@@ -0,0 +1,160 @@
+use crate::cli::output::output;
+use crate::process::tty::is_stdout_tty;
+use crate::worktree::file_copier::ProgressReporter;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often the progress bar is redrawn on a terminal
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+/// How often a progress line is logged when stdout is not a terminal
+const LOG_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Progress of the copyFiles step, redrawn in place on a terminal and logged periodically otherwise
+///
+/// Nothing is shown for copies that finish within the first interval.
+#[derive(Debug)]
+pub struct CopyProgress {
+    tty: bool,
+    state: Mutex<CopyState>,
+}
+
+#[derive(Debug)]
+struct CopyState {
+    total: usize,
+    done: usize,
+    bytes: u64,
+    started: Instant,
+    last_shown: Option<Instant>,
+}
+
+impl CopyProgress {
+    pub fn new(tty: bool) -> Self {
+        Self {
+            tty,
+            state: Mutex::new(CopyState {
+                total: 0,
+                done: 0,
+                bytes: 0,
+                started: Instant::now(),
+                last_shown: None,
+            }),
+        }
+    }
+
+    fn interval(&self) -> Duration {
+        if self.tty {
+            REDRAW_INTERVAL
+        } else {
+            LOG_INTERVAL
+        }
+    }
+}
+
+/// A reporter for the copy in `phantom create`, unless output is quiet
+///
+/// The bar is only redrawn in place with escape codes when color is enabled too,
+/// so `--color never` gets plain progress lines even on a terminal.
+pub fn copy_progress() -> Option<Arc<dyn ProgressReporter>> {
+    let output = output();
+    if output.quiet || output.json {
+        return None;
+    }
+    Some(Arc::new(CopyProgress::new(is_stdout_tty() && output.color)))
+}
+
+impl ProgressReporter for CopyProgress {
+    fn start(&self, total_files: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.total = total_files;
+        state.started = Instant::now();
+    }
+
+    fn file_done(&self, bytes: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.done += 1;
+        state.bytes += bytes;
+
+        let now = Instant::now();
+        if now.duration_since(state.last_shown.unwrap_or(state.started)) < self.interval() {
+            return;
+        }
+        state.last_shown = Some(now);
+
+        let line = progress_line(state.done, state.total, state.bytes, now - state.started);
+        if self.tty {
+            let mut stdout = io::stdout().lock();
+            let _ = write!(stdout, "\r\x1b[2K{line}");
+            let _ = stdout.flush();
+        } else {
+            output().log(&line);
+        }
+    }
+
+    fn finish(&self) {
+        let state = self.state.lock().unwrap();
+        // Clear the bar so the summary that follows starts on an empty line
+        if self.tty && state.last_shown.is_some() {
+            let mut stdout = io::stdout().lock();
+            let _ = write!(stdout, "\r\x1b[2K");
+            let _ = stdout.flush();
+        }
+    }
+}
+
+/// `Copying files: done/total files (rate)`
+fn progress_line(done: usize, total: usize, bytes: u64, elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs_f64();
+    let rate = if seconds > 0.0 { bytes as f64 / seconds } else { 0.0 };
+    format!("Copying files: {done}/{total} files ({}/s)", format_bytes(rate))
+}
+
+/// A byte count with a binary unit, e.g. `3.2 MB`
+pub(crate) fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024.0 {
+        return format!("{bytes:.0} B");
+    }
+    let mut value = bytes / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_line() {
+        assert_eq!(
+            progress_line(1200, 4000, 3 * 1024 * 1024, Duration::from_secs(2)),
+            "Copying files: 1200/4000 files (1.5 MB/s)"
+        );
+        assert_eq!(progress_line(0, 10, 0, Duration::ZERO), "Copying files: 0/10 files (0 B/s)");
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512.0), "512 B");
+        assert_eq!(format_bytes(1536.0), "1.5 KB");
+        assert_eq!(format_bytes(5.0 * 1024.0 * 1024.0 * 1024.0), "5.0 GB");
+    }
+
+    #[test]
+    fn test_copy_progress_counts_files() {
+        let progress = CopyProgress::new(false);
+        progress.start(3);
+        progress.file_done(10);
+        progress.file_done(0);
+        progress.finish();
+
+        let state = progress.state.lock().unwrap();
+        assert_eq!((state.total, state.done, state.bytes), (3, 2, 10));
+        // Finished within the first interval, so nothing was shown
+        assert!(state.last_shown.is_none());
+    }
+}
@@ -0,0 +1,203 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// A renamed configuration key, as dotted paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyAlias {
+    pub deprecated: &'static str,
+    pub replacement: &'static str,
+}
+
+/// Deprecated keys that are still accepted and rewritten on load
+pub const KEY_ALIASES: &[KeyAlias] =
+    &[KeyAlias { deprecated: "copyFiles", replacement: "postCreate.copyFiles" }];
+
+/// A deprecated key found while loading a configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AliasNote {
+    pub deprecated: String,
+    pub replacement: String,
+    /// Both keys were set, so the deprecated value was dropped
+    pub conflict: bool,
+}
+
+impl fmt::Display for AliasNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.conflict {
+            write!(
+                f,
+                "'{}' is deprecated and ignored because '{}' is also set",
+                self.deprecated, self.replacement
+            )
+        } else {
+            write!(f, "'{}' is deprecated, use '{}' instead", self.deprecated, self.replacement)
+        }
+    }
+}
+
+/// Rewrite deprecated keys in a raw configuration value
+///
+/// The deprecated value moves to its replacement unless the replacement is
+/// already set, in which case the replacement wins.
+pub fn apply_aliases(value: &mut Value, aliases: &[KeyAlias]) -> Vec<AliasNote> {
+    let mut notes = Vec::new();
+
+    for alias in aliases {
+        let Some(old_value) = take(value, alias.deprecated) else {
+            continue;
+        };
+
+        let conflict = get(value, alias.replacement).is_some();
+        if !conflict {
+            if let Err(old_value) = insert(value, alias.replacement, old_value) {
+                // The replacement's parent is not a table; leave it for validation to report
+                let _ = insert(value, alias.deprecated, old_value);
+                continue;
+            }
+        }
+
+        notes.push(AliasNote {
+            deprecated: alias.deprecated.to_string(),
+            replacement: alias.replacement.to_string(),
+            conflict,
+        });
+    }
+
+    notes
+}
+
+fn get<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |current, key| current.get(key))
+}
+
+fn take(value: &mut Value, path: &str) -> Option<Value> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => {
+            (parent.split('.').try_fold(value, |current, key| current.get_mut(key))?, key)
+        }
+        None => (value, path),
+    };
+    parent.as_object_mut()?.remove(key)
+}
+
+fn insert(value: &mut Value, path: &str, new_value: Value) -> Result<(), Value> {
+    let mut current = value;
+    let mut keys = path.split('.').peekable();
+
+    while let Some(key) = keys.next() {
+        let Some(object) = current.as_object_mut() else {
+            return Err(new_value);
+        };
+        if keys.peek().is_none() {
+            object.insert(key.to_string(), new_value);
+            return Ok(());
+        }
+        current = object.entry(key).or_insert_with(|| Value::Object(Map::new()));
+    }
+
+    Err(new_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TEST_ALIASES: &[KeyAlias] = &[
+        KeyAlias { deprecated: "copyFiles", replacement: "postCreate.copyFiles" },
+        KeyAlias { deprecated: "multiplexer", replacement: "defaultMultiplexer" },
+        KeyAlias { deprecated: "postCreate.run", replacement: "postCreate.commands" },
+    ];
+
+    #[test]
+    fn test_apply_aliases() {
+        struct Case {
+            name: &'static str,
+            input: Value,
+            expected: Value,
+            notes: &'static [(&'static str, bool)],
+        }
+
+        let cases = [
+            Case {
+                name: "no deprecated keys",
+                input: json!({"defaultMultiplexer": "tmux"}),
+                expected: json!({"defaultMultiplexer": "tmux"}),
+                notes: &[],
+            },
+            Case {
+                name: "top-level key moves into a new table",
+                input: json!({"copyFiles": [".env"]}),
+                expected: json!({"postCreate": {"copyFiles": [".env"]}}),
+                notes: &[("copyFiles", false)],
+            },
+            Case {
+                name: "top-level key moves into an existing table",
+                input: json!({"copyFiles": [".env"], "postCreate": {"commands": ["make"]}}),
+                expected: json!({"postCreate": {"copyFiles": [".env"], "commands": ["make"]}}),
+                notes: &[("copyFiles", false)],
+            },
+            Case {
+                name: "nested key renamed in place",
+                input: json!({"postCreate": {"run": ["make"]}}),
+                expected: json!({"postCreate": {"commands": ["make"]}}),
+                notes: &[("postCreate.run", false)],
+            },
+            Case {
+                name: "conflict keeps the replacement",
+                input: json!({"multiplexer": "kitty", "defaultMultiplexer": "tmux"}),
+                expected: json!({"defaultMultiplexer": "tmux"}),
+                notes: &[("multiplexer", true)],
+            },
+            Case {
+                name: "multiple aliases in table order",
+                input: json!({"copyFiles": [".env"], "multiplexer": "tmux"}),
+                expected: json!({"postCreate": {"copyFiles": [".env"]}, "defaultMultiplexer": "tmux"}),
+                notes: &[("copyFiles", false), ("multiplexer", false)],
+            },
+            Case {
+                name: "non-table parent leaves the key in place",
+                input: json!({"copyFiles": [".env"], "postCreate": "oops"}),
+                expected: json!({"copyFiles": [".env"], "postCreate": "oops"}),
+                notes: &[],
+            },
+        ];
+
+        for case in cases {
+            let mut value = case.input;
+            let notes = apply_aliases(&mut value, TEST_ALIASES);
+            assert_eq!(value, case.expected, "{}", case.name);
+
+            let actual: Vec<_> =
+                notes.iter().map(|n| (n.deprecated.as_str(), n.conflict)).collect();
+            assert_eq!(actual, case.notes, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_alias_note_display() {
+        let note = AliasNote {
+            deprecated: "copyFiles".to_string(),
+            replacement: "postCreate.copyFiles".to_string(),
+            conflict: false,
+        };
+        assert_eq!(
+            note.to_string(),
+            "'copyFiles' is deprecated, use 'postCreate.copyFiles' instead"
+        );
+
+        let note = AliasNote { conflict: true, ..note };
+        assert_eq!(
+            note.to_string(),
+            "'copyFiles' is deprecated and ignored because 'postCreate.copyFiles' is also set"
+        );
+    }
+
+    #[test]
+    fn test_key_aliases_are_distinct() {
+        for alias in KEY_ALIASES {
+            assert_ne!(alias.deprecated, alias.replacement);
+        }
+    }
+}
@@ -13,8 +13,25 @@ pub fn default_config() -> PhantomConfig {
                 "config.local.json".to_string(),
             ]),
             commands: None,
+            copy_files_respect_gitignore: None,
         }),
         default_multiplexer: None,
+        auto_split: None,
+        strict: None,
+        git_timeout_secs: None,
+        git: None,
+        editors: None,
+        scratch_dir: None,
+        hooks: None,
+        branch_prefix: None,
+        fzf: None,
+        shell: None,
+        delete: None,
+        terminal_title: None,
+        list: None,
+        env_file: None,
+        create: None,
+        attach: None,
     }
 }
 
@@ -34,8 +51,25 @@ pub fn example_config() -> PhantomConfig {
                 ".vscode/settings.json".to_string(),
             ]),
             commands: Some(vec!["npm install".to_string(), "npm run prepare".to_string()]),
+            copy_files_respect_gitignore: None,
         }),
         default_multiplexer: Some(Multiplexer::Tmux),
+        auto_split: None,
+        strict: None,
+        git_timeout_secs: None,
+        git: None,
+        editors: None,
+        scratch_dir: None,
+        hooks: None,
+        branch_prefix: None,
+        fzf: None,
+        shell: None,
+        delete: None,
+        terminal_title: None,
+        list: None,
+        env_file: None,
+        create: None,
+        attach: None,
     }
 }
 
@@ -77,7 +111,7 @@ impl ConfigFormat {
 }
 
 /// Write a configuration to a file
-async fn write_config(path: &Path, config: &PhantomConfig, format: ConfigFormat) -> Result<()> {
+pub async fn write_config(path: &Path, config: &PhantomConfig, format: ConfigFormat) -> Result<()> {
     let content = match format {
         ConfigFormat::Json => {
             serde_json::to_string_pretty(config).map_err(crate::config::ConfigError::Json)?
@@ -0,0 +1,241 @@
+use crate::config::errors::ConfigError;
+use crate::config::types::PhantomConfig;
+use crate::Result;
+use std::fmt;
+
+/// Why a `${VAR}` reference could not be expanded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterpolationError {
+    /// The variable is unset and the reference has no `:-default`
+    Unset(String),
+    /// A `${` without its closing `}`
+    Unterminated,
+    /// The text between `${` and `}` is not a variable name
+    InvalidName(String),
+}
+
+impl fmt::Display for InterpolationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unset(name) => write!(f, "environment variable '{name}' is not set"),
+            Self::Unterminated => write!(f, "'${{' is missing its closing '}}'"),
+            Self::InvalidName(name) => write!(f, "'{name}' is not a valid variable name"),
+        }
+    }
+}
+
+/// Expand `${VAR}` and `${VAR:-default}` in `input`, reading variables from `lookup`
+///
+/// Defaults may hold further references and are used when the variable is unset
+/// or empty. `$${` stands for a literal `${`; any other `$` is left alone.
+pub fn interpolate<L>(input: &str, lookup: &L) -> std::result::Result<String, InterpolationError>
+where
+    L: Fn(&str) -> Option<String>,
+{
+    let (expanded, _) = expand(input, 0, false, true, lookup)?;
+    Ok(expanded)
+}
+
+/// Expand from byte `start` until the end, or until the `}` closing a default when `nested`
+///
+/// Nothing is looked up unless `evaluate`, so an unused default cannot fail.
+/// Returns the expansion and the index just past what was consumed.
+fn expand<L>(
+    input: &str,
+    start: usize,
+    nested: bool,
+    evaluate: bool,
+    lookup: &L,
+) -> std::result::Result<(String, usize), InterpolationError>
+where
+    L: Fn(&str) -> Option<String>,
+{
+    let mut out = String::new();
+    let mut i = start;
+
+    while i < input.len() {
+        let rest = &input[i..];
+        if rest.starts_with("$${") {
+            out.push_str("${");
+            i += 3;
+        } else if rest.starts_with("${") {
+            let (value, next) = reference(input, i + 2, evaluate, lookup)?;
+            out.push_str(&value);
+            i = next;
+        } else if nested && rest.starts_with('}') {
+            return Ok((out, i + 1));
+        } else {
+            let c = rest.chars().next().expect("index is inside the string");
+            out.push(c);
+            i += c.len_utf8();
+        }
+    }
+
+    if nested {
+        return Err(InterpolationError::Unterminated);
+    }
+    Ok((out, i))
+}
+
+/// Expand the reference whose name starts at byte `start`, just past `${`
+fn reference<L>(
+    input: &str,
+    start: usize,
+    evaluate: bool,
+    lookup: &L,
+) -> std::result::Result<(String, usize), InterpolationError>
+where
+    L: Fn(&str) -> Option<String>,
+{
+    let rest = &input[start..];
+    let name_len = rest.find(['}', ':']).ok_or(InterpolationError::Unterminated)?;
+    let name = &rest[..name_len];
+    if !is_variable_name(name) {
+        return Err(InterpolationError::InvalidName(name.to_string()));
+    }
+    let value = if evaluate { lookup(name) } else { Some(String::new()) };
+
+    let after_name = start + name_len;
+    if input[after_name..].starts_with('}') {
+        let value = value.ok_or_else(|| InterpolationError::Unset(name.to_string()))?;
+        return Ok((value, after_name + 1));
+    }
+    if !input[after_name..].starts_with(":-") {
+        let end = input[after_name..].find('}').map_or(input.len(), |end| after_name + end);
+        return Err(InterpolationError::InvalidName(input[start..end].to_string()));
+    }
+
+    let value = value.filter(|value| !value.is_empty());
+    let (default, next) = expand(input, after_name + 2, true, evaluate && value.is_none(), lookup)?;
+    Ok((value.unwrap_or(default), next))
+}
+
+/// `[A-Za-z_][A-Za-z0-9_]*`
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Expand references in the configuration values that may hold them
+///
+/// These are the copyFiles entries, the post-create commands, `scratchDir` and `shell`.
+pub fn interpolate_config<L>(config: &mut PhantomConfig, lookup: &L) -> Result<()>
+where
+    L: Fn(&str) -> Option<String>,
+{
+    let expand_value = |key: &str, value: &mut String| -> Result<()> {
+        *value = interpolate(value, lookup)
+            .map_err(|e| ConfigError::ValidationError(format!("{key}: {e}")))?;
+        Ok(())
+    };
+
+    if let Some(post_create) = config.post_create.as_mut() {
+        for file in post_create.copy_files.iter_mut().flatten() {
+            expand_value("postCreate.copyFiles", file)?;
+        }
+        for command in post_create.commands.iter_mut().flatten() {
+            expand_value("postCreate.commands", command)?;
+        }
+    }
+    if let Some(scratch_dir) = config.scratch_dir.as_mut() {
+        expand_value("scratchDir", scratch_dir)?;
+    }
+    if let Some(shell) = config.shell.as_mut() {
+        expand_value("shell", shell)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::PostCreateConfig;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "TOKEN" => Some("s3cret".to_string()),
+            "EMPTY" => Some(String::new()),
+            "DIR" => Some("/home/dev".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_interpolate_variables_and_defaults() {
+        let cases = [
+            ("plain text", "plain text"),
+            ("curl -H 'X-Token: ${TOKEN}'", "curl -H 'X-Token: s3cret'"),
+            ("${DIR}/cache/${TOKEN}", "/home/dev/cache/s3cret"),
+            ("${MISSING:-fallback}", "fallback"),
+            ("${EMPTY:-fallback}", "fallback"),
+            ("${TOKEN:-fallback}", "s3cret"),
+            ("${MISSING:-}", ""),
+            ("${EMPTY}", ""),
+            ("echo $HOME $1 $", "echo $HOME $1 $"),
+            ("${MISSING:-é}", "é"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(interpolate(input, &env).as_deref(), Ok(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_interpolate_nested_defaults() {
+        let cases = [
+            ("${MISSING:-${DIR}/x}", "/home/dev/x"),
+            ("${MISSING:-${ALSO_MISSING:-deep}}", "deep"),
+            ("${MISSING:-a${ALSO_MISSING:-b}c}/d", "abc/d"),
+            // A default that is not used cannot fail
+            ("${TOKEN:-${ALSO_MISSING}}", "s3cret"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(interpolate(input, &env).as_deref(), Ok(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_interpolate_escaping() {
+        assert_eq!(interpolate("echo $${TOKEN}", &env).as_deref(), Ok("echo ${TOKEN}"));
+        assert_eq!(interpolate("$${TOKEN}${TOKEN}", &env).as_deref(), Ok("${TOKEN}s3cret"));
+        assert_eq!(interpolate("${MISSING:-$${x}}", &env).as_deref(), Ok("${x}"));
+    }
+
+    #[test]
+    fn test_interpolate_errors() {
+        let cases = [
+            ("${MISSING}", InterpolationError::Unset("MISSING".to_string())),
+            ("${MISSING:-${ALSO_MISSING}}", InterpolationError::Unset("ALSO_MISSING".to_string())),
+            ("${TOKEN", InterpolationError::Unterminated),
+            ("${MISSING:-abc", InterpolationError::Unterminated),
+            ("${1ABC}", InterpolationError::InvalidName("1ABC".to_string())),
+            ("${}", InterpolationError::InvalidName(String::new())),
+            ("${TOKEN:x}", InterpolationError::InvalidName("TOKEN:x".to_string())),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(interpolate(input, &env), Err(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_interpolate_config_names_the_key() {
+        let mut config = PhantomConfig {
+            post_create: Some(PostCreateConfig {
+                copy_files: Some(vec!["${DIR:-x}/.env".to_string()]),
+                commands: Some(vec!["login ${TOKEN}".to_string()]),
+                copy_files_respect_gitignore: None,
+            }),
+            shell: Some("${DIR}/bin/zsh".to_string()),
+            ..Default::default()
+        };
+        interpolate_config(&mut config, &env).unwrap();
+        let post_create = config.post_create.as_ref().unwrap();
+        assert_eq!(post_create.copy_files, Some(vec!["/home/dev/.env".to_string()]));
+        assert_eq!(post_create.commands, Some(vec!["login s3cret".to_string()]));
+        assert_eq!(config.shell.as_deref(), Some("/home/dev/bin/zsh"));
+
+        config.scratch_dir = Some("${SCRATCH}".to_string());
+        let err = interpolate_config(&mut config, &env).unwrap_err().to_string();
+        assert!(err.contains("scratchDir: environment variable 'SCRATCH' is not set"), "{err}");
+    }
+}
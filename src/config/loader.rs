@@ -1,7 +1,15 @@
+use crate::config::aliases::{apply_aliases, AliasNote, KEY_ALIASES};
 use crate::config::errors::ConfigError;
+use crate::config::interpolate::interpolate_config;
 use crate::config::types::PhantomConfig;
+use crate::config::unknown_keys::{find_unknown_keys, UnknownKeyNote, CONFIG_KEYS};
 use crate::config::validate::validate_config;
-use crate::Result;
+use crate::core::const_utils::dirs;
+use crate::core::environment::Environment;
+use crate::git::libs::get_git_root::discover_git_root;
+use crate::{PhantomError, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 use tracing::{debug, info};
@@ -12,30 +20,144 @@ pub const CONFIG_FILE_NAME: &str = "phantom.config.json";
 /// Alternative TOML configuration file name
 pub const TOML_CONFIG_FILE_NAME: &str = "phantom.config.toml";
 
-/// Load configuration from a git repository root
-pub async fn load_config(git_root: &Path) -> Result<Option<PhantomConfig>> {
+/// Shared configuration committed to the repository root; the local files override it
+pub const REPO_CONFIG_FILE_NAME: &str = ".phantom.toml";
+
+/// A loaded configuration along with the deprecated keys that were rewritten
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub config: PhantomConfig,
+    /// The file with the highest precedence
+    pub path: PathBuf,
+    pub deprecations: Vec<Deprecation>,
+    pub unknown_keys: Vec<UnknownKey>,
+    /// Every file that was loaded, lowest precedence first
+    pub files: Vec<PathBuf>,
+    /// Files that set each top-level key, lowest precedence first
+    pub sources: BTreeMap<String, Vec<PathBuf>>,
+}
+
+/// A deprecated key and the file it was found in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    pub path: PathBuf,
+    pub note: AliasNote,
+}
+
+impl std::fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.note)
+    }
+}
+
+/// A key that no setting reads and the file it was found in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+    pub path: PathBuf,
+    pub note: UnknownKeyNote,
+}
+
+impl std::fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.note)
+    }
+}
+
+/// One configuration file, parsed and with deprecated keys rewritten
+#[derive(Debug)]
+struct ConfigLayer {
+    value: Value,
+    path: PathBuf,
+    format_name: &'static str,
+    deprecations: Vec<AliasNote>,
+    unknown_keys: Vec<UnknownKeyNote>,
+}
+
+/// Load configuration from a git repository root, expanding `${VAR}` from `environment`
+pub async fn load_config(
+    git_root: &Path,
+    environment: &dyn Environment,
+) -> Result<Option<LoadedConfig>> {
+    match load_local_layer(git_root).await? {
+        Some(layer) => merge_layers(vec![layer], Some(environment), false).map(Some),
+        None => {
+            debug!("No configuration file found in {}", git_root.display());
+            Ok(None)
+        }
+    }
+}
+
+/// Load the shared `.phantom.toml` with the local configuration merged on top
+///
+/// Tables are merged key by key, while scalars and arrays from the local file
+/// replace the shared ones. `${VAR}` references are read from `environment`.
+pub async fn load_merged_config(
+    git_root: &Path,
+    environment: &dyn Environment,
+) -> Result<Option<LoadedConfig>> {
+    load_merged_config_with(git_root, environment, false).await
+}
+
+/// Like [`load_merged_config`], failing on deprecated and unknown keys when `strict` is set
+///
+/// `strict` is the `--strict-config` flag; `"strict": true` in a file has the same effect.
+pub async fn load_merged_config_with(
+    git_root: &Path,
+    environment: &dyn Environment,
+    strict: bool,
+) -> Result<Option<LoadedConfig>> {
+    let mut layers = Vec::new();
+    match read_toml_layer(&git_root.join(REPO_CONFIG_FILE_NAME)).await {
+        Ok(layer) => layers.push(layer),
+        Err(PhantomError::ConfigNotFound { .. }) => {}
+        Err(e) => return Err(e),
+    }
+    layers.extend(load_local_layer(git_root).await?);
+
+    if layers.is_empty() {
+        debug!("No configuration file found in {}", git_root.display());
+        return Ok(None);
+    }
+    merge_layers(layers, Some(environment), strict).map(Some)
+}
+
+/// The configuration files `load_merged_config` reads in `git_root`, lowest precedence first
+pub async fn config_file_paths(git_root: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for names in [&[REPO_CONFIG_FILE_NAME][..], &[CONFIG_FILE_NAME, TOML_CONFIG_FILE_NAME]] {
+        for name in names {
+            let path = git_root.join(name);
+            if fs::try_exists(&path).await.unwrap_or(false) {
+                paths.push(path);
+                break;
+            }
+        }
+    }
+    paths
+}
+
+/// Read the developer-local configuration, preferring JSON over TOML
+async fn load_local_layer(git_root: &Path) -> Result<Option<ConfigLayer>> {
     // Try JSON first (for backward compatibility)
-    let json_path = git_root.join(CONFIG_FILE_NAME);
-    if let Ok(config) = load_json_config(&json_path).await {
-        return Ok(Some(config));
+    match read_json_layer(&git_root.join(CONFIG_FILE_NAME)).await {
+        Ok(layer) => return Ok(Some(layer)),
+        Err(PhantomError::ConfigNotFound { .. }) => {}
+        Err(e) => return Err(e),
     }
 
     // Try TOML as alternative
-    let toml_path = git_root.join(TOML_CONFIG_FILE_NAME);
-    if let Ok(config) = load_toml_config(&toml_path).await {
-        return Ok(Some(config));
+    match read_toml_layer(&git_root.join(TOML_CONFIG_FILE_NAME)).await {
+        Ok(layer) => Ok(Some(layer)),
+        Err(PhantomError::ConfigNotFound { .. }) => Ok(None),
+        Err(e) => Err(e),
     }
-
-    // No configuration found
-    debug!("No configuration file found in {}", git_root.display());
-    Ok(None)
 }
 
-/// Load configuration from a specific file
+/// Load configuration from a specific file, leaving `${VAR}` references as written
 pub async fn load_config_from_file(path: &Path) -> Result<PhantomConfig> {
     match path.extension().and_then(|s| s.to_str()) {
-        Some("json") => load_json_config(path).await,
-        Some("toml") => load_toml_config(path).await,
+        Some("json") => load_json_config(path).await.map(|loaded| loaded.config),
+        Some("toml") => load_toml_config(path).await.map(|loaded| loaded.config),
         _ => Err(ConfigError::ValidationError(
             "Configuration file must have .json or .toml extension".to_string(),
         )
@@ -43,10 +165,10 @@ pub async fn load_config_from_file(path: &Path) -> Result<PhantomConfig> {
     }
 }
 
-/// Generic configuration loader
-async fn load_config_generic<F>(path: &Path, format_name: &str, parser: F) -> Result<PhantomConfig>
+/// Read a configuration file and rewrite its deprecated keys
+async fn read_layer<F>(path: &Path, format_name: &'static str, parser: F) -> Result<ConfigLayer>
 where
-    F: Fn(&str) -> std::result::Result<PhantomConfig, String>,
+    F: Fn(&str) -> std::result::Result<Value, String>,
 {
     debug!("Loading {} configuration from {}", format_name, path.display());
 
@@ -58,32 +180,183 @@ where
         }
     })?;
 
-    let config = parser(&content)
+    let mut value = parser(&content).map_err(|e| {
+        ConfigError::ParseError(format!("{format_name} error in {}: {e}", path.display()))
+    })?;
+
+    // Rewrite renamed keys before typed deserialization
+    let deprecations = apply_aliases(&mut value, KEY_ALIASES);
+    for note in &deprecations {
+        debug!("{}: {}", path.display(), note);
+    }
+    // serde ignores keys it does not know, so a typo would silently do nothing
+    let unknown_keys = find_unknown_keys(&value, CONFIG_KEYS);
+
+    // Type errors are reported against the file and key that caused them, not the merged result
+    serde_json::from_value::<PhantomConfig>(value.clone()).map_err(|e| {
+        let key = failing_key(&value).map(|key| format!(" at '{key}'")).unwrap_or_default();
+        ConfigError::ParseError(format!("{format_name} error in {}{key}: {e}", path.display()))
+    })?;
+
+    Ok(ConfigLayer { value, path: path.to_path_buf(), format_name, deprecations, unknown_keys })
+}
+
+/// Dotted path of the deepest key whose value alone fails to deserialize, such as `postCreate.copyFiles`
+fn failing_key(value: &Value) -> Option<String> {
+    fn find(value: &Value, wrap: &dyn Fn(Value) -> Value, prefix: &str) -> Option<String> {
+        let Value::Object(map) = value else {
+            return None;
+        };
+        for (key, child) in map {
+            let nest = |inner: Value| wrap(Value::Object(Map::from_iter([(key.clone(), inner)])));
+            if serde_json::from_value::<PhantomConfig>(nest(child.clone())).is_ok() {
+                continue;
+            }
+            let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+            return find(child, &nest, &path).or(Some(path));
+        }
+        None
+    }
+    find(value, &|value| value, "")
+}
+
+/// Merge `layers`, lowest precedence first, into a validated configuration
+///
+/// `${VAR}` references are expanded from `environment` when one is given, and
+/// `strict` rejects deprecated and unknown keys even without `"strict": true`.
+fn merge_layers(
+    layers: Vec<ConfigLayer>,
+    environment: Option<&dyn Environment>,
+    strict: bool,
+) -> Result<LoadedConfig> {
+    let mut merged = Value::Object(Map::new());
+    let mut sources: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    let mut deprecations = Vec::new();
+    let mut unknown_keys = Vec::new();
+    let mut files = Vec::new();
+    let mut format_name = "";
+
+    for layer in layers {
+        if let Value::Object(map) = &layer.value {
+            for (key, value) in map {
+                let files = sources.entry(key.clone()).or_default();
+                // A table merged into another keeps the files that set its other keys
+                if !(value.is_object() && merged.get(key).is_some_and(Value::is_object)) {
+                    files.clear();
+                }
+                files.push(layer.path.clone());
+            }
+        }
+        merge_values(&mut merged, layer.value);
+        deprecations.extend(
+            layer
+                .deprecations
+                .into_iter()
+                .map(|note| Deprecation { path: layer.path.clone(), note }),
+        );
+        unknown_keys.extend(
+            layer
+                .unknown_keys
+                .into_iter()
+                .map(|note| UnknownKey { path: layer.path.clone(), note }),
+        );
+        files.push(layer.path);
+        format_name = layer.format_name;
+    }
+
+    let mut config: PhantomConfig = serde_json::from_value(merged)
         .map_err(|e| ConfigError::ParseError(format!("{format_name} error: {e}")))?;
+    // After merging, so the shared file can refer to each developer's variables
+    if let Some(environment) = environment {
+        interpolate_config(&mut config, &|name| environment.get_var(name))?;
+    }
+
+    if strict || config.strict == Some(true) {
+        reject_in_strict_mode(&deprecations, &unknown_keys)?;
+    }
 
     validate_config(&config)?;
 
+    let path = files.last().cloned().unwrap_or_default();
     info!("Loaded configuration from {}", path.display());
-    Ok(config)
+    Ok(LoadedConfig { config, path, deprecations, unknown_keys, files, sources })
+}
+
+/// Fail on the deprecated and unknown keys that are only warnings outside strict mode
+fn reject_in_strict_mode(deprecations: &[Deprecation], unknown_keys: &[UnknownKey]) -> Result<()> {
+    if !deprecations.is_empty() {
+        let notes: Vec<String> = deprecations.iter().map(|d| d.note.to_string()).collect();
+        return Err(ConfigError::ValidationError(format!(
+            "deprecated keys are not allowed in strict mode: {}",
+            notes.join("; ")
+        ))
+        .into());
+    }
+    if !unknown_keys.is_empty() {
+        let notes: Vec<String> = unknown_keys.iter().map(ToString::to_string).collect();
+        return Err(ConfigError::ValidationError(format!(
+            "unknown keys are not allowed in strict mode: {}",
+            notes.join("; ")
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Deep-merge `overlay` into `base`: tables are merged, anything else is replaced
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Read a JSON configuration file
+async fn read_json_layer(path: &Path) -> Result<ConfigLayer> {
+    read_layer(path, "JSON", |content| serde_json::from_str(content).map_err(|e| e.to_string()))
+        .await
+}
+
+/// Read a TOML configuration file
+async fn read_toml_layer(path: &Path) -> Result<ConfigLayer> {
+    read_layer(path, "TOML", |content| toml::from_str(content).map_err(|e| e.to_string())).await
 }
 
 /// Load JSON configuration
-async fn load_json_config(path: &Path) -> Result<PhantomConfig> {
-    load_config_generic(path, "JSON", |content| {
-        serde_json::from_str(content).map_err(|e| e.to_string())
-    })
-    .await
+async fn load_json_config(path: &Path) -> Result<LoadedConfig> {
+    merge_layers(vec![read_json_layer(path).await?], None, false)
 }
 
 /// Load TOML configuration
-async fn load_toml_config(path: &Path) -> Result<PhantomConfig> {
-    load_config_generic(path, "TOML", |content| toml::from_str(content).map_err(|e| e.to_string()))
-        .await
+async fn load_toml_config(path: &Path) -> Result<LoadedConfig> {
+    merge_layers(vec![read_toml_layer(path).await?], None, false)
+}
+
+/// The main worktree when `dir` is inside a linked worktree, whose `.git` is a file
+fn main_worktree_of_linked(dir: &Path) -> Option<PathBuf> {
+    let worktree = dir.ancestors().find(|dir| dir.join(dirs::GIT).exists())?;
+    if !worktree.join(dirs::GIT).is_file() {
+        return None;
+    }
+    discover_git_root(worktree)
 }
 
 /// Find configuration file in directory hierarchy
+///
+/// Inside a linked worktree the search starts at the main worktree, so every
+/// phantom reads the configuration of the checkout it was created from.
 pub async fn find_config_file(start_dir: &Path) -> Option<PathBuf> {
-    let mut current = start_dir;
+    let main_worktree = main_worktree_of_linked(start_dir);
+    let mut current = main_worktree.as_deref().unwrap_or(start_dir);
 
     loop {
         // Check for JSON config
@@ -112,6 +385,7 @@ pub async fn find_config_file(start_dir: &Path) -> Option<PathBuf> {
 mod tests {
     use super::*;
     use crate::config::types::Multiplexer;
+    use crate::core::environment::MockEnvironment;
     use tempfile::TempDir;
 
     #[tokio::test]
@@ -129,7 +403,7 @@ mod tests {
 
         fs::write(&config_path, json).await.unwrap();
 
-        let config = load_json_config(&config_path).await.unwrap();
+        let config = load_json_config(&config_path).await.unwrap().config;
         assert!(config.post_create.is_some());
 
         let post_create = config.post_create.unwrap();
@@ -153,7 +427,7 @@ commands = ["cargo build"]
 
         fs::write(&config_path, toml).await.unwrap();
 
-        let config = load_toml_config(&config_path).await.unwrap();
+        let config = load_toml_config(&config_path).await.unwrap().config;
         assert!(config.post_create.is_some());
 
         let post_create = config.post_create.unwrap();
@@ -174,14 +448,15 @@ commands = ["cargo build"]
         fs::write(&toml_path, r#"defaultMultiplexer = "kitty""#).await.unwrap();
 
         // JSON should take priority
-        let config = load_config(temp_dir.path()).await.unwrap().unwrap();
-        assert_eq!(config.default_multiplexer, Some(Multiplexer::Tmux));
+        let loaded = load_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+        assert_eq!(loaded.config.default_multiplexer, Some(Multiplexer::Tmux));
+        assert_eq!(loaded.path, json_path);
     }
 
     #[tokio::test]
     async fn test_load_config_not_found() {
         let temp_dir = TempDir::new().unwrap();
-        let result = load_config(temp_dir.path()).await.unwrap();
+        let result = load_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap();
         assert!(result.is_none());
     }
 
@@ -242,6 +517,29 @@ commands = ["cargo build"]
         assert_eq!(found.unwrap(), config_path);
     }
 
+    #[tokio::test]
+    async fn test_find_config_file_from_a_linked_worktree_reads_the_main_one() {
+        use crate::core::executors::RealCommandExecutor;
+        use crate::test_utils::TestRepo;
+        use crate::worktree::create::create_worktree;
+
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("phantom.config.json", "{}", "Add config").await.unwrap();
+        let created =
+            create_worktree(RealCommandExecutor, repo.path(), "feature", Default::default())
+                .await
+                .unwrap();
+        let sub_dir = Path::new(&created.path).join("src");
+        fs::create_dir_all(&sub_dir).await.unwrap();
+
+        // The worktree's checked-in copy is not the one in effect
+        let found = find_config_file(&sub_dir).await.unwrap();
+        assert_eq!(
+            found.canonicalize().unwrap(),
+            repo.path().join("phantom.config.json").canonicalize().unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_find_config_file_not_found() {
         let temp_dir = TempDir::new().unwrap();
@@ -272,4 +570,317 @@ commands = ["cargo build"]
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains(".json or .toml"));
     }
+
+    #[tokio::test]
+    async fn test_load_config_propagates_invalid_file() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("phantom.config.json"), "{ invalid json").await.unwrap();
+
+        let result = load_config(temp_dir.path(), &MockEnvironment::new()).await;
+        assert!(result.unwrap_err().to_string().contains("JSON error"));
+    }
+
+    #[tokio::test]
+    async fn test_load_config_applies_aliases() {
+        let temp_dir = TempDir::new().unwrap();
+        let toml = r#"
+copyFiles = [".env"]
+
+[postCreate]
+commands = ["cargo build"]
+"#;
+        fs::write(temp_dir.path().join("phantom.config.toml"), toml).await.unwrap();
+
+        let loaded = load_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+        let post_create = loaded.config.post_create.unwrap();
+        assert_eq!(post_create.copy_files.unwrap(), vec![".env"]);
+        assert_eq!(post_create.commands.unwrap(), vec!["cargo build"]);
+        assert_eq!(loaded.deprecations.len(), 1);
+        assert_eq!(loaded.deprecations[0].note.deprecated, "copyFiles");
+        assert!(!loaded.deprecations[0].note.conflict);
+    }
+
+    #[tokio::test]
+    async fn test_load_config_alias_conflict_prefers_replacement() {
+        let temp_dir = TempDir::new().unwrap();
+        let json = r#"{"copyFiles": ["old"], "postCreate": {"copyFiles": ["new"]}}"#;
+        fs::write(temp_dir.path().join("phantom.config.json"), json).await.unwrap();
+
+        let loaded = load_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+        assert_eq!(loaded.config.post_create.unwrap().copy_files.unwrap(), vec!["new"]);
+        assert!(loaded.deprecations[0].note.conflict);
+    }
+
+    #[tokio::test]
+    async fn test_load_config_strict_mode() {
+        let cases = [
+            (r#"{"strict": true, "copyFiles": [".env"]}"#, true),
+            (r#"{"strict": true, "postCreate": {"copyFiles": [".env"]}}"#, false),
+            (r#"{"strict": false, "copyFiles": [".env"]}"#, false),
+        ];
+
+        for (json, should_fail) in cases {
+            let temp_dir = TempDir::new().unwrap();
+            fs::write(temp_dir.path().join("phantom.config.json"), json).await.unwrap();
+
+            let result = load_config(temp_dir.path(), &MockEnvironment::new()).await;
+            assert_eq!(result.is_err(), should_fail, "{json}");
+            if should_fail {
+                let error = result.unwrap_err().to_string();
+                assert!(error.contains("strict mode"), "{error}");
+                assert!(error.contains("'copyFiles' is deprecated"), "{error}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_config_reports_unknown_keys() {
+        let json = r#"{"postCreat": {"commands": ["make"]}, "hooks": {"enable": false}}"#;
+        let toml = "[post_create]\ncommands = [\"make\"]\n\n[postCreate]\ncopyFile = [\".env\"]\n";
+
+        for (name, contents) in [("phantom.config.json", json), ("phantom.config.toml", toml)] {
+            let temp_dir = TempDir::new().unwrap();
+            let path = temp_dir.path().join(name);
+            fs::write(&path, contents).await.unwrap();
+
+            let loaded =
+                load_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+            assert!(loaded.unknown_keys.iter().all(|unknown| unknown.path == path), "{name}");
+            let notes: Vec<String> =
+                loaded.unknown_keys.iter().map(|unknown| unknown.note.to_string()).collect();
+            let expected = if name.ends_with(".json") {
+                [
+                    "unknown key 'hooks.enable', did you mean 'hooks.enabled'?",
+                    "unknown key 'postCreat', did you mean 'postCreate'?",
+                ]
+            } else {
+                [
+                    "unknown key 'postCreate.copyFile', did you mean 'postCreate.copyFiles'?",
+                    "unknown key 'post_create', did you mean 'postCreate'?",
+                ]
+            };
+            assert_eq!(notes, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_reject_in_strict_mode() {
+        let unknown = UnknownKey {
+            path: PathBuf::from("phantom.config.json"),
+            note: UnknownKeyNote {
+                key: "postCreat".to_string(),
+                suggestion: Some("postCreate".to_string()),
+            },
+        };
+        assert!(reject_in_strict_mode(&[], &[]).is_ok());
+
+        let error = reject_in_strict_mode(&[], &[unknown]).unwrap_err().to_string();
+        assert!(error.contains("unknown keys are not allowed in strict mode"), "{error}");
+        assert!(error.contains("phantom.config.json: unknown key 'postCreat'"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_load_config_strict_mode_rejects_unknown_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let json = r#"{"strict": true, "postCreate": {"command": ["make"]}}"#;
+        fs::write(temp_dir.path().join("phantom.config.json"), json).await.unwrap();
+
+        let error =
+            load_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap_err().to_string();
+        assert!(error.contains("did you mean 'postCreate.commands'?"), "{error}");
+    }
+
+    #[test]
+    fn test_merge_values() {
+        let mut base = serde_json::json!({
+            "defaultMultiplexer": "tmux",
+            "postCreate": {"copyFiles": [".env", ".npmrc"], "commands": ["npm install"]},
+            "hooks": {"enabled": false}
+        });
+        let overlay = serde_json::json!({
+            "defaultMultiplexer": "kitty",
+            "postCreate": {"copyFiles": [".env.local"]},
+            "scratchDir": ".scratch"
+        });
+
+        merge_values(&mut base, overlay);
+
+        assert_eq!(
+            base,
+            serde_json::json!({
+                "defaultMultiplexer": "kitty",
+                "postCreate": {"copyFiles": [".env.local"], "commands": ["npm install"]},
+                "hooks": {"enabled": false},
+                "scratchDir": ".scratch"
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_values_replaces_mismatched_types() {
+        let mut base = serde_json::json!({"postCreate": {"commands": ["make"]}});
+        merge_values(&mut base, serde_json::json!({"postCreate": null}));
+        assert_eq!(base, serde_json::json!({"postCreate": null}));
+
+        let mut base = serde_json::json!({"strict": true});
+        merge_values(&mut base, serde_json::json!({"strict": {"nested": 1}}));
+        assert_eq!(base, serde_json::json!({"strict": {"nested": 1}}));
+    }
+
+    #[tokio::test]
+    async fn test_load_merged_config_expands_env_after_merging() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path().join(REPO_CONFIG_FILE_NAME);
+        let toml = "[postCreate]\ncommands = [\"deploy ${DEPLOY_TARGET:-staging}\"]\n";
+        fs::write(&repo_path, toml).await.unwrap();
+        let local_path = temp_dir.path().join(CONFIG_FILE_NAME);
+        let json = r#"{"postCreate": {"copyFiles": ["${CERTS_DIR}/.env"]}}"#;
+        fs::write(&local_path, json).await.unwrap();
+
+        let error = load_merged_config(temp_dir.path(), &MockEnvironment::new())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("postCreate.copyFiles"), "{error}");
+        assert!(error.contains("'CERTS_DIR' is not set"), "{error}");
+
+        let json = r#"{"postCreate": {"copyFiles": ["${CERTS_DIR:-certs}/.env"]}}"#;
+        fs::write(&local_path, json).await.unwrap();
+        let loaded =
+            load_merged_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+        let post_create = loaded.config.post_create.unwrap();
+        assert_eq!(post_create.copy_files.unwrap(), vec!["certs/.env"]);
+        assert_eq!(post_create.commands.unwrap(), vec!["deploy staging"]);
+
+        // Variables come from the given environment rather than the process
+        let environment = MockEnvironment::new().with_var("DEPLOY_TARGET", "production");
+        let loaded = load_merged_config(temp_dir.path(), &environment).await.unwrap().unwrap();
+        assert_eq!(loaded.config.post_create.unwrap().commands.unwrap(), vec!["deploy production"]);
+
+        // Migration reads the file as written
+        let config = load_config_from_file(&local_path).await.unwrap();
+        assert_eq!(
+            config.post_create.unwrap().copy_files.unwrap(),
+            vec!["${CERTS_DIR:-certs}/.env"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_merged_config_layers_local_over_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path().join(REPO_CONFIG_FILE_NAME);
+        let local_path = temp_dir.path().join(CONFIG_FILE_NAME);
+        let toml = r#"
+defaultMultiplexer = "tmux"
+gitTimeoutSecs = 30
+
+[postCreate]
+copyFiles = [".env"]
+commands = ["npm install"]
+"#;
+        fs::write(&repo_path, toml).await.unwrap();
+        let json =
+            r#"{"defaultMultiplexer": "kitty", "postCreate": {"copyFiles": [".env.local"]}}"#;
+        fs::write(&local_path, json).await.unwrap();
+
+        let loaded =
+            load_merged_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+
+        assert_eq!(loaded.config.default_multiplexer, Some(Multiplexer::Kitty));
+        assert_eq!(loaded.config.git_timeout_secs, Some(30));
+        let post_create = loaded.config.post_create.unwrap();
+        assert_eq!(post_create.copy_files.unwrap(), vec![".env.local"]);
+        assert_eq!(post_create.commands.unwrap(), vec!["npm install"]);
+
+        assert_eq!(loaded.path, local_path);
+        assert_eq!(loaded.files, vec![repo_path.clone(), local_path.clone()]);
+        assert_eq!(loaded.sources["defaultMultiplexer"], vec![local_path.clone()]);
+        assert_eq!(loaded.sources["gitTimeoutSecs"], vec![repo_path.clone()]);
+        assert_eq!(loaded.sources["postCreate"], vec![repo_path, local_path]);
+    }
+
+    #[tokio::test]
+    async fn test_load_merged_config_single_file() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(load_merged_config(temp_dir.path(), &MockEnvironment::new())
+            .await
+            .unwrap()
+            .is_none());
+
+        let repo_path = temp_dir.path().join(REPO_CONFIG_FILE_NAME);
+        fs::write(&repo_path, r#"defaultMultiplexer = "tmux""#).await.unwrap();
+        let loaded =
+            load_merged_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+        assert_eq!(loaded.config.default_multiplexer, Some(Multiplexer::Tmux));
+        assert_eq!(loaded.path, repo_path);
+
+        // The shared file is never read by the local-only loader
+        assert!(load_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_load_merged_config_errors_name_the_layer() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(REPO_CONFIG_FILE_NAME), r#"gitTimeoutSecs = "slow""#)
+            .await
+            .unwrap();
+        fs::write(temp_dir.path().join(CONFIG_FILE_NAME), r#"{"strict": true}"#).await.unwrap();
+
+        let error = load_merged_config(temp_dir.path(), &MockEnvironment::new())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("TOML error in "), "{error}");
+        assert!(error.contains(".phantom.toml at 'gitTimeoutSecs'"), "{error}");
+    }
+
+    #[test]
+    fn test_failing_key_names_the_offending_key() {
+        let value = serde_json::json!({
+            "defaultMultiplexer": "tmux",
+            "postCreate": {"commands": ["make"], "copyFiles": ".env"},
+        });
+        assert_eq!(failing_key(&value).as_deref(), Some("postCreate.copyFiles"));
+
+        let value = serde_json::json!({"defaultMultiplexer": "screen"});
+        assert_eq!(failing_key(&value).as_deref(), Some("defaultMultiplexer"));
+
+        assert_eq!(failing_key(&serde_json::json!({"branchPrefix": "me/"})), None);
+    }
+
+    #[tokio::test]
+    async fn test_load_merged_config_strict_covers_repo_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path().join(REPO_CONFIG_FILE_NAME);
+        fs::write(&repo_path, r#"copyFiles = [".env"]"#).await.unwrap();
+
+        let loaded =
+            load_merged_config(temp_dir.path(), &MockEnvironment::new()).await.unwrap().unwrap();
+        assert_eq!(loaded.deprecations[0].path, repo_path);
+        assert!(loaded.deprecations[0].to_string().starts_with(&repo_path.display().to_string()));
+
+        fs::write(temp_dir.path().join(CONFIG_FILE_NAME), r#"{"strict": true}"#).await.unwrap();
+        let error = load_merged_config(temp_dir.path(), &MockEnvironment::new())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("strict mode"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_load_merged_config_with_strict_flag() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(REPO_CONFIG_FILE_NAME), r#"copyFiles = [".env"]"#)
+            .await
+            .unwrap();
+
+        assert!(load_merged_config_with(temp_dir.path(), &MockEnvironment::new(), false)
+            .await
+            .is_ok());
+        let error = load_merged_config_with(temp_dir.path(), &MockEnvironment::new(), true)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("deprecated keys are not allowed in strict mode"), "{error}");
+    }
 }
@@ -173,8 +173,25 @@ mod tests {
             post_create: Some(PostCreateConfig {
                 copy_files: Some(vec![".env".to_string(), "config.toml".to_string()]),
                 commands: Some(vec!["npm install".to_string()]),
+                copy_files_respect_gitignore: None,
             }),
             default_multiplexer: Some(Multiplexer::Tmux),
+            auto_split: None,
+            strict: None,
+            git_timeout_secs: None,
+            git: None,
+            editors: None,
+            scratch_dir: None,
+            hooks: None,
+            branch_prefix: None,
+            fzf: None,
+            shell: None,
+            delete: None,
+            terminal_title: None,
+            list: None,
+            env_file: None,
+            create: None,
+            attach: None,
         };
 
         let json_content = serde_json::to_string_pretty(&config).unwrap();
@@ -212,8 +229,25 @@ mod tests {
             post_create: Some(PostCreateConfig {
                 copy_files: Some(vec!["Gemfile".to_string(), "Gemfile.lock".to_string()]),
                 commands: Some(vec!["bundle install".to_string()]),
+                copy_files_respect_gitignore: None,
             }),
             default_multiplexer: Some(Multiplexer::Kitty),
+            auto_split: None,
+            strict: None,
+            git_timeout_secs: None,
+            git: None,
+            editors: None,
+            scratch_dir: None,
+            hooks: None,
+            branch_prefix: None,
+            fzf: None,
+            shell: None,
+            delete: None,
+            terminal_title: None,
+            list: None,
+            env_file: None,
+            create: None,
+            attach: None,
         };
 
         let toml_content = toml::to_string_pretty(&config).unwrap();
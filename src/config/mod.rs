@@ -1,18 +1,26 @@
+pub mod aliases;
 pub mod default;
 pub mod errors;
+pub mod interpolate;
 pub mod loader;
 pub mod migrate;
+pub mod provider;
 pub mod types;
+pub mod unknown_keys;
 pub mod validate;
 
 // Re-export commonly used types
+pub use aliases::AliasNote;
 pub use default::{
-    default_config, example_config, minimal_config, write_default_config, write_example_config,
-    ConfigFormat,
+    default_config, example_config, minimal_config, write_config, write_default_config,
+    write_example_config, ConfigFormat,
 };
 pub use errors::ConfigError;
 pub use loader::{
-    find_config_file, load_config, load_config_from_file, CONFIG_FILE_NAME, TOML_CONFIG_FILE_NAME,
+    find_config_file, load_config, load_config_from_file, load_merged_config,
+    load_merged_config_with, Deprecation, LoadedConfig, UnknownKey, CONFIG_FILE_NAME,
+    REPO_CONFIG_FILE_NAME, TOML_CONFIG_FILE_NAME,
 };
+pub use provider::ConfigProvider;
 pub use types::{Multiplexer, PhantomConfig, PostCreateConfig};
 pub use validate::validate_config;
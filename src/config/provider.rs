@@ -0,0 +1,157 @@
+use crate::config::loader::{load_merged_config_with, LoadedConfig};
+use crate::core::environment::{Environment, RealEnvironment};
+use crate::core::utils::timed;
+use crate::Result;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// Loads the repository configuration on first request and keeps it for the invocation
+///
+/// Commands that never ask for the configuration never read it. Clones share the
+/// loaded configuration. A failed load is not kept, so the next request retries
+/// and reports the error itself.
+#[derive(Clone)]
+pub struct ConfigProvider {
+    loaded: Arc<OnceCell<Option<LoadedConfig>>>,
+    /// Where `${VAR}` references in the configuration are looked up
+    environment: Arc<dyn Environment>,
+    /// Reject deprecated and unknown keys (`--strict-config`)
+    strict: bool,
+}
+
+impl Default for ConfigProvider {
+    fn default() -> Self {
+        Self::new(Arc::new(RealEnvironment::new()))
+    }
+}
+
+impl fmt::Debug for ConfigProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigProvider")
+            .field("loaded", &self.loaded)
+            .field("strict", &self.strict)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConfigProvider {
+    /// A provider expanding `${VAR}` references from `environment`
+    pub fn new(environment: Arc<dyn Environment>) -> Self {
+        Self { loaded: Arc::default(), environment, strict: false }
+    }
+
+    /// Fail to load on deprecated and unknown keys, as `"strict": true` does
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Whether deprecated and unknown keys fail the load
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// The merged configuration of `git_root`, loaded on the first call
+    ///
+    /// Later calls return the first result whatever `git_root` they pass, as one
+    /// invocation works on a single repository.
+    pub async fn get(&self, git_root: &Path) -> Result<Option<&LoadedConfig>> {
+        self.get_with(git_root, |_| {}).await
+    }
+
+    /// Like [`ConfigProvider::get`], passing the configuration to `on_load` when this call read it
+    ///
+    /// `on_load` runs at most once across all clones, so notes about the file are reported once.
+    pub async fn get_with(
+        &self,
+        git_root: &Path,
+        on_load: impl FnOnce(&LoadedConfig),
+    ) -> Result<Option<&LoadedConfig>> {
+        let loaded = self
+            .loaded
+            .get_or_try_init(|| async {
+                let loaded = timed(
+                    "config",
+                    load_merged_config_with(git_root, &*self.environment, self.strict),
+                )
+                .await?;
+                if let Some(loaded) = &loaded {
+                    on_load(loaded);
+                }
+                Ok::<_, crate::PhantomError>(loaded)
+            })
+            .await?;
+        Ok(loaded.as_ref())
+    }
+
+    /// The environment `${VAR}` references are expanded from
+    pub fn environment(&self) -> &dyn Environment {
+        &*self.environment
+    }
+
+    /// Whether a configuration load has completed
+    pub fn is_loaded(&self) -> bool {
+        self.loaded.initialized()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_config_is_loaded_once_on_first_request() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("phantom.config.toml"), "branchPrefix = \"one/\"\n")
+            .unwrap();
+        let provider = ConfigProvider::default();
+        assert!(!provider.is_loaded());
+
+        let first = provider.get(dir.path()).await.unwrap().unwrap();
+        assert_eq!(first.config.branch_prefix.as_deref(), Some("one/"));
+        assert!(provider.is_loaded());
+
+        // The file is not read again
+        std::fs::write(dir.path().join("phantom.config.toml"), "branchPrefix = \"two/\"\n")
+            .unwrap();
+        let shared = provider.clone();
+        let again = shared.get(dir.path()).await.unwrap().unwrap();
+        assert_eq!(again.config.branch_prefix.as_deref(), Some("one/"));
+    }
+
+    #[tokio::test]
+    async fn test_on_load_runs_once() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("phantom.config.toml"), "colour = true\n").unwrap();
+        let provider = ConfigProvider::default();
+        let mut notes = Vec::new();
+
+        provider
+            .get_with(dir.path(), |loaded| {
+                notes.extend(loaded.unknown_keys.iter().map(ToString::to_string))
+            })
+            .await
+            .unwrap();
+        provider.clone().get_with(dir.path(), |_| panic!("loaded twice")).await.unwrap();
+
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].contains("colour"), "{notes:?}");
+    }
+
+    #[tokio::test]
+    async fn test_failed_load_is_retried() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("phantom.config.toml"), "branchPrefix = [\n").unwrap();
+        let provider = ConfigProvider::default();
+
+        assert!(provider.get(dir.path()).await.is_err());
+        assert!(!provider.is_loaded());
+
+        std::fs::remove_file(dir.path().join("phantom.config.toml")).unwrap();
+        assert!(provider.get(dir.path()).await.unwrap().is_none());
+        assert!(provider.is_loaded());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::process::envfile::DEFAULT_ENV_FILE;
+use crate::worktree::file_copier::RESPECT_GITIGNORE_PREFIX;
 use serde::{Deserialize, Serialize};
 
 /// Main configuration structure for Phantom
@@ -11,6 +13,218 @@ pub struct PhantomConfig {
     /// Default terminal multiplexer to use
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_multiplexer: Option<Multiplexer>,
+
+    /// Where `shell` and `exec` open in the default multiplexer; defaults to a new window or tab
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_split: Option<AutoSplit>,
+
+    /// Treat deprecated and unknown keys as errors instead of warnings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+
+    /// Timeout in seconds for non-interactive git commands
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_timeout_secs: Option<u64>,
+
+    /// Retries for git commands that fail on lock contention
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitConfig>,
+
+    /// Editor settings to copy into new worktrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editors: Option<EditorsConfig>,
+
+    /// Directory created in new worktrees and excluded from `git status`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scratch_dir: Option<String>,
+
+    /// Repository hooks in `.phantom/hooks/`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<HooksConfig>,
+
+    /// Prefix for the branches of new worktrees, such as `user/{user}/`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch_prefix: Option<String>,
+
+    /// Interactive fzf selection settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fzf: Option<FzfConfig>,
+
+    /// Shell for `phantom shell`, used instead of `$SHELL`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+
+    /// `phantom delete` settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<DeleteConfig>,
+
+    /// Show the worktree name in the terminal title during `shell` and `exec`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terminal_title: Option<bool>,
+
+    /// `phantom list` settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list: Option<ListConfig>,
+
+    /// Env file at the worktree root loaded by `shell` and `exec`; defaults to `.phantom.env`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<String>,
+
+    /// `phantom create` settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create: Option<CreateConfig>,
+
+    /// `phantom attach` settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach: Option<AttachConfig>,
+}
+
+/// Repository hooks configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HooksConfig {
+    /// Run the hooks in `.phantom/hooks/`; defaults to true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// Git command configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConfig {
+    /// Times to retry a command that hit another process's lock; defaults to 3
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+
+    /// Delay before the first retry, doubled for each further one; defaults to 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_delay_ms: Option<u64>,
+}
+
+/// Interactive fzf selection configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FzfConfig {
+    /// Show the worktree preview pane; defaults to true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<bool>,
+}
+
+/// `phantom delete` configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteConfig {
+    /// Delete the branch along with its worktree; defaults to true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove_branch: Option<bool>,
+
+    /// Where a forced delete saves uncommitted changes; defaults to none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<DeleteSnapshot>,
+}
+
+/// `phantom create` configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateConfig {
+    /// Directories new worktrees check out, as a cone-mode sparse checkout; defaults to everything
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sparse_patterns: Option<Vec<String>>,
+}
+
+/// `phantom attach` configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachConfig {
+    /// Track `<remote>/<branch>` when the attached branch has no upstream; defaults to true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_upstream: Option<bool>,
+}
+
+/// `phantom list` configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListConfig {
+    /// Always ask the remote which upstream branches still exist; defaults to false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_remote: Option<bool>,
+}
+
+impl PhantomConfig {
+    /// Whether repository hooks should run
+    pub fn hooks_enabled(&self) -> bool {
+        self.hooks.as_ref().and_then(|hooks| hooks.enabled).unwrap_or(true)
+    }
+
+    /// Whether fzf selection shows the built-in worktree preview
+    pub fn fzf_preview_enabled(&self) -> bool {
+        self.fzf.as_ref().and_then(|fzf| fzf.preview).unwrap_or(true)
+    }
+
+    /// Whether `phantom delete` removes the worktree's branch
+    pub fn delete_removes_branch(&self) -> bool {
+        self.delete.as_ref().and_then(|delete| delete.remove_branch).unwrap_or(true)
+    }
+
+    /// Where a forced delete saves uncommitted changes
+    pub fn delete_snapshot(&self) -> DeleteSnapshot {
+        self.delete.as_ref().and_then(|delete| delete.snapshot).unwrap_or_default()
+    }
+
+    /// Whether `shell` and `exec` set the terminal title
+    pub fn terminal_title_enabled(&self) -> bool {
+        self.terminal_title.unwrap_or(true)
+    }
+
+    /// Name of the env file `shell` and `exec` load from the worktree root
+    pub fn env_file(&self) -> &str {
+        self.env_file.as_deref().unwrap_or(DEFAULT_ENV_FILE)
+    }
+
+    /// Directories new worktrees check out, or `None` to check out everything
+    pub fn create_sparse_patterns(&self) -> Option<&[String]> {
+        self.create.as_ref().and_then(|create| create.sparse_patterns.as_deref())
+    }
+
+    /// Whether `phantom list` asks the remote for deleted upstream branches
+    pub fn list_checks_remote(&self) -> bool {
+        self.list.as_ref().and_then(|list| list.check_remote).unwrap_or(false)
+    }
+
+    /// Whether `phantom attach` sets the upstream of branches that exist on the remote
+    pub fn attach_sets_upstream(&self) -> bool {
+        self.attach.as_ref().and_then(|attach| attach.set_upstream).unwrap_or(true)
+    }
+}
+
+/// Editor settings configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorsConfig {
+    /// Copy `.vscode/` from the original worktree
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copy_vscode: Option<bool>,
+
+    /// Further editor directories to copy, such as `.idea`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dirs: Option<Vec<String>>,
+}
+
+impl EditorsConfig {
+    /// Editor directories to copy, in configuration order
+    pub fn directories(&self) -> Vec<String> {
+        let mut dirs = Vec::new();
+        if self.copy_vscode == Some(true) {
+            dirs.push(".vscode".to_string());
+        }
+        for dir in self.dirs.iter().flatten() {
+            let dir = dir.trim_end_matches('/').to_string();
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        dirs
+    }
 }
 
 /// Post-create configuration
@@ -24,6 +238,29 @@ pub struct PostCreateConfig {
     /// Commands to run after creating the worktree
     #[serde(skip_serializing_if = "Option::is_none")]
     pub commands: Option<Vec<String>>,
+
+    /// Skip files git ignores inside copied directories and glob matches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copy_files_respect_gitignore: Option<bool>,
+}
+
+impl PostCreateConfig {
+    /// The copyFiles entries, each marked to respect gitignore when the toggle is on
+    pub fn copy_file_entries(&self) -> Option<Vec<String>> {
+        let respect = self.copy_files_respect_gitignore == Some(true);
+        self.copy_files.as_ref().map(|files| {
+            files
+                .iter()
+                .map(|file| {
+                    if respect && !file.starts_with(RESPECT_GITIGNORE_PREFIX) {
+                        format!("{RESPECT_GITIGNORE_PREFIX}{file}")
+                    } else {
+                        file.clone()
+                    }
+                })
+                .collect()
+        })
+    }
 }
 
 /// Supported terminal multiplexers
@@ -36,6 +273,29 @@ pub enum Multiplexer {
     None,
 }
 
+/// Where the default multiplexer opens `shell` and `exec`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoSplit {
+    /// A new tmux window or kitty tab
+    #[default]
+    New,
+    Vertical,
+    Horizontal,
+}
+
+/// How a forced delete keeps the uncommitted changes it would otherwise discard
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeleteSnapshot {
+    /// `git stash push --include-untracked`, kept in the shared object store
+    Stash,
+    /// A patch file under `.git/phantom/trash/`
+    Patch,
+    #[default]
+    None,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,8 +306,25 @@ mod tests {
             post_create: Some(PostCreateConfig {
                 copy_files: Some(vec![".env".to_string(), "config.local.json".to_string()]),
                 commands: Some(vec!["npm install".to_string()]),
+                copy_files_respect_gitignore: None,
             }),
             default_multiplexer: Some(Multiplexer::Tmux),
+            auto_split: None,
+            strict: None,
+            git_timeout_secs: None,
+            git: None,
+            editors: None,
+            scratch_dir: None,
+            hooks: None,
+            branch_prefix: None,
+            fzf: None,
+            shell: None,
+            delete: None,
+            terminal_title: None,
+            list: None,
+            env_file: None,
+            create: None,
+            attach: None,
         };
 
         let json = serde_json::to_string_pretty(&config).unwrap();
@@ -58,6 +335,65 @@ mod tests {
         assert!(json.contains("\"tmux\""));
     }
 
+    #[test]
+    fn test_deserialize_editors_and_scratch_dir() {
+        let config: PhantomConfig = serde_json::from_str(
+            r#"{"editors": {"copyVscode": true, "dirs": [".idea/", ".vscode"]}, "scratchDir": ".scratch"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.editors.unwrap().directories(), vec![".vscode", ".idea"]);
+        assert_eq!(config.scratch_dir.as_deref(), Some(".scratch"));
+    }
+
+    #[test]
+    fn test_hooks_enabled_by_default() {
+        assert!(PhantomConfig::default().hooks_enabled());
+        let config: PhantomConfig =
+            serde_json::from_str(r#"{"hooks": {"enabled": false}}"#).unwrap();
+        assert!(!config.hooks_enabled());
+    }
+
+    #[test]
+    fn test_fzf_preview_enabled_by_default() {
+        assert!(PhantomConfig::default().fzf_preview_enabled());
+        let config: PhantomConfig = serde_json::from_str(r#"{"fzf": {"preview": false}}"#).unwrap();
+        assert!(!config.fzf_preview_enabled());
+    }
+
+    #[test]
+    fn test_delete_removes_branch_by_default() {
+        assert!(PhantomConfig::default().delete_removes_branch());
+        let config: PhantomConfig =
+            serde_json::from_str(r#"{"delete": {"removeBranch": false}}"#).unwrap();
+        assert!(!config.delete_removes_branch());
+    }
+
+    #[test]
+    fn test_delete_snapshot_defaults_to_none() {
+        assert_eq!(PhantomConfig::default().delete_snapshot(), DeleteSnapshot::None);
+        let config: PhantomConfig =
+            serde_json::from_str(r#"{"delete": {"snapshot": "stash"}}"#).unwrap();
+        assert_eq!(config.delete_snapshot(), DeleteSnapshot::Stash);
+        assert!(
+            serde_json::from_str::<PhantomConfig>(r#"{"delete": {"snapshot": "zip"}}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn test_deserialize_git_timeout() {
+        let config: PhantomConfig = serde_json::from_str(r#"{"gitTimeoutSecs": 120}"#).unwrap();
+        assert_eq!(config.git_timeout_secs, Some(120));
+    }
+
+    #[test]
+    fn test_deserialize_git_retries() {
+        let config: PhantomConfig =
+            serde_json::from_str(r#"{"git": {"retries": 5, "retryDelayMs": 250}}"#).unwrap();
+        let git = config.git.unwrap();
+        assert_eq!(git.retries, Some(5));
+        assert_eq!(git.retry_delay_ms, Some(250));
+    }
+
     #[test]
     fn test_deserialize_config() {
         let json = r#"{
@@ -101,4 +437,20 @@ mod tests {
         let json = serde_json::to_string(&config).unwrap();
         assert_eq!(json, "{}");
     }
+
+    #[test]
+    fn test_copy_file_entries_marks_entries_when_respecting_gitignore() {
+        let mut post_create = PostCreateConfig {
+            copy_files: Some(vec!["certs".to_string(), "!.env*".to_string()]),
+            commands: None,
+            copy_files_respect_gitignore: None,
+        };
+        assert_eq!(post_create.copy_file_entries(), post_create.copy_files);
+
+        post_create.copy_files_respect_gitignore = Some(true);
+        assert_eq!(
+            post_create.copy_file_entries(),
+            Some(vec!["!certs".to_string(), "!.env*".to_string()])
+        );
+    }
 }
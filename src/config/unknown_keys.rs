@@ -0,0 +1,224 @@
+use crate::core::utils::edit_distance;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// Every key `PhantomConfig` reads, as dotted paths
+pub const CONFIG_KEYS: &[&str] = &[
+    "postCreate",
+    "postCreate.copyFiles",
+    "postCreate.commands",
+    "postCreate.copyFilesRespectGitignore",
+    "defaultMultiplexer",
+    "autoSplit",
+    "strict",
+    "gitTimeoutSecs",
+    "git",
+    "git.retries",
+    "git.retryDelayMs",
+    "editors",
+    "editors.copyVscode",
+    "editors.dirs",
+    "scratchDir",
+    "hooks",
+    "hooks.enabled",
+    "branchPrefix",
+    "fzf",
+    "fzf.preview",
+    "shell",
+    "delete",
+    "delete.removeBranch",
+    "delete.snapshot",
+    "terminalTitle",
+    "list",
+    "list.checkRemote",
+    "envFile",
+    "create",
+    "create.sparsePatterns",
+    "attach",
+    "attach.setUpstream",
+];
+
+/// Most edits between an unknown key and a known one for it to be suggested
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// A key the configuration does not read, which serde would otherwise drop silently
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnknownKeyNote {
+    /// Dotted path of the key, such as `postCreate.command`
+    pub key: String,
+    /// The known key in the same table closest to it
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for UnknownKeyNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.suggestion {
+            Some(suggestion) => {
+                write!(f, "unknown key '{}', did you mean '{suggestion}'?", self.key)
+            }
+            None => write!(f, "unknown key '{}'", self.key),
+        }
+    }
+}
+
+/// Keys of a raw configuration value that are not in `known`, in table order
+///
+/// The keys of an unknown table are not reported on their own, and neither
+/// are those of a known key whose value is not a table, which fails to
+/// deserialize anyway.
+pub fn find_unknown_keys(value: &Value, known: &[&str]) -> Vec<UnknownKeyNote> {
+    let mut notes = Vec::new();
+    collect(value, known, "", &mut notes);
+    notes
+}
+
+fn collect(value: &Value, known: &[&str], prefix: &str, notes: &mut Vec<UnknownKeyNote>) {
+    let Value::Object(map) = value else {
+        return;
+    };
+    for (key, child) in map {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+        if known.contains(&path.as_str()) {
+            collect(child, known, &path, notes);
+        } else {
+            let suggestion = suggest(key, siblings(known, prefix)).map(|name| {
+                if prefix.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{prefix}.{name}")
+                }
+            });
+            notes.push(UnknownKeyNote { key: path, suggestion });
+        }
+    }
+}
+
+/// Names of the known keys directly inside the table at `prefix`
+fn siblings<'a>(known: &'a [&'a str], prefix: &'a str) -> impl Iterator<Item = &'a str> {
+    known.iter().filter_map(move |path| {
+        let name =
+            if prefix.is_empty() { *path } else { path.strip_prefix(prefix)?.strip_prefix('.')? };
+        (!name.contains('.')).then_some(name)
+    })
+}
+
+/// The candidate closest to `key`, ignoring case, `_` and `-`, if it is close enough
+fn suggest<'a>(key: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let key = normalize(key);
+    candidates
+        .map(|candidate| (edit_distance(&key, &normalize(candidate)), candidate))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// `post_create`, `Post-Create` and `postCreate` are all written `postcreate`
+fn normalize(key: &str) -> String {
+    key.chars().filter(|c| !matches!(c, '_' | '-')).flat_map(char::to_lowercase).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::{
+        AttachConfig, AutoSplit, CreateConfig, DeleteConfig, DeleteSnapshot, EditorsConfig,
+        FzfConfig, GitConfig, HooksConfig, ListConfig, Multiplexer, PhantomConfig,
+        PostCreateConfig,
+    };
+    use serde_json::json;
+
+    fn dotted_keys(value: &Value, prefix: &str, keys: &mut Vec<String>) {
+        if let Value::Object(map) = value {
+            for (key, child) in map {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+                dotted_keys(child, &path, keys);
+                keys.push(path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_config_keys_match_the_config_types() {
+        // Written out in full, so a new field fails to compile until it is listed here
+        let config = PhantomConfig {
+            post_create: Some(PostCreateConfig {
+                copy_files: Some(vec![]),
+                commands: Some(vec![]),
+                copy_files_respect_gitignore: Some(true),
+            }),
+            default_multiplexer: Some(Multiplexer::Tmux),
+            auto_split: Some(AutoSplit::New),
+            strict: Some(true),
+            git_timeout_secs: Some(1),
+            git: Some(GitConfig { retries: Some(1), retry_delay_ms: Some(1) }),
+            editors: Some(EditorsConfig { copy_vscode: Some(true), dirs: Some(vec![]) }),
+            scratch_dir: Some(String::new()),
+            hooks: Some(HooksConfig { enabled: Some(true) }),
+            branch_prefix: Some(String::new()),
+            fzf: Some(FzfConfig { preview: Some(true) }),
+            shell: Some(String::new()),
+            delete: Some(DeleteConfig {
+                remove_branch: Some(true),
+                snapshot: Some(DeleteSnapshot::Stash),
+            }),
+            terminal_title: Some(true),
+            list: Some(ListConfig { check_remote: Some(true) }),
+            env_file: Some(String::new()),
+            create: Some(CreateConfig { sparse_patterns: Some(vec![]) }),
+            attach: Some(AttachConfig { set_upstream: Some(true) }),
+        };
+
+        let mut keys = Vec::new();
+        dotted_keys(&serde_json::to_value(config).unwrap(), "", &mut keys);
+        keys.sort();
+        let mut known: Vec<String> = CONFIG_KEYS.iter().map(|key| key.to_string()).collect();
+        known.sort();
+        assert_eq!(keys, known);
+    }
+
+    #[test]
+    fn test_find_unknown_keys() {
+        let value = json!({
+            "post_create": {"commands": ["make"]},
+            "postCreate": {"command": ["make"], "copyFiles": [".env"]},
+            "scratchDir": ".scratch",
+            "editors": "not a table",
+            "completelyDifferent": {"nested": true},
+        });
+
+        let notes = find_unknown_keys(&value, CONFIG_KEYS);
+        let found: Vec<_> =
+            notes.iter().map(|n| (n.key.as_str(), n.suggestion.as_deref())).collect();
+        assert_eq!(
+            found,
+            [
+                ("completelyDifferent", None),
+                ("postCreate.command", Some("postCreate.commands")),
+                ("post_create", Some("postCreate")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suggest() {
+        let candidates = ["copyVscode", "dirs"];
+        assert_eq!(suggest("copyVSCode", candidates.into_iter()), Some("copyVscode"));
+        assert_eq!(suggest("dir", candidates.into_iter()), Some("dirs"));
+        assert_eq!(suggest("copy_vscode", candidates.into_iter()), Some("copyVscode"));
+        assert_eq!(suggest("DIRS", candidates.into_iter()), Some("dirs"));
+        assert_eq!(suggest("directories", candidates.into_iter()), None);
+    }
+
+    #[test]
+    fn test_unknown_key_note_display() {
+        let note = UnknownKeyNote {
+            key: "postCreat".to_string(),
+            suggestion: Some("postCreate".to_string()),
+        };
+        assert_eq!(note.to_string(), "unknown key 'postCreat', did you mean 'postCreate'?");
+
+        let note = UnknownKeyNote { suggestion: None, ..note };
+        assert_eq!(note.to_string(), "unknown key 'postCreat'");
+    }
+}
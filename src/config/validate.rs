@@ -1,6 +1,9 @@
 use crate::config::errors::ConfigError;
 use crate::config::types::{Multiplexer, PhantomConfig, PostCreateConfig};
 use crate::core::const_utils::{const_starts_with, is_valid_path_component};
+use crate::worktree::branch_prefix::expand_branch_prefix;
+use crate::worktree::file_copier::RESPECT_GITIGNORE_PREFIX;
+use crate::worktree::validate::validate_branch_prefix;
 use crate::Result;
 
 /// Validate a PhantomConfig instance
@@ -15,6 +18,51 @@ pub fn validate_config(config: &PhantomConfig) -> Result<()> {
         validate_multiplexer(multiplexer)?;
     }
 
+    if let Some(dirs) = config.editors.as_ref().and_then(|editors| editors.dirs.as_ref()) {
+        for dir in dirs {
+            validate_relative_dir("editors.dirs", dir)?;
+        }
+    }
+
+    if let Some(ref scratch_dir) = config.scratch_dir {
+        validate_relative_dir("scratchDir", scratch_dir)?;
+    }
+
+    if let Some(ref env_file) = config.env_file {
+        validate_relative_dir("envFile", env_file)?;
+    }
+
+    if let Some(patterns) = config.create_sparse_patterns() {
+        if patterns.is_empty() {
+            return Err(ConfigError::ValidationError(
+                "create.sparsePatterns cannot be empty; leave it out to check out everything"
+                    .to_string(),
+            )
+            .into());
+        }
+        for pattern in patterns {
+            validate_relative_dir("create.sparsePatterns", pattern)?;
+        }
+    }
+
+    if let Some(ref branch_prefix) = config.branch_prefix {
+        // Check the shape with a placeholder user; the real one is only known at create time
+        expand_branch_prefix(branch_prefix, Some("user"))
+            .and_then(|prefix| validate_branch_prefix(&prefix))
+            .map_err(|e| ConfigError::ValidationError(format!("branchPrefix: {e}")))?;
+    }
+
+    if config.git_timeout_secs == Some(0) {
+        return Err(ConfigError::ValidationError(
+            "gitTimeoutSecs must be greater than 0".to_string(),
+        )
+        .into());
+    }
+
+    if config.shell.as_deref().is_some_and(|shell| shell.trim().is_empty()) {
+        return Err(ConfigError::ValidationError("shell cannot be empty".to_string()).into());
+    }
+
     Ok(())
 }
 
@@ -23,6 +71,7 @@ fn validate_post_create(post_create: &PostCreateConfig) -> Result<()> {
     // Validate copy_files
     if let Some(ref copy_files) = post_create.copy_files {
         for file in copy_files {
+            let file = file.strip_prefix(RESPECT_GITIGNORE_PREFIX).unwrap_or(file);
             if file.trim().is_empty() {
                 return Err(ConfigError::ValidationError(
                     "postCreate.copyFiles cannot contain empty strings".to_string(),
@@ -66,6 +115,27 @@ fn validate_post_create(post_create: &PostCreateConfig) -> Result<()> {
     Ok(())
 }
 
+/// Validate a directory that must stay inside the worktree
+fn validate_relative_dir(key: &str, dir: &str) -> Result<()> {
+    let dir = dir.trim_end_matches('/');
+    if dir.trim().is_empty() {
+        return Err(ConfigError::ValidationError(format!("{key} cannot be empty")).into());
+    }
+    if const_starts_with(dir, "/") || const_starts_with(dir, "\\") {
+        return Err(ConfigError::ValidationError(format!(
+            "{key} cannot contain absolute paths: {dir}"
+        ))
+        .into());
+    }
+    if !dir.split('/').all(is_valid_path_component) {
+        return Err(ConfigError::ValidationError(format!(
+            "{key} cannot contain invalid path components: {dir}"
+        ))
+        .into());
+    }
+    Ok(())
+}
+
 /// Validate multiplexer configuration
 fn validate_multiplexer(_multiplexer: &Multiplexer) -> Result<()> {
     // Multiplexer enum values are already constrained by the type system
@@ -76,6 +146,7 @@ fn validate_multiplexer(_multiplexer: &Multiplexer) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::types::EditorsConfig;
 
     #[test]
     fn test_validate_valid_config() {
@@ -83,8 +154,25 @@ mod tests {
             post_create: Some(PostCreateConfig {
                 copy_files: Some(vec![".env".to_string(), "config.json".to_string()]),
                 commands: Some(vec!["npm install".to_string()]),
+                copy_files_respect_gitignore: None,
             }),
             default_multiplexer: Some(Multiplexer::Tmux),
+            auto_split: None,
+            strict: None,
+            git_timeout_secs: None,
+            git: None,
+            editors: None,
+            scratch_dir: None,
+            hooks: None,
+            branch_prefix: None,
+            fzf: None,
+            shell: None,
+            delete: None,
+            terminal_title: None,
+            list: None,
+            env_file: None,
+            create: None,
+            attach: None,
         };
 
         assert!(validate_config(&config).is_ok());
@@ -102,8 +190,25 @@ mod tests {
             post_create: Some(PostCreateConfig {
                 copy_files: Some(vec!["".to_string()]),
                 commands: None,
+                copy_files_respect_gitignore: None,
             }),
             default_multiplexer: None,
+            auto_split: None,
+            strict: None,
+            git_timeout_secs: None,
+            git: None,
+            editors: None,
+            scratch_dir: None,
+            hooks: None,
+            branch_prefix: None,
+            fzf: None,
+            shell: None,
+            delete: None,
+            terminal_title: None,
+            list: None,
+            env_file: None,
+            create: None,
+            attach: None,
         };
 
         let result = validate_config(&config);
@@ -117,13 +222,35 @@ mod tests {
             post_create: Some(PostCreateConfig {
                 copy_files: Some(vec!["/etc/passwd".to_string()]),
                 commands: None,
+                copy_files_respect_gitignore: None,
             }),
             default_multiplexer: None,
+            auto_split: None,
+            strict: None,
+            git_timeout_secs: None,
+            git: None,
+            editors: None,
+            scratch_dir: None,
+            hooks: None,
+            branch_prefix: None,
+            fzf: None,
+            shell: None,
+            delete: None,
+            terminal_title: None,
+            list: None,
+            env_file: None,
+            create: None,
+            attach: None,
         };
 
         let result = validate_config(&config);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("cannot contain absolute paths"));
+
+        // The respect-gitignore marker does not hide an absolute path
+        let mut config = config;
+        config.post_create.as_mut().unwrap().copy_files = Some(vec!["!/etc".to_string()]);
+        assert!(validate_config(&config).is_err());
     }
 
     #[test]
@@ -132,8 +259,25 @@ mod tests {
             post_create: Some(PostCreateConfig {
                 copy_files: Some(vec!["../secret.txt".to_string()]),
                 commands: None,
+                copy_files_respect_gitignore: None,
             }),
             default_multiplexer: None,
+            auto_split: None,
+            strict: None,
+            git_timeout_secs: None,
+            git: None,
+            editors: None,
+            scratch_dir: None,
+            hooks: None,
+            branch_prefix: None,
+            fzf: None,
+            shell: None,
+            delete: None,
+            terminal_title: None,
+            list: None,
+            env_file: None,
+            create: None,
+            attach: None,
         };
 
         let result = validate_config(&config);
@@ -147,8 +291,25 @@ mod tests {
             post_create: Some(PostCreateConfig {
                 copy_files: None,
                 commands: Some(vec!["   ".to_string()]),
+                copy_files_respect_gitignore: None,
             }),
             default_multiplexer: None,
+            auto_split: None,
+            strict: None,
+            git_timeout_secs: None,
+            git: None,
+            editors: None,
+            scratch_dir: None,
+            hooks: None,
+            branch_prefix: None,
+            fzf: None,
+            shell: None,
+            delete: None,
+            terminal_title: None,
+            list: None,
+            env_file: None,
+            create: None,
+            attach: None,
         };
 
         let result = validate_config(&config);
@@ -156,11 +317,92 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("cannot contain empty strings"));
     }
 
+    #[test]
+    fn test_validate_zero_git_timeout() {
+        let config = PhantomConfig { git_timeout_secs: Some(0), ..Default::default() };
+
+        let result = validate_config(&config);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("gitTimeoutSecs"));
+
+        let config = PhantomConfig { git_timeout_secs: Some(90), ..Default::default() };
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_branch_prefix() {
+        for prefix in ["feature/", "user/{user}/", "wip-"] {
+            let config =
+                PhantomConfig { branch_prefix: Some(prefix.to_string()), ..Default::default() };
+            assert!(validate_config(&config).is_ok(), "{prefix}");
+        }
+
+        for prefix in ["bad..prefix/", "{team}/", "-x/", "a b/"] {
+            let config =
+                PhantomConfig { branch_prefix: Some(prefix.to_string()), ..Default::default() };
+            let error = validate_config(&config).unwrap_err().to_string();
+            assert!(error.contains("branchPrefix"), "{prefix}: {error}");
+        }
+    }
+
+    #[test]
+    fn test_validate_shell() {
+        let config = PhantomConfig { shell: Some("/bin/zsh".to_string()), ..Default::default() };
+        assert!(validate_config(&config).is_ok());
+
+        let config = PhantomConfig { shell: Some("  ".to_string()), ..Default::default() };
+        assert!(validate_config(&config)
+            .unwrap_err()
+            .to_string()
+            .contains("shell cannot be empty"));
+    }
+
+    #[test]
+    fn test_validate_scratch_dir_and_editor_dirs() {
+        let config =
+            PhantomConfig { scratch_dir: Some(".scratch".to_string()), ..Default::default() };
+        assert!(validate_config(&config).is_ok());
+
+        let config =
+            PhantomConfig { scratch_dir: Some("../out".to_string()), ..Default::default() };
+        assert!(validate_config(&config).unwrap_err().to_string().contains("scratchDir"));
+
+        let config = PhantomConfig { env_file: Some("/etc/env".to_string()), ..Default::default() };
+        assert!(validate_config(&config).unwrap_err().to_string().contains("envFile"));
+
+        let config = PhantomConfig {
+            editors: Some(EditorsConfig {
+                copy_vscode: None,
+                dirs: Some(vec!["/etc".to_string()]),
+            }),
+            ..Default::default()
+        };
+        assert!(validate_config(&config).unwrap_err().to_string().contains("editors.dirs"));
+    }
+
     #[test]
     fn test_validate_all_multiplexers() {
         for multiplexer in [Multiplexer::Tmux, Multiplexer::Kitty, Multiplexer::None] {
-            let config =
-                PhantomConfig { post_create: None, default_multiplexer: Some(multiplexer) };
+            let config = PhantomConfig {
+                post_create: None,
+                default_multiplexer: Some(multiplexer),
+                auto_split: None,
+                strict: None,
+                git_timeout_secs: None,
+                git: None,
+                editors: None,
+                scratch_dir: None,
+                hooks: None,
+                branch_prefix: None,
+                fzf: None,
+                shell: None,
+                delete: None,
+                terminal_title: None,
+                list: None,
+                env_file: None,
+                create: None,
+                attach: None,
+            };
             assert!(validate_config(&config).is_ok());
         }
     }
@@ -1,25 +1,58 @@
 use async_trait::async_trait;
 use smallvec::SmallVec;
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::future::Future;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
 
+use crate::core::env_map::EnvMap;
 use crate::core::result::Result;
 use crate::core::sealed::Sealed;
+use crate::git::git_executor_adapter::GitSettings;
 
 /// Type alias for command arguments using SmallVec
 /// Most git commands use 2-4 arguments, so we optimize for 4 inline elements
 pub type CommandArgs = SmallVec<[String; 4]>;
 
+/// Where a command reads its stdin from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum StdinMode {
+    /// Share phantom's stdin, whether a terminal or a pipe
+    Inherit,
+    /// Read nothing
+    #[default]
+    Null,
+    /// Read this data, then end of file
+    Data(String),
+}
+
+impl StdinMode {
+    pub(crate) fn stdio(&self) -> Stdio {
+        match self {
+            StdinMode::Inherit => Stdio::inherit(),
+            StdinMode::Null => Stdio::null(),
+            StdinMode::Data(_) => Stdio::piped(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CommandConfig {
     pub program: String,
     pub args: CommandArgs,
     pub cwd: Option<PathBuf>,
-    pub env: Option<HashMap<String, String>>,
+    pub env: Option<EnvMap>,
+    /// Inherited environment variables to drop before `env` is applied
+    pub env_remove: Vec<String>,
     pub timeout: Option<Duration>,
-    pub stdin_data: Option<String>,
+    pub stdin: StdinMode,
+    /// Send the child's output to phantom's stderr as it is written instead of capturing it
+    pub stream_output: bool,
+    /// Connect the child's stdout and stderr to phantom's own instead of capturing them
+    pub inherit_output: bool,
 }
 
 impl CommandConfig {
@@ -29,8 +62,11 @@ impl CommandConfig {
             args: SmallVec::new(),
             cwd: None,
             env: None,
+            env_remove: Vec::new(),
             timeout: None,
-            stdin_data: None,
+            stdin: StdinMode::Null,
+            stream_output: false,
+            inherit_output: false,
         }
     }
 
@@ -49,8 +85,14 @@ impl CommandConfig {
         self
     }
 
-    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
-        self.env = Some(env);
+    pub fn with_env(mut self, env: impl Into<EnvMap>) -> Self {
+        self.env = Some(env.into());
+        self
+    }
+
+    /// Keep `key` out of the child's environment even if phantom inherited it
+    pub fn without_env(mut self, key: impl Into<String>) -> Self {
+        self.env_remove.push(key.into());
         self
     }
 
@@ -59,17 +101,47 @@ impl CommandConfig {
         self
     }
 
-    pub fn with_stdin_data(mut self, stdin_data: String) -> Self {
-        self.stdin_data = Some(stdin_data);
+    pub fn with_stdin(mut self, stdin: StdinMode) -> Self {
+        self.stdin = stdin;
+        self
+    }
+
+    /// Stream stdout and stderr to phantom's stderr, leaving `CommandOutput` empty
+    ///
+    /// Phantom's own stdout stays clean for `--json` and `$(phantom ...)` users.
+    pub fn with_streamed_output(mut self) -> Self {
+        self.stream_output = true;
+        self
+    }
+
+    /// Let the child write straight to phantom's stdout and stderr, leaving `CommandOutput` empty
+    pub fn with_inherited_output(mut self) -> Self {
+        self.inherit_output = true;
         self
     }
 }
 
+/// Which of a command's output streams a line was written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Called with each line a command writes, without its line ending
+pub type OnOutputLine<'a> = dyn for<'line> Fn(OutputStream, &'line str) + Send + Sync + 'a;
+
 #[derive(Debug, Clone)]
 pub struct CommandOutput {
     pub stdout: Cow<'static, str>,
     pub stderr: Cow<'static, str>,
     pub exit_code: i32,
+    /// Program that ran; empty unless the executor recorded it
+    pub program: String,
+    /// Arguments the program ran with
+    pub args: CommandArgs,
+    /// Time from starting the program until it exited
+    pub duration: Duration,
 }
 
 impl CommandOutput {
@@ -79,12 +151,36 @@ impl CommandOutput {
 
     /// Create a new CommandOutput with owned strings (default)
     pub fn new(stdout: String, stderr: String, exit_code: i32) -> Self {
-        Self { stdout: Cow::Owned(stdout), stderr: Cow::Owned(stderr), exit_code }
+        Self::from_cows(Cow::Owned(stdout), Cow::Owned(stderr), exit_code)
     }
 
     /// Create from static string references (zero-copy)
     pub fn from_static(stdout: &'static str, stderr: &'static str, exit_code: i32) -> Self {
-        Self { stdout: Cow::Borrowed(stdout), stderr: Cow::Borrowed(stderr), exit_code }
+        Self::from_cows(Cow::Borrowed(stdout), Cow::Borrowed(stderr), exit_code)
+    }
+
+    fn from_cows(stdout: Cow<'static, str>, stderr: Cow<'static, str>, exit_code: i32) -> Self {
+        Self {
+            stdout,
+            stderr,
+            exit_code,
+            program: String::new(),
+            args: SmallVec::new(),
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// Record the command that produced this output and how long it ran
+    pub fn with_command(mut self, program: String, args: CommandArgs, duration: Duration) -> Self {
+        self.program = program;
+        self.args = args;
+        self.duration = duration;
+        self
+    }
+
+    /// The program and its arguments as one line, such as `git worktree list`
+    pub fn command_line(&self) -> String {
+        command_line(&self.program, &self.args)
     }
 
     /// Get stdout as &str without allocation
@@ -103,10 +199,108 @@ impl CommandOutput {
     }
 }
 
+/// The exit code a shell would report for `status`: the child's own, or 128 plus the
+/// number of the signal that killed it
+pub fn exit_code_of(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(-1)
+}
+
+/// Tracing target of the one event logged per command, which `--trace-commands` shows
+pub const COMMAND_TRACE_TARGET: &str = "phantom::commands";
+
+static TRACE_COMMANDS: AtomicBool = AtomicBool::new(false);
+
+/// Log the per-command events at info instead of debug level (`--trace-commands`)
+pub fn set_trace_commands(enabled: bool) {
+    TRACE_COMMANDS.store(enabled, Ordering::Relaxed);
+}
+
+fn command_line(program: &str, args: &[String]) -> String {
+    std::iter::once(program).chain(args.iter().map(String::as_str)).collect::<Vec<_>>().join(" ")
+}
+
+/// Run `execution`, fill in the argv and duration of its output and log one event for it
+pub(crate) async fn traced_execution(
+    program: String,
+    args: CommandArgs,
+    execution: impl Future<Output = Result<CommandOutput>>,
+) -> Result<CommandOutput> {
+    let started = Instant::now();
+    let result = execution.await;
+    let duration = started.elapsed();
+    let line = command_line(&program, &args);
+    let duration_ms = duration.as_millis() as u64;
+    match &result {
+        Ok(output) if TRACE_COMMANDS.load(Ordering::Relaxed) => info!(
+            target: COMMAND_TRACE_TARGET,
+            exit_code = output.exit_code,
+            duration_ms,
+            stdout_bytes = output.stdout.len(),
+            stderr_bytes = output.stderr.len(),
+            "{line} exited {} in {duration_ms}ms",
+            output.exit_code
+        ),
+        Ok(output) => debug!(
+            target: COMMAND_TRACE_TARGET,
+            exit_code = output.exit_code,
+            duration_ms,
+            stdout_bytes = output.stdout.len(),
+            stderr_bytes = output.stderr.len(),
+            "{line} exited {} in {duration_ms}ms",
+            output.exit_code
+        ),
+        Err(e) if TRACE_COMMANDS.load(Ordering::Relaxed) => {
+            info!(target: COMMAND_TRACE_TARGET, duration_ms, "{line} failed in {duration_ms}ms: {e}")
+        }
+        Err(e) => {
+            debug!(target: COMMAND_TRACE_TARGET, duration_ms, "{line} failed in {duration_ms}ms: {e}")
+        }
+    }
+    result.map(|output| output.with_command(program, args, duration))
+}
+
 /// Trait for executing system commands
 ///
 /// This trait is sealed to prevent downstream implementations
 #[async_trait]
 pub trait CommandExecutor: Sealed + Send + Sync {
     async fn execute(&self, config: CommandConfig) -> Result<CommandOutput>;
+
+    /// Execute a command that takes over the terminal, such as fzf
+    ///
+    /// Implementations may run the child in its own process group so that
+    /// suspending or killing phantom also suspends or terminates the child.
+    async fn execute_interactive(&self, config: CommandConfig) -> Result<CommandOutput> {
+        self.execute(config).await
+    }
+
+    /// Execute a command, passing each line of its output to `on_line` as it is written
+    ///
+    /// Lines are given without their line ending, and the returned output still
+    /// holds everything the command wrote. The default runs `execute` and replays
+    /// the captured stdout, then stderr, line by line.
+    async fn execute_streaming(
+        &self,
+        config: CommandConfig,
+        on_line: &OnOutputLine<'_>,
+    ) -> Result<CommandOutput> {
+        let output = self.execute(config).await?;
+        output.stdout.lines().for_each(|line| on_line(OutputStream::Stdout, line));
+        output.stderr.lines().for_each(|line| on_line(OutputStream::Stderr, line));
+        Ok(output)
+    }
+
+    /// Settings for the git commands a [`GitExecutor`] runs through this executor
+    ///
+    /// [`GitExecutor`]: crate::git::git_executor_adapter::GitExecutor
+    fn git_settings(&self) -> GitSettings {
+        GitSettings::default()
+    }
 }
@@ -56,6 +56,11 @@ pub const fn const_starts_with(s: &str, prefix: &str) -> bool {
 /// Environment variable names as constants
 pub mod env_vars {
     pub const SHELL: &str = "SHELL";
+    pub const PHANTOM_SHELL: &str = "PHANTOM_SHELL";
+    pub const COMSPEC: &str = "COMSPEC";
+    pub const PS_MODULE_PATH: &str = "PSModulePath";
+    pub const GIT_DIR: &str = "GIT_DIR";
+    pub const GIT_WORK_TREE: &str = "GIT_WORK_TREE";
 }
 
 /// Common directory names
@@ -71,6 +76,8 @@ mod tests {
     fn test_constants() {
         // Verify key constants
         assert_eq!(env_vars::SHELL, "SHELL");
+        assert_eq!(env_vars::PHANTOM_SHELL, "PHANTOM_SHELL");
+        assert_eq!(env_vars::GIT_DIR, "GIT_DIR");
         assert_eq!(dirs::GIT, ".git");
     }
 
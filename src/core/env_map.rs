@@ -0,0 +1,226 @@
+use crate::{PhantomError, Result};
+use std::collections::{btree_map, BTreeMap, HashMap};
+
+/// Environment variables passed to a child process
+///
+/// Backed by a `BTreeMap` so iteration (and therefore any argv built from it)
+/// is always sorted by key, and setting a key twice keeps only the last value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvMap(BTreeMap<String, String>);
+
+/// Sources of environment variables, listed from lowest to highest precedence
+#[derive(Debug, Clone, Default)]
+pub struct EnvSources {
+    /// Variables phantom sets itself (PHANTOM_WORKTREE, ...)
+    pub phantom: EnvMap,
+    /// Variables from the phantom configuration file
+    pub config: EnvMap,
+    /// Variables loaded from env files
+    pub env_files: EnvMap,
+    /// Variables passed with `--env KEY=VALUE`
+    pub flags: EnvMap,
+}
+
+impl EnvSources {
+    /// Merge all sources, later sources overriding earlier ones
+    pub fn merge(self) -> EnvMap {
+        EnvMap::layered([self.phantom, self.config, self.env_files, self.flags])
+    }
+}
+
+impl EnvMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merge layers in order; a key set by a later layer wins
+    pub fn layered(layers: impl IntoIterator<Item = EnvMap>) -> Self {
+        let mut merged = Self::new();
+        for layer in layers {
+            merged.overlay(layer);
+        }
+        merged
+    }
+
+    /// Set every variable from `other`, overriding existing values
+    pub fn overlay(&mut self, other: EnvMap) {
+        self.0.extend(other.0);
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.0.insert(key.into(), value.into())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over variables in key order
+    pub fn iter(&self) -> btree_map::Iter<'_, String, String> {
+        self.0.iter()
+    }
+
+    /// Variables formatted as `KEY=VALUE`, in key order
+    pub fn to_assignments(&self) -> Vec<String> {
+        self.0.iter().map(|(key, value)| format!("{key}={value}")).collect()
+    }
+
+    /// Parse `KEY=VALUE` assignments, rejecting any without `=` or with an empty key
+    pub fn parse_assignments<S: AsRef<str>>(assignments: &[S]) -> Result<Self> {
+        let mut env = Self::new();
+        let mut invalid = Vec::new();
+        for assignment in assignments {
+            match assignment.as_ref().split_once('=') {
+                Some((key, value)) if !key.is_empty() => {
+                    env.insert(key, value);
+                }
+                _ => invalid.push(format!("'{}'", assignment.as_ref())),
+            }
+        }
+        if !invalid.is_empty() {
+            return Err(PhantomError::ValidationFailed {
+                reason: format!(
+                    "Invalid environment variable {}, expected KEY=VALUE",
+                    invalid.join(", ")
+                ),
+            });
+        }
+        Ok(env)
+    }
+}
+
+impl From<HashMap<String, String>> for EnvMap {
+    fn from(map: HashMap<String, String>) -> Self {
+        Self(map.into_iter().collect())
+    }
+}
+
+impl From<BTreeMap<String, String>> for EnvMap {
+    fn from(map: BTreeMap<String, String>) -> Self {
+        Self(map)
+    }
+}
+
+impl<K: Into<String>, V: Into<String>, const N: usize> From<[(K, V); N]> for EnvMap {
+    fn from(pairs: [(K, V); N]) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for EnvMap {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(iter.into_iter().map(|(key, value)| (key.into(), value.into())).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> Extend<(K, V)> for EnvMap {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(|(key, value)| (key.into(), value.into())));
+    }
+}
+
+impl IntoIterator for EnvMap {
+    type Item = (String, String);
+    type IntoIter = btree_map::IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a EnvMap {
+    type Item = (&'a String, &'a String);
+    type IntoIter = btree_map::Iter<'a, String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iteration_is_sorted() {
+        let env: EnvMap = [("ZETA", "1"), ("ALPHA", "2"), ("MIDDLE", "3")].into();
+        let keys: Vec<_> = env.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["ALPHA", "MIDDLE", "ZETA"]);
+        assert_eq!(env.to_assignments(), vec!["ALPHA=2", "MIDDLE=3", "ZETA=1"]);
+    }
+
+    #[test]
+    fn test_from_hash_map_is_sorted() {
+        let map: HashMap<String, String> =
+            (0..50).map(|i| (format!("VAR_{i:02}"), i.to_string())).collect();
+        let env = EnvMap::from(map);
+        let keys: Vec<_> = env.iter().map(|(key, _)| key.clone()).collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+        assert_eq!(env.len(), 50);
+    }
+
+    #[test]
+    fn test_duplicate_keys_keep_last_value() {
+        let env: EnvMap = vec![("KEY", "first"), ("KEY", "second")].into_iter().collect();
+        assert_eq!(env.len(), 1);
+        assert_eq!(env.get("KEY"), Some("second"));
+    }
+
+    #[test]
+    fn test_layered_precedence() {
+        let sources = EnvSources {
+            phantom: [("PHANTOM_WORKTREE", "feature"), ("SHARED", "phantom")].into(),
+            config: [("SHARED", "config"), ("FROM_CONFIG", "1")].into(),
+            env_files: [("SHARED", "file"), ("FROM_FILE", "1")].into(),
+            flags: [("SHARED", "flag")].into(),
+        };
+        let merged = sources.merge();
+
+        assert_eq!(merged.get("SHARED"), Some("flag"));
+        assert_eq!(merged.get("PHANTOM_WORKTREE"), Some("feature"));
+        assert_eq!(merged.get("FROM_CONFIG"), Some("1"));
+        assert_eq!(merged.get("FROM_FILE"), Some("1"));
+        assert_eq!(merged.len(), 4);
+    }
+
+    #[test]
+    fn test_layered_skips_missing_layers() {
+        let sources = EnvSources {
+            config: [("SHARED", "config")].into(),
+            env_files: [("SHARED", "file")].into(),
+            ..Default::default()
+        };
+        assert_eq!(sources.merge().get("SHARED"), Some("file"));
+        assert!(EnvSources::default().merge().is_empty());
+    }
+
+    #[test]
+    fn test_overlay_overrides_existing() {
+        let mut env: EnvMap = [("A", "1"), ("B", "1")].into();
+        env.overlay([("B", "2"), ("C", "2")].into());
+        assert_eq!(env.to_assignments(), vec!["A=1", "B=2", "C=2"]);
+    }
+
+    #[test]
+    fn test_parse_assignments() {
+        let env = EnvMap::parse_assignments(&["B=2", "A=x=y", "EMPTY=", "B=3"]).unwrap();
+        assert_eq!(env.to_assignments(), vec!["A=x=y", "B=3", "EMPTY="]);
+
+        let err = EnvMap::parse_assignments(&["OK=1", "MISSING", "=value"]).unwrap_err();
+        assert!(err.to_string().contains("'MISSING', '=value'"), "{err}");
+    }
+}
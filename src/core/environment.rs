@@ -0,0 +1,78 @@
+use crate::core::sealed::Sealed;
+
+/// Trait for reading and changing environment variables
+///
+/// Runtime checks such as whether phantom runs inside tmux go through this
+/// rather than `std::env`, so tests can describe the environment they need.
+/// This trait is sealed to prevent downstream implementations
+pub trait Environment: Sealed + Send + Sync {
+    /// Value of `key`; `None` when it is unset or not valid unicode
+    fn get_var(&self, key: &str) -> Option<String>;
+
+    /// Every variable whose name and value are valid unicode
+    fn vars(&self) -> Vec<(String, String)>;
+
+    /// Set `key` to `value`
+    fn set_var(&self, key: &str, value: &str);
+
+    /// Unset `key`
+    fn remove_var(&self, key: &str);
+}
+
+/// Real environment backed by the process environment
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealEnvironment;
+
+impl RealEnvironment {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// Implement the sealed trait
+impl Sealed for RealEnvironment {}
+
+impl Environment for RealEnvironment {
+    fn get_var(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok()
+    }
+
+    fn vars(&self) -> Vec<(String, String)> {
+        std::env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+            .collect()
+    }
+
+    #[allow(clippy::disallowed_methods)]
+    fn set_var(&self, key: &str, value: &str) {
+        // SAFETY: callers must not change the environment while other threads read it
+        unsafe {
+            std::env::set_var(key, value);
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)]
+    fn remove_var(&self, key: &str) {
+        // SAFETY: as for set_var
+        unsafe {
+            std::env::remove_var(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod mock_environment;
+#[cfg(test)]
+pub use mock_environment::MockEnvironment;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_real_environment_reads_the_process_environment() {
+        let env = RealEnvironment::new();
+        assert_eq!(env.get_var("PATH"), std::env::var("PATH").ok());
+        assert_eq!(env.get_var("PHANTOM_SURELY_UNSET_VARIABLE"), None);
+    }
+}
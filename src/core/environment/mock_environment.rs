@@ -0,0 +1,59 @@
+use super::*;
+use crate::core::sealed::Sealed;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Mock environment for testing; starts empty and clones share their variables
+#[derive(Debug, Clone, Default)]
+pub struct MockEnvironment {
+    vars: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl MockEnvironment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The environment with `key` set to `value`
+    pub fn with_var(self, key: &str, value: &str) -> Self {
+        self.set_var(key, value);
+        self
+    }
+}
+
+impl Sealed for MockEnvironment {}
+
+impl Environment for MockEnvironment {
+    fn get_var(&self, key: &str) -> Option<String> {
+        self.vars.lock().unwrap().get(key).cloned()
+    }
+
+    fn vars(&self) -> Vec<(String, String)> {
+        self.vars.lock().unwrap().iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    fn set_var(&self, key: &str, value: &str) {
+        self.vars.lock().unwrap().insert(key.to_string(), value.to_string());
+    }
+
+    fn remove_var(&self, key: &str) {
+        self.vars.lock().unwrap().remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_environment_is_shared_between_clones() {
+        let env = MockEnvironment::new().with_var("TMUX", "/tmp/tmux-1000/default,1,0");
+        let clone = env.clone();
+
+        clone.remove_var("TMUX");
+        clone.set_var("SHELL", "/bin/zsh");
+
+        assert_eq!(env.get_var("TMUX"), None);
+        assert_eq!(env.get_var("SHELL").as_deref(), Some("/bin/zsh"));
+    }
+}
@@ -1,10 +1,11 @@
 use std::io;
 use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum PhantomError {
-    #[error("Git command '{command}' failed with exit code {exit_code}")]
+    #[error("Git command '{command} {}' failed with exit code {exit_code}", .args.join(" "))]
     Git { command: String, args: Vec<String>, exit_code: i32, stderr: String },
 
     #[error("IO error: {0}")]
@@ -13,15 +14,26 @@ pub enum PhantomError {
     #[error("Worktree '{name}' already exists")]
     WorktreeExists { name: String },
 
+    #[error(
+        "Worktree '{name}' conflicts with existing worktree '{existing}' on this case-insensitive filesystem"
+    )]
+    WorktreeNameCollision { name: String, existing: String },
+
     #[error("Worktree '{name}' not found")]
     WorktreeNotFound { name: String },
 
     #[error("Cannot delete current worktree '{name}'")]
     CannotDeleteCurrent { name: String },
 
-    #[error("Worktree '{name}' has uncommitted changes")]
+    #[error("Worktree '{name}' has uncommitted changes (use --force-dirty to delete anyway)")]
     WorktreeHasUncommittedChanges { name: String },
 
+    #[error(
+        "Worktree '{name}' is locked{} (run 'phantom unlock {name}' or use --force to delete anyway)",
+        lock_reason_note(.reason.as_deref())
+    )]
+    WorktreeLocked { name: String, reason: Option<String> },
+
     #[error("Failed to create worktree directory at {path}")]
     WorktreeDirectoryCreationFailed { path: PathBuf },
 
@@ -37,17 +49,38 @@ pub enum PhantomError {
     #[error("Invalid worktree name '{name}': {reason}")]
     InvalidWorktreeName { name: String, reason: String },
 
+    #[error("Invalid branch name '{name}': {reason}")]
+    InvalidBranchName { name: String, reason: String },
+
     #[error("Not in a git repository")]
     NotInGitRepository,
 
+    #[error("Repository has no commits yet; make an initial commit first")]
+    NoCommits,
+
     #[error("Branch '{branch}' not found")]
     BranchNotFound { branch: String },
 
+    #[error("Branch '{branch}' not found locally or on remote '{remote}'")]
+    BranchNotFoundAnywhere { branch: String, remote: String },
+
+    #[error("Failed to fetch '{branch}' from '{remote}': {stderr}")]
+    FetchFailed { remote: String, branch: String, stderr: String },
+
+    #[error("Branch '{branch}' already exists")]
+    BranchExists { branch: String },
+
+    #[error("git is not installed or not found in PATH")]
+    GitNotInstalled,
+
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
 
-    #[error("Command '{command}' not found in PATH")]
-    CommandNotFound { command: String },
+    #[error("Command '{command}' not found in PATH{}", did_you_mean(.suggestions))]
+    CommandNotFound { command: String, suggestions: Vec<String> },
+
+    #[error("Shell '{shell}' set by {setting} was not found")]
+    ShellNotFound { shell: String, setting: String },
 
     #[error("Process '{command}' exited with code {code}")]
     ProcessFailed { command: String, code: i32 },
@@ -55,6 +88,9 @@ pub enum PhantomError {
     #[error("Process execution failed: {reason}")]
     ProcessExecutionError { reason: String },
 
+    #[error("Command '{command}' timed out after {timeout:?}")]
+    CommandTimeout { command: String, timeout: Duration },
+
     #[error("Feature '{feature}' not supported on {platform}")]
     UnsupportedFeature { feature: String, platform: String },
 
@@ -66,6 +102,35 @@ pub enum PhantomError {
 
     #[error("Validation failed: {reason}")]
     ValidationFailed { reason: String },
+
+    #[error("{error} (rolled back worktree '{name}'{})", unreverted_note(.unreverted))]
+    CreateRolledBack { name: String, error: Box<PhantomError>, unreverted: Vec<String> },
+}
+
+/// The lock reason, for the `WorktreeLocked` message
+fn lock_reason_note(reason: Option<&str>) -> String {
+    match reason.map(str::trim) {
+        Some(reason) if !reason.is_empty() => format!(": {reason}"),
+        _ => String::new(),
+    }
+}
+
+/// Close matches for a missing command, for the `CommandNotFound` message
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [one] => format!("; did you mean '{one}'?"),
+        many => format!("; did you mean one of: {}?", many.join(", ")),
+    }
+}
+
+/// The rollback steps that failed, for the `CreateRolledBack` message
+fn unreverted_note(unreverted: &[String]) -> String {
+    if unreverted.is_empty() {
+        String::new()
+    } else {
+        format!("; could not undo: {}", unreverted.join("; "))
+    }
 }
 
 #[cfg(test)]
@@ -80,7 +145,7 @@ mod tests {
             exit_code: 128,
             stderr: "error: failed to create worktree".to_string(),
         };
-        assert_eq!(err.to_string(), "Git command 'git' failed with exit code 128");
+        assert_eq!(err.to_string(), "Git command 'git worktree add' failed with exit code 128");
 
         let err = PhantomError::WorktreeExists { name: "feature-branch".to_string() };
         assert_eq!(err.to_string(), "Worktree 'feature-branch' already exists");
@@ -92,7 +157,25 @@ mod tests {
         assert_eq!(err.to_string(), "Cannot delete current worktree 'current'");
 
         let err = PhantomError::WorktreeHasUncommittedChanges { name: "dirty".to_string() };
-        assert_eq!(err.to_string(), "Worktree 'dirty' has uncommitted changes");
+        assert_eq!(
+            err.to_string(),
+            "Worktree 'dirty' has uncommitted changes (use --force-dirty to delete anyway)"
+        );
+
+        let err = PhantomError::WorktreeLocked { name: "locked".to_string(), reason: None };
+        assert_eq!(
+            err.to_string(),
+            "Worktree 'locked' is locked (run 'phantom unlock locked' or use --force to delete anyway)"
+        );
+
+        let err = PhantomError::WorktreeLocked {
+            name: "locked".to_string(),
+            reason: Some("on a usb drive".to_string()),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Worktree 'locked' is locked: on a usb drive (run 'phantom unlock locked' or use --force to delete anyway)"
+        );
 
         let err = PhantomError::ConfigInvalid { reason: "missing field".to_string() };
         assert_eq!(err.to_string(), "Invalid configuration: missing field");
@@ -109,15 +192,55 @@ mod tests {
         let err = PhantomError::NotInGitRepository;
         assert_eq!(err.to_string(), "Not in a git repository");
 
+        let err = PhantomError::NoCommits;
+        assert_eq!(err.to_string(), "Repository has no commits yet; make an initial commit first");
+
         let err = PhantomError::BranchNotFound { branch: "missing".to_string() };
         assert_eq!(err.to_string(), "Branch 'missing' not found");
 
-        let err = PhantomError::CommandNotFound { command: "phantom".to_string() };
+        let err = PhantomError::BranchNotFoundAnywhere {
+            branch: "missing".to_string(),
+            remote: "origin".to_string(),
+        };
+        assert_eq!(err.to_string(), "Branch 'missing' not found locally or on remote 'origin'");
+
+        let err = PhantomError::BranchExists { branch: "feature".to_string() };
+        assert_eq!(err.to_string(), "Branch 'feature' already exists");
+
+        let err = PhantomError::CreateRolledBack {
+            name: "feature".to_string(),
+            error: Box::new(PhantomError::NoCommits),
+            unreverted: vec!["delete branch 'feature': locked".to_string()],
+        };
+        assert_eq!(
+            err.to_string(),
+            "Repository has no commits yet; make an initial commit first (rolled back worktree 'feature'; could not undo: delete branch 'feature': locked)"
+        );
+
+        let err = PhantomError::GitNotInstalled;
+        assert_eq!(err.to_string(), "git is not installed or not found in PATH");
+
+        let err = PhantomError::CommandNotFound {
+            command: "phantom".to_string(),
+            suggestions: Vec::new(),
+        };
         assert_eq!(err.to_string(), "Command 'phantom' not found in PATH");
 
+        let err = PhantomError::ShellNotFound {
+            shell: "/bin/zsh".to_string(),
+            setting: "PHANTOM_SHELL".to_string(),
+        };
+        assert_eq!(err.to_string(), "Shell '/bin/zsh' set by PHANTOM_SHELL was not found");
+
         let err = PhantomError::ProcessFailed { command: "ls".to_string(), code: 1 };
         assert_eq!(err.to_string(), "Process 'ls' exited with code 1");
 
+        let err = PhantomError::CommandTimeout {
+            command: "git".to_string(),
+            timeout: Duration::from_secs(30),
+        };
+        assert_eq!(err.to_string(), "Command 'git' timed out after 30s");
+
         let err = PhantomError::UnsupportedFeature {
             feature: "symlinks".to_string(),
             platform: "Windows".to_string(),
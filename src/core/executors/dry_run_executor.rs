@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+use crate::core::command_executor::{CommandConfig, CommandExecutor, CommandOutput};
+use crate::core::result::Result;
+use crate::core::sealed::Sealed;
+use crate::git::git_executor_adapter::GitSettings;
+
+/// git subcommands that only read, whatever their arguments
+const READ_ONLY_GIT_COMMANDS: &[&str] = &[
+    "cat-file",
+    "check-ignore",
+    "describe",
+    "diff",
+    "for-each-ref",
+    "log",
+    "ls-files",
+    "ls-remote",
+    "merge-base",
+    "rev-list",
+    "rev-parse",
+    "show-ref",
+    "status",
+    "version",
+];
+
+/// Records the commands that would change something instead of running them
+///
+/// Read-only git commands still run on the wrapped executor, so lookups such
+/// as whether a branch exists see the real repository. Every other command is
+/// recorded and reported as a success with no output.
+#[derive(Debug, Clone)]
+pub struct DryRunExecutor<E> {
+    inner: E,
+    recorded: Arc<Mutex<Vec<CommandConfig>>>,
+}
+
+impl<E> DryRunExecutor<E> {
+    pub fn new(inner: E) -> Self {
+        Self { inner, recorded: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// The commands that were recorded instead of run, in order
+    pub fn recorded(&self) -> Vec<CommandConfig> {
+        self.recorded.lock().unwrap().clone()
+    }
+}
+
+/// Whether running `config` leaves the repository and the filesystem as they were
+fn is_read_only(config: &CommandConfig) -> bool {
+    if config.program != "git" {
+        return false;
+    }
+    let args: Vec<&str> = config.args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [command, ..] if READ_ONLY_GIT_COMMANDS.contains(command) => true,
+        ["worktree", "list", ..] => true,
+        ["config", rest @ ..] => {
+            rest.iter().any(|arg| arg.starts_with("--get") || matches!(*arg, "--list" | "-l"))
+        }
+        ["branch", rest @ ..] => {
+            rest.iter().any(|arg| matches!(*arg, "--list" | "-l" | "--show-current"))
+        }
+        _ => false,
+    }
+}
+
+impl<E> Sealed for DryRunExecutor<E> {}
+
+#[async_trait]
+impl<E> CommandExecutor for DryRunExecutor<E>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    async fn execute(&self, config: CommandConfig) -> Result<CommandOutput> {
+        if is_read_only(&config) {
+            return self.inner.execute(config).await;
+        }
+        debug!("Dry run, not executing: {} {:?}", config.program, config.args);
+        self.recorded.lock().unwrap().push(config);
+        Ok(CommandOutput::from_static("", "", 0))
+    }
+
+    async fn execute_interactive(&self, config: CommandConfig) -> Result<CommandOutput> {
+        self.execute(config).await
+    }
+
+    fn git_settings(&self) -> GitSettings {
+        self.inner.git_settings()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+
+    fn git(args: &[&str]) -> CommandConfig {
+        CommandConfig::new("git").with_args(args.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_executor_runs_reads_and_records_the_rest() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/main"])
+            .returns_output("", "", 1);
+        let dry_run = DryRunExecutor::new(mock.clone());
+
+        let read = dry_run.execute(git(&["show-ref", "--verify", "--quiet", "refs/heads/main"]));
+        assert_eq!(read.await.unwrap().exit_code, 1);
+        let add = dry_run.execute(git(&["worktree", "add", "-b", "feature", "/repo/wt"]));
+        assert!(add.await.unwrap().success());
+        let hook = dry_run.execute(CommandConfig::new("/repo/.phantom/hooks/post-create"));
+        assert!(hook.await.unwrap().success());
+
+        let recorded: Vec<_> = dry_run.recorded().into_iter().map(|c| c.program).collect();
+        assert_eq!(recorded, ["git", "/repo/.phantom/hooks/post-create"]);
+        assert_eq!(mock.calls().len(), 1);
+    }
+
+    #[test]
+    fn test_is_read_only() {
+        assert!(is_read_only(&git(&["rev-parse", "--git-common-dir"])));
+        assert!(is_read_only(&git(&["worktree", "list", "--porcelain"])));
+        assert!(is_read_only(&git(&["config", "--get", "phantom.branchPrefix"])));
+        assert!(is_read_only(&git(&["branch", "--list", "feature"])));
+        assert!(!is_read_only(&git(&["config", "phantom.stack.feature", "main"])));
+        assert!(!is_read_only(&git(&["branch", "--track", "feature", "origin/feature"])));
+        assert!(!is_read_only(&git(&["fetch", "origin", "feature"])));
+        assert!(!is_read_only(&CommandConfig::new("ls")));
+    }
+}
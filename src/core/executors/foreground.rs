@@ -0,0 +1,398 @@
+//! Interactive children (fzf) run in their own process group so that job
+//! control signals aimed at phantom reach them too. Commands run with
+//! phantom's stdio (exec, shell) do the same, so that phantom can pass
+//! SIGINT and SIGTERM on and report how they ended.
+
+use std::fs::{File, OpenOptions};
+use std::os::fd::AsRawFd;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, Command};
+use tracing::{debug, info};
+
+use crate::core::command_executor::{CommandConfig, CommandOutput, StdinMode};
+use crate::core::error::PhantomError;
+use crate::core::result::Result;
+
+/// How often the wait loop checks for stops and pending signals
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Signals phantom intercepts while an interactive child is running
+const FORWARDED_SIGNALS: [libc::c_int; 4] =
+    [libc::SIGTSTP, libc::SIGTERM, libc::SIGHUP, libc::SIGINT];
+
+/// Last signal delivered to phantom while a foreground child was running
+static PENDING_SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+extern "C" fn record_signal(signal: libc::c_int) {
+    PENDING_SIGNAL.store(signal, Ordering::SeqCst);
+}
+
+fn take_pending_signal() -> Option<libc::c_int> {
+    match PENDING_SIGNAL.swap(0, Ordering::SeqCst) {
+        0 => None,
+        signal => Some(signal),
+    }
+}
+
+/// What to do when phantom receives SIGINT, SIGTERM or SIGHUP while the child runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OnSignal {
+    /// Terminate the child and let phantom die of the signal itself
+    Terminate,
+    /// Send the signal on to the child's group and keep waiting for it
+    Forward,
+}
+
+/// Owns the child's process group until it exits.
+///
+/// Dropping the guard before the child has been reaped sends SIGTERM to the
+/// whole group, hands the terminal back and restores phantom's signal handlers.
+struct ProcessGroupGuard {
+    pgid: libc::pid_t,
+    tty: Option<File>,
+    previous_actions: Vec<(libc::c_int, libc::sigaction)>,
+    reaped: bool,
+}
+
+impl ProcessGroupGuard {
+    fn new(pgid: libc::pid_t) -> Self {
+        let mut guard = Self { pgid, tty: None, previous_actions: Vec::new(), reaped: false };
+        guard.install_handlers();
+
+        // Only take over the terminal when phantom is its current foreground job
+        if let Ok(tty) = OpenOptions::new().read(true).write(true).open("/dev/tty") {
+            // SAFETY: plain libc queries on a descriptor we own
+            let is_foreground = unsafe { libc::tcgetpgrp(tty.as_raw_fd()) == libc::getpgrp() };
+            if is_foreground {
+                guard.tty = Some(tty);
+                guard.give_terminal();
+            }
+        }
+
+        guard
+    }
+
+    fn install_handlers(&mut self) {
+        PENDING_SIGNAL.store(0, Ordering::SeqCst);
+        for signal in FORWARDED_SIGNALS {
+            // SAFETY: the handler only performs an atomic store, which is async-signal-safe
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = record_signal as *const () as libc::sighandler_t;
+                libc::sigemptyset(&mut action.sa_mask);
+                let mut previous: libc::sigaction = std::mem::zeroed();
+                if libc::sigaction(signal, &action, &mut previous) == 0 {
+                    self.previous_actions.push((signal, previous));
+                }
+            }
+        }
+    }
+
+    fn restore_handlers(&mut self) {
+        for (signal, previous) in self.previous_actions.drain(..) {
+            // SAFETY: reinstates the disposition captured in install_handlers
+            unsafe {
+                libc::sigaction(signal, &previous, std::ptr::null_mut());
+            }
+        }
+    }
+
+    fn set_terminal_owner(&self, pgid: libc::pid_t) {
+        let Some(tty) = &self.tty else {
+            return;
+        };
+        // A background group calling tcsetpgrp receives SIGTTOU unless it is ignored
+        // SAFETY: SIGTTOU is ignored only for the duration of the call
+        unsafe {
+            let mut ignore: libc::sigaction = std::mem::zeroed();
+            ignore.sa_sigaction = libc::SIG_IGN;
+            let mut previous: libc::sigaction = std::mem::zeroed();
+            libc::sigaction(libc::SIGTTOU, &ignore, &mut previous);
+            libc::tcsetpgrp(tty.as_raw_fd(), pgid);
+            libc::sigaction(libc::SIGTTOU, &previous, std::ptr::null_mut());
+        }
+    }
+
+    fn give_terminal(&self) {
+        self.set_terminal_owner(self.pgid);
+    }
+
+    fn reclaim_terminal(&self) {
+        // SAFETY: getpgrp cannot fail
+        self.set_terminal_owner(unsafe { libc::getpgrp() });
+    }
+
+    fn signal_group(&self, signal: libc::c_int) {
+        // SAFETY: the group id belongs to a child we spawned and have not reaped
+        unsafe {
+            libc::killpg(self.pgid, signal);
+        }
+    }
+
+    /// The signal that stopped the child since the last check (e.g. SIGTSTP when fzf handled Ctrl-Z)
+    fn child_stop_signal(&self) -> Option<libc::c_int> {
+        // SAFETY: WSTOPPED without WEXITED only consumes stop notifications, so
+        // tokio's reaper still observes the exit status
+        unsafe {
+            let mut info: libc::siginfo_t = std::mem::zeroed();
+            let result = libc::waitid(
+                libc::P_PID,
+                self.pgid as libc::id_t,
+                &mut info,
+                libc::WSTOPPED | libc::WNOHANG,
+            );
+            (result == 0 && info.si_signo == libc::SIGCHLD).then(|| info.si_status())
+        }
+    }
+
+    /// Whether the child's group is the terminal's foreground job
+    fn child_owns_terminal(&self) -> bool {
+        // SAFETY: plain libc query on a descriptor we own
+        self.tty
+            .as_ref()
+            .is_some_and(|tty| unsafe { libc::tcgetpgrp(tty.as_raw_fd()) } == self.pgid)
+    }
+
+    /// Continue a child stopped by SIGTTIN or SIGTTOU for touching the terminal before
+    /// it was handed over; `false` for any other stop, which phantom shares
+    fn resume_early_tty_stop(&self, signal: libc::c_int) -> bool {
+        if !matches!(signal, libc::SIGTTIN | libc::SIGTTOU) || !self.child_owns_terminal() {
+            return false;
+        }
+        debug!("Resuming child group {} stopped by signal {signal} before the handover", self.pgid);
+        self.signal_group(libc::SIGCONT);
+        true
+    }
+
+    /// Suspend the child group and phantom together, resuming both on SIGCONT
+    fn suspend(&self) {
+        debug!("Suspending interactive child group {}", self.pgid);
+        self.signal_group(libc::SIGTSTP);
+        self.reclaim_terminal();
+        // SAFETY: SIGSTOP cannot be caught; execution continues once the shell sends SIGCONT
+        unsafe {
+            libc::raise(libc::SIGSTOP);
+        }
+        if matches!(PENDING_SIGNAL.load(Ordering::SeqCst), 0 | libc::SIGTSTP) {
+            self.give_terminal();
+            self.signal_group(libc::SIGCONT);
+        }
+    }
+
+    fn terminate(&self) {
+        self.signal_group(libc::SIGTERM);
+        // A stopped group would not act on SIGTERM until continued
+        self.signal_group(libc::SIGCONT);
+    }
+}
+
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        if !self.reaped {
+            self.terminate();
+        }
+        self.reclaim_terminal();
+        self.restore_handlers();
+    }
+}
+
+/// Run a command in its own foreground process group, forwarding job control to it
+pub(crate) async fn run_in_process_group(config: CommandConfig) -> Result<CommandOutput> {
+    info!("Executing interactive command: {} {:?}", config.program, config.args);
+
+    let mut command = Command::new(&config.program);
+    command.args(&config.args).process_group(0);
+
+    if let Some(ref cwd) = config.cwd {
+        command.current_dir(cwd);
+    }
+
+    for key in &config.env_remove {
+        command.env_remove(key);
+    }
+
+    if let Some(ref env) = config.env {
+        command.envs(env);
+    }
+
+    command.stdin(config.stdin.stdio());
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+
+    let mut child = command.spawn().map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            PhantomError::CommandNotFound {
+                command: config.program.clone(),
+                suggestions: Vec::new(),
+            }
+        } else {
+            PhantomError::ProcessExecutionError {
+                reason: format!("Failed to spawn command '{}': {}", config.program, e),
+            }
+        }
+    })?;
+
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let stdout_task = tokio::spawn(async move {
+        let mut buf = Vec::new();
+        stdout.read_to_end(&mut buf).await.map(|_| buf)
+    });
+    let stderr_task = tokio::spawn(async move {
+        let mut buf = Vec::new();
+        stderr.read_to_end(&mut buf).await.map(|_| buf)
+    });
+
+    if let (StdinMode::Data(stdin_data), Some(mut stdin)) = (config.stdin, child.stdin.take()) {
+        tokio::spawn(async move {
+            // fzf may exit before consuming all input, so a broken pipe is not an error
+            let _ = stdin.write_all(stdin_data.as_bytes()).await;
+            let _ = stdin.shutdown().await;
+        });
+    }
+
+    let status = wait_in_process_group(&mut child, &config.program, OnSignal::Terminate).await?;
+
+    let read_failed = |e| PhantomError::ProcessExecutionError {
+        reason: format!("Failed to read output of '{}': {}", config.program, e),
+    };
+    let stdout = stdout_task.await.map_err(|e| read_failed(e.to_string()))?;
+    let stdout = stdout.map_err(|e| read_failed(e.to_string()))?;
+    let stderr = stderr_task.await.map_err(|e| read_failed(e.to_string()))?;
+    let stderr = stderr.map_err(|e| read_failed(e.to_string()))?;
+
+    Ok(CommandOutput::new(
+        String::from_utf8_lossy(&stdout).to_string(),
+        String::from_utf8_lossy(&stderr).to_string(),
+        status.code().unwrap_or(-1),
+    ))
+}
+
+/// Wait for `child`, spawned as the leader of its own process group, while it owns the terminal
+///
+/// Suspending phantom suspends the group with it; other signals are handled as `on_signal` says.
+pub(crate) async fn wait_in_process_group(
+    child: &mut Child,
+    program: &str,
+    on_signal: OnSignal,
+) -> Result<std::process::ExitStatus> {
+    let pgid = child.id().ok_or_else(|| PhantomError::ProcessExecutionError {
+        reason: format!("Command '{program}' exited before it could be tracked"),
+    })? as libc::pid_t;
+    let mut guard = ProcessGroupGuard::new(pgid);
+
+    let status = loop {
+        tokio::select! {
+            status = child.wait() => break status,
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+
+        match take_pending_signal() {
+            Some(libc::SIGTSTP) => guard.suspend(),
+            Some(signal) if on_signal == OnSignal::Forward => {
+                info!("Received signal {signal}, forwarding it to '{program}'");
+                guard.signal_group(signal);
+            }
+            Some(signal) => {
+                info!("Received signal {signal}, terminating '{program}'");
+                drop(guard);
+                // Re-deliver with phantom's original disposition so it exits as it normally would
+                // SAFETY: raising a signal on ourselves after handlers were restored
+                unsafe {
+                    libc::raise(signal);
+                }
+                return Err(PhantomError::ProcessExecutionError {
+                    reason: format!("'{program}' was interrupted by signal {signal}"),
+                });
+            }
+            None => match guard.child_stop_signal() {
+                Some(signal) if guard.resume_early_tty_stop(signal) => {}
+                Some(_) => guard.suspend(),
+                None => {}
+            },
+        }
+    }
+    .map_err(|e| PhantomError::ProcessExecutionError {
+        reason: format!("Failed to wait for command '{program}': {e}"),
+    })?;
+
+    guard.reaped = true;
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_run_in_process_group_collects_output() {
+        let config = CommandConfig::new("sh")
+            .with_args(vec!["-c".to_string(), "head -n 1; echo oops >&2; exit 3".to_string()])
+            .with_stdin(StdinMode::Data("first\nsecond".to_string()));
+
+        let output = run_in_process_group(config).await.unwrap();
+        assert_eq!(output.stdout.trim(), "first");
+        assert_eq!(output.stderr.trim(), "oops");
+        assert_eq!(output.exit_code, 3);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_run_in_process_group_uses_separate_group() {
+        let config = CommandConfig::new("sh")
+            .with_args(vec!["-c".to_string(), "ps -o pgid= -p $$".to_string()]);
+
+        let output = run_in_process_group(config).await.unwrap();
+        let child_pgid: libc::pid_t = output.stdout.trim().parse().unwrap();
+        // SAFETY: getpgrp cannot fail
+        assert_ne!(child_pgid, unsafe { libc::getpgrp() });
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_stop_signal_is_reported_and_only_tty_stops_are_resumed() {
+        let mut child = Command::new("sh")
+            .args(["-c", "kill -STOP $$; exit 0"])
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let mut guard = ProcessGroupGuard {
+            pgid: child.id().unwrap() as libc::pid_t,
+            tty: None,
+            previous_actions: Vec::new(),
+            reaped: false,
+        };
+
+        let mut stop = None;
+        for _ in 0..100 {
+            stop = guard.child_stop_signal();
+            if stop.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(stop, Some(libc::SIGSTOP));
+        assert!(!guard.resume_early_tty_stop(libc::SIGSTOP));
+        // Without the terminal there is no handover to wait for
+        assert!(!guard.resume_early_tty_stop(libc::SIGTTOU));
+
+        guard.signal_group(libc::SIGCONT);
+        assert!(child.wait().await.unwrap().success());
+        guard.reaped = true;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_run_in_process_group_command_not_found() {
+        let config = CommandConfig::new("nonexistent-interactive-command-12345");
+
+        let result = run_in_process_group(config).await;
+        assert!(matches!(result, Err(PhantomError::CommandNotFound { .. })));
+    }
+}
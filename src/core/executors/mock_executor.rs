@@ -1,9 +1,10 @@
 use async_trait::async_trait;
-use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use crate::core::command_executor::{CommandConfig, CommandExecutor, CommandOutput};
+use crate::core::command_executor::{CommandConfig, CommandExecutor, CommandOutput, StdinMode};
+use crate::core::env_map::EnvMap;
 use crate::core::error::PhantomError;
 use crate::core::result::Result;
 use crate::core::sealed::Sealed;
@@ -13,10 +14,12 @@ pub struct CommandExpectation {
     pub program: String,
     pub args: Option<Vec<String>>,
     pub cwd: Option<PathBuf>,
-    pub env: Option<HashMap<String, String>>,
-    pub stdin_data: Option<String>,
+    pub env: Option<EnvMap>,
+    pub stdin: Option<StdinMode>,
+    pub timeout: Option<Duration>,
     pub times: Option<usize>,
     pub returns: CommandOutput,
+    pub times_out: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -24,49 +27,124 @@ pub struct CommandCall {
     pub program: String,
     pub args: Vec<String>,
     pub cwd: Option<PathBuf>,
-    pub env: Option<HashMap<String, String>>,
-    pub stdin_data: Option<String>,
+    pub env: Option<EnvMap>,
+    pub env_remove: Vec<String>,
+    pub stdin: StdinMode,
+    pub timeout: Option<Duration>,
+    pub interactive: bool,
+    pub stream_output: bool,
+    pub inherit_output: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct MockCommandExecutor {
     expectations: Arc<Mutex<Vec<CommandExpectation>>>,
     calls: Arc<Mutex<Vec<CommandCall>>>,
+    /// Index of the expectation that answered each call, if any
+    served_by: Arc<Mutex<Vec<Option<usize>>>>,
+    /// Calls must use the expectations in the order they were added
+    ordered: bool,
+    /// Run `verify` when the last clone is dropped
+    verify_on_drop: bool,
 }
 
 impl MockCommandExecutor {
+    /// Duration every scripted output reports, so assertions on it stay deterministic
+    pub const COMMAND_DURATION: Duration = Duration::from_millis(5);
+
     pub fn new() -> Self {
         Self {
             expectations: Arc::new(Mutex::new(Vec::new())),
             calls: Arc::new(Mutex::new(Vec::new())),
+            served_by: Arc::new(Mutex::new(Vec::new())),
+            ordered: false,
+            verify_on_drop: false,
         }
     }
 
+    /// A mock whose calls must arrive in the order the expectations were added
+    ///
+    /// Each expectation answers `times` calls, or exactly one without `times`.
+    pub fn new_ordered() -> Self {
+        let mut mock = Self::new();
+        mock.ordered = true;
+        mock
+    }
+
+    /// Panic with the `verify` report when the last clone of this mock is dropped
+    pub fn verify_on_drop(mut self) -> Self {
+        self.verify_on_drop = true;
+        self
+    }
+
     pub fn expect_command(&mut self, program: &str) -> CommandExpectationBuilder {
         CommandExpectationBuilder::new(self.expectations.clone(), program)
     }
 
+    /// Check that every expectation was used
+    ///
+    /// An expectation with `times` must answer exactly that many calls; one
+    /// without must answer at least one. The error lists every unmet
+    /// expectation with the closest call that did not match it.
     pub fn verify(&self) -> Result<()> {
         let expectations = self.expectations.lock().unwrap();
         let calls = self.calls.lock().unwrap();
+        let served_by = self.served_by.lock().unwrap();
+
+        let mut problems = Vec::new();
+        for (index, expectation) in expectations.iter().enumerate() {
+            // Calls answered by a later expectation in a sequence don't count against this one
+            let actual_calls = calls
+                .iter()
+                .zip(served_by.iter())
+                .filter(|(call, served)| {
+                    !matches!(served, Some(other) if *other != index)
+                        && self.matches_expectation(call, expectation)
+                })
+                .count();
+
+            let problem = match self.expected_times(expectation) {
+                Some(expected_times) if actual_calls != expected_times => format!(
+                    "Expected command '{}' to be called {} times, but was called {} times",
+                    expectation.program, expected_times, actual_calls
+                ),
+                None if actual_calls == 0 => {
+                    format!(
+                        "Expected command '{}' to be called, but it never was",
+                        expectation.program
+                    )
+                }
+                _ => continue,
+            };
+
+            let mut lines =
+                vec![problem, format!("  expected: {}", describe_expectation(expectation))];
+            if actual_calls == 0 {
+                if let Some((call, mismatch)) = nearest_miss(&calls, expectation) {
+                    lines.push(format!("  nearest:  {} ({mismatch})", describe_call(call)));
+                }
+            }
+            problems.push(lines.join("\n"));
+        }
 
-        for expectation in expectations.iter() {
-            if let Some(expected_times) = expectation.times {
-                let actual_calls =
-                    calls.iter().filter(|call| self.matches_expectation(call, expectation)).count();
-
-                if actual_calls != expected_times {
-                    return Err(PhantomError::ProcessExecutionError {
-                        reason: format!(
-                            "Expected command '{}' to be called {} times, but was called {} times",
-                            expectation.program, expected_times, actual_calls
-                        ),
-                    });
+        if self.ordered {
+            for (call, served) in calls.iter().zip(served_by.iter()) {
+                if served.is_none() {
+                    problems.push(format!("Unexpected call out of order: {}", describe_call(call)));
                 }
             }
         }
 
-        Ok(())
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(PhantomError::ProcessExecutionError { reason: problems.join("\n") })
+        }
+    }
+
+    /// How many calls an expectation must answer; `None` means at least one
+    fn expected_times(&self, expectation: &CommandExpectation) -> Option<usize> {
+        expectation.times.or(if self.ordered { Some(1) } else { None })
     }
 
     fn matches_expectation(&self, call: &CommandCall, expectation: &CommandExpectation) -> bool {
@@ -92,8 +170,14 @@ impl MockCommandExecutor {
             }
         }
 
-        if let Some(ref expected_stdin) = expectation.stdin_data {
-            if call.stdin_data.as_ref() != Some(expected_stdin) {
+        if let Some(ref expected_stdin) = expectation.stdin {
+            if call.stdin != *expected_stdin {
+                return false;
+            }
+        }
+
+        if let Some(expected_timeout) = expectation.timeout {
+            if call.timeout != Some(expected_timeout) {
                 return false;
             }
         }
@@ -118,38 +202,200 @@ impl Sealed for MockCommandExecutor {}
 // Implement Sealed for &MockCommandExecutor
 impl Sealed for &MockCommandExecutor {}
 
-#[async_trait]
-impl CommandExecutor for MockCommandExecutor {
-    async fn execute(&self, config: CommandConfig) -> Result<CommandOutput> {
+impl MockCommandExecutor {
+    fn record(&self, config: CommandConfig, interactive: bool) -> Result<CommandOutput> {
         let call = CommandCall {
             program: config.program.clone(),
             args: config.args.to_vec(),
             cwd: config.cwd.clone(),
             env: config.env.clone(),
-            stdin_data: config.stdin_data.clone(),
+            env_remove: config.env_remove.clone(),
+            stdin: config.stdin.clone(),
+            timeout: config.timeout,
+            interactive,
+            stream_output: config.stream_output,
+            inherit_output: config.inherit_output,
         };
 
         self.calls.lock().unwrap().push(call.clone());
+        let mut served_by = self.served_by.lock().unwrap();
 
         let expectations = self.expectations.lock().unwrap();
-        for expectation in expectations.iter() {
+        if self.ordered {
+            // Only the first expectation that still has calls to answer may match
+            let next = expectations.iter().enumerate().find(|(index, expectation)| {
+                let served = served_by.iter().filter(|served| **served == Some(*index)).count();
+                self.expected_times(expectation).map_or(true, |times| served < times)
+            });
+            return match next {
+                Some((index, expectation)) if self.matches_expectation(&call, expectation) => {
+                    served_by.push(Some(index));
+                    respond(expectation, config)
+                }
+                next => {
+                    served_by.push(None);
+                    let expected = next
+                        .map(|(_, expectation)| describe_expectation(expectation))
+                        .unwrap_or_else(|| "no more commands".to_string());
+                    Err(PhantomError::ProcessExecutionError {
+                        reason: format!(
+                            "Unexpected command execution: {} {:?} (expected {expected})",
+                            config.program, config.args
+                        ),
+                    })
+                }
+            };
+        }
+
+        for (index, expectation) in expectations.iter().enumerate() {
             if self.matches_expectation(&call, expectation) {
-                return Ok(expectation.returns.clone());
+                // An expectation limited with `times` is used up once it has answered that often,
+                // so later expectations for the same command can script a sequence of results
+                if let Some(times) = expectation.times {
+                    if served_by.iter().filter(|served| **served == Some(index)).count() >= times {
+                        continue;
+                    }
+                }
+                served_by.push(Some(index));
+                return respond(expectation, config);
             }
         }
 
+        served_by.push(None);
         Err(PhantomError::ProcessExecutionError {
             reason: format!("Unexpected command execution: {} {:?}", config.program, config.args),
         })
     }
 }
 
+impl Drop for MockCommandExecutor {
+    fn drop(&mut self) {
+        // Builders only share the expectations, so the calls tell when the last clone goes
+        if self.verify_on_drop && Arc::strong_count(&self.calls) == 1 && !std::thread::panicking() {
+            if let Err(e) = self.verify() {
+                panic!("{e}");
+            }
+        }
+    }
+}
+
+/// The scripted result of an expectation that answered a call
+fn respond(expectation: &CommandExpectation, config: CommandConfig) -> Result<CommandOutput> {
+    if expectation.times_out {
+        return Err(PhantomError::CommandTimeout {
+            command: config.program,
+            timeout: config.timeout.unwrap_or_default(),
+        });
+    }
+    let output = expectation.returns.clone();
+    Ok(output.with_command(config.program, config.args, MockCommandExecutor::COMMAND_DURATION))
+}
+
+fn describe_call(call: &CommandCall) -> String {
+    let mut description = std::iter::once(call.program.as_str())
+        .chain(call.args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(cwd) = &call.cwd {
+        description.push_str(&format!(" in {}", cwd.display()));
+    }
+    description
+}
+
+fn describe_expectation(expectation: &CommandExpectation) -> String {
+    let mut description = expectation.program.clone();
+    match &expectation.args {
+        Some(args) => args.iter().for_each(|arg| {
+            description.push(' ');
+            description.push_str(arg);
+        }),
+        None => description.push_str(" <any args>"),
+    }
+    if let Some(cwd) = &expectation.cwd {
+        description.push_str(&format!(" in {}", cwd.display()));
+    }
+    description
+}
+
+/// How `call` differs from `expectation`, one entry per mismatched field
+fn mismatches(call: &CommandCall, expectation: &CommandExpectation) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    if let Some(expected) = &expectation.args {
+        if call.args != *expected {
+            let position =
+                call.args.iter().zip(expected).position(|(actual, expected)| actual != expected);
+            mismatches.push(match position {
+                Some(i) => {
+                    format!("argument {i} is {:?}, expected {:?}", call.args[i], expected[i])
+                }
+                None => format!("{} arguments, expected {}", call.args.len(), expected.len()),
+            });
+        }
+    }
+    if expectation.cwd.is_some() && call.cwd != expectation.cwd {
+        mismatches.push(format!("cwd is {:?}, expected {:?}", call.cwd, expectation.cwd));
+    }
+    if expectation.env.is_some() && call.env != expectation.env {
+        mismatches.push("env differs".to_string());
+    }
+    if expectation.stdin.as_ref().is_some_and(|stdin| *stdin != call.stdin) {
+        mismatches.push("stdin differs".to_string());
+    }
+    if expectation.timeout.is_some() && call.timeout != expectation.timeout {
+        mismatches
+            .push(format!("timeout is {:?}, expected {:?}", call.timeout, expectation.timeout));
+    }
+    mismatches
+}
+
+/// The call to the same program that comes closest to matching `expectation`
+fn nearest_miss<'a>(
+    calls: &'a [CommandCall],
+    expectation: &CommandExpectation,
+) -> Option<(&'a CommandCall, String)> {
+    calls
+        .iter()
+        .filter(|call| call.program == expectation.program)
+        .map(|call| {
+            let mismatches = mismatches(call, expectation);
+            // Prefer calls sharing a longer run of leading arguments
+            let shared = match &expectation.args {
+                Some(expected) => call
+                    .args
+                    .iter()
+                    .zip(expected)
+                    .take_while(|(actual, expected)| actual == expected)
+                    .count(),
+                None => 0,
+            };
+            (call, mismatches, shared)
+        })
+        .filter(|(_, mismatches, _)| !mismatches.is_empty())
+        .min_by_key(|(_, mismatches, shared)| (mismatches.len(), std::cmp::Reverse(*shared)))
+        .map(|(call, mismatches, _)| (call, mismatches.join(", ")))
+}
+
+#[async_trait]
+impl CommandExecutor for MockCommandExecutor {
+    async fn execute(&self, config: CommandConfig) -> Result<CommandOutput> {
+        self.record(config, false)
+    }
+
+    async fn execute_interactive(&self, config: CommandConfig) -> Result<CommandOutput> {
+        self.record(config, true)
+    }
+}
+
 // Implement CommandExecutor for &MockCommandExecutor
 #[async_trait]
 impl CommandExecutor for &MockCommandExecutor {
     async fn execute(&self, config: CommandConfig) -> Result<CommandOutput> {
         (*self).execute(config).await
     }
+
+    async fn execute_interactive(&self, config: CommandConfig) -> Result<CommandOutput> {
+        (*self).execute_interactive(config).await
+    }
 }
 
 pub struct CommandExpectationBuilder {
@@ -166,9 +412,11 @@ impl CommandExpectationBuilder {
                 args: None,
                 cwd: None,
                 env: None,
-                stdin_data: None,
+                stdin: None,
+                timeout: None,
                 times: None,
                 returns: CommandOutput::new(String::new(), String::new(), 0),
+                times_out: false,
             },
         }
     }
@@ -183,13 +431,19 @@ impl CommandExpectationBuilder {
         self
     }
 
-    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
-        self.expectation.env = Some(env);
+    pub fn with_env(mut self, env: impl Into<EnvMap>) -> Self {
+        self.expectation.env = Some(env.into());
+        self
+    }
+
+    pub fn with_stdin(mut self, stdin: StdinMode) -> Self {
+        self.expectation.stdin = Some(stdin);
         self
     }
 
-    pub fn with_stdin_data(mut self, stdin_data: &str) -> Self {
-        self.expectation.stdin_data = Some(stdin_data.to_string());
+    /// Only match calls configured with exactly this timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.expectation.timeout = Some(timeout);
         self
     }
 
@@ -211,6 +465,12 @@ impl CommandExpectationBuilder {
     pub fn returns_error(self, stderr: &str) {
         self.returns_output("", stderr, 1)
     }
+
+    /// Simulate the command exceeding its configured timeout
+    pub fn times_out(mut self) {
+        self.expectation.times_out = true;
+        self.expectations.lock().unwrap().push(self.expectation);
+    }
 }
 
 #[cfg(test)]
@@ -237,10 +497,39 @@ mod tests {
         assert_eq!(output.stdout, "hello world\n");
         assert_eq!(output.stderr, "");
         assert_eq!(output.exit_code, 0);
+        assert_eq!(output.command_line(), "echo hello world");
+        assert_eq!(output.duration, MockCommandExecutor::COMMAND_DURATION);
 
         assert!(mock.verify().is_ok());
     }
 
+    #[tokio::test]
+    async fn test_mock_execute_streaming_replays_output_line_by_line() {
+        use crate::core::command_executor::OutputStream;
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("make").returns_output("building\ndone\n", "warning\n", 2);
+        let lines = Mutex::new(Vec::new());
+
+        let output = mock
+            .execute_streaming(CommandConfig::new("make"), &|stream, line: &str| {
+                lines.lock().unwrap().push((stream, line.to_string()));
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(output.exit_code, 2);
+        assert_eq!(output.stdout, "building\ndone\n");
+        assert_eq!(
+            lines.into_inner().unwrap(),
+            [
+                (OutputStream::Stdout, "building".to_string()),
+                (OutputStream::Stdout, "done".to_string()),
+                (OutputStream::Stderr, "warning".to_string()),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_mock_command_with_cwd() {
         let mut mock = MockCommandExecutor::new();
@@ -257,6 +546,20 @@ mod tests {
         assert_eq!(output.stdout, "file1\nfile2\n");
     }
 
+    #[tokio::test]
+    async fn test_mock_times_scripts_a_sequence() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("poll").times(2).returns_output("pending", "", 0);
+        mock.expect_command("poll").returns_output("done", "", 0);
+
+        let mut outputs = Vec::new();
+        for _ in 0..4 {
+            outputs.push(mock.execute(CommandConfig::new("poll")).await.unwrap().stdout);
+        }
+        assert_eq!(outputs, ["pending", "pending", "done", "done"]);
+        assert!(mock.verify().is_ok());
+    }
+
     #[tokio::test]
     async fn test_mock_command_unexpected() {
         let mock = MockCommandExecutor::new();
@@ -287,6 +590,81 @@ mod tests {
         assert!(mock.verify().is_ok());
     }
 
+    #[tokio::test]
+    async fn test_mock_verify_lists_unused_expectations_with_nearest_miss() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["branch", "-D", "feature"]).returns_success();
+        mock.expect_command("git").with_args(&["status"]).returns_success();
+        mock.expect_command("ls").returns_success();
+
+        let config = CommandConfig::new("git").with_args(vec![
+            "branch".to_string(),
+            "-D".to_string(),
+            "main".to_string(),
+        ]);
+        assert!(mock.execute(config).await.is_err());
+        mock.execute(CommandConfig::new("ls")).await.unwrap();
+
+        let report = mock.verify().unwrap_err().to_string();
+        assert!(report.contains("Expected command 'git' to be called, but it never was"));
+        assert!(report.contains("expected: git branch -D feature"), "{report}");
+        assert!(
+            report.contains(
+                "nearest:  git branch -D main (argument 2 is \"main\", expected \"feature\")"
+            ),
+            "{report}"
+        );
+        assert!(report.contains("expected: git status"), "{report}");
+        assert!(!report.contains("'ls'"), "{report}");
+    }
+
+    #[tokio::test]
+    async fn test_mock_ordered_rejects_out_of_order_calls() {
+        let mut mock = MockCommandExecutor::new_ordered();
+        mock.expect_command("git").with_args(&["fetch"]).returns_success();
+        mock.expect_command("git").with_args(&["status"]).times(2).returns_output("clean", "", 0);
+
+        let status = CommandConfig::new("git").with_args(vec!["status".to_string()]);
+        let fetch = CommandConfig::new("git").with_args(vec!["fetch".to_string()]);
+
+        let error = mock.execute(status.clone()).await.unwrap_err().to_string();
+        assert!(error.contains("(expected git fetch)"), "{error}");
+        mock.execute(fetch.clone()).await.unwrap();
+        // fetch had its one call
+        assert!(mock.execute(fetch).await.is_err());
+        assert_eq!(mock.execute(status.clone()).await.unwrap().stdout, "clean");
+        assert_eq!(mock.execute(status.clone()).await.unwrap().stdout, "clean");
+        assert!(mock.execute(status).await.unwrap_err().to_string().contains("no more commands"));
+
+        let report = mock.verify().unwrap_err().to_string();
+        assert_eq!(report.matches("Unexpected call out of order").count(), 3, "{report}");
+    }
+
+    #[tokio::test]
+    async fn test_mock_ordered_passes_verify_in_order() {
+        let mut mock = MockCommandExecutor::new_ordered().verify_on_drop();
+        mock.expect_command("git").with_args(&["fetch"]).returns_success();
+        mock.expect_command("git").with_args(&["status"]).returns_success();
+
+        mock.execute(CommandConfig::new("git").with_args(vec!["fetch".to_string()])).await.unwrap();
+        mock.execute(CommandConfig::new("git").with_args(vec!["status".to_string()]))
+            .await
+            .unwrap();
+        assert!(mock.verify().is_ok());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Expected command 'git' to be called 2 times, but was called 0 times"
+    )]
+    fn test_mock_verify_on_drop_panics_for_unmet_expectations() {
+        let mut mock = MockCommandExecutor::new().verify_on_drop();
+        mock.expect_command("git").with_args(&["status"]).times(2).returns_success();
+        let clone = mock.clone();
+        // Dropping a clone does not verify yet
+        drop(clone);
+    }
+
     #[tokio::test]
     async fn test_mock_with_env() {
         let mut mock = MockCommandExecutor::new();
@@ -341,6 +719,45 @@ mod tests {
         assert_eq!(calls[1].args, vec!["log"]);
     }
 
+    #[tokio::test]
+    async fn test_mock_with_timeout() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["fetch"])
+            .with_timeout(Duration::from_secs(5))
+            .returns_success();
+
+        let untimed = CommandConfig::new("git").with_args(vec!["fetch".to_string()]);
+        assert!(mock.execute(untimed).await.is_err());
+
+        let timed = CommandConfig::new("git")
+            .with_args(vec!["fetch".to_string()])
+            .with_timeout(Duration::from_secs(5));
+        assert!(mock.execute(timed).await.is_ok());
+
+        let calls = mock.calls();
+        assert_eq!(calls[0].timeout, None);
+        assert_eq!(calls[1].timeout, Some(Duration::from_secs(5)));
+    }
+
+    #[tokio::test]
+    async fn test_mock_times_out() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["fetch"]).times_out();
+
+        let config = CommandConfig::new("git")
+            .with_args(vec!["fetch".to_string()])
+            .with_timeout(Duration::from_secs(30));
+
+        match mock.execute(config).await {
+            Err(PhantomError::CommandTimeout { command, timeout }) => {
+                assert_eq!(command, "git");
+                assert_eq!(timeout, Duration::from_secs(30));
+            }
+            other => panic!("Expected CommandTimeout, got {other:?}"),
+        }
+    }
+
     #[tokio::test]
     async fn test_default_impl() {
         let mock1 = MockCommandExecutor::new();
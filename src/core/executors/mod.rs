@@ -1,5 +1,9 @@
+pub mod dry_run_executor;
+#[cfg(unix)]
+pub(crate) mod foreground;
 pub mod mock_executor;
 pub mod real_executor;
 
+pub use dry_run_executor::DryRunExecutor;
 pub use mock_executor::{CommandExpectationBuilder, MockCommandExecutor};
 pub use real_executor::RealCommandExecutor;
@@ -1,10 +1,19 @@
 use async_trait::async_trait;
 use std::process::Stdio;
-use tokio::process::Command;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info};
 
-use crate::core::command_executor::{CommandConfig, CommandExecutor, CommandOutput};
+#[cfg(unix)]
+use crate::core::command_executor::exit_code_of;
+use crate::core::command_executor::{
+    traced_execution, CommandConfig, CommandExecutor, CommandOutput, OnOutputLine, OutputStream,
+    StdinMode,
+};
 use crate::core::error::PhantomError;
+#[cfg(unix)]
+use crate::core::executors::foreground::{wait_in_process_group, OnSignal};
 use crate::core::result::Result;
 use crate::core::sealed::Sealed;
 
@@ -32,118 +41,103 @@ impl Sealed for &RealCommandExecutor {}
 #[async_trait]
 impl CommandExecutor for RealCommandExecutor {
     async fn execute(&self, config: CommandConfig) -> Result<CommandOutput> {
-        info!("Executing command: {} {:?}", config.program, config.args);
+        traced_execution(config.program.clone(), config.args.clone(), run(config)).await
+    }
 
-        let mut command = Command::new(&config.program);
-        command.args(&config.args);
+    #[cfg(unix)]
+    async fn execute_interactive(&self, config: CommandConfig) -> Result<CommandOutput> {
+        let (program, args) = (config.program.clone(), config.args.clone());
+        traced_execution(program, args, super::foreground::run_in_process_group(config)).await
+    }
 
-        if let Some(ref cwd) = config.cwd {
-            command.current_dir(cwd);
+    async fn execute_streaming(
+        &self,
+        config: CommandConfig,
+        on_line: &OnOutputLine<'_>,
+    ) -> Result<CommandOutput> {
+        // Output that is passed on rather than captured has no lines to report
+        if config.inherit_output || config.stream_output {
+            return self.execute(config).await;
         }
+        let (program, args) = (config.program.clone(), config.args.clone());
+        traced_execution(program, args, run_streaming(config, on_line)).await
+    }
+}
 
-        if let Some(ref env) = config.env {
-            command.envs(env);
-        }
+/// Run `config` to completion; `execute` adds the argv, the duration and the trace event
+async fn run(config: CommandConfig) -> Result<CommandOutput> {
+    info!("Executing command: {} {:?}", config.program, config.args);
 
-        // Configure stdin based on whether stdin_data is provided
-        if config.stdin_data.is_some() {
-            command.stdin(Stdio::piped());
-        } else {
-            command.stdin(Stdio::null());
-        }
+    let mut command = build_command(&config);
 
-        // Always capture stdout and stderr for CommandOutput
+    // Capture stdout and stderr for CommandOutput unless the caller wants them passed on
+    if config.inherit_output {
+        command.stdout(Stdio::inherit());
+        command.stderr(Stdio::inherit());
+    } else if config.stream_output {
+        command.stdout(stderr_stdio());
+        command.stderr(Stdio::inherit());
+    } else {
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
+    }
 
-        // Handle execution with or without stdin_data
-        let output = if let Some(stdin_data) = config.stdin_data {
-            debug!("Executing command with stdin data: {} bytes", stdin_data.len());
+    // A child on phantom's own stdio gets its own process group, so that SIGINT and
+    // SIGTERM sent to phantom reach it and its real exit status can be reported
+    #[cfg(unix)]
+    if config.inherit_output
+        && config.timeout.is_none()
+        && !matches!(config.stdin, StdinMode::Data(_))
+    {
+        command.process_group(0);
+        let mut child = command.spawn().map_err(|e| spawn_error(&config.program, e))?;
+        let status = wait_in_process_group(&mut child, &config.program, OnSignal::Forward).await?;
+        return Ok(CommandOutput::new(String::new(), String::new(), exit_code_of(status)));
+    }
 
-            // Spawn the process to get access to stdin
-            let mut child = command.spawn().map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    PhantomError::CommandNotFound { command: config.program.clone() }
-                } else {
-                    PhantomError::ProcessExecutionError {
-                        reason: format!("Failed to spawn command '{}': {}", config.program, e),
-                    }
+    // Handle execution with or without stdin data
+    let output = if let StdinMode::Data(stdin_data) = config.stdin {
+        debug!("Executing command with stdin data: {} bytes", stdin_data.len());
+
+        // Spawn the process to get access to stdin
+        let mut child = command.spawn().map_err(|e| spawn_error(&config.program, e))?;
+        let stdin_writer = spawn_stdin_writer(&mut child, stdin_data, &config.program);
+
+        // Wait for completion with optional timeout
+        let output = if let Some(timeout) = config.timeout {
+            match tokio::time::timeout(timeout, child.wait_with_output()).await {
+                Ok(Ok(output)) => output,
+                Ok(Err(e)) => {
+                    return Err(PhantomError::ProcessExecutionError {
+                        reason: format!("Failed to wait for command '{}': {}", config.program, e),
+                    });
                 }
-            })?;
-
-            // Write stdin data
-            if let Some(mut stdin) = child.stdin.take() {
-                use tokio::io::AsyncWriteExt;
-                stdin.write_all(stdin_data.as_bytes()).await.map_err(|e| {
-                    PhantomError::ProcessExecutionError {
-                        reason: format!("Failed to write stdin to '{}': {}", config.program, e),
-                    }
-                })?;
-                stdin.shutdown().await.map_err(|e| PhantomError::ProcessExecutionError {
-                    reason: format!("Failed to close stdin for '{}': {}", config.program, e),
-                })?;
-            }
-
-            // Wait for completion with optional timeout
-            if let Some(timeout) = config.timeout {
-                match tokio::time::timeout(timeout, child.wait_with_output()).await {
-                    Ok(Ok(output)) => output,
-                    Ok(Err(e)) => {
-                        return Err(PhantomError::ProcessExecutionError {
-                            reason: format!(
-                                "Failed to wait for command '{}': {}",
-                                config.program, e
-                            ),
-                        });
-                    }
-                    Err(_) => {
-                        error!("Command timeout after {:?}", timeout);
-                        // The child process is consumed by wait_with_output, so we can't kill it
-                        // The timeout itself should cause the process to be terminated
-                        return Err(PhantomError::ProcessExecutionError {
-                            reason: format!(
-                                "Command '{}' timed out after {:?}",
-                                config.program, timeout
-                            ),
-                        });
-                    }
+                Err(_) => {
+                    error!("Command timeout after {:?}, killing process", timeout);
+                    return Err(PhantomError::CommandTimeout {
+                        command: config.program.clone(),
+                        timeout,
+                    });
                 }
-            } else {
-                child.wait_with_output().await.map_err(|e| PhantomError::ProcessExecutionError {
-                    reason: format!("Failed to wait for command '{}': {}", config.program, e),
-                })?
             }
         } else {
-            // No stdin data, use simpler output() method
-            if let Some(timeout) = config.timeout {
-                match tokio::time::timeout(timeout, command.output()).await {
-                    Ok(Ok(output)) => output,
-                    Ok(Err(e)) => {
-                        return Err(if e.kind() == std::io::ErrorKind::NotFound {
-                            PhantomError::CommandNotFound { command: config.program.clone() }
-                        } else {
-                            PhantomError::ProcessExecutionError {
-                                reason: format!(
-                                    "Failed to execute command '{}': {}",
-                                    config.program, e
-                                ),
-                            }
-                        });
-                    }
-                    Err(_) => {
-                        error!("Command timeout after {:?}, killing process", timeout);
-                        return Err(PhantomError::ProcessExecutionError {
-                            reason: format!(
-                                "Command '{}' timed out after {:?}",
-                                config.program, timeout
-                            ),
-                        });
-                    }
-                }
-            } else {
-                command.output().await.map_err(|e| {
-                    if e.kind() == std::io::ErrorKind::NotFound {
-                        PhantomError::CommandNotFound { command: config.program.clone() }
+            child.wait_with_output().await.map_err(|e| PhantomError::ProcessExecutionError {
+                reason: format!("Failed to wait for command '{}': {}", config.program, e),
+            })?
+        };
+        finish_stdin_writer(stdin_writer).await?;
+        output
+    } else {
+        // No stdin data to write, just wait for the command
+        if let Some(timeout) = config.timeout {
+            match tokio::time::timeout(timeout, run_to_completion(&mut command)).await {
+                Ok(Ok(output)) => output,
+                Ok(Err(e)) => {
+                    return Err(if e.kind() == std::io::ErrorKind::NotFound {
+                        PhantomError::CommandNotFound {
+                            command: config.program.clone(),
+                            suggestions: Vec::new(),
+                        }
                     } else {
                         PhantomError::ProcessExecutionError {
                             reason: format!(
@@ -151,25 +145,194 @@ impl CommandExecutor for RealCommandExecutor {
                                 config.program, e
                             ),
                         }
+                    });
+                }
+                Err(_) => {
+                    error!("Command timeout after {:?}, killing process", timeout);
+                    return Err(PhantomError::CommandTimeout {
+                        command: config.program.clone(),
+                        timeout,
+                    });
+                }
+            }
+        } else {
+            run_to_completion(&mut command).await.map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    PhantomError::CommandNotFound {
+                        command: config.program.clone(),
+                        suggestions: Vec::new(),
                     }
-                })?
+                } else {
+                    PhantomError::ProcessExecutionError {
+                        reason: format!("Failed to execute command '{}': {}", config.program, e),
+                    }
+                }
+            })?
+        }
+    };
+
+    let exit_code = output.status.code().unwrap_or(-1);
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+    Ok(CommandOutput::new(stdout, stderr, exit_code))
+}
+
+/// Run `config` with its output piped, passing each line to `on_line` as it arrives
+async fn run_streaming(config: CommandConfig, on_line: &OnOutputLine<'_>) -> Result<CommandOutput> {
+    info!("Streaming command: {} {:?}", config.program, config.args);
+
+    let mut command = build_command(&config);
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+    let mut child = command.spawn().map_err(|e| spawn_error(&config.program, e))?;
+    let stdin_writer = match config.stdin {
+        StdinMode::Data(ref stdin_data) => {
+            spawn_stdin_writer(&mut child, stdin_data.clone(), &config.program)
+        }
+        _ => None,
+    };
+
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let completion = async {
+        let (stdout, stderr) = tokio::try_join!(
+            read_lines(stdout, OutputStream::Stdout, on_line),
+            read_lines(stderr, OutputStream::Stderr, on_line),
+        )?;
+        Ok::<_, std::io::Error>((stdout, stderr, child.wait().await?))
+    };
+
+    let result = match config.timeout {
+        Some(timeout) => tokio::time::timeout(timeout, completion).await.map_err(|_| {
+            error!("Command timeout after {:?}, killing process", timeout);
+            PhantomError::CommandTimeout { command: config.program.clone(), timeout }
+        })?,
+        None => completion.await,
+    };
+    let (stdout, stderr, status) = result.map_err(|e| PhantomError::ProcessExecutionError {
+        reason: format!("Failed to read output of command '{}': {}", config.program, e),
+    })?;
+    finish_stdin_writer(stdin_writer).await?;
+
+    Ok(CommandOutput::new(stdout, stderr, status.code().unwrap_or(-1)))
+}
+
+/// Pass each line of `reader` to `on_line` as it arrives, returning all of it
+async fn read_lines(
+    reader: impl AsyncRead + Unpin,
+    stream: OutputStream,
+    on_line: &OnOutputLine<'_>,
+) -> std::io::Result<String> {
+    let mut reader = BufReader::new(reader);
+    let mut collected = String::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            return Ok(collected);
+        }
+        let text = String::from_utf8_lossy(&line);
+        on_line(stream, text.trim_end_matches(&['\n', '\r'][..]));
+        collected.push_str(&text);
+    }
+}
+
+/// A `Command` for `config`, with everything but stdout and stderr set up
+fn build_command(config: &CommandConfig) -> Command {
+    let mut command = Command::new(&config.program);
+    command.args(&config.args);
+
+    if let Some(ref cwd) = config.cwd {
+        command.current_dir(cwd);
+    }
+
+    for key in &config.env_remove {
+        command.env_remove(key);
+    }
+
+    if let Some(ref env) = config.env {
+        command.envs(env);
+    }
+
+    command.stdin(config.stdin.stdio());
+
+    // Dropping the pending future on timeout must not leave the child running
+    if config.timeout.is_some() {
+        command.kill_on_drop(true);
+    }
+    command
+}
+
+/// Write `data` to the child's piped stdin from a task of its own, then close it
+///
+/// The writer runs alongside the output readers, so a child that fills its
+/// stdout pipe before it has read all of stdin cannot deadlock with phantom.
+fn spawn_stdin_writer(
+    child: &mut Child,
+    data: String,
+    program: &str,
+) -> Option<JoinHandle<Result<()>>> {
+    let mut stdin = child.stdin.take()?;
+    let program = program.to_string();
+    Some(tokio::spawn(async move {
+        use tokio::io::AsyncWriteExt;
+        match stdin.write_all(data.as_bytes()).await {
+            Ok(()) => {}
+            // The child exited or closed stdin without reading all of it
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                debug!("'{program}' stopped reading stdin: {e}");
+                return Ok(());
             }
-        };
+            Err(e) => {
+                return Err(PhantomError::ProcessExecutionError {
+                    reason: format!("Failed to write stdin to '{program}': {e}"),
+                })
+            }
+        }
+        stdin.shutdown().await.map_err(|e| PhantomError::ProcessExecutionError {
+            reason: format!("Failed to close stdin for '{program}': {e}"),
+        })
+    }))
+}
 
-        let exit_code = output.status.code().unwrap_or(-1);
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+/// Wait for the writer started by [`spawn_stdin_writer`], if there is one
+async fn finish_stdin_writer(writer: Option<JoinHandle<Result<()>>>) -> Result<()> {
+    let Some(writer) = writer else {
+        return Ok(());
+    };
+    writer.await.map_err(|e| PhantomError::ProcessExecutionError {
+        reason: format!("Failed to write stdin: {e}"),
+    })?
+}
 
-        debug!(
-            "Command '{}' exited with code: {}, stdout: {} bytes, stderr: {} bytes",
-            config.program,
-            exit_code,
-            stdout.len(),
-            stderr.len()
-        );
+/// `CommandNotFound` when `program` does not exist, otherwise a spawn failure
+fn spawn_error(program: &str, e: std::io::Error) -> PhantomError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        PhantomError::CommandNotFound { command: program.to_string(), suggestions: Vec::new() }
+    } else {
+        PhantomError::ProcessExecutionError {
+            reason: format!("Failed to spawn command '{program}': {e}"),
+        }
+    }
+}
 
-        Ok(CommandOutput::new(stdout, stderr, exit_code))
+/// Run `command` and collect its output, keeping the configured stdio
+/// (`Command::output` would force both streams to be piped)
+async fn run_to_completion(command: &mut Command) -> std::io::Result<std::process::Output> {
+    command.spawn()?.wait_with_output().await
+}
+
+/// A duplicate of phantom's stderr, so streamed child stdout does not end up on phantom's stdout
+fn stderr_stdio() -> Stdio {
+    #[cfg(unix)]
+    {
+        use std::os::fd::AsFd;
+        if let Ok(fd) = std::io::stderr().as_fd().try_clone_to_owned() {
+            return Stdio::from(fd);
+        }
     }
+    Stdio::inherit()
 }
 
 // Implement CommandExecutor for &RealCommandExecutor
@@ -178,6 +341,18 @@ impl CommandExecutor for &RealCommandExecutor {
     async fn execute(&self, config: CommandConfig) -> Result<CommandOutput> {
         (*self).execute(config).await
     }
+
+    async fn execute_interactive(&self, config: CommandConfig) -> Result<CommandOutput> {
+        (*self).execute_interactive(config).await
+    }
+
+    async fn execute_streaming(
+        &self,
+        config: CommandConfig,
+        on_line: &OnOutputLine<'_>,
+    ) -> Result<CommandOutput> {
+        (*self).execute_streaming(config, on_line).await
+    }
 }
 
 #[cfg(test)]
@@ -201,6 +376,64 @@ mod tests {
         assert!(output.stderr.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_execute_records_argv_and_duration() {
+        let config = CommandConfig::new("sleep").with_args(vec!["0.05".to_string()]);
+
+        let output = RealCommandExecutor::new().execute(config).await.unwrap();
+
+        assert_eq!(output.command_line(), "sleep 0.05");
+        assert!(output.duration >= Duration::from_millis(50), "{:?}", output.duration);
+    }
+
+    #[tokio::test]
+    async fn test_execute_streamed_output_is_not_captured() {
+        let executor = RealCommandExecutor::new();
+        let config = CommandConfig::new("sh")
+            .with_args(vec!["-c".to_string(), "echo out; echo err >&2; exit 3".to_string()])
+            .with_streamed_output();
+
+        let output = executor.execute(config).await.unwrap();
+        assert_eq!(output.exit_code, 3);
+        assert!(output.stdout.is_empty());
+        assert!(output.stderr.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_passes_lines_as_they_are_written() {
+        let lines = std::sync::Mutex::new(Vec::new());
+        let on_line = |stream: OutputStream, line: &str| {
+            lines.lock().unwrap().push((stream, line.to_string()));
+        };
+        // The second line is only written once the first has been read
+        let config = CommandConfig::new("sh").with_args(vec![
+            "-c".to_string(),
+            "echo one; echo oops >&2; sleep 0.1; printf 'two\\r\\n'; exit 3".to_string(),
+        ]);
+
+        let output = RealCommandExecutor::new().execute_streaming(config, &on_line).await.unwrap();
+
+        assert_eq!(output.exit_code, 3);
+        assert_eq!(output.stdout, "one\ntwo\r\n");
+        assert_eq!(output.stderr, "oops\n");
+        assert_eq!(output.program, "sh");
+        let lines = lines.into_inner().unwrap();
+        assert_eq!(lines.last(), Some(&(OutputStream::Stdout, "two".to_string())));
+        assert!(lines.contains(&(OutputStream::Stdout, "one".to_string())));
+        assert!(lines.contains(&(OutputStream::Stderr, "oops".to_string())));
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_with_timeout() {
+        let config = CommandConfig::new("sh")
+            .with_args(vec!["-c".to_string(), "echo started; sleep 10".to_string()])
+            .with_timeout(Duration::from_millis(100));
+
+        let result = RealCommandExecutor::new().execute_streaming(config, &|_, _| {}).await;
+        assert!(matches!(result, Err(PhantomError::CommandTimeout { .. })));
+    }
+
     #[tokio::test]
     async fn test_execute_with_cwd() {
         let executor = RealCommandExecutor::new();
@@ -245,7 +478,7 @@ mod tests {
         let result = executor.execute(config).await;
         assert!(result.is_err());
         match result.unwrap_err() {
-            PhantomError::CommandNotFound { command } => {
+            PhantomError::CommandNotFound { command, .. } => {
                 assert_eq!(command, "nonexistent-command-xyz123");
             }
             _ => panic!("Expected CommandNotFound error"),
@@ -264,6 +497,22 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("timed out"));
     }
 
+    #[tokio::test]
+    async fn test_execute_timeout_kills_process() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let marker = temp_dir.path().join("finished");
+        let executor = RealCommandExecutor::new();
+        let config = CommandConfig::new("sh")
+            .with_args(vec!["-c".to_string(), format!("sleep 0.5 && touch '{}'", marker.display())])
+            .with_timeout(Duration::from_millis(50));
+
+        let result = executor.execute(config).await;
+        assert!(matches!(result, Err(PhantomError::CommandTimeout { .. })));
+
+        tokio::time::sleep(Duration::from_millis(800)).await;
+        assert!(!marker.exists(), "timed out process should have been killed");
+    }
+
     #[tokio::test]
     async fn test_execute_non_zero_exit() {
         let executor = RealCommandExecutor::new();
@@ -293,7 +542,8 @@ mod tests {
     #[tokio::test]
     async fn test_execute_with_stdin_data() {
         let executor = RealCommandExecutor::new();
-        let config = CommandConfig::new("cat").with_stdin_data("hello from stdin".to_string());
+        let config =
+            CommandConfig::new("cat").with_stdin(StdinMode::Data("hello from stdin".to_string()));
 
         let result = executor.execute(config).await;
         assert!(result.is_ok());
@@ -309,7 +559,7 @@ mod tests {
         let executor = RealCommandExecutor::new();
         let config = CommandConfig::new("grep")
             .with_args(vec!["hello".to_string()])
-            .with_stdin_data("hello world\ngoodbye world\nhello again".to_string());
+            .with_stdin(StdinMode::Data("hello world\ngoodbye world\nhello again".to_string()));
 
         let result = executor.execute(config).await;
         assert!(result.is_ok());
@@ -321,13 +571,43 @@ mod tests {
         assert!(!output.stdout.contains("goodbye"));
     }
 
+    /// Writes more than a pipe holds before reading any of stdin
+    fn output_before_input() -> CommandConfig {
+        CommandConfig::new("sh")
+            .with_args(vec![
+                "-c".to_string(),
+                "head -c 200000 /dev/zero; wc -c | tr -d ' ' >&2".to_string(),
+            ])
+            .with_stdin(StdinMode::Data("x".repeat(200_000)))
+            .with_timeout(Duration::from_secs(10))
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_stdin_data_does_not_deadlock() {
+        let output = RealCommandExecutor::new().execute(output_before_input()).await.unwrap();
+
+        assert_eq!(output.stdout.len(), 200_000);
+        assert_eq!(output.stderr.trim(), "200000");
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_with_stdin_data_does_not_deadlock() {
+        let output = RealCommandExecutor::new()
+            .execute_streaming(output_before_input(), &|_, _| {})
+            .await
+            .unwrap();
+
+        assert_eq!(output.stdout.len(), 200_000);
+        assert_eq!(output.stderr.trim(), "200000");
+    }
+
     #[tokio::test]
     async fn test_execute_with_stdin_data_timeout() {
         let executor = RealCommandExecutor::new();
         // Use a command that will block waiting for more input
         let config = CommandConfig::new("sh")
             .with_args(vec!["-c".to_string(), "cat && sleep 10".to_string()])
-            .with_stdin_data("test".to_string())
+            .with_stdin(StdinMode::Data("test".to_string()))
             .with_timeout(Duration::from_millis(100));
 
         let result = executor.execute(config).await;
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use std::fs::{Metadata, Permissions};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use tokio::fs::DirEntry;
 
 use crate::core::result::Result;
@@ -17,12 +18,18 @@ pub trait FileSystem: Sealed + Send + Sync {
     /// Get metadata for a path
     async fn metadata(&self, path: &Path) -> Result<Metadata>;
 
+    /// Get the last modification time of a path
+    async fn modified(&self, path: &Path) -> Result<SystemTime>;
+
     /// Check if a path is a file
     async fn is_file(&self, path: &Path) -> Result<bool>;
 
     /// Check if a path is a directory
     async fn is_dir(&self, path: &Path) -> Result<bool>;
 
+    /// Check if a path is a file with an execute permission bit set
+    async fn is_executable(&self, path: &Path) -> Result<bool>;
+
     /// Create a directory
     async fn create_dir(&self, path: &Path) -> Result<()>;
 
@@ -32,15 +39,31 @@ pub trait FileSystem: Sealed + Send + Sync {
     /// Remove a directory and all its contents
     async fn remove_dir_all(&self, path: &Path) -> Result<()>;
 
+    /// Remove an empty directory; fails if it has entries
+    async fn remove_dir(&self, path: &Path) -> Result<()>;
+
     /// Read a directory
     async fn read_dir(&self, path: &Path) -> Result<Vec<DirEntry>>;
 
+    /// List the paths of a directory's entries
+    async fn list_dir(&self, path: &Path) -> Result<Vec<PathBuf>>;
+
     /// Read a file to string
     async fn read_to_string(&self, path: &Path) -> Result<String>;
 
     /// Write a string to a file
     async fn write(&self, path: &Path, contents: &str) -> Result<()>;
 
+    /// Replace a file's contents so that readers see either the old or the new
+    /// contents in full, even if the process crashes mid-write
+    async fn write_atomic(&self, path: &Path, contents: &[u8]) -> Result<()>;
+
+    /// Rename a file or directory
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()>;
+
+    /// Remove a file
+    async fn remove_file(&self, path: &Path) -> Result<()>;
+
     /// Copy a file
     async fn copy(&self, from: &Path, to: &Path) -> Result<u64>;
 
@@ -1,8 +1,9 @@
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::{Metadata, Permissions};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use tokio::fs::DirEntry;
 
 use crate::core::error::PhantomError;
@@ -14,14 +15,21 @@ use crate::core::sealed::Sealed;
 pub enum FileSystemOperation {
     Exists,
     Metadata,
+    Modified,
     IsFile,
     IsDir,
+    IsExecutable,
     CreateDir,
     CreateDirAll,
     RemoveDirAll,
+    RemoveDir,
     ReadDir,
+    ListDir,
     ReadToString,
     Write,
+    WriteAtomic,
+    Rename,
+    RemoveFile,
     Copy,
     SetPermissions,
     CurrentDir,
@@ -50,6 +58,7 @@ pub enum MockResult {
     Unit,
     PathBuf(PathBuf),
     OptionPathBuf(Option<PathBuf>),
+    SystemTime(SystemTime),
 }
 
 #[derive(Debug, Clone)]
@@ -63,9 +72,19 @@ pub struct MockDirEntry {
     pub path: PathBuf,
 }
 
+/// An operation the mock was asked to perform, whether or not an expectation answered it
+#[derive(Debug, Clone)]
+pub struct FileSystemCall {
+    pub operation: FileSystemOperation,
+    pub path: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone)]
 pub struct MockFileSystem {
     expectations: Arc<Mutex<HashMap<String, Vec<FileSystemExpectation>>>>,
+    calls: Arc<Mutex<Vec<FileSystemCall>>>,
+    /// Directories that answer `is_dir` and `exists` with true any number of times
+    default_dirs: Arc<Mutex<HashSet<PathBuf>>>,
     current_dir: Arc<Mutex<PathBuf>>,
 }
 
@@ -76,10 +95,43 @@ impl MockFileSystem {
     pub fn new() -> Self {
         Self {
             expectations: Arc::new(Mutex::new(HashMap::new())),
+            calls: Arc::new(Mutex::new(Vec::new())),
+            default_dirs: Arc::new(Mutex::new(HashSet::new())),
             current_dir: Arc::new(Mutex::new(PathBuf::from("/mock/dir"))),
         }
     }
 
+    /// A mock where each of `dirs` exists and is a directory, however often it is asked
+    ///
+    /// Expectations added with [`expect`](Self::expect) are still answered first.
+    pub fn with_default_dirs<P: AsRef<Path>>(dirs: &[P]) -> Self {
+        let mock = Self::new();
+        mock.add_default_dirs(dirs);
+        mock
+    }
+
+    /// Answer `is_dir` and `exists` for each of `dirs` with true from now on
+    pub fn add_default_dirs<P: AsRef<Path>>(&self, dirs: &[P]) {
+        let mut default_dirs = self.default_dirs.lock().unwrap();
+        default_dirs.extend(dirs.iter().map(|dir| dir.as_ref().to_path_buf()));
+    }
+
+    /// Panic listing every expectation that was never used
+    pub fn verify_all_consumed(&self) {
+        let expectations = self.expectations.lock().unwrap();
+        let mut unused: Vec<_> = expectations
+            .iter()
+            .filter(|(_, remaining)| !remaining.is_empty())
+            .map(|(key, remaining)| format!("{key} ({} left)", remaining.len()))
+            .collect();
+        unused.sort();
+        assert!(unused.is_empty(), "Unused filesystem expectations:\n  {}", unused.join("\n  "));
+    }
+
+    pub fn calls(&self) -> Vec<FileSystemCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
     pub fn expect(&self, expectation: FileSystemExpectation) {
         let key = self.expectation_key(&expectation);
         let mut expectations = self.expectations.lock().unwrap();
@@ -88,13 +140,13 @@ impl MockFileSystem {
 
     fn expectation_key(&self, expectation: &FileSystemExpectation) -> String {
         match &expectation.operation {
-            FileSystemOperation::Copy => {
+            FileSystemOperation::Copy | FileSystemOperation::Rename => {
                 format!(
                     "{:?}:{:?}:{:?}",
                     expectation.operation, expectation.from_path, expectation.to_path
                 )
             }
-            FileSystemOperation::Write => {
+            FileSystemOperation::Write | FileSystemOperation::WriteAtomic => {
                 format!(
                     "{:?}:{:?}:{:?}",
                     expectation.operation, expectation.path, expectation.contents
@@ -115,6 +167,10 @@ impl MockFileSystem {
         to_path: Option<&Path>,
         contents: Option<&str>,
     ) -> Result<FileSystemExpectation> {
+        self.calls.lock().unwrap().push(FileSystemCall {
+            operation: operation.clone(),
+            path: path.map(|p| p.to_path_buf()),
+        });
         let expectation = FileSystemExpectation {
             operation,
             path: path.map(|p| p.to_path_buf()),
@@ -132,6 +188,11 @@ impl MockFileSystem {
                 return Ok(vec.remove(0));
             }
         }
+        if matches!(expectation.operation, FileSystemOperation::IsDir | FileSystemOperation::Exists)
+            && path.is_some_and(|path| self.default_dirs.lock().unwrap().contains(path))
+        {
+            return Ok(FileSystemExpectation { result: Ok(MockResult::Bool(true)), ..expectation });
+        }
 
         Err(PhantomError::FileOperationFailed {
             operation: format!("{:?}", expectation.operation),
@@ -173,6 +234,19 @@ impl FileSystem for MockFileSystem {
         }
     }
 
+    async fn modified(&self, path: &Path) -> Result<SystemTime> {
+        let expectation =
+            self.find_expectation(FileSystemOperation::Modified, Some(path), None, None, None)?;
+        match expectation.result? {
+            MockResult::SystemTime(time) => Ok(time),
+            _ => Err(PhantomError::FileOperationFailed {
+                operation: "modified".to_string(),
+                path: path.to_path_buf(),
+                reason: "Unexpected result type".to_string(),
+            }),
+        }
+    }
+
     async fn is_file(&self, path: &Path) -> Result<bool> {
         let expectation =
             self.find_expectation(FileSystemOperation::IsFile, Some(path), None, None, None)?;
@@ -199,6 +273,19 @@ impl FileSystem for MockFileSystem {
         }
     }
 
+    async fn is_executable(&self, path: &Path) -> Result<bool> {
+        let expectation =
+            self.find_expectation(FileSystemOperation::IsExecutable, Some(path), None, None, None)?;
+        match expectation.result? {
+            MockResult::Bool(b) => Ok(b),
+            _ => Err(PhantomError::FileOperationFailed {
+                operation: "is_executable".to_string(),
+                path: path.to_path_buf(),
+                reason: "Unexpected result type".to_string(),
+            }),
+        }
+    }
+
     async fn create_dir(&self, path: &Path) -> Result<()> {
         let expectation =
             self.find_expectation(FileSystemOperation::CreateDir, Some(path), None, None, None)?;
@@ -238,6 +325,19 @@ impl FileSystem for MockFileSystem {
         }
     }
 
+    async fn remove_dir(&self, path: &Path) -> Result<()> {
+        let expectation =
+            self.find_expectation(FileSystemOperation::RemoveDir, Some(path), None, None, None)?;
+        match expectation.result? {
+            MockResult::Unit => Ok(()),
+            _ => Err(PhantomError::FileOperationFailed {
+                operation: "remove_dir".to_string(),
+                path: path.to_path_buf(),
+                reason: "Unexpected result type".to_string(),
+            }),
+        }
+    }
+
     async fn read_dir(&self, path: &Path) -> Result<Vec<DirEntry>> {
         let expectation =
             self.find_expectation(FileSystemOperation::ReadDir, Some(path), None, None, None)?;
@@ -255,6 +355,21 @@ impl FileSystem for MockFileSystem {
         }
     }
 
+    async fn list_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let expectation =
+            self.find_expectation(FileSystemOperation::ListDir, Some(path), None, None, None)?;
+        match expectation.result? {
+            MockResult::DirEntries(entries) => {
+                Ok(entries.into_iter().map(|entry| entry.path).collect())
+            }
+            _ => Err(PhantomError::FileOperationFailed {
+                operation: "list_dir".to_string(),
+                path: path.to_path_buf(),
+                reason: "Unexpected result type".to_string(),
+            }),
+        }
+    }
+
     async fn read_to_string(&self, path: &Path) -> Result<String> {
         let expectation =
             self.find_expectation(FileSystemOperation::ReadToString, Some(path), None, None, None)?;
@@ -286,6 +401,51 @@ impl FileSystem for MockFileSystem {
         }
     }
 
+    async fn write_atomic(&self, path: &Path, contents: &[u8]) -> Result<()> {
+        let contents = String::from_utf8_lossy(contents);
+        let expectation = self.find_expectation(
+            FileSystemOperation::WriteAtomic,
+            Some(path),
+            None,
+            None,
+            Some(&contents),
+        )?;
+        match expectation.result? {
+            MockResult::Unit => Ok(()),
+            _ => Err(PhantomError::FileOperationFailed {
+                operation: "write_atomic".to_string(),
+                path: path.to_path_buf(),
+                reason: "Unexpected result type".to_string(),
+            }),
+        }
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let expectation =
+            self.find_expectation(FileSystemOperation::Rename, None, Some(from), Some(to), None)?;
+        match expectation.result? {
+            MockResult::Unit => Ok(()),
+            _ => Err(PhantomError::FileOperationFailed {
+                operation: "rename".to_string(),
+                path: from.to_path_buf(),
+                reason: "Unexpected result type".to_string(),
+            }),
+        }
+    }
+
+    async fn remove_file(&self, path: &Path) -> Result<()> {
+        let expectation =
+            self.find_expectation(FileSystemOperation::RemoveFile, Some(path), None, None, None)?;
+        match expectation.result? {
+            MockResult::Unit => Ok(()),
+            _ => Err(PhantomError::FileOperationFailed {
+                operation: "remove_file".to_string(),
+                path: path.to_path_buf(),
+                reason: "Unexpected result type".to_string(),
+            }),
+        }
+    }
+
     async fn copy(&self, from: &Path, to: &Path) -> Result<u64> {
         let expectation =
             self.find_expectation(FileSystemOperation::Copy, None, Some(from), Some(to), None)?;
@@ -441,4 +601,48 @@ mod tests {
         let result = mock_fs.copy(Path::new("/source/file.txt"), Path::new("/dest/file.txt")).await;
         assert_eq!(result.unwrap(), 1024);
     }
+
+    #[tokio::test]
+    async fn test_mock_filesystem_default_dirs() {
+        let mock_fs = MockFileSystem::with_default_dirs(&["/repo/worktree"]);
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/worktree")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(false)),
+        });
+
+        // The one-shot expectation answers first, then the default takes over
+        let worktree = Path::new("/repo/worktree");
+        assert!(!mock_fs.is_dir(worktree).await.unwrap());
+        for _ in 0..3 {
+            assert!(mock_fs.is_dir(worktree).await.unwrap());
+            assert!(mock_fs.exists(worktree).await.unwrap());
+        }
+        assert!(mock_fs.is_file(worktree).await.is_err());
+        assert!(mock_fs.is_dir(Path::new("/repo/other")).await.is_err());
+        mock_fs.verify_all_consumed();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Modified:Some(\"/repo/file\") (1 left)")]
+    async fn test_mock_filesystem_verify_all_consumed() {
+        let mock_fs = MockFileSystem::new();
+        let modified = SystemTime::UNIX_EPOCH;
+        for _ in 0..2 {
+            mock_fs.expect(FileSystemExpectation {
+                operation: FileSystemOperation::Modified,
+                path: Some(PathBuf::from("/repo/file")),
+                from_path: None,
+                to_path: None,
+                contents: None,
+                result: Ok(MockResult::SystemTime(modified)),
+            });
+        }
+
+        assert_eq!(mock_fs.modified(Path::new("/repo/file")).await.unwrap(), modified);
+        mock_fs.verify_all_consumed();
+    }
 }
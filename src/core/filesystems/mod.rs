@@ -1,5 +1,5 @@
 pub mod mock_filesystem;
 pub mod real_filesystem;
 
-pub use mock_filesystem::{FileSystemExpectation, MockFileSystem};
+pub use mock_filesystem::{FileSystemCall, FileSystemExpectation, MockFileSystem};
 pub use real_filesystem::RealFileSystem;
@@ -1,6 +1,9 @@
 use async_trait::async_trait;
-use std::fs::{Metadata, Permissions};
+use std::fs::{Metadata, OpenOptions, Permissions};
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
 use tokio::fs::{self, DirEntry};
 
 use crate::core::error::PhantomError;
@@ -34,6 +37,15 @@ impl FileSystem for RealFileSystem {
         })
     }
 
+    async fn modified(&self, path: &Path) -> Result<SystemTime> {
+        let metadata = self.metadata(path).await?;
+        metadata.modified().map_err(|e| PhantomError::FileOperationFailed {
+            operation: "modified".to_string(),
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })
+    }
+
     async fn is_file(&self, path: &Path) -> Result<bool> {
         match self.metadata(path).await {
             Ok(meta) => Ok(meta.is_file()),
@@ -48,6 +60,19 @@ impl FileSystem for RealFileSystem {
         }
     }
 
+    async fn is_executable(&self, path: &Path) -> Result<bool> {
+        match self.metadata(path).await {
+            #[cfg(unix)]
+            Ok(meta) => {
+                use std::os::unix::fs::PermissionsExt;
+                Ok(meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+            }
+            #[cfg(not(unix))]
+            Ok(meta) => Ok(meta.is_file()),
+            Err(_) => Ok(false),
+        }
+    }
+
     async fn create_dir(&self, path: &Path) -> Result<()> {
         fs::create_dir(path).await.map_err(|e| PhantomError::FileOperationFailed {
             operation: "create_dir".to_string(),
@@ -72,6 +97,14 @@ impl FileSystem for RealFileSystem {
         })
     }
 
+    async fn remove_dir(&self, path: &Path) -> Result<()> {
+        fs::remove_dir(path).await.map_err(|e| PhantomError::FileOperationFailed {
+            operation: "remove_dir".to_string(),
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })
+    }
+
     async fn read_dir(&self, path: &Path) -> Result<Vec<DirEntry>> {
         let mut entries = Vec::new();
         let mut dir = fs::read_dir(path).await.map_err(|e| PhantomError::FileOperationFailed {
@@ -93,6 +126,11 @@ impl FileSystem for RealFileSystem {
         Ok(entries)
     }
 
+    async fn list_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let entries = self.read_dir(path).await?;
+        Ok(entries.into_iter().map(|entry| entry.path()).collect())
+    }
+
     async fn read_to_string(&self, path: &Path) -> Result<String> {
         fs::read_to_string(path).await.map_err(|e| PhantomError::FileOperationFailed {
             operation: "read_to_string".to_string(),
@@ -109,6 +147,42 @@ impl FileSystem for RealFileSystem {
         })
     }
 
+    async fn write_atomic(&self, path: &Path, contents: &[u8]) -> Result<()> {
+        let target = path.to_path_buf();
+        let contents = contents.to_vec();
+        tokio::task::spawn_blocking(move || {
+            let temp = write_temp_file(&target, &contents)?;
+            commit_temp_file(&temp, &target)
+        })
+        .await
+        .map_err(|e| PhantomError::FileOperationFailed {
+            operation: "write_atomic".to_string(),
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })?
+        .map_err(|e| PhantomError::FileOperationFailed {
+            operation: "write_atomic".to_string(),
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        fs::rename(from, to).await.map_err(|e| PhantomError::FileOperationFailed {
+            operation: format!("rename to {to:?}"),
+            path: from.to_path_buf(),
+            reason: e.to_string(),
+        })
+    }
+
+    async fn remove_file(&self, path: &Path) -> Result<()> {
+        fs::remove_file(path).await.map_err(|e| PhantomError::FileOperationFailed {
+            operation: "remove_file".to_string(),
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })
+    }
+
     async fn copy(&self, from: &Path, to: &Path) -> Result<u64> {
         fs::copy(from, to).await.map_err(|e| PhantomError::FileOperationFailed {
             operation: format!("copy from {from:?} to {to:?}"),
@@ -156,8 +230,128 @@ impl FileSystem for RealFileSystem {
     }
 }
 
+/// Distinguishes temp files written concurrently by the same process
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Write `contents` to a fresh temp file next to `target` and flush it to disk
+fn write_temp_file(target: &Path, contents: &[u8]) -> std::io::Result<PathBuf> {
+    let dir = target.parent().unwrap_or(Path::new("."));
+    let file_name = target.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+    let temp = dir.join(format!(
+        ".{file_name}.tmp-{}-{}",
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result =
+        OpenOptions::new().write(true).create_new(true).open(&temp).and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        });
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e);
+    }
+    Ok(temp)
+}
+
+/// Move a flushed temp file over `target` and persist the rename
+fn commit_temp_file(temp: &Path, target: &Path) -> std::io::Result<()> {
+    if let Err(e) = std::fs::rename(temp, target) {
+        let _ = std::fs::remove_file(temp);
+        return Err(e);
+    }
+    sync_parent_dir(target)
+}
+
+/// Flush a directory entry change to disk; directories cannot be opened for syncing on Windows
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> std::io::Result<()> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    std::fs::File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
 impl Default for RealFileSystem {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_is_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let script = temp_dir.path().join("hook");
+        let fs = RealFileSystem::new();
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+
+        assert!(!fs.is_executable(&script).await.unwrap());
+        std::fs::set_permissions(&script, Permissions::from_mode(0o755)).unwrap();
+        assert!(fs.is_executable(&script).await.unwrap());
+        assert!(!fs.is_executable(temp_dir.path()).await.unwrap());
+        assert!(!fs.is_executable(&temp_dir.path().join("missing")).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_modified() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("state.json");
+        let fs = RealFileSystem::new();
+        let file = std::fs::File::create(&path).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        file.set_modified(modified).unwrap();
+
+        assert_eq!(fs.modified(&path).await.unwrap(), modified);
+        assert!(fs.modified(&temp_dir.path().join("missing")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_write_atomic_replaces_contents() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("state.json");
+        let fs = RealFileSystem::new();
+
+        fs.write_atomic(&path, b"first").await.unwrap();
+        fs.write_atomic(&path, b"second").await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+        // No temp files are left behind
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_atomic_write_leaves_original_untouched() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("state.json");
+        let fs = RealFileSystem::new();
+        fs.write_atomic(&path, br#"{"baseRef":"main"}"#).await.unwrap();
+
+        // Crash after a partial write: the temp file is truncated and never renamed
+        let temp = write_temp_file(&path, br#"{"baseRef":"develop"}"#).unwrap();
+        OpenOptions::new().write(true).open(&temp).unwrap().set_len(5).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), r#"{"baseRef":"main"}"#);
+        assert_ne!(temp, path);
+        assert_eq!(temp.parent(), path.parent());
+    }
+
+    #[tokio::test]
+    async fn test_write_atomic_missing_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("missing").join("state.json");
+
+        let result = RealFileSystem::new().write_atomic(&path, b"data").await;
+        assert!(matches!(result, Err(PhantomError::FileOperationFailed { .. })));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod command_executor;
 pub mod const_utils;
+pub mod env_map;
+pub mod environment;
 pub mod error;
 pub mod executors;
 pub mod exit_handler;
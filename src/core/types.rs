@@ -1,3 +1,4 @@
+use crate::git::const_utils::is_null_oid;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -11,7 +12,20 @@ pub struct Worktree {
     pub is_bare: bool,
     pub is_detached: bool,
     pub is_locked: bool,
+    /// Reason given to `git worktree lock --reason`
+    #[serde(default)]
+    pub locked_reason: Option<String>,
     pub is_prunable: bool,
+    /// Why git considers the worktree prunable, such as a missing directory
+    #[serde(default)]
+    pub prunable_reason: Option<String>,
+}
+
+impl Worktree {
+    /// Whether this worktree's HEAD points at a branch with no commits yet
+    pub fn is_unborn(&self) -> bool {
+        !self.is_bare && is_null_oid(&self.commit)
+    }
 }
 
 /// Git configuration
@@ -68,7 +82,9 @@ mod tests {
             is_bare: false,
             is_detached: false,
             is_locked: false,
+            locked_reason: None,
             is_prunable: false,
+            prunable_reason: None,
         };
 
         assert_eq!(worktree.name, "feature-branch");
@@ -91,7 +107,9 @@ mod tests {
             is_bare: true,
             is_detached: true,
             is_locked: false,
+            locked_reason: None,
             is_prunable: true,
+            prunable_reason: None,
         };
 
         // Serialize
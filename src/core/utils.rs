@@ -1,6 +1,11 @@
 use crate::{PhantomError, Result};
-use std::path::{Path, PathBuf};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
 use std::process::ExitCode;
+use std::time::{Duration, Instant};
 use tracing::error;
 
 /// Get the exit code for a PhantomError as an integer
@@ -9,24 +14,34 @@ fn error_to_exit_code_int(error: &PhantomError) -> i32 {
         PhantomError::Git { exit_code, .. } => *exit_code,
         PhantomError::NotInGitRepository => 128,
         PhantomError::WorktreeExists { .. } => 2,
+        PhantomError::WorktreeNameCollision { .. } => 2,
         PhantomError::WorktreeNotFound { .. } => 3,
         PhantomError::BranchNotFound { .. } => 4,
+        PhantomError::BranchNotFoundAnywhere { .. } => 4,
+        PhantomError::BranchExists { .. } => 4,
+        PhantomError::GitNotInstalled => 8,
         PhantomError::InvalidWorktreeName { .. } => 5,
+        PhantomError::InvalidBranchName { .. } => 5,
         PhantomError::ConfigNotFound { .. } => 6,
         PhantomError::ConfigInvalid { .. } => 6,
         PhantomError::MultiplexerNotFound { .. } => 7,
-        PhantomError::CommandNotFound { .. } => 8,
+        PhantomError::CommandNotFound { .. } | PhantomError::ShellNotFound { .. } => 8,
         PhantomError::ProcessFailed { .. } => 8,
         PhantomError::ProcessExecutionError { .. } => 8,
+        PhantomError::CommandTimeout { .. } => 8,
+        PhantomError::FetchFailed { .. } => 8,
         PhantomError::UnsupportedFeature { .. } => 9,
         PhantomError::Io(_) => 10,
         PhantomError::Json(_) => 11,
         PhantomError::WorktreeDirectoryCreationFailed { .. } => 12,
         PhantomError::WorktreeHasUncommittedChanges { .. } => 12,
         PhantomError::CannotDeleteCurrent { .. } => 12,
+        PhantomError::WorktreeLocked { .. } => 12,
         PhantomError::ValidationFailed { .. } => 13,
+        PhantomError::NoCommits => 13,
         PhantomError::FileOperationFailed { .. } => 14,
         PhantomError::InvalidPath { .. } => 15,
+        PhantomError::CreateRolledBack { error, .. } => error_to_exit_code_int(error),
     }
 }
 
@@ -51,15 +66,222 @@ pub fn ensure_absolute_path(path: &Path) -> Result<PathBuf> {
     }
 }
 
+/// The form of `path` to compare with other paths
+///
+/// Symlinks are resolved, so `/var/...` and `/private/var/...` on macOS compare
+/// equal. When `path` does not exist, its longest existing ancestor is resolved
+/// and the rest is appended after dropping `.` and resolving `..` lexically.
+/// Trailing slashes never matter.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+
+    let mut lexical = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(lexical.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                lexical.pop();
+            }
+            component => lexical.push(component),
+        }
+    }
+
+    let mut missing = Vec::new();
+    let mut existing = lexical.as_path();
+    while let Some(parent) = existing.parent() {
+        if let Ok(canonical) = existing.canonicalize() {
+            return missing.iter().rev().fold(canonical, |path, name| path.join(name));
+        }
+        missing.extend(existing.file_name());
+        existing = parent;
+    }
+    lexical
+}
+
+/// Whether `a` and `b` name the same location once normalized
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    a == b || normalize_path(a) == normalize_path(b)
+}
+
 /// Check if a command exists in PATH
 pub fn command_exists(command: &str) -> bool {
     which::which(command).is_ok()
 }
 
+/// Find `command` in the directories of a PATH value, the way a shell would
+///
+/// `extensions` is a PATHEXT value such as `.COM;.EXE;.BAT;.CMD`, whose entries are
+/// tried after the bare name, so on Windows `cargo` is found as `cargo.exe`.
+pub fn find_in_path(command: &str, path: &OsStr, extensions: Option<&str>) -> Option<PathBuf> {
+    if command.is_empty() {
+        return None;
+    }
+    let extensions: Vec<&str> = extensions
+        .map_or_else(Vec::new, |exts| exts.split(';').filter(|e| !e.is_empty()).collect());
+    std::env::split_paths(path).find_map(|dir| {
+        std::iter::once(dir.join(command))
+            .chain(extensions.iter().map(|ext| dir.join(format!("{command}{ext}"))))
+            .find(|candidate| is_executable_file(candidate))
+    })
+}
+
+/// Executables in a PATH value whose name is within two edits of `command`, closest first
+pub fn similar_commands(command: &str, path: &OsStr, limit: usize) -> Vec<String> {
+    const MAX_DISTANCE: usize = 2;
+    let wanted = command.chars().count();
+    let mut found: Vec<(usize, String)> = Vec::new();
+
+    for dir in std::env::split_paths(path) {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let Ok(name) = entry.file_name().into_string() else { continue };
+            if name.chars().count().abs_diff(wanted) > MAX_DISTANCE
+                || found.iter().any(|(_, seen)| *seen == name)
+            {
+                continue;
+            }
+            let distance = edit_distance(command, &name);
+            if distance > 0 && distance <= MAX_DISTANCE && is_executable_file(&entry.path()) {
+                found.push((distance, name));
+            }
+        }
+    }
+
+    found.sort();
+    found.into_iter().take(limit).map(|(_, name)| name).collect()
+}
+
+/// Edits between two strings, counting a swap of neighbouring characters as one
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best =
+                (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// A regular file (after following symlinks) that can be executed
+#[cfg(unix)]
+fn is_executable_file(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+/// A regular file (after following symlinks) that can be executed
+#[cfg(not(unix))]
+fn is_executable_file(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Wall-clock time spent in the named sections of one command, in the order they first ran
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timings {
+    sections: Vec<(String, Duration)>,
+}
+
+impl Timings {
+    /// Add `elapsed` to the section `label`, so a section timed twice is summed
+    pub fn record(&mut self, label: &str, elapsed: Duration) {
+        match self.sections.iter_mut().find(|(name, _)| name == label) {
+            Some((_, total)) => *total += elapsed,
+            None => self.sections.push((label.to_string(), elapsed)),
+        }
+    }
+
+    /// The timed sections with their total duration
+    pub fn sections(&self) -> &[(String, Duration)] {
+        &self.sections
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Milliseconds per section, as reported under `timings_ms` in JSON output
+    pub fn to_millis(&self) -> BTreeMap<String, u64> {
+        self.sections
+            .iter()
+            .map(|(label, elapsed)| (label.clone(), elapsed.as_millis() as u64))
+            .collect()
+    }
+}
+
+tokio::task_local! {
+    static TIMINGS: RefCell<Timings>;
+}
+
+/// Run `future`, collecting every section timed with [`timed`] while it runs
+pub async fn collect_timings<T>(future: impl Future<Output = T>) -> (T, Timings) {
+    TIMINGS
+        .scope(RefCell::new(Timings::default()), async {
+            let value = future.await;
+            (value, TIMINGS.with(|timings| timings.borrow().clone()))
+        })
+        .await
+}
+
+/// Await `future` and record its duration under `label`
+///
+/// A no-op outside [`collect_timings`], so library code can time sections unconditionally.
+pub async fn timed<T>(label: &str, future: impl Future<Output = T>) -> T {
+    let start = Instant::now();
+    let value = future.await;
+    let _ = TIMINGS.try_with(|timings| timings.borrow_mut().record(label, start.elapsed()));
+    value
+}
+
+/// The sections timed so far, or `None` when timings are not being collected
+pub fn current_timings() -> Option<Timings> {
+    TIMINGS.try_with(|timings| timings.borrow().clone()).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(unix)]
+    #[test]
+    fn test_normalize_path_resolves_symlinked_directories() {
+        // Reproduces macOS, where /var is a symlink to /private/var
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().canonicalize().unwrap().join("private");
+        std::fs::create_dir_all(real.join("repo")).unwrap();
+        let link = dir.path().join("var");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        assert_eq!(normalize_path(&link.join("repo")), real.join("repo"));
+        assert_eq!(normalize_path(&link.join("repo/")), real.join("repo"));
+        assert_eq!(normalize_path(&link.join("repo/not/yet")), real.join("repo/not/yet"));
+        assert_eq!(normalize_path(&link.join("repo/new/../other/./x")), real.join("repo/other/x"));
+        assert!(same_path(&link.join("repo"), &real.join("repo/")));
+        assert!(!same_path(&link.join("repo"), &real.join("other")));
+    }
+
+    #[test]
+    fn test_normalize_path_without_existing_ancestor_is_lexical() {
+        assert_eq!(normalize_path(Path::new("relative/./a/../b/")), PathBuf::from("relative/b"));
+        assert_eq!(normalize_path(Path::new("/../up")), PathBuf::from("/up"));
+    }
+
     #[test]
     fn test_error_to_exit_code() {
         // Test Git error with custom exit code
@@ -189,6 +411,78 @@ mod tests {
         assert!(!command_exists(""));
     }
 
+    #[cfg(unix)]
+    fn executables(names: &[&str]) -> tempfile::TempDir {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::TempDir::new().unwrap();
+        for name in names {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_in_path_scans_directories_in_order() {
+        let first = executables(&["tool"]);
+        let second = executables(&["tool", "other"]);
+        std::fs::write(first.path().join("data"), "").unwrap();
+        let path = std::env::join_paths([first.path(), second.path()]).unwrap();
+
+        assert_eq!(find_in_path("tool", &path, None), Some(first.path().join("tool")));
+        assert_eq!(find_in_path("other", &path, None), Some(second.path().join("other")));
+        // Present but not executable
+        assert_eq!(find_in_path("data", &path, None), None);
+        assert_eq!(find_in_path("missing", &path, None), None);
+        assert_eq!(find_in_path("", &path, None), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_in_path_tries_each_extension() {
+        let first = executables(&["npm.cmd"]);
+        let second = executables(&["cargo.exe", "npm"]);
+        let path = std::env::join_paths([first.path(), second.path()]).unwrap();
+        let extensions = Some(".com;.exe;.bat;.cmd");
+
+        assert_eq!(find_in_path("cargo", &path, extensions), Some(second.path().join("cargo.exe")));
+        assert_eq!(
+            find_in_path("cargo.exe", &path, extensions),
+            Some(second.path().join("cargo.exe"))
+        );
+        // The first directory with any match wins
+        assert_eq!(find_in_path("npm", &path, extensions), Some(first.path().join("npm.cmd")));
+        assert_eq!(find_in_path("cargo", &path, None), None);
+        assert_eq!(find_in_path("cargo", &path, Some("")), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_similar_commands_ranks_by_distance() {
+        let first = executables(&["npm", "nvm", "npx", "make"]);
+        let second = executables(&["npm", "pnpm", "cargo"]);
+        std::fs::write(first.path().join("nmp-notes"), "").unwrap();
+        let path = std::env::join_paths([first.path(), second.path()]).unwrap();
+
+        // A transposition is one edit, and names found twice are listed once
+        assert_eq!(similar_commands("nmp", &path, 5), vec!["npm", "npx", "nvm", "pnpm"]);
+        assert_eq!(similar_commands("nmp", &path, 1), vec!["npm"]);
+        assert_eq!(similar_commands("carg", &path, 5), vec!["cargo"]);
+        assert!(similar_commands("kubectl", &path, 5).is_empty());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("npm", "npm"), 0);
+        assert_eq!(edit_distance("nmp", "npm"), 1);
+        assert_eq!(edit_distance("gti", "git"), 1);
+        assert_eq!(edit_distance("carg", "cargo"), 1);
+        assert_eq!(edit_distance("", "ls"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
     #[test]
     fn test_error_display() {
         // Test that errors display correctly
@@ -217,8 +511,21 @@ mod tests {
             },
             PhantomError::NotInGitRepository,
             PhantomError::WorktreeExists { name: "wt".to_string() },
+            PhantomError::WorktreeNameCollision {
+                name: "wt".to_string(),
+                existing: "WT".to_string(),
+            },
             PhantomError::WorktreeNotFound { name: "wt".to_string() },
             PhantomError::BranchNotFound { branch: "br".to_string() },
+            PhantomError::BranchNotFoundAnywhere {
+                branch: "br".to_string(),
+                remote: "origin".to_string(),
+            },
+            PhantomError::FetchFailed {
+                remote: "origin".to_string(),
+                branch: "br".to_string(),
+                stderr: "fatal".to_string(),
+            },
             PhantomError::InvalidWorktreeName {
                 name: "invalid".to_string(),
                 reason: "test".to_string(),
@@ -249,4 +556,26 @@ mod tests {
             let _ = error_to_exit_code(&error);
         }
     }
+
+    #[tokio::test]
+    async fn test_timed_sections_are_collected_and_summed() {
+        let ((), timings) = collect_timings(async {
+            timed("git_root", async {}).await;
+            timed("file_copy", async {}).await;
+            timed("git_root", async {}).await;
+            assert_eq!(current_timings().unwrap().sections().len(), 2);
+        })
+        .await;
+
+        let labels: Vec<&str> =
+            timings.sections().iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, vec!["git_root", "file_copy"]);
+        assert_eq!(timings.to_millis().keys().collect::<Vec<_>>(), vec!["file_copy", "git_root"]);
+    }
+
+    #[tokio::test]
+    async fn test_timed_outside_a_collection_is_a_no_op() {
+        assert_eq!(timed("git_root", async { 42 }).await, 42);
+        assert!(current_timings().is_none());
+    }
 }
@@ -1,5 +1,6 @@
 use crate::core::sealed::Sealed;
 use crate::core::types::Worktree;
+use crate::worktree::const_validate::timeouts::GIT_OPERATION_TIMEOUT;
 use crate::Result;
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
@@ -37,12 +38,21 @@ pub trait GitBackend: Sealed + Send + Sync {
         commitish: Option<&str>,
     ) -> Result<()>;
 
+    /// Add a worktree with a detached HEAD at `commitish`, creating no branch
+    async fn add_detached_worktree(&self, path: &Path, commitish: Option<&str>) -> Result<()>;
+
     /// Attach a worktree to an existing branch
     async fn attach_worktree(&self, path: &Path, branch: &str) -> Result<()>;
 
     /// Remove a worktree
     async fn remove_worktree(&self, path: &Path) -> Result<()>;
 
+    /// Remove a worktree even if it has modified or untracked files
+    async fn force_remove_worktree(&self, path: &Path) -> Result<()>;
+
+    /// Force-delete a local branch
+    async fn delete_branch(&self, name: &str) -> Result<()>;
+
     /// Get the current commit hash
     async fn current_commit(&self) -> Result<String>;
 
@@ -66,7 +76,7 @@ pub struct GitConfig {
 
 impl Default for GitConfig {
     fn default() -> Self {
-        Self { cwd: None, env: Vec::new(), timeout: Some(30) }
+        Self { cwd: None, env: Vec::new(), timeout: Some(GIT_OPERATION_TIMEOUT.as_secs()) }
     }
 }
 
@@ -129,6 +139,10 @@ impl GitBackend for std::sync::Arc<dyn GitBackend> {
         (**self).add_worktree(path, branch, new_branch, commitish).await
     }
 
+    async fn add_detached_worktree(&self, path: &Path, commitish: Option<&str>) -> Result<()> {
+        (**self).add_detached_worktree(path, commitish).await
+    }
+
     async fn attach_worktree(&self, path: &Path, branch: &str) -> Result<()> {
         (**self).attach_worktree(path, branch).await
     }
@@ -137,6 +151,14 @@ impl GitBackend for std::sync::Arc<dyn GitBackend> {
         (**self).remove_worktree(path).await
     }
 
+    async fn force_remove_worktree(&self, path: &Path) -> Result<()> {
+        (**self).force_remove_worktree(path).await
+    }
+
+    async fn delete_branch(&self, name: &str) -> Result<()> {
+        (**self).delete_branch(name).await
+    }
+
     async fn current_commit(&self) -> Result<String> {
         (**self).current_commit().await
     }
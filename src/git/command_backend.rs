@@ -1,17 +1,27 @@
-use crate::core::command_executor::CommandExecutor;
+use crate::core::command_executor::{CommandConfig, CommandExecutor, CommandOutput};
 use crate::core::sealed::Sealed;
 use crate::core::types::Worktree;
 use crate::git::backend::{GitBackend, GitConfig};
+use crate::git::git_executor_adapter::GitSettings;
 use crate::git::libs::{
-    add_worktree::add_worktree, attach_worktree::attach_worktree, branch_exists::branch_exists,
-    create_branch::create_branch, current_commit::current_commit,
-    get_current_branch::get_current_branch, get_current_worktree::get_current_worktree,
-    get_git_root::get_git_root, is_inside_work_tree::is_inside_work_tree,
-    list_branches::list_branches, list_worktrees::list_worktrees, remove_worktree::remove_worktree,
+    add_worktree::{add_detached_worktree, add_worktree},
+    attach_worktree::attach_worktree,
+    branch_exists::branch_exists,
+    create_branch::create_branch,
+    current_commit::current_commit,
+    delete_branch::delete_branch,
+    get_current_branch::get_current_branch,
+    get_current_worktree::get_current_worktree,
+    get_git_root::get_git_root_from,
+    is_inside_work_tree::is_inside_work_tree,
+    list_branches::list_branches,
+    list_worktrees::list_worktrees,
+    remove_worktree::{force_remove_worktree, remove_worktree},
 };
 use crate::Result;
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Git backend implementation using command-line git
 pub struct CommandBackend<E> {
@@ -27,6 +37,40 @@ where
     pub fn new(config: GitConfig, executor: E) -> Self {
         Self { config, executor }
     }
+
+    fn executor(&self) -> TimedExecutor<E> {
+        TimedExecutor {
+            inner: self.executor.clone(),
+            timeout: self.config.timeout.map(Duration::from_secs),
+        }
+    }
+}
+
+/// Applies the backend's configured timeout to every git invocation
+#[derive(Clone)]
+struct TimedExecutor<E> {
+    inner: E,
+    timeout: Option<Duration>,
+}
+
+impl<E> Sealed for TimedExecutor<E> where E: CommandExecutor {}
+
+#[async_trait]
+impl<E> CommandExecutor for TimedExecutor<E>
+where
+    E: CommandExecutor,
+{
+    async fn execute(&self, config: CommandConfig) -> Result<CommandOutput> {
+        let config = match self.timeout {
+            Some(timeout) => config.with_timeout(timeout),
+            None => config,
+        };
+        self.inner.execute(config).await
+    }
+
+    fn git_settings(&self) -> GitSettings {
+        self.inner.git_settings()
+    }
 }
 
 impl<E> Default for CommandBackend<E>
@@ -48,31 +92,31 @@ where
 {
     async fn current_branch(&self) -> Result<String> {
         let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
-        get_current_branch(self.executor.clone(), cwd).await
+        get_current_branch(self.executor(), cwd).await
     }
 
     async fn list_branches(&self) -> Result<Vec<String>> {
         let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
-        list_branches(self.executor.clone(), cwd).await
+        list_branches(self.executor(), cwd).await
     }
 
     async fn create_branch(&self, name: &str) -> Result<()> {
         let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
-        create_branch(self.executor.clone(), cwd, name).await
+        create_branch(self.executor(), cwd, name).await
     }
 
     async fn branch_exists(&self, name: &str) -> Result<bool> {
         let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
-        branch_exists(self.executor.clone(), cwd, name).await
+        branch_exists(self.executor(), cwd, name).await
     }
 
     async fn get_root(&self) -> Result<PathBuf> {
-        get_git_root(self.executor.clone()).await
+        get_git_root_from(self.executor(), self.config.cwd.as_deref()).await
     }
 
     async fn list_worktrees(&self) -> Result<Vec<Worktree>> {
         let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
-        list_worktrees(self.executor.clone(), cwd).await
+        list_worktrees(self.executor(), cwd).await
     }
 
     async fn add_worktree(
@@ -83,31 +127,46 @@ where
         commitish: Option<&str>,
     ) -> Result<()> {
         let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
-        add_worktree(self.executor.clone(), cwd, path, branch, new_branch, commitish).await
+        add_worktree(self.executor(), cwd, path, branch, new_branch, commitish).await
+    }
+
+    async fn add_detached_worktree(&self, path: &Path, commitish: Option<&str>) -> Result<()> {
+        let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
+        add_detached_worktree(self.executor(), cwd, path, commitish).await
     }
 
     async fn attach_worktree(&self, path: &Path, branch: &str) -> Result<()> {
         let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
-        attach_worktree(self.executor.clone(), cwd, path, branch).await
+        attach_worktree(self.executor(), cwd, path, branch).await
     }
 
     async fn remove_worktree(&self, path: &Path) -> Result<()> {
         let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
-        remove_worktree(self.executor.clone(), cwd, path).await
+        remove_worktree(self.executor(), cwd, path).await
+    }
+
+    async fn force_remove_worktree(&self, path: &Path) -> Result<()> {
+        let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
+        force_remove_worktree(self.executor(), cwd, path).await
+    }
+
+    async fn delete_branch(&self, name: &str) -> Result<()> {
+        let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
+        delete_branch(self.executor(), cwd, name).await
     }
 
     async fn current_commit(&self) -> Result<String> {
         let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
-        current_commit(self.executor.clone(), cwd).await
+        current_commit(self.executor(), cwd).await
     }
 
     async fn is_inside_work_tree(&self) -> Result<bool> {
-        is_inside_work_tree(self.executor.clone(), self.config.cwd.as_deref()).await
+        is_inside_work_tree(self.executor(), self.config.cwd.as_deref()).await
     }
 
     async fn current_worktree(&self) -> Result<Option<String>> {
         let cwd = self.config.cwd.as_deref().unwrap_or(Path::new("."));
-        get_current_worktree(self.executor.clone(), cwd).await
+        get_current_worktree(self.executor(), cwd).await
     }
 }
 
@@ -174,6 +233,21 @@ mod tests {
         assert_eq!(worktrees.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_command_backend_applies_configured_timeout() {
+        use crate::core::executors::MockCommandExecutor;
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["branch", "--show-current"])
+            .with_timeout(Duration::from_secs(5))
+            .returns_output("main\n", "", 0);
+
+        let config = GitConfig::with_cwd("/repo").with_timeout(5);
+        let backend = CommandBackend::new(config, mock);
+
+        assert_eq!(backend.current_branch().await.unwrap(), "main");
+    }
+
     #[tokio::test]
     async fn test_command_backend_current_commit() {
         let repo = TestRepo::new().await.unwrap();
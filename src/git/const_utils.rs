@@ -3,6 +3,9 @@
 /// Git reference prefixes
 pub const REFS_HEADS_PREFIX: &str = "refs/heads/";
 
+/// Object id git reports for HEAD before the first commit (unborn HEAD)
+pub const NULL_OID: &str = "0000000000000000000000000000000000000000";
+
 /// Check if a string starts with a prefix (const version)
 const fn const_starts_with(s: &str, prefix: &str) -> bool {
     let s_bytes = s.as_bytes();
@@ -54,6 +57,24 @@ pub const fn is_valid_git_hash(hash: &str) -> bool {
     true
 }
 
+/// Check if a hash is the all-zero object id (const version)
+pub const fn is_null_oid(hash: &str) -> bool {
+    let bytes = hash.as_bytes();
+    if bytes.is_empty() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'0' {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
 /// Common git command names as constants
 pub mod commands {
     pub const GIT: &str = "git";
@@ -66,7 +87,10 @@ pub mod commands {
 /// Common git flags as constants
 pub mod flags {
     pub const PORCELAIN: &str = "--porcelain";
+    pub const NUL: &str = "-z";
     pub const BRANCH_FLAG: &str = "-b";
+    pub const DETACH: &str = "--detach";
+    pub const NO_CHECKOUT: &str = "--no-checkout";
 }
 
 #[cfg(test)]
@@ -82,7 +106,9 @@ mod tests {
         assert_eq!(commands::ADD, "add");
         assert_eq!(commands::BRANCH, "branch");
         assert_eq!(flags::PORCELAIN, "--porcelain");
+        assert_eq!(flags::NUL, "-z");
         assert_eq!(flags::BRANCH_FLAG, "-b");
+        assert_eq!(flags::DETACH, "--detach");
         assert_eq!(REFS_HEADS_PREFIX, "refs/heads/");
     }
 
@@ -124,4 +150,14 @@ mod tests {
         let _ = INVALID_CHARS;
         let _ = TOO_SHORT;
     }
+
+    #[test]
+    fn test_is_null_oid() {
+        const _: () = assert!(is_null_oid(NULL_OID));
+
+        assert!(is_null_oid("0000000"));
+        assert!(!is_null_oid(""));
+        assert!(!is_null_oid("abc123def456789012345678901234567890abcd"));
+        assert!(!is_null_oid("0000000000000000000000000000000000000001"));
+    }
 }
@@ -1,11 +1,96 @@
-use crate::core::command_executor::{CommandConfig, CommandExecutor};
+use crate::config::types::PhantomConfig;
+use crate::core::command_executor::{
+    CommandConfig, CommandExecutor, CommandOutput, OnOutputLine, StdinMode,
+};
+use crate::core::const_utils::env_vars;
+use crate::core::sealed::Sealed;
 use crate::git::const_utils::commands;
-use crate::worktree::const_validate::timeouts::GIT_OPERATION_TIMEOUT;
+use crate::worktree::const_validate::timeouts::{
+    GIT_LOCK_RETRIES, GIT_LOCK_RETRY_DELAY, GIT_OPERATION_TIMEOUT,
+};
 use crate::{PhantomError, Result};
+use async_trait::async_trait;
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{debug, trace};
 
+/// Repository settings for the git commands phantom runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GitSettings {
+    /// Timeout for git commands that don't set one explicitly (`gitTimeoutSecs`)
+    pub timeout: Duration,
+    /// Retries for a command that hit another process's lock (`git.retries`)
+    pub lock_retries: u32,
+    /// Delay before the first of those retries (`git.retryDelayMs`)
+    pub lock_retry_delay: Duration,
+}
+
+impl Default for GitSettings {
+    fn default() -> Self {
+        Self {
+            timeout: GIT_OPERATION_TIMEOUT,
+            lock_retries: GIT_LOCK_RETRIES,
+            lock_retry_delay: GIT_LOCK_RETRY_DELAY,
+        }
+    }
+}
+
+impl GitSettings {
+    /// The settings in `config`, with the defaults for anything it leaves out
+    pub fn from_config(config: &PhantomConfig) -> Self {
+        let defaults = Self::default();
+        let git = config.git.clone().unwrap_or_default();
+        Self {
+            timeout: config.git_timeout_secs.map_or(defaults.timeout, Duration::from_secs),
+            lock_retries: git.retries.unwrap_or(defaults.lock_retries),
+            lock_retry_delay: git
+                .retry_delay_ms
+                .map_or(defaults.lock_retry_delay, Duration::from_millis),
+        }
+    }
+}
+
+/// Runs commands on the wrapped executor, giving every [`GitExecutor`] built on it `settings`
+#[derive(Debug, Clone)]
+pub struct GitSettingsExecutor<E> {
+    inner: E,
+    settings: GitSettings,
+}
+
+impl<E> GitSettingsExecutor<E> {
+    pub fn new(inner: E, settings: GitSettings) -> Self {
+        Self { inner, settings }
+    }
+}
+
+impl<E> Sealed for GitSettingsExecutor<E> {}
+
+#[async_trait]
+impl<E> CommandExecutor for GitSettingsExecutor<E>
+where
+    E: CommandExecutor,
+{
+    async fn execute(&self, config: CommandConfig) -> Result<CommandOutput> {
+        self.inner.execute(config).await
+    }
+
+    async fn execute_interactive(&self, config: CommandConfig) -> Result<CommandOutput> {
+        self.inner.execute_interactive(config).await
+    }
+
+    async fn execute_streaming(
+        &self,
+        config: CommandConfig,
+        on_line: &OnOutputLine<'_>,
+    ) -> Result<CommandOutput> {
+        self.inner.execute_streaming(config, on_line).await
+    }
+
+    fn git_settings(&self) -> GitSettings {
+        self.settings
+    }
+}
+
 /// Git command executor that uses CommandExecutor internally
 #[derive(Clone)]
 pub struct GitExecutor<E>
@@ -15,15 +100,26 @@ where
     executor: E,
     cwd: Option<String>,
     timeout_duration: Duration,
+    inherit_git_dir: bool,
+    lock_retries: u32,
+    lock_retry_delay: Duration,
 }
 
 impl<E> GitExecutor<E>
 where
     E: CommandExecutor + Clone,
 {
-    /// Create a new GitExecutor with a CommandExecutor
+    /// Create a new GitExecutor with a CommandExecutor, using the executor's [`GitSettings`]
     pub fn new(executor: E) -> Self {
-        Self { executor, cwd: None, timeout_duration: GIT_OPERATION_TIMEOUT }
+        let settings = executor.git_settings();
+        Self {
+            executor,
+            cwd: None,
+            timeout_duration: settings.timeout,
+            inherit_git_dir: false,
+            lock_retries: settings.lock_retries,
+            lock_retry_delay: settings.lock_retry_delay,
+        }
     }
 
     /// Create a GitExecutor with a specific working directory
@@ -32,31 +128,87 @@ where
         self
     }
 
+    /// Let git see an inherited `GIT_DIR`, which is otherwise removed so `cwd` decides the repository
+    pub fn inheriting_git_dir(mut self) -> Self {
+        self.inherit_git_dir = true;
+        self
+    }
+
     /// Set a custom timeout for git operations
     pub fn with_timeout(mut self, duration: Duration) -> Self {
         self.timeout_duration = duration;
         self
     }
 
+    /// Retry commands that fail on another process's lock up to `retries` times,
+    /// waiting `delay` before the first retry and doubling it for each further one
+    pub fn with_lock_retries(mut self, retries: u32, delay: Duration) -> Self {
+        self.lock_retries = retries;
+        self.lock_retry_delay = delay;
+        self
+    }
+
     /// Run a git command with arguments
     pub async fn run(&self, args: &[&str]) -> Result<String> {
+        self.run_untrimmed(args).await.map(|stdout| stdout.trim().to_string())
+    }
+
+    /// Run a git command and return stdout as-is, for formats where leading
+    /// whitespace is significant (e.g. `status --porcelain`)
+    pub async fn run_untrimmed(&self, args: &[&str]) -> Result<String> {
+        self.run_with_stdin_mode(args, StdinMode::default()).await
+    }
+
+    /// Run a git command with `input` on its stdin and return stdout as-is
+    pub async fn run_with_stdin(&self, args: &[&str], input: String) -> Result<String> {
+        self.run_with_stdin_mode(args, StdinMode::Data(input)).await
+    }
+
+    async fn run_with_stdin_mode(&self, args: &[&str], stdin: StdinMode) -> Result<String> {
         debug!("Running git command: git {:?}", args);
 
         let mut config = CommandConfig::new(commands::GIT)
             .with_args(args.iter().map(|s| s.to_string()).collect())
-            .with_timeout(self.timeout_duration);
+            .with_timeout(self.timeout_duration)
+            .with_stdin(stdin)
+            .without_env(env_vars::GIT_WORK_TREE);
+        if !self.inherit_git_dir {
+            config = config.without_env(env_vars::GIT_DIR);
+        }
 
         if let Some(ref cwd) = self.cwd {
             config = config.with_cwd(cwd.into());
         }
 
-        let output = self.executor.execute(config).await?;
+        let mut attempt = 0;
+        let output = loop {
+            let output = self.executor.execute(config.clone()).await.map_err(|e| match e {
+                PhantomError::CommandNotFound { ref command, .. } if command == commands::GIT => {
+                    PhantomError::GitNotInstalled
+                }
+                _ => e,
+            })?;
+            if output.success()
+                || attempt >= self.lock_retries
+                || !is_lock_contention(&output.stderr)
+            {
+                break output;
+            }
+
+            attempt += 1;
+            let delay = retry_delay(self.lock_retry_delay, attempt);
+            debug!(
+                "git {:?} hit a lock, retrying in {:?} (attempt {}/{})",
+                args, delay, attempt, self.lock_retries
+            );
+            tokio::time::sleep(delay).await;
+        };
 
         trace!("Git command stdout: {}", output.stdout);
         trace!("Git command stderr: {}", output.stderr);
 
         if output.success() {
-            Ok(output.stdout.trim().to_string())
+            Ok(output.stdout.into_owned())
         } else {
             let exit_code = output.exit_code;
 
@@ -81,6 +233,68 @@ where
     }
 }
 
+/// Whether git failed because another process holds a lock, so the command may succeed later
+fn is_lock_contention(stderr: &str) -> bool {
+    // "cannot lock ref 'a/b': 'a' exists" is a naming conflict that no retry will fix
+    stderr.contains(".lock': File exists")
+        || (stderr.contains("cannot lock ref") && !stderr.contains("exists; cannot create"))
+}
+
+/// Exponential backoff from `base` for the given retry, plus up to `base` of jitter
+fn retry_delay(base: Duration, attempt: u32) -> Duration {
+    let backoff = base.saturating_mul(1 << (attempt - 1).min(16));
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.subsec_nanos());
+    backoff + base.mul_f64(f64::from(nanos % 1000) / 1000.0)
+}
+
+/// Whether git refused to create a branch because it already exists
+pub(crate) fn is_branch_exists_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    stderr.contains("a branch named") && stderr.contains("already exists")
+}
+
+/// Whether `git branch -d` refused to delete a branch with unmerged commits
+pub(crate) fn is_branch_not_merged_error(stderr: &str) -> bool {
+    stderr.to_lowercase().contains("is not fully merged")
+}
+
+/// Whether git could not find the branch to delete
+pub(crate) fn is_branch_not_found_error(stderr: &str) -> bool {
+    stderr.contains("error: branch") && stderr.contains("not found")
+}
+
+/// Whether git refused to remove a worktree because it has local changes
+pub(crate) fn is_dirty_worktree_error(stderr: &str) -> bool {
+    stderr.contains("contains modified or untracked files")
+}
+
+/// Whether git refused to remove a worktree because it is locked
+pub(crate) fn is_locked_worktree_error(stderr: &str) -> bool {
+    stderr.contains("locked working tree")
+}
+
+/// The reason git gives when refusing to remove a locked worktree
+pub(crate) fn locked_worktree_reason(stderr: &str) -> Option<String> {
+    let (_, rest) = stderr.split_once("lock reason: ")?;
+    let reason = rest.lines().next()?.trim();
+    (!reason.is_empty()).then(|| reason.to_string())
+}
+
+/// Whether `git worktree lock` found the worktree already locked
+pub(crate) fn is_already_locked_error(stderr: &str) -> bool {
+    stderr.contains("is already locked")
+}
+
+/// Whether `git worktree unlock` found the worktree not locked
+pub(crate) fn is_not_locked_error(stderr: &str) -> bool {
+    stderr.contains("is not locked")
+}
+
+/// Whether git does not recognise the path as a worktree
+pub(crate) fn is_not_a_worktree_error(stderr: &str) -> bool {
+    stderr.contains("is not a working tree")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,6 +314,119 @@ mod tests {
         assert_eq!(result, "M file.txt");
     }
 
+    #[tokio::test]
+    async fn test_git_executor_drops_inherited_repository_env() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["status"]).returns_success();
+
+        GitExecutor::new(mock.clone()).run(&["status"]).await.unwrap();
+        GitExecutor::new(mock.clone()).inheriting_git_dir().run(&["status"]).await.unwrap();
+
+        let calls = mock.calls();
+        assert_eq!(calls[0].env_remove, ["GIT_WORK_TREE", "GIT_DIR"]);
+        assert_eq!(calls[1].env_remove, ["GIT_WORK_TREE"]);
+    }
+
+    #[tokio::test]
+    async fn test_git_executor_uses_the_executors_settings() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["status"]).returns_success();
+        let settings = GitSettings { timeout: Duration::from_secs(5), ..Default::default() };
+
+        GitExecutor::new(mock.clone()).run(&["status"]).await.unwrap();
+        GitExecutor::new(GitSettingsExecutor::new(mock.clone(), settings))
+            .run(&["status"])
+            .await
+            .unwrap();
+
+        let calls = mock.calls();
+        assert_eq!(calls[0].timeout, Some(GIT_OPERATION_TIMEOUT));
+        assert_eq!(calls[1].timeout, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_git_settings_from_config() {
+        assert_eq!(GitSettings::from_config(&PhantomConfig::default()), GitSettings::default());
+        let config = PhantomConfig { git_timeout_secs: Some(120), ..Default::default() };
+        assert_eq!(GitSettings::from_config(&config).timeout, Duration::from_secs(120));
+        let config = PhantomConfig {
+            git: Some(crate::config::types::GitConfig { retries: Some(5), retry_delay_ms: None }),
+            ..Default::default()
+        };
+        let settings = GitSettings::from_config(&config);
+        assert_eq!(settings.lock_retries, 5);
+        assert_eq!(settings.lock_retry_delay, GIT_LOCK_RETRY_DELAY);
+    }
+
+    #[tokio::test]
+    async fn test_git_executor_uses_the_executors_lock_retries() {
+        let mut mock = MockCommandExecutor::new();
+        let lock = "fatal: Unable to create '/repo/.git/index.lock': File exists.";
+        mock.expect_command("git").with_args(&["add", "."]).times(2).returns_output("", lock, 128);
+        let settings =
+            GitSettings { lock_retries: 1, lock_retry_delay: Duration::ZERO, ..Default::default() };
+
+        let git = GitExecutor::new(GitSettingsExecutor::new(mock.clone(), settings));
+        assert!(git.run(&["add", "."]).await.is_err());
+
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_git_executor_retries_lock_contention() {
+        let mut mock = MockCommandExecutor::new();
+        let lock = "fatal: Unable to create '/repo/.git/index.lock': File exists.";
+        mock.expect_command("git").with_args(&["add", "."]).times(2).returns_output("", lock, 128);
+        mock.expect_command("git").with_args(&["add", "."]).times(1).returns_success();
+
+        let git = GitExecutor::new(mock.clone()).with_lock_retries(3, Duration::ZERO);
+        git.run(&["add", "."]).await.unwrap();
+
+        assert_eq!(mock.calls().len(), 3);
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_git_executor_gives_up_after_lock_retries() {
+        let mut mock = MockCommandExecutor::new();
+        let lock = "error: cannot lock ref 'refs/heads/feature': Unable to create \
+                    '/repo/.git/refs/heads/feature.lock': File exists.";
+        mock.expect_command("git").times(3).returns_output("", lock, 1);
+
+        let git = GitExecutor::new(mock.clone()).with_lock_retries(2, Duration::ZERO);
+        let err = git.run(&["branch", "feature"]).await.unwrap_err();
+
+        assert!(
+            matches!(err, PhantomError::Git { ref stderr, .. } if stderr.contains("feature.lock"))
+        );
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_git_executor_does_not_retry_other_failures() {
+        let mut mock = MockCommandExecutor::new();
+        let conflict = "fatal: cannot lock ref 'refs/heads/a/b': 'refs/heads/a' exists; \
+                        cannot create 'refs/heads/a/b'";
+        mock.expect_command("git").with_args(&["branch", "a/b"]).returns_output("", conflict, 128);
+        mock.expect_command("git").with_args(&["status"]).returns_output("", "fatal: bad", 128);
+
+        let git = GitExecutor::new(mock.clone()).with_lock_retries(3, Duration::ZERO);
+        assert!(git.run(&["branch", "a/b"]).await.is_err());
+        assert!(git.run(&["status"]).await.is_err());
+
+        assert_eq!(mock.calls().len(), 2);
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_with_jitter() {
+        let base = Duration::from_millis(100);
+        for (attempt, backoff) in [(1, 100), (2, 200), (3, 400)] {
+            let delay = retry_delay(base, attempt);
+            assert!(delay >= Duration::from_millis(backoff), "{delay:?}");
+            assert!(delay < Duration::from_millis(backoff) + base, "{delay:?}");
+        }
+    }
+
     #[tokio::test]
     async fn test_git_executor_with_cwd() {
         let mut mock = MockCommandExecutor::new();
@@ -134,6 +461,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_stderr_classifiers() {
+        assert!(is_branch_exists_error("fatal: a branch named 'foo' already exists"));
+        assert!(is_branch_exists_error("fatal: A branch named 'foo' already exists."));
+        assert!(!is_branch_exists_error("fatal: invalid reference: foo"));
+
+        assert!(is_branch_not_merged_error(
+            "error: The branch 'foo' is not fully merged.\nIf you are sure you want to delete it, run 'git branch -D foo'."
+        ));
+        assert!(is_branch_not_found_error("error: branch 'foo' not found."));
+        assert!(!is_branch_not_found_error("error: The branch 'foo' is not fully merged."));
+
+        assert!(is_dirty_worktree_error(
+            "fatal: '/repo/wt' contains modified or untracked files, use --force to delete it"
+        ));
+        assert!(is_locked_worktree_error(
+            "fatal: cannot remove a locked working tree, lock reason: ci\nuse 'remove -f -f' to override or unlock first"
+        ));
+        assert_eq!(
+            locked_worktree_reason(
+                "fatal: cannot remove a locked working tree, lock reason: ci job\nuse 'remove -f -f' to override or unlock first"
+            )
+            .as_deref(),
+            Some("ci job")
+        );
+        assert_eq!(locked_worktree_reason("fatal: cannot remove a locked working tree\n"), None);
+        assert!(is_already_locked_error("fatal: '/repo/wt' is already locked, reason: ci"));
+        assert!(is_not_locked_error("fatal: '/repo/wt' is not locked"));
+        assert!(is_not_a_worktree_error("fatal: '/repo/wt' is not a working tree"));
+    }
+
     #[tokio::test]
     async fn test_run_lines() {
         let mut mock = MockCommandExecutor::new();
@@ -1,6 +1,6 @@
 use crate::core::command_executor::CommandExecutor;
 use crate::git::const_utils::{commands, flags};
-use crate::git::git_executor_adapter::GitExecutor;
+use crate::git::git_executor_adapter::{is_branch_exists_error, GitExecutor};
 use crate::{PhantomError, Result};
 use std::path::Path;
 use tracing::info;
@@ -14,12 +14,47 @@ pub async fn add_worktree<E>(
     new_branch: bool,
     commitish: Option<&str>,
 ) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    add(executor, repo_path, worktree_path, branch, new_branch, commitish, false).await
+}
+
+/// Add a worktree without checking out any files, on a new `branch` or else with a detached HEAD
+pub async fn add_worktree_without_checkout<E>(
+    executor: E,
+    repo_path: &Path,
+    worktree_path: &Path,
+    branch: Option<&str>,
+    commitish: Option<&str>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    match branch {
+        Some(_) => add(executor, repo_path, worktree_path, branch, true, commitish, true).await,
+        None => add_detached(executor, repo_path, worktree_path, commitish, true).await,
+    }
+}
+
+async fn add<E>(
+    executor: E,
+    repo_path: &Path,
+    worktree_path: &Path,
+    branch: Option<&str>,
+    new_branch: bool,
+    commitish: Option<&str>,
+    no_checkout: bool,
+) -> Result<()>
 where
     E: CommandExecutor + Clone + 'static,
 {
     let git_executor = GitExecutor::new(executor).with_cwd(repo_path);
 
     let mut args = vec![commands::WORKTREE, commands::ADD];
+    if no_checkout {
+        args.push(flags::NO_CHECKOUT);
+    }
 
     // If creating a new branch
     if new_branch {
@@ -53,8 +88,55 @@ where
         "Creating worktree at {:?} for branch {:?} from base {:?}",
         worktree_path, branch, commitish
     );
-    git_executor.run(&args).await?;
+    git_executor.run(&args).await.map_err(|e| match (e, branch) {
+        (PhantomError::Git { ref stderr, .. }, Some(branch_name))
+            if new_branch && is_branch_exists_error(stderr) =>
+        {
+            PhantomError::BranchExists { branch: branch_name.to_string() }
+        }
+        (e, _) => e,
+    })?;
+
+    Ok(())
+}
+
+/// Add a worktree with a detached HEAD at `commitish` (defaults to HEAD), creating no branch
+pub async fn add_detached_worktree<E>(
+    executor: E,
+    repo_path: &Path,
+    worktree_path: &Path,
+    commitish: Option<&str>,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    add_detached(executor, repo_path, worktree_path, commitish, false).await
+}
 
+async fn add_detached<E>(
+    executor: E,
+    repo_path: &Path,
+    worktree_path: &Path,
+    commitish: Option<&str>,
+    no_checkout: bool,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(repo_path);
+
+    let path_str = worktree_path.to_string_lossy();
+    let mut args = vec![commands::WORKTREE, commands::ADD, flags::DETACH];
+    if no_checkout {
+        args.push(flags::NO_CHECKOUT);
+    }
+    args.push(&path_str);
+    if let Some(base) = commitish {
+        args.push(base);
+    }
+
+    info!("Creating detached worktree at {:?} from {:?}", worktree_path, commitish);
+    git_executor.run(&args).await?;
     Ok(())
 }
 
@@ -209,4 +291,52 @@ mod tests {
         let worktree_commit = worktree_executor.run(&["rev-parse", "HEAD"]).await.unwrap();
         assert_eq!(worktree_commit.trim(), first_commit);
     }
+
+    #[tokio::test]
+    async fn test_add_detached_worktree() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "add", "--detach", "/repo/wt", "v1.0"])
+            .in_dir("/repo")
+            .times(1)
+            .returns_success();
+
+        add_detached_worktree(
+            mock.clone(),
+            Path::new("/repo"),
+            Path::new("/repo/wt"),
+            Some("v1.0"),
+        )
+        .await
+        .unwrap();
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_add_worktree_branch_already_exists() {
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+        repo.create_branch("existing-branch").await.unwrap();
+
+        let temp_dir = tempdir().unwrap();
+        let worktree_path = temp_dir.path().join("existing-branch");
+
+        use crate::core::executors::RealCommandExecutor;
+        let result = add_worktree(
+            RealCommandExecutor,
+            repo.path(),
+            &worktree_path,
+            Some("existing-branch"),
+            true,
+            None,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(PhantomError::BranchExists { ref branch }) if branch == "existing-branch"
+        ));
+    }
 }
@@ -1,8 +1,9 @@
 use crate::core::command_executor::CommandExecutor;
 use crate::git::git_executor_adapter::GitExecutor;
+use crate::git::libs::fetch_branch::remote_branch_exists;
 use crate::Result;
 use std::path::Path;
-use tracing::info;
+use tracing::{debug, info};
 
 /// Attach a worktree to an existing branch with executor
 pub async fn attach_worktree<E>(
@@ -24,13 +25,121 @@ where
     Ok(())
 }
 
+/// Upstream of the local `branch_name` in short form, such as `origin/feature`
+pub async fn branch_upstream<E>(
+    executor: E,
+    git_root: &Path,
+    branch_name: &str,
+) -> Result<Option<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(git_root);
+    let output = git_executor
+        .run(&["for-each-ref", "--format=%(upstream:short)", &format!("refs/heads/{branch_name}")])
+        .await?;
+    let upstream = output.trim();
+    Ok((!upstream.is_empty()).then(|| upstream.to_string()))
+}
+
+/// Make `branch_name` track `<remote>/<branch_name>` when it has no upstream yet
+///
+/// Returns the upstream that was set, or `None` when the branch already had one
+/// or the remote has no such branch.
+pub async fn set_upstream<E>(
+    executor: E,
+    git_root: &Path,
+    remote: &str,
+    branch_name: &str,
+) -> Result<Option<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    if let Some(upstream) = branch_upstream(executor.clone(), git_root, branch_name).await? {
+        debug!("Branch '{}' already tracks '{}'", branch_name, upstream);
+        return Ok(None);
+    }
+    if !remote_branch_exists(executor.clone(), git_root, remote, branch_name).await? {
+        debug!("'{}/{}' does not exist, leaving '{}' untracked", remote, branch_name, branch_name);
+        return Ok(None);
+    }
+
+    let upstream = format!("{remote}/{branch_name}");
+    info!("Setting the upstream of '{}' to '{}'", branch_name, upstream);
+    GitExecutor::new(executor)
+        .with_cwd(git_root)
+        .run(&["branch", &format!("--set-upstream-to={upstream}"), branch_name])
+        .await?;
+    Ok(Some(upstream))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::core::executors::MockCommandExecutor;
     use crate::test_utils::TestRepo;
     use tempfile::tempdir;
 
+    fn expect_upstream(mock: &mut MockCommandExecutor, branch: &str, upstream: &str) {
+        mock.expect_command("git")
+            .with_args(&[
+                "for-each-ref",
+                "--format=%(upstream:short)",
+                &format!("refs/heads/{branch}"),
+            ])
+            .in_dir("/repo")
+            .returns_output(upstream, "", 0);
+    }
+
+    #[tokio::test]
+    async fn test_set_upstream_tracks_the_remote_branch() {
+        let mut mock = MockCommandExecutor::new();
+        expect_upstream(&mut mock, "feature", "\n");
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/remotes/origin/feature"])
+            .in_dir("/repo")
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["branch", "--set-upstream-to=origin/feature", "feature"])
+            .in_dir("/repo")
+            .returns_success();
+
+        let upstream =
+            set_upstream(mock.clone(), Path::new("/repo"), "origin", "feature").await.unwrap();
+
+        assert_eq!(upstream.as_deref(), Some("origin/feature"));
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_set_upstream_skips_branches_missing_on_the_remote() {
+        let mut mock = MockCommandExecutor::new();
+        expect_upstream(&mut mock, "local-only", "");
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/remotes/origin/local-only"])
+            .in_dir("/repo")
+            .returns_output("", "", 1);
+
+        let upstream =
+            set_upstream(mock.clone(), Path::new("/repo"), "origin", "local-only").await.unwrap();
+
+        assert_eq!(upstream, None);
+        assert_eq!(mock.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_set_upstream_keeps_an_existing_upstream() {
+        let mut mock = MockCommandExecutor::new();
+        expect_upstream(&mut mock, "feature", "upstream/feature\n");
+
+        let upstream =
+            set_upstream(mock.clone(), Path::new("/repo"), "origin", "feature").await.unwrap();
+
+        assert_eq!(upstream, None);
+        assert_eq!(mock.calls().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_attach_worktree_existing_branch() {
         let repo = TestRepo::new().await.unwrap();
@@ -132,8 +241,8 @@ mod tests {
 
         // On some systems, git might create the worktree in the existing directory
         // Let's check if it's an error, and if so, check the message
-        if result.is_err() {
-            let err_msg = result.unwrap_err().to_string();
+        if let Err(err) = result {
+            let err_msg = err.to_string();
             assert!(err_msg.contains("already exists") || err_msg.contains("not empty"));
         } else {
             // If it succeeded, the directory should now be a worktree
@@ -6,26 +6,32 @@ use tracing::debug;
 
 /// Check if a branch exists in the repository using a provided executor
 pub async fn branch_exists<E>(executor: E, git_root: &Path, branch_name: &str) -> Result<bool>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    ref_exists(executor, git_root, &format!("refs/heads/{branch_name}")).await
+}
+
+/// Check if a full ref such as `refs/remotes/origin/feature` exists
+pub async fn ref_exists<E>(executor: E, git_root: &Path, full_ref: &str) -> Result<bool>
 where
     E: CommandExecutor + Clone + 'static,
 {
     let git_executor = git_executor_adapter::GitExecutor::new(executor).with_cwd(git_root);
 
-    debug!("Checking if branch '{}' exists in {:?}", branch_name, git_root);
+    debug!("Checking if '{}' exists in {:?}", full_ref, git_root);
 
-    // Use show-ref to check if the branch exists
-    let result = git_executor
-        .run(&["show-ref", "--verify", "--quiet", &format!("refs/heads/{branch_name}")])
-        .await;
+    // Use show-ref to check if the ref exists
+    let result = git_executor.run(&["show-ref", "--verify", "--quiet", full_ref]).await;
 
     match result {
         Ok(_) => {
-            debug!("Branch '{}' exists", branch_name);
+            debug!("'{}' exists", full_ref);
             Ok(true)
         }
         Err(PhantomError::Git { exit_code: 1, .. }) => {
-            // Exit code 1 means the branch doesn't exist
-            debug!("Branch '{}' does not exist", branch_name);
+            // Exit code 1 means the ref doesn't exist
+            debug!("'{}' does not exist", full_ref);
             Ok(false)
         }
         Err(e) => {
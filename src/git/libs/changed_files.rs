@@ -0,0 +1,252 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter;
+use crate::git::libs::branch_exists::branch_exists;
+use crate::git::libs::upstreams::branch_upstream;
+use crate::git::parse::{parse_name_status, parse_status_porcelain, FileChange};
+use crate::{PhantomError, Result};
+use std::path::Path;
+use tracing::debug;
+
+/// Files changed on the worktree's branch since it diverged from `base`
+pub async fn committed_changes<E>(
+    executor: E,
+    worktree_path: &Path,
+    base: &str,
+) -> Result<Vec<FileChange>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = git_executor_adapter::GitExecutor::new(executor).with_cwd(worktree_path);
+    let output =
+        git_executor.run_untrimmed(&["diff", "--name-status", &format!("{base}...HEAD")]).await?;
+    Ok(parse_name_status(&output))
+}
+
+/// Staged, unstaged and untracked files in the worktree
+pub async fn uncommitted_changes<E>(executor: E, worktree_path: &Path) -> Result<Vec<FileChange>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = git_executor_adapter::GitExecutor::new(executor).with_cwd(worktree_path);
+    let output = git_executor.run_untrimmed(&["status", "--porcelain"]).await?;
+    Ok(parse_status_porcelain(&output))
+}
+
+/// Check whether a revision names a commit
+pub async fn resolves_to_commit<E>(executor: E, cwd: &Path, rev: &str) -> Result<bool>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = git_executor_adapter::GitExecutor::new(executor).with_cwd(cwd);
+
+    match git_executor
+        .run(&["rev-parse", "--verify", "--quiet", &format!("{rev}^{{commit}}")])
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(PhantomError::Git { exit_code: 1, .. }) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// The repository's default branch: `origin/HEAD` if set, else a local `main` or `master`
+pub async fn default_branch<E>(executor: E, git_root: &Path) -> Result<Option<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = git_executor_adapter::GitExecutor::new(executor.clone()).with_cwd(git_root);
+
+    match git_executor
+        .run(&["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"])
+        .await
+    {
+        Ok(branch) if !branch.is_empty() => return Ok(Some(branch)),
+        Ok(_) | Err(PhantomError::Git { .. }) => {}
+        Err(e) => return Err(e),
+    }
+
+    for candidate in ["main", "master"] {
+        if branch_exists(executor.clone(), git_root, candidate).await? {
+            return Ok(Some(candidate.to_string()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// The base to record for a worktree attached to an existing `branch`
+///
+/// The branch's upstream when it tracks another branch, such as `origin/main`;
+/// otherwise the default branch. An upstream that is only the branch's own
+/// remote copy is not a base.
+pub async fn base_of_branch<E>(executor: E, git_root: &Path, branch: &str) -> Result<Option<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    if let Some(upstream) = branch_upstream(executor.clone(), git_root, branch).await? {
+        if upstream.split_once('/').map(|(_, name)| name) != Some(branch) {
+            return Ok(Some(upstream));
+        }
+    }
+
+    Ok(default_branch(executor, git_root).await?.filter(|base| base != branch))
+}
+
+/// Pick the ref to diff a worktree against
+///
+/// A ref recorded at creation time wins when it still resolves; otherwise the
+/// default branch is used. Returns `None` when neither is available.
+pub async fn resolve_diff_base<E>(
+    executor: E,
+    git_root: &Path,
+    recorded_base: Option<&str>,
+    own_branch: Option<&str>,
+) -> Result<Option<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    // Older versions recorded "HEAD", or the branch itself on attach; neither is a base
+    if let Some(base) = recorded_base.filter(|base| *base != "HEAD" && Some(*base) != own_branch) {
+        if resolves_to_commit(executor.clone(), git_root, base).await? {
+            return Ok(Some(base.to_string()));
+        }
+        debug!("Recorded base '{}' no longer resolves", base);
+    }
+
+    default_branch(executor, git_root).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+
+    #[tokio::test]
+    async fn test_committed_changes() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["diff", "--name-status", "main...HEAD"])
+            .in_dir("/repo/wt")
+            .returns_output("M\tsrc/lib.rs\nR090\ta.rs\tb.rs\n", "", 0);
+
+        let changes = committed_changes(mock, Path::new("/repo/wt"), "main").await.unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[1].old_path.as_deref(), Some("a.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_uncommitted_changes_keeps_leading_space() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir("/repo/wt")
+            .returns_output(" M src/lib.rs\n?? notes.txt\n", "", 0);
+
+        let changes = uncommitted_changes(mock, Path::new("/repo/wt")).await.unwrap();
+        assert_eq!(changes[0].status, "M");
+        assert_eq!(changes[0].path, "src/lib.rs");
+        assert_eq!(changes[1].status, "??");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_diff_base_prefers_recorded() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--verify", "--quiet", "develop^{commit}"])
+            .returns_output("abc123\n", "", 0);
+
+        let base =
+            resolve_diff_base(mock, Path::new("/repo"), Some("develop"), None).await.unwrap();
+        assert_eq!(base.as_deref(), Some("develop"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_diff_base_falls_back_to_origin_head() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--verify", "--quiet", "deleted^{commit}"])
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"])
+            .returns_output("origin/main\n", "", 0);
+
+        let base =
+            resolve_diff_base(mock, Path::new("/repo"), Some("deleted"), None).await.unwrap();
+        assert_eq!(base.as_deref(), Some("origin/main"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_diff_base_local_default_branch() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"])
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/main"])
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/master"])
+            .returns_output("", "", 0);
+
+        let base = resolve_diff_base(mock, Path::new("/repo"), Some("HEAD"), None).await.unwrap();
+        assert_eq!(base.as_deref(), Some("master"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_diff_base_ignores_own_branch() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"])
+            .returns_output("origin/main\n", "", 0);
+
+        let base =
+            resolve_diff_base(mock.clone(), Path::new("/repo"), Some("feature"), Some("feature"))
+                .await
+                .unwrap();
+        assert_eq!(base.as_deref(), Some("origin/main"));
+        // The recorded base was never checked
+        assert_eq!(mock.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_base_of_branch_skips_own_upstream() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--abbrev-ref", "--symbolic-full-name", "feature@{upstream}"])
+            .returns_output("origin/feature\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"])
+            .returns_output("origin/main\n", "", 0);
+
+        let base = base_of_branch(mock, Path::new("/repo"), "feature").await.unwrap();
+        assert_eq!(base.as_deref(), Some("origin/main"));
+    }
+
+    #[tokio::test]
+    async fn test_base_of_branch_uses_upstream() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--abbrev-ref", "--symbolic-full-name", "feature@{upstream}"])
+            .returns_output("origin/develop\n", "", 0);
+
+        let base = base_of_branch(mock, Path::new("/repo"), "feature").await.unwrap();
+        assert_eq!(base.as_deref(), Some("origin/develop"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_diff_base_unresolvable() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"])
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/main"])
+            .returns_output("", "", 1);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/master"])
+            .returns_output("", "", 1);
+
+        let base = resolve_diff_base(mock, Path::new("/repo"), None, None).await.unwrap();
+        assert_eq!(base, None);
+    }
+}
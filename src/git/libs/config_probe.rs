@@ -0,0 +1,183 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter::GitExecutor;
+use std::path::Path;
+use tracing::debug;
+
+/// Config keys that change how git treats worktrees
+pub const PROBED_KEYS: [&str; 3] =
+    ["extensions.worktreeConfig", "worktree.guessRemote", "core.bare"];
+
+/// Worktree-related git settings phantom adapts to or warns about
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GitConfigProbe {
+    /// `extensions.worktreeConfig`: worktrees read their own `config.worktree`
+    pub worktree_config: bool,
+    /// `worktree.guessRemote`: `git worktree add` may base branches on a remote.
+    /// Phantom always names the branch it checks out, so this is only recorded.
+    pub guess_remote: bool,
+    /// `core.bare`: git considers the repository to have no working tree
+    pub bare: bool,
+}
+
+impl GitConfigProbe {
+    /// Arguments for `git config` that set `key` for the current worktree only
+    ///
+    /// Falls back to the shared config when `extensions.worktreeConfig` is off,
+    /// since `--worktree` then writes to the shared config anyway.
+    pub fn worktree_config_args<'a>(&self, key: &'a str, value: &'a str) -> Vec<&'a str> {
+        if self.worktree_config {
+            vec!["config", "--worktree", key, value]
+        } else {
+            vec!["config", key, value]
+        }
+    }
+
+    /// Problems phantom cannot work around, one message per setting
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.bare {
+            warnings.push(
+                "core.bare is true: git treats the repository as having no working tree, \
+                 so phantom commands run from the repository root may fail"
+                    .to_string(),
+            );
+            if self.worktree_config {
+                warnings.push(
+                    "extensions.worktreeConfig is enabled while core.bare is in the shared config: \
+                     move it to the main worktree with `git config --unset core.bare` and \
+                     `git config --worktree core.bare true`"
+                        .to_string(),
+                );
+            }
+        }
+        warnings
+    }
+}
+
+/// Read the worktree-related config keys, treating unset or unreadable keys as false
+pub async fn probe_git_config<E>(executor: E, cwd: &Path) -> GitConfigProbe
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(cwd);
+    let [worktree_config, guess_remote, bare] = PROBED_KEYS;
+
+    let (worktree_config, guess_remote, bare) = tokio::join!(
+        read_bool(&git_executor, worktree_config),
+        read_bool(&git_executor, guess_remote),
+        read_bool(&git_executor, bare),
+    );
+    let probe = GitConfigProbe { worktree_config, guess_remote, bare };
+    debug!("Probed git config in {:?}: {:?}", cwd, probe);
+    probe
+}
+
+async fn read_bool<E>(git_executor: &GitExecutor<E>, key: &str) -> bool
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    // `git config` exits 1 for unset keys; the last value wins like in git itself
+    match git_executor.run_untrimmed(&["config", "--get-all", key]).await {
+        Ok(values) => values.lines().last().is_some_and(parse_git_bool),
+        Err(_) => false,
+    }
+}
+
+/// Interpret a git boolean; a key without a value (`[core] bare`) means true
+fn parse_git_bool(value: &str) -> bool {
+    matches!(value.trim().to_ascii_lowercase().as_str(), "" | "true" | "yes" | "on" | "1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+
+    fn mock_with(values: &[(&str, &str)]) -> MockCommandExecutor {
+        let mut mock = MockCommandExecutor::new();
+        for key in PROBED_KEYS {
+            let expectation =
+                mock.expect_command("git").with_args(&["config", "--get-all", key]).in_dir("/repo");
+            match values.iter().find(|(k, _)| *k == key) {
+                Some((_, value)) => expectation.returns_output(value, "", 0),
+                None => expectation.returns_output("", "", 1),
+            }
+        }
+        mock
+    }
+
+    #[tokio::test]
+    async fn test_probe_defaults() {
+        let probe = probe_git_config(mock_with(&[]), Path::new("/repo")).await;
+        assert_eq!(probe, GitConfigProbe::default());
+        assert!(probe.warnings().is_empty());
+        assert_eq!(
+            probe.worktree_config_args("core.hooksPath", "hooks"),
+            ["config", "core.hooksPath", "hooks"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_worktree_config_uses_worktree_scope() {
+        let probe = probe_git_config(
+            mock_with(&[("extensions.worktreeConfig", "true\n")]),
+            Path::new("/repo"),
+        )
+        .await;
+        assert!(probe.worktree_config);
+        assert!(probe.warnings().is_empty());
+        assert_eq!(
+            probe.worktree_config_args("core.hooksPath", "hooks"),
+            ["config", "--worktree", "core.hooksPath", "hooks"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_guess_remote_is_recorded_without_warning() {
+        let probe =
+            probe_git_config(mock_with(&[("worktree.guessRemote", "yes\n")]), Path::new("/repo"))
+                .await;
+        assert!(probe.guess_remote);
+        assert!(probe.warnings().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_probe_bare_warns() {
+        let probe =
+            probe_git_config(mock_with(&[("core.bare", "true\n")]), Path::new("/repo")).await;
+        assert!(probe.bare);
+        let warnings = probe.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("core.bare is true"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_bare_with_worktree_config_suggests_moving_it() {
+        let probe = probe_git_config(
+            mock_with(&[("extensions.worktreeConfig", "1\n"), ("core.bare", "on\n")]),
+            Path::new("/repo"),
+        )
+        .await;
+        let warnings = probe.warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[1].contains("git config --worktree core.bare true"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_last_value_wins() {
+        let probe =
+            probe_git_config(mock_with(&[("core.bare", "true\nfalse\n")]), Path::new("/repo"))
+                .await;
+        assert!(!probe.bare);
+    }
+
+    #[test]
+    fn test_parse_git_bool() {
+        for value in ["true", "TRUE", "yes", "on", "1", ""] {
+            assert!(parse_git_bool(value), "{value}");
+        }
+        for value in ["false", "no", "off", "0"] {
+            assert!(!parse_git_bool(value), "{value}");
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::core::command_executor::CommandExecutor;
-use crate::git::git_executor_adapter;
-use crate::Result;
+use crate::git::git_executor_adapter::{self, is_branch_exists_error};
+use crate::{PhantomError, Result};
 use std::path::Path;
 use tracing::debug;
 
@@ -14,7 +14,12 @@ where
     debug!("Creating branch '{}' in {:?}", branch_name, git_root);
 
     // Create the new branch
-    git_executor.run(&["branch", branch_name]).await?;
+    git_executor.run(&["branch", branch_name]).await.map_err(|e| match e {
+        PhantomError::Git { ref stderr, .. } if is_branch_exists_error(stderr) => {
+            PhantomError::BranchExists { branch: branch_name.to_string() }
+        }
+        _ => e,
+    })?;
 
     debug!("Successfully created branch '{}'", branch_name);
     Ok(())
@@ -94,8 +99,10 @@ mod tests {
             .returns_output("", "fatal: A branch named 'existing-branch' already exists.", 128);
 
         let result = create_branch(mock, Path::new("/test/repo"), "existing-branch").await;
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), PhantomError::Git { exit_code: 128, .. }));
+        assert!(matches!(
+            result,
+            Err(PhantomError::BranchExists { ref branch }) if branch == "existing-branch"
+        ));
     }
 
     #[tokio::test]
@@ -1,6 +1,6 @@
 use crate::core::command_executor::CommandExecutor;
 use crate::git::git_executor_adapter::GitExecutor as GitExecutorAdapter;
-use crate::Result;
+use crate::{PhantomError, Result};
 use std::path::Path;
 use tracing::debug;
 
@@ -32,7 +32,12 @@ where
     let git_executor = GitExecutorAdapter::new(executor).with_cwd(repo_path);
 
     debug!("Getting current commit in {:?}", repo_path);
-    let output = git_executor.run(&["rev-parse", "HEAD"]).await?;
+    let output = git_executor.run(&["rev-parse", "HEAD"]).await.map_err(|e| match e {
+        PhantomError::Git { ref stderr, .. } if stderr.contains("ambiguous argument 'HEAD'") => {
+            PhantomError::NoCommits
+        }
+        _ => e,
+    })?;
 
     let commit = output.trim().to_string();
     debug!("Current commit: {}", commit);
@@ -150,7 +155,7 @@ mod tests {
         );
 
         let result = current_commit(mock, Path::new("/test")).await;
-        assert!(result.is_err());
+        assert!(matches!(result, Err(PhantomError::NoCommits)));
     }
 
     #[tokio::test]
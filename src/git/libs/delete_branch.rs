@@ -0,0 +1,48 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter;
+use crate::Result;
+use std::path::Path;
+use tracing::debug;
+
+/// Force-delete a local branch, even if it is not merged
+pub async fn delete_branch<E>(executor: E, git_root: &Path, branch_name: &str) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = git_executor_adapter::GitExecutor::new(executor).with_cwd(git_root);
+
+    debug!("Deleting branch '{}' in {:?}", branch_name, git_root);
+    git_executor.run(&["branch", "-D", branch_name]).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::{MockCommandExecutor, RealCommandExecutor};
+    use crate::git::libs::branch_exists::branch_exists;
+    use crate::test_utils::TestRepo;
+
+    #[tokio::test]
+    async fn test_delete_branch_real_repo() {
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+        let git = git_executor_adapter::GitExecutor::new(RealCommandExecutor).with_cwd(repo.path());
+        git.run(&["branch", "unmerged"]).await.unwrap();
+
+        delete_branch(RealCommandExecutor, repo.path(), "unmerged").await.unwrap();
+        assert!(!branch_exists(RealCommandExecutor, repo.path(), "unmerged").await.unwrap());
+        assert!(delete_branch(RealCommandExecutor, repo.path(), "unmerged").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delete_branch_with_mock() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["branch", "-D", "feature"])
+            .in_dir("/test/repo")
+            .returns_success();
+
+        delete_branch(mock, Path::new("/test/repo"), "feature").await.unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter;
+use crate::git::libs::branch_exists::ref_exists;
+use crate::{PhantomError, Result};
+use std::path::Path;
+use tracing::debug;
+
+/// Whether git could not fetch because the remote has no such ref
+fn is_missing_remote_ref_error(stderr: &str) -> bool {
+    stderr.contains("couldn't find remote ref")
+}
+
+/// Fetch a single branch from a remote
+///
+/// Returns `false` if the remote has no branch with that name.
+pub async fn fetch_branch<E>(
+    executor: E,
+    git_root: &Path,
+    remote: &str,
+    branch: &str,
+) -> Result<bool>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = git_executor_adapter::GitExecutor::new(executor).with_cwd(git_root);
+
+    debug!("Fetching branch '{}' from '{}' in {:?}", branch, remote, git_root);
+
+    match git_executor.run(&["fetch", remote, branch]).await {
+        Ok(_) => Ok(true),
+        Err(PhantomError::Git { ref stderr, .. }) if is_missing_remote_ref_error(stderr) => {
+            debug!("Remote '{}' has no branch '{}'", remote, branch);
+            Ok(false)
+        }
+        Err(PhantomError::Git { stderr, .. }) => Err(PhantomError::FetchFailed {
+            remote: remote.to_string(),
+            branch: branch.to_string(),
+            stderr,
+        }),
+        Err(e) => Err(e),
+    }
+}
+
+/// Check if a remote-tracking branch exists
+pub async fn remote_branch_exists<E>(
+    executor: E,
+    git_root: &Path,
+    remote: &str,
+    branch: &str,
+) -> Result<bool>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    ref_exists(executor, git_root, &format!("refs/remotes/{remote}/{branch}")).await
+}
+
+/// Create a local branch tracking `<remote>/<branch>`
+pub async fn create_tracking_branch<E>(
+    executor: E,
+    git_root: &Path,
+    remote: &str,
+    branch: &str,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = git_executor_adapter::GitExecutor::new(executor).with_cwd(git_root);
+
+    debug!("Creating branch '{}' tracking '{}/{}'", branch, remote, branch);
+
+    git_executor.run(&["branch", "--track", branch, &format!("{remote}/{branch}")]).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+
+    #[tokio::test]
+    async fn test_fetch_branch_success() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["fetch", "origin", "feature/foo"])
+            .in_dir("/test/repo")
+            .returns_success();
+
+        let fetched =
+            fetch_branch(mock, Path::new("/test/repo"), "origin", "feature/foo").await.unwrap();
+        assert!(fetched);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_branch_missing_on_remote() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["fetch", "origin", "missing"])
+            .in_dir("/test/repo")
+            .returns_output("", "fatal: couldn't find remote ref missing", 128);
+
+        let fetched =
+            fetch_branch(mock, Path::new("/test/repo"), "origin", "missing").await.unwrap();
+        assert!(!fetched);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_branch_network_error_keeps_stderr() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["fetch", "origin", "feature"])
+            .in_dir("/test/repo")
+            .returns_output(
+                "",
+                "fatal: unable to access 'https://example.com/repo.git/': Could not resolve host: example.com",
+                128,
+            );
+
+        let error =
+            fetch_branch(mock, Path::new("/test/repo"), "origin", "feature").await.unwrap_err();
+        match &error {
+            PhantomError::FetchFailed { remote, branch, stderr } => {
+                assert_eq!(remote, "origin");
+                assert_eq!(branch, "feature");
+                assert!(stderr.contains("Could not resolve host"));
+            }
+            e => panic!("Expected FetchFailed, got {e:?}"),
+        }
+        assert!(error.to_string().contains("Could not resolve host: example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_remote_branch_exists() {
+        for (exit_code, expected) in [(0, true), (1, false)] {
+            let mut mock = MockCommandExecutor::new();
+            mock.expect_command("git")
+                .with_args(&["show-ref", "--verify", "--quiet", "refs/remotes/upstream/feature"])
+                .in_dir("/test/repo")
+                .returns_output("", "", exit_code);
+
+            let exists = remote_branch_exists(mock, Path::new("/test/repo"), "upstream", "feature")
+                .await
+                .unwrap();
+            assert_eq!(exists, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_tracking_branch() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["branch", "--track", "feature", "origin/feature"])
+            .in_dir("/test/repo")
+            .returns_success();
+
+        create_tracking_branch(mock, Path::new("/test/repo"), "origin", "feature").await.unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 use crate::core::command_executor::CommandExecutor;
+use crate::core::utils::normalize_path;
 use crate::git::libs::list_worktrees::list_worktrees;
 use crate::Result;
 use std::path::Path;
@@ -13,28 +14,22 @@ where
     let git_executor = crate::git::git_executor_adapter::GitExecutor::new(executor.clone());
     let current_path = git_executor.run(&["rev-parse", "--show-toplevel"]).await?;
     let current_path = current_path.trim();
-    let current_path = Path::new(current_path);
-    // Canonicalize the current path for consistent comparison
-    let current_path_canonical = current_path.canonicalize().unwrap_or(current_path.to_path_buf());
+    // Normalize the current path for consistent comparison
+    let current_path_canonical = normalize_path(Path::new(current_path));
 
     debug!("Current worktree path: {:?}", current_path_canonical);
 
     // Get all worktrees
     let worktrees = list_worktrees(executor, git_root).await?;
 
-    // Find the current worktree by comparing canonical paths
-    let current_worktree = worktrees.into_iter().find(|wt| {
-        let wt_path = Path::new(&wt.path);
-        let wt_canonical = wt_path.canonicalize().unwrap_or(wt_path.to_path_buf());
-        wt_canonical == current_path_canonical
-    });
+    // Find the current worktree by comparing normalized paths
+    let current_worktree =
+        worktrees.into_iter().find(|wt| normalize_path(&wt.path) == current_path_canonical);
 
     match current_worktree {
         Some(wt) => {
-            // Use the already canonicalized current path
-            let git_root_canonical = git_root.canonicalize().unwrap_or(git_root.to_path_buf());
-
-            if current_path_canonical != git_root_canonical {
+            // Use the already normalized current path
+            if current_path_canonical != normalize_path(git_root) {
                 debug!("Current worktree branch: {:?}", wt.branch);
                 Ok(wt.branch)
             } else {
@@ -68,11 +63,9 @@ mod tests {
             .returns_output("/repo", "", 0);
 
         // Mock worktree list for list_worktrees_with_executor
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output("worktree /repo\0HEAD abc123\0branch refs/heads/main\0", "", 0);
 
         let result = get_current_worktree(mock, Path::new("/repo")).await.unwrap();
 
@@ -91,14 +84,51 @@ mod tests {
         );
 
         // Mock worktree list for list_worktrees_with_executor
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n\n\
-                 worktree /repo-feature\nHEAD def456\nbranch refs/heads/feature\n",
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree /repo-feature\0HEAD def456\0branch refs/heads/feature\0",
+                "",
+                0,
+            );
+
+        let result = get_current_worktree(mock, Path::new("/repo")).await.unwrap();
+
+        assert_eq!(result, Some("feature".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_get_current_worktree_through_symlinked_directory() {
+        // macOS: git reports /private/var/... while the repository was found through /var/...
+        let dir = tempfile::tempdir().unwrap();
+        let private = dir.path().canonicalize().unwrap().join("private");
+        std::fs::create_dir_all(private.join("repo/.git/phantom/worktrees/feature")).unwrap();
+        let var = dir.path().join("var");
+        std::os::unix::fs::symlink(&private, &var).unwrap();
+        let worktree = private.join("repo/.git/phantom/worktrees/feature");
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--show-toplevel"]).returns_output(
+            &format!("{}/\n", var.join("repo/.git/phantom/worktrees/feature").display()),
             "",
             0,
         );
-
-        let result = get_current_worktree(mock, Path::new("/repo")).await.unwrap();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                &format!(
+                    "worktree {}\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree {}\0HEAD def456\0branch refs/heads/feature\0",
+                    private.join("repo").display(),
+                    worktree.display()
+                ),
+                "",
+                0,
+            );
+
+        let result = get_current_worktree(mock, &var.join("repo")).await.unwrap();
 
         assert_eq!(result, Some("feature".to_string()));
     }
@@ -115,12 +145,14 @@ mod tests {
         );
 
         // Mock worktree list for list_worktrees_with_executor
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n\n\
-                 worktree /repo-detached\nHEAD def456\ndetached\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree /repo-detached\0HEAD def456\0detached\0",
+                "",
+                0,
+            );
 
         let result = get_current_worktree(mock, Path::new("/repo")).await.unwrap();
 
@@ -139,11 +171,9 @@ mod tests {
         );
 
         // Mock worktree list for list_worktrees_with_executor
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n",
-            "",
-            0,
-        );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output("worktree /repo\0HEAD abc123\0branch refs/heads/main\0", "", 0);
 
         let result = get_current_worktree(mock, Path::new("/repo")).await.unwrap();
 
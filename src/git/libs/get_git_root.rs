@@ -11,7 +11,29 @@ pub async fn get_git_root<E>(executor: E) -> Result<PathBuf>
 where
     E: CommandExecutor + Clone + 'static,
 {
-    let git_executor = GitExecutor::new(executor);
+    get_git_root_from(executor, None).await
+}
+
+/// Get the main repository root of `repo`, or of the current directory when `None`
+///
+/// Without `repo` an inherited `GIT_DIR` still picks the repository, as it
+/// does for git itself; an inherited `GIT_WORK_TREE` is always ignored.
+pub async fn get_git_root_from<E>(executor: E, repo: Option<&Path>) -> Result<PathBuf>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = match repo {
+        Some(repo) => GitExecutor::new(executor).with_cwd(repo),
+        None => GitExecutor::new(executor).inheriting_git_dir(),
+    };
+    // git prints relative paths against the directory it ran in
+    let absolute = |path: &Path| -> Result<PathBuf> {
+        Ok(match repo {
+            _ if path.is_absolute() => path.to_path_buf(),
+            Some(repo) => repo.join(path),
+            None => std::env::current_dir()?.join(path),
+        })
+    };
 
     // First try to get the git common directory
     let common_dir = match git_executor.run(&["rev-parse", "--git-common-dir"]).await {
@@ -30,11 +52,7 @@ where
         // We're in a regular repository or worktree
         let path = Path::new(&common_dir);
         if let Some(parent) = path.parent() {
-            let absolute = if parent.is_relative() {
-                std::env::current_dir()?.join(parent)
-            } else {
-                parent.to_path_buf()
-            };
+            let absolute = absolute(parent)?;
             // Always canonicalize the path to ensure consistency
             return Ok(absolute.canonicalize().unwrap_or(absolute));
         }
@@ -47,14 +65,36 @@ where
 
     debug!("Git top level: {}", top_level);
 
-    let path = Path::new(top_level);
-    let absolute =
-        if path.is_relative() { std::env::current_dir()?.join(path) } else { path.to_path_buf() };
+    let absolute = absolute(Path::new(top_level))?;
 
     // Always canonicalize the path to ensure consistency
     Ok(absolute.canonicalize().unwrap_or(absolute))
 }
 
+/// Find the main repository root above `start` by reading `.git` entries, without running git
+///
+/// A `.git` file (linked worktree) is followed to its `commondir`. Returns
+/// `None` when no repository is found.
+pub fn discover_git_root(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let dot_git = dir.join(dirs::GIT);
+        let Ok(metadata) = std::fs::metadata(&dot_git) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            return Some(dir.to_path_buf());
+        }
+
+        let contents = std::fs::read_to_string(&dot_git).ok()?;
+        let git_dir = dir.join(contents.trim().strip_prefix("gitdir:")?.trim());
+        let common_dir = std::fs::read_to_string(git_dir.join("commondir")).ok()?;
+        let common_dir = git_dir.join(common_dir.trim());
+        let common_dir = common_dir.canonicalize().unwrap_or(common_dir);
+        return common_dir.parent().map(Path::to_path_buf);
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,4 +149,50 @@ mod tests {
 
         assert_eq!(result.canonicalize().unwrap(), repo.path().canonicalize().unwrap());
     }
+
+    #[tokio::test]
+    async fn test_get_git_root_from_repo_ignores_inherited_env() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--git-common-dir"])
+            .in_dir("/other/repo")
+            .returns_output(".git", "", 0);
+
+        let result = get_git_root_from(mock.clone(), Some(Path::new("/other/repo"))).await;
+
+        // A relative answer is against the repository, not the current directory
+        assert_eq!(result.unwrap(), PathBuf::from("/other/repo"));
+        assert_eq!(mock.calls()[0].env_remove, ["GIT_WORK_TREE", "GIT_DIR"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_git_root_follows_inherited_git_dir() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["rev-parse", "--git-common-dir"]).returns_output(
+            "/home/user/project/.git",
+            "",
+            0,
+        );
+
+        get_git_root(mock.clone()).await.unwrap();
+
+        // Only GIT_WORK_TREE is dropped, so an inherited GIT_DIR still picks the repository
+        assert_eq!(mock.calls()[0].cwd, None);
+        assert_eq!(mock.calls()[0].env_remove, ["GIT_WORK_TREE"]);
+    }
+
+    #[test]
+    fn test_discover_git_root_from_linked_worktree() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let git_dir = root.join(".git/worktrees/feature");
+        let worktree = root.join(".git/phantom/worktrees/feature");
+        std::fs::create_dir_all(&git_dir).unwrap();
+        std::fs::create_dir_all(worktree.join("src")).unwrap();
+        std::fs::write(git_dir.join("commondir"), "../..\n").unwrap();
+        std::fs::write(worktree.join(".git"), format!("gitdir: {}\n", git_dir.display())).unwrap();
+
+        assert_eq!(discover_git_root(&worktree.join("src")), Some(root.clone()));
+        assert_eq!(discover_git_root(&root), Some(root));
+    }
 }
@@ -0,0 +1,59 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::libs::list_worktrees::list_worktrees;
+use crate::Result;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Root of the main worktree: the first entry of `git worktree list`, run from `repo_path`
+///
+/// Files are copied from the main worktree and its configuration and hooks are
+/// used wherever phantom runs. `None` for a bare repository, which has no main
+/// worktree.
+pub async fn get_main_worktree_root<E>(executor: E, repo_path: &Path) -> Result<Option<PathBuf>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let main = list_worktrees(executor, repo_path).await?.into_iter().next();
+    debug!("Main worktree: {:?}", main.as_ref().map(|worktree| &worktree.path));
+    Ok(main.filter(|worktree| !worktree.is_bare).map(|worktree| worktree.path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+
+    #[tokio::test]
+    async fn test_get_main_worktree_root_from_a_linked_worktree() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .in_dir("/repo/.git/phantom/worktrees/feature")
+            .returns_output(
+                "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree /repo/.git/phantom/worktrees/feature\0HEAD def456\0branch refs/heads/feature\0\0",
+                "",
+                0,
+            );
+
+        let root =
+            get_main_worktree_root(mock, Path::new("/repo/.git/phantom/worktrees/feature")).await;
+        assert_eq!(root.unwrap(), Some(PathBuf::from("/repo")));
+    }
+
+    #[tokio::test]
+    async fn test_get_main_worktree_root_of_a_bare_repository() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /srv/repo.git\0bare\0\0\
+                 worktree /srv/feature\0HEAD def456\0branch refs/heads/feature\0\0",
+                "",
+                0,
+            );
+
+        let root = get_main_worktree_root(mock, Path::new("/srv/feature")).await;
+        assert_eq!(root.unwrap(), None);
+    }
+}
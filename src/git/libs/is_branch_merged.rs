@@ -0,0 +1,80 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter::GitExecutor as GitExecutorAdapter;
+use crate::{PhantomError, Result};
+use std::path::Path;
+use tracing::debug;
+
+/// Check whether every commit on `branch` is reachable from `target`
+///
+/// A branch without commits of its own counts as merged, as with `git branch --merged`.
+pub async fn is_branch_merged<E>(
+    executor: E,
+    repo_path: &Path,
+    branch: &str,
+    target: &str,
+) -> Result<bool>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutorAdapter::new(executor).with_cwd(repo_path);
+
+    debug!("Checking whether '{}' is merged into '{}'", branch, target);
+    match git_executor.run(&["merge-base", "--is-ancestor", branch, target]).await {
+        Ok(_) => Ok(true),
+        // --is-ancestor exits 1 when the branch is not an ancestor
+        Err(PhantomError::Git { exit_code: 1, .. }) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::{MockCommandExecutor, RealCommandExecutor};
+    use crate::test_utils::TestRepo;
+
+    #[tokio::test]
+    async fn test_is_branch_merged_with_mock() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["merge-base", "--is-ancestor", "feature", "main"])
+            .in_dir("/repo")
+            .returns_output("", "", 0);
+        mock.expect_command("git")
+            .with_args(&["merge-base", "--is-ancestor", "spike", "main"])
+            .in_dir("/repo")
+            .returns_output("", "", 1);
+
+        let repo = Path::new("/repo");
+        assert!(is_branch_merged(mock.clone(), repo, "feature", "main").await.unwrap());
+        assert!(!is_branch_merged(mock, repo, "spike", "main").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_is_branch_merged_propagates_unknown_ref() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["merge-base", "--is-ancestor", "feature", "nope"])
+            .in_dir("/repo")
+            .returns_output("", "fatal: Not a valid object name nope", 128);
+
+        let result = is_branch_merged(mock, Path::new("/repo"), "feature", "nope").await;
+        assert!(matches!(result, Err(PhantomError::Git { exit_code: 128, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_is_branch_merged_real_repo() {
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("a.txt", "a", "Initial commit").await.unwrap();
+        repo.create_branch("base").await.unwrap();
+        repo.create_branch("feature").await.unwrap();
+        repo.create_file_and_commit("b.txt", "b", "Feature work").await.unwrap();
+
+        assert!(!is_branch_merged(RealCommandExecutor, repo.path(), "feature", "base")
+            .await
+            .unwrap());
+        assert!(is_branch_merged(RealCommandExecutor, repo.path(), "base", "feature")
+            .await
+            .unwrap());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter::GitExecutor as GitExecutorAdapter;
+use crate::{PhantomError, Result};
+use std::path::Path;
+use tracing::debug;
+
+/// Check whether HEAD is unborn, i.e. the repository has no commits yet
+pub async fn is_head_unborn<E>(executor: E, repo_path: &Path) -> Result<bool>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutorAdapter::new(executor).with_cwd(repo_path);
+
+    debug!("Checking for unborn HEAD in {:?}", repo_path);
+    match git_executor.run(&["rev-parse", "--verify", "--quiet", "HEAD"]).await {
+        Ok(_) => Ok(false),
+        // With --quiet, an unresolvable HEAD exits 1 without output
+        Err(PhantomError::Git { exit_code: 1, .. }) => {
+            debug!("HEAD is unborn in {:?}", repo_path);
+            Ok(true)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::{MockCommandExecutor, RealCommandExecutor};
+    use crate::test_utils::TestRepo;
+
+    #[tokio::test]
+    async fn test_is_head_unborn_fresh_repo() {
+        let repo = TestRepo::new().await.unwrap();
+
+        assert!(is_head_unborn(RealCommandExecutor, repo.path()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_is_head_unborn_after_commit() {
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+
+        assert!(!is_head_unborn(RealCommandExecutor, repo.path()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_is_head_unborn_with_mock() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--verify", "--quiet", "HEAD"])
+            .in_dir("/test")
+            .returns_output("", "", 1);
+
+        assert!(is_head_unborn(mock, Path::new("/test")).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_is_head_unborn_propagates_other_errors() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--verify", "--quiet", "HEAD"])
+            .in_dir("/test")
+            .returns_output("", "fatal: not a git repository", 128);
+
+        let result = is_head_unborn(mock, Path::new("/test")).await;
+        assert!(matches!(result, Err(PhantomError::Git { exit_code: 128, .. })));
+    }
+}
@@ -24,6 +24,58 @@ where
     Ok(branches)
 }
 
+/// A branch and the date of its last commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatedBranch {
+    /// Branch name, without `refs/heads/` or the remote prefix
+    pub name: String,
+    /// Remote the branch was listed from; `None` for local branches
+    pub remote: Option<String>,
+    /// Committer date of the branch tip, as YYYY-MM-DD
+    pub date: String,
+}
+
+/// List local branches, plus the branches of `remote` when given, with their last commit dates
+pub async fn list_branches_with_dates<E>(
+    executor: E,
+    cwd: &Path,
+    remote: Option<&str>,
+) -> Result<Vec<DatedBranch>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(cwd);
+    let remote_refs = remote.map(|remote| format!("refs/remotes/{remote}/"));
+
+    let mut args =
+        vec!["for-each-ref", "--format=%(refname)%09%(committerdate:short)", "refs/heads/"];
+    args.extend(remote_refs.as_deref());
+    let output = git_executor.run(&args).await?;
+
+    let branches: Vec<DatedBranch> = output
+        .lines()
+        .filter_map(|line| {
+            let (refname, date) = line.split_once('\t')?;
+            let (name, remote) = match refname.strip_prefix("refs/heads/") {
+                Some(name) => (name, None),
+                None => (refname.strip_prefix(remote_refs.as_deref()?)?, remote),
+            };
+            // refs/remotes/<remote>/HEAD only points at another remote branch
+            if remote.is_some() && name == "HEAD" {
+                return None;
+            }
+            Some(DatedBranch {
+                name: name.to_string(),
+                remote: remote.map(str::to_string),
+                date: date.to_string(),
+            })
+        })
+        .collect();
+
+    debug!("Found {} branches with dates", branches.len());
+    Ok(branches)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,6 +122,42 @@ mod tests {
         assert_eq!(branches, ["main", "feature/awesome", "bugfix/critical"]);
     }
 
+    #[tokio::test]
+    async fn test_list_branches_with_dates_strips_remote_prefix() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&[
+                "for-each-ref",
+                "--format=%(refname)%09%(committerdate:short)",
+                "refs/heads/",
+                "refs/remotes/origin/",
+            ])
+            .in_dir("/test/repo")
+            .returns_output(
+                "refs/heads/main\t2026-01-03\n\
+                 refs/remotes/origin/HEAD\t2026-01-03\n\
+                 refs/remotes/origin/feature/login\t2026-01-02\n",
+                "",
+                0,
+            );
+
+        let branches =
+            list_branches_with_dates(mock, Path::new("/test/repo"), Some("origin")).await.unwrap();
+
+        let dated = |name: &str, remote: Option<&str>, date: &str| DatedBranch {
+            name: name.to_string(),
+            remote: remote.map(str::to_string),
+            date: date.to_string(),
+        };
+        assert_eq!(
+            branches,
+            [
+                dated("main", None, "2026-01-03"),
+                dated("feature/login", Some("origin"), "2026-01-02")
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_list_branches_empty_with_mock() {
         let mut mock = MockCommandExecutor::new();
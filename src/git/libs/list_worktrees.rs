@@ -2,8 +2,8 @@ use crate::core::command_executor::CommandExecutor;
 use crate::core::types::Worktree;
 use crate::git::const_utils::{commands, flags};
 use crate::git::git_executor_adapter::GitExecutor;
-use crate::git::parse::parse_worktree_list;
-use crate::Result;
+use crate::git::parse::{parse_worktree_list, parse_worktree_list_z};
+use crate::{PhantomError, Result};
 use std::path::Path;
 use tracing::debug;
 
@@ -15,9 +15,20 @@ where
     let git_executor = GitExecutor::new(executor).with_cwd(repo_path);
 
     debug!("Listing worktrees in {:?}", repo_path);
-    let output = git_executor.run(&[commands::WORKTREE, commands::LIST, flags::PORCELAIN]).await?;
-
-    let worktrees = parse_worktree_list(&output);
+    let worktrees = match git_executor
+        .run_untrimmed(&[commands::WORKTREE, commands::LIST, flags::PORCELAIN, flags::NUL])
+        .await
+    {
+        Ok(output) => parse_worktree_list_z(&output),
+        // git before 2.36 rejects -z as an unknown switch
+        Err(PhantomError::Git { exit_code: 129, .. }) => {
+            debug!("git worktree list does not support -z, using line output");
+            let output =
+                git_executor.run(&[commands::WORKTREE, commands::LIST, flags::PORCELAIN]).await?;
+            parse_worktree_list(&output)
+        }
+        Err(e) => return Err(e),
+    };
     debug!("Found {} worktrees", worktrees.len());
 
     Ok(worktrees)
@@ -121,4 +132,46 @@ mod tests {
         assert!(detached_worktree.is_detached);
         assert!(detached_worktree.branch.is_none());
     }
+
+    #[tokio::test]
+    async fn test_list_worktrees_lock_reason() {
+        use crate::core::executors::RealCommandExecutor;
+
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+
+        let executor = GitExecutor::new(RealCommandExecutor::new()).with_cwd(repo.path());
+        let worktree_path = repo.path().join("locked wt");
+        let worktree_path = worktree_path.to_string_lossy();
+        executor.run(&["worktree", "add", "-b", "locked", &worktree_path]).await.unwrap();
+        executor
+            .run(&["worktree", "lock", "--reason", "on a\nusb drive", &worktree_path])
+            .await
+            .unwrap();
+
+        let worktrees = list_worktrees(RealCommandExecutor, repo.path()).await.unwrap();
+        let locked = worktrees.iter().find(|w| w.name == "locked wt").unwrap();
+        assert!(locked.is_locked);
+        // Older git without -z quotes the reason, so only check it was captured
+        assert!(locked.locked_reason.as_deref().is_some_and(|reason| reason.contains("usb drive")));
+    }
+
+    #[tokio::test]
+    async fn test_list_worktrees_falls_back_without_nul_support() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output("", "error: unknown switch `z'", 129);
+        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
+            "worktree /repo\nHEAD abc\nbranch refs/heads/main\nlocked why\n",
+            "",
+            0,
+        );
+
+        let worktrees = list_worktrees(mock, Path::new("/repo")).await.unwrap();
+        assert_eq!(worktrees.len(), 1);
+        assert_eq!(worktrees[0].locked_reason.as_deref(), Some("why"));
+    }
 }
@@ -0,0 +1,193 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::core::utils::same_path;
+use crate::git::git_executor_adapter::{
+    is_already_locked_error, is_not_a_worktree_error, is_not_locked_error, GitExecutor,
+};
+use crate::git::libs::list_worktrees::list_worktrees;
+use crate::{PhantomError, Result};
+use std::path::Path;
+use tracing::debug;
+
+/// Whether a worktree is locked against pruning and removal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockState {
+    Unlocked,
+    /// Locked, with the reason given to `git worktree lock --reason`
+    Locked {
+        reason: Option<String>,
+    },
+}
+
+/// Lock a worktree; returns false when it was already locked
+pub async fn lock_worktree<E>(
+    executor: E,
+    git_root: &Path,
+    worktree_path: &Path,
+    reason: Option<&str>,
+) -> Result<bool>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(git_root);
+    let path = worktree_path.to_string_lossy();
+    let mut args = vec!["worktree", "lock"];
+    if let Some(reason) = reason {
+        args.extend(["--reason", reason]);
+    }
+    args.push(&path);
+
+    debug!("Locking worktree at {:?}", worktree_path);
+    match git_executor.run(&args).await {
+        Ok(_) => Ok(true),
+        Err(PhantomError::Git { ref stderr, .. }) if is_already_locked_error(stderr) => Ok(false),
+        Err(e) => Err(not_found_or(e, worktree_path)),
+    }
+}
+
+/// Unlock a worktree; returns false when it was not locked
+pub async fn unlock_worktree<E>(executor: E, git_root: &Path, worktree_path: &Path) -> Result<bool>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(git_root);
+
+    debug!("Unlocking worktree at {:?}", worktree_path);
+    match git_executor.run(&["worktree", "unlock", &worktree_path.to_string_lossy()]).await {
+        Ok(_) => Ok(true),
+        Err(PhantomError::Git { ref stderr, .. }) if is_not_locked_error(stderr) => Ok(false),
+        Err(e) => Err(not_found_or(e, worktree_path)),
+    }
+}
+
+/// Read the lock state of the worktree at `worktree_path` from `git worktree list`
+pub async fn lock_state<E>(executor: E, git_root: &Path, worktree_path: &Path) -> Result<LockState>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let worktrees = list_worktrees(executor, git_root).await?;
+    let worktree = worktrees.into_iter().find(|worktree| same_path(&worktree.path, worktree_path));
+
+    Ok(match worktree {
+        Some(worktree) if worktree.is_locked => {
+            LockState::Locked { reason: worktree.locked_reason }
+        }
+        _ => LockState::Unlocked,
+    })
+}
+
+/// `WorktreeNotFound` when git does not know the path, otherwise the error itself
+fn not_found_or(error: PhantomError, worktree_path: &Path) -> PhantomError {
+    match error {
+        PhantomError::Git { ref stderr, .. } if is_not_a_worktree_error(stderr) => {
+            PhantomError::WorktreeNotFound {
+                name: worktree_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            }
+        }
+        e => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::{MockCommandExecutor, RealCommandExecutor};
+    use crate::test_utils::TestRepo;
+
+    const WORKTREE: &str = "/repo/.git/phantom/worktrees/feature";
+
+    #[tokio::test]
+    async fn test_lock_worktree_argv() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "lock", "--reason", "on a usb drive", WORKTREE])
+            .in_dir("/repo")
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["worktree", "lock", WORKTREE])
+            .in_dir("/repo")
+            .returns_output(
+                "",
+                "fatal: '/repo/.git/phantom/worktrees/feature' is already locked\n",
+                128,
+            );
+
+        let locked = lock_worktree(
+            mock.clone(),
+            Path::new("/repo"),
+            Path::new(WORKTREE),
+            Some("on a usb drive"),
+        )
+        .await
+        .unwrap();
+        assert!(locked);
+        let locked =
+            lock_worktree(mock, Path::new("/repo"), Path::new(WORKTREE), None).await.unwrap();
+        assert!(!locked);
+    }
+
+    #[tokio::test]
+    async fn test_unlock_worktree_argv() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "unlock", WORKTREE])
+            .in_dir("/repo")
+            .times(1)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["worktree", "unlock", WORKTREE])
+            .in_dir("/repo")
+            .returns_output(
+                "",
+                "fatal: '/repo/.git/phantom/worktrees/feature' is not locked\n",
+                128,
+            );
+
+        assert!(unlock_worktree(mock.clone(), Path::new("/repo"), Path::new(WORKTREE))
+            .await
+            .unwrap());
+        assert!(!unlock_worktree(mock, Path::new("/repo"), Path::new(WORKTREE)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_lock_missing_worktree_is_not_found() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "lock", WORKTREE])
+            .in_dir("/repo")
+            .returns_output(
+                "",
+                "fatal: '/repo/.git/phantom/worktrees/feature' is not a working tree\n",
+                128,
+            );
+
+        let result = lock_worktree(mock, Path::new("/repo"), Path::new(WORKTREE), None).await;
+        assert!(matches!(
+            result,
+            Err(PhantomError::WorktreeNotFound { ref name }) if name == "feature"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_lock_state_round_trip() {
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+        let worktree_path = repo.path().join("feature wt");
+        GitExecutor::new(RealCommandExecutor)
+            .with_cwd(repo.path())
+            .run(&["worktree", "add", "-b", "feature", &worktree_path.to_string_lossy()])
+            .await
+            .unwrap();
+
+        let state = || lock_state(RealCommandExecutor, repo.path(), &worktree_path);
+        assert_eq!(state().await.unwrap(), LockState::Unlocked);
+
+        lock_worktree(RealCommandExecutor, repo.path(), &worktree_path, Some("ci")).await.unwrap();
+        assert_eq!(state().await.unwrap(), LockState::Locked { reason: Some("ci".to_string()) });
+
+        unlock_worktree(RealCommandExecutor, repo.path(), &worktree_path).await.unwrap();
+        assert_eq!(state().await.unwrap(), LockState::Unlocked);
+    }
+}
@@ -1,12 +1,24 @@
 pub mod add_worktree;
 pub mod attach_worktree;
 pub mod branch_exists;
+pub mod changed_files;
+pub mod config_probe;
 pub mod create_branch;
 pub mod current_commit;
+pub mod delete_branch;
+pub mod fetch_branch;
 pub mod get_current_branch;
 pub mod get_current_worktree;
 pub mod get_git_root;
+pub mod get_main_worktree_root;
+pub mod is_branch_merged;
+pub mod is_head_unborn;
 pub mod is_inside_work_tree;
 pub mod list_branches;
 pub mod list_worktrees;
+pub mod lock_worktree;
+pub mod move_worktree;
 pub mod remove_worktree;
+pub mod sparse_checkout;
+pub mod upstreams;
+pub mod worktree_status;
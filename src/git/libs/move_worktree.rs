@@ -0,0 +1,58 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter::GitExecutor;
+use crate::Result;
+use std::path::Path;
+use tracing::debug;
+
+/// Move a worktree to `destination` with `git worktree move`
+pub async fn move_worktree<E>(
+    executor: E,
+    git_root: &Path,
+    worktree_path: &Path,
+    destination: &Path,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(git_root);
+
+    debug!("Moving worktree at {:?} to {:?}", worktree_path, destination);
+    git_executor
+        .run(&[
+            "worktree",
+            "move",
+            &worktree_path.to_string_lossy(),
+            &destination.to_string_lossy(),
+        ])
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+
+    #[tokio::test]
+    async fn test_move_worktree_argv() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&[
+                "worktree",
+                "move",
+                "/work/feature",
+                "/repo/.git/phantom/worktrees/feature",
+            ])
+            .in_dir("/repo")
+            .returns_success();
+
+        move_worktree(
+            mock,
+            Path::new("/repo"),
+            Path::new("/work/feature"),
+            Path::new("/repo/.git/phantom/worktrees/feature"),
+        )
+        .await
+        .unwrap();
+    }
+}
@@ -1,6 +1,8 @@
 use crate::core::command_executor::CommandExecutor;
-use crate::git::git_executor_adapter::GitExecutor;
-use crate::Result;
+use crate::git::git_executor_adapter::{
+    is_dirty_worktree_error, is_not_a_worktree_error, GitExecutor,
+};
+use crate::{PhantomError, Result};
 use std::path::Path;
 use tracing::debug;
 
@@ -12,12 +14,52 @@ where
     let git_executor = GitExecutor::new(executor).with_cwd(cwd);
 
     debug!("Removing worktree at {:?}", worktree_path);
-    git_executor.run(&["worktree", "remove", &worktree_path.to_string_lossy()]).await?;
+    git_executor.run(&["worktree", "remove", &worktree_path.to_string_lossy()]).await.map_err(
+        |e| {
+            let name = worktree_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            match e {
+                PhantomError::Git { ref stderr, .. } if is_not_a_worktree_error(stderr) => {
+                    PhantomError::WorktreeNotFound { name }
+                }
+                PhantomError::Git { ref stderr, .. } if is_dirty_worktree_error(stderr) => {
+                    PhantomError::WorktreeHasUncommittedChanges { name }
+                }
+                _ => e,
+            }
+        },
+    )?;
     debug!("Worktree removed successfully");
 
     Ok(())
 }
 
+/// Remove a worktree along with any modified or untracked files in it
+pub async fn force_remove_worktree<E>(executor: E, cwd: &Path, worktree_path: &Path) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(cwd);
+
+    debug!("Force-removing worktree at {:?}", worktree_path);
+    git_executor.run(&["worktree", "remove", "--force", &worktree_path.to_string_lossy()]).await?;
+    Ok(())
+}
+
+/// Drop the registrations of worktrees whose directories no longer exist
+pub async fn prune_worktrees<E>(executor: E, git_root: &Path) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(git_root);
+
+    debug!("Pruning stale worktree registrations in {:?}", git_root);
+    git_executor.run(&["worktree", "prune"]).await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -60,7 +102,10 @@ mod tests {
         )
         .await;
 
-        assert!(result.is_err());
+        assert!(matches!(
+            result,
+            Err(PhantomError::WorktreeNotFound { ref name }) if name == "nonexistent"
+        ));
     }
 
     #[tokio::test]
@@ -82,6 +127,37 @@ mod tests {
         )
         .await;
 
-        assert!(result.is_err());
+        assert!(matches!(
+            result,
+            Err(PhantomError::WorktreeHasUncommittedChanges { ref name }) if name == "feature"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_force_remove_worktree_with_mock_executor() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "--force", "/test/repo/worktrees/feature"])
+            .in_dir("/test/repo")
+            .returns_output("", "", 0);
+
+        force_remove_worktree(
+            mock,
+            Path::new("/test/repo"),
+            Path::new("/test/repo/worktrees/feature"),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prune_worktrees_with_mock_executor() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "prune"])
+            .in_dir("/test/repo")
+            .returns_success();
+
+        prune_worktrees(mock, Path::new("/test/repo")).await.unwrap();
     }
 }
@@ -0,0 +1,105 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter::GitExecutor;
+use crate::{PhantomError, Result};
+use std::path::Path;
+use tracing::info;
+
+/// Oldest git with the `git sparse-checkout` command
+pub const MIN_SPARSE_CHECKOUT_VERSION: (u32, u32) = (2, 25);
+
+/// Version of the installed git as (major, minor, patch)
+pub async fn git_version<E>(executor: E) -> Result<(u32, u32, u32)>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let output = GitExecutor::new(executor).run(&["version"]).await?;
+    parse_git_version(&output).ok_or_else(|| PhantomError::ValidationFailed {
+        reason: format!("Unrecognized git version '{output}'"),
+    })
+}
+
+/// Parse `git version 2.39.5`, ignoring vendor suffixes such as `.windows.1`
+fn parse_git_version(output: &str) -> Option<(u32, u32, u32)> {
+    let version = output.trim().strip_prefix("git version ")?;
+    let mut numbers = version.split(|c: char| !c.is_ascii_digit());
+    let major = numbers.next()?.parse().ok()?;
+    let minor = numbers.next()?.parse().ok()?;
+    let patch = numbers.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+/// Fail with the minimum version unless the installed git has `git sparse-checkout`
+pub async fn ensure_sparse_checkout_supported<E>(executor: E) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let (major, minor, patch) = git_version(executor).await?;
+    let (min_major, min_minor) = MIN_SPARSE_CHECKOUT_VERSION;
+    if (major, minor) < MIN_SPARSE_CHECKOUT_VERSION {
+        return Err(PhantomError::UnsupportedFeature {
+            feature: "sparse checkout".to_string(),
+            platform: format!(
+                "git {major}.{minor}.{patch} (needs {min_major}.{min_minor} or newer)"
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Limit a worktree added with `--no-checkout` to the cone `patterns`, then check out what they cover
+pub async fn init_sparse_checkout<E>(
+    executor: E,
+    worktree_path: &Path,
+    patterns: &[String],
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(worktree_path);
+
+    info!("Initializing sparse checkout of {:?} in {:?}", patterns, worktree_path);
+    git_executor.run(&["sparse-checkout", "init", "--cone"]).await?;
+    let mut set = vec!["sparse-checkout", "set"];
+    set.extend(patterns.iter().map(String::as_str));
+    git_executor.run(&set).await?;
+    git_executor.run(&["checkout"]).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+
+    #[test]
+    fn test_parse_git_version() {
+        assert_eq!(parse_git_version("git version 2.39.5\n"), Some((2, 39, 5)));
+        assert_eq!(parse_git_version("git version 2.45.1.windows.1"), Some((2, 45, 1)));
+        assert_eq!(parse_git_version("git version 2.39.3 (Apple Git-146)"), Some((2, 39, 3)));
+        assert_eq!(parse_git_version("git version 2.25"), Some((2, 25, 0)));
+        assert_eq!(parse_git_version("hub version 2.14.2"), None);
+    }
+
+    #[tokio::test]
+    async fn test_ensure_sparse_checkout_supported() {
+        let mut old = MockCommandExecutor::new();
+        old.expect_command("git").with_args(&["version"]).returns_output(
+            "git version 2.20.1\n",
+            "",
+            0,
+        );
+        let err = ensure_sparse_checkout_supported(old).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Feature 'sparse checkout' not supported on git 2.20.1 (needs 2.25 or newer)"
+        );
+
+        let mut new = MockCommandExecutor::new();
+        new.expect_command("git").with_args(&["version"]).returns_output(
+            "git version 2.25.0\n",
+            "",
+            0,
+        );
+        ensure_sparse_checkout_supported(new).await.unwrap();
+    }
+}
@@ -0,0 +1,164 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter::GitExecutor as GitExecutorAdapter;
+use crate::{PhantomError, Result};
+use std::collections::HashSet;
+use std::path::Path;
+use tracing::debug;
+
+/// Upstream of a local branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchUpstream {
+    pub branch: String,
+    /// Remote the upstream lives on, such as `origin`
+    pub remote: String,
+    /// Branch on the remote, such as `refs/heads/feature`
+    pub remote_ref: String,
+    /// git tracks the upstream as `[gone]`, which it does once a fetch prunes it
+    pub gone: bool,
+}
+
+/// Upstreams of every local branch that has one, from a single `git for-each-ref`
+pub async fn branch_upstreams<E>(executor: E, git_root: &Path) -> Result<Vec<BranchUpstream>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutorAdapter::new(executor).with_cwd(git_root);
+
+    debug!("Reading branch upstreams in {:?}", git_root);
+    let output = git_executor
+        .run(&[
+            "for-each-ref",
+            "--format=%(refname:short)%00%(upstream:remotename)%00%(upstream:remoteref)%00%(upstream:track)",
+            "refs/heads",
+        ])
+        .await?;
+
+    Ok(output.lines().filter_map(parse_upstream).collect())
+}
+
+/// Short name of the upstream a local branch tracks, such as `origin/main`
+pub async fn branch_upstream<E>(
+    executor: E,
+    git_root: &Path,
+    branch: &str,
+) -> Result<Option<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutorAdapter::new(executor).with_cwd(git_root);
+
+    match git_executor
+        .run(&[
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            &format!("{branch}@{{upstream}}"),
+        ])
+        .await
+    {
+        Ok(upstream) if !upstream.is_empty() => Ok(Some(upstream)),
+        // git fails when the branch tracks nothing
+        Ok(_) | Err(PhantomError::Git { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn parse_upstream(line: &str) -> Option<BranchUpstream> {
+    let mut fields = line.split('\0');
+    let branch = fields.next()?;
+    let remote = fields.next()?;
+    let remote_ref = fields.next()?;
+    let track = fields.next().unwrap_or_default();
+    if branch.is_empty() || remote.is_empty() || remote_ref.is_empty() {
+        return None;
+    }
+    Some(BranchUpstream {
+        branch: branch.to_string(),
+        remote: remote.to_string(),
+        remote_ref: remote_ref.to_string(),
+        gone: track == "[gone]",
+    })
+}
+
+/// Branches of `remote` as full refs, from a single `git ls-remote --heads`
+pub async fn remote_heads<E>(executor: E, git_root: &Path, remote: &str) -> Result<HashSet<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutorAdapter::new(executor).with_cwd(git_root);
+
+    debug!("Listing the branches of '{}'", remote);
+    let output = git_executor.run(&["ls-remote", "--heads", remote]).await?;
+
+    Ok(output
+        .lines()
+        .filter_map(|line| line.split_once('\t').map(|(_, name)| name.to_string()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+
+    #[tokio::test]
+    async fn test_branch_upstreams_parses_tracking_state() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&[
+                "for-each-ref",
+                "--format=%(refname:short)%00%(upstream:remotename)%00%(upstream:remoteref)%00%(upstream:track)",
+                "refs/heads",
+            ])
+            .in_dir("/repo")
+            .returns_output(
+                "main\0origin\0refs/heads/main\0\n\
+                 merged\0origin\0refs/heads/merged\0[gone]\n\
+                 ahead\0origin\0refs/heads/ahead\0[ahead 2]\n\
+                 local-only\0\0\0\n",
+                "",
+                0,
+            );
+
+        let upstreams = branch_upstreams(mock, Path::new("/repo")).await.unwrap();
+
+        let gone: Vec<_> = upstreams.iter().map(|u| (u.branch.as_str(), u.gone)).collect();
+        assert_eq!(gone, [("main", false), ("merged", true), ("ahead", false)]);
+        assert_eq!(upstreams[2].remote_ref, "refs/heads/ahead");
+    }
+
+    #[tokio::test]
+    async fn test_branch_upstream() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--abbrev-ref", "--symbolic-full-name", "feature@{upstream}"])
+            .in_dir("/repo")
+            .returns_output("origin/main\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--abbrev-ref", "--symbolic-full-name", "spike@{upstream}"])
+            .in_dir("/repo")
+            .returns_output("", "fatal: no upstream configured for branch 'spike'", 128);
+
+        let repo = Path::new("/repo");
+        assert_eq!(
+            branch_upstream(mock.clone(), repo, "feature").await.unwrap().as_deref(),
+            Some("origin/main")
+        );
+        assert_eq!(branch_upstream(mock, repo, "spike").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_remote_heads() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git").with_args(&["ls-remote", "--heads", "origin"]).returns_output(
+            "abc123\trefs/heads/main\ndef456\trefs/heads/feature/login\n",
+            "",
+            0,
+        );
+
+        let heads = remote_heads(mock, Path::new("/repo"), "origin").await.unwrap();
+
+        assert_eq!(heads.len(), 2);
+        assert!(heads.contains("refs/heads/feature/login"));
+    }
+}
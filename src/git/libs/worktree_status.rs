@@ -0,0 +1,88 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter::GitExecutor as GitExecutorAdapter;
+use crate::git::parse::{parse_status_porcelain_v2, StatusSummary};
+use crate::Result;
+use std::path::Path;
+use tracing::debug;
+
+/// Branch, upstream and file counts for the worktree at `worktree_path`
+pub async fn worktree_status<E>(executor: E, worktree_path: &Path) -> Result<StatusSummary>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutorAdapter::new(executor).with_cwd(worktree_path);
+
+    debug!("Reading status of {:?}", worktree_path);
+    let output = git_executor.run_untrimmed(&["status", "--porcelain=v2", "--branch"]).await?;
+    Ok(parse_status_porcelain_v2(&output))
+}
+
+/// Subject line of the HEAD commit
+pub async fn head_subject<E>(executor: E, worktree_path: &Path) -> Result<String>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutorAdapter::new(executor).with_cwd(worktree_path);
+    git_executor.run(&["log", "-1", "--format=%s"]).await
+}
+
+/// One-line summaries of the last `count` commits, newest first
+pub async fn recent_commits<E>(executor: E, worktree_path: &Path, count: usize) -> Result<String>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutorAdapter::new(executor).with_cwd(worktree_path);
+    git_executor.run(&["log", "--oneline", &format!("-{count}")]).await
+}
+
+/// `git status --short` output, keeping the leading status column
+pub async fn short_status<E>(executor: E, worktree_path: &Path) -> Result<String>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutorAdapter::new(executor).with_cwd(worktree_path);
+    let output = git_executor.run_untrimmed(&["status", "--short"]).await?;
+    Ok(output.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::{MockCommandExecutor, RealCommandExecutor};
+    use crate::test_utils::TestRepo;
+
+    #[tokio::test]
+    async fn test_worktree_status_with_mock() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain=v2", "--branch"])
+            .in_dir("/repo")
+            .returns_output("# branch.oid abc\n# branch.head main\n? notes.txt\n", "", 0);
+
+        let summary = worktree_status(mock, Path::new("/repo")).await.unwrap();
+        assert_eq!(summary.head.as_deref(), Some("main"));
+        assert_eq!(summary.untracked, 1);
+    }
+
+    #[tokio::test]
+    async fn test_worktree_status_real_repo() {
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("a.txt", "a", "Add a").await.unwrap();
+        tokio::fs::write(repo.path().join("a.txt"), "changed").await.unwrap();
+        tokio::fs::write(repo.path().join("b.txt"), "b").await.unwrap();
+
+        let summary = worktree_status(RealCommandExecutor, repo.path()).await.unwrap();
+        assert!(summary.oid.is_some());
+        assert_eq!(summary.modified, 1);
+        assert_eq!(summary.untracked, 1);
+        assert_eq!(summary.upstream, None);
+
+        let subject = head_subject(RealCommandExecutor, repo.path()).await.unwrap();
+        assert_eq!(subject, "Add a");
+
+        let commits = recent_commits(RealCommandExecutor, repo.path(), 5).await.unwrap();
+        assert!(commits.ends_with(" Add a"));
+        let status = short_status(RealCommandExecutor, repo.path()).await.unwrap();
+        assert_eq!(status, " M a.txt\n?? b.txt");
+    }
+}
@@ -1,13 +1,27 @@
 use crate::core::types::Worktree;
-use crate::git::const_utils::{is_branch_ref, REFS_HEADS_PREFIX};
+use crate::git::const_utils::{is_branch_ref, NULL_OID, REFS_HEADS_PREFIX};
+use serde::Serialize;
 use std::path::PathBuf;
 
-/// Parse git worktree list output
+/// Parse `git worktree list --porcelain` output, one attribute per line
 pub fn parse_worktree_list(output: &str) -> Vec<Worktree> {
+    parse_worktree_records(output.lines())
+}
+
+/// Parse `git worktree list --porcelain -z` output
+///
+/// Attributes end with NUL instead of a newline, so paths and lock reasons
+/// come through verbatim even when they contain newlines.
+pub fn parse_worktree_list_z(output: &str) -> Vec<Worktree> {
+    parse_worktree_records(output.split('\0'))
+}
+
+/// Build worktrees from porcelain attributes, where an empty attribute ends a record
+fn parse_worktree_records<'a>(attributes: impl Iterator<Item = &'a str>) -> Vec<Worktree> {
     let mut worktrees = Vec::new();
     let mut current_worktree: Option<WorktreeBuilder> = None;
 
-    for line in output.lines() {
+    for line in attributes {
         if line.is_empty() {
             if let Some(builder) = current_worktree.take() {
                 if let Some(worktree) = builder.build() {
@@ -66,11 +80,13 @@ pub fn parse_worktree_list(output: &str) -> Vec<Worktree> {
             "locked" => {
                 if let Some(ref mut builder) = current_worktree {
                     builder.is_locked = true;
+                    builder.locked_reason = value.map(str::to_string);
                 }
             }
             "prunable" => {
                 if let Some(ref mut builder) = current_worktree {
                     builder.is_prunable = true;
+                    builder.prunable_reason = value.map(str::to_string);
                 }
             }
             _ => {} // Ignore other fields
@@ -106,6 +122,139 @@ pub fn parse_branch_list(output: &str) -> Vec<(String, bool)> {
         .collect()
 }
 
+/// A changed file reported by `git diff --name-status` or `git status --porcelain`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    /// Status letters as git printed them, without rename/copy scores (e.g. "M", "R", "??")
+    pub status: String,
+    pub path: String,
+    /// Source path of a rename or copy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+}
+
+/// Parse `git diff --name-status` output
+///
+/// Renames and copies carry a similarity score and two paths (`R100\told\tnew`).
+pub fn parse_name_status(output: &str) -> Vec<FileChange> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let code = fields.next()?.trim();
+            let letter = code.chars().next()?;
+            let first = fields.next()?.to_string();
+            match (letter, fields.next()) {
+                ('R' | 'C', Some(second)) => Some(FileChange {
+                    status: letter.to_string(),
+                    path: second.to_string(),
+                    old_path: Some(first),
+                }),
+                _ => Some(FileChange { status: code.to_string(), path: first, old_path: None }),
+            }
+        })
+        .collect()
+}
+
+/// Parse `git status --porcelain` (v1) output
+pub fn parse_status_porcelain(output: &str) -> Vec<FileChange> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let code = line.get(..2)?;
+            let rest = line.get(3..)?;
+            let status = code.trim().to_string();
+            if status.is_empty() {
+                return None;
+            }
+            match rest.split_once(" -> ") {
+                Some((old, new)) if code.contains('R') || code.contains('C') => Some(FileChange {
+                    status,
+                    path: unquote_path(new),
+                    old_path: Some(unquote_path(old)),
+                }),
+                _ => Some(FileChange { status, path: unquote_path(rest), old_path: None }),
+            }
+        })
+        .collect()
+}
+
+/// Branch and file counts from `git status --porcelain=v2 --branch`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StatusSummary {
+    /// HEAD commit; absent on an unborn branch
+    pub oid: Option<String>,
+    /// Checked-out branch; absent when HEAD is detached
+    pub head: Option<String>,
+    pub upstream: Option<String>,
+    /// Commits ahead of and behind the upstream; absent when it is gone or unset
+    pub ahead: Option<u32>,
+    pub behind: Option<u32>,
+    /// Tracked files with staged or unstaged changes, renames and copies included
+    pub modified: usize,
+    /// Files with unresolved merge conflicts
+    pub unmerged: usize,
+    pub untracked: usize,
+}
+
+impl StatusSummary {
+    pub fn is_clean(&self) -> bool {
+        self.modified == 0 && self.unmerged == 0 && self.untracked == 0
+    }
+}
+
+/// Parse `git status --porcelain=v2 --branch` output
+///
+/// Headers start with `#`; entries are `1` (changed), `2` (renamed or copied),
+/// `u` (unmerged), `?` (untracked) and `!` (ignored, not counted). Unknown or
+/// malformed lines are skipped so newer git versions do not break parsing.
+pub fn parse_status_porcelain_v2(output: &str) -> StatusSummary {
+    let mut summary = StatusSummary::default();
+
+    for line in output.lines() {
+        let Some((kind, rest)) = line.split_once(' ') else {
+            continue;
+        };
+        match kind {
+            "#" => parse_branch_header(&mut summary, rest),
+            "1" | "2" => summary.modified += 1,
+            "u" => summary.unmerged += 1,
+            "?" => summary.untracked += 1,
+            _ => {}
+        }
+    }
+
+    summary
+}
+
+fn parse_branch_header(summary: &mut StatusSummary, header: &str) {
+    let Some((key, value)) = header.split_once(' ') else {
+        return;
+    };
+    match key {
+        "branch.oid" if value != "(initial)" => summary.oid = Some(value.to_string()),
+        "branch.head" if value != "(detached)" => summary.head = Some(value.to_string()),
+        "branch.upstream" => summary.upstream = Some(value.to_string()),
+        "branch.ab" => {
+            let mut counts = value.split(' ');
+            let ahead =
+                counts.next().and_then(|c| c.strip_prefix('+')).and_then(|c| c.parse().ok());
+            let behind =
+                counts.next().and_then(|c| c.strip_prefix('-')).and_then(|c| c.parse().ok());
+            if let (Some(ahead), Some(behind)) = (ahead, behind) {
+                summary.ahead = Some(ahead);
+                summary.behind = Some(behind);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Strip the quotes git adds around paths containing special characters
+fn unquote_path(path: &str) -> String {
+    path.strip_prefix('"').and_then(|p| p.strip_suffix('"')).unwrap_or(path).to_string()
+}
+
 /// Builder for Worktree
 #[derive(Debug)]
 struct WorktreeBuilder {
@@ -115,7 +264,9 @@ struct WorktreeBuilder {
     is_bare: bool,
     is_detached: bool,
     is_locked: bool,
+    locked_reason: Option<String>,
     is_prunable: bool,
+    prunable_reason: Option<String>,
 }
 
 impl WorktreeBuilder {
@@ -127,13 +278,21 @@ impl WorktreeBuilder {
             is_bare: false,
             is_detached: false,
             is_locked: false,
+            locked_reason: None,
             is_prunable: false,
+            prunable_reason: None,
         }
     }
 
     fn build(self) -> Option<Worktree> {
         let name = self.path.file_name()?.to_string_lossy().to_string();
-        let commit = self.commit?;
+        // A worktree on an unborn branch may be reported without a usable HEAD,
+        // and a bare repository never has one
+        let commit = match self.commit {
+            Some(commit) => commit,
+            None if self.branch.is_some() || self.is_bare => NULL_OID.to_string(),
+            None => return None,
+        };
 
         Some(Worktree {
             name,
@@ -143,7 +302,9 @@ impl WorktreeBuilder {
             is_bare: self.is_bare,
             is_detached: self.is_detached,
             is_locked: self.is_locked,
+            locked_reason: self.locked_reason,
             is_prunable: self.is_prunable,
+            prunable_reason: self.prunable_reason,
         })
     }
 }
@@ -252,9 +413,8 @@ locked
 
     #[test]
     fn test_parse_worktree_list_invalid_entries() {
-        // Test with worktree missing HEAD or commit
+        // Test with worktree missing both HEAD and branch
         let output = r#"worktree /path/to/repo
-branch refs/heads/main
 
 worktree /path/to/valid
 HEAD abc123
@@ -265,6 +425,37 @@ HEAD abc123
         assert_eq!(worktrees[0].name, "valid");
     }
 
+    #[test]
+    fn test_parse_worktree_list_unborn_head() {
+        let output = r#"worktree /path/to/repo
+HEAD 0000000000000000000000000000000000000000
+branch refs/heads/main
+"#;
+
+        let worktrees = parse_worktree_list(output);
+        assert_eq!(worktrees.len(), 1);
+        assert_eq!(worktrees[0].branch, Some("main".to_string()));
+        assert!(worktrees[0].is_unborn());
+    }
+
+    #[test]
+    fn test_parse_worktree_list_missing_head_with_branch() {
+        // Entry with a branch but no HEAD record is treated as unborn
+        let output = r#"worktree /path/to/repo
+branch refs/heads/main
+
+worktree /path/to/valid
+HEAD abc123
+"#;
+
+        let worktrees = parse_worktree_list(output);
+        assert_eq!(worktrees.len(), 2);
+        assert_eq!(worktrees[0].name, "repo");
+        assert_eq!(worktrees[0].commit, NULL_OID);
+        assert!(worktrees[0].is_unborn());
+        assert!(!worktrees[1].is_unborn());
+    }
+
     #[test]
     fn test_parse_worktree_list_with_unknown_fields() {
         let output = r#"worktree /path/to/repo
@@ -280,6 +471,67 @@ another_unknown field
         // Unknown fields should be ignored
     }
 
+    #[test]
+    fn test_parse_worktree_list_lock_and_prune_reasons() {
+        let output = "worktree /repo/a\nHEAD abc\nbranch refs/heads/a\nlocked on a usb drive\n\n\
+                      worktree /repo/b\nHEAD def\ndetached\nprunable gitdir file points to non-existent location\n";
+
+        let worktrees = parse_worktree_list(output);
+        assert_eq!(worktrees[0].locked_reason.as_deref(), Some("on a usb drive"));
+        assert!(!worktrees[0].is_prunable);
+        assert!(worktrees[1].is_prunable);
+        assert_eq!(
+            worktrees[1].prunable_reason.as_deref(),
+            Some("gitdir file points to non-existent location")
+        );
+    }
+
+    #[test]
+    fn test_parse_worktree_list_z_bare_and_detached() {
+        let output = "worktree /srv/repo.git\0bare\0\0\
+                      worktree /srv/wt/detached\0HEAD abc123\0detached\0\0";
+
+        let worktrees = parse_worktree_list_z(output);
+        assert_eq!(worktrees.len(), 2);
+        assert_eq!(worktrees[0].name, "repo.git");
+        assert!(worktrees[0].is_bare);
+        assert!(!worktrees[0].is_unborn());
+        assert_eq!(worktrees[1].name, "detached");
+        assert!(worktrees[1].is_detached);
+        assert!(worktrees[1].branch.is_none());
+        assert!(!worktrees[1].is_bare);
+    }
+
+    #[test]
+    fn test_parse_worktree_list_z_locked_with_and_without_reason() {
+        let output = "worktree /repo\0HEAD abc\0branch refs/heads/main\0\0\
+                      worktree /repo/.git/phantom/worktrees/plain\0HEAD def\0branch refs/heads/plain\0locked\0\0\
+                      worktree /repo/.git/phantom/worktrees/why\0HEAD fed\0branch refs/heads/why\0locked line one\nline two\0\0";
+
+        let worktrees = parse_worktree_list_z(output);
+        assert_eq!(worktrees.len(), 3);
+        assert!(!worktrees[0].is_locked);
+        assert!(worktrees[1].is_locked);
+        assert_eq!(worktrees[1].locked_reason, None);
+        assert!(worktrees[2].is_locked);
+        assert_eq!(worktrees[2].locked_reason.as_deref(), Some("line one\nline two"));
+    }
+
+    #[test]
+    fn test_parse_worktree_list_z_paths_with_spaces_unicode_and_newlines() {
+        let output = "worktree /home/me/my repo\0HEAD abc\0branch refs/heads/main\0\0\
+                      worktree /home/me/my repo/.git/phantom/worktrees/café ☕\0HEAD def\0branch refs/heads/café\0\0\
+                      worktree /tmp/odd\nname\0HEAD fed\0detached\0\0";
+
+        let worktrees = parse_worktree_list_z(output);
+        assert_eq!(worktrees.len(), 3);
+        assert_eq!(worktrees[0].path, PathBuf::from("/home/me/my repo"));
+        assert_eq!(worktrees[1].name, "café ☕");
+        assert_eq!(worktrees[1].branch.as_deref(), Some("café"));
+        assert_eq!(worktrees[2].path, PathBuf::from("/tmp/odd\nname"));
+        assert_eq!(worktrees[2].name, "odd\nname");
+    }
+
     #[test]
     fn test_parse_branch_list_with_whitespace() {
         let output = r#"  
@@ -307,7 +559,9 @@ another_unknown field
             is_bare: false,
             is_detached: false,
             is_locked: false,
+            locked_reason: None,
             is_prunable: false,
+            prunable_reason: None,
         };
 
         // This should return None because "/" has no file name
@@ -353,4 +607,148 @@ branch refs/heads/last-branch"#; // No trailing newline
         assert!(debug_str.contains("WorktreeBuilder"));
         assert!(debug_str.contains("/path/to/repo"));
     }
+
+    #[test]
+    fn test_parse_name_status() {
+        let output = "M\tsrc/lib.rs\nA\tnew.txt\nD\tgone.txt\nR100\told/name.rs\tnew/name.rs\nC075\ta.rs\tb.rs\nT\tlink\n";
+
+        let changes = parse_name_status(output);
+        assert_eq!(changes.len(), 6);
+        assert_eq!(
+            changes[0],
+            FileChange { status: "M".to_string(), path: "src/lib.rs".to_string(), old_path: None }
+        );
+        assert_eq!(changes[1].status, "A");
+        assert_eq!(changes[2].status, "D");
+        assert_eq!(
+            changes[3],
+            FileChange {
+                status: "R".to_string(),
+                path: "new/name.rs".to_string(),
+                old_path: Some("old/name.rs".to_string()),
+            }
+        );
+        assert_eq!(changes[4].status, "C");
+        assert_eq!(changes[4].old_path.as_deref(), Some("a.rs"));
+        assert_eq!(changes[5].status, "T");
+    }
+
+    #[test]
+    fn test_parse_name_status_empty_and_malformed() {
+        assert!(parse_name_status("").is_empty());
+        assert!(parse_name_status("M\n\n").is_empty());
+        let changes = parse_name_status("M\tpath with spaces.txt");
+        assert_eq!(changes[0].path, "path with spaces.txt");
+    }
+
+    #[test]
+    fn test_parse_status_porcelain() {
+        let output = " M src/lib.rs\nM  staged.rs\nMM both.rs\n?? untracked.txt\nR  old.rs -> new.rs\n D \"with space.txt\"\n";
+
+        let changes = parse_status_porcelain(output);
+        assert_eq!(changes.len(), 6);
+        assert_eq!(changes[0].status, "M");
+        assert_eq!(changes[0].path, "src/lib.rs");
+        assert_eq!(changes[1].status, "M");
+        assert_eq!(changes[2].status, "MM");
+        assert_eq!(changes[3].status, "??");
+        assert_eq!(changes[3].path, "untracked.txt");
+        assert_eq!(changes[4].status, "R");
+        assert_eq!(changes[4].path, "new.rs");
+        assert_eq!(changes[4].old_path.as_deref(), Some("old.rs"));
+        assert_eq!(changes[5].status, "D");
+        assert_eq!(changes[5].path, "with space.txt");
+    }
+
+    #[test]
+    fn test_parse_status_porcelain_arrow_in_plain_path() {
+        let changes = parse_status_porcelain("?? a -> b.txt\n");
+        assert_eq!(changes[0].path, "a -> b.txt");
+        assert_eq!(changes[0].old_path, None);
+    }
+
+    #[test]
+    fn test_parse_status_porcelain_v2_clean_tracking_branch() {
+        let output = "# branch.oid 1234567890abcdef1234567890abcdef12345678\n\
+                      # branch.head feature\n\
+                      # branch.upstream origin/feature\n\
+                      # branch.ab +2 -1\n";
+
+        let summary = parse_status_porcelain_v2(output);
+        assert_eq!(summary.oid.as_deref(), Some("1234567890abcdef1234567890abcdef12345678"));
+        assert_eq!(summary.head.as_deref(), Some("feature"));
+        assert_eq!(summary.upstream.as_deref(), Some("origin/feature"));
+        assert_eq!(summary.ahead, Some(2));
+        assert_eq!(summary.behind, Some(1));
+        assert!(summary.is_clean());
+    }
+
+    #[test]
+    fn test_parse_status_porcelain_v2_entry_types() {
+        let output = "# branch.oid abc\n\
+                      # branch.head main\n\
+                      1 .M N... 100644 100644 100644 abc abc src/lib.rs\n\
+                      1 A. N... 000000 100644 100644 000 abc new file.rs\n\
+                      2 R. N... 100644 100644 100644 abc abc R100 new.rs\told.rs\n\
+                      u UU N... 100644 100644 100644 100644 a b c conflict.rs\n\
+                      ? untracked.txt\n\
+                      ? other dir/file.txt\n\
+                      ! target/\n";
+
+        let summary = parse_status_porcelain_v2(output);
+        assert_eq!(summary.modified, 3);
+        assert_eq!(summary.unmerged, 1);
+        assert_eq!(summary.untracked, 2);
+        assert!(!summary.is_clean());
+    }
+
+    #[test]
+    fn test_parse_status_porcelain_v2_ignored_only_is_clean() {
+        let summary = parse_status_porcelain_v2("# branch.head main\n! target/\n! .env\n");
+        assert!(summary.is_clean());
+    }
+
+    #[test]
+    fn test_parse_status_porcelain_v2_detached_and_initial() {
+        let summary = parse_status_porcelain_v2("# branch.oid abc\n# branch.head (detached)\n");
+        assert_eq!(summary.oid.as_deref(), Some("abc"));
+        assert_eq!(summary.head, None);
+
+        let summary = parse_status_porcelain_v2("# branch.oid (initial)\n# branch.head main\n");
+        assert_eq!(summary.oid, None);
+        assert_eq!(summary.head.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_parse_status_porcelain_v2_upstream_gone() {
+        // git omits branch.ab when the upstream ref no longer exists
+        let summary =
+            parse_status_porcelain_v2("# branch.head feature\n# branch.upstream origin/feature\n");
+        assert_eq!(summary.upstream.as_deref(), Some("origin/feature"));
+        assert_eq!(summary.ahead, None);
+        assert_eq!(summary.behind, None);
+    }
+
+    #[test]
+    fn test_parse_status_porcelain_v2_skips_unknown_and_malformed() {
+        let output = "# stash 3\n\
+                      # branch.ab +x -1\n\
+                      # branch.ab\n\
+                      #\n\
+                      garbage\n\
+                      \n\
+                      z future record\n\
+                      ? kept.txt\n";
+
+        let summary = parse_status_porcelain_v2(output);
+        assert_eq!(summary.ahead, None);
+        assert_eq!(summary.behind, None);
+        assert_eq!(summary.untracked, 1);
+        assert_eq!(summary.modified, 0);
+    }
+
+    #[test]
+    fn test_parse_status_porcelain_v2_empty() {
+        assert_eq!(parse_status_porcelain_v2(""), StatusSummary::default());
+    }
 }
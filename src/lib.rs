@@ -1,12 +1,58 @@
+//! Ephemeral Git worktrees made easy
+//!
+//! The supported library surface is [`api::Phantom`] plus the types re-exported
+//! here: the option and result structs it uses, the error type, and the
+//! executor and filesystem traits with their real and mock implementations.
+//! The remaining modules are internal to the `phantom` binary and may change
+//! in any release.
+//!
+//! The default `cli` feature builds the binary. Library users can turn it off
+//! to drop clap and the terminal helpers:
+//!
+//! ```toml
+//! phantom-rs = { version = "0.1", default-features = false }
+//! ```
+
+pub mod api;
+
+#[cfg(feature = "cli")]
+#[doc(hidden)]
 pub mod cli;
+#[doc(hidden)]
 pub mod config;
+#[doc(hidden)]
 pub mod core;
+#[doc(hidden)]
 pub mod git;
+#[doc(hidden)]
 pub mod process;
+#[doc(hidden)]
+pub mod workspace;
+#[doc(hidden)]
 pub mod worktree;
 
 #[cfg(test)]
 pub mod test_utils;
 
+pub use crate::api::Phantom;
+pub use crate::core::command_executor::{
+    CommandArgs, CommandConfig, CommandExecutor, CommandOutput, OutputStream,
+};
+pub use crate::core::env_map::EnvMap;
 pub use crate::core::error::PhantomError;
+pub use crate::core::executors::{
+    CommandExpectationBuilder, MockCommandExecutor, RealCommandExecutor,
+};
+pub use crate::core::filesystem::FileSystem;
+pub use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+pub use crate::core::filesystems::{FileSystemExpectation, MockFileSystem, RealFileSystem};
 pub use crate::core::result::Result;
+pub use crate::process::spawn::SpawnSuccess;
+pub use crate::worktree::file_copier::ProgressReporter;
+pub use crate::worktree::list::{ListOptions, ListWorktreesSuccess, WorktreeInfo, WorktreeOrigin};
+pub use crate::worktree::locate::WhereWorktreeSuccess;
+pub use crate::worktree::types::{
+    AttachWorktreeOptions, AttachWorktreeSuccess, CopyFilesSelection, CreateWorktreeOptions,
+    CreateWorktreeSuccess, DeleteOutcome, DeleteWorktreeOptions, DeleteWorktreeSuccess,
+    ForceOptions,
+};
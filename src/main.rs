@@ -1,54 +1,78 @@
-use anyhow::{anyhow, Result};
 use clap::Parser;
+use phantom_rs::cli;
 use phantom_rs::cli::context::ProductionContext;
-use phantom_rs::cli::{self, Commands};
+use phantom_rs::core::utils::{collect_timings, current_timings, Timings};
 use std::process;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use std::time::Instant;
 
 #[tokio::main]
 async fn main() {
     // Parse CLI arguments
+    let started = Instant::now();
     let cli = cli::Cli::parse();
+    let parse_time = started.elapsed();
 
     // Initialize output handler based on flags
-    cli::output::init_output(cli.quiet, cli.verbose, false);
+    let log_format = cli.log_format.unwrap_or_default();
+    cli::output::init_output(cli.quiet, cli.verbose, false, log_format, cli.color);
 
-    // Initialize tracing if verbose mode
-    if cli.verbose {
-        if let Err(e) = init_tracing() {
+    // Initialize tracing if verbose mode, a log format or command tracing was asked for
+    if cli.trace_commands {
+        phantom_rs::core::command_executor::set_trace_commands(true);
+    }
+    if cli.verbose || cli.log_format.is_some() || cli.trace_commands {
+        let commands_only = cli.trace_commands && !cli.verbose;
+        let color = cli::output::output().color;
+        if let Err(e) = cli::logging::init_tracing(log_format, color, commands_only) {
             eprintln!("Failed to initialize tracing: {e}");
         }
     }
 
-    // Create handler context
-    let context = ProductionContext::default();
+    let (report_timings, profile_startup) = (cli.timings, cli.profile_startup);
+    let report = move |timings: &Timings| {
+        if report_timings {
+            cli::output::report_timings(timings, started.elapsed());
+        }
+        if profile_startup {
+            cli::output::report_startup_profile(parse_time, timings, started.elapsed());
+        }
+    };
+
+    // Create handler context; the repository config is only loaded once something asks for it.
+    // Handlers that exit with a command's status (exec, shell) report before exiting.
+    let context = ProductionContext::default()
+        .with_strict_config(cli.strict_config)
+        .with_exit_report(move || {
+            if let Some(timings) = current_timings() {
+                report(&timings);
+            }
+        });
+
+    // Point repository discovery at --repo before anything looks for the repository
+    let repository = cli.repo.as_deref().map(cli::resolve_repository).transpose();
 
     // Handle commands
-    let result = match cli.command {
-        Commands::Create(args) => cli::handlers::create::handle(args, context.clone()).await,
-        Commands::Attach(args) => cli::handlers::attach::handle(args, context.clone()).await,
-        Commands::List(args) => cli::handlers::list::handle(args, context.clone()).await,
-        Commands::Where(args) => cli::handlers::where_cmd::handle(args, context.clone()).await,
-        Commands::Delete(args) => cli::handlers::delete::handle(args, context.clone()).await,
-        Commands::Exec(args) => cli::handlers::exec::handle(args, context.clone()).await,
-        Commands::Shell(args) => cli::handlers::shell::handle(args, context.clone()).await,
-        Commands::Version(args) => {
-            cli::handlers::version::handle(args);
-            Ok(())
+    let dispatch = async move {
+        match repository {
+            Err(e) => Err(e),
+            Ok(Some(repository)) => {
+                cli::run(cli.command, context.with_repository(repository)).await
+            }
+            Ok(None) => cli::run(cli.command, context).await,
         }
-        Commands::Completion(args) => cli::handlers::completion::handle(args),
+    };
+    let result = if report_timings || profile_startup {
+        let (result, timings) = collect_timings(dispatch).await;
+        report(&timings);
+        result
+    } else {
+        dispatch.await
     };
 
     // Handle errors
     if let Err(e) = result {
-        // Format error message to include root cause
-        let error_message = if e.chain().count() > 1 {
-            // Include the full error chain for better context
-            format!("{e:#}")
-        } else {
-            e.to_string()
-        };
-        cli::output::output().error(&error_message);
+        // Include the full error chain, and git's stderr when a git command failed
+        cli::output::output().error(&cli::error::format_error(&e));
 
         // Try to find a PhantomError in the error chain to determine the correct exit code
         let exit_code = if let Some(phantom_err) = e.downcast_ref::<phantom_rs::PhantomError>() {
@@ -68,23 +92,3 @@ async fn main() {
         process::exit(exit_code);
     }
 }
-
-/// Initialize the tracing subscriber with environment filter
-fn init_tracing() -> Result<()> {
-    let filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("info"))
-        .add_directive("phantom=debug".parse().unwrap());
-
-    let fmt_layer = tracing_subscriber::fmt::layer()
-        .with_target(false)
-        .with_thread_ids(false)
-        .with_thread_names(false);
-
-    tracing_subscriber::registry()
-        .with(filter)
-        .with(fmt_layer)
-        .try_init()
-        .map_err(|e| anyhow!("Failed to initialize tracing: {}", e))?;
-
-    Ok(())
-}
@@ -0,0 +1,165 @@
+use crate::core::env_map::EnvMap;
+use crate::core::filesystem::FileSystem;
+use crate::Result;
+use std::path::Path;
+
+/// Env file loaded from the worktree root unless the `envFile` config names another
+pub const DEFAULT_ENV_FILE: &str = ".phantom.env";
+
+/// Variables parsed from an env file, with a warning for every line that was skipped
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvFile {
+    pub vars: EnvMap,
+    pub warnings: Vec<String>,
+}
+
+/// Parse `KEY=VALUE` lines in the style of a shell or dotenv file
+///
+/// Blank lines and `#` comments are skipped, and an `export ` prefix is allowed.
+/// Double-quoted values understand `\n`, `\"` and `\\`; single-quoted values are
+/// taken literally. An unquoted value ends at a ` #` comment. A line that cannot
+/// be parsed is skipped with a warning naming its line number.
+pub fn parse_env_file(contents: &str) -> EnvFile {
+    let mut env_file = EnvFile::default();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match parse_line(line) {
+            Ok((key, value)) => {
+                env_file.vars.insert(key, value);
+            }
+            Err(reason) => env_file.warnings.push(format!("line {}: {reason}", index + 1)),
+        }
+    }
+    env_file
+}
+
+fn parse_line(line: &str) -> std::result::Result<(&str, String), String> {
+    let line = line
+        .strip_prefix("export")
+        .filter(|rest| rest.starts_with(char::is_whitespace))
+        .map_or(line, str::trim_start);
+    let (key, value) = line.split_once('=').ok_or("expected KEY=VALUE")?;
+    let key = key.trim_end();
+    if !is_valid_key(key) {
+        return Err(format!("invalid variable name '{key}'"));
+    }
+    Ok((key, parse_value(value.trim_start())?))
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_value(value: &str) -> std::result::Result<String, String> {
+    let (parsed, rest) = if let Some(quoted) = value.strip_prefix('"') {
+        let mut parsed = String::new();
+        let mut chars = quoted.char_indices();
+        let end = loop {
+            match chars.next() {
+                Some((i, '"')) => break i,
+                Some((_, '\\')) => match chars.next() {
+                    Some((_, 'n')) => parsed.push('\n'),
+                    Some((_, c)) => parsed.push(c),
+                    None => return Err("unterminated double quote".to_string()),
+                },
+                Some((_, c)) => parsed.push(c),
+                None => return Err("unterminated double quote".to_string()),
+            }
+        };
+        (parsed, &quoted[end + 1..])
+    } else if let Some(quoted) = value.strip_prefix('\'') {
+        let end = quoted.find('\'').ok_or("unterminated single quote")?;
+        (quoted[..end].to_string(), &quoted[end + 1..])
+    } else {
+        let end = value.find(" #").or_else(|| value.find("\t#")).unwrap_or(value.len());
+        return Ok(value[..end].trim_end().to_string());
+    };
+
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(parsed)
+    } else {
+        Err(format!("unexpected '{rest}' after the quoted value"))
+    }
+}
+
+/// Read and parse the env file at `path`, or `None` if there is none
+pub async fn load_env_file(filesystem: &dyn FileSystem, path: &Path) -> Result<Option<EnvFile>> {
+    if !filesystem.exists(path).await.unwrap_or(false) {
+        return Ok(None);
+    }
+    let contents = filesystem.read_to_string(path).await?;
+    Ok(Some(parse_env_file(&contents)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::filesystems::RealFileSystem;
+
+    #[test]
+    fn test_parse_env_file() {
+        let env_file = parse_env_file(
+            "# ports for this worktree\n\
+             \n\
+             PORT=3001\n\
+             export DATABASE_URL=postgres://localhost/feature # per worktree\n\
+             GREETING=\"hello \\\"world\\\"\\nbye\"\n\
+             LITERAL='$HOME\\n' # kept as is\n\
+             EMPTY=\n\
+             URL=http://example.com/#anchor\n\
+             \x20 SPACED = value  \n",
+        );
+
+        assert!(env_file.warnings.is_empty(), "{:?}", env_file.warnings);
+        let vars = &env_file.vars;
+        assert_eq!(vars.get("PORT"), Some("3001"));
+        assert_eq!(vars.get("DATABASE_URL"), Some("postgres://localhost/feature"));
+        assert_eq!(vars.get("GREETING"), Some("hello \"world\"\nbye"));
+        assert_eq!(vars.get("LITERAL"), Some("$HOME\\n"));
+        assert_eq!(vars.get("EMPTY"), Some(""));
+        assert_eq!(vars.get("URL"), Some("http://example.com/#anchor"));
+        assert_eq!(vars.get("SPACED"), Some("value"));
+        assert_eq!(vars.len(), 7);
+    }
+
+    #[test]
+    fn test_parse_env_file_warns_about_malformed_lines() {
+        let env_file = parse_env_file(
+            "GOOD=1\n\
+             no equals sign\n\
+             1BAD=x\n\
+             OPEN=\"never closed\n\
+             TRAILING='quoted' junk\n\
+             exported=fine\n",
+        );
+
+        assert_eq!(env_file.vars.to_assignments(), ["GOOD=1", "exported=fine"]);
+        assert_eq!(
+            env_file.warnings,
+            [
+                "line 2: expected KEY=VALUE",
+                "line 3: invalid variable name '1BAD'",
+                "line 4: unterminated double quote",
+                "line 5: unexpected 'junk' after the quoted value",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_env_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_ENV_FILE);
+        let fs = RealFileSystem::new();
+        assert_eq!(load_env_file(&fs, &path).await.unwrap(), None);
+
+        std::fs::write(&path, "export PORT=3001\n").unwrap();
+        let env_file = load_env_file(&fs, &path).await.unwrap().unwrap();
+        assert_eq!(env_file.vars.get("PORT"), Some("3001"));
+    }
+}
@@ -1,11 +1,16 @@
-use crate::core::command_executor::{CommandConfig, CommandExecutor};
+use crate::core::command_executor::{
+    CommandConfig, CommandExecutor, CommandOutput, OnOutputLine, StdinMode,
+};
+use crate::core::env_map::{EnvMap, EnvSources};
+use crate::core::environment::Environment;
 use crate::core::filesystem::FileSystem;
-use crate::process::shell::{detect_shell, get_phantom_env};
+use crate::core::utils::{find_in_path, similar_commands};
+use crate::process::shell::{detect_shell, get_phantom_env, ShellInfo, ShellType};
 use crate::process::spawn::{spawn_process, SpawnConfig, SpawnSuccess};
+use crate::process::tty::{is_stdin_piped, is_stdin_tty};
 use crate::worktree::validate::validate_worktree_exists;
 use crate::{PhantomError, Result};
-use std::collections::HashMap;
-use std::env;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use tracing::{debug, error, info};
 
@@ -14,15 +19,20 @@ async fn execute_with_optional_executor<E>(
     command: &str,
     args: Vec<String>,
     cwd: PathBuf,
-    env: HashMap<String, String>,
+    env: EnvMap,
     executor: Option<E>,
 ) -> Result<SpawnSuccess>
 where
     E: CommandExecutor,
 {
     if let Some(executor) = executor {
-        // Use CommandExecutor
-        let config = CommandConfig::new(command).with_args(args).with_cwd(cwd).with_env(env);
+        // Use CommandExecutor, passing stdio through like the spawn path below
+        let config = CommandConfig::new(command)
+            .with_args(args)
+            .with_cwd(cwd)
+            .with_env(env)
+            .with_stdin(exec_stdin_mode())
+            .with_inherited_output();
 
         let output = executor.execute(config).await?;
         Ok(SpawnSuccess { exit_code: output.exit_code })
@@ -41,6 +51,115 @@ where
     }
 }
 
+/// Stdin for a command run in a worktree: phantom's own when it is a terminal or a pipe
+pub fn exec_stdin_mode() -> StdinMode {
+    if is_stdin_tty() || is_stdin_piped() {
+        StdinMode::Inherit
+    } else {
+        StdinMode::Null
+    }
+}
+
+/// `environment` plus the phantom variables for a worktree, with `extra_env` on top
+///
+/// `shell_type` is the shell being started, or `Unknown` for any other command.
+fn worktree_env(
+    environment: &dyn Environment,
+    shell_type: ShellType,
+    worktree_name: &str,
+    worktree_path: &Path,
+    extra_env: &EnvMap,
+) -> EnvMap {
+    let mut env: EnvMap = environment.vars().into_iter().collect();
+    env.overlay(
+        EnvSources {
+            phantom: get_phantom_env(
+                environment,
+                shell_type,
+                worktree_name,
+                &worktree_path.to_string_lossy(),
+            ),
+            flags: extra_env.clone(),
+            ..Default::default()
+        }
+        .merge(),
+    );
+    env
+}
+
+/// The directory to run in: `subdir` of the worktree, or the worktree itself when `None`
+///
+/// `subdir` is resolved through symlinks and must stay inside the worktree.
+pub async fn resolve_worktree_dir(
+    worktree_path: &Path,
+    subdir: Option<&Path>,
+    filesystem: &dyn FileSystem,
+) -> Result<PathBuf> {
+    let Some(subdir) = subdir else {
+        return Ok(worktree_path.to_path_buf());
+    };
+    let escape = || PhantomError::ValidationFailed {
+        reason: format!("Directory '{}' is outside the worktree", subdir.display()),
+    };
+    if subdir.has_root() || subdir.is_absolute() {
+        return Err(escape());
+    }
+
+    let root = filesystem.canonicalize(worktree_path).await?;
+    let joined = worktree_path.join(subdir);
+    let dir = filesystem.canonicalize(&joined).await.map_err(|_| PhantomError::InvalidPath {
+        path: joined.display().to_string(),
+        reason: "no such directory in the worktree".to_string(),
+    })?;
+    if !dir.starts_with(&root) {
+        return Err(escape());
+    }
+    if !filesystem.is_dir(&dir).await? {
+        return Err(PhantomError::InvalidPath {
+            path: joined.display().to_string(),
+            reason: "not a directory".to_string(),
+        });
+    }
+    Ok(dir)
+}
+
+/// The executable extensions Windows tries when PATHEXT is unset
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
+/// Fail with `CommandNotFound` unless `command` can be run from `dir`
+///
+/// A command with a path separator is checked relative to `dir`; a bare name is
+/// looked up in the `PATH` from `env`, falling back to the one in `environment`.
+pub async fn ensure_command_exists(
+    command: &str,
+    dir: &Path,
+    env: &EnvMap,
+    environment: &dyn Environment,
+    filesystem: &dyn FileSystem,
+) -> Result<()> {
+    const SUGGESTIONS: usize = 3;
+    let not_found =
+        |suggestions| PhantomError::CommandNotFound { command: command.to_string(), suggestions };
+
+    if command.contains(std::path::MAIN_SEPARATOR) || command.contains('/') {
+        if filesystem.is_executable(&dir.join(command)).await? {
+            return Ok(());
+        }
+        return Err(not_found(Vec::new()));
+    }
+
+    let path = match env.get("PATH") {
+        Some(path) => OsString::from(path),
+        None => OsString::from(environment.get_var("PATH").unwrap_or_default()),
+    };
+    // Windows finds `cargo` as `cargo.exe` through PATHEXT
+    let extensions = cfg!(windows).then(|| env.get("PATHEXT").unwrap_or(DEFAULT_PATHEXT));
+    if find_in_path(command, &path, extensions).is_some() {
+        return Ok(());
+    }
+    Err(not_found(similar_commands(command, &path, SUGGESTIONS)))
+}
+
 /// Execute a command in a specific directory
 pub async fn exec_in_dir(dir: &Path, command: &str, args: &[String]) -> Result<SpawnSuccess> {
     info!("Executing '{}' in directory: {}", command, dir.display());
@@ -56,13 +175,17 @@ pub async fn exec_in_dir(dir: &Path, command: &str, args: &[String]) -> Result<S
     spawn_process(config).await
 }
 
-/// Execute a command in a worktree with optional CommandExecutor
+/// Execute a command in a worktree, or its `subdir`, with optional CommandExecutor
+#[allow(clippy::too_many_arguments)]
 pub async fn exec_in_worktree<E>(
     git_root: &Path,
     worktree_name: &str,
     command: &str,
     args: &[String],
+    extra_env: &EnvMap,
+    subdir: Option<&Path>,
     filesystem: &dyn FileSystem,
+    environment: &dyn Environment,
     executor: Option<E>,
 ) -> Result<SpawnSuccess>
 where
@@ -74,24 +197,106 @@ where
 
     info!("Executing '{}' in worktree '{}' at {}", command, worktree_name, worktree_path.display());
 
-    // Prepare environment with phantom variables
-    let mut env: HashMap<String, String> = env::vars().collect();
-    let phantom_env = get_phantom_env(worktree_name, &worktree_path.to_string_lossy());
-    env.extend(phantom_env);
+    let env =
+        worktree_env(environment, ShellType::Unknown, worktree_name, &worktree_path, extra_env);
+    let cwd = resolve_worktree_dir(&worktree_path, subdir, filesystem).await?;
+    execute_with_optional_executor(command, args.to_vec(), cwd, env, executor).await
+}
+
+/// Execute a command in a worktree, capturing its stdout and stderr instead of passing them through
+#[allow(clippy::too_many_arguments)]
+pub async fn capture_in_worktree<E>(
+    git_root: &Path,
+    worktree_name: &str,
+    command: &str,
+    args: &[String],
+    extra_env: &EnvMap,
+    subdir: Option<&Path>,
+    filesystem: &dyn FileSystem,
+    environment: &dyn Environment,
+    executor: E,
+) -> Result<CommandOutput>
+where
+    E: CommandExecutor,
+{
+    info!("Capturing '{}' in worktree '{}'", command, worktree_name);
+    let config = captured_config(
+        git_root,
+        worktree_name,
+        command,
+        args,
+        extra_env,
+        subdir,
+        filesystem,
+        environment,
+    )
+    .await?;
+    executor.execute(config).await
+}
 
-    execute_with_optional_executor(
+/// Capture a command's output like [`capture_in_worktree`], passing each line to `on_line` as it is written
+#[allow(clippy::too_many_arguments)]
+pub async fn stream_in_worktree<E>(
+    git_root: &Path,
+    worktree_name: &str,
+    command: &str,
+    args: &[String],
+    extra_env: &EnvMap,
+    subdir: Option<&Path>,
+    filesystem: &dyn FileSystem,
+    environment: &dyn Environment,
+    executor: E,
+    on_line: &OnOutputLine<'_>,
+) -> Result<CommandOutput>
+where
+    E: CommandExecutor,
+{
+    info!("Streaming '{}' in worktree '{}'", command, worktree_name);
+    let config = captured_config(
+        git_root,
+        worktree_name,
         command,
-        args.to_vec(),
-        worktree_path.to_path_buf(),
-        env,
-        executor,
+        args,
+        extra_env,
+        subdir,
+        filesystem,
+        environment,
     )
-    .await
+    .await?;
+    executor.execute_streaming(config, on_line).await
+}
+
+/// The command to run in a worktree, or its `subdir`, with its output captured
+#[allow(clippy::too_many_arguments)]
+async fn captured_config(
+    git_root: &Path,
+    worktree_name: &str,
+    command: &str,
+    args: &[String],
+    extra_env: &EnvMap,
+    subdir: Option<&Path>,
+    filesystem: &dyn FileSystem,
+    environment: &dyn Environment,
+) -> Result<CommandConfig> {
+    let validation = validate_worktree_exists(git_root, worktree_name, filesystem).await?;
+    let worktree_path = validation.path;
+    let cwd = resolve_worktree_dir(&worktree_path, subdir, filesystem).await?;
+    Ok(CommandConfig::new(command)
+        .with_args(args.to_vec())
+        .with_env(worktree_env(
+            environment,
+            ShellType::Unknown,
+            worktree_name,
+            &worktree_path,
+            extra_env,
+        ))
+        .with_cwd(cwd)
+        .with_stdin(exec_stdin_mode()))
 }
 
 /// Spawn a shell in a specific directory
-pub async fn spawn_shell_in_dir(dir: &Path) -> Result<SpawnSuccess> {
-    let shell_info = detect_shell()?;
+pub async fn spawn_shell_in_dir(dir: &Path, environment: &dyn Environment) -> Result<SpawnSuccess> {
+    let shell_info = detect_shell(environment)?;
     info!("Spawning {} shell in directory: {}", shell_info.name, dir.display());
 
     let config = SpawnConfig {
@@ -105,11 +310,16 @@ pub async fn spawn_shell_in_dir(dir: &Path) -> Result<SpawnSuccess> {
     spawn_process(config).await
 }
 
-/// Spawn a shell in a worktree with optional CommandExecutor
+/// Spawn a shell in a worktree, or its `subdir`, with optional CommandExecutor
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_shell_in_worktree<E>(
     git_root: &Path,
     worktree_name: &str,
+    shell_info: &ShellInfo,
+    extra_env: &EnvMap,
+    subdir: Option<&Path>,
     filesystem: &dyn FileSystem,
+    environment: &dyn Environment,
     executor: Option<E>,
 ) -> Result<SpawnSuccess>
 where
@@ -119,7 +329,6 @@ where
     let validation = validate_worktree_exists(git_root, worktree_name, filesystem).await?;
     let worktree_path = validation.path;
 
-    let shell_info = detect_shell()?;
     info!(
         "Spawning {} shell in worktree '{}' at {}",
         shell_info.name,
@@ -127,18 +336,16 @@ where
         worktree_path.display()
     );
 
-    // Prepare environment with phantom variables
-    let mut env: HashMap<String, String> = env::vars().collect();
-    let phantom_env = get_phantom_env(worktree_name, &worktree_path.to_string_lossy());
-    env.extend(phantom_env);
+    let env =
+        worktree_env(environment, shell_info.shell_type, worktree_name, &worktree_path, extra_env);
+    let cwd = resolve_worktree_dir(&worktree_path, subdir, filesystem).await?;
 
-    // Add a custom prompt or greeting for the shell
     debug!("Shell type: {:?}", shell_info.shell_type);
 
     let result = execute_with_optional_executor(
         &shell_info.path,
-        shell_info.shell_type.init_args().iter().map(|s| s.to_string()).collect(),
-        worktree_path.to_path_buf(),
+        shell_info.shell_type.interactive_args(),
+        cwd,
         env,
         executor,
     )
@@ -189,6 +396,7 @@ pub async fn exec_commands_in_dir(dir: &Path, commands: &[String]) -> Result<Vec
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::environment::{MockEnvironment, RealEnvironment};
     use crate::core::executors::RealCommandExecutor;
     use crate::core::filesystems::RealFileSystem;
     use crate::test_utils::TestRepo;
@@ -196,6 +404,50 @@ mod tests {
     use crate::worktree::types::CreateWorktreeOptions;
     use tempfile::TempDir;
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_ensure_command_exists() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = TempDir::new().unwrap();
+        let script = dir.path().join("run.sh");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let mut env = EnvMap::new();
+        env.insert("PATH", dir.path().to_string_lossy());
+        let check = |command: &'static str| {
+            let (dir, env) = (dir.path().to_path_buf(), env.clone());
+            async move {
+                ensure_command_exists(command, &dir, &env, &RealEnvironment, &RealFileSystem::new())
+                    .await
+            }
+        };
+
+        check("run.sh").await.unwrap();
+        check("./run.sh").await.unwrap();
+        match check("rnu.sh").await {
+            Err(PhantomError::CommandNotFound { command, suggestions }) => {
+                assert_eq!(command, "rnu.sh");
+                assert_eq!(suggestions, vec!["run.sh"]);
+            }
+            other => panic!("expected CommandNotFound, got {other:?}"),
+        }
+        assert!(matches!(
+            check("./missing.sh").await,
+            Err(PhantomError::CommandNotFound { suggestions, .. }) if suggestions.is_empty()
+        ));
+        // Without PATH in the command's env, the one from the environment is searched
+        let environment = MockEnvironment::new().with_var("PATH", &dir.path().to_string_lossy());
+        ensure_command_exists(
+            "run.sh",
+            dir.path(),
+            &EnvMap::new(),
+            &environment,
+            &RealFileSystem::new(),
+        )
+        .await
+        .unwrap();
+    }
+
     #[tokio::test]
     async fn test_exec_in_dir() {
         let temp_dir = TempDir::new().unwrap();
@@ -265,7 +517,10 @@ mod tests {
             "test-worktree",
             "echo",
             &["hello".to_string()],
+            &EnvMap::new(),
+            None,
             &filesystem,
+            &RealEnvironment,
             None,
         )
         .await;
@@ -274,6 +529,155 @@ mod tests {
         assert_eq!(result.unwrap().exit_code, 0);
     }
 
+    #[tokio::test]
+    async fn test_exec_in_worktree_passes_output_through_executor() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+        create_worktree(RealCommandExecutor, repo.path(), "feature", Default::default())
+            .await
+            .unwrap();
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("cat").returns_output("", "", 0);
+        let filesystem = RealFileSystem::new();
+        let environment = MockEnvironment::new().with_var("EDITOR", "vim");
+        let extra = EnvMap::from([("DATABASE_URL", "postgres://localhost/test")]);
+        exec_in_worktree(
+            repo.path(),
+            "feature",
+            "cat",
+            &[],
+            &extra,
+            None,
+            &filesystem,
+            &environment,
+            Some(mock.clone()),
+        )
+        .await
+        .unwrap();
+        capture_in_worktree(
+            repo.path(),
+            "feature",
+            "cat",
+            &[],
+            &extra,
+            None,
+            &filesystem,
+            &environment,
+            mock.clone(),
+        )
+        .await
+        .unwrap();
+
+        let calls = mock.calls();
+        assert!(calls[0].inherit_output);
+        assert!(!calls[1].inherit_output);
+        for call in &calls {
+            assert_eq!(call.stdin, exec_stdin_mode());
+            assert_eq!(call.env.as_ref().unwrap().get("PHANTOM_WORKTREE"), Some("feature"));
+            assert_eq!(call.env.as_ref().unwrap().get("EDITOR"), Some("vim"));
+            assert_eq!(
+                call.env.as_ref().unwrap().get("DATABASE_URL"),
+                Some("postgres://localhost/test")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_capture_in_worktree() {
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+        create_worktree(RealCommandExecutor, repo.path(), "feature", Default::default())
+            .await
+            .unwrap();
+
+        let output = capture_in_worktree(
+            repo.path(),
+            "feature",
+            "sh",
+            &["-c".to_string(), "echo \"$PHANTOM_WORKTREE\"; echo oops >&2; exit 3".to_string()],
+            &EnvMap::new(),
+            None,
+            &RealFileSystem::new(),
+            &RealEnvironment,
+            RealCommandExecutor,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(output.stdout, "feature\n");
+        assert_eq!(output.stderr, "oops\n");
+        assert_eq!(output.exit_code, 3);
+    }
+
+    #[tokio::test]
+    async fn test_capture_in_worktree_with_awkward_paths() {
+        let repo = TestRepo::new_at("Work Projects/client 'repo' #1 é").await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+        create_worktree(RealCommandExecutor, repo.path(), "feature", Default::default())
+            .await
+            .unwrap();
+        let worktree = repo.path().join(".git/phantom/worktrees/feature");
+        std::fs::create_dir(worktree.join("sub dir=1")).unwrap();
+
+        let output = capture_in_worktree(
+            repo.path(),
+            "feature",
+            "sh",
+            &[
+                "-c".to_string(),
+                "pwd; printf '%s' \"$1\"".to_string(),
+                "sh".to_string(),
+                "a b; $(x) 'q' é".to_string(),
+            ],
+            &EnvMap::new(),
+            Some(Path::new("sub dir=1")),
+            &RealFileSystem::new(),
+            &RealEnvironment,
+            RealCommandExecutor,
+        )
+        .await
+        .unwrap();
+
+        let expected = worktree.canonicalize().unwrap().join("sub dir=1");
+        assert_eq!(output.stdout, format!("{}\na b; $(x) 'q' é", expected.display()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_worktree_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let worktree = temp_dir.path().join("worktree");
+        std::fs::create_dir_all(worktree.join("src/bin")).unwrap();
+        std::fs::write(worktree.join("README.md"), "").unwrap();
+        std::fs::create_dir(temp_dir.path().join("sibling")).unwrap();
+        let filesystem = RealFileSystem::new();
+        let root = worktree.canonicalize().unwrap();
+
+        assert_eq!(resolve_worktree_dir(&worktree, None, &filesystem).await.unwrap(), worktree);
+        for (subdir, expected) in [("src/bin", "src/bin"), ("src/../src", "src")] {
+            let dir = resolve_worktree_dir(&worktree, Some(Path::new(subdir)), &filesystem).await;
+            assert_eq!(dir.unwrap(), root.join(expected));
+        }
+
+        for escape in ["..", "../sibling", "src/../../sibling", "/tmp"] {
+            let err = resolve_worktree_dir(&worktree, Some(Path::new(escape)), &filesystem)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, PhantomError::ValidationFailed { .. }), "{escape}: {err}");
+        }
+
+        let err = resolve_worktree_dir(&worktree, Some(Path::new("missing")), &filesystem)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(&worktree.join("missing").display().to_string()));
+        let err = resolve_worktree_dir(&worktree, Some(Path::new("README.md")), &filesystem)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not a directory"));
+    }
+
     #[tokio::test]
     async fn test_exec_in_nonexistent_worktree() {
         let repo = TestRepo::new().await.unwrap();
@@ -284,7 +688,10 @@ mod tests {
             "nonexistent",
             "echo",
             &["hello".to_string()],
+            &EnvMap::new(),
+            None,
             &filesystem,
+            &RealEnvironment,
             None,
         )
         .await;
@@ -353,7 +760,10 @@ mod tests {
             "test-env",
             "printenv",
             &["PHANTOM_WORKTREE".to_string()],
+            &EnvMap::new(),
+            None,
             &filesystem,
+            &RealEnvironment,
             None,
         )
         .await;
@@ -396,7 +806,7 @@ mod tests {
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            PhantomError::CommandNotFound { command } => {
+            PhantomError::CommandNotFound { command, .. } => {
                 assert_eq!(command, "nonexistent-command-xyz123");
             }
             _ => panic!("Expected CommandNotFound error"),
@@ -427,7 +837,10 @@ mod tests {
             "does-not-exist",
             "echo",
             &["test".to_string()],
+            &EnvMap::new(),
+            None,
             &filesystem,
+            &RealEnvironment,
             None,
         )
         .await;
@@ -1,4 +1,4 @@
-use crate::core::command_executor::{CommandArgs, CommandExecutor};
+use crate::core::command_executor::{CommandArgs, CommandExecutor, StdinMode};
 use crate::{PhantomError, Result};
 use smallvec::smallvec;
 use tracing::{debug, error};
@@ -51,9 +51,10 @@ where
     // Execute fzf with stdin data
     let config = crate::core::command_executor::CommandConfig::new("fzf")
         .with_args_smallvec(args)
-        .with_stdin_data(stdin_data);
+        .with_stdin(StdinMode::Data(stdin_data));
 
-    match executor.execute(config).await {
+    // fzf owns the terminal, so it runs in its own process group that follows phantom's job control
+    match executor.execute_interactive(config).await {
         Ok(output) => {
             match output.exit_code {
                 0 => {
@@ -90,7 +91,10 @@ where
         Err(e) => {
             if e.to_string().contains("command not found") || e.to_string().contains("No such file")
             {
-                Err(PhantomError::CommandNotFound { command: "fzf".to_string() })
+                Err(PhantomError::CommandNotFound {
+                    command: "fzf".to_string(),
+                    suggestions: Vec::new(),
+                })
             } else {
                 Err(e)
             }
@@ -175,11 +179,17 @@ mod tests {
         let items = vec!["item1".to_string(), "item2".to_string()];
 
         // Set up expectation for fzf command
-        mock.expect_command("fzf").with_stdin_data("item1\nitem2").returns_output("item1\n", "", 0);
+        mock.expect_command("fzf")
+            .with_stdin(StdinMode::Data("item1\nitem2".to_string()))
+            .returns_output("item1\n", "", 0);
 
         let result = select_with_fzf(&mock, items, FzfOptions::default()).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), Some("item1".to_string()));
+
+        // Interactive selection must never be cut off by a timeout
+        assert_eq!(mock.calls()[0].timeout, None);
+        assert!(mock.calls()[0].interactive);
     }
 
     #[test]
@@ -212,11 +222,9 @@ mod tests {
         let items = vec!["single-item".to_string()];
 
         // Expect fzf to be called with the single item as stdin
-        mock.expect_command("fzf").with_stdin_data("single-item").returns_output(
-            "single-item\n",
-            "",
-            0,
-        );
+        mock.expect_command("fzf")
+            .with_stdin(StdinMode::Data("single-item".to_string()))
+            .returns_output("single-item\n", "", 0);
 
         let result = select_with_fzf(&mock, items, FzfOptions::default()).await;
 
@@ -244,7 +252,7 @@ mod tests {
                 "--preview",
                 "echo preview: {}",
             ])
-            .with_stdin_data("item1\nitem2")
+            .with_stdin(StdinMode::Data("item1\nitem2".to_string()))
             .returns_output("item2\n", "", 0);
 
         let result = select_with_fzf(&mock, items, options).await;
@@ -259,7 +267,9 @@ mod tests {
         let items = vec!["item1".to_string(), "item2".to_string()];
 
         // Simulate user pressing Ctrl+C (exit code 130)
-        mock.expect_command("fzf").with_stdin_data("item1\nitem2").returns_output("", "", 130);
+        mock.expect_command("fzf")
+            .with_stdin(StdinMode::Data("item1\nitem2".to_string()))
+            .returns_output("", "", 130);
 
         let result = select_with_fzf(&mock, items, FzfOptions::default()).await;
 
@@ -273,7 +283,9 @@ mod tests {
         let items = vec!["item1".to_string(), "item2".to_string()];
 
         // Simulate no match found (exit code 1)
-        mock.expect_command("fzf").with_stdin_data("item1\nitem2").returns_output("", "", 1);
+        mock.expect_command("fzf")
+            .with_stdin(StdinMode::Data("item1\nitem2".to_string()))
+            .returns_output("", "", 1);
 
         let result = select_with_fzf(&mock, items, FzfOptions::default()).await;
 
@@ -288,7 +300,8 @@ mod tests {
         let phantom_error = PhantomError::Io(io_error);
         assert!(!phantom_error.to_string().is_empty());
 
-        let exec_error = PhantomError::CommandNotFound { command: "fzf".to_string() };
+        let exec_error =
+            PhantomError::CommandNotFound { command: "fzf".to_string(), suggestions: Vec::new() };
         assert!(exec_error.to_string().contains("Command 'fzf' not found"));
 
         let exit_error = PhantomError::ProcessFailed { command: "fzf".to_string(), code: 2 };
@@ -368,7 +381,7 @@ mod tests {
 
         // Simulate user selecting the second option
         mock.expect_command("fzf")
-            .with_stdin_data("option-one\noption-two\noption-three")
+            .with_stdin(StdinMode::Data("option-one\noption-two\noption-three".to_string()))
             .returns_output("option-two\n", "", 0);
 
         let result = select_with_fzf(&mock, items.clone(), FzfOptions::default()).await;
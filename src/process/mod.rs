@@ -1,9 +1,17 @@
+pub mod envfile;
 pub mod exec;
+#[cfg(feature = "cli")]
 pub mod fzf;
+#[cfg(feature = "cli")]
 pub mod kitty;
+#[cfg(feature = "cli")]
 pub mod multiplexer;
+#[cfg(feature = "cli")]
 pub mod prompt;
 pub mod shell;
 pub mod spawn;
+#[cfg(feature = "cli")]
+pub mod title;
+#[cfg(feature = "cli")]
 pub mod tmux;
 pub mod tty;
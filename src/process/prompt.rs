@@ -167,9 +167,9 @@ mod tests {
     #[test]
     fn test_prompt_default_display() {
         // Test with default value
-        let default = Some("default-value");
+        let default = "default-value";
         let message = "Enter value";
-        let formatted = format!("{} [{}] ", message, default.unwrap());
+        let formatted = format!("{message} [{default}] ");
         assert_eq!(formatted, "Enter value [default-value] ");
 
         // Test without default
@@ -284,8 +284,8 @@ mod tests {
 
     #[test]
     fn test_empty_string_checks() {
-        assert!("".is_empty());
-        assert!(!"non-empty".is_empty());
+        assert!(String::new().is_empty());
+        assert!(!String::from("non-empty").is_empty());
         assert!("   ".trim().is_empty());
         assert!("\n".trim().is_empty());
     }
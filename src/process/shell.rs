@@ -1,11 +1,18 @@
 use crate::core::command_executor::{CommandConfig, CommandExecutor};
 use crate::core::const_utils::env_vars;
-use crate::Result;
-use std::collections::HashMap;
-use std::env;
+use crate::core::env_map::EnvMap;
+use crate::core::environment::Environment;
+use crate::core::utils::command_exists;
+use crate::{PhantomError, Result};
 use std::path::Path;
 use tracing::{debug, info};
 
+/// Wraps fish's prompt so it starts with `PHANTOM_PROMPT_PREFIX`, after config.fish has set it up
+const FISH_PROMPT_INIT: &str =
+    "functions -q fish_prompt; and functions -c fish_prompt __phantom_fish_prompt; \
+     function fish_prompt; printf '%s' \"$PHANTOM_PROMPT_PREFIX\"; \
+     functions -q __phantom_fish_prompt; and __phantom_fish_prompt; end";
+
 /// Detected shell information
 #[derive(Debug, Clone, PartialEq)]
 pub struct ShellInfo {
@@ -21,10 +28,17 @@ pub enum ShellType {
     Zsh,
     Fish,
     Sh,
+    PowerShell,
+    Cmd,
     Unknown,
 }
 
 impl ShellType {
+    /// The type of the shell at `path`, such as `/bin/zsh`
+    pub fn from_path(path: &str) -> Self {
+        analyze_shell_path(path).map_or(ShellType::Unknown, |info| info.shell_type)
+    }
+
     /// Get the appropriate RC file for this shell type
     pub fn rc_file(&self) -> Option<&'static str> {
         match self {
@@ -32,6 +46,8 @@ impl ShellType {
             ShellType::Zsh => Some(".zshrc"),
             ShellType::Fish => Some(".config/fish/config.fish"),
             ShellType::Sh => Some(".profile"),
+            ShellType::PowerShell => Some("Documents/PowerShell/Microsoft.PowerShell_profile.ps1"),
+            ShellType::Cmd => None,
             ShellType::Unknown => None,
         }
     }
@@ -39,19 +55,74 @@ impl ShellType {
     /// Get shell-specific initialization arguments
     pub fn init_args(&self) -> Vec<&'static str> {
         match self {
-            ShellType::Bash => vec!["-i"], // Interactive
-            ShellType::Zsh => vec!["-i"],  // Interactive
-            ShellType::Fish => vec!["-i"], // Interactive
-            ShellType::Sh => vec![],       // No special args for sh
+            ShellType::Bash => vec!["-i"],            // Interactive
+            ShellType::Zsh => vec!["-i"],             // Interactive
+            ShellType::Fish => vec!["-i"],            // Interactive
+            ShellType::Sh => vec![],                  // No special args for sh
+            ShellType::PowerShell => vec!["-NoLogo"], // Interactive by default
+            ShellType::Cmd => vec![],                 // Interactive by default
             ShellType::Unknown => vec![],
         }
     }
+
+    /// Arguments that make the shell show the phantom prompt prefix; only fish needs any
+    pub fn prompt_args(&self) -> Option<Vec<String>> {
+        match self {
+            ShellType::Fish => {
+                Some(vec!["--init-command".to_string(), FISH_PROMPT_INIT.to_string()])
+            }
+            _ => None,
+        }
+    }
+
+    /// Arguments for an interactive phantom shell: the init arguments, then the prompt ones
+    pub fn interactive_args(&self) -> Vec<String> {
+        let init_args = self.init_args().into_iter().map(String::from);
+        init_args.chain(self.prompt_args().into_iter().flatten()).collect()
+    }
+
+    /// Flag that makes the shell run a command string and exit
+    pub fn command_flag(&self) -> &'static str {
+        match self {
+            ShellType::PowerShell => "-Command",
+            ShellType::Cmd => "/C",
+            // POSIX shells and fish all take -c
+            ShellType::Bash
+            | ShellType::Zsh
+            | ShellType::Fish
+            | ShellType::Sh
+            | ShellType::Unknown => "-c",
+        }
+    }
 }
 
 /// Detect the current shell
-pub fn detect_shell() -> Result<ShellInfo> {
-    // First, try the SHELL environment variable
-    if let Ok(shell_path) = env::var(env_vars::SHELL) {
+pub fn detect_shell(environment: &dyn Environment) -> Result<ShellInfo> {
+    detect_shell_with_config(environment, None)
+}
+
+/// Detect the shell, preferring `$PHANTOM_SHELL` and then `configured` over `$SHELL`
+///
+/// A shell that was chosen explicitly must exist; it is not silently replaced.
+pub fn detect_shell_with_config(
+    environment: &dyn Environment,
+    configured: Option<&str>,
+) -> Result<ShellInfo> {
+    if let Some(shell_path) = environment.get_var(env_vars::PHANTOM_SHELL).filter(|s| !s.is_empty())
+    {
+        let shell_info = explicit_shell(&shell_path, env_vars::PHANTOM_SHELL)?;
+        debug!("Using shell from ${}: {:?}", env_vars::PHANTOM_SHELL, shell_info);
+        return Ok(shell_info);
+    }
+
+    if let Some(shell_path) = configured {
+        let shell_info = explicit_shell(shell_path, "the 'shell' config key")?;
+        debug!("Using shell from config: {:?}", shell_info);
+        return Ok(shell_info);
+    }
+
+    // Then try the SHELL environment variable
+    if let Some(shell_path) = environment.get_var(env_vars::SHELL) {
         if let Some(shell_info) = analyze_shell_path(&shell_path) {
             debug!("Detected shell from $SHELL: {:?}", shell_info);
             return Ok(shell_info);
@@ -64,24 +135,68 @@ pub fn detect_shell() -> Result<ShellInfo> {
         return Ok(shell_info);
     }
 
-    // Ultimate fallback: use /bin/sh
-    info!("Could not detect shell, falling back to /bin/sh");
-    Ok(ShellInfo { name: "sh".to_string(), path: "/bin/sh".to_string(), shell_type: ShellType::Sh })
+    if cfg!(windows) {
+        let comspec = environment.get_var(env_vars::COMSPEC);
+        let ps_module_path = environment.get_var(env_vars::PS_MODULE_PATH);
+        if let Some(shell_info) = analyze_windows_env(comspec.as_deref(), ps_module_path.as_deref())
+        {
+            debug!("Detected shell from Windows environment: {:?}", shell_info);
+            return Ok(shell_info);
+        }
+    }
+
+    Ok(fallback_shell())
+}
+
+/// Shell the user asked for by `setting`, which must be an existing executable
+fn explicit_shell(path: &str, setting: &str) -> Result<ShellInfo> {
+    let not_found =
+        || PhantomError::ShellNotFound { shell: path.to_string(), setting: setting.to_string() };
+    if !command_exists(path) {
+        return Err(not_found());
+    }
+    analyze_shell_path(path).ok_or_else(not_found)
+}
+
+/// Shell used when nothing else could be detected
+fn fallback_shell() -> ShellInfo {
+    if cfg!(windows) {
+        info!("Could not detect shell, falling back to cmd.exe");
+        ShellInfo {
+            name: "cmd.exe".to_string(),
+            path: "cmd.exe".to_string(),
+            shell_type: ShellType::Cmd,
+        }
+    } else {
+        info!("Could not detect shell, falling back to /bin/sh");
+        ShellInfo { name: "sh".to_string(), path: "/bin/sh".to_string(), shell_type: ShellType::Sh }
+    }
+}
+
+/// Final component of a path, accepting both `/` and `\` as separators
+fn shell_file_name(path: &str) -> Option<&str> {
+    path.trim_end_matches(['/', '\\']).rsplit(['/', '\\']).next().filter(|name| !name.is_empty())
 }
 
 /// Analyze a shell path and determine its type
 fn analyze_shell_path(path: &str) -> Option<ShellInfo> {
     let path_lower = path.to_lowercase();
-    let name = Path::new(path).file_name()?.to_str()?;
+    let name = shell_file_name(path)?;
+    let name_lower = name.to_lowercase();
+    let stem = name_lower.strip_suffix(".exe").unwrap_or(&name_lower);
 
-    let shell_type = if path_lower.contains("bash") || name == "bash" {
+    let shell_type = if path_lower.contains("bash") || stem == "bash" {
         ShellType::Bash
-    } else if path_lower.contains("zsh") || name == "zsh" {
+    } else if path_lower.contains("zsh") || stem == "zsh" {
         ShellType::Zsh
-    } else if path_lower.contains("fish") || name == "fish" {
+    } else if path_lower.contains("fish") || stem == "fish" {
         ShellType::Fish
-    } else if name == "sh" {
+    } else if stem == "sh" {
         ShellType::Sh
+    } else if stem == "pwsh" || stem == "powershell" {
+        ShellType::PowerShell
+    } else if stem == "cmd" {
+        ShellType::Cmd
     } else {
         ShellType::Unknown
     };
@@ -89,6 +204,29 @@ fn analyze_shell_path(path: &str) -> Option<ShellInfo> {
     Some(ShellInfo { name: name.to_string(), path: path.to_string(), shell_type })
 }
 
+/// Guess the shell from Windows environment variables
+///
+/// `PSModulePath` is set system-wide, but only a PowerShell session adds the
+/// per-user module directory under `Documents`; otherwise `COMSPEC` names cmd.
+fn analyze_windows_env(comspec: Option<&str>, ps_module_path: Option<&str>) -> Option<ShellInfo> {
+    let in_powershell = ps_module_path.is_some_and(|paths| {
+        paths.split(';').any(|entry| {
+            let entry = entry.to_lowercase().replace('/', "\\");
+            entry.contains("\\documents\\") && entry.contains("powershell")
+        })
+    });
+
+    if in_powershell {
+        return Some(ShellInfo {
+            name: "powershell.exe".to_string(),
+            path: "powershell.exe".to_string(),
+            shell_type: ShellType::PowerShell,
+        });
+    }
+
+    comspec.filter(|path| !path.is_empty()).and_then(analyze_shell_path)
+}
+
 /// Try to detect shell from parent process (Unix-specific)
 #[cfg(unix)]
 fn detect_from_parent_process() -> Option<ShellInfo> {
@@ -114,7 +252,50 @@ fn detect_from_parent_process() -> Option<ShellInfo> {
     None
 }
 
-#[cfg(not(unix))]
+/// Try to detect shell from parent process (Windows-specific)
+#[cfg(windows)]
+fn detect_from_parent_process() -> Option<ShellInfo> {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+
+    // SAFETY: the snapshot handle is checked before use and closed before returning,
+    // and PROCESSENTRY32W is initialised with its size as the API requires
+    let entries = unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return None;
+        }
+
+        let mut entries = Vec::new();
+        let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+        let mut found = Process32FirstW(snapshot, &mut entry) != 0;
+        while found {
+            let len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+            let exe = String::from_utf16_lossy(&entry.szExeFile[..len]);
+            entries.push((entry.th32ProcessID, entry.th32ParentProcessID, exe));
+            found = Process32NextW(snapshot, &mut entry) != 0;
+        }
+        CloseHandle(snapshot);
+        entries
+    };
+
+    let pid = std::process::id();
+    let ppid = entries.iter().find(|(id, _, _)| *id == pid).map(|(_, parent, _)| *parent)?;
+    let (_, _, exe) = entries.iter().find(|(id, _, _)| *id == ppid)?;
+
+    let shell_info = analyze_shell_path(exe)?;
+    // Only use recognized shells, as with the Unix detection
+    if matches!(shell_info.shell_type, ShellType::Unknown) {
+        return None;
+    }
+    Some(shell_info)
+}
+
+#[cfg(not(any(unix, windows)))]
 fn detect_from_parent_process() -> Option<ShellInfo> {
     None
 }
@@ -136,48 +317,86 @@ fn get_parent_pid() -> Option<u32> {
     None
 }
 
+/// Program and arguments that run `command_line` through the given shell
+pub fn shell_command(shell_info: &ShellInfo, command_line: &str) -> (String, Vec<String>) {
+    (
+        shell_info.path.clone(),
+        vec![shell_info.shell_type.command_flag().to_string(), command_line.to_string()],
+    )
+}
+
 /// Get environment variables for a phantom session
-pub fn get_phantom_env(worktree_name: &str, worktree_path: &str) -> HashMap<String, String> {
-    let mut env = HashMap::new();
+///
+/// The `(phantom:name)` prompt prefix goes where `shell_type` reads its prompt:
+/// `PS1` for bash, sh and unknown shells, `PROMPT` for zsh, and
+/// `PHANTOM_PROMPT_PREFIX` for the fish snippet in [`ShellType::prompt_args`].
+/// Bash, sh and zsh only get one when phantom inherited a prompt to extend.
+pub fn get_phantom_env(
+    environment: &dyn Environment,
+    shell_type: ShellType,
+    worktree_name: &str,
+    worktree_path: &str,
+) -> EnvMap {
+    let mut env = EnvMap::new();
 
     // Set phantom-specific environment variables
     env.insert("PHANTOM_WORKTREE".to_string(), worktree_name.to_string());
     env.insert("PHANTOM_WORKTREE_PATH".to_string(), worktree_path.to_string());
     env.insert("PHANTOM_ACTIVE".to_string(), "1".to_string());
 
-    // Update prompt if PS1 is set
-    if let Ok(ps1) = env::var("PS1") {
-        let phantom_ps1 = format!("(phantom:{worktree_name}) {ps1}");
-        env.insert("PS1".to_string(), phantom_ps1);
+    let prefix = format!("(phantom:{worktree_name}) ");
+    match shell_type {
+        ShellType::Bash | ShellType::Sh | ShellType::Unknown => {
+            if let Some(ps1) = environment.get_var("PS1") {
+                env.insert("PS1".to_string(), format!("{prefix}{ps1}"));
+            }
+        }
+        ShellType::Zsh => {
+            let ps1 = environment.get_var("PS1");
+            if let Some(prompt) = environment.get_var("PROMPT").or_else(|| ps1.clone()) {
+                let prompt = format!("{prefix}{prompt}");
+                // PS1 is another name for PROMPT in zsh, so an inherited one must agree
+                if ps1.is_some() {
+                    env.insert("PS1".to_string(), prompt.clone());
+                }
+                env.insert("PROMPT".to_string(), prompt);
+            }
+        }
+        ShellType::Fish => {
+            env.insert("PHANTOM_PROMPT_PREFIX".to_string(), prefix);
+        }
+        // Neither reads a prompt from the environment
+        ShellType::PowerShell | ShellType::Cmd => {}
     }
 
     env
 }
 
 /// Check if we're currently in a phantom session
-pub fn is_phantom_session() -> bool {
-    env::var("PHANTOM_ACTIVE").is_ok()
+pub fn is_phantom_session(environment: &dyn Environment) -> bool {
+    environment.get_var("PHANTOM_ACTIVE").is_some()
 }
 
 /// Get the current phantom worktree name if in a session
-pub fn current_phantom_worktree() -> Option<String> {
-    env::var("PHANTOM_WORKTREE").ok()
+pub fn current_phantom_worktree(environment: &dyn Environment) -> Option<String> {
+    environment.get_var("PHANTOM_WORKTREE")
 }
 
 /// Open an interactive shell in a directory with CommandExecutor
-pub async fn shell_in_dir<E>(executor: &E, dir: &Path) -> Result<()>
+pub async fn shell_in_dir<E>(executor: &E, environment: &dyn Environment, dir: &Path) -> Result<()>
 where
     E: CommandExecutor,
 {
-    let shell_info = detect_shell()?;
-    let worktree_name = dir.file_name().and_then(|n| n.to_str()).unwrap_or("phantom");
+    let shell_info = detect_shell(environment)?;
+    let dir_string = dir.to_string_lossy();
+    let worktree_name = shell_file_name(&dir_string).unwrap_or("phantom");
 
-    let env_vars = get_phantom_env(worktree_name, &dir.to_string_lossy());
+    let env_vars = get_phantom_env(environment, shell_info.shell_type, worktree_name, &dir_string);
 
     info!("Opening shell in: {}", dir.display());
 
     let config = CommandConfig::new(shell_info.path.clone())
-        .with_args(shell_info.shell_type.init_args().iter().map(|s| s.to_string()).collect())
+        .with_args(shell_info.shell_type.interactive_args())
         .with_cwd(dir.to_path_buf())
         .with_env(env_vars);
 
@@ -188,8 +407,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::environment::MockEnvironment;
     use crate::core::executors::MockCommandExecutor;
-    use crate::test_utils::EnvGuard;
 
     #[test]
     fn test_analyze_shell_path() {
@@ -200,12 +419,61 @@ mod tests {
         assert_eq!(analyze_shell_path("/usr/bin/ksh").unwrap().shell_type, ShellType::Unknown);
     }
 
+    #[test]
+    fn test_analyze_windows_shell_path() {
+        let info = analyze_shell_path(r"C:\Windows\System32\cmd.exe").unwrap();
+        assert_eq!(info.shell_type, ShellType::Cmd);
+        assert_eq!(info.name, "cmd.exe");
+
+        let info = analyze_shell_path(r"C:\Windows\System32\WindowsPowerShell\v1.0\powershell.exe")
+            .unwrap();
+        assert_eq!(info.shell_type, ShellType::PowerShell);
+        assert_eq!(info.name, "powershell.exe");
+
+        assert_eq!(
+            analyze_shell_path(r"C:\Program Files\PowerShell\7\pwsh.exe").unwrap().shell_type,
+            ShellType::PowerShell
+        );
+        assert_eq!(
+            analyze_shell_path(r"C:\Program Files\Git\bin\bash.exe").unwrap().shell_type,
+            ShellType::Bash
+        );
+        assert_eq!(analyze_shell_path("PWSH.EXE").unwrap().shell_type, ShellType::PowerShell);
+    }
+
+    #[test]
+    fn test_analyze_windows_env() {
+        let ps_session =
+            r"C:\Users\me\Documents\PowerShell\Modules;C:\Program Files\PowerShell\Modules";
+        let system_only = r"C:\Program Files\WindowsPowerShell\Modules;C:\Windows\system32\WindowsPowerShell\v1.0\Modules";
+        let comspec = r"C:\Windows\system32\cmd.exe";
+
+        let info = analyze_windows_env(Some(comspec), Some(ps_session)).unwrap();
+        assert_eq!(info.shell_type, ShellType::PowerShell);
+
+        let info = analyze_windows_env(Some(comspec), Some(system_only)).unwrap();
+        assert_eq!(info.shell_type, ShellType::Cmd);
+        assert_eq!(info.path, comspec);
+
+        assert_eq!(analyze_windows_env(None, Some(system_only)), None);
+        assert_eq!(analyze_windows_env(Some(""), None), None);
+    }
+
+    #[test]
+    fn test_shell_file_name() {
+        assert_eq!(shell_file_name("/bin/bash"), Some("bash"));
+        assert_eq!(shell_file_name(r"C:\repo\.git\phantom\worktrees\feature"), Some("feature"));
+        assert_eq!(shell_file_name("/repo/worktrees/feature/"), Some("feature"));
+        assert_eq!(shell_file_name(""), None);
+    }
+
     #[test]
     fn test_shell_type_rc_file() {
         assert_eq!(ShellType::Bash.rc_file(), Some(".bashrc"));
         assert_eq!(ShellType::Zsh.rc_file(), Some(".zshrc"));
         assert_eq!(ShellType::Fish.rc_file(), Some(".config/fish/config.fish"));
         assert_eq!(ShellType::Sh.rc_file(), Some(".profile"));
+        assert_eq!(ShellType::Cmd.rc_file(), None);
         assert_eq!(ShellType::Unknown.rc_file(), None);
     }
 
@@ -215,21 +483,101 @@ mod tests {
         assert_eq!(ShellType::Zsh.init_args(), vec!["-i"]);
         assert_eq!(ShellType::Fish.init_args(), vec!["-i"]);
         assert_eq!(ShellType::Sh.init_args(), vec![] as Vec<&str>);
+        assert_eq!(ShellType::PowerShell.init_args(), vec!["-NoLogo"]);
+        assert_eq!(ShellType::Cmd.init_args(), vec![] as Vec<&str>);
+    }
+
+    #[test]
+    fn test_shell_type_command_flag() {
+        assert_eq!(ShellType::Bash.command_flag(), "-c");
+        assert_eq!(ShellType::Fish.command_flag(), "-c");
+        assert_eq!(ShellType::PowerShell.command_flag(), "-Command");
+        assert_eq!(ShellType::Cmd.command_flag(), "/C");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_shell_command_expands_globs_in_cwd() {
+        use crate::core::executors::RealCommandExecutor;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("a.txt"), "").unwrap();
+        std::fs::write(temp_dir.path().join("b.txt"), "").unwrap();
+        std::fs::write(temp_dir.path().join("c.rs"), "").unwrap();
+
+        let sh = analyze_shell_path("/bin/sh").unwrap();
+        let (program, args) = shell_command(&sh, "printf '%s\\n' *.txt | wc -l");
+        let config =
+            CommandConfig::new(program).with_args(args).with_cwd(temp_dir.path().to_path_buf());
+
+        let output = RealCommandExecutor::new().execute(config).await.unwrap();
+        assert_eq!(output.exit_code, 0);
+        assert_eq!(output.stdout.trim(), "2");
     }
 
     #[test]
     fn test_get_phantom_env() {
-        let env = get_phantom_env("feature-branch", "/path/to/worktree");
+        let env = get_phantom_env(
+            &MockEnvironment::new(),
+            ShellType::Bash,
+            "feature-branch",
+            "/path/to/worktree",
+        );
 
         assert_eq!(env.get("PHANTOM_WORKTREE").unwrap(), "feature-branch");
         assert_eq!(env.get("PHANTOM_WORKTREE_PATH").unwrap(), "/path/to/worktree");
         assert_eq!(env.get("PHANTOM_ACTIVE").unwrap(), "1");
     }
 
+    #[test]
+    fn test_get_phantom_env_sets_the_prompt_of_each_shell() {
+        let keys = |environment: &MockEnvironment, shell_type| {
+            let env = get_phantom_env(environment, shell_type, "wt", "/wt");
+            // Leave out the variables every shell gets
+            env.iter()
+                .map(|(key, _)| key.clone())
+                .filter(|key| !key.starts_with("PHANTOM_") || key == "PHANTOM_PROMPT_PREFIX")
+                .collect::<Vec<_>>()
+        };
+
+        // Nothing to extend: only fish, which needs no inherited prompt, gets a prefix
+        let bare = MockEnvironment::new();
+        for shell_type in [ShellType::Bash, ShellType::Zsh, ShellType::Sh, ShellType::PowerShell] {
+            assert!(keys(&bare, shell_type).is_empty(), "{shell_type:?}");
+        }
+        assert_eq!(keys(&bare, ShellType::Fish), ["PHANTOM_PROMPT_PREFIX"]);
+
+        let ps1 = MockEnvironment::new().with_var("PS1", "$ ");
+        assert_eq!(keys(&ps1, ShellType::Bash), ["PS1"]);
+        assert_eq!(keys(&ps1, ShellType::Unknown), ["PS1"]);
+        assert_eq!(keys(&ps1, ShellType::Zsh), ["PROMPT", "PS1"]);
+        assert_eq!(keys(&ps1, ShellType::Fish), ["PHANTOM_PROMPT_PREFIX"]);
+        assert!(keys(&ps1, ShellType::Cmd).is_empty());
+
+        let prompt = MockEnvironment::new().with_var("PROMPT", "%~ %# ");
+        let env = get_phantom_env(&prompt, ShellType::Zsh, "wt", "/wt");
+        assert_eq!(env.get("PROMPT"), Some("(phantom:wt) %~ %# "));
+        assert_eq!(env.get("PS1"), None);
+        let env = get_phantom_env(&prompt, ShellType::Fish, "wt", "/wt");
+        assert_eq!(env.get("PHANTOM_PROMPT_PREFIX"), Some("(phantom:wt) "));
+    }
+
+    #[test]
+    fn test_shell_type_prompt_args() {
+        assert_eq!(ShellType::Bash.interactive_args(), ["-i"]);
+        assert_eq!(ShellType::Zsh.prompt_args(), None);
+
+        let fish = ShellType::Fish.interactive_args();
+        assert_eq!(fish[..2], ["-i", "--init-command"]);
+        assert!(fish[2].contains("$PHANTOM_PROMPT_PREFIX"));
+        assert_eq!(ShellType::from_path("/usr/local/bin/fish"), ShellType::Fish);
+        assert_eq!(ShellType::from_path(""), ShellType::Unknown);
+    }
+
     #[test]
     fn test_detect_shell() {
         // This test should always pass since we have a fallback
-        let result = detect_shell();
+        let result = detect_shell(&MockEnvironment::new());
         assert!(result.is_ok());
 
         let shell_info = result.unwrap();
@@ -239,14 +587,12 @@ mod tests {
 
     #[test]
     fn test_is_phantom_session() {
-        // Should be false in test environment
-        assert!(!is_phantom_session());
+        assert!(!is_phantom_session(&MockEnvironment::new()));
     }
 
     #[test]
     fn test_current_phantom_worktree() {
-        // Should be None in test environment
-        assert!(current_phantom_worktree().is_none());
+        assert!(current_phantom_worktree(&MockEnvironment::new()).is_none());
     }
 
     #[test]
@@ -321,40 +667,26 @@ mod tests {
 
     #[test]
     fn test_get_phantom_env_with_ps1() {
-        // Temporarily set PS1
-        let _guard = EnvGuard::set("PS1", "$ ");
+        let environment = MockEnvironment::new().with_var("PS1", "$ ");
 
-        let env = get_phantom_env("test-wt", "/path/to/test-wt");
+        let env = get_phantom_env(&environment, ShellType::Bash, "test-wt", "/path/to/test-wt");
 
-        // Should have modified PS1
-        if let Some(ps1) = env.get("PS1") {
-            assert!(ps1.contains("(phantom:test-wt)"));
-            assert!(ps1.contains("$ "));
-        }
-
-        // Guard will automatically restore env var when dropped
+        assert_eq!(env.get("PS1"), Some("(phantom:test-wt) $ "));
     }
 
     #[test]
     fn test_is_phantom_session_with_env() {
-        // Set the environment variable
-        let _guard = EnvGuard::set("PHANTOM_ACTIVE", "1");
-        assert!(is_phantom_session());
-        drop(_guard);
+        let env = MockEnvironment::new().with_var("PHANTOM_ACTIVE", "1");
+        assert!(is_phantom_session(&env));
 
-        // After guard is dropped, env var should be restored
-        assert!(!is_phantom_session());
+        env.remove_var("PHANTOM_ACTIVE");
+        assert!(!is_phantom_session(&env));
     }
 
     #[test]
     fn test_current_phantom_worktree_with_env() {
-        // Set the environment variable
-        let _guard = EnvGuard::set("PHANTOM_WORKTREE", "my-feature");
-        assert_eq!(current_phantom_worktree(), Some("my-feature".to_string()));
-        drop(_guard);
-
-        // After guard is dropped, env var should be restored
-        assert!(current_phantom_worktree().is_none());
+        let env = MockEnvironment::new().with_var("PHANTOM_WORKTREE", "my-feature");
+        assert_eq!(current_phantom_worktree(&env), Some("my-feature".to_string()));
     }
 
     #[test]
@@ -390,48 +722,39 @@ mod tests {
 
     // Mock tests for CommandExecutor functions
     #[tokio::test]
-    #[serial_test::serial]
     async fn test_shell_in_dir_with_executor_bash() {
-        // Mock SHELL environment variable for consistent testing
-        let _guard = EnvGuard::set("SHELL", "/bin/bash");
+        let env = MockEnvironment::new().with_var("SHELL", "/bin/bash");
 
         let mut mock = MockCommandExecutor::new();
         mock.expect_command("/bin/bash").with_args(&["-i"]).returns_output("", "", 0);
 
         let temp_dir = std::env::temp_dir().join("phantom-test");
-        let result = shell_in_dir(&mock, &temp_dir).await;
+        let result = shell_in_dir(&mock, &env, &temp_dir).await;
         if let Err(e) = &result {
             eprintln!("Test failed with error: {e:?}");
         }
         assert!(result.is_ok());
-
-        // Guard will automatically restore env var when dropped
     }
 
     #[tokio::test]
-    #[serial_test::serial]
     async fn test_shell_in_dir_with_executor_zsh() {
-        // Mock SHELL environment variable
-        let _guard = EnvGuard::set("SHELL", "/usr/bin/zsh");
+        let env = MockEnvironment::new().with_var("SHELL", "/usr/bin/zsh");
 
         let mut mock = MockCommandExecutor::new();
         mock.expect_command("/usr/bin/zsh").with_args(&["-i"]).returns_output("", "", 0);
 
         let temp_dir = std::env::temp_dir().join("phantom-test");
-        let result = shell_in_dir(&mock, &temp_dir).await;
+        let result = shell_in_dir(&mock, &env, &temp_dir).await;
         if let Err(e) = &result {
             eprintln!("Test failed with error: {e:?}");
         }
         assert!(result.is_ok());
-
-        // Guard will automatically restore env var when dropped
     }
 
     #[tokio::test]
-    #[serial_test::serial]
     async fn test_shell_in_dir_with_executor_fallback() {
-        // Remove SHELL to force fallback
-        let _guard = EnvGuard::remove("SHELL");
+        // Without SHELL the shell comes from the parent process or the fallback
+        let env = MockEnvironment::new();
 
         let mut mock = MockCommandExecutor::new();
         mock.expect_command("/bin/sh")
@@ -439,13 +762,66 @@ mod tests {
             .returns_output("", "", 0);
 
         let temp_dir = std::env::temp_dir().join("phantom-test");
-        let result = shell_in_dir(&mock, &temp_dir).await;
+        let result = shell_in_dir(&mock, &env, &temp_dir).await;
         if let Err(e) = &result {
             eprintln!("Test failed with error: {e:?}");
         }
         assert!(result.is_ok());
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_detect_shell_precedence() {
+        let env = MockEnvironment::new().with_var("SHELL", "/bin/sh");
+
+        // $PHANTOM_SHELL wins over the config and $SHELL
+        env.set_var("PHANTOM_SHELL", "/bin/bash");
+        let shell_info = detect_shell_with_config(&env, Some("/bin/sh")).unwrap();
+        assert_eq!(shell_info.path, "/bin/bash");
+        assert_eq!(shell_info.shell_type, ShellType::Bash);
+
+        // Then the configured shell, still analyzed for its type
+        env.remove_var("PHANTOM_SHELL");
+        let shell_info = detect_shell_with_config(&env, Some("/bin/bash")).unwrap();
+        assert_eq!(shell_info.path, "/bin/bash");
+        assert_eq!(shell_info.shell_type.init_args(), vec!["-i"]);
+
+        // Then $SHELL
+        let shell_info = detect_shell_with_config(&env, None).unwrap();
+        assert_eq!(shell_info.path, "/bin/sh");
+        assert_eq!(shell_info.shell_type, ShellType::Sh);
+    }
+
+    #[test]
+    fn test_detect_shell_rejects_missing_explicit_shell() {
+        let env = MockEnvironment::new()
+            .with_var("SHELL", "/bin/sh")
+            .with_var("PHANTOM_SHELL", "/nonexistent/zsh");
+        match detect_shell_with_config(&env, None) {
+            Err(PhantomError::ShellNotFound { shell, setting }) => {
+                assert_eq!(shell, "/nonexistent/zsh");
+                assert_eq!(setting, "PHANTOM_SHELL");
+            }
+            other => panic!("Expected ShellNotFound, got {other:?}"),
+        }
+
+        env.remove_var("PHANTOM_SHELL");
+        let error = detect_shell_with_config(&env, Some("/nonexistent/zsh")).unwrap_err();
+        assert!(error.to_string().contains("'shell' config key"), "{error}");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_detect_shell_with_config_prefers_configured_shell() {
+        let env = MockEnvironment::new().with_var("SHELL", "/bin/sh");
+
+        let shell_info = detect_shell_with_config(&env, Some("/bin/bash")).unwrap();
+        assert_eq!(shell_info.shell_type, ShellType::Bash);
+
+        let shell_info = detect_shell_with_config(&env, None).unwrap();
+        assert_eq!(shell_info.path, "/bin/sh");
+    }
+
     #[test]
     fn test_get_parent_pid() {
         // Note: get_parent_pid uses /proc filesystem which is Linux-specific
@@ -455,9 +831,9 @@ mod tests {
             let ppid = get_parent_pid();
             // On Linux with /proc, we should get Some value
             // On macOS and others, we'll get None
-            if ppid.is_some() {
+            if let Some(ppid) = ppid {
                 // If we got a parent PID, it should be > 0
-                assert!(ppid.unwrap() > 0);
+                assert!(ppid > 0);
             }
             // If None, that's also valid (no /proc filesystem)
         }
@@ -1,8 +1,12 @@
+use crate::core::command_executor::exit_code_of;
+use crate::core::env_map::EnvMap;
+#[cfg(unix)]
+use crate::core::executors::foreground::{wait_in_process_group, OnSignal};
 use crate::{PhantomError, Result};
-use std::collections::HashMap;
+use serde::Serialize;
 use std::ffi::OsStr;
 use std::path::Path;
-use std::process::Stdio;
+use std::process::{ExitStatus, Stdio};
 use tokio::process::{Child, Command};
 use tracing::{debug, error, info};
 
@@ -16,7 +20,7 @@ pub struct SpawnConfig {
     /// Working directory for the process
     pub cwd: Option<String>,
     /// Environment variables
-    pub env: Option<HashMap<String, String>>,
+    pub env: Option<EnvMap>,
     /// Whether to inherit stdio
     pub inherit_stdio: bool,
     /// Timeout in milliseconds (None for no timeout)
@@ -37,7 +41,7 @@ impl Default for SpawnConfig {
 }
 
 /// Result of a successful process spawn
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SpawnSuccess {
     pub exit_code: i32,
 }
@@ -64,6 +68,11 @@ pub async fn spawn_process(config: SpawnConfig) -> Result<SpawnSuccess> {
         command.stdin(Stdio::inherit());
         command.stdout(Stdio::inherit());
         command.stderr(Stdio::inherit());
+        // Its own group, so that signals phantom receives can be passed on to it
+        #[cfg(unix)]
+        if config.timeout_ms.is_none() {
+            command.process_group(0);
+        }
     } else {
         command.stdin(Stdio::null());
         command.stdout(Stdio::null());
@@ -73,7 +82,10 @@ pub async fn spawn_process(config: SpawnConfig) -> Result<SpawnSuccess> {
     // Spawn the process
     let mut child = command.spawn().map_err(|e| {
         if e.kind() == std::io::ErrorKind::NotFound {
-            PhantomError::CommandNotFound { command: config.command.clone() }
+            PhantomError::CommandNotFound {
+                command: config.command.clone(),
+                suggestions: Vec::new(),
+            }
         } else {
             PhantomError::ProcessExecutionError {
                 reason: format!("Failed to spawn process '{}': {}", config.command, e),
@@ -107,17 +119,26 @@ pub async fn spawn_process(config: SpawnConfig) -> Result<SpawnSuccess> {
             }
         }
     } else {
-        child.wait().await.map_err(|e| PhantomError::ProcessExecutionError {
-            reason: format!("Failed to wait for process: {e}"),
-        })?
+        wait_forwarding_signals(&mut child, &config).await?
     };
 
-    let exit_code = exit_status.code().unwrap_or(-1);
+    let exit_code = exit_code_of(exit_status);
     debug!("Process exited with code: {}", exit_code);
 
     Ok(SpawnSuccess { exit_code })
 }
 
+/// Wait for `child`, forwarding SIGINT and SIGTERM to it when it shares phantom's stdio
+async fn wait_forwarding_signals(child: &mut Child, config: &SpawnConfig) -> Result<ExitStatus> {
+    #[cfg(unix)]
+    if config.inherit_stdio {
+        return wait_in_process_group(child, &config.command, OnSignal::Forward).await;
+    }
+    child.wait().await.map_err(|e| PhantomError::ProcessExecutionError {
+        reason: format!("Failed to wait for process '{}': {e}", config.command),
+    })
+}
+
 /// Spawn a process and return immediately without waiting
 pub async fn spawn_detached(config: SpawnConfig) -> Result<Child> {
     info!("Spawning detached process: {} {:?}", config.command, config.args);
@@ -143,7 +164,10 @@ pub async fn spawn_detached(config: SpawnConfig) -> Result<Child> {
     // Spawn the process
     let child = command.spawn().map_err(|e| {
         if e.kind() == std::io::ErrorKind::NotFound {
-            PhantomError::CommandNotFound { command: config.command.clone() }
+            PhantomError::CommandNotFound {
+                command: config.command.clone(),
+                suggestions: Vec::new(),
+            }
         } else {
             PhantomError::ProcessExecutionError {
                 reason: format!("Failed to spawn detached process '{}': {}", config.command, e),
@@ -278,7 +302,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_spawn_process_with_env() {
-        let mut env = HashMap::new();
+        let mut env = EnvMap::new();
         env.insert("TEST_VAR".to_string(), "test_value".to_string());
 
         let config = SpawnConfig {
@@ -346,7 +370,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_spawn_config_debug() {
-        let mut env = HashMap::new();
+        let mut env = EnvMap::new();
         env.insert("KEY".to_string(), "value".to_string());
 
         let config = SpawnConfig {
@@ -372,7 +396,7 @@ mod tests {
             command: "test".to_string(),
             args: vec!["arg".to_string()],
             cwd: Some("/home".to_string()),
-            env: Some(HashMap::from([("VAR".to_string(), "val".to_string())])),
+            env: Some(EnvMap::from([("VAR".to_string(), "val".to_string())])),
             inherit_stdio: false,
             timeout_ms: Some(1000),
         };
@@ -453,7 +477,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_spawn_detached_with_env() {
-        let mut env = HashMap::new();
+        let mut env = EnvMap::new();
         env.insert("TEST_DETACHED".to_string(), "yes".to_string());
 
         let config = SpawnConfig {
@@ -503,13 +527,50 @@ mod tests {
         let result = spawn_detached(config).await;
         assert!(result.is_err());
         match result.unwrap_err() {
-            PhantomError::CommandNotFound { command } => {
+            PhantomError::CommandNotFound { command, .. } => {
                 assert_eq!(command, "command-that-does-not-exist-xyz");
             }
             _ => panic!("Expected CommandNotFound error"),
         }
     }
 
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_spawn_process_reports_death_by_signal_like_a_shell() {
+        let config = SpawnConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "kill -INT $$".to_string()],
+            inherit_stdio: false,
+            ..Default::default()
+        };
+
+        assert_eq!(spawn_process(config).await.unwrap().exit_code, 130);
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    #[serial_test::serial]
+    async fn test_spawn_process_forwards_sigint_to_the_child() {
+        let config = SpawnConfig {
+            command: "sleep".to_string(),
+            args: vec!["30".to_string()],
+            inherit_stdio: true,
+            ..Default::default()
+        };
+        tokio::spawn(async {
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            // SAFETY: phantom records the signal instead of dying while it waits for the child
+            unsafe {
+                libc::kill(libc::getpid(), libc::SIGINT);
+            }
+        });
+
+        let started = std::time::Instant::now();
+        let result = spawn_process(config).await.unwrap();
+        assert_eq!(result.exit_code, 130);
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    }
+
     #[tokio::test]
     #[cfg(unix)]
     async fn test_setup_signal_handlers() {
@@ -0,0 +1,74 @@
+use crate::core::environment::Environment;
+use crate::process::tty::is_stdout_tty;
+use std::io::{self, Write};
+
+/// Save the current title on the terminal's title stack
+const PUSH_TITLE: &str = "\x1b[22;0t";
+/// Clear the title, then pop the saved one where the terminal keeps a title stack
+const RESTORE_TITLE: &str = "\x1b]0;\x07\x1b[23;0t";
+
+/// The bytes that save the current title and show `phantom: <worktree_name>`
+///
+/// `None` when titles are turned off, stdout is not a terminal or `TERM` is `dumb`.
+pub fn title_sequence(
+    worktree_name: &str,
+    enabled: bool,
+    stdout_tty: bool,
+    term: Option<&str>,
+) -> Option<String> {
+    if !enabled || !stdout_tty || term == Some("dumb") {
+        return None;
+    }
+    let name: String = worktree_name.chars().filter(|c| !c.is_control()).collect();
+    Some(format!("{PUSH_TITLE}\x1b]0;phantom: {name}\x07"))
+}
+
+/// Shows the worktree name in the terminal title until dropped
+#[derive(Debug)]
+#[must_use = "the title is restored as soon as the guard is dropped"]
+pub struct TerminalTitle(());
+
+impl TerminalTitle {
+    /// Set the title for `worktree_name`; `None` when the terminal is left alone
+    pub fn set(worktree_name: &str, enabled: bool, environment: &dyn Environment) -> Option<Self> {
+        let term = environment.get_var("TERM");
+        let sequence = title_sequence(worktree_name, enabled, is_stdout_tty(), term.as_deref())?;
+        write_stdout(&sequence);
+        Some(Self(()))
+    }
+}
+
+impl Drop for TerminalTitle {
+    fn drop(&mut self) {
+        write_stdout(RESTORE_TITLE);
+    }
+}
+
+/// Write straight to the terminal; a title that cannot be set is not worth failing over
+fn write_stdout(sequence: &str) {
+    let mut stdout = io::stdout().lock();
+    let _ = stdout.write_all(sequence.as_bytes());
+    let _ = stdout.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_sequence_names_the_worktree() {
+        let sequence = title_sequence("feature/login", true, true, Some("xterm-256color"));
+        assert_eq!(sequence.as_deref(), Some("\x1b[22;0t\x1b]0;phantom: feature/login\x07"));
+
+        // Control characters cannot end the sequence early
+        let sequence = title_sequence("a\x07b\x1b", true, true, None).unwrap();
+        assert!(sequence.ends_with("phantom: ab\x07"));
+    }
+
+    #[test]
+    fn test_title_sequence_skipped_when_not_wanted() {
+        assert_eq!(title_sequence("feature", false, true, Some("xterm")), None);
+        assert_eq!(title_sequence("feature", true, false, Some("xterm")), None);
+        assert_eq!(title_sequence("feature", true, true, Some("dumb")), None);
+    }
+}
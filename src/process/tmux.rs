@@ -1,10 +1,11 @@
 use crate::core::command_executor::{CommandArgs, CommandConfig, CommandExecutor};
-use crate::Result;
+use crate::core::env_map::EnvMap;
+use crate::core::environment::Environment;
+use crate::{PhantomError, Result};
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
-use std::collections::HashMap;
-use std::env;
 use std::path::Path;
+use std::time::Duration;
 
 use super::spawn::SpawnSuccess;
 
@@ -24,16 +25,20 @@ pub struct TmuxOptions {
     pub command: String,
     pub args: Option<Vec<String>>,
     pub cwd: Option<String>,
-    pub env: Option<HashMap<String, String>>,
+    pub env: Option<EnvMap>,
     pub window_name: Option<String>,
+    /// Size of a split pane as a percentage of the current pane (`-p`)
+    pub percentage: Option<u8>,
+    /// Window or pane to split or open the window next to (`-t`)
+    pub target: Option<String>,
 }
 
 /// Success result for tmux operations
 pub type TmuxSuccess = SpawnSuccess;
 
 /// Check if we're running inside a tmux session
-pub async fn is_inside_tmux() -> bool {
-    env::var("TMUX").is_ok()
+pub async fn is_inside_tmux(environment: &dyn Environment) -> bool {
+    environment.get_var("TMUX").is_some()
 }
 
 /// Execute a command in tmux with CommandExecutor
@@ -41,6 +46,81 @@ pub async fn execute_tmux_command<E>(executor: &E, options: TmuxOptions) -> Resu
 where
     E: CommandExecutor,
 {
+    validate_tmux_options(&options)?;
+    let config = CommandConfig::new("tmux").with_args_smallvec(tmux_command_args(&options, &[]));
+    executor.execute(config).await?;
+    Ok(())
+}
+
+/// Execute a command in tmux and wait for it to exit, returning its exit code
+///
+/// The pane is kept open after the command exits (`remain-on-exit`) so its
+/// output stays visible and its status can be read; requires tmux 3.2 or later.
+pub async fn execute_tmux_command_and_wait<E>(
+    executor: &E,
+    options: TmuxOptions,
+    poll_interval: Duration,
+) -> Result<i32>
+where
+    E: CommandExecutor,
+{
+    validate_tmux_options(&options)?;
+
+    // Setting remain-on-exit in the same command sequence applies it to the new
+    // pane before tmux can notice a command that exits immediately
+    let mut args = tmux_command_args(&options, &["-P", "-F", "#{pane_id}"]);
+    args.extend([";", "set-option", "-p", "remain-on-exit", "on"].map(String::from));
+    let output = executor.execute(CommandConfig::new("tmux").with_args_smallvec(args)).await?;
+    let pane_id = output.stdout.trim().to_string();
+    if output.exit_code != 0 || pane_id.is_empty() {
+        return Err(PhantomError::ProcessExecutionError {
+            reason: format!("tmux did not report the new pane: {}", output.stderr.trim()),
+        });
+    }
+
+    loop {
+        let args = smallvec![
+            "display-message".to_string(),
+            "-p".to_string(),
+            "-t".to_string(),
+            pane_id.clone(),
+            "#{pane_dead} #{pane_dead_status}".to_string(),
+        ];
+        let output = executor.execute(CommandConfig::new("tmux").with_args_smallvec(args)).await?;
+        if output.exit_code != 0 {
+            return Err(PhantomError::ProcessExecutionError {
+                reason: format!("tmux pane {pane_id} was closed before the command exited"),
+            });
+        }
+        if let Some(status) = output.stdout.trim().strip_prefix("1 ") {
+            return status.parse().map_err(|_| PhantomError::ProcessExecutionError {
+                reason: format!("Unexpected exit status from tmux pane {pane_id}: '{status}'"),
+            });
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// Reject sizes tmux cannot honor
+fn validate_tmux_options(options: &TmuxOptions) -> Result<()> {
+    let Some(percentage) = options.percentage else {
+        return Ok(());
+    };
+    if options.direction == TmuxSplitDirection::New {
+        return Err(PhantomError::ValidationFailed {
+            reason: "A tmux pane size only applies to split panes, not new windows".to_string(),
+        });
+    }
+    if !(1..=99).contains(&percentage) {
+        return Err(PhantomError::ValidationFailed {
+            reason: format!("tmux pane size must be between 1 and 99 percent, got {percentage}"),
+        });
+    }
+    Ok(())
+}
+
+/// Build the `new-window`/`split-window` invocation, with `extra` flags after the subcommand
+fn tmux_command_args(options: &TmuxOptions, extra: &[&str]) -> CommandArgs {
     let mut tmux_args: CommandArgs = smallvec![];
 
     // Set up the tmux command based on direction
@@ -49,7 +129,7 @@ where
             tmux_args.push("new-window".to_string());
             if let Some(window_name) = &options.window_name {
                 tmux_args.push("-n".to_string());
-                tmux_args.push(window_name.clone());
+                tmux_args.push(escape_format(window_name));
             }
         }
         TmuxSplitDirection::Vertical => {
@@ -62,36 +142,57 @@ where
         }
     }
 
+    if let Some(percentage) = options.percentage {
+        tmux_args.push("-p".to_string());
+        tmux_args.push(percentage.to_string());
+    }
+
+    if let Some(target) = &options.target {
+        tmux_args.push("-t".to_string());
+        tmux_args.push(target.clone());
+    }
+
+    tmux_args.extend(extra.iter().map(|flag| flag.to_string()));
+
     // Add working directory if specified
     if let Some(cwd) = &options.cwd {
         tmux_args.push("-c".to_string());
-        tmux_args.push(cwd.clone());
+        tmux_args.push(escape_format(cwd));
     }
 
-    // Add environment variables in sorted order for deterministic output
+    // Add environment variables (EnvMap iterates in key order)
     if let Some(env_vars) = &options.env {
-        let mut sorted_keys: Vec<_> = env_vars.keys().collect();
-        sorted_keys.sort();
-        for key in sorted_keys {
-            if let Some(value) = env_vars.get(key) {
-                tmux_args.push("-e".to_string());
-                tmux_args.push(format!("{key}={value}"));
-            }
+        for assignment in env_vars.to_assignments() {
+            tmux_args.push("-e".to_string());
+            tmux_args.push(assignment);
         }
     }
 
-    // Add the command
-    tmux_args.push(options.command.clone());
-
-    // Add command arguments
-    if let Some(args) = &options.args {
-        tmux_args.extend(args.clone());
+    // tmux runs a lone shell-command through `sh -c` but execs several arguments directly
+    match options.args.as_deref() {
+        Some(args) if !args.is_empty() => {
+            tmux_args.push(options.command.clone());
+            tmux_args.extend(args.iter().cloned());
+        }
+        _ => tmux_args.push(quote_for_shell(&options.command)),
     }
 
-    // Execute the tmux command
-    let config = CommandConfig::new("tmux").with_args_smallvec(tmux_args);
-    executor.execute(config).await?;
-    Ok(())
+    tmux_args
+}
+
+/// Escape `#` in an option tmux expands as a format, like `-c` and `-n`
+fn escape_format(value: &str) -> String {
+    value.replace('#', "##")
+}
+
+/// Quote `word` for `sh -c` unless it is made only of characters the shell leaves alone
+fn quote_for_shell(word: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "_-./:@%+,=".contains(c);
+    if !word.is_empty() && word.chars().all(plain) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
 }
 
 /// Create a new tmux session with CommandExecutor
@@ -112,7 +213,7 @@ where
 
     if let Some(cwd) = cwd {
         args.push("-c".to_string());
-        args.push(cwd.to_string_lossy().to_string());
+        args.push(escape_format(&cwd.to_string_lossy()));
     }
 
     let config = CommandConfig::new("tmux").with_args_smallvec(args);
@@ -146,6 +247,45 @@ where
     Ok(sessions)
 }
 
+/// Find a window in the current tmux session by name
+pub async fn find_tmux_window<E>(executor: &E, window_name: &str) -> Result<Option<String>>
+where
+    E: CommandExecutor,
+{
+    let args =
+        smallvec!["list-windows".to_string(), "-F".to_string(), "#{window_name}".to_string()];
+    let config = CommandConfig::new("tmux").with_args_smallvec(args);
+    let output = executor.execute(config).await?;
+
+    if output.exit_code != 0 {
+        return Ok(None);
+    }
+
+    Ok(output.stdout.lines().find(|line| *line == window_name).map(|s| s.to_string()))
+}
+
+/// Switch to an existing tmux window
+pub async fn select_tmux_window<E>(executor: &E, window_name: &str) -> Result<()>
+where
+    E: CommandExecutor,
+{
+    let args = smallvec!["select-window".to_string(), "-t".to_string(), window_name.to_string()];
+    let config = CommandConfig::new("tmux").with_args_smallvec(args);
+    let output = executor.execute(config).await?;
+
+    if output.exit_code != 0 {
+        return Err(crate::PhantomError::ProcessExecutionError {
+            reason: format!(
+                "tmux select-window failed for '{}': {}",
+                window_name,
+                output.stderr.trim()
+            ),
+        });
+    }
+
+    Ok(())
+}
+
 /// Check if a tmux session exists with CommandExecutor
 pub async fn tmux_session_exists<E>(executor: &E, session_name: &str) -> Result<bool>
 where
@@ -161,7 +301,10 @@ where
         }
         Err(_) => {
             // If tmux command fails to execute (e.g., tmux not installed)
-            Err(crate::PhantomError::CommandNotFound { command: "tmux".to_string() })
+            Err(crate::PhantomError::CommandNotFound {
+                command: "tmux".to_string(),
+                suggestions: Vec::new(),
+            })
         }
     }
 }
@@ -169,14 +312,15 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::environment::MockEnvironment;
     use crate::core::executors::MockCommandExecutor;
+    use std::env;
 
     #[tokio::test]
     async fn test_is_inside_tmux() {
-        // This will be false in most test environments
-        let inside = is_inside_tmux().await;
-        // We can't assert a specific value as it depends on the environment
-        assert!(inside == env::var("TMUX").is_ok());
+        assert!(!is_inside_tmux(&MockEnvironment::new()).await);
+        let env = MockEnvironment::new().with_var("TMUX", "/tmp/tmux-1000/default,12345,0");
+        assert!(is_inside_tmux(&env).await);
     }
 
     #[tokio::test]
@@ -186,8 +330,10 @@ mod tests {
             command: "echo".to_string(),
             args: Some(vec!["hello".to_string()]),
             cwd: Some("/tmp".to_string()),
-            env: Some(HashMap::from([("TEST".to_string(), "value".to_string())])),
+            env: Some(EnvMap::from([("TEST".to_string(), "value".to_string())])),
             window_name: None,
+            percentage: None,
+            target: None,
         };
 
         assert_eq!(options.direction, TmuxSplitDirection::Vertical);
@@ -267,6 +413,8 @@ mod tests {
             cwd: None,
             env: None,
             window_name: Some("TestWindow".to_string()),
+            percentage: None,
+            target: None,
         };
 
         let debug_str = format!("{options:?}");
@@ -283,8 +431,10 @@ mod tests {
             command: "vim".to_string(),
             args: Some(vec!["file.txt".to_string()]),
             cwd: Some("/workspace".to_string()),
-            env: Some(HashMap::from([("EDITOR".to_string(), "vim".to_string())])),
+            env: Some(EnvMap::from([("EDITOR".to_string(), "vim".to_string())])),
             window_name: Some("Editor".to_string()),
+            percentage: None,
+            target: None,
         };
 
         let cloned = options.clone();
@@ -306,6 +456,8 @@ mod tests {
             cwd: None,
             env: None,
             window_name: None,
+            percentage: None,
+            target: None,
         };
 
         assert_eq!(options.command, "sh");
@@ -324,6 +476,8 @@ mod tests {
             cwd: None,
             env: None,
             window_name: None,
+            percentage: None,
+            target: None,
         };
 
         let args = options.args.unwrap();
@@ -341,6 +495,8 @@ mod tests {
             cwd: None,
             env: None,
             window_name: Some("System Monitor".to_string()),
+            percentage: None,
+            target: None,
         };
 
         assert_eq!(options.window_name, Some("System Monitor".to_string()));
@@ -370,8 +526,42 @@ mod tests {
             command: "vim".to_string(),
             args: Some(vec!["file.txt".to_string()]),
             cwd: Some("/workspace".to_string()),
-            env: Some(HashMap::from([("VAR1".to_string(), "value1".to_string())])),
+            env: Some(EnvMap::from([("VAR1".to_string(), "value1".to_string())])),
             window_name: Some("TestWindow".to_string()),
+            percentage: None,
+            target: None,
+        };
+
+        let result = execute_tmux_command(&mock, options).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_execute_tmux_command_env_in_key_order() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&[
+                "split-window",
+                "-h",
+                "-e",
+                "ALPHA=1",
+                "-e",
+                "MIDDLE=2",
+                "-e",
+                "ZETA=3",
+                "env",
+            ])
+            .returns_output("", "", 0);
+
+        let options = TmuxOptions {
+            direction: TmuxSplitDirection::Horizontal,
+            command: "env".to_string(),
+            args: None,
+            cwd: None,
+            env: Some(EnvMap::from([("ZETA", "3"), ("ALPHA", "1"), ("MIDDLE", "2")])),
+            window_name: None,
+            percentage: None,
+            target: None,
         };
 
         let result = execute_tmux_command(&mock, options).await;
@@ -392,12 +582,194 @@ mod tests {
             cwd: None,
             env: None,
             window_name: None,
+            percentage: None,
+            target: None,
         };
 
         let result = execute_tmux_command(&mock, options).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_execute_tmux_command_sized_split() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&[
+                "split-window",
+                "-h",
+                "-p",
+                "30",
+                "-t",
+                "editor",
+                "-c",
+                "/workspace",
+                "htop",
+            ])
+            .times(1)
+            .returns_success();
+
+        let options = TmuxOptions {
+            direction: TmuxSplitDirection::Horizontal,
+            command: "htop".to_string(),
+            args: None,
+            cwd: Some("/workspace".to_string()),
+            env: None,
+            window_name: None,
+            percentage: Some(30),
+            target: Some("editor".to_string()),
+        };
+
+        execute_tmux_command(&mock, options).await.unwrap();
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_tmux_command_new_window_with_target() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&["new-window", "-n", "feature", "-t", "work:", "sh"])
+            .times(1)
+            .returns_success();
+
+        let options = TmuxOptions {
+            direction: TmuxSplitDirection::New,
+            command: "sh".to_string(),
+            args: None,
+            cwd: None,
+            env: None,
+            window_name: Some("feature".to_string()),
+            percentage: None,
+            target: Some("work:".to_string()),
+        };
+
+        execute_tmux_command(&mock, options).await.unwrap();
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_tmux_command_rejects_invalid_size() {
+        let mock = MockCommandExecutor::new();
+        for (direction, percentage, expected) in [
+            (TmuxSplitDirection::Vertical, 0, "between 1 and 99"),
+            (TmuxSplitDirection::Vertical, 100, "between 1 and 99"),
+            (TmuxSplitDirection::New, 30, "not new windows"),
+        ] {
+            let options =
+                TmuxOptions { direction, percentage: Some(percentage), ..split_options() };
+            let err = execute_tmux_command(&mock, options).await.unwrap_err();
+            assert!(err.to_string().contains(expected), "{err}");
+        }
+        assert!(mock.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_tmux_command_and_wait_sized_split() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&[
+                "split-window",
+                "-v",
+                "-p",
+                "25",
+                "-P",
+                "-F",
+                "#{pane_id}",
+                "make",
+                "test",
+                ";",
+                "set-option",
+                "-p",
+                "remain-on-exit",
+                "on",
+            ])
+            .returns_output(
+                "%3
+", "", 0,
+            );
+        mock.expect_command("tmux")
+            .with_args(&["display-message", "-p", "-t", "%3", "#{pane_dead} #{pane_dead_status}"])
+            .returns_output(
+                "1 0
+", "", 0,
+            );
+
+        let options = TmuxOptions { percentage: Some(25), ..split_options() };
+        let exit_code =
+            execute_tmux_command_and_wait(&mock, options, Duration::ZERO).await.unwrap();
+        assert_eq!(exit_code, 0);
+    }
+
+    fn split_options() -> TmuxOptions {
+        TmuxOptions {
+            direction: TmuxSplitDirection::Vertical,
+            command: "make".to_string(),
+            args: Some(vec!["test".to_string()]),
+            cwd: None,
+            env: None,
+            window_name: None,
+            percentage: None,
+            target: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_tmux_command_and_wait_polls_until_pane_exits() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&[
+                "split-window",
+                "-v",
+                "-P",
+                "-F",
+                "#{pane_id}",
+                "make",
+                "test",
+                ";",
+                "set-option",
+                "-p",
+                "remain-on-exit",
+                "on",
+            ])
+            .returns_output("%7\n", "", 0);
+        let poll = ["display-message", "-p", "-t", "%7", "#{pane_dead} #{pane_dead_status}"];
+        mock.expect_command("tmux").with_args(&poll).times(2).returns_output("0 \n", "", 0);
+        mock.expect_command("tmux").with_args(&poll).returns_output("1 2\n", "", 0);
+
+        let exit_code =
+            execute_tmux_command_and_wait(&mock, split_options(), Duration::ZERO).await.unwrap();
+        assert_eq!(exit_code, 2);
+        assert_eq!(mock.calls().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_execute_tmux_command_and_wait_fails_when_pane_is_closed() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&[
+                "split-window",
+                "-v",
+                "-P",
+                "-F",
+                "#{pane_id}",
+                "make",
+                "test",
+                ";",
+                "set-option",
+                "-p",
+                "remain-on-exit",
+                "on",
+            ])
+            .returns_output("%7\n", "", 0);
+        mock.expect_command("tmux")
+            .with_args(&["display-message", "-p", "-t", "%7", "#{pane_dead} #{pane_dead_status}"])
+            .returns_output("", "can't find pane: %7", 1);
+
+        let err = execute_tmux_command_and_wait(&mock, split_options(), Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("closed before the command exited"));
+    }
+
     #[tokio::test]
     async fn test_create_tmux_session_with_cwd() {
         let mut mock = MockCommandExecutor::new();
@@ -435,6 +807,60 @@ mod tests {
         assert_eq!(result[2], "session3");
     }
 
+    #[tokio::test]
+    async fn test_find_tmux_window_found() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&["list-windows", "-F", "#{window_name}"])
+            .returns_output("zsh\nfeature\nfeature-2\n", "", 0);
+
+        let result = find_tmux_window(&mock, "feature").await.unwrap();
+        assert_eq!(result, Some("feature".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_find_tmux_window_requires_exact_match() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&["list-windows", "-F", "#{window_name}"])
+            .returns_output("zsh\nfeature-2\n", "", 0);
+
+        assert_eq!(find_tmux_window(&mock, "feature").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_find_tmux_window_list_failure() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&["list-windows", "-F", "#{window_name}"])
+            .returns_error("no server running");
+
+        assert_eq!(find_tmux_window(&mock, "feature").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_select_tmux_window() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&["select-window", "-t", "feature"])
+            .times(1)
+            .returns_success();
+
+        select_tmux_window(&mock, "feature").await.unwrap();
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_select_tmux_window_failure() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("tmux")
+            .with_args(&["select-window", "-t", "gone"])
+            .returns_error("can't find window: gone");
+
+        let err = select_tmux_window(&mock, "gone").await.unwrap_err();
+        assert!(err.to_string().contains("can't find window"));
+    }
+
     #[tokio::test]
     async fn test_tmux_session_exists_true() {
         let mut mock = MockCommandExecutor::new();
@@ -493,34 +919,33 @@ mod tests {
             command: "vim".to_string(),
             args: Some(vec!["file.txt".to_string()]),
             cwd: Some("/workspace".to_string()),
-            env: Some(HashMap::from([
+            env: Some(EnvMap::from([
                 ("VAR1".to_string(), "value1".to_string()),
                 ("VAR2".to_string(), "value2".to_string()),
             ])),
             window_name: Some("editor".to_string()),
+            percentage: None,
+            target: None,
         };
 
         // Simulate building tmux args
-        let mut tmux_args = Vec::new();
-
-        // New window
-        tmux_args.push("new-window".to_string());
-        tmux_args.push("-n".to_string());
-        tmux_args.push("editor".to_string());
-
-        // Working directory
-        tmux_args.push("-c".to_string());
-        tmux_args.push("/workspace".to_string());
-
-        // Environment variables
-        tmux_args.push("-e".to_string());
-        tmux_args.push("VAR1=value1".to_string());
-        tmux_args.push("-e".to_string());
-        tmux_args.push("VAR2=value2".to_string());
-
-        // Command and args
-        tmux_args.push("vim".to_string());
-        tmux_args.push("file.txt".to_string());
+        let tmux_args = vec![
+            // New window
+            "new-window".to_string(),
+            "-n".to_string(),
+            "editor".to_string(),
+            // Working directory
+            "-c".to_string(),
+            "/workspace".to_string(),
+            // Environment variables
+            "-e".to_string(),
+            "VAR1=value1".to_string(),
+            "-e".to_string(),
+            "VAR2=value2".to_string(),
+            // Command and args
+            "vim".to_string(),
+            "file.txt".to_string(),
+        ];
 
         assert!(tmux_args.contains(&"new-window".to_string()));
         assert!(tmux_args.contains(&"-n".to_string()));
@@ -593,17 +1018,61 @@ mod tests {
 
     #[test]
     fn test_env_var_formatting() {
-        let env_vars = HashMap::from([
+        let env_vars = EnvMap::from([
             ("PATH".to_string(), "/usr/bin:/bin".to_string()),
             ("HOME".to_string(), "/home/user".to_string()),
             ("TERM".to_string(), "xterm-256color".to_string()),
         ]);
 
-        for (key, value) in env_vars {
-            let formatted = format!("{key}={value}");
-            assert!(formatted.contains('='));
-            assert!(formatted.contains(&key));
-            assert!(formatted.contains(&value));
+        assert_eq!(
+            env_vars.to_assignments(),
+            vec!["HOME=/home/user", "PATH=/usr/bin:/bin", "TERM=xterm-256color"]
+        );
+    }
+
+    #[test]
+    fn test_tmux_command_args_keep_awkward_paths_intact() {
+        let path = "/home/me/Work Projects/client 'repo' #S é";
+        let options = TmuxOptions {
+            direction: TmuxSplitDirection::New,
+            command: "/opt/my tools/zsh".to_string(),
+            args: None,
+            cwd: Some(path.to_string()),
+            env: Some(EnvMap::from([("PHANTOM_WORKTREE_PATH".to_string(), path.to_string())])),
+            window_name: Some("fix#1".to_string()),
+            percentage: None,
+            target: None,
+        };
+
+        assert_eq!(
+            tmux_command_args(&options, &[]).to_vec(),
+            vec![
+                "new-window",
+                "-n",
+                "fix##1",
+                "-c",
+                "/home/me/Work Projects/client 'repo' ##S é",
+                "-e",
+                &format!("PHANTOM_WORKTREE_PATH={path}"),
+                "'/opt/my tools/zsh'",
+            ]
+        );
+
+        // Several arguments are exec'd directly, so they are passed as they are
+        let options = TmuxOptions { args: Some(vec!["a b; 'q'".to_string()]), ..options };
+        assert_eq!(tmux_command_args(&options, &[]).last().unwrap(), "a b; 'q'");
+    }
+
+    #[tokio::test]
+    async fn test_quote_for_shell_round_trips_through_sh() {
+        assert_eq!(quote_for_shell("/bin/zsh"), "/bin/zsh");
+        for word in ["/opt/my tools/zsh", "it's", "a;b $(x) `y` \\ é", ""] {
+            let output = tokio::process::Command::new("sh")
+                .args(["-c", &format!("printf '%s' {}", quote_for_shell(word))])
+                .output()
+                .await
+                .unwrap();
+            assert_eq!(String::from_utf8(output.stdout).unwrap(), word);
         }
     }
 }
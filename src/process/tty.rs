@@ -1,4 +1,4 @@
-use std::env;
+use crate::core::environment::Environment;
 use std::io::{self, IsTerminal};
 
 /// Check if stdin is a TTY
@@ -16,28 +16,42 @@ pub fn is_stderr_tty() -> bool {
     io::stderr().is_terminal()
 }
 
+/// Check if stdin is a pipe or a redirected file rather than a terminal
+pub fn is_stdin_piped() -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::fd::AsFd;
+        use std::os::unix::fs::FileTypeExt;
+        if let Ok(fd) = io::stdin().as_fd().try_clone_to_owned() {
+            if let Ok(metadata) = std::fs::File::from(fd).metadata() {
+                let file_type = metadata.file_type();
+                return file_type.is_fifo() || file_type.is_socket() || file_type.is_file();
+            }
+        }
+    }
+    false
+}
+
 /// Check if we're in an interactive terminal session
 pub fn is_interactive() -> bool {
     is_stdin_tty() && is_stdout_tty()
 }
 
 /// Check if color output should be enabled
-pub fn should_use_color() -> bool {
+pub fn should_use_color(environment: &dyn Environment) -> bool {
     // Check NO_COLOR first (it has the highest precedence)
-    if env::var("NO_COLOR").is_ok() {
+    if environment.get_var("NO_COLOR").is_some() {
         return false;
     }
 
     // Check FORCE_COLOR
-    if env::var("FORCE_COLOR").is_ok() {
+    if environment.get_var("FORCE_COLOR").is_some() {
         return true;
     }
 
     // Check TERM
-    if let Ok(term) = env::var("TERM") {
-        if term == "dumb" {
-            return false;
-        }
+    if environment.get_var("TERM").as_deref() == Some("dumb") {
+        return false;
     }
 
     // Default to using color if stdout is a TTY
@@ -45,16 +59,19 @@ pub fn should_use_color() -> bool {
 }
 
 /// Get terminal width
+#[cfg(feature = "cli")]
 pub fn terminal_width() -> Option<usize> {
     terminal_size::terminal_size().map(|(terminal_size::Width(w), _)| w as usize)
 }
 
 /// Get terminal height
+#[cfg(feature = "cli")]
 pub fn terminal_height() -> Option<usize> {
     terminal_size::terminal_size().map(|(_, terminal_size::Height(h))| h as usize)
 }
 
 /// Get terminal size (width, height)
+#[cfg(feature = "cli")]
 pub fn terminal_size() -> Option<(usize, usize)> {
     terminal_size::terminal_size()
         .map(|(terminal_size::Width(w), terminal_size::Height(h))| (w as usize, h as usize))
@@ -63,7 +80,7 @@ pub fn terminal_size() -> Option<(usize, usize)> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::EnvGuard;
+    use crate::core::environment::MockEnvironment;
 
     #[test]
     fn test_tty_detection() {
@@ -88,16 +105,15 @@ mod tests {
     #[test]
     fn test_should_use_color() {
         // Test FORCE_COLOR
-        let _guard1 = EnvGuard::set("FORCE_COLOR", "1");
-        assert!(should_use_color());
+        let env = MockEnvironment::new().with_var("FORCE_COLOR", "1");
+        assert!(should_use_color(&env));
 
         // Test NO_COLOR (should override FORCE_COLOR)
-        let _guard2 = EnvGuard::set("NO_COLOR", "1");
-        assert!(!should_use_color());
-
-        // Guards will automatically restore env vars when dropped
+        env.set_var("NO_COLOR", "1");
+        assert!(!should_use_color(&env));
     }
 
+    #[cfg(feature = "cli")]
     #[test]
     fn test_terminal_size() {
         // This will depend on the terminal
@@ -110,15 +126,16 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "cli")]
     #[test]
     fn test_terminal_width_height() {
         // Test individual width/height functions
         let width = terminal_width();
         let height = terminal_height();
 
-        if width.is_some() && height.is_some() {
-            assert!(width.unwrap() > 0);
-            assert!(height.unwrap() > 0);
+        if let (Some(width), Some(height)) = (width, height) {
+            assert!(width > 0);
+            assert!(height > 0);
         }
 
         // If we can get full size, individual functions should work too
@@ -130,21 +147,17 @@ mod tests {
 
     #[test]
     fn test_should_use_color_with_term() {
-        // Clean environment
-        let _guard1 = EnvGuard::remove("NO_COLOR");
-        let _guard2 = EnvGuard::remove("FORCE_COLOR");
-
         // Test with dumb terminal
-        let _guard3 = EnvGuard::set("TERM", "dumb");
-        assert!(!should_use_color());
-        drop(_guard3);
+        let env = MockEnvironment::new().with_var("TERM", "dumb");
+        assert!(!should_use_color(&env));
 
-        // Test with normal terminal
-        let _guard4 = EnvGuard::set("TERM", "xterm-256color");
-        // Result depends on if stdout is a TTY
-        let _ = should_use_color();
+        // FORCE_COLOR wins over a dumb terminal
+        env.set_var("FORCE_COLOR", "1");
+        assert!(should_use_color(&env));
 
-        // Guards will automatically restore env vars when dropped
+        // With a normal terminal it follows whether stdout is a TTY
+        let env = MockEnvironment::new().with_var("TERM", "xterm-256color");
+        assert_eq!(should_use_color(&env), is_stdout_tty());
     }
 
     #[test]
@@ -154,9 +167,12 @@ mod tests {
         let _ = is_stdout_tty();
         let _ = is_stderr_tty();
         let _ = is_interactive();
-        let _ = should_use_color();
-        let _ = terminal_width();
-        let _ = terminal_height();
-        let _ = terminal_size();
+        let _ = should_use_color(&MockEnvironment::new());
+        #[cfg(feature = "cli")]
+        {
+            let _ = terminal_width();
+            let _ = terminal_height();
+            let _ = terminal_size();
+        }
     }
 }
@@ -21,6 +21,18 @@ impl TestRepo {
     pub async fn new() -> Result<Self> {
         let dir = tempdir().map_err(crate::PhantomError::Io)?;
         let path = dir.path().to_path_buf();
+        Self::init(dir, path).await
+    }
+
+    /// Create a new test repository at `relative` inside a fresh temporary directory
+    pub async fn new_at(relative: &str) -> Result<Self> {
+        let dir = tempdir().map_err(crate::PhantomError::Io)?;
+        let path = dir.path().join(relative);
+        std::fs::create_dir_all(&path).map_err(crate::PhantomError::Io)?;
+        Self::init(dir, path).await
+    }
+
+    async fn init(dir: TempDir, path: PathBuf) -> Result<Self> {
         let git = SafeGitCommand::new().map_err(crate::PhantomError::Io)?;
 
         // Initialize git repo with explicit main branch
@@ -0,0 +1,60 @@
+use crate::PhantomError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Workspace manifest errors
+#[derive(Error, Debug)]
+pub enum WorkspaceError {
+    #[error("No phantom-workspace.toml found in {0} or any parent directory")]
+    NotFound(String),
+
+    #[error("Failed to parse workspace manifest: {0}")]
+    ParseError(String),
+
+    #[error("Workspace manifest does not list any members")]
+    NoMembers,
+
+    #[error("Workspace member '{member}' not found at {path}")]
+    MemberNotFound { member: String, path: String },
+
+    #[error("Workspace member name '{0}' is used by more than one path")]
+    DuplicateMember(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<WorkspaceError> for PhantomError {
+    fn from(err: WorkspaceError) -> Self {
+        match err {
+            WorkspaceError::NotFound(path) => {
+                PhantomError::ConfigNotFound { path: PathBuf::from(path) }
+            }
+            WorkspaceError::Io(err) => PhantomError::Io(err),
+            err => PhantomError::ConfigInvalid { reason: err.to_string() },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_error_to_phantom_error() {
+        let error: PhantomError = WorkspaceError::NotFound("/repo".to_string()).into();
+        assert!(matches!(error, PhantomError::ConfigNotFound { .. }));
+
+        let error: PhantomError = WorkspaceError::MemberNotFound {
+            member: "../api".to_string(),
+            path: "/work/api".to_string(),
+        }
+        .into();
+        match error {
+            PhantomError::ConfigInvalid { reason } => {
+                assert_eq!(reason, "Workspace member '../api' not found at /work/api")
+            }
+            e => panic!("Expected ConfigInvalid, got {e:?}"),
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use crate::config::types::PhantomConfig;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::filesystem::FileSystem;
+use crate::git::libs::branch_exists::branch_exists;
+use crate::workspace::manifest::{Workspace, WorkspaceMember};
+use crate::worktree::concurrent::{list_worktrees_concurrent_with_options, run_bounded};
+use crate::worktree::create::create_worktree;
+use crate::worktree::delete::delete_worktree;
+use crate::worktree::list::{ListOptions, ListWorktreesSuccess};
+use crate::worktree::state::remove_worktree_metadata;
+use crate::worktree::types::{
+    CopyFilesSelection, CreateWorktreeOptions, CreateWorktreeSuccess, DeleteWorktreeOptions,
+    DeleteWorktreeSuccess,
+};
+use crate::Result;
+use std::future::Future;
+
+/// Maximum number of member repositories processed at once
+pub const MAX_CONCURRENT_MEMBERS: usize = 4;
+
+/// Outcome of an operation in a single workspace member
+#[derive(Debug)]
+pub struct MemberResult<T> {
+    pub member: WorkspaceMember,
+    pub result: Result<T>,
+}
+
+/// Run an operation in every workspace member, in manifest order
+///
+/// A failing member does not stop the others.
+pub async fn fan_out<T, F, Fut>(workspace: &Workspace, limit: usize, f: F) -> Vec<MemberResult<T>>
+where
+    F: Fn(WorkspaceMember) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    run_bounded(workspace.members.iter().cloned(), limit, |member| {
+        let future = f(member.clone());
+        async move { MemberResult { member, result: future.await } }
+    })
+    .await
+}
+
+/// Names of the members that already have the branch `branch_of` gives for them
+pub async fn members_with_branch<E, B>(
+    executor: E,
+    workspace: &Workspace,
+    branch_of: B,
+) -> Vec<String>
+where
+    E: CommandExecutor + Clone + 'static,
+    B: Fn(&WorkspaceMember) -> String,
+{
+    fan_out(workspace, MAX_CONCURRENT_MEMBERS, |member| {
+        let executor = executor.clone();
+        let branch = branch_of(&member);
+        async move { branch_exists(executor, &member.path, &branch).await }
+    })
+    .await
+    .into_iter()
+    .filter(|outcome| matches!(outcome.result, Ok(true)))
+    .map(|outcome| outcome.member.name)
+    .collect()
+}
+
+/// Create a worktree with the same name in every member
+///
+/// Each member creates the branch `branch_of` gives for it and combines
+/// `copy_files` with the copyFiles from its own configuration; `options.branch`
+/// and `options.copy_files` are replaced by the results.
+pub async fn create_in_workspace<E, B>(
+    executor: E,
+    workspace: &Workspace,
+    name: &str,
+    options: CreateWorktreeOptions,
+    copy_files: &CopyFilesSelection,
+    branch_of: B,
+) -> Vec<MemberResult<CreateWorktreeSuccess>>
+where
+    E: CommandExecutor + Clone + 'static,
+    B: Fn(&WorkspaceMember) -> String,
+{
+    fan_out(workspace, MAX_CONCURRENT_MEMBERS, |member| {
+        let executor = executor.clone();
+        let mut options = options.clone();
+        options.branch = Some(branch_of(&member));
+        async move {
+            let configured = if copy_files.uses_config() {
+                member
+                    .config
+                    .get(&member.path)
+                    .await?
+                    .and_then(|loaded| loaded.config.post_create.as_ref())
+                    .and_then(|post_create| post_create.copy_file_entries())
+            } else {
+                None
+            };
+            options.copy_files = copy_files.resolve(configured);
+            create_worktree(executor, &member.path, name, options).await
+        }
+    })
+    .await
+}
+
+/// Delete the worktree with the given name from every member
+pub async fn delete_in_workspace<E>(
+    executor: E,
+    workspace: &Workspace,
+    name: &str,
+    options: DeleteWorktreeOptions,
+    filesystem: &dyn FileSystem,
+) -> Vec<MemberResult<DeleteWorktreeSuccess>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    fan_out(workspace, MAX_CONCURRENT_MEMBERS, |member| {
+        let executor = executor.clone();
+        let mut options = options.clone();
+        async move {
+            // An unreadable config keeps the defaults
+            let config = member.config.get(&member.path).await.ok().flatten().map(|l| &l.config);
+            options.keep_branch |= !config.map_or(true, PhantomConfig::delete_removes_branch);
+            if options.snapshot.is_none() {
+                options.snapshot = config.map(PhantomConfig::delete_snapshot);
+            }
+            let result = delete_worktree(executor, &member.path, name, options, filesystem).await?;
+            // Best effort, like the single-repository delete
+            let _ = remove_worktree_metadata(filesystem, &member.path, name).await;
+            Ok(result)
+        }
+    })
+    .await
+}
+
+/// List the worktrees of every member
+pub async fn list_in_workspace<E>(
+    executor: E,
+    workspace: &Workspace,
+    options: ListOptions,
+    filesystem: &dyn FileSystem,
+) -> Vec<MemberResult<ListWorktreesSuccess>>
+where
+    E: CommandExecutor + Clone + Send + Sync + 'static,
+{
+    fan_out(workspace, MAX_CONCURRENT_MEMBERS, |member| {
+        let executor = executor.clone();
+        async move {
+            list_worktrees_concurrent_with_options(executor, filesystem, &member.path, options)
+                .await
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::PhantomError;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Two member repositories backed by real directories
+    fn two_member_workspace() -> (TempDir, Workspace) {
+        let root = TempDir::new().unwrap();
+        let members = ["api", "web"]
+            .iter()
+            .map(|name| {
+                let path = root.path().join(name);
+                std::fs::create_dir_all(&path).unwrap();
+                WorkspaceMember::new(name.to_string(), path)
+            })
+            .collect();
+        let workspace =
+            Workspace { manifest_path: root.path().join("phantom-workspace.toml"), members };
+        (root, workspace)
+    }
+
+    fn expect_create(
+        mock: &mut MockCommandExecutor,
+        repo: &Path,
+        branch: &str,
+        exit_code: i32,
+        stderr: &str,
+    ) {
+        let repo_str = repo.to_string_lossy().to_string();
+        let worktree = repo.join(".git/phantom/worktrees/feature");
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--verify", "--quiet", "HEAD"])
+            .in_dir(&repo_str)
+            .returns_output("abc123\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["worktree", "add", "-b", branch, &worktree.to_string_lossy()])
+            .in_dir(&repo_str)
+            .returns_output("", stderr, exit_code);
+    }
+
+    #[tokio::test]
+    async fn test_create_in_two_repositories() {
+        let (_root, workspace) = two_member_workspace();
+        let mut mock = MockCommandExecutor::new();
+        // Each member may put its own prefix in front of the branch
+        expect_create(&mut mock, &workspace.members[0].path, "api/feature", 0, "");
+        expect_create(&mut mock, &workspace.members[1].path, "web/feature", 0, "");
+
+        let results = create_in_workspace(
+            mock,
+            &workspace,
+            "feature",
+            CreateWorktreeOptions::default(),
+            &CopyFilesSelection::default(),
+            |member| format!("{}/feature", member.name),
+        )
+        .await;
+
+        let names: Vec<_> = results.iter().map(|r| r.member.name.as_str()).collect();
+        assert_eq!(names, vec!["api", "web"]);
+        for outcome in &results {
+            let success = outcome.result.as_ref().unwrap();
+            assert!(success.path.starts_with(&outcome.member.path.to_string_lossy().to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_member_failure_does_not_abort_others() {
+        let (_root, workspace) = two_member_workspace();
+        let mut mock = MockCommandExecutor::new();
+        expect_create(
+            &mut mock,
+            &workspace.members[0].path,
+            "feature",
+            128,
+            "fatal: a branch named 'feature' already exists",
+        );
+        expect_create(&mut mock, &workspace.members[1].path, "feature", 0, "");
+
+        let results = create_in_workspace(
+            mock,
+            &workspace,
+            "feature",
+            CreateWorktreeOptions::default(),
+            &CopyFilesSelection::default(),
+            |_| "feature".to_string(),
+        )
+        .await;
+
+        assert!(matches!(results[0].result, Err(PhantomError::BranchExists { .. })));
+        assert!(results[1].result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_members_with_branch() {
+        let (_root, workspace) = two_member_workspace();
+        let mut mock = MockCommandExecutor::new();
+        for (member, exit_code) in workspace.members.iter().zip([0, 1]) {
+            let branch = format!("refs/heads/{}/feature", member.name);
+            mock.expect_command("git")
+                .with_args(&["show-ref", "--verify", "--quiet", &branch])
+                .in_dir(&member.path)
+                .returns_output("", "", exit_code);
+        }
+
+        let members =
+            members_with_branch(mock, &workspace, |member| format!("{}/feature", member.name))
+                .await;
+        assert_eq!(members, vec!["api"]);
+    }
+}
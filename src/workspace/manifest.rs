@@ -0,0 +1,237 @@
+use crate::config::provider::ConfigProvider;
+use crate::workspace::errors::WorkspaceError;
+use crate::Result;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tracing::debug;
+
+/// Workspace manifest file name
+pub const WORKSPACE_FILE_NAME: &str = "phantom-workspace.toml";
+
+/// Raw contents of a workspace manifest
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceManifest {
+    /// Member repository paths, relative to the manifest
+    pub members: Vec<String>,
+}
+
+/// A member repository of a workspace
+#[derive(Debug, Clone)]
+pub struct WorkspaceMember {
+    /// Short name used to prefix output (the repository directory name)
+    pub name: String,
+    /// Repository root
+    pub path: PathBuf,
+    /// The member's own configuration, read when an operation first needs it
+    pub config: ConfigProvider,
+}
+
+impl WorkspaceMember {
+    pub fn new(name: String, path: PathBuf) -> Self {
+        Self { name, path, config: ConfigProvider::default() }
+    }
+}
+
+/// Members are the same when they name the same repository
+impl PartialEq for WorkspaceMember {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.path == other.path
+    }
+}
+
+/// A workspace with its members resolved to repository roots
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub manifest_path: PathBuf,
+    pub members: Vec<WorkspaceMember>,
+}
+
+/// Parse a workspace manifest
+pub fn parse_manifest(content: &str) -> Result<WorkspaceManifest> {
+    let manifest: WorkspaceManifest =
+        toml::from_str(content).map_err(|e| WorkspaceError::ParseError(e.to_string()))?;
+
+    if manifest.members.is_empty() {
+        return Err(WorkspaceError::NoMembers.into());
+    }
+
+    Ok(manifest)
+}
+
+/// Find a workspace manifest in `start_dir` or any of its parents
+pub async fn find_workspace_manifest(start_dir: &Path) -> Option<PathBuf> {
+    let mut current = Some(start_dir);
+
+    while let Some(dir) = current {
+        let candidate = dir.join(WORKSPACE_FILE_NAME);
+        if fs::metadata(&candidate).await.is_ok() {
+            return Some(candidate);
+        }
+        current = dir.parent();
+    }
+
+    None
+}
+
+/// Resolve manifest members relative to the manifest directory
+pub async fn resolve_members(
+    manifest_dir: &Path,
+    manifest: &WorkspaceManifest,
+) -> Result<Vec<WorkspaceMember>> {
+    let mut members: Vec<WorkspaceMember> = Vec::with_capacity(manifest.members.len());
+
+    for entry in &manifest.members {
+        let joined = manifest_dir.join(entry);
+        let path = match fs::canonicalize(&joined).await {
+            Ok(path) if path.is_dir() => path,
+            _ => {
+                return Err(WorkspaceError::MemberNotFound {
+                    member: entry.clone(),
+                    path: joined.display().to_string(),
+                }
+                .into());
+            }
+        };
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| entry.clone());
+
+        if let Some(existing) = members.iter().find(|member| member.name == name) {
+            // The same repository listed twice is harmless; two repositories sharing a name are not
+            if existing.path == path {
+                continue;
+            }
+            return Err(WorkspaceError::DuplicateMember(name).into());
+        }
+
+        members.push(WorkspaceMember::new(name, path));
+    }
+
+    Ok(members)
+}
+
+/// Load a workspace from a manifest file
+pub async fn load_workspace(manifest_path: &Path) -> Result<Workspace> {
+    debug!("Loading workspace manifest from {}", manifest_path.display());
+
+    let content = fs::read_to_string(manifest_path).await.map_err(WorkspaceError::Io)?;
+    let manifest = parse_manifest(&content)?;
+    let manifest_dir = manifest_path.parent().unwrap_or(Path::new("."));
+    let members = resolve_members(manifest_dir, &manifest).await?;
+
+    Ok(Workspace { manifest_path: manifest_path.to_path_buf(), members })
+}
+
+/// Discover and load the workspace enclosing `start_dir`
+pub async fn discover_workspace(start_dir: &Path) -> Result<Workspace> {
+    let manifest_path = find_workspace_manifest(start_dir)
+        .await
+        .ok_or_else(|| WorkspaceError::NotFound(start_dir.display().to_string()))?;
+
+    load_workspace(&manifest_path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PhantomError;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_manifest() {
+        let cases: [(&str, Option<&[&str]>); 4] = [
+            (r#"members = ["../api", "../web"]"#, Some(&["../api", "../web"])),
+            (r#"members = ["."]"#, Some(&["."])),
+            (r#"members = []"#, None),
+            (
+                r#"members = ["../api"]
+extra = true"#,
+                None,
+            ),
+        ];
+
+        for (content, expected) in cases {
+            let result = parse_manifest(content);
+            match expected {
+                Some(members) => assert_eq!(result.unwrap().members, members, "{content}"),
+                None => assert!(result.is_err(), "{content}"),
+            }
+        }
+
+        let error = parse_manifest("members = []").unwrap_err();
+        assert!(error.to_string().contains("does not list any members"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_members_relative_paths() {
+        let root = TempDir::new().unwrap();
+        let api = root.path().join("api");
+        let web = root.path().join("web");
+        let manifest_dir = root.path().join("meta");
+        for dir in [&api, &web, &manifest_dir] {
+            fs::create_dir_all(dir).await.unwrap();
+        }
+
+        let manifest = WorkspaceManifest {
+            members: vec!["../api".to_string(), "../web".to_string(), "../api/".to_string()],
+        };
+        let members = resolve_members(&manifest_dir, &manifest).await.unwrap();
+
+        let names: Vec<_> = members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["api", "web"]);
+        assert_eq!(members[0].path, api.canonicalize().unwrap());
+        assert_eq!(members[1].path, web.canonicalize().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_members_missing_member() {
+        let root = TempDir::new().unwrap();
+        let manifest = WorkspaceManifest { members: vec!["missing".to_string()] };
+
+        let error = resolve_members(root.path(), &manifest).await.unwrap_err();
+        match error {
+            PhantomError::ConfigInvalid { reason } => {
+                assert!(reason.contains("Workspace member 'missing' not found"), "{reason}");
+                assert!(reason.contains(&root.path().join("missing").display().to_string()));
+            }
+            e => panic!("Expected ConfigInvalid, got {e:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_members_duplicate_names() {
+        let root = TempDir::new().unwrap();
+        fs::create_dir_all(root.path().join("a/app")).await.unwrap();
+        fs::create_dir_all(root.path().join("b/app")).await.unwrap();
+
+        let manifest =
+            WorkspaceManifest { members: vec!["a/app".to_string(), "b/app".to_string()] };
+        let error = resolve_members(root.path(), &manifest).await.unwrap_err();
+        assert!(error.to_string().contains("'app' is used by more than one path"));
+    }
+
+    #[tokio::test]
+    async fn test_discover_workspace_from_subdirectory() {
+        let root = TempDir::new().unwrap();
+        let api = root.path().join("api");
+        let nested = api.join("src/deep");
+        fs::create_dir_all(&nested).await.unwrap();
+        fs::write(root.path().join(WORKSPACE_FILE_NAME), r#"members = ["api"]"#).await.unwrap();
+
+        let workspace = discover_workspace(&nested).await.unwrap();
+        assert_eq!(workspace.manifest_path, root.path().join(WORKSPACE_FILE_NAME));
+        assert_eq!(workspace.members.len(), 1);
+        assert_eq!(workspace.members[0].name, "api");
+    }
+
+    #[tokio::test]
+    async fn test_discover_workspace_not_found() {
+        let root = TempDir::new().unwrap();
+        let error = discover_workspace(root.path()).await.unwrap_err();
+        assert!(matches!(error, PhantomError::ConfigNotFound { .. }));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod errors;
+pub mod fanout;
+pub mod manifest;
+
+pub use errors::WorkspaceError;
+pub use fanout::{fan_out, MemberResult, MAX_CONCURRENT_MEMBERS};
+pub use manifest::{discover_workspace, Workspace, WorkspaceMember, WORKSPACE_FILE_NAME};
@@ -0,0 +1,221 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::core::filesystem::FileSystem;
+use crate::core::utils::normalize_path;
+use crate::git::libs::list_worktrees::list_worktrees;
+use crate::git::libs::move_worktree::move_worktree;
+use crate::worktree::concurrent::phantom_worktrees;
+use crate::worktree::state::{write_worktree_metadata, WorktreeMetadata};
+use crate::worktree::validate::{validate_worktree_does_not_exist, validate_worktree_name};
+use crate::{PhantomError, Result};
+use serde::Serialize;
+use std::path::Path;
+use tracing::info;
+
+/// Options for adopting a worktree created outside phantom
+#[derive(Debug, Clone, Default)]
+pub struct AdoptWorktreeOptions {
+    /// Phantom name; defaults to the branch, or the directory name when detached
+    pub name: Option<String>,
+    /// Leave the worktree where it is and record its location instead of moving it
+    pub in_place: bool,
+}
+
+/// Result of adopting a worktree
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdoptWorktreeSuccess {
+    pub name: String,
+    pub path: String,
+    /// Where the worktree was before it was moved under the phantom root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moved_from: Option<String>,
+}
+
+/// Bring a worktree of this repository that phantom did not create under its management
+///
+/// The worktree is moved under the phantom root with `git worktree move`, or
+/// with `in_place` left where it is and its location recorded in the state file.
+pub async fn adopt_worktree<E>(
+    executor: E,
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    worktree_path: &Path,
+    options: AdoptWorktreeOptions,
+) -> Result<AdoptWorktreeSuccess>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let not_a_worktree = || PhantomError::ValidationFailed {
+        reason: format!("'{}' is not a worktree of this repository", worktree_path.display()),
+    };
+    let wanted = worktree_path.canonicalize().map_err(|_| not_a_worktree())?;
+
+    // git lists the main worktree first
+    let worktrees = list_worktrees(executor.clone(), git_root).await?;
+    let (index, worktree) = worktrees
+        .iter()
+        .enumerate()
+        .find(|(_, worktree)| normalize_path(&worktree.path) == wanted)
+        .ok_or_else(not_a_worktree)?;
+    if index == 0 || worktree.is_bare {
+        return Err(PhantomError::ValidationFailed {
+            reason: format!(
+                "'{}' is the main worktree and cannot be adopted",
+                worktree_path.display()
+            ),
+        });
+    }
+    if let Some((name, _)) =
+        phantom_worktrees(filesystem, git_root, vec![worktree.clone()]).await.pop()
+    {
+        return Err(PhantomError::ValidationFailed {
+            reason: format!("'{}' is already the phantom '{name}'", worktree_path.display()),
+        });
+    }
+
+    let name = match options.name.or_else(|| worktree.branch.clone()) {
+        Some(name) => name,
+        None => wanted.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+    };
+    validate_worktree_name(&name)?;
+    let destination = validate_worktree_does_not_exist(git_root, &name, filesystem).await?.path;
+
+    let mut metadata = WorktreeMetadata::now(worktree.branch.clone());
+    if options.in_place {
+        info!("Adopting worktree '{}' in place at {:?}", name, wanted);
+        metadata.location = Some(wanted.clone());
+        write_worktree_metadata(filesystem, git_root, &name, &metadata).await?;
+        return Ok(AdoptWorktreeSuccess {
+            name,
+            path: wanted.to_string_lossy().to_string(),
+            moved_from: None,
+        });
+    }
+
+    if let Some(parent) = destination.parent() {
+        filesystem.create_dir_all(parent).await?;
+    }
+    info!("Adopting worktree '{}' by moving {:?} to {:?}", name, wanted, destination);
+    move_worktree(executor, git_root, &worktree.path, &destination).await?;
+    write_worktree_metadata(filesystem, git_root, &name, &metadata).await?;
+
+    Ok(AdoptWorktreeSuccess {
+        name,
+        path: destination.to_string_lossy().to_string(),
+        moved_from: Some(worktree.path.to_string_lossy().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::RealCommandExecutor;
+    use crate::core::filesystems::RealFileSystem;
+    use crate::git::git_executor_adapter::GitExecutor;
+    use crate::test_utils::TestRepo;
+    use crate::worktree::concurrent::list_worktrees_concurrent;
+    use crate::worktree::validate::validate_worktree_exists;
+    use std::path::PathBuf;
+
+    /// A repository with a worktree for branch `manual` added by hand next to it
+    async fn repo_with_manual_worktree() -> (TestRepo, PathBuf) {
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+        let path = repo.path().join("manual-checkout");
+        GitExecutor::new(RealCommandExecutor)
+            .with_cwd(repo.path())
+            .run(&["worktree", "add", "-b", "manual", &path.to_string_lossy()])
+            .await
+            .unwrap();
+        (repo, path)
+    }
+
+    async fn listed_names(git_root: &Path) -> Vec<(String, String)> {
+        list_worktrees_concurrent(RealCommandExecutor, &RealFileSystem::new(), git_root)
+            .await
+            .unwrap()
+            .worktrees
+            .into_iter()
+            .map(|worktree| (worktree.name, worktree.path))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_adopt_moves_the_worktree_under_the_phantom_root() {
+        let (repo, path) = repo_with_manual_worktree().await;
+        let fs = RealFileSystem::new();
+
+        let adopted = adopt_worktree(
+            RealCommandExecutor,
+            &fs,
+            repo.path(),
+            &path,
+            AdoptWorktreeOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(adopted.name, "manual");
+        assert!(!path.exists());
+        let exists = validate_worktree_exists(repo.path(), "manual", &fs).await.unwrap();
+        assert_eq!(exists.path.to_string_lossy(), adopted.path);
+        assert_eq!(listed_names(repo.path()).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_adopt_in_place_records_the_location() {
+        let (repo, path) = repo_with_manual_worktree().await;
+        let fs = RealFileSystem::new();
+        let options = AdoptWorktreeOptions { name: Some("legacy".to_string()), in_place: true };
+
+        let adopted =
+            adopt_worktree(RealCommandExecutor, &fs, repo.path(), &path, options).await.unwrap();
+
+        let location = path.canonicalize().unwrap();
+        assert_eq!(adopted.path, location.to_string_lossy());
+        let exists = validate_worktree_exists(repo.path(), "legacy", &fs).await.unwrap();
+        assert_eq!(exists.path, location);
+        let listed = listed_names(repo.path()).await;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].0, "legacy");
+
+        // Adopting it a second time is refused
+        let err = adopt_worktree(
+            RealCommandExecutor,
+            &fs,
+            repo.path(),
+            &path,
+            AdoptWorktreeOptions::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("already the phantom 'legacy'"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_adopt_rejects_the_main_worktree_and_foreign_paths() {
+        let (repo, _) = repo_with_manual_worktree().await;
+        let fs = RealFileSystem::new();
+        let adopt = |path: PathBuf| {
+            let fs = fs.clone();
+            let git_root = repo.path().to_path_buf();
+            async move {
+                adopt_worktree(
+                    RealCommandExecutor,
+                    &fs,
+                    &git_root,
+                    &path,
+                    AdoptWorktreeOptions::default(),
+                )
+                .await
+                .unwrap_err()
+                .to_string()
+            }
+        };
+
+        assert!(adopt(repo.path().to_path_buf()).await.contains("main worktree"));
+        let other = TestRepo::new().await.unwrap();
+        assert!(adopt(other.path().to_path_buf()).await.contains("not a worktree of this"));
+        assert!(adopt(repo.path().join("missing")).await.contains("not a worktree of this"));
+    }
+}
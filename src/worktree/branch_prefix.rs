@@ -0,0 +1,165 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::core::environment::Environment;
+use crate::git::git_executor_adapter::GitExecutor;
+use crate::worktree::validate::validate_branch_prefix;
+use crate::{PhantomError, Result};
+use std::path::Path;
+use tracing::debug;
+
+/// Template variable replaced with the current user's slug
+const USER_VARIABLE: &str = "{user}";
+
+/// Expand the template variables in a branch prefix
+///
+/// `{user}` is the only variable; any other `{...}` is rejected so a typo does
+/// not end up in branch names.
+pub fn expand_branch_prefix(template: &str, user: Option<&str>) -> Result<String> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let variable = match rest[start..].find('}') {
+            Some(end) => &rest[start..start + end + 1],
+            None => &rest[start..],
+        };
+        if variable != USER_VARIABLE {
+            return Err(PhantomError::InvalidBranchName {
+                name: template.to_string(),
+                reason: format!(
+                    "unknown template variable '{variable}', only {USER_VARIABLE} is supported"
+                ),
+            });
+        }
+        match user {
+            Some(user) => expanded.push_str(user),
+            None => {
+                return Err(PhantomError::InvalidBranchName {
+                    name: template.to_string(),
+                    reason: "{user} needs git config user.name or $USER to be set".to_string(),
+                })
+            }
+        }
+        rest = &rest[start + variable.len()..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Lowercase `name` and join its words with hyphens, keeping only ASCII letters and digits
+pub fn slugify(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The `{user}` value: the slugified `git config user.name`, falling back to `$USER`
+pub async fn current_user<E>(
+    executor: E,
+    environment: &dyn Environment,
+    git_root: &Path,
+) -> Option<String>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(git_root);
+    let git_name = git_executor.run(&["config", "user.name"]).await.ok();
+    let env_name = environment.get_var("USER");
+    [git_name, env_name]
+        .into_iter()
+        .flatten()
+        .map(|name| slugify(&name))
+        .find(|slug| !slug.is_empty())
+}
+
+/// Expand and validate a configured branch prefix
+pub async fn resolve_branch_prefix<E>(
+    executor: E,
+    environment: &dyn Environment,
+    git_root: &Path,
+    template: &str,
+) -> Result<String>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let user = if template.contains(USER_VARIABLE) {
+        current_user(executor, environment, git_root).await
+    } else {
+        None
+    };
+    let prefix = expand_branch_prefix(template, user.as_deref())?;
+    validate_branch_prefix(&prefix)?;
+    debug!("Using branch prefix '{}'", prefix);
+    Ok(prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::environment::MockEnvironment;
+    use crate::core::executors::MockCommandExecutor;
+
+    #[test]
+    fn test_expand_branch_prefix() {
+        assert_eq!(expand_branch_prefix("feature/", None).unwrap(), "feature/");
+        assert_eq!(expand_branch_prefix("user/{user}/", Some("alice")).unwrap(), "user/alice/");
+        assert!(expand_branch_prefix("user/{user}/", None).is_err());
+        assert!(expand_branch_prefix("{team}/", Some("alice")).is_err());
+        assert!(expand_branch_prefix("user/{user", Some("alice")).is_err());
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Alice Smith"), "alice-smith");
+        assert_eq!(slugify("  bob_o'neil "), "bob-o-neil");
+        assert_eq!(slugify("Zoë"), "zo");
+        assert_eq!(slugify("李"), "");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_branch_prefix_uses_git_user_name() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["config", "user.name"])
+            .in_dir("/repo")
+            .returns_output("Alice Smith\n", "", 0);
+
+        let prefix = resolve_branch_prefix(
+            mock,
+            &MockEnvironment::new(),
+            Path::new("/repo"),
+            "user/{user}/",
+        )
+        .await
+        .unwrap();
+        assert_eq!(prefix, "user/alice-smith/");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_branch_prefix_falls_back_to_user() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["config", "user.name"])
+            .in_dir("/repo")
+            .returns_output("", "", 1);
+        let environment = MockEnvironment::new().with_var("USER", "Bob");
+
+        let prefix =
+            resolve_branch_prefix(mock, &environment, Path::new("/repo"), "user/{user}/").await;
+        assert_eq!(prefix.unwrap(), "user/bob/");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_branch_prefix_rejects_invalid_refs() {
+        let mock = MockCommandExecutor::new();
+        let result = resolve_branch_prefix(
+            mock,
+            &MockEnvironment::new(),
+            Path::new("/repo"),
+            "bad..prefix/",
+        )
+        .await;
+        assert!(matches!(result, Err(PhantomError::InvalidBranchName { .. })));
+    }
+}
@@ -64,7 +64,13 @@ impl<State> WorktreeBuilder<State> {
         CreateWorktreeOptions {
             branch: self.branch.or_else(|| Some(name.clone())),
             commitish: self.base,
+            detach: false,
+            no_checkout: false,
+            sparse_patterns: None,
             copy_files: if self.copy_files.is_empty() { None } else { Some(self.copy_files) },
+            copy_source: None,
+            keep_on_failure: false,
+            copy_progress: None,
         }
     }
 }
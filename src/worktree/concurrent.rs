@@ -1,63 +1,112 @@
 /// Concurrent operations for worktree management
 /// These functions use async concurrency to improve performance when dealing with multiple worktrees
 use crate::core::command_executor::CommandExecutor;
+use crate::core::filesystem::FileSystem;
+use crate::core::types::Worktree;
+use crate::core::utils::normalize_path;
 use crate::git::libs::list_worktrees::list_worktrees as git_list_worktrees;
-use crate::worktree::list::{get_worktree_status, ListWorktreesSuccess, WorktreeInfo};
+use crate::git::libs::upstreams::{branch_upstreams, remote_heads};
+use crate::worktree::list::{
+    get_worktree_status, unborn_main_worktree, ListOptions, ListWorktreesSuccess, WorktreeInfo,
+    WorktreeOrigin,
+};
 use crate::worktree::paths::get_phantom_directory;
+use crate::worktree::state::adopted_worktrees;
 use crate::Result;
 use futures::future::join_all;
-use std::path::Path;
+use futures::stream::{self, StreamExt};
+use std::collections::{BTreeSet, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use tracing::debug;
 
+/// Run `f` over `items` with at most `limit` futures in flight
+///
+/// Results are returned in the order of `items`.
+pub async fn run_bounded<I, T, F, Fut>(items: I, limit: usize, f: F) -> Vec<T>
+where
+    I: IntoIterator,
+    F: Fn(I::Item) -> Fut,
+    Fut: Future<Output = T>,
+{
+    let futures = items.into_iter().enumerate().map(|(index, item)| {
+        let future = f(item);
+        async move { (index, future.await) }
+    });
+
+    let mut results: Vec<(usize, T)> =
+        stream::iter(futures).buffer_unordered(limit.max(1)).collect().await;
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
 /// List all phantom worktrees with concurrent status checks
 /// This version processes status checks for all worktrees in parallel
 pub async fn list_worktrees_concurrent<E>(
     executor: E,
+    filesystem: &dyn FileSystem,
     git_root: &Path,
 ) -> Result<ListWorktreesSuccess>
+where
+    E: CommandExecutor + Clone + Send + Sync + 'static,
+{
+    list_worktrees_concurrent_with_options(executor, filesystem, git_root, ListOptions::default())
+        .await
+}
+
+/// List all phantom worktrees, checking their status only if `options` asks for it
+///
+/// Branches and commits come from a single `git worktree list`; without status
+/// or upstream checks no other git process is spawned.
+pub async fn list_worktrees_concurrent_with_options<E>(
+    executor: E,
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    options: ListOptions,
+) -> Result<ListWorktreesSuccess>
 where
     E: CommandExecutor + Clone + Send + Sync + 'static,
 {
     debug!("Listing worktrees concurrently from git root: {:?}", git_root);
 
     let git_worktrees = git_list_worktrees(executor.clone(), git_root).await?;
-    let phantom_dir = get_phantom_directory(git_root);
-    let phantom_dir_canonical = phantom_dir.canonicalize().unwrap_or_else(|_| phantom_dir.clone());
-    let phantom_dir_str = phantom_dir_canonical.to_string_lossy();
-
-    // Filter phantom worktrees first
-    let phantom_worktrees_filtered: Vec<_> = git_worktrees
-        .into_iter()
-        .filter_map(|worktree| {
-            let worktree_path_canonical =
-                worktree.path.canonicalize().unwrap_or_else(|_| worktree.path.clone());
-            if worktree_path_canonical.starts_with(&phantom_dir_canonical) {
-                let canonical_path_str = worktree_path_canonical.to_string_lossy();
-                let name = if let Some(stripped) =
-                    canonical_path_str.strip_prefix(&format!("{phantom_dir_str}/"))
-                {
-                    stripped.to_string()
-                } else {
-                    worktree.name.clone()
-                };
-                Some((name, worktree))
-            } else {
-                None
-            }
-        })
-        .collect();
+    // With include_all the main worktree is listed like any other
+    let unborn_main = if options.include_all { None } else { unborn_main_worktree(&git_worktrees) };
+    let listed = listed_worktrees(filesystem, git_root, git_worktrees, options.include_all).await;
+    let gone = if options.check_gone || options.check_remote {
+        let branches = listed.iter().filter_map(|(_, _, w)| w.branch.as_deref());
+        gone_branches(executor.clone(), git_root, branches.collect(), options.check_remote).await
+    } else {
+        HashSet::new()
+    };
 
     // Create futures for concurrent status checks
-    let status_futures: Vec<_> = phantom_worktrees_filtered
+    let status_futures: Vec<_> = listed
         .into_iter()
-        .map(|(name, worktree)| {
+        .map(|(name, origin, worktree)| {
             let executor = executor.clone();
             let path_str = worktree.path.to_string_lossy().to_string();
 
+            let gone = worktree.branch.as_ref().is_some_and(|branch| gone.contains(branch));
+
             async move {
-                let is_clean = get_worktree_status(executor, &worktree.path).await.unwrap_or(true);
+                let is_clean = if options.include_status {
+                    get_worktree_status(executor, &worktree.path).await.unwrap_or(true)
+                } else {
+                    true
+                };
 
-                WorktreeInfo { name, path: path_str, branch: worktree.branch, is_clean }
+                WorktreeInfo {
+                    name,
+                    path: path_str,
+                    branch: worktree.branch,
+                    is_clean,
+                    is_locked: worktree.is_locked,
+                    locked_reason: worktree.locked_reason,
+                    is_detached: worktree.is_detached,
+                    gone,
+                    origin,
+                }
             }
         })
         .collect();
@@ -68,7 +117,127 @@ where
     let message =
         if phantom_worktrees.is_empty() { Some("No worktrees found".to_string()) } else { None };
 
-    Ok(ListWorktreesSuccess { worktrees: phantom_worktrees, message })
+    Ok(ListWorktreesSuccess { worktrees: phantom_worktrees, message, unborn_main })
+}
+
+/// Those of `branches` whose upstream no longer exists
+///
+/// git tracks an upstream as `[gone]` once a fetch prunes it. With `check_remote`
+/// each remote is also asked with one `git ls-remote`, which catches branches
+/// deleted since the last fetch. A failed check leaves the branches unmarked.
+async fn gone_branches<E>(
+    executor: E,
+    git_root: &Path,
+    branches: HashSet<&str>,
+    check_remote: bool,
+) -> HashSet<String>
+where
+    E: CommandExecutor + Clone + Send + Sync + 'static,
+{
+    let upstreams = match branch_upstreams(executor.clone(), git_root).await {
+        Ok(upstreams) => upstreams,
+        Err(e) => {
+            debug!("Failed to read branch upstreams: {}", e);
+            return HashSet::new();
+        }
+    };
+    let upstreams: Vec<_> =
+        upstreams.into_iter().filter(|u| branches.contains(u.branch.as_str())).collect();
+    let mut gone: HashSet<String> =
+        upstreams.iter().filter(|u| u.gone).map(|u| u.branch.clone()).collect();
+
+    if check_remote {
+        let remotes: BTreeSet<&str> =
+            upstreams.iter().filter(|u| !u.gone).map(|u| u.remote.as_str()).collect();
+        for remote in remotes {
+            match remote_heads(executor.clone(), git_root, remote).await {
+                Ok(heads) => gone.extend(
+                    upstreams
+                        .iter()
+                        .filter(|u| u.remote == remote && !heads.contains(&u.remote_ref))
+                        .map(|u| u.branch.clone()),
+                ),
+                Err(e) => debug!("Failed to list the branches of '{}': {}", remote, e),
+            }
+        }
+    }
+    gone
+}
+
+/// The worktrees to list, with their names and origins
+///
+/// Only phantom worktrees are kept unless `include_all`, which also keeps the
+/// main worktree and those added outside phantom, named after their directories.
+/// A bare main worktree has nothing checked out and is always left out.
+async fn listed_worktrees(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    git_worktrees: Vec<Worktree>,
+    include_all: bool,
+) -> Vec<(String, WorktreeOrigin, Worktree)> {
+    if !include_all {
+        return phantom_worktrees(filesystem, git_root, git_worktrees)
+            .await
+            .into_iter()
+            .map(|(name, worktree)| (name, WorktreeOrigin::Phantom, worktree))
+            .collect();
+    }
+
+    let phantom = phantom_worktrees(filesystem, git_root, git_worktrees.clone()).await;
+    git_worktrees
+        .into_iter()
+        .enumerate()
+        .filter(|(_, worktree)| !worktree.is_bare)
+        .map(|(index, worktree)| {
+            match phantom.iter().find(|(_, candidate)| candidate.path == worktree.path) {
+                Some((name, _)) => (name.clone(), WorktreeOrigin::Phantom, worktree),
+                // git always lists the main worktree first
+                None if index == 0 => (worktree.name.clone(), WorktreeOrigin::Main, worktree),
+                None => (worktree.name.clone(), WorktreeOrigin::External, worktree),
+            }
+        })
+        .collect()
+}
+
+/// Keep the worktrees under the phantom directory, and those adopted in place,
+/// paired with their phantom names and sorted by name
+pub(crate) async fn phantom_worktrees(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    git_worktrees: Vec<Worktree>,
+) -> Vec<(String, Worktree)> {
+    let phantom_dir = normalize_path(&get_phantom_directory(git_root));
+    let adopted: Vec<(String, PathBuf)> = adopted_worktrees(filesystem, git_root)
+        .await
+        .into_iter()
+        .map(|(name, location)| (name, normalize_path(&location)))
+        .collect();
+
+    let mut phantom: Vec<(String, Worktree)> = git_worktrees
+        .into_iter()
+        .filter_map(|worktree| {
+            let worktree_path = normalize_path(&worktree.path);
+            match worktree_path.strip_prefix(&phantom_dir) {
+                Ok(relative) if relative.as_os_str().is_empty() => {
+                    Some((worktree.name.clone(), worktree))
+                }
+                Ok(relative) => {
+                    let name = relative
+                        .components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    Some((name, worktree))
+                }
+                Err(_) => adopted
+                    .iter()
+                    .find(|(_, location)| *location == worktree_path)
+                    .map(|(name, _)| (name.clone(), worktree)),
+            }
+        })
+        .collect();
+    phantom.sort_by(|a, b| a.0.cmp(&b.0));
+    phantom
 }
 
 /// Get information about multiple worktrees concurrently
@@ -124,6 +293,7 @@ where
 mod tests {
     use super::*;
     use crate::core::executors::MockCommandExecutor;
+    use crate::core::filesystems::{MockFileSystem, RealFileSystem};
     use std::path::PathBuf;
 
     #[tokio::test]
@@ -132,16 +302,273 @@ mod tests {
         let git_root = PathBuf::from("/repo");
 
         // Mock empty worktree list
-        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain"]).returns_output(
-            "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n",
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output("worktree /repo\0HEAD abc123\0branch refs/heads/main\0", "", 0);
+
+        let result =
+            list_worktrees_concurrent(mock, &MockFileSystem::new(), &git_root).await.unwrap();
+
+        assert!(result.worktrees.is_empty());
+        assert_eq!(result.message, Some("No worktrees found".to_string()));
+        assert!(result.unborn_main.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_worktrees_concurrent_unborn_head() {
+        let mut mock = MockCommandExecutor::new();
+        let git_root = PathBuf::from("/repo");
+
+        // Fresh repository: HEAD points at a branch with no commits
+        mock.expect_command("git").with_args(&["worktree", "list", "--porcelain", "-z"]).returns_output(
+            "worktree /repo\0HEAD 0000000000000000000000000000000000000000\0branch refs/heads/main\0",
             "",
             0,
         );
 
-        let result = list_worktrees_concurrent(mock, &git_root).await.unwrap();
+        let result =
+            list_worktrees_concurrent(mock, &MockFileSystem::new(), &git_root).await.unwrap();
 
         assert!(result.worktrees.is_empty());
-        assert_eq!(result.message, Some("No worktrees found".to_string()));
+        let main = result.unborn_main.unwrap();
+        assert_eq!(main.name, "repo");
+        assert_eq!(main.branch, Some("main".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_phantom_worktrees_matches_through_symlinked_git_root() {
+        // git reports /private/var/... while the repository was found through /var/...,
+        // and the other way around for a worktree whose directory is gone
+        let dir = tempfile::tempdir().unwrap();
+        let real_root = dir.path().canonicalize().unwrap().join("private/repo");
+        std::fs::create_dir_all(get_phantom_directory(&real_root).join("feature/login")).unwrap();
+        let linked_root = dir.path().join("var");
+        std::os::unix::fs::symlink(real_root.parent().unwrap(), &linked_root).unwrap();
+
+        let worktree = |path: PathBuf| Worktree {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            path,
+            branch: Some("feature/login".to_string()),
+            commit: "abc123".to_string(),
+            is_bare: false,
+            is_detached: false,
+            is_locked: false,
+            locked_reason: None,
+            is_prunable: false,
+            prunable_reason: None,
+        };
+        let real_path = get_phantom_directory(&real_root).join("feature/login");
+        let missing_path = get_phantom_directory(&linked_root.join("repo")).join("gone");
+
+        for git_root in [linked_root.join("repo"), real_root.clone()] {
+            let worktrees = vec![worktree(real_path.clone()), worktree(missing_path.clone())];
+            let names: Vec<String> =
+                phantom_worktrees(&RealFileSystem::new(), &git_root, worktrees)
+                    .await
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect();
+            assert_eq!(names, ["feature/login", "gone"], "{}", git_root.display());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_adopted_worktrees_are_listed_in_name_order() {
+        use crate::worktree::state::{write_worktree_metadata, WorktreeMetadata};
+
+        let dir = tempfile::tempdir().unwrap();
+        let git_root = dir.path().join("repo");
+        let phantom_dir = get_phantom_directory(&git_root);
+        let adopted_path = dir.path().join("elsewhere");
+        let fs = RealFileSystem::new();
+        let adopted =
+            WorktreeMetadata { location: Some(adopted_path.clone()), ..Default::default() };
+        write_worktree_metadata(&fs, &git_root, "middle", &adopted).await.unwrap();
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                &format!(
+                    "worktree {}\0HEAD a\0branch refs/heads/main\0\0\
+                     worktree {}\0HEAD b\0branch refs/heads/zeta\0\0\
+                     worktree {}\0HEAD c\0branch refs/heads/middle\0\0\
+                     worktree {}\0HEAD d\0branch refs/heads/alpha\0\0",
+                    git_root.display(),
+                    phantom_dir.join("zeta").display(),
+                    adopted_path.display(),
+                    phantom_dir.join("alpha").display(),
+                ),
+                "",
+                0,
+            );
+        let options = ListOptions { include_status: false, ..Default::default() };
+
+        let result =
+            list_worktrees_concurrent_with_options(mock, &fs, &git_root, options).await.unwrap();
+
+        let names: Vec<_> = result.worktrees.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "middle", "zeta"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_without_status_runs_a_single_git_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let git_root = dir.path().canonicalize().unwrap();
+        let phantom_dir = get_phantom_directory(&git_root);
+        let mut listing =
+            format!("worktree {}\0HEAD abc123\0branch refs/heads/main\0\0", git_root.display());
+        for name in ["one", "two", "three"] {
+            let path = phantom_dir.join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            listing.push_str(&format!(
+                "worktree {}\0HEAD def456\0branch refs/heads/{name}\0\0",
+                path.display()
+            ));
+        }
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(&listing, "", 0);
+        mock.expect_command("git").with_args(&["status", "--porcelain"]).returns_output(
+            "M file.txt\n",
+            "",
+            0,
+        );
+
+        let fast = list_worktrees_concurrent_with_options(
+            mock.clone(),
+            &MockFileSystem::new(),
+            &git_root,
+            ListOptions { include_status: false, ..ListOptions::default() },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(fast.worktrees.len(), 3);
+        assert!(fast.worktrees.iter().all(|w| w.is_clean));
+        let names: Vec<_> = fast.worktrees.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["one", "three", "two"]);
+        assert_eq!(fast.worktrees[2].branch, Some("two".to_string()));
+        assert_eq!(mock.calls().len(), 1);
+
+        let full = list_worktrees_concurrent(mock.clone(), &MockFileSystem::new(), &git_root)
+            .await
+            .unwrap();
+
+        assert!(full.worktrees.iter().all(|w| !w.is_clean));
+        assert_eq!(mock.calls().len(), 1 + 1 + 3);
+    }
+
+    #[tokio::test]
+    async fn test_list_include_all_marks_the_origin_of_each_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let git_root = dir.path().canonicalize().unwrap().join("repo");
+        let phantom_path = get_phantom_directory(&git_root).join("feature");
+        std::fs::create_dir_all(&phantom_path).unwrap();
+        let external_path = git_root.parent().unwrap().join("hotfix");
+        let listing = format!(
+            "worktree {}\0HEAD abc123\0branch refs/heads/main\0\0\
+             worktree {}\0HEAD def456\0branch refs/heads/feature\0\0\
+             worktree {}\0HEAD 789abc\0branch refs/heads/hotfix\0\0",
+            git_root.display(),
+            phantom_path.display(),
+            external_path.display()
+        );
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(&listing, "", 0);
+        let fs = MockFileSystem::new();
+        let list = |include_all: bool| {
+            let options = ListOptions { include_status: false, include_all, ..Default::default() };
+            list_worktrees_concurrent_with_options(mock.clone(), &fs, &git_root, options)
+        };
+
+        let all = list(true).await.unwrap();
+        let origins: Vec<_> = all.worktrees.iter().map(|w| (w.name.as_str(), w.origin)).collect();
+        assert_eq!(
+            origins,
+            [
+                ("repo", WorktreeOrigin::Main),
+                ("feature", WorktreeOrigin::Phantom),
+                ("hotfix", WorktreeOrigin::External)
+            ]
+        );
+
+        let phantom_only = list(false).await.unwrap();
+        let names: Vec<_> = phantom_only.worktrees.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["feature"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_marks_worktrees_whose_upstream_is_gone() {
+        let dir = tempfile::tempdir().unwrap();
+        let git_root = dir.path().canonicalize().unwrap();
+        let phantom_dir = get_phantom_directory(&git_root);
+        let mut listing =
+            format!("worktree {}\0HEAD abc123\0branch refs/heads/main\0\0", git_root.display());
+        for name in ["fetched", "deleted", "kept"] {
+            let path = phantom_dir.join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            listing.push_str(&format!(
+                "worktree {}\0HEAD def456\0branch refs/heads/{name}\0\0",
+                path.display()
+            ));
+        }
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(&listing, "", 0);
+        mock.expect_command("git")
+            .with_args(&[
+                "for-each-ref",
+                "--format=%(refname:short)%00%(upstream:remotename)%00%(upstream:remoteref)%00%(upstream:track)",
+                "refs/heads",
+            ])
+            .returns_output(
+                "main\0origin\0refs/heads/main\0\n\
+                 fetched\0origin\0refs/heads/fetched\0[gone]\n\
+                 deleted\0origin\0refs/heads/deleted\0\n\
+                 kept\0origin\0refs/heads/kept\0[behind 1]\n",
+                "",
+                0,
+            );
+        mock.expect_command("git").with_args(&["ls-remote", "--heads", "origin"]).returns_output(
+            "abc123\trefs/heads/main\n123456\trefs/heads/kept\n",
+            "",
+            0,
+        );
+
+        let gone = |check_remote: bool| {
+            let mock = mock.clone();
+            let git_root = git_root.clone();
+            async move {
+                let options = ListOptions {
+                    include_status: false,
+                    check_gone: true,
+                    check_remote,
+                    ..ListOptions::default()
+                };
+                let listed = list_worktrees_concurrent_with_options(
+                    mock,
+                    &MockFileSystem::new(),
+                    &git_root,
+                    options,
+                )
+                .await
+                .unwrap();
+                listed.worktrees.into_iter().filter(|w| w.gone).map(|w| w.name).collect::<Vec<_>>()
+            }
+        };
+
+        // Without the remote only what a fetch already pruned is gone
+        assert_eq!(gone(false).await, ["fetched"]);
+        assert_eq!(gone(true).await, ["deleted", "fetched"]);
     }
 
     #[tokio::test]
@@ -181,4 +608,30 @@ mod tests {
         assert!(!results[1].1.as_ref().unwrap()); // Dirty
         assert!(results[2].1.as_ref().unwrap()); // Clean
     }
+
+    #[tokio::test]
+    async fn test_run_bounded_limits_and_preserves_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let results = run_bounded(0..8u64, 3, |i| {
+            let in_flight = in_flight.clone();
+            let peak = peak.clone();
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                // Later items finish first to exercise reordering
+                tokio::time::sleep(std::time::Duration::from_millis(8 - i)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                i * 10
+            }
+        })
+        .await;
+
+        assert_eq!(results, vec![0, 10, 20, 30, 40, 50, 60, 70]);
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
 }
@@ -4,6 +4,12 @@
 /// Default phantom directory name as a const
 pub const DEFAULT_PHANTOM_DIR: &str = ".git/phantom/worktrees";
 
+/// Directory holding per-worktree metadata files
+pub const PHANTOM_STATE_DIR: &str = ".git/phantom/state";
+
+/// Directory holding patches of uncommitted changes saved by `delete --snapshot patch`
+pub const PHANTOM_TRASH_DIR: &str = ".git/phantom/trash";
+
 /// Maximum allowed worktree name length
 pub const MAX_WORKTREE_NAME_LENGTH: usize = 255;
 
@@ -83,6 +89,12 @@ pub mod timeouts {
 
     /// Default timeout for git operations (30 seconds)
     pub const GIT_OPERATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Default retries for git commands that hit another process's lock
+    pub const GIT_LOCK_RETRIES: u32 = 3;
+
+    /// Default delay before the first of those retries
+    pub const GIT_LOCK_RETRY_DELAY: Duration = Duration::from_millis(100);
 }
 
 #[cfg(test)]
@@ -91,23 +103,15 @@ mod tests {
 
     #[test]
     fn test_const_validation() {
-        // These should compile successfully
-        const VALID_NAME_1: bool = is_valid_worktree_name_basic("feature-branch");
-        const VALID_NAME_2: bool = is_valid_worktree_name_basic("feature/sub");
-        const VALID_NAME_3: bool = is_valid_worktree_name_basic("v1.0.0");
-
-        assert!(VALID_NAME_1);
-        assert!(VALID_NAME_2);
-        assert!(VALID_NAME_3);
+        // These are checked at compile time
+        const _: () = assert!(is_valid_worktree_name_basic("feature-branch"));
+        const _: () = assert!(is_valid_worktree_name_basic("feature/sub"));
+        const _: () = assert!(is_valid_worktree_name_basic("v1.0.0"));
 
         // These should be false
-        const INVALID_NAME_1: bool = is_valid_worktree_name_basic("");
-        const INVALID_NAME_2: bool = is_valid_worktree_name_basic("feature..branch");
-        const INVALID_NAME_3: bool = is_valid_worktree_name_basic("feature@branch");
-
-        assert!(!INVALID_NAME_1);
-        assert!(!INVALID_NAME_2);
-        assert!(!INVALID_NAME_3);
+        const _: () = assert!(!is_valid_worktree_name_basic(""));
+        const _: () = assert!(!is_valid_worktree_name_basic("feature..branch"));
+        const _: () = assert!(!is_valid_worktree_name_basic("feature@branch"));
     }
 
     #[test]
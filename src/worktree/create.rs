@@ -1,15 +1,94 @@
-use crate::git::backend::GitBackend;
-use crate::worktree::errors::WorktreeError;
-use crate::worktree::file_copier::copy_files_concurrent;
+use crate::core::executors::RealCommandExecutor;
+use crate::core::utils::timed;
+use crate::git::backend::{GitBackend, GitConfig};
+use crate::git::command_backend::CommandBackend;
+use crate::git::libs::is_head_unborn::is_head_unborn;
+use crate::git::libs::sparse_checkout::init_sparse_checkout;
+use crate::worktree::file_copier::copy_configured_files;
 use crate::worktree::paths::{get_phantom_directory, get_worktree_path};
 use crate::worktree::types::{CreateWorktreeOptions, CreateWorktreeSuccess};
 use crate::worktree::validate::{validate_worktree_does_not_exist, validate_worktree_name};
 use crate::{PhantomError, Result};
-use std::path::Path;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use tokio::fs;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+/// The steps of a worktree creation that completed and are undone if a later step fails
+#[derive(Debug, Clone)]
+pub struct CreateRollback {
+    worktree_path: PathBuf,
+    branch: String,
+    worktree_added: bool,
+    branch_created: bool,
+}
+
+impl CreateRollback {
+    /// A creation whose worktree and branch both exist
+    pub fn new(worktree_path: impl Into<PathBuf>, branch: impl Into<String>) -> Self {
+        Self {
+            worktree_path: worktree_path.into(),
+            branch: branch.into(),
+            worktree_added: true,
+            branch_created: true,
+        }
+    }
+
+    /// A creation of a detached worktree, which has no branch to undo
+    pub fn detached(worktree_path: impl Into<PathBuf>) -> Self {
+        Self {
+            worktree_path: worktree_path.into(),
+            branch: String::new(),
+            worktree_added: true,
+            branch_created: false,
+        }
+    }
+
+    /// Undo the completed steps in reverse order
+    ///
+    /// Removing the worktree also removes any files copied into it. Returns a
+    /// description of each step that could not be undone.
+    pub async fn run<B>(&self, backend: &B) -> Vec<String>
+    where
+        B: GitBackend,
+    {
+        let mut unreverted = Vec::new();
+        if self.worktree_added {
+            info!("Rolling back worktree at {:?}", self.worktree_path);
+            if let Err(e) = backend.force_remove_worktree(&self.worktree_path).await {
+                warn!("Failed to remove worktree during rollback: {e}");
+                unreverted.push(format!("remove {}: {e}", self.worktree_path.display()));
+            }
+        }
+        if self.branch_created {
+            info!("Rolling back branch '{}'", self.branch);
+            if let Err(e) = backend.delete_branch(&self.branch).await {
+                warn!("Failed to delete branch during rollback: {e}");
+                unreverted.push(format!("delete branch '{}': {e}", self.branch));
+            }
+        }
+        unreverted
+    }
+
+    /// Run the rollback and wrap `error` with its outcome
+    async fn undo<B>(&self, backend: &B, name: &str, error: PhantomError) -> PhantomError
+    where
+        B: GitBackend,
+    {
+        let unreverted = self.run(backend).await;
+        PhantomError::CreateRolledBack {
+            name: name.to_string(),
+            error: Box::new(error),
+            unreverted,
+        }
+    }
+}
 
 /// Create a new worktree
+///
+/// If a step after `git worktree add` fails, the worktree and its new branch
+/// are removed again unless `options.keep_on_failure` is set. With
+/// `options.detach` no branch is created.
 pub async fn create_worktree<E>(
     executor: E,
     git_root: &Path,
@@ -30,6 +109,11 @@ where
     let branch = options.branch.as_deref().unwrap_or(name);
     let commitish = options.commitish.as_deref();
 
+    // A new branch needs a commit to start from; fail before touching the filesystem
+    if commitish.is_none() && is_head_unborn(executor.clone(), git_root).await? {
+        return Err(PhantomError::NoCommits);
+    }
+
     let worktrees_path = get_phantom_directory(git_root);
     let worktree_path = get_worktree_path(git_root, name);
 
@@ -48,48 +132,45 @@ where
     let filesystem = RealFileSystem::new();
     validate_worktree_does_not_exist(git_root, name, &filesystem).await?;
 
-    // Add the worktree using the git backend
-    info!("Creating worktree '{}' at {:?}", name, worktree_path);
-
     // Use the executor version directly
-    use crate::git::libs::add_worktree::add_worktree;
-    add_worktree(executor, git_root, &worktree_path, Some(branch), true, commitish).await.map_err(
-        |e| match e {
-            PhantomError::Git { command: _, args, exit_code, stderr } => {
-                WorktreeError::GitOperation {
-                    operation: format!("git {} failed with exit code {exit_code}", args.join(" ")),
-                    details: stderr,
-                }
-                .into()
-            }
-            _ => e,
-        },
-    )?;
-
-    let mut result = CreateWorktreeSuccess {
-        message: format!("Created worktree '{name}' at {}", worktree_path.display()),
-        path: worktree_path.to_string_lossy().to_string(),
-        copied_files: None,
-        skipped_files: None,
-        copy_error: None,
+    use crate::git::libs::add_worktree::{
+        add_detached_worktree, add_worktree, add_worktree_without_checkout,
     };
-
-    // Handle file copying if requested
-    if let Some(ref files_to_copy) = options.copy_files {
-        if !files_to_copy.is_empty() {
-            match copy_files_concurrent(git_root, &worktree_path, files_to_copy).await {
-                Ok(copy_result) => {
-                    result.copied_files = Some(copy_result.copied_files);
-                    result.skipped_files = Some(copy_result.skipped_files);
-                }
-                Err(e) => {
-                    result.copy_error = Some(e.to_string());
-                }
+    let branch = (!options.detach).then_some(branch);
+    // A sparse worktree is checked out only once its patterns are set
+    let no_checkout = options.no_checkout || options.sparse_patterns.is_some();
+    let (git, path) = (executor.clone(), worktree_path.as_path());
+    let add = async move {
+        match branch {
+            _ if no_checkout => {
+                add_worktree_without_checkout(git, git_root, path, branch, commitish).await
             }
+            Some(branch) => add_worktree(git, git_root, path, Some(branch), true, commitish).await,
+            None => add_detached_worktree(git, git_root, path, commitish).await,
         }
-    }
-
-    Ok(result)
+    };
+    let (git, patterns) = (executor.clone(), options.sparse_patterns.clone());
+    let sparse = async move {
+        match patterns {
+            Some(patterns) => init_sparse_checkout(git, path, &patterns).await,
+            None => Ok(()),
+        }
+    };
+    let config =
+        GitConfig::with_cwd(git_root).with_timeout(executor.git_settings().timeout.as_secs());
+    let backend = CommandBackend::new(config, executor.clone());
+    add_and_populate(
+        &backend,
+        executor,
+        add,
+        sparse,
+        git_root,
+        name,
+        branch,
+        &worktree_path,
+        &options,
+    )
+    .await
 }
 
 /// Create a new worktree using a GitBackend
@@ -112,6 +193,14 @@ where
 
     let branch = options.branch.as_deref().unwrap_or(name);
     let commitish = options.commitish.as_deref();
+
+    // A new branch needs a commit to start from; fail before touching the filesystem
+    if commitish.is_none() {
+        if let Err(PhantomError::NoCommits) = backend.current_commit().await {
+            return Err(PhantomError::NoCommits);
+        }
+    }
+
     let worktrees_path = get_phantom_directory(git_root);
     let worktree_path = get_worktree_path(git_root, name);
 
@@ -130,45 +219,112 @@ where
     let filesystem = RealFileSystem::new();
     validate_worktree_does_not_exist(git_root, name, &filesystem).await?;
 
-    // Add the worktree using the git backend
-    info!("Creating worktree '{}' at {:?}", name, worktree_path);
-    backend.add_worktree(&worktree_path, Some(branch), true, commitish).await.map_err(
-        |e| match e {
-            PhantomError::Git { command: _, args, exit_code, stderr } => {
-                WorktreeError::GitOperation {
-                    operation: format!("git {} failed with exit code {exit_code}", args.join(" ")),
-                    details: stderr,
-                }
-                .into()
+    if options.no_checkout || options.sparse_patterns.is_some() {
+        return Err(PhantomError::ValidationFailed {
+            reason: "--no-checkout and sparse checkout are not supported by this git backend"
+                .to_string(),
+        });
+    }
+
+    let branch = (!options.detach).then_some(branch);
+    let add = async {
+        match branch {
+            Some(branch) => {
+                backend.add_worktree(&worktree_path, Some(branch), true, commitish).await
             }
-            _ => e,
-        },
-    )?;
+            None => backend.add_detached_worktree(&worktree_path, commitish).await,
+        }
+    };
+    // A backend runs no arbitrary commands, so the copy asks git for ignored files directly
+    add_and_populate(
+        backend,
+        RealCommandExecutor,
+        add,
+        async { Ok(()) },
+        git_root,
+        name,
+        branch,
+        &worktree_path,
+        &options,
+    )
+    .await
+}
 
-    let mut result = CreateWorktreeSuccess {
-        message: format!("Created worktree '{name}' at {}", worktree_path.display()),
-        path: worktree_path.to_string_lossy().to_string(),
-        copied_files: None,
-        skipped_files: None,
-        copy_error: None,
+/// Add the worktree, check out its sparse patterns and copy the requested files into it,
+/// rolling back if any step fails
+#[allow(clippy::too_many_arguments)]
+async fn add_and_populate<B, E, Fut, SparseFut>(
+    backend: &B,
+    executor: E,
+    add: Fut,
+    sparse: SparseFut,
+    git_root: &Path,
+    name: &str,
+    branch: Option<&str>,
+    worktree_path: &Path,
+    options: &CreateWorktreeOptions,
+) -> Result<CreateWorktreeSuccess>
+where
+    B: GitBackend,
+    E: crate::core::command_executor::CommandExecutor + Clone + 'static,
+    Fut: Future<Output = Result<()>>,
+    SparseFut: Future<Output = Result<()>>,
+{
+    info!("Creating worktree '{}' at {:?}", name, worktree_path);
+    let mut rollback = match branch {
+        Some(branch) => CreateRollback::new(worktree_path, branch),
+        None => CreateRollback::detached(worktree_path),
     };
 
-    // Handle file copying if requested
-    if let Some(ref files_to_copy) = options.copy_files {
-        if !files_to_copy.is_empty() {
-            match copy_files_concurrent(git_root, &worktree_path, files_to_copy).await {
-                Ok(copy_result) => {
-                    result.copied_files = Some(copy_result.copied_files);
-                    result.skipped_files = Some(copy_result.skipped_files);
-                }
-                Err(e) => {
-                    result.copy_error = Some(e.to_string());
-                }
-            }
+    if let Err(e) = timed("worktree_add", add).await {
+        // An existing branch makes `git worktree add -b` fail before anything is created
+        if options.keep_on_failure || matches!(e, PhantomError::BranchExists { .. }) {
+            return Err(e);
         }
+        // A failed detached add leaves nothing behind
+        let Some(branch) = branch else {
+            return Err(e);
+        };
+        // Otherwise git may have created the branch before the checkout failed
+        rollback.worktree_added = false;
+        rollback.branch_created = backend.branch_exists(branch).await.unwrap_or(false);
+        if !rollback.branch_created {
+            return Err(e);
+        }
+        return Err(rollback.undo(backend, name, e).await);
     }
 
-    Ok(result)
+    match timed("sparse_checkout", sparse).await {
+        Ok(()) => {}
+        Err(e) if options.keep_on_failure => return Err(e),
+        Err(e) => return Err(rollback.undo(backend, name, e).await),
+    }
+
+    let copy = match timed(
+        "file_copy",
+        copy_configured_files(
+            executor,
+            options.copy_files.as_deref(),
+            options.copy_source.as_deref().unwrap_or(git_root),
+            worktree_path,
+            options.copy_progress.as_deref(),
+        ),
+    )
+    .await
+    {
+        Ok(copy) => copy,
+        Err(e) if options.keep_on_failure => return Err(e),
+        Err(e) => return Err(rollback.undo(backend, name, e).await),
+    };
+
+    Ok(CreateWorktreeSuccess {
+        message: format!("Created worktree '{name}' at {}", worktree_path.display()),
+        path: worktree_path.to_string_lossy().to_string(),
+        copied_files: copy.copied_files,
+        skipped_files: copy.skipped_files,
+        expanded_from: copy.expanded_from,
+        metadata_error: None,
+    })
 }
 
 #[cfg(test)]
@@ -252,6 +408,72 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_create_sparse_worktree_runs_git_in_order() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let repo = tempfile::tempdir().unwrap();
+        let git_root = repo.path();
+        let path = get_worktree_path(git_root, "feature");
+        let mut mock = MockCommandExecutor::new_ordered();
+        mock.expect_command("git")
+            .with_args(&[
+                "worktree",
+                "add",
+                "--no-checkout",
+                "-b",
+                "feature",
+                &path.to_string_lossy(),
+                "main",
+            ])
+            .in_dir(git_root)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["sparse-checkout", "init", "--cone"])
+            .in_dir(&path)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["sparse-checkout", "set", "services/api", "libs"])
+            .in_dir(&path)
+            .returns_success();
+        mock.expect_command("git").with_args(&["checkout"]).in_dir(&path).returns_success();
+
+        let options = CreateWorktreeOptions {
+            commitish: Some("main".to_string()),
+            sparse_patterns: Some(vec!["services/api".to_string(), "libs".to_string()]),
+            ..Default::default()
+        };
+        create_worktree(mock.clone(), git_root, "feature", options).await.unwrap();
+        assert_eq!(mock.calls().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_create_sparse_worktree_checks_out_only_its_patterns() {
+        use crate::core::executors::RealCommandExecutor;
+
+        let repo = TestRepo::new().await.unwrap();
+        for dir in ["app", "docs"] {
+            std::fs::create_dir(repo.path().join(dir)).unwrap();
+        }
+        repo.create_file_and_commit("app/main.rs", "fn main() {}", "App").await.unwrap();
+        repo.create_file_and_commit("docs/guide.md", "# Guide", "Docs").await.unwrap();
+
+        let options = CreateWorktreeOptions {
+            sparse_patterns: Some(vec!["app".to_string()]),
+            ..Default::default()
+        };
+        let sparse =
+            create_worktree(RealCommandExecutor, repo.path(), "sparse", options).await.unwrap();
+        assert!(Path::new(&sparse.path).join("app/main.rs").exists());
+        assert!(!Path::new(&sparse.path).join("docs").exists());
+
+        let options = CreateWorktreeOptions { no_checkout: true, ..Default::default() };
+        let empty =
+            create_worktree(RealCommandExecutor, repo.path(), "empty", options).await.unwrap();
+        assert!(!Path::new(&empty.path).join("app").exists());
+        assert!(Path::new(&empty.path).join(".git").exists());
+    }
+
     #[tokio::test]
     async fn test_create_worktree_with_backend() {
         let repo = TestRepo::new().await.unwrap();
@@ -287,7 +509,6 @@ mod tests {
         assert_eq!(success.copied_files.unwrap().len(), 2);
         assert!(success.skipped_files.is_some());
         assert_eq!(success.skipped_files.unwrap().len(), 0);
-        assert!(success.copy_error.is_none());
 
         // Verify files were copied
         let worktree_path = Path::new(&success.path);
@@ -315,7 +536,6 @@ mod tests {
         assert_eq!(success.copied_files.unwrap().len(), 1);
         assert!(success.skipped_files.is_some());
         assert_eq!(success.skipped_files.unwrap().len(), 1);
-        assert!(success.copy_error.is_none());
     }
 
     #[tokio::test]
@@ -332,7 +552,6 @@ mod tests {
         let success = result.unwrap();
         assert!(success.copied_files.is_none());
         assert!(success.skipped_files.is_none());
-        assert!(success.copy_error.is_none());
     }
 
     #[tokio::test]
@@ -350,7 +569,6 @@ mod tests {
         assert!(result.path.ends_with("verify-fields"));
         assert!(result.copied_files.is_none());
         assert!(result.skipped_files.is_none());
-        assert!(result.copy_error.is_none());
     }
 
     #[tokio::test]
@@ -387,7 +605,8 @@ mod tests {
             path: "/path/to/worktree".to_string(),
             copied_files: Some(vec!["file1".to_string()]),
             skipped_files: Some(vec!["file2".to_string()]),
-            copy_error: Some("Error".to_string()),
+            expanded_from: None,
+            metadata_error: None,
         };
         let json = serde_json::to_string(&success).unwrap();
         let deserialized: CreateWorktreeSuccess = serde_json::from_str(&json).unwrap();
@@ -395,7 +614,6 @@ mod tests {
         assert_eq!(deserialized.path, success.path);
         assert_eq!(deserialized.copied_files, success.copied_files);
         assert_eq!(deserialized.skipped_files, success.skipped_files);
-        assert_eq!(deserialized.copy_error, success.copy_error);
 
         // Test with skip_serializing_if
         let success_minimal = CreateWorktreeSuccess {
@@ -403,11 +621,181 @@ mod tests {
             path: "/path".to_string(),
             copied_files: None,
             skipped_files: None,
-            copy_error: None,
+            expanded_from: None,
+            metadata_error: None,
         };
         let json = serde_json::to_string(&success_minimal).unwrap();
         assert!(!json.contains("copied_files"));
         assert!(!json.contains("skipped_files"));
-        assert!(!json.contains("copy_error"));
+        assert!(!json.contains("metadata_error"));
+    }
+
+    #[tokio::test]
+    async fn test_create_worktree_unborn_head_fails_fast() {
+        use crate::api::Phantom;
+        use crate::core::executors::MockCommandExecutor;
+        use crate::core::filesystems::mock_filesystem::FileSystemOperation;
+        use crate::core::filesystems::MockFileSystem;
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--verify", "--quiet", "HEAD"])
+            .in_dir("/test/repo")
+            .returns_output("", "", 1);
+        let filesystem = MockFileSystem::new();
+        let phantom = Phantom::new("/test/repo", mock.clone(), filesystem.clone());
+
+        let result = phantom.create("feature", CreateWorktreeOptions::default()).await;
+
+        assert!(matches!(result, Err(PhantomError::NoCommits)));
+        // Git was only asked about HEAD, and no directory was made
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 1, "{calls:?}");
+        assert!(!calls.iter().any(|call| call.args.first().is_some_and(|arg| arg == "worktree")));
+        assert!(!filesystem.calls().iter().any(|call| matches!(
+            call.operation,
+            FileSystemOperation::CreateDir | FileSystemOperation::CreateDirAll
+        )));
+        mock.verify().unwrap();
+    }
+
+    /// A repository whose copy step fails: a file sits where the worktree directory goes
+    fn blocked_copy_repo() -> (tempfile::TempDir, PathBuf, CreateWorktreeOptions) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".env"), "KEY=1").unwrap();
+        let worktree_path = get_worktree_path(dir.path(), "feature");
+        std::fs::create_dir_all(worktree_path.parent().unwrap()).unwrap();
+        std::fs::write(&worktree_path, "").unwrap();
+        let options = CreateWorktreeOptions {
+            copy_files: Some(vec![".env".to_string()]),
+            ..Default::default()
+        };
+        (dir, worktree_path, options)
+    }
+
+    fn expect_add(
+        mock: &mut crate::core::executors::MockCommandExecutor,
+        git_root: &Path,
+        worktree: &str,
+    ) {
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--verify", "--quiet", "HEAD"])
+            .in_dir(git_root)
+            .returns_output("abc123\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["worktree", "add", "-b", "feature", worktree])
+            .in_dir(git_root)
+            .returns_success();
+    }
+
+    #[tokio::test]
+    async fn test_create_worktree_rolls_back_failed_copy() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let (dir, worktree_path, options) = blocked_copy_repo();
+        let worktree = worktree_path.to_string_lossy().to_string();
+        let mut mock = MockCommandExecutor::new_ordered();
+        expect_add(&mut mock, dir.path(), &worktree);
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "--force", &worktree])
+            .in_dir(dir.path())
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["branch", "-D", "feature"])
+            .in_dir(dir.path())
+            .returns_success();
+
+        let result = create_worktree(mock.clone(), dir.path(), "feature", options).await;
+
+        match result {
+            Err(PhantomError::CreateRolledBack { name, unreverted, .. }) => {
+                assert_eq!(name, "feature");
+                assert!(unreverted.is_empty());
+            }
+            other => panic!("Expected a rolled back error, got {other:?}"),
+        }
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_worktree_keep_on_failure_skips_rollback() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let (dir, worktree_path, options) = blocked_copy_repo();
+        let options = CreateWorktreeOptions { keep_on_failure: true, ..options };
+        let mut mock = MockCommandExecutor::new_ordered();
+        expect_add(&mut mock, dir.path(), &worktree_path.to_string_lossy());
+
+        let result = create_worktree(mock.clone(), dir.path(), "feature", options).await;
+
+        assert!(result.is_err());
+        assert!(!matches!(result, Err(PhantomError::CreateRolledBack { .. })));
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_worktree_rolls_back_branch_after_failed_add() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let dir = tempfile::tempdir().unwrap();
+        let worktree = get_worktree_path(dir.path(), "feature").to_string_lossy().to_string();
+        let mut mock = MockCommandExecutor::new_ordered();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--verify", "--quiet", "HEAD"])
+            .in_dir(dir.path())
+            .returns_output("abc123\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["worktree", "add", "-b", "feature", &worktree])
+            .in_dir(dir.path())
+            .returns_output("", "error: unable to checkout working tree\n", 128);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/feature"])
+            .in_dir(dir.path())
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["branch", "-D", "feature"])
+            .in_dir(dir.path())
+            .returns_output("", "error: branch 'feature' is locked\n", 1);
+
+        let error =
+            create_worktree(mock.clone(), dir.path(), "feature", CreateWorktreeOptions::default())
+                .await
+                .unwrap_err();
+
+        match &error {
+            PhantomError::CreateRolledBack { error, unreverted, .. } => {
+                assert!(
+                    matches!(error.as_ref(), PhantomError::Git { stderr, .. } if stderr.contains("unable to checkout"))
+                );
+                assert_eq!(unreverted.len(), 1);
+            }
+            other => panic!("Expected a rolled back error, got {other:?}"),
+        }
+        assert!(error.to_string().contains("could not undo: delete branch 'feature'"));
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_worktree_existing_branch_has_nothing_to_roll_back() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let dir = tempfile::tempdir().unwrap();
+        let worktree = get_worktree_path(dir.path(), "feature").to_string_lossy().to_string();
+        let mut mock = MockCommandExecutor::new_ordered();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--verify", "--quiet", "HEAD"])
+            .in_dir(dir.path())
+            .returns_output("abc123\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["worktree", "add", "-b", "feature", &worktree])
+            .in_dir(dir.path())
+            .returns_output("", "fatal: a branch named 'feature' already exists\n", 128);
+
+        let result =
+            create_worktree(mock.clone(), dir.path(), "feature", CreateWorktreeOptions::default())
+                .await;
+
+        assert!(matches!(result, Err(PhantomError::BranchExists { .. })));
+        mock.verify().unwrap();
     }
 }
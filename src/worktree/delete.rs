@@ -1,12 +1,22 @@
 use crate::core::command_executor::CommandExecutor;
 use crate::core::filesystem::FileSystem;
-use crate::git::git_executor_adapter::GitExecutor;
-use crate::worktree::errors::WorktreeError;
-use crate::worktree::types::DeleteWorktreeOptions;
-use crate::worktree::types::DeleteWorktreeSuccess;
+use crate::core::types::Worktree;
+use crate::core::utils::same_path;
+use crate::core::utils::timed;
+use crate::git::git_executor_adapter::{
+    is_branch_not_found_error, is_branch_not_merged_error, is_dirty_worktree_error,
+    is_locked_worktree_error, is_not_a_worktree_error, locked_worktree_reason, GitExecutor,
+};
+use crate::git::libs::get_current_branch::get_current_branch;
+use crate::git::libs::list_worktrees::list_worktrees;
+use crate::worktree::snapshot::snapshot_worktree;
+use crate::worktree::state::resolve_worktree_path;
+use crate::worktree::types::{DeleteOutcome, DeleteWorktreeSuccess};
+use crate::worktree::types::{DeleteWorktreeOptions, ForceOptions};
 use crate::worktree::validate::validate_worktree_exists;
 use crate::{PhantomError, Result};
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, info};
 
 /// Status of a worktree regarding uncommitted changes
@@ -24,12 +34,6 @@ where
     GitExecutor::new(executor).with_cwd(cwd)
 }
 
-/// Helper to convert git operation errors
-fn git_operation_error(operation: &str, error: impl std::fmt::Display) -> PhantomError {
-    WorktreeError::GitOperation { operation: operation.to_string(), details: error.to_string() }
-        .into()
-}
-
 /// Get the status of a worktree (uncommitted changes) with executor
 pub async fn get_worktree_status<E>(executor: E, worktree_path: &Path) -> WorktreeStatus
 where
@@ -58,48 +62,201 @@ where
 async fn remove_worktree<E>(
     executor: E,
     git_root: &Path,
+    name: &str,
     worktree_path: &Path,
-    force: bool,
+    force: ForceOptions,
 ) -> Result<()>
 where
     E: CommandExecutor + Clone + 'static,
 {
     let git_executor = create_git_executor(executor, git_root);
+    let path = worktree_path.to_string_lossy();
 
     // First try normal removal
-    let result = git_executor.run(&["worktree", "remove", &worktree_path.to_string_lossy()]).await;
+    let result = git_executor.run(&["worktree", "remove", &path]).await;
 
     match result {
         Ok(_) => Ok(()),
-        Err(_) if force => {
-            // If normal removal fails and force is true, try force removal
-            git_executor
-                .run(&["worktree", "remove", "--force", &worktree_path.to_string_lossy()])
-                .await
-                .map(|_| ())
-                .map_err(|e| git_operation_error("worktree remove", e))
+        Err(PhantomError::Git { ref stderr, .. }) if is_locked_worktree_error(stderr) => {
+            if !force.locked {
+                return Err(PhantomError::WorktreeLocked {
+                    name: name.to_string(),
+                    reason: locked_worktree_reason(stderr),
+                });
+            }
+            // git requires --force twice to remove a locked worktree
+            git_executor.run(&["worktree", "remove", "--force", "--force", &path]).await.map(|_| ())
+        }
+        Err(PhantomError::Git { ref stderr, .. }) if is_not_a_worktree_error(stderr) => {
+            Err(PhantomError::WorktreeNotFound { name: name.to_string() })
+        }
+        Err(_) if force.dirty => {
+            // If normal removal fails and dirty state may be overridden, try force removal
+            git_executor.run(&["worktree", "remove", "--force", &path]).await.map(|_| ())
         }
-        Err(e) => Err(git_operation_error("worktree remove", e)),
+        Err(PhantomError::Git { ref stderr, .. }) if is_dirty_worktree_error(stderr) => {
+            Err(PhantomError::WorktreeHasUncommittedChanges { name: name.to_string() })
+        }
+        Err(e) => Err(e),
     }
 }
 
-/// Delete a branch with executor
-async fn delete_branch<E>(executor: E, git_root: &Path, branch_name: &str) -> Result<bool>
+/// What happened to the branch of a deleted worktree
+enum BranchDeletion {
+    Deleted,
+    NotMerged,
+    Missing,
+    Failed(PhantomError),
+}
+
+/// Delete a branch with executor; `-d` unless unmerged branches may be deleted
+async fn delete_branch<E>(
+    executor: E,
+    git_root: &Path,
+    branch_name: &str,
+    force: ForceOptions,
+) -> BranchDeletion
 where
     E: CommandExecutor + Clone + 'static,
 {
     let git_executor = create_git_executor(executor, git_root);
+    let flag = if force.unmerged { "-D" } else { "-d" };
 
-    match git_executor.run(&["branch", "-D", branch_name]).await {
-        Ok(_) => Ok(true),
+    match git_executor.run(&["branch", flag, branch_name]).await {
+        Ok(_) => BranchDeletion::Deleted,
+        Err(PhantomError::Git { ref stderr, .. }) if is_branch_not_merged_error(stderr) => {
+            BranchDeletion::NotMerged
+        }
+        Err(PhantomError::Git { ref stderr, .. }) if is_branch_not_found_error(stderr) => {
+            BranchDeletion::Missing
+        }
         Err(e) => {
             debug!("Failed to delete branch '{branch_name}': {e}");
-            Ok(false)
+            BranchDeletion::Failed(e)
+        }
+    }
+}
+
+/// Remove the directories a nested name like `feature/login` created, as far as they are empty
+async fn remove_empty_parents(filesystem: &dyn FileSystem, worktree_path: &Path, name: &str) {
+    let mut parent = worktree_path.parent();
+    for _ in 1..name.split('/').count() {
+        let Some(dir) = parent else {
+            break;
+        };
+        if let Err(e) = filesystem.remove_dir(dir).await {
+            debug!("Keeping {}: {e}", dir.display());
+            break;
+        }
+        parent = dir.parent();
+    }
+}
+
+/// The registration git still has for `name` after its directory was removed by hand
+async fn stale_registration<E>(
+    executor: E,
+    git_root: &Path,
+    name: &str,
+    filesystem: &dyn FileSystem,
+) -> Option<Worktree>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let path = resolve_worktree_path(filesystem, git_root, name).await;
+    if filesystem.is_dir(&path).await.unwrap_or(false) {
+        return None;
+    }
+    let worktrees = match list_worktrees(executor, git_root).await {
+        Ok(worktrees) => worktrees,
+        Err(e) => {
+            debug!("Failed to list worktrees while looking for '{name}': {e}");
+            return None;
         }
+    };
+    worktrees.into_iter().find(|worktree| same_path(&worktree.path, &path))
+}
+
+/// Drop the registration of a worktree whose directory is gone, then handle its branch as usual
+async fn delete_stale_worktree<E>(
+    executor: E,
+    git_root: &Path,
+    name: &str,
+    worktree: Worktree,
+    options: DeleteWorktreeOptions,
+) -> Result<DeleteWorktreeSuccess>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    if worktree.is_locked && !options.force.locked {
+        return Err(PhantomError::WorktreeLocked {
+            name: name.to_string(),
+            reason: worktree.locked_reason,
+        });
+    }
+
+    info!("Pruning the stale registration of '{}' at {:?}", name, worktree.path);
+    let path = worktree.path.to_string_lossy();
+    // git requires --force twice to remove a locked worktree
+    let args: &[&str] = if worktree.is_locked {
+        &["worktree", "remove", "--force", "--force", &path]
+    } else {
+        &["worktree", "remove", "--force", &path]
+    };
+    timed("worktree_remove", create_git_executor(executor.clone(), git_root).run(args)).await?;
+
+    let message = format!(
+        "Worktree '{name}' was already removed from disk; pruned its stale registration\n{}",
+        deletion_message(executor, git_root, name, worktree.branch, options).await
+    );
+    Ok(DeleteWorktreeSuccess {
+        message,
+        path: worktree.path.to_string_lossy().to_string(),
+        outcome: DeleteOutcome::PrunedStale,
+    })
+}
+
+/// Delete the branch of the removed worktree `name` unless it is kept, and say what happened to it
+async fn deletion_message<E>(
+    executor: E,
+    git_root: &Path,
+    name: &str,
+    branch: Option<String>,
+    options: DeleteWorktreeOptions,
+) -> String
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    match branch {
+        None => format!("Deleted worktree '{name}'"),
+        Some(branch) if options.keep_branch => {
+            format!("Deleted worktree '{name}' and kept its branch '{branch}'")
+        }
+        Some(branch) => match timed(
+            "branch_delete",
+            delete_branch(executor, git_root, &branch, options.force),
+        )
+        .await
+        {
+            BranchDeletion::Deleted => {
+                format!("Deleted worktree '{name}' and its branch '{branch}'")
+            }
+            BranchDeletion::NotMerged => format!(
+                "Deleted worktree '{name}' and kept its branch '{branch}': it is not fully merged (use --force to delete unmerged branches, or --keep-branch to keep branches)"
+            ),
+            BranchDeletion::Missing => {
+                format!("Deleted worktree '{name}'; its branch '{branch}' did not exist")
+            }
+            BranchDeletion::Failed(e) => {
+                format!("Deleted worktree '{name}' but failed to delete its branch '{branch}': {e}")
+            }
+        },
     }
 }
 
 /// Delete a worktree with executor
+///
+/// A worktree whose directory was removed by hand while git still has it
+/// registered is pruned instead, skipping the checks that need the directory.
 pub async fn delete_worktree<E>(
     executor: E,
     git_root: &Path,
@@ -111,31 +268,69 @@ where
     E: CommandExecutor + Clone + 'static,
 {
     // Validate worktree exists
-    let validation = validate_worktree_exists(git_root, name, filesystem).await?;
-    let worktree_path = validation.path;
+    let worktree_path = match validate_worktree_exists(git_root, name, filesystem).await {
+        Ok(validation) => validation.path,
+        Err(e) => {
+            return match stale_registration(executor.clone(), git_root, name, filesystem).await {
+                Some(worktree) => {
+                    delete_stale_worktree(executor, git_root, name, worktree, options).await
+                }
+                None => Err(e),
+            };
+        }
+    };
 
     // Get worktree status
     let status = get_worktree_status(executor.clone(), &worktree_path).await;
 
     // Check for uncommitted changes
-    if status.has_uncommitted_changes && !options.force {
+    if status.has_uncommitted_changes && !options.force.dirty {
         return Err(PhantomError::WorktreeHasUncommittedChanges { name: name.to_string() });
     }
 
-    // Remove the worktree
-    info!("Removing worktree '{}' at {:?}", name, worktree_path);
-    remove_worktree(executor.clone(), git_root, &worktree_path, options.force).await?;
-
-    // Try to delete the branch
-    let branch_deleted = delete_branch(executor, git_root, name).await?;
-
-    // Build the success message
-    let mut message = if branch_deleted {
-        format!("Deleted worktree '{name}' and its branch '{name}'")
+    // Save what the forced removal is about to discard
+    let snapshot = if status.has_uncommitted_changes {
+        let mode = options.snapshot.unwrap_or_default();
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        snapshot_worktree(
+            executor.clone(),
+            filesystem,
+            git_root,
+            name,
+            &worktree_path,
+            mode,
+            timestamp,
+        )
+        .await?
     } else {
-        format!("Deleted worktree '{name}'")
+        None
+    };
+
+    // The branch may differ from the name, e.g. behind a branch prefix; a detached HEAD has none
+    let branch = match get_current_branch(executor.clone(), &worktree_path).await {
+        Ok(branch) if !branch.is_empty() => Some(branch),
+        Ok(_) => None,
+        Err(e) => {
+            debug!("Failed to read the branch of '{name}', assuming '{name}': {e}");
+            Some(name.to_string())
+        }
     };
 
+    // Remove the worktree
+    info!("Removing worktree '{}' at {:?}", name, worktree_path);
+    timed(
+        "worktree_remove",
+        remove_worktree(executor.clone(), git_root, name, &worktree_path, options.force),
+    )
+    .await?;
+    remove_empty_parents(filesystem, &worktree_path, name).await;
+
+    // Build the success message, which says what happened to the branch
+    let mut message = deletion_message(executor, git_root, name, branch, options).await;
+
+    if let Some(snapshot) = snapshot {
+        message = format!("Saved its uncommitted changes to {snapshot}\n{message}");
+    }
     if status.has_uncommitted_changes {
         message = format!(
             "Warning: Worktree '{name}' had uncommitted changes ({} files)\n{message}",
@@ -143,16 +338,22 @@ where
         );
     }
 
-    Ok(DeleteWorktreeSuccess { message, path: worktree_path.to_string_lossy().to_string() })
+    Ok(DeleteWorktreeSuccess {
+        message,
+        path: worktree_path.to_string_lossy().to_string(),
+        outcome: DeleteOutcome::Deleted,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::types::DeleteSnapshot;
     use crate::test_utils::TestRepo;
     use crate::worktree::create::create_worktree;
     use crate::worktree::types::CreateWorktreeOptions;
     use crate::PhantomError;
+    use tempfile::TempDir;
 
     #[tokio::test]
     async fn test_get_worktree_status_clean() {
@@ -207,6 +408,40 @@ mod tests {
         assert!(success.message.contains("Deleted worktree 'feature'"));
     }
 
+    #[tokio::test]
+    async fn test_delete_worktree_removes_prefixed_branch() {
+        use crate::core::executors::RealCommandExecutor;
+        use crate::core::filesystems::RealFileSystem;
+        use crate::git::libs::branch_exists::branch_exists;
+
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+        let create_options = CreateWorktreeOptions {
+            branch: Some("user/alice/feature".to_string()),
+            ..Default::default()
+        };
+        create_worktree(RealCommandExecutor, repo.path(), "feature", create_options).await.unwrap();
+
+        let success = delete_worktree(
+            RealCommandExecutor,
+            repo.path(),
+            "feature",
+            DeleteWorktreeOptions::default(),
+            &RealFileSystem::new(),
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            success.message.contains("and its branch 'user/alice/feature'"),
+            "{}",
+            success.message
+        );
+        assert!(!branch_exists(RealCommandExecutor, repo.path(), "user/alice/feature")
+            .await
+            .unwrap());
+    }
+
     #[tokio::test]
     async fn test_delete_worktree_with_uncommitted_changes() {
         let repo = TestRepo::new().await.unwrap();
@@ -227,7 +462,11 @@ mod tests {
         // Try to delete without force
         use crate::core::filesystems::RealFileSystem;
         let filesystem = RealFileSystem::new();
-        let delete_options = DeleteWorktreeOptions { force: false };
+        let delete_options = DeleteWorktreeOptions {
+            force: ForceOptions::none(),
+            keep_branch: false,
+            snapshot: None,
+        };
         let result = delete_worktree(
             RealCommandExecutor,
             repo.path(),
@@ -245,8 +484,28 @@ mod tests {
             _ => panic!("Expected WorktreeHasUncommittedChanges error"),
         }
 
+        // Overriding only the lock protection still refuses dirty worktrees
+        let delete_options = DeleteWorktreeOptions {
+            force: ForceOptions { dirty: false, locked: true, unmerged: false },
+            keep_branch: false,
+            snapshot: None,
+        };
+        let result = delete_worktree(
+            RealCommandExecutor,
+            repo.path(),
+            "feature",
+            delete_options,
+            &filesystem,
+        )
+        .await;
+        assert!(matches!(result, Err(PhantomError::WorktreeHasUncommittedChanges { .. })));
+
         // Delete with force
-        let delete_options = DeleteWorktreeOptions { force: true };
+        let delete_options = DeleteWorktreeOptions {
+            force: ForceOptions { dirty: true, locked: false, unmerged: false },
+            keep_branch: false,
+            snapshot: None,
+        };
         let result = delete_worktree(
             RealCommandExecutor,
             repo.path(),
@@ -261,6 +520,69 @@ mod tests {
         assert!(success.message.contains("Warning: Worktree 'feature' had uncommitted changes"));
     }
 
+    #[tokio::test]
+    async fn test_delete_worktree_locked() {
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+
+        use crate::core::executors::RealCommandExecutor;
+        let create_result = create_worktree(
+            RealCommandExecutor,
+            repo.path(),
+            "feature",
+            CreateWorktreeOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let git_executor = GitExecutor::new(RealCommandExecutor).with_cwd(repo.path());
+        git_executor
+            .run(&["worktree", "lock", "--reason", "ci", &create_result.path])
+            .await
+            .unwrap();
+
+        use crate::core::filesystems::RealFileSystem;
+        let filesystem = RealFileSystem::new();
+
+        // Overriding only the dirty protection still refuses locked worktrees
+        let delete_options = DeleteWorktreeOptions {
+            force: ForceOptions { dirty: true, locked: false, unmerged: false },
+            keep_branch: false,
+            snapshot: None,
+        };
+        let result = delete_worktree(
+            RealCommandExecutor,
+            repo.path(),
+            "feature",
+            delete_options,
+            &filesystem,
+        )
+        .await;
+        match result.unwrap_err() {
+            PhantomError::WorktreeLocked { name, reason } => {
+                assert_eq!(name, "feature");
+                assert_eq!(reason.as_deref(), Some("ci"));
+            }
+            e => panic!("Expected WorktreeLocked error, got {e:?}"),
+        }
+
+        let delete_options = DeleteWorktreeOptions {
+            force: ForceOptions { dirty: false, locked: true, unmerged: false },
+            keep_branch: false,
+            snapshot: None,
+        };
+        let result = delete_worktree(
+            RealCommandExecutor,
+            repo.path(),
+            "feature",
+            delete_options,
+            &filesystem,
+        )
+        .await;
+        assert!(result.is_ok());
+        assert!(!Path::new(&create_result.path).exists());
+    }
+
     #[tokio::test]
     async fn test_delete_worktree_not_found() {
         let repo = TestRepo::new().await.unwrap();
@@ -284,4 +606,395 @@ mod tests {
             _ => panic!("Expected Worktree error"),
         }
     }
+
+    #[tokio::test]
+    async fn test_delete_worktree_prunes_stale_registration() {
+        use crate::core::executors::MockCommandExecutor;
+        use crate::core::filesystems::MockFileSystem;
+
+        // The directory is gone: the mock filesystem knows no path
+        let path = "/repo/.git/phantom/worktrees/feature";
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .in_dir("/repo")
+            .returns_output(
+                &format!(
+                    "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                     worktree {path}\0HEAD def456\0branch refs/heads/feature\0\
+                     prunable gitdir file points to non-existent location\0\0"
+                ),
+                "",
+                0,
+            );
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "--force", path])
+            .in_dir("/repo")
+            .times(1)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["branch", "-d", "feature"])
+            .in_dir("/repo")
+            .times(1)
+            .returns_success();
+
+        let success = delete_worktree(
+            mock.clone(),
+            Path::new("/repo"),
+            "feature",
+            DeleteWorktreeOptions::default(),
+            &MockFileSystem::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(success.outcome, DeleteOutcome::PrunedStale);
+        assert_eq!(
+            success.message,
+            "Worktree 'feature' was already removed from disk; pruned its stale registration\n\
+             Deleted worktree 'feature' and its branch 'feature'"
+        );
+        mock.verify().unwrap();
+        // Nothing ran inside the missing directory
+        assert!(!mock.calls().iter().any(|call| call.args[0] == "status"));
+    }
+
+    #[tokio::test]
+    async fn test_delete_worktree_after_rm_rf() {
+        use crate::core::executors::RealCommandExecutor;
+        use crate::core::filesystems::RealFileSystem;
+        use crate::git::libs::branch_exists::branch_exists;
+
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+        let created = create_worktree(
+            RealCommandExecutor,
+            repo.path(),
+            "feature",
+            CreateWorktreeOptions::default(),
+        )
+        .await
+        .unwrap();
+        std::fs::remove_dir_all(&created.path).unwrap();
+
+        let success = delete_worktree(
+            RealCommandExecutor,
+            repo.path(),
+            "feature",
+            DeleteWorktreeOptions::default(),
+            &RealFileSystem::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(success.outcome, DeleteOutcome::PrunedStale);
+        let worktrees = list_worktrees(RealCommandExecutor, repo.path()).await.unwrap();
+        assert_eq!(worktrees.len(), 1);
+        assert!(!branch_exists(RealCommandExecutor, repo.path(), "feature").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_delete_worktree_git_refuses_dirty_worktree() {
+        use crate::core::executors::MockCommandExecutor;
+        use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+        use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+        use std::path::PathBuf;
+
+        let mock_fs = MockFileSystem::new();
+        mock_fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from("/repo/.git/phantom/worktrees/feature")),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir("/repo/.git/phantom/worktrees/feature")
+            .returns_output("", "", 0);
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", "/repo/.git/phantom/worktrees/feature"])
+            .in_dir("/repo")
+            .returns_output(
+                "",
+                "fatal: '/repo/.git/phantom/worktrees/feature' contains modified or untracked files, use --force to delete it",
+                128,
+            );
+
+        let result = delete_worktree(
+            mock,
+            Path::new("/repo"),
+            "feature",
+            DeleteWorktreeOptions::default(),
+            &mock_fs,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(PhantomError::WorktreeHasUncommittedChanges { ref name }) if name == "feature"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_detached_worktree_round_trip() {
+        use crate::core::executors::MockCommandExecutor;
+        use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+        use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+        use crate::worktree::list::list_worktrees;
+
+        let root = tempfile::tempdir().unwrap();
+        let git_root = root.path();
+        let worktree_path = git_root.join(".git/phantom/worktrees/bisect");
+        let path = worktree_path.to_string_lossy().to_string();
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "add", "--detach", &path, "v1.0"])
+            .in_dir(git_root)
+            .times(1)
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .in_dir(git_root)
+            .returns_output(
+                &format!(
+                    "worktree {}\0HEAD abc123\0branch refs/heads/main\0\0\
+                     worktree {path}\0HEAD def456\0detached\0",
+                    git_root.display()
+                ),
+                "",
+                0,
+            );
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir(&worktree_path)
+            .returns_output("", "", 0);
+        mock.expect_command("git")
+            .with_args(&["branch", "--show-current"])
+            .in_dir(&worktree_path)
+            .returns_output("\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", &path])
+            .in_dir(git_root)
+            .times(1)
+            .returns_success();
+
+        let options = CreateWorktreeOptions {
+            commitish: Some("v1.0".to_string()),
+            detach: true,
+            ..Default::default()
+        };
+        create_worktree(mock.clone(), git_root, "bisect", options).await.unwrap();
+
+        let listed = list_worktrees(mock.clone(), &MockFileSystem::new(), git_root).await.unwrap();
+        assert_eq!(listed.worktrees.len(), 1);
+        assert_eq!(listed.worktrees[0].name, "bisect");
+        assert!(listed.worktrees[0].is_detached);
+        assert_eq!(listed.worktrees[0].branch, None);
+
+        let fs = MockFileSystem::new();
+        fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(worktree_path.clone()),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+        let deleted = delete_worktree(
+            mock.clone(),
+            git_root,
+            "bisect",
+            DeleteWorktreeOptions::default(),
+            &fs,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(deleted.message, "Deleted worktree 'bisect'");
+        mock.verify().unwrap();
+        assert!(!mock.calls().iter().any(|call| call.args.first().is_some_and(|a| a == "branch")
+            && call.args.get(1).is_some_and(|a| a == "-D")));
+    }
+
+    /// Delete the clean worktree 'feature' at `/repo`, with `branch -d` answering as given
+    async fn delete_with_branch_answer(
+        options: DeleteWorktreeOptions,
+        branch_answer: Option<(&str, i32)>,
+    ) -> (DeleteWorktreeSuccess, Vec<String>) {
+        use crate::core::executors::MockCommandExecutor;
+        use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+        use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+        use std::path::PathBuf;
+
+        let path = "/repo/.git/phantom/worktrees/feature";
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir(path)
+            .returns_output("", "", 0);
+        mock.expect_command("git")
+            .with_args(&["branch", "--show-current"])
+            .in_dir(path)
+            .returns_output("feature\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["worktree", "remove", path])
+            .in_dir("/repo")
+            .returns_success();
+        if let Some((stderr, code)) = branch_answer {
+            mock.expect_command("git")
+                .with_args(&["branch", "-d", "feature"])
+                .in_dir("/repo")
+                .returns_output("", stderr, code);
+        }
+        let fs = MockFileSystem::new();
+        fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from(path)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(true)),
+        });
+
+        let success = delete_worktree(mock.clone(), Path::new("/repo"), "feature", options, &fs)
+            .await
+            .unwrap();
+        let calls = mock.calls().into_iter().map(|call| call.args.join(" ")).collect();
+        (success, calls)
+    }
+
+    #[tokio::test]
+    async fn test_delete_worktree_reports_branch_outcome() {
+        let (success, _) =
+            delete_with_branch_answer(DeleteWorktreeOptions::default(), Some(("", 0))).await;
+        assert_eq!(success.message, "Deleted worktree 'feature' and its branch 'feature'");
+
+        let not_merged = "error: The branch 'feature' is not fully merged.\n";
+        let (success, _) =
+            delete_with_branch_answer(DeleteWorktreeOptions::default(), Some((not_merged, 1)))
+                .await;
+        assert!(
+            success.message.starts_with(
+                "Deleted worktree 'feature' and kept its branch 'feature': it is not fully merged"
+            ),
+            "{}",
+            success.message
+        );
+        assert!(success.message.contains("--force"));
+        assert!(success.message.contains("--keep-branch"));
+
+        let not_found = "error: branch 'feature' not found.\n";
+        let (success, _) =
+            delete_with_branch_answer(DeleteWorktreeOptions::default(), Some((not_found, 1))).await;
+        assert_eq!(
+            success.message,
+            "Deleted worktree 'feature'; its branch 'feature' did not exist"
+        );
+
+        let keep = DeleteWorktreeOptions { keep_branch: true, ..Default::default() };
+        let (success, calls) = delete_with_branch_answer(keep, None).await;
+        assert_eq!(success.message, "Deleted worktree 'feature' and kept its branch 'feature'");
+        assert!(!calls.iter().any(|call| call.starts_with("branch -d")), "{calls:?}");
+    }
+
+    /// Force-delete the dirty worktree 'feature', saving its changes as `mode` says
+    async fn delete_dirty_with_snapshot(mode: DeleteSnapshot) -> (String, Vec<String>, TempDir) {
+        use crate::core::executors::MockCommandExecutor;
+        use crate::core::filesystems::RealFileSystem;
+
+        let root = tempfile::tempdir().unwrap();
+        let git_root = root.path();
+        let worktree_path = git_root.join(".git/phantom/worktrees/feature");
+        std::fs::create_dir_all(&worktree_path).unwrap();
+        let path = worktree_path.to_string_lossy().to_string();
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir(&worktree_path)
+            .returns_output(" M src.rs\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["branch", "--show-current"])
+            .in_dir(&worktree_path)
+            .returns_output("feature\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["diff", "HEAD", "--binary"])
+            .in_dir(&worktree_path)
+            .returns_output("diff --git a/src.rs b/src.rs\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["ls-files", "--others", "--exclude-standard", "-z"])
+            .in_dir(&worktree_path)
+            .returns_output("", "", 0);
+        mock.expect_command("git").with_args(&["worktree", "remove", &path]).returns_success();
+        // The stash message carries a timestamp
+        mock.expect_command("git").in_dir(&worktree_path).returns_success();
+
+        let options = DeleteWorktreeOptions {
+            force: ForceOptions { dirty: true, ..ForceOptions::none() },
+            keep_branch: true,
+            snapshot: Some(mode),
+        };
+        let success =
+            delete_worktree(mock.clone(), git_root, "feature", options, &RealFileSystem::new())
+                .await
+                .unwrap();
+        let calls = mock.calls().into_iter().map(|call| call.args.join(" ")).collect();
+        (success.message, calls, root)
+    }
+
+    #[tokio::test]
+    async fn test_delete_worktree_snapshots_before_removal() {
+        let position = |calls: &[String], prefix: &str| {
+            calls.iter().position(|call| call.starts_with(prefix)).unwrap()
+        };
+
+        let (message, calls, _root) = delete_dirty_with_snapshot(DeleteSnapshot::Stash).await;
+        assert!(
+            position(&calls, "stash push --include-untracked -m phantom-delete feature ")
+                < position(&calls, "worktree remove"),
+            "{calls:?}"
+        );
+        assert!(message.contains("Saved its uncommitted changes to stash 'phantom-delete feature "));
+
+        let (message, calls, root) = delete_dirty_with_snapshot(DeleteSnapshot::Patch).await;
+        assert!(position(&calls, "diff HEAD") < position(&calls, "worktree remove"), "{calls:?}");
+        let trash = root.path().join(".git/phantom/trash");
+        let patches: Vec<_> =
+            std::fs::read_dir(&trash).unwrap().map(|e| e.unwrap().path()).collect();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(std::fs::read_to_string(&patches[0]).unwrap(), "diff --git a/src.rs b/src.rs\n");
+        assert!(
+            message.contains(&format!("Saved its uncommitted changes to {}", patches[0].display()))
+        );
+
+        let (message, calls, _root) = delete_dirty_with_snapshot(DeleteSnapshot::None).await;
+        assert!(!calls.iter().any(|call| call.starts_with("stash") || call.starts_with("diff")));
+        assert!(!message.contains("Saved"));
+    }
+
+    #[tokio::test]
+    async fn test_remove_empty_parents_stops_at_shared_directories() {
+        use crate::core::filesystems::RealFileSystem;
+
+        let root = tempfile::tempdir().unwrap();
+        let worktrees = root.path().join(".git/phantom/worktrees");
+        std::fs::create_dir_all(worktrees.join("team/feature")).unwrap();
+        std::fs::create_dir_all(worktrees.join("team/other")).unwrap();
+
+        let login = worktrees.join("team/feature/login");
+        remove_empty_parents(&RealFileSystem::new(), &login, "team/feature/login").await;
+
+        assert!(!worktrees.join("team/feature").exists());
+        assert!(worktrees.join("team/other").exists());
+
+        let other = worktrees.join("team/other/x");
+        remove_empty_parents(&RealFileSystem::new(), &other, "team/other/x").await;
+        assert!(!worktrees.join("team").exists());
+        assert!(worktrees.exists());
+    }
 }
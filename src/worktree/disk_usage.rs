@@ -0,0 +1,202 @@
+use crate::core::filesystem::FileSystem;
+use crate::worktree::concurrent::run_bounded;
+use crate::worktree::const_validate::PHANTOM_STATE_DIR;
+use crate::worktree::paths::join_slash_separated;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::fs;
+use tracing::debug;
+
+/// Most worktrees walked at once
+const MAX_CONCURRENT_WALKS: usize = 4;
+
+/// Sizes from earlier runs, kept next to the worktree metadata
+///
+/// Not a `.json` file, so `phantom gc` does not take it for orphaned metadata.
+const CACHE_FILE: &str = "disk-usage.cache";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiskUsageCache {
+    /// Keyed by worktree path
+    #[serde(default)]
+    entries: BTreeMap<String, CachedSize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedSize {
+    /// Newest modification time, in milliseconds, of the worktree and its top-level directories
+    stamp: u64,
+    size_bytes: u64,
+}
+
+/// Bytes in the files under `path`, not following symlinks
+///
+/// The `.git` link at the top of a worktree is skipped; what it points to
+/// belongs to the main repository.
+pub async fn directory_size(path: &Path) -> Result<u64> {
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if dir == path && entry.file_name() == ".git" {
+                continue;
+            }
+            let metadata = entry.metadata().await?;
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                total += metadata.len();
+            }
+        }
+    }
+    Ok(total)
+}
+
+/// The cache key for `path`: the newest modification time of it and its top-level directories
+///
+/// Adding or removing a file only touches its own directory, so changes deeper
+/// down can go unnoticed until something near the top changes too.
+async fn change_stamp(path: &Path) -> Result<u64> {
+    let millis = |time: SystemTime| {
+        time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+    };
+    let mut stamp = millis(fs::metadata(path).await?.modified()?);
+    let mut entries = fs::read_dir(path).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let metadata = entry.metadata().await?;
+        if metadata.is_dir() {
+            stamp = stamp.max(millis(metadata.modified()?));
+        }
+    }
+    Ok(stamp)
+}
+
+fn cache_path(git_root: &Path) -> PathBuf {
+    join_slash_separated(git_root, &format!("{PHANTOM_STATE_DIR}/{CACHE_FILE}"))
+}
+
+/// Size in bytes of each of `paths`, in order; `None` where a worktree could not be read
+///
+/// Worktrees are walked concurrently. A size is reused from the cache while the
+/// worktree's change stamp is the same as when it was measured.
+pub async fn worktree_sizes(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    paths: &[PathBuf],
+) -> Vec<Option<u64>> {
+    let cache_path = cache_path(git_root);
+    let mut cache: DiskUsageCache = match filesystem.read_to_string(&cache_path).await {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+        Err(_) => DiskUsageCache::default(),
+    };
+
+    let measured = run_bounded(paths, MAX_CONCURRENT_WALKS, |path| {
+        let cached = cache.entries.get(path.to_string_lossy().as_ref()).copied();
+        async move {
+            let stamp = change_stamp(path).await.ok()?;
+            if let Some(cached) = cached.filter(|cached| cached.stamp == stamp) {
+                return Some((cached, false));
+            }
+            let size_bytes = directory_size(path).await.ok()?;
+            Some((CachedSize { stamp, size_bytes }, true))
+        }
+    })
+    .await;
+
+    let mut changed = false;
+    for (path, measured) in paths.iter().zip(&measured) {
+        if let Some((size, true)) = measured {
+            cache.entries.insert(path.to_string_lossy().to_string(), *size);
+            changed = true;
+        }
+    }
+    if changed {
+        cache.entries.retain(|path, _| Path::new(path).is_dir());
+        if let Err(e) = write_cache(filesystem, &cache_path, &cache).await {
+            debug!("Failed to write the disk usage cache: {}", e);
+        }
+    }
+
+    measured.into_iter().map(|measured| measured.map(|(size, _)| size.size_bytes)).collect()
+}
+
+async fn write_cache(
+    filesystem: &dyn FileSystem,
+    path: &Path,
+    cache: &DiskUsageCache,
+) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        filesystem.create_dir_all(parent).await?;
+    }
+    filesystem.write_atomic(path, serde_json::to_string(cache)?.as_bytes()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::filesystems::RealFileSystem;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_directory_size_counts_nested_files_only() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.txt"), vec![0u8; 100]).unwrap();
+        std::fs::create_dir_all(root.join("src/deep")).unwrap();
+        std::fs::write(root.join("src/deep/b.rs"), vec![0u8; 250]).unwrap();
+        std::fs::create_dir(root.join("empty")).unwrap();
+        // The worktree's .git link is not part of its size
+        std::fs::write(root.join(".git"), vec![0u8; 1000]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root, root.join("src/loop")).unwrap();
+
+        assert_eq!(directory_size(root).await.unwrap(), 350);
+    }
+
+    #[tokio::test]
+    async fn test_directory_size_of_missing_directory_fails() {
+        let dir = TempDir::new().unwrap();
+        assert!(directory_size(&dir.path().join("missing")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_worktree_sizes_reuses_cache_until_stamp_changes() {
+        let repo = TempDir::new().unwrap();
+        let git_root = repo.path();
+        let worktree = git_root.join(".git/phantom/worktrees/feature");
+        std::fs::create_dir_all(worktree.join("src")).unwrap();
+        std::fs::write(worktree.join("src/main.rs"), vec![0u8; 40]).unwrap();
+        let missing = git_root.join(".git/phantom/worktrees/gone");
+        let paths = vec![worktree.clone(), missing];
+        let fs = RealFileSystem::new();
+
+        assert_eq!(worktree_sizes(&fs, git_root, &paths).await, vec![Some(40), None]);
+        assert!(cache_path(git_root).exists());
+
+        // A stale entry with the current stamp wins over walking the tree again
+        let stamp = change_stamp(&worktree).await.unwrap();
+        let mut cache = DiskUsageCache::default();
+        cache
+            .entries
+            .insert(worktree.to_string_lossy().to_string(), CachedSize { stamp, size_bytes: 7 });
+        write_cache(&fs, &cache_path(git_root), &cache).await.unwrap();
+        assert_eq!(worktree_sizes(&fs, git_root, &paths[..1]).await, vec![Some(7)]);
+
+        // A different stamp means the tree is walked again
+        cache.entries.insert(
+            worktree.to_string_lossy().to_string(),
+            CachedSize { stamp: stamp - 1, size_bytes: 7 },
+        );
+        write_cache(&fs, &cache_path(git_root), &cache).await.unwrap();
+        assert_eq!(worktree_sizes(&fs, git_root, &paths[..1]).await, vec![Some(40)]);
+    }
+}
@@ -1,34 +1,221 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter::GitExecutor;
 use crate::worktree::errors::WorktreeError;
-use crate::Result;
-use std::path::Path;
+use crate::worktree::glob::{expand_glob, is_glob_pattern};
+use crate::{PhantomError, Result};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 use tracing::debug;
 
+/// Prefix of a copyFiles entry whose files are skipped when git ignores them
+pub const RESPECT_GITIGNORE_PREFIX: char = '!';
+
 /// Result of a file copy operation
 #[derive(Debug, Clone)]
 pub struct CopyFileResult {
+    /// Every file copied, including each one inside a copied directory
     pub copied_files: Vec<String>,
     pub skipped_files: Vec<String>,
+    /// Files each glob pattern or directory expanded to
+    pub expanded_from: HashMap<String, Vec<String>>,
+}
+
+/// Receives progress while files are copied into a new worktree
+pub trait ProgressReporter: Debug + Send + Sync {
+    /// Called once before copying starts, with the number of files to copy
+    fn start(&self, total_files: usize);
+    /// Called after each file is copied or skipped, with the bytes written
+    fn file_done(&self, bytes: u64);
+    /// Called once after the last file, whether or not the copy succeeded
+    fn finish(&self);
+}
+
+/// Files to copy after glob and directory expansion
+struct ExpandedFiles {
+    files: Vec<String>,
+    /// Symlinks inside copied directories, recreated instead of followed
+    links: HashSet<String>,
+    unmatched_patterns: Vec<String>,
+    expanded_from: HashMap<String, Vec<String>>,
+}
+
+/// Expand glob patterns and directories in `files` relative to `source_dir`,
+/// keeping literal paths as-is
+///
+/// Entries starting with [`RESPECT_GITIGNORE_PREFIX`] drop the files git ignores.
+/// `target_dir` is never walked into, so copying a parent of the new worktree ends.
+async fn expand_files<E>(
+    executor: E,
+    source_dir: &Path,
+    target_dir: &Path,
+    files: &[String],
+) -> Result<ExpandedFiles>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let mut expanded = ExpandedFiles {
+        files: Vec::new(),
+        links: HashSet::new(),
+        unmatched_patterns: Vec::new(),
+        expanded_from: HashMap::new(),
+    };
+
+    for entry in files {
+        let (file, respect_gitignore) = match entry.strip_prefix(RESPECT_GITIGNORE_PREFIX) {
+            Some(file) => (file, true),
+            None => (entry.as_str(), false),
+        };
+
+        let (mut matches, expands) = if is_glob_pattern(file) {
+            let matches = expand_glob(source_dir, file).await?;
+            debug!("Pattern '{file}' matched {} files", matches.len());
+            (matches, true)
+        } else if is_dir(&source_dir.join(file)).await? {
+            let matches = walk_directory(source_dir, file, target_dir, &mut expanded.links).await?;
+            debug!("Directory '{file}' holds {} files", matches.len());
+            (matches, true)
+        } else {
+            (vec![file.to_string()], false)
+        };
+        if respect_gitignore {
+            let ignored = ignored_files(executor.clone(), source_dir, &matches).await;
+            matches.retain(|matched| !ignored.contains(matched));
+        }
+
+        if matches.is_empty() {
+            expanded.unmatched_patterns.push(entry.clone());
+            continue;
+        }
+        for matched in &matches {
+            if !expanded.files.contains(matched) {
+                expanded.files.push(matched.clone());
+            }
+        }
+        if expands {
+            expanded.expanded_from.insert(entry.clone(), matches);
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// Whether `path` is a directory, following a symlink named in copyFiles itself
+async fn is_dir(path: &Path) -> Result<bool> {
+    match fs::metadata(path).await {
+        Ok(metadata) => Ok(metadata.is_dir()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(WorktreeError::FileOperation(format!(
+            "Failed to check metadata for '{}': {e}",
+            path.display()
+        ))
+        .into()),
+    }
+}
+
+/// Every file and symlink below `dir`, as sorted paths relative to `source_dir`
+///
+/// Symlinks are collected into `links` and never followed, and each directory
+/// is read once, so a link back up the tree cannot loop. `.git` is skipped.
+async fn walk_directory(
+    source_dir: &Path,
+    dir: &str,
+    target_dir: &Path,
+    links: &mut HashSet<String>,
+) -> Result<Vec<String>> {
+    let io_error = |path: &Path, e: std::io::Error| {
+        WorktreeError::FileOperation(format!("Failed to read '{}': {e}", path.display()))
+    };
+    let target_dir = fs::canonicalize(target_dir).await.ok();
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut pending = vec![dir.trim_end_matches('/').to_string()];
+    let mut files = Vec::new();
+
+    while let Some(relative) = pending.pop() {
+        let path = source_dir.join(&relative);
+        let canonical = fs::canonicalize(&path).await.map_err(|e| io_error(&path, e))?;
+        if target_dir.as_ref() == Some(&canonical) || !visited.insert(canonical) {
+            debug!("Not descending into '{relative}' again");
+            continue;
+        }
+
+        let mut entries = fs::read_dir(&path).await.map_err(|e| io_error(&path, e))?;
+        while let Some(entry) = entries.next_entry().await.map_err(|e| io_error(&path, e))? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ".git" {
+                continue;
+            }
+            let child = format!("{relative}/{name}");
+            let file_type = entry.file_type().await.map_err(|e| io_error(&entry.path(), e))?;
+            if file_type.is_symlink() {
+                links.insert(child.clone());
+                files.push(child);
+            } else if file_type.is_dir() {
+                pending.push(child);
+            } else if file_type.is_file() {
+                files.push(child);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// The subset of `files` git ignores in `source_dir`
+///
+/// git itself decides, so negations, nested `.gitignore` files, directory-only
+/// patterns and `.git/info/exclude` behave exactly as they do for `git status`.
+/// Nothing counts as ignored when git cannot tell, such as outside a repository.
+async fn ignored_files<E>(executor: E, source_dir: &Path, files: &[String]) -> HashSet<String>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    if files.is_empty() {
+        return HashSet::new();
+    }
+    let git = GitExecutor::new(executor).with_cwd(source_dir);
+    match git.run_with_stdin(&["check-ignore", "-z", "--stdin"], files.join("\0")).await {
+        Ok(stdout) => stdout.split('\0').filter(|f| !f.is_empty()).map(String::from).collect(),
+        // Exit code 1 means none of the paths are ignored
+        Err(PhantomError::Git { exit_code: 1, .. }) => HashSet::new(),
+        Err(e) => {
+            debug!("git check-ignore failed, copying every file: {e}");
+            HashSet::new()
+        }
+    }
 }
 
 /// Copy multiple files from source directory to target directory
-pub async fn copy_files(
+pub async fn copy_files<E>(
+    executor: E,
     source_dir: &Path,
     target_dir: &Path,
     files: &[String],
-) -> Result<CopyFileResult> {
+) -> Result<CopyFileResult>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let ExpandedFiles { files, links, unmatched_patterns, expanded_from } =
+        expand_files(executor, source_dir, target_dir, files).await?;
     let mut copied_files = Vec::new();
-    let mut skipped_files = Vec::new();
+    let mut skipped_files = unmatched_patterns;
 
-    for file in files {
+    for file in &files {
         let source_path = source_dir.join(file);
         let target_path = target_dir.join(file);
 
-        match copy_single_file(&source_path, &target_path, file).await {
-            Ok(true) => {
+        let copied = if links.contains(file) {
+            copy_symlink(&source_path, &target_path, file).await
+        } else {
+            copy_single_file(&source_path, &target_path, file).await
+        };
+        match copied {
+            Ok(Some(_)) => {
                 copied_files.push(file.clone());
             }
-            Ok(false) => {
+            Ok(None) => {
                 skipped_files.push(file.clone());
             }
             Err(e) => {
@@ -41,22 +228,24 @@ pub async fn copy_files(
 
     debug!("Copied {} files, skipped {} files", copied_files.len(), skipped_files.len());
 
-    Ok(CopyFileResult { copied_files, skipped_files })
+    Ok(CopyFileResult { copied_files, skipped_files, expanded_from })
 }
 
 /// Copy a single file, creating parent directories as needed
-async fn copy_single_file(source: &Path, target: &Path, file_name: &str) -> Result<bool> {
+///
+/// Returns the bytes copied, or `None` when the source is missing or not a file.
+async fn copy_single_file(source: &Path, target: &Path, file_name: &str) -> Result<Option<u64>> {
     // Check if source exists and is a file
     match fs::metadata(source).await {
         Ok(metadata) => {
             if !metadata.is_file() {
                 debug!("Skipping '{file_name}': not a file");
-                return Ok(false);
+                return Ok(None);
             }
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
             debug!("Skipping '{file_name}': file not found");
-            return Ok(false);
+            return Ok(None);
         }
         Err(e) => {
             return Err(WorktreeError::FileOperation(format!(
@@ -76,22 +265,70 @@ async fn copy_single_file(source: &Path, target: &Path, file_name: &str) -> Resu
     }
 
     // Copy the file
-    fs::copy(source, target)
+    let bytes = fs::copy(source, target)
         .await
         .map_err(|e| WorktreeError::FileOperation(format!("Failed to copy '{file_name}': {e}")))?;
 
     debug!("Copied file: {file_name}");
-    Ok(true)
+    Ok(Some(bytes))
+}
+
+/// Recreate the symlink at `source` at `target`, pointing where the original points
+///
+/// Returns `Some(0)` so links count as copied files.
+async fn copy_symlink(source: &Path, target: &Path, file_name: &str) -> Result<Option<u64>> {
+    let error = |action: &str, e: std::io::Error| {
+        WorktreeError::FileOperation(format!("Failed to {action} '{file_name}': {e}"))
+    };
+    let destination = fs::read_link(source).await.map_err(|e| error("read link", e))?;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).await.map_err(|e| error("create directory for", e))?;
+    }
+    if fs::symlink_metadata(target).await.is_ok() {
+        fs::remove_file(target).await.map_err(|e| error("replace", e))?;
+    }
+    #[cfg(unix)]
+    fs::symlink(&destination, target).await.map_err(|e| error("link", e))?;
+    #[cfg(windows)]
+    fs::symlink_file(&destination, target).await.map_err(|e| error("link", e))?;
+
+    debug!("Linked {file_name} -> {}", destination.display());
+    Ok(Some(0))
 }
 
 /// Copy multiple files from source directory to target directory concurrently
-pub async fn copy_files_concurrent(
+pub async fn copy_files_concurrent<E>(
+    executor: E,
+    source_dir: &Path,
+    target_dir: &Path,
+    files: &[String],
+) -> Result<CopyFileResult>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    copy_files_concurrent_with_progress(executor, source_dir, target_dir, files, None).await
+}
+
+/// Copy files concurrently like [`copy_files_concurrent`], reporting each one to `progress`
+pub async fn copy_files_concurrent_with_progress<E>(
+    executor: E,
     source_dir: &Path,
     target_dir: &Path,
     files: &[String],
-) -> Result<CopyFileResult> {
+    progress: Option<&dyn ProgressReporter>,
+) -> Result<CopyFileResult>
+where
+    E: CommandExecutor + Clone + 'static,
+{
     use futures::stream::{FuturesUnordered, StreamExt};
 
+    let ExpandedFiles { files, links, unmatched_patterns, expanded_from } =
+        expand_files(executor, source_dir, target_dir, files).await?;
+    if let Some(progress) = progress {
+        progress.start(files.len());
+    }
+
     // Create futures for concurrent file copies
     let copy_futures: FuturesUnordered<_> = files
         .iter()
@@ -99,24 +336,37 @@ pub async fn copy_files_concurrent(
             let source_path = source_dir.join(file);
             let target_path = target_dir.join(file);
             let file = file.clone();
+            let is_link = links.contains(&file);
 
             async move {
-                match copy_single_file(&source_path, &target_path, &file).await {
-                    Ok(true) => (file, true, None),
-                    Ok(false) => (file, false, None),
-                    Err(e) => (file, false, Some(e)),
+                let copied = if is_link {
+                    copy_symlink(&source_path, &target_path, &file).await
+                } else {
+                    copy_single_file(&source_path, &target_path, &file).await
+                };
+                let result = match copied {
+                    Ok(Some(bytes)) => (file, true, None, bytes),
+                    Ok(None) => (file, false, None, 0),
+                    Err(e) => (file, false, Some(e), 0),
+                };
+                if let Some(progress) = progress {
+                    progress.file_done(result.3);
                 }
+                result
             }
         })
         .collect();
 
     // Execute all copies concurrently and collect results
     let results: Vec<_> = copy_futures.collect().await;
+    if let Some(progress) = progress {
+        progress.finish();
+    }
 
     let mut copied_files = Vec::new();
-    let mut skipped_files = Vec::new();
+    let mut skipped_files = unmatched_patterns;
 
-    for (file, success, error) in results {
+    for (file, success, error, _) in results {
         if let Some(e) = error {
             return Err(WorktreeError::FileOperation(format!("Failed to copy {file}: {e}")).into());
         }
@@ -134,15 +384,221 @@ pub async fn copy_files_concurrent(
         skipped_files.len()
     );
 
-    Ok(CopyFileResult { copied_files, skipped_files })
+    Ok(CopyFileResult { copied_files, skipped_files, expanded_from })
+}
+
+/// Files copied from the configured copyFiles; all `None` when nothing was requested
+#[derive(Debug, Clone, Default)]
+pub struct ConfiguredCopy {
+    pub copied_files: Option<Vec<String>>,
+    pub skipped_files: Option<Vec<String>>,
+    pub expanded_from: Option<HashMap<String, Vec<String>>>,
+}
+
+/// Copy `files` from `source_dir` into `target_dir`; nothing is copied when the list is absent or empty
+pub async fn copy_configured_files<E>(
+    executor: E,
+    files: Option<&[String]>,
+    source_dir: &Path,
+    target_dir: &Path,
+    progress: Option<&dyn ProgressReporter>,
+) -> Result<ConfiguredCopy>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let files = match files {
+        Some(files) if !files.is_empty() => files,
+        _ => return Ok(ConfiguredCopy::default()),
+    };
+    let result =
+        copy_files_concurrent_with_progress(executor, source_dir, target_dir, files, progress)
+            .await?;
+    Ok(ConfiguredCopy {
+        copied_files: Some(result.copied_files),
+        skipped_files: Some(result.skipped_files),
+        expanded_from: Some(result.expanded_from),
+    })
+}
+
+/// The copies [`copy_configured_files`] would make, as (source, target) pairs, without copying
+///
+/// Files the copy would skip because they do not exist are left out.
+pub async fn plan_configured_copies<E>(
+    executor: E,
+    files: Option<&[String]>,
+    source_dir: &Path,
+    target_dir: &Path,
+) -> Result<Vec<(PathBuf, PathBuf)>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let files = match files {
+        Some(files) if !files.is_empty() => files,
+        _ => return Ok(Vec::new()),
+    };
+    let mut planned = Vec::new();
+    for file in expand_files(executor, source_dir, target_dir, files).await?.files {
+        let source = source_dir.join(&file);
+        if fs::symlink_metadata(&source).await.is_ok() {
+            planned.push((source, target_dir.join(&file)));
+        }
+    }
+    Ok(planned)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::executors::RealCommandExecutor;
+    use crate::test_utils::TestRepo;
     use tempfile::TempDir;
     use tokio::fs;
 
+    #[tokio::test]
+    async fn test_copy_configured_files() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+        fs::write(source_dir.path().join(".env"), "KEY=1").await.unwrap();
+
+        let files = vec![".env".to_string(), "missing.txt".to_string()];
+        let copy = copy_configured_files(
+            RealCommandExecutor,
+            Some(&files),
+            source_dir.path(),
+            target_dir.path(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(copy.copied_files, Some(vec![".env".to_string()]));
+        assert_eq!(copy.skipped_files, Some(vec!["missing.txt".to_string()]));
+        assert!(target_dir.path().join(".env").exists());
+
+        let copy = copy_configured_files(
+            RealCommandExecutor,
+            Some(&[]),
+            source_dir.path(),
+            target_dir.path(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(copy.copied_files.is_none() && copy.skipped_files.is_none());
+        let copy = copy_configured_files(
+            RealCommandExecutor,
+            None,
+            source_dir.path(),
+            target_dir.path(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(copy.copied_files.is_none() && copy.expanded_from.is_none());
+
+        // A file where the target directory should be makes the copy fail
+        let blocked = target_dir.path().join("blocked");
+        fs::write(&blocked, "").await.unwrap();
+        let files = vec![".env".to_string()];
+        assert!(copy_configured_files(
+            RealCommandExecutor,
+            Some(&files),
+            source_dir.path(),
+            &blocked,
+            None
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plan_configured_copies_copies_nothing() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+        let target = target_dir.path().join("feature");
+        fs::write(source_dir.path().join(".env"), "KEY=1").await.unwrap();
+        fs::create_dir(source_dir.path().join("config")).await.unwrap();
+        fs::write(source_dir.path().join("config/local.toml"), "").await.unwrap();
+
+        let files = vec![".env".to_string(), "config".to_string(), "missing.txt".to_string()];
+        let planned =
+            plan_configured_copies(RealCommandExecutor, Some(&files), source_dir.path(), &target)
+                .await
+                .unwrap();
+
+        assert_eq!(
+            planned,
+            vec![
+                (source_dir.path().join(".env"), target.join(".env")),
+                (source_dir.path().join("config/local.toml"), target.join("config/local.toml")),
+            ]
+        );
+        assert!(!target.exists());
+        assert!(plan_configured_copies(RealCommandExecutor, None, source_dir.path(), &target)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    /// Records the calls a copy makes to its reporter
+    #[derive(Debug, Default)]
+    struct RecordingProgress(std::sync::Mutex<Vec<String>>);
+
+    impl ProgressReporter for RecordingProgress {
+        fn start(&self, total_files: usize) {
+            self.0.lock().unwrap().push(format!("start {total_files}"));
+        }
+        fn file_done(&self, bytes: u64) {
+            self.0.lock().unwrap().push(format!("done {bytes}"));
+        }
+        fn finish(&self) {
+            self.0.lock().unwrap().push("finish".to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_copy_files_concurrent_reports_progress() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+        fs::write(source_dir.path().join("a.txt"), "12345").await.unwrap();
+
+        let progress = RecordingProgress::default();
+        let files = vec!["a.txt".to_string(), "missing.txt".to_string()];
+        copy_files_concurrent_with_progress(
+            RealCommandExecutor,
+            source_dir.path(),
+            target_dir.path(),
+            &files,
+            Some(&progress),
+        )
+        .await
+        .unwrap();
+
+        let mut calls = progress.0.into_inner().unwrap();
+        assert_eq!(calls.first().map(String::as_str), Some("start 2"));
+        assert_eq!(calls.last().map(String::as_str), Some("finish"));
+        // Files complete in any order
+        calls[1..3].sort();
+        assert_eq!(calls[1..3], ["done 0".to_string(), "done 5".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_copy_files_with_awkward_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("Work Projects/client 'repo' é");
+        let target =
+            temp_dir.path().join("Work Projects/client 'repo' é/.git/phantom/worktrees/a b");
+        fs::create_dir_all(source.join("conf dir")).await.unwrap();
+        fs::write(source.join("conf dir/it's #1 é.env"), "KEY=1").await.unwrap();
+        fs::create_dir_all(&target).await.unwrap();
+
+        let files = vec!["conf dir/it's #1 é.env".to_string()];
+        let result = copy_files(RealCommandExecutor, &source, &target, &files).await.unwrap();
+
+        assert_eq!(result.copied_files, files);
+        let copied = fs::read_to_string(target.join("conf dir/it's #1 é.env")).await.unwrap();
+        assert_eq!(copied, "KEY=1");
+    }
+
     #[tokio::test]
     async fn test_copy_files_basic() {
         let source_dir = TempDir::new().unwrap();
@@ -155,7 +611,9 @@ mod tests {
         fs::write(&file2, "content2").await.unwrap();
 
         let files = vec!["file1.txt".to_string(), "file2.txt".to_string()];
-        let result = copy_files(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
 
         assert_eq!(result.copied_files.len(), 2);
         assert_eq!(result.skipped_files.len(), 0);
@@ -181,7 +639,9 @@ mod tests {
         fs::write(&file, "content").await.unwrap();
 
         let files = vec!["subdir/file.txt".to_string()];
-        let result = copy_files(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
 
         assert_eq!(result.copied_files.len(), 1);
         assert_eq!(result.skipped_files.len(), 0);
@@ -202,7 +662,9 @@ mod tests {
         fs::write(&file1, "content").await.unwrap();
 
         let files = vec!["exists.txt".to_string(), "missing.txt".to_string()];
-        let result = copy_files(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
 
         assert_eq!(result.copied_files.len(), 1);
         assert_eq!(result.copied_files[0], "exists.txt");
@@ -211,19 +673,189 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_copy_files_skip_directories() {
+    async fn test_copy_files_skips_empty_directories() {
         let source_dir = TempDir::new().unwrap();
         let target_dir = TempDir::new().unwrap();
 
-        // Create a directory instead of a file
-        let dir = source_dir.path().join("dir");
-        fs::create_dir(&dir).await.unwrap();
+        // A directory with nothing to copy in it
+        let dir = source_dir.path().join("dir/empty");
+        fs::create_dir_all(&dir).await.unwrap();
 
         let files = vec!["dir".to_string()];
-        let result = copy_files(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
 
         assert_eq!(result.copied_files.len(), 0);
-        assert_eq!(result.skipped_files.len(), 1);
+        assert_eq!(result.skipped_files, vec!["dir"]);
+    }
+
+    #[tokio::test]
+    async fn test_copy_files_copies_directories_recursively() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+
+        for file in ["certs/ca.pem", "certs/client/key.pem", "certs/client/deep/extra.pem"] {
+            let path = source_dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+            fs::write(path, file).await.unwrap();
+        }
+
+        let files = vec!["certs".to_string(), "certs/ca.pem".to_string()];
+        let result = copy_files_concurrent(
+            RealCommandExecutor,
+            source_dir.path(),
+            target_dir.path(),
+            &files,
+        )
+        .await
+        .unwrap();
+
+        // Each file inside the directory counts once
+        let mut copied = result.copied_files.clone();
+        copied.sort();
+        assert_eq!(
+            copied,
+            vec!["certs/ca.pem", "certs/client/deep/extra.pem", "certs/client/key.pem"]
+        );
+        assert_eq!(result.expanded_from["certs"].len(), 3);
+        assert_eq!(
+            fs::read_to_string(target_dir.path().join("certs/client/deep/extra.pem"))
+                .await
+                .unwrap(),
+            "certs/client/deep/extra.pem"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_files_keeps_symlinks_inside_directories() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+        let certs = source_dir.path().join("certs");
+        fs::create_dir_all(&certs).await.unwrap();
+        fs::write(certs.join("ca.pem"), "ca").await.unwrap();
+        fs::symlink("ca.pem", certs.join("current.pem")).await.unwrap();
+        // A link back up the tree would recurse forever if followed
+        fs::symlink("..", certs.join("parent")).await.unwrap();
+
+        let files = vec!["certs".to_string()];
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
+
+        assert_eq!(result.copied_files, vec!["certs/ca.pem", "certs/current.pem", "certs/parent"]);
+        let target = target_dir.path().join("certs");
+        assert_eq!(fs::read_link(target.join("current.pem")).await.unwrap(), Path::new("ca.pem"));
+        assert_eq!(fs::read_link(target.join("parent")).await.unwrap(), Path::new(".."));
+        assert_eq!(fs::read_to_string(target.join("current.pem")).await.unwrap(), "ca");
+
+        // Copying again replaces the links
+        copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_copy_files_respects_gitignore_when_asked() {
+        let repo = TestRepo::new().await.unwrap();
+        let target_dir = TempDir::new().unwrap();
+        fs::create_dir_all(repo.path().join("certs/nested")).await.unwrap();
+        repo.create_file_and_commit("certs/.gitignore", "*.tmp\n", "Ignore temp files")
+            .await
+            .unwrap();
+        for file in ["certs/ca.pem", "certs/cache.tmp", "certs/nested/old.tmp"] {
+            fs::write(repo.path().join(file), "").await.unwrap();
+        }
+
+        let files = vec!["certs".to_string()];
+        let result =
+            copy_files(RealCommandExecutor, repo.path(), target_dir.path(), &files).await.unwrap();
+        assert_eq!(result.copied_files.len(), 4);
+
+        let files = vec!["!certs".to_string(), "!*.none".to_string()];
+        let result =
+            copy_files(RealCommandExecutor, repo.path(), target_dir.path(), &files).await.unwrap();
+        assert_eq!(result.copied_files, vec!["certs/.gitignore", "certs/ca.pem"]);
+        assert_eq!(result.skipped_files, vec!["!*.none"]);
+        assert_eq!(result.expanded_from["!certs"], result.copied_files);
+    }
+
+    #[tokio::test]
+    async fn test_ignored_files_follows_git_precedence() {
+        let repo = TestRepo::new().await.unwrap();
+        let gitignores = [
+            (
+                ".gitignore",
+                "*.log\n!important.log\nbuild/\nlogs/\n!logs/keep.log\n/root-only.txt\n",
+            ),
+            ("app/.gitignore", "!*.log\nsecret.*\n"),
+            ("app/deep/.gitignore", "secret.*\n!secret.pub\n"),
+        ];
+        for (path, contents) in gitignores {
+            fs::create_dir_all(repo.path().join(path).parent().unwrap()).await.unwrap();
+            fs::write(repo.path().join(path), contents).await.unwrap();
+        }
+
+        // (path, ignored by git)
+        let cases = [
+            // A later negation overrides an earlier pattern in the same file
+            ("debug.log", true),
+            ("important.log", false),
+            ("nested/important.log", false),
+            // A deeper .gitignore overrides a shallower one, in both directions
+            ("app/debug.log", false),
+            ("app/secret.key", true),
+            ("app/deep/secret.key", true),
+            ("app/deep/secret.pub", false),
+            // Directory-only patterns do not match files with the same name
+            ("build/out.bin", true),
+            ("src/build", false),
+            // Nothing inside an excluded directory can be re-included
+            ("logs/keep.log", true),
+            // Anchored patterns only match next to their .gitignore
+            ("root-only.txt", true),
+            ("app/root-only.txt", false),
+        ];
+        for (path, _) in cases {
+            let path = repo.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+            fs::write(path, "").await.unwrap();
+        }
+
+        let files: Vec<String> = cases.iter().map(|(path, _)| path.to_string()).collect();
+        let ignored = ignored_files(RealCommandExecutor, repo.path(), &files).await;
+
+        for (path, expected) in cases {
+            assert_eq!(ignored.contains(path), expected, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ignored_files_asks_git_through_the_executor() {
+        use crate::core::command_executor::StdinMode;
+        use crate::core::executors::MockCommandExecutor;
+
+        let files = vec!["secret.key".to_string(), "notes.txt".to_string()];
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["check-ignore", "-z", "--stdin"])
+            .in_dir("/repo")
+            .with_stdin(StdinMode::Data("secret.key\0notes.txt".to_string()))
+            .returns_output("secret.key\0", "", 0);
+
+        let ignored = ignored_files(mock.clone(), Path::new("/repo"), &files).await;
+
+        assert_eq!(ignored, HashSet::from(["secret.key".to_string()]));
+        let calls = mock.calls();
+        assert!(calls[0].env_remove.contains(&"GIT_DIR".to_string()));
+        assert!(calls[0].timeout.is_some());
+
+        // Exit code 1 means nothing is ignored
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["check-ignore", "-z", "--stdin"])
+            .returns_output("", "", 1);
+        assert!(ignored_files(mock, Path::new("/repo"), &files).await.is_empty());
     }
 
     #[tokio::test]
@@ -232,7 +864,9 @@ mod tests {
         let target_dir = TempDir::new().unwrap();
 
         let files = vec![];
-        let result = copy_files(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
 
         assert_eq!(result.copied_files.len(), 0);
         assert_eq!(result.skipped_files.len(), 0);
@@ -252,7 +886,9 @@ mod tests {
         fs::write(&target_file, "old content").await.unwrap();
 
         let files = vec!["file.txt".to_string()];
-        let result = copy_files(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
 
         assert_eq!(result.copied_files.len(), 1);
         assert_eq!(result.skipped_files.len(), 0);
@@ -273,7 +909,9 @@ mod tests {
         fs::write(&file, "nested content").await.unwrap();
 
         let files = vec!["a/b/c/file.txt".to_string()];
-        let result = copy_files(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
 
         assert_eq!(result.copied_files.len(), 1);
         assert_eq!(result.skipped_files.len(), 0);
@@ -289,6 +927,7 @@ mod tests {
         let result = CopyFileResult {
             copied_files: vec!["file1.txt".to_string(), "file2.txt".to_string()],
             skipped_files: vec!["skip.txt".to_string()],
+            expanded_from: HashMap::new(),
         };
 
         let debug_str = format!("{result:?}");
@@ -303,6 +942,7 @@ mod tests {
         let result = CopyFileResult {
             copied_files: vec!["file1.txt".to_string()],
             skipped_files: vec!["file2.txt".to_string()],
+            expanded_from: HashMap::new(),
         };
 
         let cloned = result.clone();
@@ -320,7 +960,9 @@ mod tests {
         fs::write(&file, "content").await.unwrap();
 
         let files = vec!["file-with-dashes.txt".to_string()];
-        let result = copy_files(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
 
         assert_eq!(result.copied_files.len(), 1);
         assert_eq!(result.copied_files[0], "file-with-dashes.txt");
@@ -346,7 +988,9 @@ mod tests {
         fs::set_permissions(&file, perms).await.unwrap();
 
         let files = vec!["executable.sh".to_string()];
-        let result = copy_files(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
 
         assert_eq!(result.copied_files.len(), 1);
 
@@ -370,8 +1014,14 @@ mod tests {
             files.push(filename);
         }
 
-        let result =
-            copy_files_concurrent(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files_concurrent(
+            RealCommandExecutor,
+            source_dir.path(),
+            target_dir.path(),
+            &files,
+        )
+        .await
+        .unwrap();
 
         assert_eq!(result.copied_files.len(), 10);
         assert_eq!(result.skipped_files.len(), 0);
@@ -402,8 +1052,14 @@ mod tests {
             "dir".to_string(),
         ];
 
-        let result =
-            copy_files_concurrent(source_dir.path(), target_dir.path(), &files).await.unwrap();
+        let result = copy_files_concurrent(
+            RealCommandExecutor,
+            source_dir.path(),
+            target_dir.path(),
+            &files,
+        )
+        .await
+        .unwrap();
 
         assert_eq!(result.copied_files.len(), 2);
         assert!(result.copied_files.contains(&"exists1.txt".to_string()));
@@ -414,4 +1070,63 @@ mod tests {
         assert!(result.skipped_files.contains(&"missing2.txt".to_string()));
         assert!(result.skipped_files.contains(&"dir".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_copy_files_glob_patterns() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+
+        fs::write(source_dir.path().join(".env"), "base").await.unwrap();
+        fs::write(source_dir.path().join(".env.local"), "local").await.unwrap();
+        fs::write(source_dir.path().join(".env.test"), "test").await.unwrap();
+
+        let files = vec![".env".to_string(), ".env.*".to_string(), "*.json".to_string()];
+        let result = copy_files(RealCommandExecutor, source_dir.path(), target_dir.path(), &files)
+            .await
+            .unwrap();
+
+        assert_eq!(result.copied_files, vec![".env", ".env.local", ".env.test"]);
+        assert_eq!(result.skipped_files, vec!["*.json"]);
+        assert_eq!(result.expanded_from.len(), 1);
+        assert_eq!(result.expanded_from[".env.*"], vec![".env.local", ".env.test"]);
+        assert_eq!(
+            fs::read_to_string(target_dir.path().join(".env.local")).await.unwrap(),
+            "local"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_files_concurrent_nested_glob() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+
+        for file in ["config/app.local", "config/dev/db.local", "config/dev/db.json"] {
+            let path = source_dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+            fs::write(path, file).await.unwrap();
+        }
+        // Directories matching the pattern are not copied
+        fs::create_dir_all(source_dir.path().join("config/cache.local")).await.unwrap();
+
+        let files = vec!["config/**/*.local".to_string(), "config/app.local".to_string()];
+        let result = copy_files_concurrent(
+            RealCommandExecutor,
+            source_dir.path(),
+            target_dir.path(),
+            &files,
+        )
+        .await
+        .unwrap();
+
+        let mut copied = result.copied_files.clone();
+        copied.sort();
+        assert_eq!(copied, vec!["config/app.local", "config/dev/db.local"]);
+        assert!(result.skipped_files.is_empty());
+        assert_eq!(
+            result.expanded_from["config/**/*.local"],
+            vec!["config/app.local", "config/dev/db.local"]
+        );
+        assert!(target_dir.path().join("config/dev/db.local").exists());
+        assert!(!target_dir.path().join("config/dev/db.json").exists());
+    }
 }
@@ -0,0 +1,302 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::core::filesystem::FileSystem;
+use crate::git::libs::list_worktrees::list_worktrees as git_list_worktrees;
+use crate::git::libs::remove_worktree::prune_worktrees;
+use crate::worktree::concurrent::phantom_worktrees;
+use crate::worktree::const_validate::PHANTOM_STATE_DIR;
+use crate::worktree::paths::{get_phantom_directory, get_worktree_path, join_slash_separated};
+use crate::worktree::state::metadata_path;
+use crate::Result;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Leftovers of worktrees that were removed without phantom
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GcFindings {
+    /// Directories under the phantom worktrees root that no worktree uses
+    pub stale_directories: Vec<PathBuf>,
+    /// Registered worktrees whose directory is gone, cleared by `git worktree prune`
+    pub missing_worktrees: Vec<PathBuf>,
+    /// Metadata files of worktrees that no longer exist
+    pub orphaned_state_files: Vec<PathBuf>,
+}
+
+impl GcFindings {
+    /// Whether there is nothing to clean up
+    pub fn is_empty(&self) -> bool {
+        self.stale_directories.is_empty()
+            && self.missing_worktrees.is_empty()
+            && self.orphaned_state_files.is_empty()
+    }
+}
+
+/// Compare git's worktree registrations with the phantom directories and state files
+pub async fn find_garbage<E>(
+    executor: E,
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+) -> Result<GcFindings>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_worktrees = git_list_worktrees(executor, git_root).await?;
+    let missing_worktrees =
+        git_worktrees.iter().filter(|w| w.is_prunable).map(|w| w.path.clone()).collect();
+    let live: Vec<String> = phantom_worktrees(filesystem, git_root, git_worktrees)
+        .await
+        .into_iter()
+        .filter(|(_, worktree)| !worktree.is_prunable)
+        .map(|(name, _)| name)
+        .collect();
+
+    let live_dirs: BTreeSet<PathBuf> =
+        live.iter().map(|name| get_worktree_path(git_root, name)).collect();
+    let stale_directories =
+        find_stale_directories(filesystem, &get_phantom_directory(git_root), &live_dirs).await?;
+
+    let live_state: BTreeSet<PathBuf> =
+        live.iter().map(|name| metadata_path(git_root, name)).collect();
+    let state_dir = join_slash_separated(git_root, PHANTOM_STATE_DIR);
+    let orphaned_state_files = find_orphaned_state(filesystem, &state_dir, &live_state).await?;
+
+    Ok(GcFindings { stale_directories, missing_worktrees, orphaned_state_files })
+}
+
+/// Remove everything in `findings`
+pub async fn remove_garbage<E>(
+    executor: E,
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    findings: &GcFindings,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    for dir in &findings.stale_directories {
+        info!("Removing stale phantom directory {}", dir.display());
+        filesystem.remove_dir_all(dir).await?;
+    }
+    for file in &findings.orphaned_state_files {
+        info!("Removing orphaned state file {}", file.display());
+        filesystem.remove_file(file).await?;
+    }
+    if !findings.missing_worktrees.is_empty() {
+        prune_worktrees(executor, git_root).await?;
+    }
+    Ok(())
+}
+
+/// Directories under `root` that are neither a live worktree nor one of its parents
+///
+/// Worktree names may contain `/`, so a directory holding nested worktrees is
+/// searched rather than reported.
+async fn find_stale_directories(
+    filesystem: &dyn FileSystem,
+    root: &Path,
+    live_dirs: &BTreeSet<PathBuf>,
+) -> Result<Vec<PathBuf>> {
+    let mut stale = Vec::new();
+    if !filesystem.exists(root).await? {
+        return Ok(stale);
+    }
+
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = filesystem.list_dir(&dir).await?;
+        entries.sort();
+        for entry in entries {
+            if live_dirs.contains(&entry) || !filesystem.is_dir(&entry).await? {
+                continue;
+            }
+            if live_dirs.iter().any(|live| live.starts_with(&entry)) {
+                pending.push(entry);
+            } else {
+                debug!("No worktree uses {}", entry.display());
+                stale.push(entry);
+            }
+        }
+    }
+    stale.sort();
+    Ok(stale)
+}
+
+/// Metadata files under `state_dir` that belong to no live worktree
+///
+/// Quarantined `.corrupt-*` files are kept for inspection.
+async fn find_orphaned_state(
+    filesystem: &dyn FileSystem,
+    state_dir: &Path,
+    live_state: &BTreeSet<PathBuf>,
+) -> Result<Vec<PathBuf>> {
+    let mut orphaned = Vec::new();
+    if !filesystem.exists(state_dir).await? {
+        return Ok(orphaned);
+    }
+
+    let mut pending = vec![state_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in filesystem.list_dir(&dir).await? {
+            if filesystem.is_dir(&entry).await? {
+                pending.push(entry);
+            } else if entry.extension().is_some_and(|ext| ext == "json")
+                && !live_state.contains(&entry)
+            {
+                orphaned.push(entry);
+            }
+        }
+    }
+    orphaned.sort();
+    Ok(orphaned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::filesystems::mock_filesystem::{
+        FileSystemOperation, MockDirEntry, MockResult,
+    };
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+
+    fn expect(fs: &MockFileSystem, operation: FileSystemOperation, path: &str, result: MockResult) {
+        fs.expect(FileSystemExpectation {
+            operation,
+            path: Some(PathBuf::from(path)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(result),
+        });
+    }
+
+    fn expect_dir(fs: &MockFileSystem, dir: &str, entries: &[&str]) {
+        let entries =
+            entries.iter().map(|entry| MockDirEntry { path: PathBuf::from(dir).join(entry) });
+        expect(fs, FileSystemOperation::ListDir, dir, MockResult::DirEntries(entries.collect()));
+    }
+
+    fn expect_is_dir(fs: &MockFileSystem, path: &str, is_dir: bool) {
+        expect(fs, FileSystemOperation::IsDir, path, MockResult::Bool(is_dir));
+    }
+
+    /// `/repo` with a live `feature/live` worktree and a pruned `gone` worktree
+    fn mock_git() -> MockCommandExecutor {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .in_dir("/repo")
+            .returns_output(
+                "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree /repo/.git/phantom/worktrees/feature/live\0HEAD def456\0branch refs/heads/feature/live\0\0\
+                 worktree /repo/.git/phantom/worktrees/gone\0HEAD ghi789\0branch refs/heads/gone\0\
+                 prunable gitdir file points to non-existent location\0",
+                "",
+                0,
+            );
+        mock
+    }
+
+    /// A phantom directory and state directory with leftovers next to the live worktree
+    fn mock_tree() -> MockFileSystem {
+        let fs = MockFileSystem::new();
+        let worktrees = "/repo/.git/phantom/worktrees";
+        expect(&fs, FileSystemOperation::Exists, worktrees, MockResult::Bool(true));
+        expect_dir(&fs, worktrees, &["feature", "leftover", ".case-probe"]);
+        expect_is_dir(&fs, &format!("{worktrees}/feature"), true);
+        expect_is_dir(&fs, &format!("{worktrees}/leftover"), true);
+        expect_is_dir(&fs, &format!("{worktrees}/.case-probe"), false);
+        expect_dir(&fs, &format!("{worktrees}/feature"), &["live", "old"]);
+        expect_is_dir(&fs, &format!("{worktrees}/feature/old"), true);
+
+        let state = "/repo/.git/phantom/state";
+        expect(&fs, FileSystemOperation::Exists, state, MockResult::Bool(true));
+        // Walked once for adopted worktrees (their files are unreadable here) and once for orphans
+        for _ in 0..2 {
+            expect_dir(&fs, state, &["feature", "gone.json", "gone.json.corrupt-1"]);
+            expect_is_dir(&fs, &format!("{state}/feature"), true);
+            expect_is_dir(&fs, &format!("{state}/gone.json"), false);
+            expect_is_dir(&fs, &format!("{state}/gone.json.corrupt-1"), false);
+            expect_dir(&fs, &format!("{state}/feature"), &["live.json", "old.json"]);
+            expect_is_dir(&fs, &format!("{state}/feature/live.json"), false);
+            expect_is_dir(&fs, &format!("{state}/feature/old.json"), false);
+        }
+        fs
+    }
+
+    #[tokio::test]
+    async fn test_find_garbage() {
+        let findings = find_garbage(mock_git(), &mock_tree(), Path::new("/repo")).await.unwrap();
+
+        assert_eq!(
+            findings,
+            GcFindings {
+                stale_directories: vec![
+                    PathBuf::from("/repo/.git/phantom/worktrees/feature/old"),
+                    PathBuf::from("/repo/.git/phantom/worktrees/leftover"),
+                ],
+                missing_worktrees: vec![PathBuf::from("/repo/.git/phantom/worktrees/gone")],
+                orphaned_state_files: vec![
+                    PathBuf::from("/repo/.git/phantom/state/feature/old.json"),
+                    PathBuf::from("/repo/.git/phantom/state/gone.json"),
+                ],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_garbage_without_phantom_directories() {
+        let fs = MockFileSystem::new();
+        expect(
+            &fs,
+            FileSystemOperation::Exists,
+            "/repo/.git/phantom/worktrees",
+            MockResult::Bool(false),
+        );
+        expect(
+            &fs,
+            FileSystemOperation::Exists,
+            "/repo/.git/phantom/state",
+            MockResult::Bool(false),
+        );
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output("worktree /repo\0HEAD abc123\0branch refs/heads/main\0", "", 0);
+
+        let findings = find_garbage(mock, &fs, Path::new("/repo")).await.unwrap();
+        assert!(findings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_remove_garbage() {
+        let findings = GcFindings {
+            stale_directories: vec![PathBuf::from("/repo/.git/phantom/worktrees/leftover")],
+            missing_worktrees: vec![PathBuf::from("/repo/.git/phantom/worktrees/gone")],
+            orphaned_state_files: vec![PathBuf::from("/repo/.git/phantom/state/gone.json")],
+        };
+        let fs = MockFileSystem::new();
+        expect(
+            &fs,
+            FileSystemOperation::RemoveDirAll,
+            "/repo/.git/phantom/worktrees/leftover",
+            MockResult::Unit,
+        );
+        expect(
+            &fs,
+            FileSystemOperation::RemoveFile,
+            "/repo/.git/phantom/state/gone.json",
+            MockResult::Unit,
+        );
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "prune"])
+            .in_dir("/repo")
+            .times(1)
+            .returns_success();
+
+        remove_garbage(mock.clone(), &fs, Path::new("/repo"), &findings).await.unwrap();
+        mock.verify().unwrap();
+    }
+}
@@ -0,0 +1,262 @@
+use crate::worktree::errors::WorktreeError;
+use crate::Result;
+use std::collections::BTreeSet;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Check whether a copy_files entry should be treated as a glob pattern
+pub fn is_glob_pattern(entry: &str) -> bool {
+    entry.contains(['*', '?', '['])
+}
+
+/// Expand a glob pattern relative to `base_dir` into the matching file paths
+///
+/// Supports `*`, `?`, `[...]` within a path segment and `**` as a whole
+/// segment matching any number of directories. Directories are never
+/// returned, `.git` is never descended into and symlinked directories are
+/// not followed. Results are relative to `base_dir`, sorted and deduplicated.
+pub async fn expand_glob(base_dir: &Path, pattern: &str) -> Result<Vec<String>> {
+    let segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty() && *s != ".").collect();
+    let mut matches = BTreeSet::new();
+    let mut stack: Vec<(PathBuf, String, usize)> = vec![(base_dir.to_path_buf(), String::new(), 0)];
+
+    while let Some((path, relative, index)) = stack.pop() {
+        let Some(segment) = segments.get(index) else {
+            if is_file(&path).await? {
+                matches.insert(relative);
+            }
+            continue;
+        };
+
+        if *segment == "**" {
+            // Zero directories, or one more directory with the same segment
+            stack.push((path.clone(), relative.clone(), index + 1));
+            for (name, child) in read_dir(&path).await? {
+                if name != ".git" && is_real_dir(&child).await? {
+                    stack.push((child, join(&relative, &name), index));
+                }
+            }
+        } else if is_glob_pattern(segment) {
+            let pattern: Vec<char> = segment.chars().collect();
+            for (name, child) in read_dir(&path).await? {
+                let chars: Vec<char> = name.chars().collect();
+                if match_segment(&pattern, &chars) {
+                    stack.push((child, join(&relative, &name), index + 1));
+                }
+            }
+        } else {
+            stack.push((path.join(segment), join(&relative, segment), index + 1));
+        }
+    }
+
+    Ok(matches.into_iter().collect())
+}
+
+fn join(relative: &str, name: &str) -> String {
+    if relative.is_empty() {
+        name.to_string()
+    } else {
+        format!("{relative}/{name}")
+    }
+}
+
+/// List a directory, treating missing paths and non-directories as empty
+async fn read_dir(path: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut entries = match fs::read_dir(path).await {
+        Ok(entries) => entries,
+        Err(e) if matches!(e.kind(), ErrorKind::NotFound | ErrorKind::NotADirectory) => {
+            return Ok(Vec::new());
+        }
+        Err(e) => {
+            return Err(WorktreeError::FileOperation(format!(
+                "Failed to read directory '{}': {e}",
+                path.display()
+            ))
+            .into());
+        }
+    };
+
+    let mut result = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(|e| {
+        WorktreeError::FileOperation(format!("Failed to read directory '{}': {e}", path.display()))
+    })? {
+        result.push((entry.file_name().to_string_lossy().to_string(), entry.path()));
+    }
+    Ok(result)
+}
+
+async fn is_file(path: &Path) -> Result<bool> {
+    match fs::metadata(path).await {
+        Ok(metadata) => Ok(metadata.is_file()),
+        Err(e) if matches!(e.kind(), ErrorKind::NotFound | ErrorKind::NotADirectory) => Ok(false),
+        Err(e) => Err(WorktreeError::FileOperation(format!(
+            "Failed to check metadata for '{}': {e}",
+            path.display()
+        ))
+        .into()),
+    }
+}
+
+/// A directory that is not a symlink, so `**` cannot loop
+async fn is_real_dir(path: &Path) -> Result<bool> {
+    match fs::symlink_metadata(path).await {
+        Ok(metadata) => Ok(metadata.is_dir()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(WorktreeError::FileOperation(format!(
+            "Failed to check metadata for '{}': {e}",
+            path.display()
+        ))
+        .into()),
+    }
+}
+
+/// Match a single path segment against a pattern without `/`
+fn match_segment(pattern: &[char], name: &[char]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') => (0..=name.len()).any(|skip| match_segment(&pattern[1..], &name[skip..])),
+        Some('?') => !name.is_empty() && match_segment(&pattern[1..], &name[1..]),
+        Some('[') => match parse_class(&pattern[1..]) {
+            Some((class, rest)) => {
+                !name.is_empty() && class.matches(name[0]) && match_segment(rest, &name[1..])
+            }
+            // An unterminated class is a literal '['
+            None => name.first() == Some(&'[') && match_segment(&pattern[1..], &name[1..]),
+        },
+        Some(c) => name.first() == Some(c) && match_segment(&pattern[1..], &name[1..]),
+    }
+}
+
+struct CharClass {
+    negated: bool,
+    ranges: Vec<(char, char)>,
+}
+
+impl CharClass {
+    fn matches(&self, c: char) -> bool {
+        self.ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != self.negated
+    }
+}
+
+/// Parse the body of a `[...]` class, returning it and the remaining pattern
+fn parse_class(pattern: &[char]) -> Option<(CharClass, &[char])> {
+    let negated = matches!(pattern.first(), Some('!' | '^'));
+    let mut i = usize::from(negated);
+    let mut ranges = Vec::new();
+
+    // A ']' right after the opening bracket is a literal member
+    let mut first = true;
+    while i < pattern.len() {
+        let c = pattern[i];
+        if c == ']' && !first {
+            return Some((CharClass { negated, ranges }, &pattern[i + 1..]));
+        }
+        first = false;
+        if i + 2 < pattern.len() && pattern[i + 1] == '-' && pattern[i + 2] != ']' {
+            ranges.push((c, pattern[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((c, c));
+            i += 1;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn matches(pattern: &str, name: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+        match_segment(&pattern, &name)
+    }
+
+    async fn touch(dir: &Path, file: &str) {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+        fs::write(path, "x").await.unwrap();
+    }
+
+    #[test]
+    fn test_is_glob_pattern() {
+        assert!(is_glob_pattern(".env.*"));
+        assert!(is_glob_pattern("config/?.json"));
+        assert!(is_glob_pattern("[ab].txt"));
+        assert!(!is_glob_pattern(".env"));
+        assert!(!is_glob_pattern("config/local.json"));
+    }
+
+    #[test]
+    fn test_match_segment() {
+        assert!(matches(".env.*", ".env.local"));
+        assert!(matches(".env.*", ".env."));
+        assert!(!matches(".env.*", ".env"));
+        assert!(matches("*.local", "app.local"));
+        assert!(matches("file?.txt", "file1.txt"));
+        assert!(!matches("file?.txt", "file.txt"));
+        assert!(matches("[ab].txt", "a.txt"));
+        assert!(!matches("[ab].txt", "c.txt"));
+        assert!(matches("[a-c].txt", "b.txt"));
+        assert!(matches("[!a].txt", "b.txt"));
+        assert!(!matches("[!a].txt", "a.txt"));
+        assert!(matches("[]].txt", "].txt"));
+        assert!(matches("[abc", "[abc"));
+    }
+
+    #[tokio::test]
+    async fn test_expand_glob_top_level() {
+        let dir = TempDir::new().unwrap();
+        for file in [".env", ".env.local", ".env.test", "other"] {
+            touch(dir.path(), file).await;
+        }
+
+        let result = expand_glob(dir.path(), ".env.*").await.unwrap();
+        assert_eq!(result, vec![".env.local", ".env.test"]);
+    }
+
+    #[tokio::test]
+    async fn test_expand_glob_nested_double_star() {
+        let dir = TempDir::new().unwrap();
+        for file in [
+            "config/app.local",
+            "config/dev/db.local",
+            "config/dev/eu/cache.local",
+            "config/dev/db.json",
+            "other/x.local",
+        ] {
+            touch(dir.path(), file).await;
+        }
+
+        let result = expand_glob(dir.path(), "config/**/*.local").await.unwrap();
+        assert_eq!(
+            result,
+            vec!["config/app.local", "config/dev/db.local", "config/dev/eu/cache.local"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expand_glob_skips_directories_and_git() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("data.local")).await.unwrap();
+        touch(dir.path(), ".git/config.local").await;
+        touch(dir.path(), "keep.local").await;
+
+        let result = expand_glob(dir.path(), "**/*.local").await.unwrap();
+        assert_eq!(result, vec!["keep.local"]);
+    }
+
+    #[tokio::test]
+    async fn test_expand_glob_no_matches() {
+        let dir = TempDir::new().unwrap();
+        touch(dir.path(), "file.txt").await;
+
+        assert!(expand_glob(dir.path(), "*.json").await.unwrap().is_empty());
+        assert!(expand_glob(dir.path(), "missing/*.txt").await.unwrap().is_empty());
+        assert!(expand_glob(dir.path(), "file.txt/*").await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::core::command_executor::{CommandConfig, CommandExecutor};
+use crate::core::environment::Environment;
+use crate::core::filesystem::FileSystem;
+use crate::process::shell::{get_phantom_env, ShellType};
+use crate::Result;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Directory under the repository root holding the hook executables
+pub const HOOKS_DIR: &str = ".phantom/hooks";
+
+/// Repository-provided scripts run around worktree changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Runs once a worktree has been created and set up
+    PostCreate,
+    /// Runs before a worktree is removed; a failure stops the deletion
+    PreDelete,
+}
+
+impl Hook {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Hook::PostCreate => "post-create",
+            Hook::PreDelete => "pre-delete",
+        }
+    }
+
+    pub fn path(self, git_root: &Path) -> PathBuf {
+        git_root.join(HOOKS_DIR).join(self.file_name())
+    }
+}
+
+/// Run `hook` for a worktree, returning its exit code
+///
+/// Returns `None` without running anything when the hook is missing or not
+/// executable, or when the worktree directory does not exist. The hook gets the worktree name and path as arguments, runs
+/// inside the worktree with the phantom variables set, and its output is
+/// streamed to stderr.
+pub async fn run_hook<E>(
+    executor: E,
+    filesystem: &dyn FileSystem,
+    environment: &dyn Environment,
+    git_root: &Path,
+    hook: Hook,
+    name: &str,
+    worktree_path: &Path,
+) -> Result<Option<i32>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let path = hook.path(git_root);
+    if !filesystem.is_executable(&path).await.unwrap_or(false) {
+        debug!("No executable {} hook at {:?}", hook.file_name(), path);
+        return Ok(None);
+    }
+    if !filesystem.is_dir(worktree_path).await.unwrap_or(false) {
+        debug!("Skipping {} hook, {:?} does not exist", hook.file_name(), worktree_path);
+        return Ok(None);
+    }
+
+    let worktree_path = worktree_path.to_string_lossy().to_string();
+    let env = get_phantom_env(environment, ShellType::Unknown, name, &worktree_path);
+    let config = CommandConfig::new(path.to_string_lossy())
+        .with_args(vec![name.to_string(), worktree_path.clone()])
+        .with_cwd(PathBuf::from(&worktree_path))
+        .with_env(env)
+        .with_streamed_output();
+
+    debug!("Running {} hook for '{}'", hook.file_name(), name);
+    let output = executor.execute(config).await?;
+    Ok(Some(output.exit_code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::environment::MockEnvironment;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+
+    fn expect_bool(fs: &MockFileSystem, operation: FileSystemOperation, path: &str, value: bool) {
+        fs.expect(FileSystemExpectation {
+            operation,
+            path: Some(PathBuf::from(path)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(value)),
+        });
+    }
+
+    #[tokio::test]
+    async fn test_run_hook_passes_name_path_and_env() {
+        let fs = MockFileSystem::new();
+        expect_bool(
+            &fs,
+            FileSystemOperation::IsExecutable,
+            "/repo/.phantom/hooks/post-create",
+            true,
+        );
+        expect_bool(&fs, FileSystemOperation::IsDir, "/repo/.git/phantom/worktrees/feature", true);
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("/repo/.phantom/hooks/post-create")
+            .with_args(&["feature", "/repo/.git/phantom/worktrees/feature"])
+            .in_dir("/repo/.git/phantom/worktrees/feature")
+            .returns_output("", "", 2);
+
+        let code = run_hook(
+            mock.clone(),
+            &fs,
+            &MockEnvironment::new().with_var("PHANTOM_SURELY_UNSET_VARIABLE", "x"),
+            Path::new("/repo"),
+            Hook::PostCreate,
+            "feature",
+            Path::new("/repo/.git/phantom/worktrees/feature"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(code, Some(2));
+        let call = &mock.calls()[0];
+        assert!(call.stream_output);
+        let env = call.env.as_ref().unwrap();
+        assert_eq!(env.get("PHANTOM_WORKTREE"), Some("feature"));
+        assert_eq!(env.get("PHANTOM_WORKTREE_PATH"), Some("/repo/.git/phantom/worktrees/feature"));
+    }
+
+    #[tokio::test]
+    async fn test_run_hook_skips_missing_or_non_executable() {
+        let fs = MockFileSystem::new();
+        expect_bool(
+            &fs,
+            FileSystemOperation::IsExecutable,
+            "/repo/.phantom/hooks/pre-delete",
+            false,
+        );
+        let mock = MockCommandExecutor::new();
+        let env = MockEnvironment::new();
+
+        let worktree = Path::new("/repo/.git/phantom/worktrees/feature");
+        let repo = Path::new("/repo");
+        assert_eq!(
+            run_hook(mock.clone(), &fs, &env, repo, Hook::PreDelete, "feature", worktree)
+                .await
+                .unwrap(),
+            None
+        );
+        // No expectation at all behaves like a missing file
+        assert_eq!(
+            run_hook(mock.clone(), &fs, &env, repo, Hook::PostCreate, "feature", worktree)
+                .await
+                .unwrap(),
+            None
+        );
+        // An executable hook is not run for a worktree that is already gone
+        expect_bool(
+            &fs,
+            FileSystemOperation::IsExecutable,
+            "/repo/.phantom/hooks/pre-delete",
+            true,
+        );
+        assert_eq!(
+            run_hook(mock.clone(), &fs, &env, repo, Hook::PreDelete, "feature", worktree)
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(mock.calls().is_empty());
+    }
+}
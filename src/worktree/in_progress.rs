@@ -0,0 +1,221 @@
+use crate::core::filesystem::FileSystem;
+use crate::{PhantomError, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// A git operation left in progress inside a worktree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InProgressOperation {
+    Rebase,
+    Merge,
+    CherryPick,
+    Bisect,
+}
+
+impl InProgressOperation {
+    /// Marker shown next to the worktree in listings
+    pub const fn marker(self) -> &'static str {
+        match self {
+            Self::Rebase => "[rebasing]",
+            Self::Merge => "[merging]",
+            Self::CherryPick => "[cherry-picking]",
+            Self::Bisect => "[bisecting]",
+        }
+    }
+}
+
+impl fmt::Display for InProgressOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Rebase => "rebase",
+            Self::Merge => "merge",
+            Self::CherryPick => "cherry-pick",
+            Self::Bisect => "bisect",
+        };
+        f.write_str(name)
+    }
+}
+
+/// State files checked in the worktree's git dir, in priority order
+const STATE_FILES: [(&str, InProgressOperation); 5] = [
+    ("rebase-merge", InProgressOperation::Rebase),
+    ("rebase-apply", InProgressOperation::Rebase),
+    ("MERGE_HEAD", InProgressOperation::Merge),
+    ("CHERRY_PICK_HEAD", InProgressOperation::CherryPick),
+    ("BISECT_LOG", InProgressOperation::Bisect),
+];
+
+/// Resolve the private git dir of a worktree
+///
+/// For linked worktrees `.git` is a file pointing at `.git/worktrees/<name>`;
+/// for the main worktree it is the git dir itself.
+pub async fn resolve_worktree_git_dir(
+    worktree_path: &Path,
+    filesystem: &dyn FileSystem,
+) -> Result<PathBuf> {
+    let dot_git = worktree_path.join(".git");
+
+    if filesystem.is_dir(&dot_git).await? {
+        return Ok(dot_git);
+    }
+
+    let contents = filesystem.read_to_string(&dot_git).await?;
+    let git_dir =
+        contents.lines().find_map(|line| line.strip_prefix("gitdir:")).map(str::trim).ok_or_else(
+            || PhantomError::InvalidPath {
+                path: dot_git.to_string_lossy().to_string(),
+                reason: "missing gitdir entry".to_string(),
+            },
+        )?;
+
+    let git_dir = PathBuf::from(git_dir);
+    Ok(if git_dir.is_relative() { worktree_path.join(git_dir) } else { git_dir })
+}
+
+/// Detect a rebase, merge, cherry-pick or bisect left in progress in a worktree
+pub async fn detect_in_progress_operation(
+    worktree_path: &Path,
+    filesystem: &dyn FileSystem,
+) -> Result<Option<InProgressOperation>> {
+    let git_dir = resolve_worktree_git_dir(worktree_path, filesystem).await?;
+
+    for (file, operation) in STATE_FILES {
+        if filesystem.exists(&git_dir.join(file)).await? {
+            debug!("Found {} in {:?}: {} in progress", file, git_dir, operation);
+            return Ok(Some(operation));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+
+    const WORKTREE: &str = "/repo/.git/phantom/worktrees/feature";
+    const GIT_DIR: &str = "/repo/.git/worktrees/feature";
+
+    fn expect(fs: &MockFileSystem, operation: FileSystemOperation, path: &str, result: MockResult) {
+        fs.expect(FileSystemExpectation {
+            operation,
+            path: Some(PathBuf::from(path)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(result),
+        });
+    }
+
+    /// Mock a linked worktree whose git dir contains the given state files
+    fn mock_worktree(present: &[&str]) -> MockFileSystem {
+        let fs = MockFileSystem::new();
+        expect(
+            &fs,
+            FileSystemOperation::IsDir,
+            &format!("{WORKTREE}/.git"),
+            MockResult::Bool(false),
+        );
+        expect(
+            &fs,
+            FileSystemOperation::ReadToString,
+            &format!("{WORKTREE}/.git"),
+            MockResult::String(format!("gitdir: {GIT_DIR}\n")),
+        );
+        for (file, _) in STATE_FILES {
+            expect(
+                &fs,
+                FileSystemOperation::Exists,
+                &format!("{GIT_DIR}/{file}"),
+                MockResult::Bool(present.contains(&file)),
+            );
+        }
+        fs
+    }
+
+    async fn detect(present: &[&str]) -> Option<InProgressOperation> {
+        let fs = mock_worktree(present);
+        detect_in_progress_operation(Path::new(WORKTREE), &fs).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_detect_clean_worktree() {
+        assert_eq!(detect(&[]).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_detect_each_state_file() {
+        assert_eq!(detect(&["rebase-merge"]).await, Some(InProgressOperation::Rebase));
+        assert_eq!(detect(&["rebase-apply"]).await, Some(InProgressOperation::Rebase));
+        assert_eq!(detect(&["MERGE_HEAD"]).await, Some(InProgressOperation::Merge));
+        assert_eq!(detect(&["CHERRY_PICK_HEAD"]).await, Some(InProgressOperation::CherryPick));
+        assert_eq!(detect(&["BISECT_LOG"]).await, Some(InProgressOperation::Bisect));
+    }
+
+    #[tokio::test]
+    async fn test_detect_combinations_prefer_rebase() {
+        // A conflicted merge during a rebase is still reported as a rebase
+        assert_eq!(
+            detect(&["rebase-merge", "MERGE_HEAD"]).await,
+            Some(InProgressOperation::Rebase)
+        );
+        assert_eq!(
+            detect(&["MERGE_HEAD", "CHERRY_PICK_HEAD"]).await,
+            Some(InProgressOperation::Merge)
+        );
+        assert_eq!(
+            detect(&["CHERRY_PICK_HEAD", "BISECT_LOG"]).await,
+            Some(InProgressOperation::CherryPick)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_git_dir_main_worktree() {
+        let fs = MockFileSystem::new();
+        expect(&fs, FileSystemOperation::IsDir, "/repo/.git", MockResult::Bool(true));
+
+        let git_dir = resolve_worktree_git_dir(Path::new("/repo"), &fs).await.unwrap();
+        assert_eq!(git_dir, PathBuf::from("/repo/.git"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_git_dir_relative_gitdir() {
+        let fs = MockFileSystem::new();
+        expect(&fs, FileSystemOperation::IsDir, "/wt/.git", MockResult::Bool(false));
+        expect(
+            &fs,
+            FileSystemOperation::ReadToString,
+            "/wt/.git",
+            MockResult::String("gitdir: ../repo/.git/worktrees/wt\n".to_string()),
+        );
+
+        let git_dir = resolve_worktree_git_dir(Path::new("/wt"), &fs).await.unwrap();
+        assert_eq!(git_dir, PathBuf::from("/wt/../repo/.git/worktrees/wt"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_git_dir_malformed_file() {
+        let fs = MockFileSystem::new();
+        expect(&fs, FileSystemOperation::IsDir, "/wt/.git", MockResult::Bool(false));
+        expect(
+            &fs,
+            FileSystemOperation::ReadToString,
+            "/wt/.git",
+            MockResult::String("garbage".to_string()),
+        );
+
+        let result = resolve_worktree_git_dir(Path::new("/wt"), &fs).await;
+        assert!(matches!(result, Err(PhantomError::InvalidPath { .. })));
+    }
+
+    #[test]
+    fn test_operation_display_and_marker() {
+        assert_eq!(InProgressOperation::Rebase.to_string(), "rebase");
+        assert_eq!(InProgressOperation::CherryPick.to_string(), "cherry-pick");
+        assert_eq!(InProgressOperation::Rebase.marker(), "[rebasing]");
+        assert_eq!(InProgressOperation::Merge.marker(), "[merging]");
+    }
+}
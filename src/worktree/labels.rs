@@ -0,0 +1,140 @@
+use crate::core::filesystem::FileSystem;
+use crate::worktree::state::{read_worktree_metadata, write_worktree_metadata};
+use crate::{PhantomError, Result};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Longest label accepted by `phantom label`
+pub const MAX_LABEL_LENGTH: usize = 32;
+
+/// Check that a label is lowercase alphanumerics and dashes, at most 32 characters
+pub fn validate_label(label: &str) -> Result<()> {
+    let reason = if label.is_empty() {
+        "must not be empty".to_string()
+    } else if label.len() > MAX_LABEL_LENGTH {
+        format!("must be at most {MAX_LABEL_LENGTH} characters")
+    } else if !label.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-') {
+        "may only contain lowercase letters, digits and dashes".to_string()
+    } else {
+        return Ok(());
+    };
+    Err(PhantomError::ValidationFailed { reason: format!("label '{label}' {reason}") })
+}
+
+/// Which labels a worktree must carry to be included
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelFilter {
+    pub labels: Vec<String>,
+    /// Match worktrees with any of `labels` instead of all of them
+    pub any: bool,
+}
+
+impl LabelFilter {
+    /// Build a filter from `--label` values, validating each one
+    pub fn new(labels: Vec<String>, any: bool) -> Result<Self> {
+        for label in &labels {
+            validate_label(label)?;
+        }
+        Ok(Self { labels, any })
+    }
+
+    /// Whether the filter lets every worktree through
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Whether a worktree with `labels` passes the filter
+    pub fn matches(&self, labels: &BTreeSet<String>) -> bool {
+        if self.any {
+            self.is_empty() || self.labels.iter().any(|label| labels.contains(label))
+        } else {
+            self.labels.iter().all(|label| labels.contains(label))
+        }
+    }
+}
+
+/// Add a label to the named worktree; returns `false` if it was already set
+pub async fn add_label(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    name: &str,
+    label: &str,
+) -> Result<bool> {
+    validate_label(label)?;
+    let mut metadata = read_worktree_metadata(filesystem, git_root, name).await;
+    if !metadata.labels.insert(label.to_string()) {
+        return Ok(false);
+    }
+    write_worktree_metadata(filesystem, git_root, name, &metadata).await?;
+    Ok(true)
+}
+
+/// Remove a label from the named worktree; returns `false` if it was not set
+pub async fn remove_label(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    name: &str,
+    label: &str,
+) -> Result<bool> {
+    validate_label(label)?;
+    let mut metadata = read_worktree_metadata(filesystem, git_root, name).await;
+    if !metadata.labels.remove(label) {
+        return Ok(false);
+    }
+    write_worktree_metadata(filesystem, git_root, name, &metadata).await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(labels: &[&str]) -> BTreeSet<String> {
+        labels.iter().map(|label| label.to_string()).collect()
+    }
+
+    #[test]
+    fn test_validate_label() {
+        assert!(validate_label("review").is_ok());
+        assert!(validate_label("needs-rebase-2").is_ok());
+        assert!(validate_label(&"a".repeat(MAX_LABEL_LENGTH)).is_ok());
+
+        for (label, reason) in [
+            ("", "must not be empty"),
+            ("Review", "lowercase letters"),
+            ("spike_1", "lowercase letters"),
+            ("has space", "lowercase letters"),
+        ] {
+            let err = validate_label(label).unwrap_err().to_string();
+            assert!(err.contains(reason), "{label}: {err}");
+        }
+        let err = validate_label(&"a".repeat(MAX_LABEL_LENGTH + 1)).unwrap_err();
+        assert!(err.to_string().contains("at most 32 characters"));
+    }
+
+    #[test]
+    fn test_label_filter_all() {
+        let filter = LabelFilter::new(vec!["review".into(), "spike".into()], false).unwrap();
+        assert!(filter.matches(&set(&["review", "spike", "blocked"])));
+        assert!(!filter.matches(&set(&["review"])));
+        assert!(!filter.matches(&set(&[])));
+    }
+
+    #[test]
+    fn test_label_filter_any() {
+        let filter = LabelFilter::new(vec!["review".into(), "spike".into()], true).unwrap();
+        assert!(filter.matches(&set(&["spike"])));
+        assert!(!filter.matches(&set(&["blocked"])));
+    }
+
+    #[test]
+    fn test_empty_label_filter_matches_everything() {
+        assert!(LabelFilter::default().matches(&set(&[])));
+        assert!(LabelFilter { labels: vec![], any: true }.matches(&set(&["review"])));
+    }
+
+    #[test]
+    fn test_label_filter_rejects_invalid_label() {
+        assert!(LabelFilter::new(vec!["Bad".into()], false).is_err());
+    }
+}
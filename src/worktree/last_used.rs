@@ -0,0 +1,75 @@
+use crate::core::filesystem::FileSystem;
+use crate::worktree::const_validate::PHANTOM_STATE_DIR;
+use crate::worktree::paths::join_slash_separated;
+use crate::Result;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Name of the worktree most recently used by exec, shell, create or attach
+///
+/// Not a `.json` file, so it is never taken for worktree metadata.
+const LAST_WORKTREE_FILE: &str = "last_worktree";
+
+fn last_worktree_path(git_root: &Path) -> PathBuf {
+    join_slash_separated(git_root, &format!("{PHANTOM_STATE_DIR}/{LAST_WORKTREE_FILE}"))
+}
+
+/// Remember `name` as the last used worktree for `--last`
+///
+/// Best-effort: a failed write is only logged, never surfaced to the command.
+pub async fn record_last_worktree(filesystem: &dyn FileSystem, git_root: &Path, name: &str) {
+    if let Err(e) = write_last_worktree(filesystem, git_root, name).await {
+        debug!("Failed to record the last used worktree: {}", e);
+    }
+}
+
+async fn write_last_worktree(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    name: &str,
+) -> Result<()> {
+    let path = last_worktree_path(git_root);
+    if let Some(parent) = path.parent() {
+        filesystem.create_dir_all(parent).await?;
+    }
+    filesystem.write_atomic(&path, format!("{name}\n").as_bytes()).await
+}
+
+/// The worktree last recorded by `record_last_worktree`, if any
+pub async fn read_last_worktree(filesystem: &dyn FileSystem, git_root: &Path) -> Option<String> {
+    let contents = filesystem.read_to_string(&last_worktree_path(git_root)).await.ok()?;
+    let name = contents.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::filesystems::RealFileSystem;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_last_worktree_round_trip() {
+        let repo = TempDir::new().unwrap();
+        let fs = RealFileSystem::new();
+        assert_eq!(read_last_worktree(&fs, repo.path()).await, None);
+
+        record_last_worktree(&fs, repo.path(), "feature/login").await;
+        assert_eq!(read_last_worktree(&fs, repo.path()).await.as_deref(), Some("feature/login"));
+
+        record_last_worktree(&fs, repo.path(), "hotfix").await;
+        assert_eq!(read_last_worktree(&fs, repo.path()).await.as_deref(), Some("hotfix"));
+    }
+
+    #[tokio::test]
+    async fn test_record_last_worktree_ignores_write_failures() {
+        let repo = TempDir::new().unwrap();
+        // The state directory cannot be created where a file is in the way
+        std::fs::create_dir_all(repo.path().join(".git/phantom")).unwrap();
+        std::fs::write(repo.path().join(".git/phantom/state"), "").unwrap();
+        let fs = RealFileSystem::new();
+
+        record_last_worktree(&fs, repo.path(), "feature").await;
+        assert_eq!(read_last_worktree(&fs, repo.path()).await, None);
+    }
+}
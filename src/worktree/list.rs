@@ -1,12 +1,41 @@
 use crate::core::command_executor::CommandExecutor;
+use crate::core::filesystem::FileSystem;
+use crate::core::types::Worktree;
 use crate::git::git_executor_adapter::GitExecutor as GitExecutorAdapter;
 use crate::git::libs::list_worktrees::list_worktrees as git_list_worktrees;
 use crate::worktree::paths::get_phantom_directory;
+use crate::worktree::state::adopted_worktrees;
 use crate::Result;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use tracing::debug;
 
+/// Branch reported for a worktree checked out at a commit
+const DETACHED_HEAD: &str = "(detached HEAD)";
+
+/// Where a listed worktree came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorktreeOrigin {
+    /// Under the phantom directory, or adopted with a state file
+    #[default]
+    Phantom,
+    /// Added with `git worktree add` outside phantom
+    External,
+    /// The repository's main worktree
+    Main,
+}
+
+impl WorktreeOrigin {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WorktreeOrigin::Phantom => "phantom",
+            WorktreeOrigin::External => "external",
+            WorktreeOrigin::Main => "main",
+        }
+    }
+}
+
 /// Information about a worktree
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorktreeInfo {
@@ -14,6 +43,20 @@ pub struct WorktreeInfo {
     pub path: String,
     pub branch: Option<String>,
     pub is_clean: bool,
+    #[serde(default)]
+    pub is_locked: bool,
+    /// Reason given when the worktree was locked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked_reason: Option<String>,
+    /// Checked out at a commit rather than a branch
+    #[serde(default)]
+    pub is_detached: bool,
+    /// The branch's upstream no longer exists; only checked when `ListOptions` asks for it
+    #[serde(default)]
+    pub gone: bool,
+    /// Always `Phantom` unless `ListOptions::include_all` was set
+    #[serde(default)]
+    pub origin: WorktreeOrigin,
 }
 
 /// Result of listing worktrees
@@ -21,6 +64,44 @@ pub struct WorktreeInfo {
 pub struct ListWorktreesSuccess {
     pub worktrees: Vec<WorktreeInfo>,
     pub message: Option<String>,
+    /// Main worktree, set only when the repository has no commits yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unborn_main: Option<WorktreeInfo>,
+}
+
+/// Options for listing worktrees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListOptions {
+    /// Run `git status` in each worktree; when false every worktree is reported clean
+    pub include_status: bool,
+    /// Mark worktrees whose upstream git tracks as `[gone]`
+    pub check_gone: bool,
+    /// Also ask each remote which branches it still has; implies `check_gone`
+    pub check_remote: bool,
+    /// Also list the main worktree and those created outside phantom
+    pub include_all: bool,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self { include_status: true, check_gone: false, check_remote: false, include_all: false }
+    }
+}
+
+/// Describe the main worktree if its HEAD is unborn
+pub(crate) fn unborn_main_worktree(git_worktrees: &[Worktree]) -> Option<WorktreeInfo> {
+    // git always lists the main worktree first
+    git_worktrees.first().filter(|worktree| worktree.is_unborn()).map(|worktree| WorktreeInfo {
+        name: worktree.name.clone(),
+        path: worktree.path.to_string_lossy().to_string(),
+        branch: worktree.branch.clone(),
+        is_clean: true,
+        is_locked: worktree.is_locked,
+        locked_reason: worktree.locked_reason.clone(),
+        is_detached: worktree.is_detached,
+        gone: false,
+        origin: WorktreeOrigin::Main,
+    })
 }
 
 /// Get the current branch of a worktree with executor
@@ -32,7 +113,7 @@ where
     match git_executor.run(&["branch", "--show-current"]).await {
         Ok(output) => {
             let branch = output.trim().to_string();
-            Ok(if branch.is_empty() { "(detached HEAD)".to_string() } else { branch })
+            Ok(if branch.is_empty() { DETACHED_HEAD.to_string() } else { branch })
         }
         Err(_) => Ok("unknown".to_string()),
     }
@@ -50,6 +131,46 @@ where
     }
 }
 
+/// Names of the phantom worktrees found by reading the phantom directory
+///
+/// Avoids git entirely, for shell completion; a worktree is any directory
+/// holding a `.git` file, plus those adopted in place. Names are sorted.
+pub async fn worktree_names_from_directory(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut stack = vec![(get_phantom_directory(git_root), String::new())];
+
+    while let Some((dir, prefix)) = stack.pop() {
+        let Ok(entries) = filesystem.list_dir(&dir).await else {
+            continue;
+        };
+        for path in entries {
+            if !filesystem.is_dir(&path).await.unwrap_or(false) {
+                continue;
+            }
+            let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let name = if prefix.is_empty() { name } else { format!("{prefix}/{name}") };
+            if filesystem.is_file(&path.join(".git")).await.unwrap_or(false) {
+                names.push(name);
+            } else {
+                stack.push((path, name));
+            }
+        }
+    }
+
+    for (name, location) in adopted_worktrees(filesystem, git_root).await {
+        if filesystem.is_file(&location.join(".git")).await.unwrap_or(false) {
+            names.push(name);
+        }
+    }
+    names.sort();
+    names
+}
+
 /// Get detailed information about a worktree with executor
 pub async fn get_worktree_info<E>(executor: E, git_root: &Path, name: &str) -> Result<WorktreeInfo>
 where
@@ -62,75 +183,96 @@ where
         get_worktree_status(executor.clone(), &worktree_path)
     );
 
+    let branch = branch.unwrap_or_else(|_| "unknown".to_string());
     Ok(WorktreeInfo {
         name: name.to_string(),
         path: worktree_path.to_string_lossy().to_string(),
-        branch: Some(branch.unwrap_or_else(|_| "unknown".to_string())),
+        is_detached: branch == DETACHED_HEAD,
+        gone: false,
+        branch: Some(branch),
         is_clean: is_clean.unwrap_or(true),
+        is_locked: false,
+        locked_reason: None,
+        origin: WorktreeOrigin::Phantom,
     })
 }
 
 /// List all phantom worktrees with executor
-pub async fn list_worktrees<E>(executor: E, git_root: &Path) -> Result<ListWorktreesSuccess>
+pub async fn list_worktrees<E>(
+    executor: E,
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+) -> Result<ListWorktreesSuccess>
 where
     E: CommandExecutor + Clone + 'static,
 {
     debug!("Listing worktrees from git root: {:?}", git_root);
 
     let git_worktrees = git_list_worktrees(executor.clone(), git_root).await?;
-    let phantom_dir = get_phantom_directory(git_root);
-    // Canonicalize the phantom directory path for consistent comparison
-    let phantom_dir_canonical = phantom_dir.canonicalize().unwrap_or(phantom_dir.clone());
-    let phantom_dir_str = phantom_dir_canonical.to_string_lossy();
+    let unborn_main = unborn_main_worktree(&git_worktrees);
 
-    // Filter worktrees to only include those in the phantom directory
     let mut phantom_worktrees = Vec::new();
-    for worktree in git_worktrees {
-        // Canonicalize the worktree path for consistent comparison
-        let worktree_path_canonical = worktree.path.canonicalize().unwrap_or(worktree.path.clone());
-        if worktree_path_canonical.starts_with(&phantom_dir_canonical) {
-            // Extract the name from the canonical path
-            let canonical_path_str = worktree_path_canonical.to_string_lossy();
-            let name = if let Some(stripped) =
-                canonical_path_str.strip_prefix(&format!("{phantom_dir_str}/"))
-            {
-                stripped.to_string()
-            } else {
-                worktree.name.clone()
-            };
-
-            let is_clean =
-                get_worktree_status(executor.clone(), &worktree.path).await.unwrap_or(true);
-
-            phantom_worktrees.push(WorktreeInfo {
-                name,
-                path: worktree.path.to_string_lossy().to_string(),
-                branch: worktree.branch,
-                is_clean,
-            });
-        }
+    for (name, worktree) in
+        crate::worktree::concurrent::phantom_worktrees(filesystem, git_root, git_worktrees).await
+    {
+        let is_clean = get_worktree_status(executor.clone(), &worktree.path).await.unwrap_or(true);
+
+        phantom_worktrees.push(WorktreeInfo {
+            name,
+            path: worktree.path.to_string_lossy().to_string(),
+            branch: worktree.branch,
+            is_clean,
+            is_locked: worktree.is_locked,
+            locked_reason: worktree.locked_reason,
+            is_detached: worktree.is_detached,
+            gone: false,
+            origin: WorktreeOrigin::Phantom,
+        });
     }
 
     let message =
         if phantom_worktrees.is_empty() { Some("No worktrees found".to_string()) } else { None };
 
-    Ok(ListWorktreesSuccess { worktrees: phantom_worktrees, message })
+    Ok(ListWorktreesSuccess { worktrees: phantom_worktrees, message, unborn_main })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::core::executors::RealCommandExecutor;
+    use crate::core::filesystems::RealFileSystem;
     use crate::test_utils::TestRepo;
     use crate::worktree::create::create_worktree;
     use crate::worktree::types::CreateWorktreeOptions;
 
+    #[tokio::test]
+    async fn test_worktree_names_from_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let phantom_dir = get_phantom_directory(dir.path());
+        for name in ["beta", "feature/login", "alpha"] {
+            let path = phantom_dir.join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            std::fs::write(path.join(".git"), "gitdir: elsewhere\n").unwrap();
+        }
+        std::fs::create_dir_all(phantom_dir.join("leftover")).unwrap();
+
+        let fs = RealFileSystem::new();
+        assert_eq!(
+            worktree_names_from_directory(&fs, dir.path()).await,
+            vec!["alpha", "beta", "feature/login"]
+        );
+        assert!(worktree_names_from_directory(&fs, &dir.path().join("missing")).await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_list_empty_worktrees() {
         let repo = TestRepo::new().await.unwrap();
         repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
 
-        let result = list_worktrees(RealCommandExecutor::new(), repo.path()).await.unwrap();
+        let result =
+            list_worktrees(RealCommandExecutor::new(), &RealFileSystem::new(), repo.path())
+                .await
+                .unwrap();
         assert!(result.worktrees.is_empty());
         assert_eq!(result.message, Some("No worktrees found".to_string()));
     }
@@ -269,6 +411,11 @@ mod tests {
             path: "/path/to/test".to_string(),
             branch: Some("main".to_string()),
             is_clean: true,
+            is_locked: false,
+            locked_reason: None,
+            is_detached: false,
+            gone: false,
+            origin: WorktreeOrigin::Phantom,
         };
 
         // Test JSON serialization
@@ -293,15 +440,26 @@ mod tests {
                     path: "/path/to/feature1".to_string(),
                     branch: Some("feature1".to_string()),
                     is_clean: true,
+                    is_locked: false,
+                    locked_reason: None,
+                    is_detached: false,
+                    gone: false,
+                    origin: WorktreeOrigin::Phantom,
                 },
                 WorktreeInfo {
                     name: "feature2".to_string(),
                     path: "/path/to/feature2".to_string(),
                     branch: Some("feature2".to_string()),
                     is_clean: false,
+                    is_locked: false,
+                    locked_reason: None,
+                    is_detached: false,
+                    gone: false,
+                    origin: WorktreeOrigin::Phantom,
                 },
             ],
             message: None,
+            unborn_main: None,
         };
 
         let json = serde_json::to_string(&success).unwrap();
@@ -317,6 +475,7 @@ mod tests {
         let success = ListWorktreesSuccess {
             worktrees: vec![],
             message: Some("No worktrees found".to_string()),
+            unborn_main: None,
         };
 
         let json = serde_json::to_string(&success).unwrap();
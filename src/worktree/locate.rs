@@ -1,9 +1,15 @@
+use crate::core::command_executor::CommandExecutor;
 use crate::core::filesystem::FileSystem;
+use crate::core::utils::normalize_path;
+use crate::git::libs::get_main_worktree_root::get_main_worktree_root;
 use crate::worktree::validate::validate_worktree_exists;
-use crate::Result;
-use std::path::Path;
+use crate::{PhantomError, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tracing::debug;
 
 /// Result of where worktree operation
+#[derive(Debug, Clone, Serialize)]
 pub struct WhereWorktreeSuccess {
     pub path: String,
 }
@@ -19,6 +25,44 @@ pub async fn where_worktree(
     Ok(WhereWorktreeSuccess { path: validation.path.to_string_lossy().to_string() })
 }
 
+/// Directory new worktrees copy their files from: the phantom or directory `source` names,
+/// otherwise the main worktree, wherever phantom runs
+pub async fn resolve_copy_source<E>(
+    executor: E,
+    git_root: &Path,
+    source: Option<&str>,
+    filesystem: &dyn FileSystem,
+) -> Result<PathBuf>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let Some(source) = source else {
+        return match get_main_worktree_root(executor, git_root).await {
+            Ok(Some(main)) => Ok(main),
+            Ok(None) => Ok(git_root.to_path_buf()),
+            Err(e) => {
+                debug!(
+                    "Failed to find the main worktree, copying from {}: {e}",
+                    git_root.display()
+                );
+                Ok(git_root.to_path_buf())
+            }
+        };
+    };
+
+    // A phantom name wins over a directory of the same name
+    if let Ok(validation) = validate_worktree_exists(git_root, source, filesystem).await {
+        return Ok(validation.path);
+    }
+    let path = normalize_path(Path::new(source));
+    if filesystem.is_dir(&path).await.unwrap_or(false) {
+        return Ok(path);
+    }
+    Err(PhantomError::ValidationFailed {
+        reason: format!("'{source}' is neither a phantom nor a directory"),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,4 +100,38 @@ mod tests {
         let result = where_worktree(repo.path(), "non-existent", &filesystem).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_resolve_copy_source() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let repo = TestRepo::new().await.unwrap();
+        repo.create_file_and_commit("test.txt", "content", "Initial commit").await.unwrap();
+        create_worktree(RealCommandExecutor, repo.path(), "feature", Default::default())
+            .await
+            .unwrap();
+        let fs = RealFileSystem::new();
+        let resolve = |source: Option<&'static str>| {
+            resolve_copy_source(RealCommandExecutor, repo.path(), source, &fs)
+        };
+
+        let main = resolve(None).await.unwrap();
+        assert_eq!(main.canonicalize().unwrap(), repo.path().canonicalize().unwrap());
+        let feature = resolve(Some("feature")).await.unwrap();
+        assert!(feature.ends_with(".git/phantom/worktrees/feature"), "{}", feature.display());
+        let error = resolve(Some("no-such-phantom")).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Validation failed: 'no-such-phantom' is neither a phantom nor a directory"
+        );
+
+        let elsewhere = tempfile::tempdir().unwrap();
+        let source = elsewhere.path().to_str().unwrap();
+        let directory = resolve_copy_source(RealCommandExecutor, repo.path(), Some(source), &fs);
+        assert_eq!(directory.await.unwrap(), normalize_path(elsewhere.path()));
+
+        // Without git to ask, files come from the repository root
+        let root = resolve_copy_source(MockCommandExecutor::new(), repo.path(), None, &fs);
+        assert_eq!(root.await.unwrap(), repo.path());
+    }
 }
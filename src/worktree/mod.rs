@@ -1,15 +1,28 @@
+pub mod adopt;
 pub mod attach;
+pub mod branch_prefix;
 pub mod builder;
 pub mod concurrent;
 pub mod const_validate;
 pub mod create;
 pub mod delete;
+pub mod disk_usage;
 pub mod errors;
 pub mod file_copier;
+pub mod gc;
+pub mod glob;
+pub mod hooks;
+pub mod in_progress;
+pub mod labels;
+pub mod last_used;
 pub mod list;
 pub mod locate;
 pub mod paths;
+#[cfg(feature = "cli")]
 pub mod select;
+pub mod setup;
+pub mod snapshot;
+pub mod stack;
 pub mod state;
 pub mod types;
 pub mod validate;
@@ -1,14 +1,61 @@
 use crate::worktree::const_validate::DEFAULT_PHANTOM_DIR;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 /// Get the phantom directory path within the git repository
 pub fn get_phantom_directory(git_root: &Path) -> PathBuf {
-    git_root.join(DEFAULT_PHANTOM_DIR)
+    join_slash_separated(git_root, DEFAULT_PHANTOM_DIR)
 }
 
 /// Get the path for a specific worktree
 pub fn get_worktree_path(git_root: &Path, name: &str) -> PathBuf {
-    get_phantom_directory(git_root).join(name)
+    join_slash_separated(&get_phantom_directory(git_root), name)
+}
+
+/// Join a `/`-separated relative path onto `base` one component at a time
+///
+/// Joining the string as a whole would leave `/` inside Windows paths.
+pub fn join_slash_separated(base: &Path, relative: &str) -> PathBuf {
+    relative
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .fold(base.to_path_buf(), |path, part| path.join(part))
+}
+
+/// Express an absolute path relative to `base`
+///
+/// Returns `None` when the two paths do not share a filesystem root
+/// (e.g. different drives on Windows) or either path is relative.
+pub fn relative_path(path: &Path, base: &Path) -> Option<PathBuf> {
+    if !path.is_absolute() || !base.is_absolute() {
+        return None;
+    }
+
+    let path_components: Vec<Component> = path.components().collect();
+    let base_components: Vec<Component> = base.components().collect();
+
+    let is_root = |c: &Component| matches!(c, Component::Prefix(_) | Component::RootDir);
+    let path_root: Vec<_> = path_components.iter().take_while(|c| is_root(c)).collect();
+    let base_root: Vec<_> = base_components.iter().take_while(|c| is_root(c)).collect();
+    if path_root != base_root {
+        return None;
+    }
+
+    let common =
+        path_components.iter().zip(base_components.iter()).take_while(|(a, b)| a == b).count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..base_components.len() {
+        relative.push("..");
+    }
+    for component in &path_components[common..] {
+        relative.push(component);
+    }
+
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+
+    Some(relative)
 }
 
 #[cfg(test)]
@@ -32,6 +79,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_worktree_path_uses_native_separators() {
+        let git_root = Path::new("project");
+        let worktree_path = get_worktree_path(git_root, "feature/sub-feature");
+        let foreign = if cfg!(windows) { '/' } else { '\\' };
+
+        assert!(!worktree_path.to_string_lossy().contains(foreign));
+        let components: Vec<_> = worktree_path.components().map(|c| c.as_os_str()).collect();
+        assert_eq!(
+            components,
+            vec!["project", ".git", "phantom", "worktrees", "feature", "sub-feature"]
+        );
+    }
+
     #[test]
     fn test_get_worktree_path_with_slashes() {
         let git_root = Path::new("/home/user/project");
@@ -41,4 +102,32 @@ mod tests {
             PathBuf::from("/home/user/project/.git/phantom/worktrees/feature/sub-feature")
         );
     }
+
+    #[test]
+    fn test_relative_path_descendant() {
+        let relative =
+            relative_path(Path::new("/repo/.git/phantom/worktrees/feature"), Path::new("/repo"));
+        assert_eq!(relative, Some(PathBuf::from(".git/phantom/worktrees/feature")));
+    }
+
+    #[test]
+    fn test_relative_path_sibling() {
+        let relative = relative_path(
+            Path::new("/repo/.git/phantom/worktrees/feature"),
+            Path::new("/repo/.git/phantom/worktrees/other/src"),
+        );
+        assert_eq!(relative, Some(PathBuf::from("../../feature")));
+    }
+
+    #[test]
+    fn test_relative_path_same_directory() {
+        let relative = relative_path(Path::new("/repo"), Path::new("/repo"));
+        assert_eq!(relative, Some(PathBuf::from(".")));
+    }
+
+    #[test]
+    fn test_relative_path_requires_absolute_paths() {
+        assert_eq!(relative_path(Path::new("repo"), Path::new("/home")), None);
+        assert_eq!(relative_path(Path::new("/repo"), Path::new("home")), None);
+    }
 }
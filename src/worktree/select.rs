@@ -1,8 +1,14 @@
-use crate::core::command_executor::{CommandArgs, CommandExecutor};
-use crate::worktree::concurrent::list_worktrees_concurrent;
+use crate::config::types::PhantomConfig;
+use crate::core::command_executor::{CommandArgs, CommandExecutor, StdinMode};
+use crate::core::filesystem::FileSystem;
+use crate::git::libs::list_branches::{list_branches_with_dates, DatedBranch};
+use crate::git::libs::list_worktrees::list_worktrees;
+use crate::worktree::concurrent::list_worktrees_concurrent_with_options;
+use crate::worktree::list::{get_worktree_status, ListOptions};
 use crate::{PhantomError, Result};
 use smallvec::smallvec;
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
 /// Result of selecting a worktree
@@ -13,41 +19,90 @@ pub struct SelectWorktreeResult {
     pub is_clean: bool,
 }
 
+/// Result of selecting a branch to attach
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectBranchResult {
+    pub name: String,
+    /// Remote the branch only exists on; `None` for a local branch
+    pub remote: Option<String>,
+}
+
 /// Options for FZF selection
 #[derive(Debug, Clone, Default)]
 pub struct FzfOptions {
     pub prompt: Option<String>,
     pub header: Option<String>,
+    /// Preview command; `{name}` is replaced by the highlighted worktree's name
     pub preview_command: Option<String>,
+    /// Check every listed worktree's status to mark dirty ones; by default only the
+    /// selected worktree is checked
+    pub check_status: bool,
+    /// Leave out the built-in preview when no preview command is given
+    pub no_preview: bool,
+}
+
+/// A worktree offered for selection; `is_clean` is `None` until its status is checked
+struct Candidate {
+    name: String,
+    branch: Option<String>,
+    path: PathBuf,
+    is_clean: Option<bool>,
 }
 
+/// Preview used when no preview command is given, unless `no_preview` is set
+const WORKTREE_PREVIEW: &str = "phantom __preview {name}";
+
 /// Select a worktree interactively using fzf with CommandExecutor
+///
+/// The built-in preview is shown unless `config` turns off `fzf.preview`.
 pub async fn select_worktree_with_fzf<E>(
     executor: E,
+    filesystem: &dyn FileSystem,
     git_root: &Path,
+    config: &PhantomConfig,
 ) -> Result<Option<SelectWorktreeResult>>
 where
     E: CommandExecutor + Clone + 'static,
 {
-    select_worktree_with_fzf_and_options(executor, git_root, FzfOptions::default()).await
+    let options = FzfOptions { no_preview: !config.fzf_preview_enabled(), ..FzfOptions::default() };
+    select_worktree_with_fzf_and_options(executor, filesystem, git_root, options).await
 }
 
 /// Select a worktree interactively using fzf with custom options and CommandExecutor
 pub async fn select_worktree_with_fzf_and_options<E>(
     executor: E,
+    filesystem: &dyn FileSystem,
     git_root: &Path,
-    options: FzfOptions,
+    mut options: FzfOptions,
 ) -> Result<Option<SelectWorktreeResult>>
 where
     E: CommandExecutor + Clone + 'static,
 {
     info!("Selecting worktree with fzf");
 
-    // List all worktrees using concurrent operations
-    let list_result = list_worktrees_concurrent(executor.clone(), git_root).await?;
+    if options.preview_command.is_none() && !options.no_preview {
+        options.preview_command = Some(WORKTREE_PREVIEW.to_string());
+    }
 
-    // Filter to only phantom worktrees (concurrent list already does this)
-    let worktrees = list_result.worktrees;
+    // Without status checks this is a single `git worktree list`, so fzf opens right away
+    let list_options =
+        ListOptions { include_status: options.check_status, ..ListOptions::default() };
+    let worktrees: Vec<_> = list_worktrees_concurrent_with_options(
+        executor.clone(),
+        filesystem,
+        git_root,
+        list_options,
+    )
+    .await?
+    .worktrees
+    .into_iter()
+    .map(|wt| Candidate {
+        name: wt.name,
+        branch: wt.branch,
+        path: PathBuf::from(wt.path),
+        is_clean: options.check_status.then_some(wt.is_clean),
+    })
+    .collect();
 
     if worktrees.is_empty() {
         debug!("No phantom worktrees found");
@@ -59,7 +114,7 @@ where
         .iter()
         .map(|wt| {
             let branch_info = wt.branch.as_ref().map(|b| format!(" ({b})")).unwrap_or_default();
-            let status = if !wt.is_clean { " [dirty]" } else { "" };
+            let status = if wt.is_clean == Some(false) { " [dirty]" } else { "" };
             format!("{}{branch_info}{status}", wt.name)
         })
         .collect();
@@ -80,10 +135,17 @@ where
                     PhantomError::WorktreeNotFound { name: selected_name.to_string() }
                 })?;
 
+            let is_clean = match selected_worktree.is_clean {
+                Some(is_clean) => is_clean,
+                None => {
+                    get_worktree_status(executor, &selected_worktree.path).await.unwrap_or(true)
+                }
+            };
+
             Ok(Some(SelectWorktreeResult {
                 name: selected_worktree.name,
                 branch: selected_worktree.branch,
-                is_clean: selected_worktree.is_clean,
+                is_clean,
             }))
         }
         None => {
@@ -93,6 +155,81 @@ where
     }
 }
 
+/// Select a branch without a worktree interactively using fzf
+///
+/// With `remote`, branches that only exist on that remote are offered too, without the remote prefix.
+pub async fn select_branch_with_fzf<E>(
+    executor: E,
+    git_root: &Path,
+    remote: Option<&str>,
+) -> Result<Option<SelectBranchResult>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    info!("Selecting branch with fzf");
+
+    let branches = list_branches_with_dates(executor.clone(), git_root, remote).await?;
+    let checked_out: HashSet<String> = list_worktrees(executor.clone(), git_root)
+        .await?
+        .into_iter()
+        .filter_map(|worktree| worktree.branch)
+        .collect();
+    let branches = attachable_branches(branches, &checked_out);
+
+    if branches.is_empty() {
+        debug!("No branches without a worktree");
+        return Ok(None);
+    }
+
+    let items: Vec<String> = branches
+        .iter()
+        .map(|branch| match &branch.remote {
+            Some(remote) => format!("{} ({}, {remote})", branch.name, branch.date),
+            None => format!("{} ({})", branch.name, branch.date),
+        })
+        .collect();
+    let options = FzfOptions {
+        prompt: Some("Select branch> ".to_string()),
+        header: Some("Branches without a worktree".to_string()),
+        ..Default::default()
+    };
+
+    let Some(selection) = run_fzf(&executor, &items, options).await? else {
+        debug!("No branch selected");
+        return Ok(None);
+    };
+    let selected_name = selection.split(' ').next().unwrap_or_default();
+    branches
+        .into_iter()
+        .find(|branch| branch.name == selected_name)
+        .map(|branch| Some(SelectBranchResult { name: branch.name, remote: branch.remote }))
+        .ok_or_else(|| PhantomError::BranchNotFound { branch: selected_name.to_string() })
+}
+
+/// Drop branches checked out in a worktree, and remote branches that also exist locally
+fn attachable_branches(
+    branches: Vec<DatedBranch>,
+    checked_out: &HashSet<String>,
+) -> Vec<DatedBranch> {
+    let local: HashSet<String> = branches
+        .iter()
+        .filter(|branch| branch.remote.is_none())
+        .map(|branch| branch.name.clone())
+        .collect();
+    branches
+        .into_iter()
+        .filter(|branch| !checked_out.contains(&branch.name))
+        .filter(|branch| branch.remote.is_none() || !local.contains(&branch.name))
+        .collect()
+}
+
+/// Turn a preview template into an fzf preview command
+///
+/// fzf splits each line on whitespace, so the worktree name is field `{1}`.
+fn preview_from_template(template: &str) -> String {
+    template.replace("{name}", "{1}")
+}
+
 /// Run fzf with the given items and options using CommandExecutor
 async fn run_fzf<E>(executor: &E, items: &[String], options: FzfOptions) -> Result<Option<String>>
 where
@@ -100,7 +237,10 @@ where
 {
     // Check if fzf is available
     if !is_fzf_available(executor).await {
-        return Err(PhantomError::CommandNotFound { command: "fzf".to_string() });
+        return Err(PhantomError::CommandNotFound {
+            command: "fzf".to_string(),
+            suggestions: Vec::new(),
+        });
     }
 
     let mut args: CommandArgs = smallvec![];
@@ -124,7 +264,7 @@ where
 
     if let Some(preview) = options.preview_command {
         args.push("--preview".to_string());
-        args.push(preview);
+        args.push(preview_from_template(&preview));
     }
 
     // Join items with newlines for stdin
@@ -133,9 +273,10 @@ where
     // Execute fzf with stdin data
     let config = crate::core::command_executor::CommandConfig::new("fzf")
         .with_args_smallvec(args)
-        .with_stdin_data(stdin_data);
+        .with_stdin(StdinMode::Data(stdin_data));
 
-    match executor.execute(config).await {
+    // fzf owns the terminal, so it runs in its own process group that follows phantom's job control
+    match executor.execute_interactive(config).await {
         Ok(output) => {
             match output.exit_code {
                 0 => {
@@ -160,7 +301,10 @@ where
         Err(e) => {
             if e.to_string().contains("command not found") || e.to_string().contains("No such file")
             {
-                Err(PhantomError::CommandNotFound { command: "fzf".to_string() })
+                Err(PhantomError::CommandNotFound {
+                    command: "fzf".to_string(),
+                    suggestions: Vec::new(),
+                })
             } else {
                 Err(e)
             }
@@ -185,6 +329,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::filesystems::MockFileSystem;
     use crate::core::types::Worktree;
     use std::path::PathBuf;
 
@@ -205,7 +350,9 @@ mod tests {
             is_bare: false,
             is_detached: false,
             is_locked: false,
+            locked_reason: None,
             is_prunable: false,
+            prunable_reason: None,
         };
 
         let is_clean = true;
@@ -229,7 +376,9 @@ mod tests {
             is_bare: false,
             is_detached: false,
             is_locked: false,
+            locked_reason: None,
             is_prunable: false,
+            prunable_reason: None,
         };
 
         let is_clean = false;
@@ -243,6 +392,74 @@ mod tests {
         assert_eq!(formatted, "feature (feature-branch) [dirty]");
     }
 
+    fn dated(name: &str, remote: Option<&str>) -> DatedBranch {
+        DatedBranch {
+            name: name.to_string(),
+            remote: remote.map(str::to_string),
+            date: "2026-01-02".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_attachable_branches() {
+        let branches = vec![
+            dated("main", None),
+            dated("feature", None),
+            dated("review", None),
+            dated("main", Some("origin")),
+            dated("review", Some("origin")),
+            dated("hotfix", Some("origin")),
+        ];
+        let checked_out = HashSet::from(["main".to_string(), "feature".to_string()]);
+
+        let attachable = attachable_branches(branches, &checked_out);
+
+        assert_eq!(attachable, [dated("review", None), dated("hotfix", Some("origin"))]);
+    }
+
+    #[tokio::test]
+    async fn test_select_branch_with_fzf() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&[
+                "for-each-ref",
+                "--format=%(refname)%09%(committerdate:short)",
+                "refs/heads/",
+                "refs/remotes/origin/",
+            ])
+            .returns_output(
+                "refs/heads/main\t2026-01-03\n\
+                 refs/heads/review\t2026-01-02\n\
+                 refs/remotes/origin/hotfix\t2026-01-01\n",
+                "",
+                0,
+            );
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output("worktree /repo\0HEAD abc\0branch refs/heads/main\0\0", "", 0);
+        mock.expect_command("fzf").with_args(&["--version"]).returns_success();
+        mock.expect_command("fzf")
+            .with_args(&["--prompt", "Select branch> ", "--header", "Branches without a worktree"])
+            .with_stdin(StdinMode::Data(
+                "review (2026-01-02)\nhotfix (2026-01-01, origin)".to_string(),
+            ))
+            .returns_output("hotfix (2026-01-01, origin)\n", "", 0);
+
+        let selected =
+            select_branch_with_fzf(mock.clone(), Path::new("/repo"), Some("origin")).await.unwrap();
+
+        assert_eq!(
+            selected,
+            Some(SelectBranchResult {
+                name: "hotfix".to_string(),
+                remote: Some("origin".to_string())
+            })
+        );
+        mock.verify().unwrap();
+    }
+
     #[test]
     fn test_select_worktree_result() {
         let result = SelectWorktreeResult {
@@ -286,6 +503,7 @@ mod tests {
         assert!(options.prompt.is_none());
         assert!(options.header.is_none());
         assert!(options.preview_command.is_none());
+        assert!(!options.check_status);
     }
 
     #[test]
@@ -294,6 +512,8 @@ mod tests {
             prompt: Some("Select:".to_string()),
             header: Some("Worktrees".to_string()),
             preview_command: Some("echo {}".to_string()),
+            check_status: true,
+            no_preview: false,
         };
 
         assert_eq!(options.prompt, Some("Select:".to_string()));
@@ -311,7 +531,9 @@ mod tests {
             is_bare: false,
             is_detached: true,
             is_locked: false,
+            locked_reason: None,
             is_prunable: false,
+            prunable_reason: None,
         };
 
         let is_clean = true;
@@ -331,6 +553,8 @@ mod tests {
             prompt: Some("test".to_string()),
             header: None,
             preview_command: Some("preview".to_string()),
+            check_status: true,
+            no_preview: false,
         };
 
         let debug_str = format!("{options:?}");
@@ -345,6 +569,8 @@ mod tests {
             prompt: Some("Select>".to_string()),
             header: Some("Header".to_string()),
             preview_command: Some("cat {}".to_string()),
+            check_status: true,
+            no_preview: false,
         };
 
         let cloned = options.clone();
@@ -384,7 +610,9 @@ mod tests {
                 is_bare: false,
                 is_detached: branch.is_none(),
                 is_locked: false,
+                locked_reason: None,
                 is_prunable: false,
+                prunable_reason: None,
             };
 
             let formatted = format!(
@@ -423,7 +651,8 @@ mod tests {
         let _options = FzfOptions::default();
 
         // Verify the error message format
-        let error = PhantomError::CommandNotFound { command: "fzf".to_string() };
+        let error =
+            PhantomError::CommandNotFound { command: "fzf".to_string(), suggestions: Vec::new() };
         assert!(error.to_string().contains("Command 'fzf' not found"));
     }
 
@@ -500,11 +729,11 @@ mod tests {
 
         // Mock git worktree list - only main worktree exists
         mock.expect_command("git")
-            .with_args(&["worktree", "list", "--porcelain"])
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
             .in_dir(repo.path().to_path_buf())
             .returns_output(
                 &format!(
-                    "worktree {}\nHEAD abc123\nbranch refs/heads/main\n",
+                    "worktree {}\0HEAD abc123\0branch refs/heads/main\0",
                     repo.path().display()
                 ),
                 "",
@@ -512,7 +741,13 @@ mod tests {
             );
 
         // Should return None when only main worktree exists
-        let result = select_worktree_with_fzf(mock, repo.path()).await;
+        let result = select_worktree_with_fzf(
+            mock,
+            &MockFileSystem::new(),
+            repo.path(),
+            &PhantomConfig::default(),
+        )
+        .await;
         match result {
             Ok(None) => {} // Expected - no worktrees to select
             Ok(Some(_)) => panic!("Should not select a worktree when none exist"),
@@ -541,11 +776,11 @@ mod tests {
 
         // Mock git worktree list - show main and feature-1
         mock.expect_command("git")
-            .with_args(&["worktree", "list", "--porcelain"])
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
             .in_dir(repo.path().to_path_buf())
             .returns_output(
                 &format!(
-                    "worktree {}\nHEAD abc123\nbranch refs/heads/main\n\nworktree {}\nHEAD def456\nbranch refs/heads/feature-1\n",
+                    "worktree {}\0HEAD abc123\0branch refs/heads/main\0\0worktree {}\0HEAD def456\0branch refs/heads/feature-1\0",
                     repo.path().display(),
                     repo.path().join(".git/phantom/worktrees").join("feature-1").display()
                 ),
@@ -578,21 +813,34 @@ mod tests {
                 "--preview",
                 "echo preview",
             ])
-            .with_stdin_data("feature-1 (feature-1)")
+            .with_stdin(StdinMode::Data("feature-1 (feature-1)".to_string()))
             .returns_output("feature-1 (feature-1)\n", "", 0);
 
         let options = FzfOptions {
             prompt: Some("Custom prompt> ".to_string()),
             header: Some("Custom header".to_string()),
             preview_command: Some("echo preview".to_string()),
+            check_status: true,
+            no_preview: false,
         };
 
         // Test with custom options
-        let result = select_worktree_with_fzf_and_options(mock, repo.path(), options).await;
+        let result = select_worktree_with_fzf_and_options(
+            mock.clone(),
+            &MockFileSystem::new(),
+            repo.path(),
+            options,
+        )
+        .await;
         assert!(result.is_ok());
         let selected = result.unwrap();
         assert!(selected.is_some());
         assert_eq!(selected.unwrap().name, "feature-1");
+
+        // Only the picker itself takes over the terminal
+        let fzf_calls: Vec<_> = mock.calls().into_iter().filter(|c| c.program == "fzf").collect();
+        assert!(!fzf_calls[0].interactive);
+        assert!(fzf_calls[1].interactive);
     }
 
     #[test]
@@ -624,7 +872,7 @@ mod tests {
 
     #[test]
     fn test_worktree_position_finding() {
-        let worktrees = vec![
+        let worktrees = [
             Worktree {
                 name: "first".to_string(),
                 path: PathBuf::from("/first"),
@@ -633,7 +881,9 @@ mod tests {
                 is_bare: false,
                 is_detached: false,
                 is_locked: false,
+                locked_reason: None,
                 is_prunable: false,
+                prunable_reason: None,
             },
             Worktree {
                 name: "second".to_string(),
@@ -643,7 +893,9 @@ mod tests {
                 is_bare: false,
                 is_detached: false,
                 is_locked: false,
+                locked_reason: None,
                 is_prunable: false,
+                prunable_reason: None,
             },
         ];
 
@@ -680,18 +932,24 @@ mod tests {
 
         // Mock git worktree list - only main worktree exists
         mock.expect_command("git")
-            .with_args(&["worktree", "list", "--porcelain"])
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
             .in_dir(repo.path().to_path_buf())
             .returns_output(
                 &format!(
-                    "worktree {}\nHEAD abc123\nbranch refs/heads/main\n",
+                    "worktree {}\0HEAD abc123\0branch refs/heads/main\0",
                     repo.path().display()
                 ),
                 "",
                 0,
             );
 
-        let result = select_worktree_with_fzf(mock, repo.path()).await;
+        let result = select_worktree_with_fzf(
+            mock,
+            &MockFileSystem::new(),
+            repo.path(),
+            &PhantomConfig::default(),
+        )
+        .await;
         assert!(result.is_ok());
         assert!(result.unwrap().is_none());
     }
@@ -722,14 +980,14 @@ mod tests {
 
         // Mock git worktree list
         mock.expect_command("git")
-            .with_args(&["worktree", "list", "--porcelain"])
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
             .in_dir(repo.path().to_path_buf())
             .returns_output(
                 &format!(
-                    "worktree {}\nHEAD abc123\nbranch refs/heads/main\n\n\
-                     worktree {}\nHEAD def456\nbranch refs/heads/feature-1\n\n\
-                     worktree {}\nHEAD ghi789\nbranch refs/heads/feature-2\n\n\
-                     worktree {}\nHEAD jkl012\nbranch refs/heads/bugfix-1\n",
+                    "worktree {}\0HEAD abc123\0branch refs/heads/main\0\0\
+                     worktree {}\0HEAD def456\0branch refs/heads/feature-1\0\0\
+                     worktree {}\0HEAD ghi789\0branch refs/heads/feature-2\0\0\
+                     worktree {}\0HEAD jkl012\0branch refs/heads/bugfix-1\0",
                     repo.path().display(),
                     repo.path().join(".git/phantom/worktrees").join("feature-1").display(),
                     repo.path().join(".git/phantom/worktrees").join("feature-2").display(),
@@ -739,39 +997,38 @@ mod tests {
                 0,
             );
 
-        // Mock git status for each worktree
-        mock.expect_command("git")
-            .with_args(&["status", "--porcelain"])
-            .in_dir(repo.path().to_path_buf())
-            .returns_output("", "", 0);
-
-        mock.expect_command("git")
-            .with_args(&["status", "--porcelain"])
-            .in_dir(repo.path().join(".git/phantom/worktrees").join("feature-1"))
-            .returns_output("", "", 0);
-
+        // Only the selected worktree's status is checked
         mock.expect_command("git")
             .with_args(&["status", "--porcelain"])
             .in_dir(repo.path().join(".git/phantom/worktrees").join("feature-2"))
             .returns_output("M file.txt\n", "", 0); // dirty
 
-        mock.expect_command("git")
-            .with_args(&["status", "--porcelain"])
-            .in_dir(repo.path().join(".git/phantom/worktrees").join("bugfix-1"))
-            .returns_output("", "", 0);
-
         // Mock fzf availability check
         mock.expect_command("fzf").with_args(&["--version"]).returns_output("0.42.0", "", 0);
 
         // Mock fzf selection - user selects feature-2
         mock.expect_command("fzf")
-            .with_args(&["--prompt", "Select worktree> ", "--header", "Git Worktrees"])
-            .with_stdin_data(
-                "feature-1 (feature-1)\nfeature-2 (feature-2) [dirty]\nbugfix-1 (bugfix-1)",
-            )
-            .returns_output("feature-2 (feature-2) [dirty]\n", "", 0);
-
-        let result = select_worktree_with_fzf(mock, repo.path()).await;
+            .with_args(&[
+                "--prompt",
+                "Select worktree> ",
+                "--header",
+                "Git Worktrees",
+                "--preview",
+                "phantom __preview {1}",
+            ])
+            // Statuses are not checked while listing, so nothing is marked dirty
+            .with_stdin(StdinMode::Data(
+                "bugfix-1 (bugfix-1)\nfeature-1 (feature-1)\nfeature-2 (feature-2)".to_string(),
+            ))
+            .returns_output("feature-2 (feature-2)\n", "", 0);
+
+        let result = select_worktree_with_fzf(
+            mock,
+            &MockFileSystem::new(),
+            repo.path(),
+            &PhantomConfig::default(),
+        )
+        .await;
         assert!(result.is_ok());
 
         let selected = result.unwrap();
@@ -782,4 +1039,96 @@ mod tests {
         assert_eq!(selected_worktree.branch, Some("feature-2".to_string()));
         assert!(!selected_worktree.is_clean); // Should be dirty
     }
+
+    #[test]
+    fn test_preview_from_template() {
+        assert_eq!(preview_from_template("phantom show {name}"), "phantom show {1}");
+        assert_eq!(preview_from_template("cat {}"), "cat {}");
+    }
+
+    #[tokio::test]
+    async fn test_select_worktree_checks_only_selection_by_default() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .in_dir("/repo")
+            .returns_output(
+                "worktree /repo\0HEAD abc123\0branch refs/heads/main\0\0\
+                 worktree /repo/.git/phantom/worktrees/feature-1\0HEAD def456\0branch refs/heads/feature-1\0\0\
+                 worktree /repo/.git/phantom/worktrees/feature-2\0HEAD ghi789\0branch refs/heads/feature-2\0",
+                "",
+                0,
+            );
+        mock.expect_command("fzf").with_args(&["--version"]).returns_output("0.42.0", "", 0);
+        mock.expect_command("fzf")
+            .with_args(&[
+                "--prompt",
+                "Select worktree> ",
+                "--header",
+                "Git Worktrees",
+                "--preview",
+                "phantom __preview {1}",
+            ])
+            .with_stdin(StdinMode::Data("feature-1 (feature-1)\nfeature-2 (feature-2)".to_string()))
+            .returns_output("feature-2 (feature-2)\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["status", "--porcelain"])
+            .in_dir("/repo/.git/phantom/worktrees/feature-2")
+            .times(1)
+            .returns_output(" M file.txt\n", "", 0);
+
+        let options = FzfOptions::default();
+        let selected = select_worktree_with_fzf_and_options(
+            mock.clone(),
+            &MockFileSystem::new(),
+            Path::new("/repo"),
+            options,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(selected.name, "feature-2");
+        assert!(!selected.is_clean);
+        let status_calls = mock
+            .calls()
+            .into_iter()
+            .filter(|call| call.args.first().is_some_and(|arg| arg == "status"))
+            .count();
+        assert_eq!(status_calls, 1);
+    }
+
+    #[tokio::test]
+    async fn test_preview_follows_config() {
+        use crate::core::executors::MockCommandExecutor;
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["worktree", "list", "--porcelain", "-z"])
+            .returns_output(
+                "worktree /repo\0HEAD abc\0branch refs/heads/main\0\0\
+                 worktree /repo/.git/phantom/worktrees/feature\0HEAD def\0branch refs/heads/feature\0",
+                "",
+                0,
+            );
+        mock.expect_command("fzf").with_args(&["--version"]).returns_output("0.42.0", "", 0);
+        mock.expect_command("fzf")
+            .with_args(&["--prompt", "Select worktree> ", "--header", "Git Worktrees"])
+            .returns_output("", "", 130);
+        let config: PhantomConfig = serde_json::from_str(r#"{"fzf": {"preview": false}}"#).unwrap();
+
+        let selected = select_worktree_with_fzf(
+            mock.clone(),
+            &MockFileSystem::new(),
+            Path::new("/repo"),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        assert!(selected.is_none());
+        mock.verify().unwrap();
+    }
 }
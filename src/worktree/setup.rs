@@ -0,0 +1,173 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::git::git_executor_adapter::GitExecutor;
+use crate::worktree::errors::WorktreeError;
+use crate::worktree::file_copier::copy_files_concurrent;
+use crate::Result;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tracing::debug;
+
+/// Copy editor directories such as `.vscode` from the original worktree
+///
+/// Directories missing from `source_dir` are skipped. Returns the copied files.
+pub async fn copy_editor_dirs<E>(
+    executor: E,
+    source_dir: &Path,
+    target_dir: &Path,
+    dirs: &[String],
+) -> Result<Vec<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let patterns = editor_dir_patterns(source_dir, dirs).await;
+    if patterns.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(copy_files_concurrent(executor, source_dir, target_dir, &patterns).await?.copied_files)
+}
+
+/// copyFiles patterns matching everything in the editor directories present in `source_dir`
+pub async fn editor_dir_patterns(source_dir: &Path, dirs: &[String]) -> Vec<String> {
+    let mut patterns = Vec::new();
+    for dir in dirs {
+        if fs::metadata(source_dir.join(dir)).await.is_ok_and(|metadata| metadata.is_dir()) {
+            patterns.push(format!("{dir}/**/*"));
+        } else {
+            debug!("Editor directory '{dir}' not found, skipping");
+        }
+    }
+    patterns
+}
+
+/// Create `dir` in the worktree and exclude it from `git status`
+///
+/// Git only reads `info/exclude` from the common git directory, so the entry
+/// is anchored to the worktree root and shared by every checkout.
+pub async fn setup_scratch_dir<E>(executor: E, worktree_path: &Path, dir: &str) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let dir = dir.trim_end_matches('/');
+    fs::create_dir_all(worktree_path.join(dir)).await.map_err(|e| {
+        WorktreeError::FileOperation(format!("Failed to create scratch directory '{dir}': {e}"))
+    })?;
+
+    let exclude_path = exclude_file(executor, worktree_path).await?;
+    append_exclude(&exclude_path, &format!("/{dir}/")).await?;
+    Ok(())
+}
+
+/// Path of the exclude file git reads for `worktree_path`
+async fn exclude_file<E>(executor: E, worktree_path: &Path) -> Result<PathBuf>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(worktree_path);
+    let path = PathBuf::from(git_executor.run(&["rev-parse", "--git-path", "info/exclude"]).await?);
+    Ok(if path.is_absolute() { path } else { worktree_path.join(path) })
+}
+
+/// Append `entry` to an exclude file unless it is already listed
+///
+/// Returns whether the file changed.
+pub async fn append_exclude(path: &Path, entry: &str) -> Result<bool> {
+    let existing = match fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(WorktreeError::FileOperation(format!(
+                "Failed to read '{}': {e}",
+                path.display()
+            ))
+            .into())
+        }
+    };
+
+    if existing.lines().any(|line| line.trim() == entry) {
+        return Ok(false);
+    }
+
+    let mut contents = existing;
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(entry);
+    contents.push('\n');
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await.map_err(|e| {
+            WorktreeError::FileOperation(format!("Failed to create '{}': {e}", parent.display()))
+        })?;
+    }
+    fs::write(path, contents).await.map_err(|e| {
+        WorktreeError::FileOperation(format!("Failed to write '{}': {e}", path.display()))
+    })?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_append_exclude_is_idempotent() {
+        let dir = TempDir::new().unwrap();
+        let exclude = dir.path().join("info/exclude");
+        fs::create_dir_all(exclude.parent().unwrap()).await.unwrap();
+        fs::write(&exclude, "# git ls-files --others --exclude-from=.git/info/exclude\n*.log")
+            .await
+            .unwrap();
+
+        assert!(append_exclude(&exclude, "/.scratch/").await.unwrap());
+        assert!(!append_exclude(&exclude, "/.scratch/").await.unwrap());
+
+        let contents = fs::read_to_string(&exclude).await.unwrap();
+        assert!(contents.ends_with("*.log\n/.scratch/\n"));
+        assert_eq!(contents.matches("/.scratch/").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_setup_scratch_dir_twice() {
+        let dir = TempDir::new().unwrap();
+        let worktree = dir.path().join("worktree");
+        let exclude = dir.path().join("git/info/exclude");
+        fs::create_dir_all(&worktree).await.unwrap();
+
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["rev-parse", "--git-path", "info/exclude"])
+            .in_dir(&worktree)
+            .times(2)
+            .returns_output(&format!("{}\n", exclude.display()), "", 0);
+
+        setup_scratch_dir(mock.clone(), &worktree, ".scratch").await.unwrap();
+        setup_scratch_dir(mock, &worktree, ".scratch/").await.unwrap();
+
+        assert!(worktree.join(".scratch").is_dir());
+        assert_eq!(fs::read_to_string(&exclude).await.unwrap(), "/.scratch/\n");
+    }
+
+    #[tokio::test]
+    async fn test_copy_editor_dirs_skips_missing() {
+        let source = TempDir::new().unwrap();
+        let target = TempDir::new().unwrap();
+        fs::create_dir_all(source.path().join(".vscode")).await.unwrap();
+        fs::write(source.path().join(".vscode/settings.json"), "{}").await.unwrap();
+
+        let copied = copy_editor_dirs(
+            MockCommandExecutor::new(),
+            source.path(),
+            target.path(),
+            &[".vscode".to_string(), ".idea".to_string()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(copied, vec![".vscode/settings.json"]);
+        assert!(target.path().join(".vscode/settings.json").exists());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::config::types::DeleteSnapshot;
+use crate::core::command_executor::CommandExecutor;
+use crate::core::filesystem::FileSystem;
+use crate::git::git_executor_adapter::GitExecutor;
+use crate::worktree::const_validate::PHANTOM_TRASH_DIR;
+use crate::worktree::paths::join_slash_separated;
+use crate::Result;
+use std::path::Path;
+use tracing::info;
+
+/// Save the uncommitted changes of the worktree `name` before a forced delete
+///
+/// Returns where the changes went, or `None` when `mode` is [`DeleteSnapshot::None`].
+/// `timestamp` (seconds since the epoch) keeps repeated snapshots of one name apart.
+pub async fn snapshot_worktree<E>(
+    executor: E,
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    name: &str,
+    worktree_path: &Path,
+    mode: DeleteSnapshot,
+    timestamp: u64,
+) -> Result<Option<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git = GitExecutor::new(executor).with_cwd(worktree_path);
+    match mode {
+        DeleteSnapshot::None => Ok(None),
+        DeleteSnapshot::Stash => {
+            // Stashes live in the shared object store, so they outlive the worktree
+            let label = format!("phantom-delete {name} {timestamp}");
+            git.run(&["stash", "push", "--include-untracked", "-m", &label]).await?;
+            info!("Stashed uncommitted changes of '{name}' as '{label}'");
+            Ok(Some(format!("stash '{label}'")))
+        }
+        DeleteSnapshot::Patch => {
+            let mut patch = git.run_untrimmed(&["diff", "HEAD", "--binary"]).await?;
+            let untracked =
+                git.run_untrimmed(&["ls-files", "--others", "--exclude-standard", "-z"]).await?;
+            for file in untracked.split('\0').filter(|file| !file.is_empty()) {
+                let contents = filesystem.read_to_string(&worktree_path.join(file)).await?;
+                patch.push_str(&new_file_patch(file, &contents));
+            }
+
+            let path = join_slash_separated(git_root, PHANTOM_TRASH_DIR)
+                .join(format!("{name}-{timestamp}.patch"));
+            if let Some(parent) = path.parent() {
+                filesystem.create_dir_all(parent).await?;
+            }
+            filesystem.write(&path, &patch).await?;
+            info!("Saved uncommitted changes of '{name}' to {}", path.display());
+            Ok(Some(path.display().to_string()))
+        }
+    }
+}
+
+/// A `git apply`-able diff that adds the untracked file `file`
+fn new_file_patch(file: &str, contents: &str) -> String {
+    let mut patch = format!("diff --git a/{file} b/{file}\nnew file mode 100644\n");
+    if contents.is_empty() {
+        return patch;
+    }
+
+    let lines: Vec<&str> = contents.lines().collect();
+    patch.push_str(&format!("--- /dev/null\n+++ b/{file}\n@@ -0,0 +1,{} @@\n", lines.len()));
+    for line in lines {
+        patch.push('+');
+        patch.push_str(line);
+        patch.push('\n');
+    }
+    if !contents.ends_with('\n') {
+        patch.push_str("\\ No newline at end of file\n");
+    }
+    patch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+    use std::path::PathBuf;
+
+    const WORKTREE: &str = "/repo/.git/phantom/worktrees/feature";
+
+    fn expect(
+        fs: &MockFileSystem,
+        operation: FileSystemOperation,
+        path: &str,
+        contents: Option<&str>,
+        result: MockResult,
+    ) {
+        fs.expect(FileSystemExpectation {
+            operation,
+            path: Some(PathBuf::from(path)),
+            from_path: None,
+            to_path: None,
+            contents: contents.map(str::to_string),
+            result: Ok(result),
+        });
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_stash() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["stash", "push", "--include-untracked", "-m", "phantom-delete feature 42"])
+            .in_dir(WORKTREE)
+            .times(1)
+            .returns_success();
+
+        let saved = snapshot_worktree(
+            mock.clone(),
+            &MockFileSystem::new(),
+            Path::new("/repo"),
+            "feature",
+            Path::new(WORKTREE),
+            DeleteSnapshot::Stash,
+            42,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(saved.as_deref(), Some("stash 'phantom-delete feature 42'"));
+        mock.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_patch_includes_untracked_files() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["diff", "HEAD", "--binary"])
+            .in_dir(WORKTREE)
+            .returns_output("diff --git a/src.rs b/src.rs\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["ls-files", "--others", "--exclude-standard", "-z"])
+            .in_dir(WORKTREE)
+            .returns_output("notes.txt\0empty\0", "", 0);
+        let fs = MockFileSystem::new();
+        let notes = format!("{WORKTREE}/notes.txt");
+        expect(
+            &fs,
+            FileSystemOperation::ReadToString,
+            &notes,
+            None,
+            MockResult::String("a\nb".to_string()),
+        );
+        let empty = format!("{WORKTREE}/empty");
+        expect(
+            &fs,
+            FileSystemOperation::ReadToString,
+            &empty,
+            None,
+            MockResult::String(String::new()),
+        );
+        expect(
+            &fs,
+            FileSystemOperation::CreateDirAll,
+            "/repo/.git/phantom/trash",
+            None,
+            MockResult::Unit,
+        );
+        let patch = "diff --git a/src.rs b/src.rs\n\
+                     diff --git a/notes.txt b/notes.txt\nnew file mode 100644\n\
+                     --- /dev/null\n+++ b/notes.txt\n@@ -0,0 +1,2 @@\n+a\n+b\n\
+                     \\ No newline at end of file\n\
+                     diff --git a/empty b/empty\nnew file mode 100644\n";
+        expect(
+            &fs,
+            FileSystemOperation::Write,
+            "/repo/.git/phantom/trash/feature-42.patch",
+            Some(patch),
+            MockResult::Unit,
+        );
+
+        let saved = snapshot_worktree(
+            mock,
+            &fs,
+            Path::new("/repo"),
+            "feature",
+            Path::new(WORKTREE),
+            DeleteSnapshot::Patch,
+            42,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(saved.as_deref(), Some("/repo/.git/phantom/trash/feature-42.patch"));
+    }
+}
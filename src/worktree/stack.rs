@@ -0,0 +1,429 @@
+use crate::core::command_executor::CommandExecutor;
+use crate::core::filesystem::FileSystem;
+use crate::git::git_executor_adapter::GitExecutor;
+use crate::git::libs::branch_exists::branch_exists;
+use crate::worktree::list::WorktreeInfo;
+use crate::worktree::paths::get_worktree_path;
+use crate::{PhantomError, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use tracing::debug;
+
+/// git config key prefix/suffix under which stack parents are recorded
+///
+/// Stored as `phantom.<worktree-name>.stackParent = <parent-branch>` in the
+/// repository config, which all worktrees share.
+const STACK_KEY_PREFIX: &str = "phantom.";
+const STACK_KEY_SUFFIX: &str = ".stackparent";
+
+fn stack_key(name: &str) -> String {
+    format!("{STACK_KEY_PREFIX}{name}.stackParent")
+}
+
+/// The branch a stacked worktree is based on
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackBase {
+    pub branch: String,
+    /// Phantom the branch was resolved from, if any
+    pub phantom: Option<String>,
+}
+
+/// Current branch of a worktree, or `None` on a detached HEAD
+async fn current_branch<E>(executor: E, worktree_path: &Path) -> Result<Option<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(worktree_path);
+    let branch = git_executor.run(&["branch", "--show-current"]).await?;
+    Ok(Some(branch).filter(|b| !b.is_empty()))
+}
+
+/// Resolve `--stack-on` to a branch: a phantom name wins over a branch name
+pub async fn resolve_stack_base<E>(
+    executor: E,
+    git_root: &Path,
+    target: &str,
+    filesystem: &dyn FileSystem,
+) -> Result<StackBase>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let worktree_path = get_worktree_path(git_root, target);
+    if filesystem.is_dir(&worktree_path).await.unwrap_or(false) {
+        let branch = current_branch(executor, &worktree_path).await?.ok_or_else(|| {
+            PhantomError::ValidationFailed {
+                reason: format!("Cannot stack on '{target}': its HEAD is detached"),
+            }
+        })?;
+        debug!("Stacking on phantom '{}' (branch '{}')", target, branch);
+        return Ok(StackBase { branch, phantom: Some(target.to_string()) });
+    }
+
+    if branch_exists(executor, git_root, target).await? {
+        return Ok(StackBase { branch: target.to_string(), phantom: None });
+    }
+
+    Err(PhantomError::ValidationFailed {
+        reason: format!("Cannot stack on '{target}': no phantom or branch with that name"),
+    })
+}
+
+/// Make `git rebase`/`git pull` in the worktree default to the stack parent
+pub async fn set_upstream<E>(executor: E, worktree_path: &Path, base_branch: &str) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(worktree_path);
+    git_executor.run(&["branch", &format!("--set-upstream-to={base_branch}")]).await?;
+    Ok(())
+}
+
+/// Record the branch a worktree is stacked on
+pub async fn record_stack_parent<E>(
+    executor: E,
+    git_root: &Path,
+    name: &str,
+    parent_branch: &str,
+) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(git_root);
+    git_executor.run(&["config", &stack_key(name), parent_branch]).await?;
+    Ok(())
+}
+
+/// Forget the stack parent of a worktree
+pub async fn clear_stack_parent<E>(executor: E, git_root: &Path, name: &str) -> Result<()>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(git_root);
+    match git_executor.run(&["config", "--unset", &stack_key(name)]).await {
+        // Exit code 5 means the key was not set
+        Ok(_) | Err(PhantomError::Git { exit_code: 5, .. }) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Read all recorded stack parents, keyed by worktree name
+pub async fn read_stack_parents<E>(executor: E, git_root: &Path) -> Result<BTreeMap<String, String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let git_executor = GitExecutor::new(executor).with_cwd(git_root);
+    let lines = match git_executor
+        .run_lines(&["config", "--get-regexp", r"^phantom\..*\.stackparent$"])
+        .await
+    {
+        Ok(lines) => lines,
+        // Exit code 1 means no matching keys
+        Err(PhantomError::Git { exit_code: 1, .. }) => return Ok(BTreeMap::new()),
+        Err(e) => return Err(e),
+    };
+
+    Ok(lines.iter().filter_map(|line| parse_stack_entry(line)).collect())
+}
+
+fn parse_stack_entry(line: &str) -> Option<(String, String)> {
+    let (key, parent) = line.split_once(' ')?;
+    let name = key.strip_prefix(STACK_KEY_PREFIX)?.strip_suffix(STACK_KEY_SUFFIX)?;
+    Some((name.to_string(), parent.trim().to_string()))
+}
+
+/// Worktrees recorded as stacked on `branch`
+pub fn stack_children(parents: &BTreeMap<String, String>, branch: &str) -> Vec<String> {
+    parents.iter().filter(|(_, parent)| *parent == branch).map(|(name, _)| name.clone()).collect()
+}
+
+/// Worktrees recorded as stacked on the branch of worktree `name`
+pub async fn children_of_worktree<E>(
+    executor: E,
+    git_root: &Path,
+    name: &str,
+) -> Result<Vec<String>>
+where
+    E: CommandExecutor + Clone + 'static,
+{
+    let worktree_path = get_worktree_path(git_root, name);
+    let Some(branch) = current_branch(executor.clone(), &worktree_path).await? else {
+        return Ok(Vec::new());
+    };
+    let parents = read_stack_parents(executor, git_root).await?;
+    Ok(stack_children(&parents, &branch))
+}
+
+/// Render worktrees as stacks, children indented under their parent
+pub fn render_stacks(
+    worktrees: &[WorktreeInfo],
+    parents: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let parent_of = |worktree: &WorktreeInfo| -> Option<usize> {
+        let parent_branch = parents.get(&worktree.name)?;
+        worktrees.iter().position(|w| w.branch.as_deref() == Some(parent_branch.as_str()))
+    };
+    let parent_index: Vec<Option<usize>> = worktrees.iter().map(parent_of).collect();
+
+    let mut lines = Vec::new();
+    let mut visited = HashSet::new();
+    for (index, worktree) in worktrees.iter().enumerate() {
+        // A worktree whose parent is missing (or part of a cycle) starts its own stack
+        let is_root = match parent_index[index] {
+            None => true,
+            Some(parent) => parent == index,
+        };
+        if !is_root {
+            continue;
+        }
+
+        let mut label = describe(worktree);
+        if let Some(base) = parents.get(&worktree.name) {
+            label.push_str(&format!(" [on {base}]"));
+        }
+        lines.push(label);
+        visited.insert(index);
+        render_children(worktrees, &parent_index, index, "", &mut visited, &mut lines);
+    }
+
+    // Anything left over is part of a cycle; show it flat rather than dropping it
+    for (index, worktree) in worktrees.iter().enumerate() {
+        if visited.insert(index) {
+            lines.push(describe(worktree));
+        }
+    }
+
+    lines
+}
+
+fn describe(worktree: &WorktreeInfo) -> String {
+    match &worktree.branch {
+        Some(branch) if *branch != worktree.name => format!("{} ({branch})", worktree.name),
+        _ => worktree.name.clone(),
+    }
+}
+
+fn render_children(
+    worktrees: &[WorktreeInfo],
+    parent_index: &[Option<usize>],
+    parent: usize,
+    indent: &str,
+    visited: &mut HashSet<usize>,
+    lines: &mut Vec<String>,
+) {
+    let children: Vec<usize> = (0..worktrees.len())
+        .filter(|&i| i != parent && parent_index[i] == Some(parent) && !visited.contains(&i))
+        .collect();
+
+    for (position, &child) in children.iter().enumerate() {
+        let last = position + 1 == children.len();
+        let (branch, continuation) =
+            if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+        lines.push(format!("{indent}{branch}{}", describe(&worktrees[child])));
+        visited.insert(child);
+        render_children(
+            worktrees,
+            parent_index,
+            child,
+            &format!("{indent}{continuation}"),
+            visited,
+            lines,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::executors::MockCommandExecutor;
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem};
+    use crate::worktree::list::WorktreeOrigin;
+    use std::path::PathBuf;
+
+    fn expect_is_dir(fs: &MockFileSystem, path: &str, is_dir: bool) {
+        fs.expect(FileSystemExpectation {
+            operation: FileSystemOperation::IsDir,
+            path: Some(PathBuf::from(path)),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result: Ok(MockResult::Bool(is_dir)),
+        });
+    }
+
+    fn worktree(name: &str, branch: &str) -> WorktreeInfo {
+        WorktreeInfo {
+            name: name.to_string(),
+            path: format!("/repo/.git/phantom/worktrees/{name}"),
+            branch: Some(branch.to_string()),
+            is_clean: true,
+            is_locked: false,
+            locked_reason: None,
+            is_detached: false,
+            gone: false,
+            origin: WorktreeOrigin::Phantom,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_stack_base_from_phantom_name() {
+        let mut mock = MockCommandExecutor::new();
+        let fs = MockFileSystem::new();
+        expect_is_dir(&fs, "/repo/.git/phantom/worktrees/feature-1", true);
+        mock.expect_command("git")
+            .with_args(&["branch", "--show-current"])
+            .in_dir("/repo/.git/phantom/worktrees/feature-1")
+            .returns_output("user/feature-1\n", "", 0);
+
+        let base = resolve_stack_base(mock, Path::new("/repo"), "feature-1", &fs).await.unwrap();
+        assert_eq!(
+            base,
+            StackBase {
+                branch: "user/feature-1".to_string(),
+                phantom: Some("feature-1".to_string())
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_stack_base_from_branch() {
+        let mut mock = MockCommandExecutor::new();
+        let fs = MockFileSystem::new();
+        expect_is_dir(&fs, "/repo/.git/phantom/worktrees/main", false);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/main"])
+            .in_dir("/repo")
+            .returns_success();
+
+        let base = resolve_stack_base(mock, Path::new("/repo"), "main", &fs).await.unwrap();
+        assert_eq!(base, StackBase { branch: "main".to_string(), phantom: None });
+    }
+
+    #[tokio::test]
+    async fn test_resolve_stack_base_unknown() {
+        let mut mock = MockCommandExecutor::new();
+        let fs = MockFileSystem::new();
+        expect_is_dir(&fs, "/repo/.git/phantom/worktrees/nope", false);
+        mock.expect_command("git")
+            .with_args(&["show-ref", "--verify", "--quiet", "refs/heads/nope"])
+            .in_dir("/repo")
+            .returns_output("", "", 1);
+
+        let error = resolve_stack_base(mock, Path::new("/repo"), "nope", &fs).await.unwrap_err();
+        assert!(error.to_string().contains("no phantom or branch with that name"));
+    }
+
+    #[tokio::test]
+    async fn test_set_upstream_and_record_parent() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["branch", "--set-upstream-to=feature-1"])
+            .in_dir("/repo/.git/phantom/worktrees/feature-2")
+            .returns_success();
+        mock.expect_command("git")
+            .with_args(&["config", "phantom.feature-2.stackParent", "feature-1"])
+            .in_dir("/repo")
+            .returns_success();
+
+        set_upstream(
+            mock.clone(),
+            Path::new("/repo/.git/phantom/worktrees/feature-2"),
+            "feature-1",
+        )
+        .await
+        .unwrap();
+        record_stack_parent(mock.clone(), Path::new("/repo"), "feature-2", "feature-1")
+            .await
+            .unwrap();
+        assert_eq!(mock.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_read_stack_parents() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["config", "--get-regexp", r"^phantom\..*\.stackparent$"])
+            .in_dir("/repo")
+            .returns_output(
+                "phantom.feature-2.stackparent feature-1\nphantom.feature-3.stackparent feature-2\n",
+                "",
+                0,
+            );
+
+        let parents = read_stack_parents(mock, Path::new("/repo")).await.unwrap();
+        assert_eq!(parents.get("feature-2").map(String::as_str), Some("feature-1"));
+        assert_eq!(parents.get("feature-3").map(String::as_str), Some("feature-2"));
+        assert_eq!(stack_children(&parents, "feature-1"), vec!["feature-2"]);
+        assert!(stack_children(&parents, "feature-3").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_stack_parents_none_recorded() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["config", "--get-regexp", r"^phantom\..*\.stackparent$"])
+            .in_dir("/repo")
+            .returns_output("", "", 1);
+
+        assert!(read_stack_parents(mock, Path::new("/repo")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_children_of_worktree() {
+        let mut mock = MockCommandExecutor::new();
+        mock.expect_command("git")
+            .with_args(&["branch", "--show-current"])
+            .in_dir("/repo/.git/phantom/worktrees/feature-1")
+            .returns_output("feature-1\n", "", 0);
+        mock.expect_command("git")
+            .with_args(&["config", "--get-regexp", r"^phantom\..*\.stackparent$"])
+            .in_dir("/repo")
+            .returns_output(
+                "phantom.feature-2.stackparent feature-1\nphantom.other.stackparent main\n",
+                "",
+                0,
+            );
+
+        let children = children_of_worktree(mock, Path::new("/repo"), "feature-1").await.unwrap();
+        assert_eq!(children, vec!["feature-2"]);
+    }
+
+    #[test]
+    fn test_render_stacks() {
+        let worktrees = vec![
+            worktree("feature-1", "feature-1"),
+            worktree("other", "other"),
+            worktree("feature-2", "feature-2"),
+            worktree("feature-3", "feature-3"),
+            worktree("feature-2b", "feature-2b"),
+        ];
+        let parents = BTreeMap::from([
+            ("feature-1".to_string(), "main".to_string()),
+            ("feature-2".to_string(), "feature-1".to_string()),
+            ("feature-3".to_string(), "feature-2".to_string()),
+            ("feature-2b".to_string(), "feature-1".to_string()),
+        ]);
+
+        assert_eq!(
+            render_stacks(&worktrees, &parents),
+            vec![
+                "feature-1 [on main]",
+                "├── feature-2",
+                "│   └── feature-3",
+                "└── feature-2b",
+                "other",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_stacks_cycle_is_not_dropped() {
+        let worktrees = vec![worktree("a", "a"), worktree("b", "b")];
+        let parents = BTreeMap::from([
+            ("a".to_string(), "b".to_string()),
+            ("b".to_string(), "a".to_string()),
+        ]);
+
+        assert_eq!(render_stacks(&worktrees, &parents), vec!["a", "b"]);
+    }
+}
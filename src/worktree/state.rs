@@ -1,6 +1,13 @@
+use crate::core::filesystem::FileSystem;
+use crate::worktree::const_validate::PHANTOM_STATE_DIR;
+use crate::worktree::paths::{get_worktree_path, join_slash_separated};
 use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::marker::PhantomData;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
 
 /// Type-level states for worktrees
 pub mod states {
@@ -222,6 +229,195 @@ impl From<crate::core::types::Worktree> for TypedWorktree<states::Attached> {
     }
 }
 
+/// Creation metadata recorded alongside each phantom worktree
+///
+/// Every field is optional so that files written by older versions, or
+/// damaged by hand, still load.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeMetadata {
+    /// Seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phantom_version: Option<String>,
+    /// Labels set with `phantom label`
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub labels: BTreeSet<String>,
+    /// Directory of a worktree adopted with `phantom adopt --in-place`, outside the phantom root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<PathBuf>,
+}
+
+impl WorktreeMetadata {
+    /// Metadata for a worktree created now from `base_ref`
+    pub fn now(base_ref: Option<String>) -> Self {
+        Self {
+            created_at: SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs()),
+            base_ref,
+            phantom_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            labels: BTreeSet::new(),
+            location: None,
+        }
+    }
+}
+
+/// Path of the metadata file for the named worktree
+pub fn metadata_path(git_root: &Path, name: &str) -> PathBuf {
+    join_slash_separated(git_root, &format!("{PHANTOM_STATE_DIR}/{name}.json"))
+}
+
+/// Write the metadata file for the named worktree
+pub async fn write_worktree_metadata(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    name: &str,
+    metadata: &WorktreeMetadata,
+) -> Result<()> {
+    let path = metadata_path(git_root, name);
+    if let Some(parent) = path.parent() {
+        filesystem.create_dir_all(parent).await?;
+    }
+    let contents = serde_json::to_string_pretty(metadata)?;
+    filesystem.write_atomic(&path, contents.as_bytes()).await
+}
+
+/// Where a corrupt state file is moved so it can still be inspected
+fn quarantine_path(path: &Path, now: u64) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".corrupt-{now}"));
+    path.with_file_name(file_name)
+}
+
+/// Read the metadata for the named worktree, falling back to empty metadata
+/// when the file is missing or unreadable
+///
+/// A file that does not parse is renamed aside with a `.corrupt-<timestamp>`
+/// suffix, so the next write starts clean and the evidence is kept.
+pub async fn read_worktree_metadata(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    name: &str,
+) -> WorktreeMetadata {
+    let path = metadata_path(git_root, name);
+    let contents = match filesystem.read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(e) => {
+            debug!("No metadata for worktree '{}': {}", name, e);
+            return WorktreeMetadata::default();
+        }
+    };
+    match serde_json::from_str(&contents) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            debug!("Ignoring corrupt metadata file {}: {}", path.display(), e);
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+            let quarantine = quarantine_path(&path, now);
+            if let Err(e) = filesystem.rename(&path, &quarantine).await {
+                debug!("Failed to quarantine {}: {}", path.display(), e);
+            }
+            WorktreeMetadata::default()
+        }
+    }
+}
+
+/// Remove the metadata file for the named worktree, if there is one
+///
+/// Directories left empty by a nested name such as `feature/login` are
+/// removed too, up to the state root.
+pub async fn remove_worktree_metadata(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    name: &str,
+) -> Result<()> {
+    let path = metadata_path(git_root, name);
+    if filesystem.exists(&path).await? {
+        filesystem.remove_file(&path).await?;
+    }
+
+    let state_root = join_slash_separated(git_root, PHANTOM_STATE_DIR);
+    let mut dir = path.parent();
+    while let Some(current) = dir.filter(|d| *d != state_root && d.starts_with(&state_root)) {
+        // A directory still holding another worktree's metadata ends the walk
+        if !filesystem.list_dir(current).await.is_ok_and(|entries| entries.is_empty()) {
+            break;
+        }
+        filesystem.remove_dir(current).await?;
+        dir = current.parent();
+    }
+    Ok(())
+}
+
+/// Directory of the named worktree: where it was adopted in place, otherwise
+/// its directory under the phantom root
+pub async fn resolve_worktree_path(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+    name: &str,
+) -> PathBuf {
+    read_worktree_metadata(filesystem, git_root, name)
+        .await
+        .location
+        .unwrap_or_else(|| get_worktree_path(git_root, name))
+}
+
+/// Worktrees adopted in place, as (name, location) pairs sorted by name
+///
+/// Unreadable directories and metadata files are skipped.
+pub async fn adopted_worktrees(
+    filesystem: &dyn FileSystem,
+    git_root: &Path,
+) -> Vec<(String, PathBuf)> {
+    let mut adopted = Vec::new();
+    let mut pending = vec![(join_slash_separated(git_root, PHANTOM_STATE_DIR), String::new())];
+
+    while let Some((dir, prefix)) = pending.pop() {
+        let Ok(entries) = filesystem.list_dir(&dir).await else {
+            continue;
+        };
+        for path in entries {
+            let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let name = if prefix.is_empty() { file_name } else { format!("{prefix}/{file_name}") };
+            if filesystem.is_dir(&path).await.unwrap_or(false) {
+                pending.push((path, name));
+                continue;
+            }
+            let Some(name) = name.strip_suffix(".json") else {
+                continue;
+            };
+            let location = filesystem
+                .read_to_string(&path)
+                .await
+                .ok()
+                .and_then(|contents| serde_json::from_str::<WorktreeMetadata>(&contents).ok())
+                .and_then(|metadata| metadata.location);
+            if let Some(location) = location {
+                adopted.push((name.to_string(), location));
+            }
+        }
+    }
+
+    adopted.sort();
+    adopted
+}
+
+/// Describe how long ago a Unix timestamp was, e.g. "3 days ago"
+pub fn format_age(created_at: u64, now: u64) -> String {
+    let elapsed = now.saturating_sub(created_at);
+    let (value, unit) = match elapsed {
+        0..=59 => return "just now".to_string(),
+        60..=3599 => (elapsed / 60, "minute"),
+        3600..=86_399 => (elapsed / 3600, "hour"),
+        _ => (elapsed / 86_400, "day"),
+    };
+    let plural = if value == 1 { "" } else { "s" };
+    format!("{value} {unit}{plural} ago")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,4 +478,233 @@ mod tests {
         assert_eq!(worktree.commit(), "def456");
         assert!(!worktree.is_bare());
     }
+
+    use crate::core::filesystems::mock_filesystem::{FileSystemOperation, MockResult};
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem, RealFileSystem};
+
+    fn expect_read(fs: &MockFileSystem, path: PathBuf, result: crate::Result<MockResult>) {
+        expect_operation(fs, FileSystemOperation::ReadToString, path, result);
+    }
+
+    fn expect_operation(
+        fs: &MockFileSystem,
+        operation: FileSystemOperation,
+        path: PathBuf,
+        result: crate::Result<MockResult>,
+    ) {
+        fs.expect(FileSystemExpectation {
+            operation,
+            path: Some(path),
+            from_path: None,
+            to_path: None,
+            contents: None,
+            result,
+        });
+    }
+
+    #[test]
+    fn test_metadata_path() {
+        assert_eq!(
+            metadata_path(Path::new("/repo"), "feature/x"),
+            PathBuf::from("/repo/.git/phantom/state/feature/x.json")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metadata_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fs = RealFileSystem::new();
+        let metadata = WorktreeMetadata::now(Some("main".to_string()));
+
+        write_worktree_metadata(&fs, temp_dir.path(), "feature/x", &metadata).await.unwrap();
+        let read = read_worktree_metadata(&fs, temp_dir.path(), "feature/x").await;
+        assert_eq!(read, metadata);
+        assert_eq!(read.phantom_version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+
+        remove_worktree_metadata(&fs, temp_dir.path(), "feature/x").await.unwrap();
+        assert!(!metadata_path(temp_dir.path(), "feature/x").exists());
+        // Removing again is a no-op
+        remove_worktree_metadata(&fs, temp_dir.path(), "feature/x").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_remove_metadata_prunes_empty_parents() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fs = RealFileSystem::new();
+        let metadata = WorktreeMetadata::now(Some("main".to_string()));
+        for name in ["feature/login", "feature/ui/nav", "main-fix"] {
+            write_worktree_metadata(&fs, temp_dir.path(), name, &metadata).await.unwrap();
+        }
+        let state_root = temp_dir.path().join(".git/phantom/state");
+
+        remove_worktree_metadata(&fs, temp_dir.path(), "feature/ui/nav").await.unwrap();
+        // feature/ still holds login.json
+        assert!(!state_root.join("feature/ui").exists());
+        assert!(state_root.join("feature").exists());
+
+        remove_worktree_metadata(&fs, temp_dir.path(), "feature/login").await.unwrap();
+        assert!(!state_root.join("feature").exists());
+        assert!(state_root.join("main-fix.json").exists());
+
+        remove_worktree_metadata(&fs, temp_dir.path(), "main-fix").await.unwrap();
+        // The state root itself is kept
+        assert!(state_root.exists());
+    }
+
+    #[tokio::test]
+    async fn test_read_missing_metadata() {
+        let fs = MockFileSystem::new();
+        expect_read(
+            &fs,
+            metadata_path(Path::new("/repo"), "gone"),
+            Err(crate::PhantomError::FileOperationFailed {
+                operation: "read".to_string(),
+                path: PathBuf::from("gone"),
+                reason: "No such file or directory".to_string(),
+            }),
+        );
+
+        let metadata = read_worktree_metadata(&fs, Path::new("/repo"), "gone").await;
+        assert_eq!(metadata, WorktreeMetadata::default());
+    }
+
+    #[tokio::test]
+    async fn test_read_corrupt_metadata() {
+        let fs = MockFileSystem::new();
+        expect_read(
+            &fs,
+            metadata_path(Path::new("/repo"), "broken"),
+            Ok(MockResult::String("{ not json".to_string())),
+        );
+
+        let metadata = read_worktree_metadata(&fs, Path::new("/repo"), "broken").await;
+        assert_eq!(metadata.created_at, None);
+        assert_eq!(metadata.base_ref, None);
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_metadata_is_quarantined() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fs = RealFileSystem::new();
+        let path = metadata_path(temp_dir.path(), "broken");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{ \"baseRef\": \"ma").unwrap();
+
+        let metadata = read_worktree_metadata(&fs, temp_dir.path(), "broken").await;
+        assert_eq!(metadata, WorktreeMetadata::default());
+        assert!(!path.exists());
+
+        let quarantined: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(quarantined.len(), 1);
+        assert!(quarantined[0].starts_with("broken.json.corrupt-"));
+
+        // The next write starts from a clean slate
+        let fresh = WorktreeMetadata::now(Some("main".to_string()));
+        write_worktree_metadata(&fs, temp_dir.path(), "broken", &fresh).await.unwrap();
+        assert_eq!(read_worktree_metadata(&fs, temp_dir.path(), "broken").await, fresh);
+    }
+
+    #[test]
+    fn test_quarantine_path() {
+        assert_eq!(
+            quarantine_path(Path::new("/repo/.git/phantom/state/x.json"), 42),
+            PathBuf::from("/repo/.git/phantom/state/x.json.corrupt-42")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_partial_metadata() {
+        let fs = MockFileSystem::new();
+        expect_read(
+            &fs,
+            metadata_path(Path::new("/repo"), "old"),
+            Ok(MockResult::String(r#"{"baseRef": "develop", "unknown": 1}"#.to_string())),
+        );
+
+        let metadata = read_worktree_metadata(&fs, Path::new("/repo"), "old").await;
+        assert_eq!(metadata.base_ref.as_deref(), Some("develop"));
+        assert_eq!(metadata.created_at, None);
+        assert_eq!(metadata.phantom_version, None);
+    }
+
+    #[tokio::test]
+    async fn test_adopted_worktrees_through_the_filesystem() {
+        use crate::core::filesystems::mock_filesystem::MockDirEntry;
+
+        let git_root = Path::new("/repo");
+        let state = join_slash_separated(git_root, PHANTOM_STATE_DIR);
+        let entries = |paths: &[&PathBuf]| {
+            let entries = paths.iter().map(|&path| MockDirEntry { path: path.clone() }).collect();
+            Ok(MockResult::DirEntries(entries))
+        };
+        let fs = MockFileSystem::new();
+        let (zeta, team, regular) =
+            (state.join("zeta.json"), state.join("team"), state.join("regular.json"));
+        let alpha = team.join("alpha.json");
+        expect_operation(
+            &fs,
+            FileSystemOperation::ListDir,
+            state.clone(),
+            entries(&[&zeta, &team, &regular]),
+        );
+        expect_operation(&fs, FileSystemOperation::ListDir, team.clone(), entries(&[&alpha]));
+        expect_operation(&fs, FileSystemOperation::IsDir, team, Ok(MockResult::Bool(true)));
+        for file in [&zeta, &regular, &alpha] {
+            expect_operation(
+                &fs,
+                FileSystemOperation::IsDir,
+                file.clone(),
+                Ok(MockResult::Bool(false)),
+            );
+        }
+        let adopted =
+            |location: &str| Ok(MockResult::String(format!(r#"{{"location": "{location}"}}"#)));
+        expect_read(&fs, zeta, adopted("/elsewhere/zeta"));
+        expect_read(&fs, alpha, adopted("/elsewhere/alpha"));
+        expect_read(&fs, regular, Ok(MockResult::String(r#"{"baseRef": "main"}"#.to_string())));
+
+        assert_eq!(
+            adopted_worktrees(&fs, git_root).await,
+            vec![
+                ("team/alpha".to_string(), PathBuf::from("/elsewhere/alpha")),
+                ("zeta".to_string(), PathBuf::from("/elsewhere/zeta")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_adopted_worktrees_are_found_by_location() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fs = RealFileSystem::new();
+        let git_root = temp_dir.path();
+        let outside = git_root.join("elsewhere");
+        let adopted = WorktreeMetadata { location: Some(outside.clone()), ..Default::default() };
+        write_worktree_metadata(&fs, git_root, "team/adopted", &adopted).await.unwrap();
+        write_worktree_metadata(&fs, git_root, "regular", &WorktreeMetadata::now(None))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            adopted_worktrees(&fs, git_root).await,
+            vec![("team/adopted".to_string(), outside.clone())]
+        );
+        assert_eq!(resolve_worktree_path(&fs, git_root, "team/adopted").await, outside);
+        assert_eq!(
+            resolve_worktree_path(&fs, git_root, "regular").await,
+            get_worktree_path(git_root, "regular")
+        );
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(1_000, 1_030), "just now");
+        assert_eq!(format_age(1_000, 1_060), "1 minute ago");
+        assert_eq!(format_age(0, 7_200), "2 hours ago");
+        assert_eq!(format_age(0, 3 * 86_400), "3 days ago");
+        // Clock skew must not underflow
+        assert_eq!(format_age(5_000, 1_000), "just now");
+    }
 }
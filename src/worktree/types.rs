@@ -1,15 +1,75 @@
+use crate::config::types::DeleteSnapshot;
+use crate::worktree::file_copier::ProgressReporter;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Options for creating a new worktree
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CreateWorktreeOptions {
     /// Branch name to create (defaults to worktree name)
     pub branch: Option<String>,
     /// Commit/branch to base the new worktree on (defaults to HEAD)
     pub commitish: Option<String>,
+    /// Check out `commitish` with a detached HEAD instead of creating a branch
+    pub detach: bool,
+    /// Add the worktree without checking out any files
+    pub no_checkout: bool,
+    /// Check out only these cone-mode sparse-checkout directories
+    pub sparse_patterns: Option<Vec<String>>,
     /// Files to copy from the source worktree
     pub copy_files: Option<Vec<String>>,
+    /// Directory `copy_files` are read from instead of the repository root
+    pub copy_source: Option<PathBuf>,
+    /// Leave a partially created worktree in place instead of rolling it back
+    pub keep_on_failure: bool,
+    /// Receives progress while the files are copied
+    #[serde(skip)]
+    pub copy_progress: Option<Arc<dyn ProgressReporter>>,
+}
+
+/// How the files copied into a new worktree are chosen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyFilesSelection {
+    /// The configured copyFiles plus any extra files
+    Merge(Vec<String>),
+    /// Only the given files, ignoring the configuration
+    Only(Vec<String>),
+    /// Copy nothing
+    Skip,
+}
+
+impl Default for CopyFilesSelection {
+    fn default() -> Self {
+        Self::Merge(Vec::new())
+    }
+}
+
+impl CopyFilesSelection {
+    /// Whether the configured copyFiles take part in the selection
+    pub fn uses_config(&self) -> bool {
+        matches!(self, Self::Merge(_))
+    }
+
+    /// Combine the selection with the configured copyFiles
+    pub fn resolve(&self, configured: Option<Vec<String>>) -> Option<Vec<String>> {
+        match self {
+            Self::Merge(extra) if extra.is_empty() => configured,
+            Self::Merge(extra) => {
+                let mut files = configured.unwrap_or_default();
+                for file in extra {
+                    if !files.contains(file) {
+                        files.push(file.clone());
+                    }
+                }
+                Some(files)
+            }
+            Self::Only(files) => Some(files.clone()),
+            Self::Skip => None,
+        }
+    }
 }
 
 /// Result of a successful worktree creation
@@ -21,15 +81,89 @@ pub struct CreateWorktreeSuccess {
     pub copied_files: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub skipped_files: Option<Vec<String>>,
+    /// Files each copy_files glob pattern expanded to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expanded_from: Option<HashMap<String, Vec<String>>>,
+    /// Set when the worktree exists but its metadata could not be recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_error: Option<String>,
+}
+
+/// Options for attaching a worktree to an existing branch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttachWorktreeOptions {
+    /// Files to copy from the source worktree
+    pub copy_files: Option<Vec<String>>,
+    /// Directory `copy_files` are read from instead of the repository root
+    pub copy_source: Option<PathBuf>,
+}
+
+/// Result of attaching a worktree to an existing branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachWorktreeSuccess {
+    pub name: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copied_files: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped_files: Option<Vec<String>>,
+    /// Files each copy_files glob pattern expanded to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expanded_from: Option<HashMap<String, Vec<String>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub copy_error: Option<String>,
+    /// Set when the worktree exists but its metadata could not be recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_error: Option<String>,
+}
+
+/// Protections that a deletion is allowed to override
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForceOptions {
+    /// Delete even if there are untracked/modified files
+    pub dirty: bool,
+    /// Delete even if the worktree is locked
+    pub locked: bool,
+    /// Delete the branch even if it is not fully merged
+    pub unmerged: bool,
+}
+
+impl ForceOptions {
+    /// Override no protections
+    pub const fn none() -> Self {
+        Self { dirty: false, locked: false, unmerged: false }
+    }
+
+    /// Override every protection
+    pub const fn all() -> Self {
+        Self { dirty: true, locked: true, unmerged: true }
+    }
 }
 
 /// Options for deleting a worktree
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DeleteWorktreeOptions {
-    /// Force deletion even if there are untracked/modified files
-    pub force: bool,
+    /// Which protections to override
+    pub force: ForceOptions,
+    /// Leave the worktree's branch in place
+    pub keep_branch: bool,
+    /// Where to save uncommitted changes a forced delete discards; `None` uses the
+    /// repository config through [`Phantom::delete`](crate::api::Phantom::delete), the default otherwise
+    pub snapshot: Option<DeleteSnapshot>,
+}
+
+/// What a successful delete removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeleteOutcome {
+    /// The worktree directory and its registration
+    #[default]
+    Deleted,
+    /// Only the registration git kept after the directory was removed by hand
+    PrunedStale,
 }
 
 /// Result of a successful worktree deletion
@@ -37,6 +171,8 @@ pub struct DeleteWorktreeOptions {
 pub struct DeleteWorktreeSuccess {
     pub message: String,
     pub path: String,
+    #[serde(default)]
+    pub outcome: DeleteOutcome,
 }
 
 /// Result of worktree validation
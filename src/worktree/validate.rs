@@ -1,10 +1,13 @@
 use crate::core::filesystem::FileSystem;
+use crate::core::utils::normalize_path;
 use crate::worktree::const_validate::{is_valid_worktree_name_basic, MAX_WORKTREE_NAME_LENGTH};
 use crate::worktree::errors::WorktreeError;
 use crate::worktree::paths::{get_phantom_directory, get_worktree_path};
+use crate::worktree::state::read_worktree_metadata;
 use crate::worktree::types::{WorktreeDoesNotExistSuccess, WorktreeExistsSuccess};
-use crate::Result;
-use std::path::Path;
+use crate::{PhantomError, Result};
+use std::path::{Path, PathBuf};
+use tracing::debug;
 
 /// Validate that a worktree exists
 pub async fn validate_worktree_exists(
@@ -14,11 +17,24 @@ pub async fn validate_worktree_exists(
 ) -> Result<WorktreeExistsSuccess> {
     let worktree_path = get_worktree_path(git_root, name);
 
-    match filesystem.is_dir(&worktree_path).await {
-        Ok(true) => Ok(WorktreeExistsSuccess { path: worktree_path }),
-        Ok(false) => Err(WorktreeError::NotFound(name.to_string()).into()),
-        Err(_) => Err(WorktreeError::NotFound(name.to_string()).into()),
+    if filesystem.is_dir(&worktree_path).await.unwrap_or(false) {
+        return Ok(WorktreeExistsSuccess { path: worktree_path });
     }
+    match adopted_location(git_root, name, filesystem).await {
+        Some(location) => Ok(WorktreeExistsSuccess { path: location }),
+        None => Err(WorktreeError::NotFound(name.to_string()).into()),
+    }
+}
+
+/// Location of a worktree adopted in place under `name`, if it still exists
+async fn adopted_location(
+    git_root: &Path,
+    name: &str,
+    filesystem: &dyn FileSystem,
+) -> Option<PathBuf> {
+    let location =
+        normalize_path(&read_worktree_metadata(filesystem, git_root, name).await.location?);
+    filesystem.is_dir(&location).await.unwrap_or(false).then_some(location)
 }
 
 /// Validate that a worktree does not exist
@@ -27,13 +43,78 @@ pub async fn validate_worktree_does_not_exist(
     name: &str,
     filesystem: &dyn FileSystem,
 ) -> Result<WorktreeDoesNotExistSuccess> {
+    if let Some(existing) = find_case_collision(git_root, name, filesystem).await {
+        return Err(PhantomError::WorktreeNameCollision { name: name.to_string(), existing });
+    }
+
     let worktree_path = get_worktree_path(git_root, name);
 
-    match filesystem.is_dir(&worktree_path).await {
-        Ok(true) => Err(WorktreeError::AlreadyExists(name.to_string()).into()),
-        Ok(false) => Ok(WorktreeDoesNotExistSuccess { path: worktree_path }),
-        Err(_) => Ok(WorktreeDoesNotExistSuccess { path: worktree_path }),
+    if filesystem.is_dir(&worktree_path).await.unwrap_or(false)
+        || adopted_location(git_root, name, filesystem).await.is_some()
+    {
+        return Err(WorktreeError::AlreadyExists(name.to_string()).into());
+    }
+    Ok(WorktreeDoesNotExistSuccess { path: worktree_path })
+}
+
+/// Find an existing worktree whose name differs from `name` only by case
+///
+/// Only reported when the phantom directory is on a case-insensitive
+/// filesystem, where both names would resolve to the same directory.
+pub async fn find_case_collision(
+    git_root: &Path,
+    name: &str,
+    filesystem: &dyn FileSystem,
+) -> Option<String> {
+    let phantom_dir = get_phantom_directory(git_root);
+    let mut dir = phantom_dir.clone();
+    let mut existing = Vec::new();
+    let mut collision = None;
+
+    for component in name.split('/') {
+        let Ok(entries) = filesystem.list_dir(&dir).await else {
+            break;
+        };
+        let names: Vec<String> = entries
+            .iter()
+            .filter_map(|entry| entry.file_name())
+            .map(|entry| entry.to_string_lossy().to_string())
+            .collect();
+
+        if names.iter().any(|entry| entry == component) {
+            existing.push(component.to_string());
+            dir = dir.join(component);
+            continue;
+        }
+
+        if let Some(entry) = names.into_iter().find(|entry| entry.eq_ignore_ascii_case(component)) {
+            existing.push(entry);
+            collision = Some(existing.join("/"));
+        }
+        break;
+    }
+
+    let existing = collision?;
+    if is_case_insensitive(&phantom_dir, filesystem).await {
+        Some(existing)
+    } else {
+        debug!("'{name}' differs from '{existing}' only by case on a case-sensitive filesystem");
+        None
+    }
+}
+
+/// Probe whether a directory is on a case-insensitive filesystem
+async fn is_case_insensitive(dir: &Path, filesystem: &dyn FileSystem) -> bool {
+    let probe_name = format!(".Phantom-Case-Probe-{}", std::process::id());
+    let probe = dir.join(&probe_name);
+    if filesystem.write(&probe, "").await.is_err() {
+        return false;
     }
+
+    let insensitive =
+        filesystem.exists(&dir.join(probe_name.to_lowercase())).await.unwrap_or(false);
+    let _ = filesystem.remove_file(&probe).await;
+    insensitive
 }
 
 /// Validate that the phantom directory exists
@@ -82,14 +163,119 @@ pub fn validate_worktree_name(name: &str) -> Result<()> {
         ).into());
     }
 
+    // Each `/`-separated part becomes a directory below the worktrees root
+    if name.split('/').any(|component| component.is_empty() || component == ".") {
+        return Err(WorktreeError::InvalidName(
+            "Phantom name cannot start or end with '/', contain '//', or use '.' as a path component"
+                .to_string(),
+        )
+        .into());
+    }
+
     Ok(())
 }
 
+/// Validate a branch name against the rules of `git check-ref-format --branch`
+pub fn validate_branch_name(name: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        Err(PhantomError::InvalidBranchName { name: name.to_string(), reason: reason.to_string() })
+    };
+
+    if name.is_empty() {
+        return invalid("branch name cannot be empty");
+    }
+    if name.starts_with('-') {
+        return invalid("cannot start with '-'");
+    }
+    if name == "@" || name.contains("@{") {
+        return invalid("cannot be '@' or contain '@{'");
+    }
+    if name.contains("..") {
+        return invalid("cannot contain '..'");
+    }
+    if let Some(c) = name.chars().find(|c| {
+        c.is_ascii_control() || matches!(c, ' ' | '~' | '^' | ':' | '?' | '*' | '[' | '\\')
+    }) {
+        return invalid(&format!("cannot contain {c:?}"));
+    }
+    if name.ends_with('.') {
+        return invalid("cannot end with '.'");
+    }
+    for component in name.split('/') {
+        if component.is_empty() {
+            return invalid("cannot start or end with '/' or contain '//'");
+        }
+        if component.starts_with('.') {
+            return invalid("path components cannot start with '.'");
+        }
+        if component.ends_with(".lock") {
+            return invalid("path components cannot end with '.lock'");
+        }
+    }
+    Ok(())
+}
+
+/// Validate a prefix that new branch names start with
+pub fn validate_branch_prefix(prefix: &str) -> Result<()> {
+    // A prefix only has to form a valid name once something follows it
+    validate_branch_name(&format!("{prefix}topic")).map_err(|e| match e {
+        PhantomError::InvalidBranchName { reason, .. } => {
+            PhantomError::InvalidBranchName { name: prefix.to_string(), reason }
+        }
+        e => e,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::filesystems::RealFileSystem;
+    use crate::core::filesystems::mock_filesystem::{
+        FileSystemOperation, MockDirEntry, MockResult,
+    };
+    use crate::core::filesystems::{FileSystemExpectation, MockFileSystem, RealFileSystem};
     use crate::test_utils::TestRepo;
+    use std::path::PathBuf;
+
+    const PHANTOM_DIR: &str = "/repo/.git/phantom/worktrees";
+
+    fn expect(
+        fs: &MockFileSystem,
+        operation: FileSystemOperation,
+        path: &str,
+        contents: Option<&str>,
+        result: MockResult,
+    ) {
+        fs.expect(FileSystemExpectation {
+            operation,
+            path: Some(PathBuf::from(path)),
+            from_path: None,
+            to_path: None,
+            contents: contents.map(str::to_string),
+            result: Ok(result),
+        });
+    }
+
+    fn expect_entries(fs: &MockFileSystem, dir: &str, entries: &[&str]) {
+        let entries = entries
+            .iter()
+            .map(|entry| MockDirEntry { path: PathBuf::from(dir).join(entry) })
+            .collect();
+        expect(fs, FileSystemOperation::ListDir, dir, None, MockResult::DirEntries(entries));
+    }
+
+    /// Mock the case-sensitivity probe in the phantom directory
+    fn expect_probe(fs: &MockFileSystem, case_insensitive: bool) {
+        let probe = format!("{PHANTOM_DIR}/.Phantom-Case-Probe-{}", std::process::id());
+        expect(fs, FileSystemOperation::Write, &probe, Some(""), MockResult::Unit);
+        expect(
+            fs,
+            FileSystemOperation::Exists,
+            &probe.replace(".Phantom-Case-Probe", ".phantom-case-probe"),
+            None,
+            MockResult::Bool(case_insensitive),
+        );
+        expect(fs, FileSystemOperation::RemoveFile, &probe, None, MockResult::Unit);
+    }
 
     #[tokio::test]
     async fn test_validate_worktree_exists() {
@@ -108,6 +294,30 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_adopted_worktree_exists_at_its_location() {
+        use crate::worktree::state::{write_worktree_metadata, WorktreeMetadata};
+
+        let repo = TestRepo::new().await.unwrap();
+        let filesystem = RealFileSystem::new();
+        let location = repo.path().join("outside");
+        let metadata = WorktreeMetadata { location: Some(location.clone()), ..Default::default() };
+        write_worktree_metadata(&filesystem, repo.path(), "adopted", &metadata).await.unwrap();
+
+        // A location that is gone is not a worktree
+        assert!(validate_worktree_exists(repo.path(), "adopted", &filesystem).await.is_err());
+        assert!(validate_worktree_does_not_exist(repo.path(), "adopted", &filesystem)
+            .await
+            .is_ok());
+
+        std::fs::create_dir(&location).unwrap();
+        let exists = validate_worktree_exists(repo.path(), "adopted", &filesystem).await.unwrap();
+        assert_eq!(exists.path, location);
+        assert!(validate_worktree_does_not_exist(repo.path(), "adopted", &filesystem)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_validate_phantom_directory_exists() {
         let repo = TestRepo::new().await.unwrap();
@@ -131,6 +341,95 @@ mod tests {
         assert!(validate_worktree_name("feature branch").is_err()); // Contains space
         assert!(validate_worktree_name("feature@branch").is_err()); // Contains @
         assert!(validate_worktree_name("feature..branch").is_err()); // Contains ..
+        assert!(validate_worktree_name("feature/login").is_ok());
+        for name in [".", "/etc", "feature/", "feature//login", "feature/./login", "../x"] {
+            assert!(validate_worktree_name(name).is_err(), "{name} should be rejected");
+        }
         assert!(validate_worktree_name("feature!branch").is_err()); // Contains !
     }
+
+    #[test]
+    fn test_validate_branch_name() {
+        for valid in ["topic", "user/alice/topic", "fix-1.2", "a@b"] {
+            assert!(validate_branch_name(valid).is_ok(), "{valid}");
+        }
+        for invalid in [
+            "", "-topic", "@", "a@{1}", "a..b", "a b", "a~b", "a:b", "a\\b", "a.", "/a", "a/",
+            "a//b", "a/.b", "a.lock/b",
+        ] {
+            assert!(validate_branch_name(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_validate_branch_prefix() {
+        assert!(validate_branch_prefix("").is_ok());
+        assert!(validate_branch_prefix("user/alice/").is_ok());
+        assert!(validate_branch_prefix("feature-").is_ok());
+        match validate_branch_prefix("user/alice//") {
+            Err(PhantomError::InvalidBranchName { name, .. }) => assert_eq!(name, "user/alice//"),
+            other => panic!("Expected InvalidBranchName, got {other:?}"),
+        }
+        assert!(validate_branch_prefix("bad prefix/").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_case_collision_on_case_insensitive_filesystem() {
+        let fs = MockFileSystem::new();
+        expect_entries(&fs, PHANTOM_DIR, &["Feature-X", "other"]);
+        expect_probe(&fs, true);
+
+        let result = validate_worktree_does_not_exist(Path::new("/repo"), "feature-x", &fs).await;
+        match result {
+            Err(PhantomError::WorktreeNameCollision { name, existing }) => {
+                assert_eq!(name, "feature-x");
+                assert_eq!(existing, "Feature-X");
+            }
+            other => panic!("Expected WorktreeNameCollision, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_case_collision_ignored_on_case_sensitive_filesystem() {
+        let fs = MockFileSystem::new();
+        expect_entries(&fs, PHANTOM_DIR, &["Feature-X"]);
+        expect_probe(&fs, false);
+        expect(
+            &fs,
+            FileSystemOperation::IsDir,
+            &format!("{PHANTOM_DIR}/feature-x"),
+            None,
+            MockResult::Bool(false),
+        );
+
+        let result = validate_worktree_does_not_exist(Path::new("/repo"), "feature-x", &fs).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_case_collision_in_nested_name() {
+        let fs = MockFileSystem::new();
+        expect_entries(&fs, PHANTOM_DIR, &["Team"]);
+        expect_probe(&fs, true);
+
+        let existing = find_case_collision(Path::new("/repo"), "team/feature", &fs).await;
+        assert_eq!(existing.as_deref(), Some("Team"));
+
+        let fs = MockFileSystem::new();
+        expect_entries(&fs, PHANTOM_DIR, &["team"]);
+        expect_entries(&fs, &format!("{PHANTOM_DIR}/team"), &["Feature"]);
+        expect_probe(&fs, true);
+
+        let existing = find_case_collision(Path::new("/repo"), "team/feature", &fs).await;
+        assert_eq!(existing.as_deref(), Some("team/Feature"));
+    }
+
+    #[tokio::test]
+    async fn test_no_case_collision_without_similar_entries() {
+        let fs = MockFileSystem::new();
+        expect_entries(&fs, PHANTOM_DIR, &["feature-y"]);
+
+        // No probe expectations: the filesystem is only probed on a case-insensitive match
+        assert_eq!(find_case_collision(Path::new("/repo"), "feature-x", &fs).await, None);
+    }
 }
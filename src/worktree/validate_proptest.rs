@@ -9,8 +9,9 @@ mod tests {
         fn valid_names_always_pass(
             s in "[a-zA-Z0-9][a-zA-Z0-9._/-]{0,99}"
         ) {
-            // Skip patterns with consecutive dots as they're invalid
+            // Skip patterns with consecutive dots or empty and '.' path components
             prop_assume!(!s.contains(".."));
+            prop_assume!(s.split('/').all(|part| !part.is_empty() && part != "."));
 
             let result = validate_worktree_name(&s);
             prop_assert!(result.is_ok());
@@ -64,8 +65,9 @@ mod tests {
         fn long_names_accepted(
             s in "[a-zA-Z0-9][a-zA-Z0-9._/-]{100,254}"
         ) {
-            // Skip patterns with consecutive dots
+            // Skip patterns with consecutive dots or empty and '.' path components
             prop_assume!(!s.contains(".."));
+            prop_assume!(s.split('/').all(|part| !part.is_empty() && part != "."));
             // Ensure we don't exceed the maximum length
             prop_assume!(s.len() <= crate::worktree::const_validate::MAX_WORKTREE_NAME_LENGTH);
 
@@ -97,8 +99,8 @@ mod tests {
             parts in prop::collection::vec("[a-zA-Z0-9._-]{1,20}", 1..5)
         ) {
             let name = parts.join("/");
-            // Skip if any part is empty or contains consecutive dots
-            prop_assume!(!name.contains("..") && !name.contains("//"));
+            // Skip if any part is '.' or contains consecutive dots
+            prop_assume!(!name.contains("..") && parts.iter().all(|part| part != "."));
 
             let result = validate_worktree_name(&name);
             prop_assert!(result.is_ok());
@@ -140,4 +142,46 @@ mod tests {
             prop_assert!(result.is_ok());
         }
     }
+
+    // Property: Names that could escape the worktrees root are rejected
+    proptest! {
+        #[test]
+        fn traversal_names_fail(
+            parts in prop::collection::vec("[a-zA-Z0-9_-]{1,10}", 0..4),
+            position in 0usize..4,
+            attack in prop::sample::select(&["..", ".", ""]),
+            leading_slash in any::<bool>()
+        ) {
+            let mut parts = parts;
+            let position = position.min(parts.len());
+            parts.insert(position, attack.to_string());
+            let mut name = parts.join("/");
+            if leading_slash {
+                name.insert(0, '/');
+            }
+
+            prop_assert!(validate_worktree_name(&name).is_err(), "{name} was accepted");
+        }
+    }
+
+    // Property: Accepted names stay below the worktrees root
+    proptest! {
+        #[test]
+        fn valid_names_stay_inside_worktrees_root(
+            s in "[a-zA-Z0-9./_-]{1,60}"
+        ) {
+            use crate::worktree::paths::{get_phantom_directory, get_worktree_path};
+            use std::path::{Component, Path};
+
+            prop_assume!(validate_worktree_name(&s).is_ok());
+
+            let root = Path::new("/repo");
+            let path = get_worktree_path(root, &s);
+            let relative = path.strip_prefix(get_phantom_directory(root));
+            prop_assert!(relative.is_ok(), "{} escapes the worktrees root", path.display());
+            let relative = relative.unwrap();
+            prop_assert!(relative.components().all(|c| matches!(c, Component::Normal(_))));
+            prop_assert_eq!(relative.to_str().unwrap(), s.as_str());
+        }
+    }
 }
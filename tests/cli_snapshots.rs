@@ -74,7 +74,8 @@ fn test_phantom_help_output() {
         .stdout(predicate::str::contains("create"))
         .stdout(predicate::str::contains("attach"))
         .stdout(predicate::str::contains("list"))
-        .stdout(predicate::str::contains("delete"));
+        .stdout(predicate::str::contains("delete"))
+        .stdout(predicate::str::contains("Exit codes:"));
 }
 
 #[test]
@@ -193,6 +194,71 @@ fn test_phantom_completion_fish() {
     cmd.assert().success().stdout(predicate::str::contains("complete -c phantom"));
 }
 
+#[test]
+fn test_phantom_shellenv_snapshots() {
+    let snapshots = [
+        ("bash", include_str!("snapshots/shellenv.bash")),
+        ("zsh", include_str!("snapshots/shellenv.zsh")),
+        ("fish", include_str!("snapshots/shellenv.fish")),
+    ];
+    for (shell, expected) in snapshots {
+        let mut cmd = Command::cargo_bin("phantom").unwrap();
+        cmd.args(["shellenv", shell]);
+        cmd.assert().success().stdout(expected);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_phantom_shellenv_bash_cd_and_passthrough() {
+    let temp_dir = setup_test_repo();
+    let repo = temp_dir.path();
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .args(["create", "feat"])
+        .current_dir(repo)
+        .assert()
+        .success();
+
+    let phantom = assert_cmd::cargo::cargo_bin("phantom");
+    let path = format!(
+        "{}:{}",
+        phantom.parent().unwrap().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let script = r#"
+        eval "$(phantom shellenv bash)"
+        phantom cd missing; echo "cd status $?"; pwd
+        phantom cd feat; echo "cd status $?"; pwd
+        phantom where missing >/dev/null 2>&1; echo "where status $?"
+    "#;
+    let output = std::process::Command::new("bash")
+        .args(["-c", script])
+        .current_dir(repo)
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    let repo = repo.canonicalize().unwrap();
+    let where_status = Command::cargo_bin("phantom")
+        .unwrap()
+        .args(["where", "missing"])
+        .current_dir(&repo)
+        .output()
+        .unwrap()
+        .status
+        .code()
+        .unwrap();
+    assert_ne!(where_status, 0);
+    assert_eq!(lines[0], format!("cd status {where_status}"), "{stdout}");
+    assert_eq!(lines[1], repo.display().to_string());
+    assert_eq!(lines[2], "cd status 0");
+    assert_eq!(lines[3], repo.join(".git/phantom/worktrees/feat").display().to_string());
+    assert_eq!(lines[4], format!("where status {where_status}"));
+}
+
 #[test]
 fn test_phantom_invalid_command() {
     let mut cmd = Command::cargo_bin("phantom").unwrap();
@@ -395,6 +395,130 @@ fn test_e2e_copy_files_workflow() {
     assert!(worktree_path.join("README.md").exists());
 }
 
+/// Untracked files only reach a new worktree by being copied
+fn setup_copy_files_project() -> TempDir {
+    let temp_dir = setup_test_project();
+    let repo_path = temp_dir.path();
+    fs::write(repo_path.join(".env"), "KEY=dev\n").unwrap();
+    fs::write(repo_path.join(".env.staging"), "KEY=staging\n").unwrap();
+    fs::write(
+        repo_path.join("phantom.config.json"),
+        r#"{ "postCreate": { "copyFiles": [".env"] } }"#,
+    )
+    .unwrap();
+    temp_dir
+}
+
+#[test]
+fn test_e2e_copy_files_override() {
+    let temp_dir = setup_copy_files_project();
+    let repo_path = temp_dir.path();
+    let worktrees = repo_path.join(".git/phantom/worktrees");
+
+    // --copy-files adds to the configured list
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .args(["create", "merged", "--copy-files", ".env.staging,missing.txt"])
+        .current_dir(repo_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Copied 2 files, skipped 1"));
+    assert!(worktrees.join("merged/.env").exists());
+    assert!(worktrees.join("merged/.env.staging").exists());
+
+    // --copy-files-only replaces it
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .args(["create", "replaced", "--copy-files", ".env.staging", "--copy-files-only"])
+        .current_dir(repo_path)
+        .assert()
+        .success();
+    assert!(!worktrees.join("replaced/.env").exists());
+    assert!(worktrees.join("replaced/.env.staging").exists());
+
+    // --no-copy skips copying altogether
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .args(["create", "bare", "--no-copy"])
+        .current_dir(repo_path)
+        .assert()
+        .success();
+    assert!(!worktrees.join("bare/.env").exists());
+
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .args(["create", "conflict", "--no-copy", "--copy-files", ".env"])
+        .current_dir(repo_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_e2e_editor_settings_and_scratch_dir() {
+    let temp_dir = setup_test_project();
+    let repo_path = temp_dir.path();
+    fs::create_dir_all(repo_path.join(".vscode")).unwrap();
+    fs::write(repo_path.join(".vscode/settings.json"), "{}").unwrap();
+    fs::write(
+        repo_path.join("phantom.config.json"),
+        r#"{ "editors": { "copyVscode": true }, "scratchDir": ".scratch" }"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .args(["create", "feature"])
+        .current_dir(repo_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Copied 1 editor settings files"))
+        .stdout(predicate::str::contains("Scratch directory: .scratch"));
+
+    let worktree = repo_path.join(".git/phantom/worktrees/feature");
+    assert!(worktree.join(".vscode/settings.json").exists());
+    fs::write(worktree.join(".scratch/notes.txt"), "todo").unwrap();
+
+    let status = std::process::Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(&worktree)
+        .output()
+        .unwrap();
+    assert!(!String::from_utf8_lossy(&status.stdout).contains(".scratch"));
+}
+
+#[test]
+fn test_e2e_complete_worktrees() {
+    let temp_dir = setup_test_project();
+    let repo_path = temp_dir.path();
+
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .args(["create", "feature"])
+        .current_dir(repo_path)
+        .assert()
+        .success();
+
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .arg("__complete-worktrees")
+        .current_dir(repo_path.join(".git/phantom/worktrees/feature"))
+        .assert()
+        .success()
+        .stdout("feature\n");
+
+    // Outside a repository completion gets no names and no error
+    let outside = TempDir::new().unwrap();
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .arg("__complete-worktrees")
+        .current_dir(outside.path())
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+}
+
 #[test]
 fn test_e2e_verbose_mode() {
     let temp_dir = setup_test_project();
@@ -417,3 +541,33 @@ fn test_e2e_verbose_mode() {
         .assert()
         .success();
 }
+
+#[test]
+#[cfg(target_os = "macos")]
+fn test_e2e_case_insensitive_name_collision() {
+    let temp_dir = setup_test_project();
+    let repo_path = temp_dir.path();
+
+    // Case-sensitive APFS volumes keep both names apart; nothing to check there
+    fs::write(repo_path.join("CaseProbe"), "").unwrap();
+    if !repo_path.join("caseprobe").exists() {
+        return;
+    }
+    fs::remove_file(repo_path.join("CaseProbe")).unwrap();
+
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .args(["create", "Feature-X"])
+        .current_dir(repo_path)
+        .assert()
+        .success();
+
+    Command::cargo_bin("phantom")
+        .unwrap()
+        .args(["create", "feature-x"])
+        .current_dir(repo_path)
+        .assert()
+        .failure()
+        .code(4) // WORKTREE_EXISTS
+        .stderr(predicate::str::contains("conflicts with existing worktree 'Feature-X'"));
+}
@@ -0,0 +1,92 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(repo)
+        .env("GIT_AUTHOR_NAME", "Test User")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test User")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .status()
+        .expect("failed to run git");
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// A process counts as gone once it no longer exists or is only a zombie
+fn process_alive(pid: &str) -> bool {
+    let output = Command::new("ps").args(["-o", "stat=", "-p", pid]).output().unwrap();
+    let state = String::from_utf8_lossy(&output.stdout);
+    let state = state.trim();
+    !state.is_empty() && !state.starts_with('Z')
+}
+
+fn wait_for<T>(timeout: Duration, mut check: impl FnMut() -> Option<T>) -> Option<T> {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if let Some(value) = check() {
+            return Some(value);
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    None
+}
+
+#[test]
+#[ignore = "spawns the phantom binary and signals it; run with --ignored"]
+fn test_sigint_reaches_exec_child_and_exits_130() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    fs::create_dir(&repo).unwrap();
+    git(&repo, &["init", "-b", "main"]);
+    fs::write(repo.join("README.md"), "# Test\n").unwrap();
+    git(&repo, &["add", "."]);
+    git(&repo, &["commit", "-m", "Initial commit"]);
+
+    let phantom = assert_cmd::cargo::cargo_bin("phantom");
+    let status = Command::new(&phantom)
+        .args(["create", "busy"])
+        .current_dir(&repo)
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let pid_file = temp_dir.path().join("sleep.pid");
+    let mut child = Command::new(&phantom)
+        .args(["exec", "busy", "sh", "-c", "echo $$ > \"$SLEEP_PID_FILE\"; exec sleep 30"])
+        .current_dir(&repo)
+        .env("SLEEP_PID_FILE", &pid_file)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let sleep_pid = wait_for(Duration::from_secs(10), || {
+        fs::read_to_string(&pid_file)
+            .ok()
+            .map(|pid| pid.trim().to_string())
+            .filter(|p| !p.is_empty())
+    })
+    .expect("sleep never started");
+    assert!(process_alive(&sleep_pid));
+
+    let status = Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap();
+    assert!(status.success());
+    let status = child.wait().unwrap();
+
+    let gone = wait_for(Duration::from_secs(5), || (!process_alive(&sleep_pid)).then_some(()));
+    if gone.is_none() {
+        let _ = Command::new("kill").args(["-KILL", &sleep_pid]).status();
+        panic!("sleep (pid {sleep_pid}) outlived phantom");
+    }
+    assert_eq!(status.code(), Some(130));
+}
@@ -0,0 +1,109 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// Stand-in for fzf that records its pid and blocks like a picker waiting for input
+const FAKE_FZF: &str = r#"#!/bin/sh
+if [ "$1" = "--version" ]; then
+    echo "0.0.0 (fake)"
+    exit 0
+fi
+echo $$ > "$FAKE_FZF_PID_FILE"
+exec sleep 30
+"#;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(repo)
+        .env("GIT_AUTHOR_NAME", "Test User")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test User")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .status()
+        .expect("failed to run git");
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// A process counts as gone once it no longer exists or is only a zombie
+fn process_alive(pid: &str) -> bool {
+    let output = Command::new("ps").args(["-o", "stat=", "-p", pid]).output().unwrap();
+    let state = String::from_utf8_lossy(&output.stdout);
+    let state = state.trim();
+    !state.is_empty() && !state.starts_with('Z')
+}
+
+fn wait_for<T>(timeout: Duration, mut check: impl FnMut() -> Option<T>) -> Option<T> {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if let Some(value) = check() {
+            return Some(value);
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    None
+}
+
+#[test]
+#[ignore = "spawns the phantom binary and signals it; run with --ignored"]
+fn test_killing_phantom_terminates_fzf() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    fs::create_dir(&repo).unwrap();
+    git(&repo, &["init", "-b", "main"]);
+    fs::write(repo.join("README.md"), "# Test\n").unwrap();
+    git(&repo, &["add", "."]);
+    git(&repo, &["commit", "-m", "Initial commit"]);
+
+    let phantom = assert_cmd::cargo::cargo_bin("phantom");
+    let status = Command::new(&phantom)
+        .args(["create", "picked"])
+        .current_dir(&repo)
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let bin_dir = temp_dir.path().join("bin");
+    fs::create_dir(&bin_dir).unwrap();
+    let fake_fzf = bin_dir.join("fzf");
+    fs::write(&fake_fzf, FAKE_FZF).unwrap();
+    fs::set_permissions(&fake_fzf, fs::Permissions::from_mode(0o755)).unwrap();
+    let pid_file = temp_dir.path().join("fzf.pid");
+    let path = format!("{}:{}", bin_dir.display(), std::env::var("PATH").unwrap_or_default());
+
+    let mut child = Command::new(&phantom)
+        .args(["where", "--fzf"])
+        .current_dir(&repo)
+        .env("PATH", path)
+        .env("FAKE_FZF_PID_FILE", &pid_file)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let fzf_pid = wait_for(Duration::from_secs(10), || {
+        fs::read_to_string(&pid_file)
+            .ok()
+            .map(|pid| pid.trim().to_string())
+            .filter(|p| !p.is_empty())
+    })
+    .expect("fake fzf never started");
+    assert!(process_alive(&fzf_pid));
+
+    let status = Command::new("kill").args(["-TERM", &child.id().to_string()]).status().unwrap();
+    assert!(status.success());
+    child.wait().unwrap();
+
+    let gone = wait_for(Duration::from_secs(5), || (!process_alive(&fzf_pid)).then_some(()));
+    if gone.is_none() {
+        let _ = Command::new("kill").args(["-KILL", &fzf_pid]).status();
+        panic!("fzf (pid {fzf_pid}) outlived phantom");
+    }
+}
@@ -0,0 +1,105 @@
+//! Guards the documented library surface re-exported from `lib.rs`
+//!
+//! Adding or removing an export is a deliberate API change: update
+//! `EXPECTED_EXPORTS` in the same commit.
+
+use std::fs;
+
+/// Everything a library user can reach without going through a hidden module
+const EXPECTED_EXPORTS: &[&str] = &[
+    "AttachWorktreeOptions",
+    "AttachWorktreeSuccess",
+    "CommandArgs",
+    "CommandConfig",
+    "CommandExecutor",
+    "CommandExpectationBuilder",
+    "CommandOutput",
+    "CopyFilesSelection",
+    "CreateWorktreeOptions",
+    "CreateWorktreeSuccess",
+    "DeleteOutcome",
+    "DeleteWorktreeOptions",
+    "DeleteWorktreeSuccess",
+    "EnvMap",
+    "FileSystem",
+    "FileSystemExpectation",
+    "FileSystemOperation",
+    "ForceOptions",
+    "ListOptions",
+    "ListWorktreesSuccess",
+    "MockCommandExecutor",
+    "MockFileSystem",
+    "MockResult",
+    "OutputStream",
+    "Phantom",
+    "PhantomError",
+    "ProgressReporter",
+    "RealCommandExecutor",
+    "RealFileSystem",
+    "Result",
+    "SpawnSuccess",
+    "WhereWorktreeSuccess",
+    "WorktreeInfo",
+    "WorktreeOrigin",
+    "api",
+];
+
+/// Names made public by `lib.rs`: every `pub use` item and each `pub mod`
+/// not marked `#[doc(hidden)]` or `#[cfg(test)]`
+fn exported_names(source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut hidden = false;
+    for statement in source.split(';') {
+        for line in statement.lines().map(str::trim) {
+            if line == "#[doc(hidden)]" || line == "#[cfg(test)]" {
+                hidden = true;
+            } else if let Some(module) = line.strip_prefix("pub mod ") {
+                if !hidden {
+                    names.push(module.to_string());
+                }
+                hidden = false;
+            } else if line.starts_with("pub use ") {
+                let rest = &statement[statement.find("pub use ").unwrap() + "pub use ".len()..];
+                let items = rest.rsplit_once("::").map_or(rest, |(_, items)| items);
+                names.extend(
+                    items
+                        .trim_matches(|c: char| c == '{' || c == '}' || c.is_whitespace())
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .map(String::from),
+                );
+                break;
+            } else if !line.is_empty() && !line.starts_with("//") && !line.starts_with("#[") {
+                hidden = false;
+            }
+        }
+    }
+    names.sort();
+    names
+}
+
+#[test]
+fn test_exported_surface_is_unchanged() {
+    let source = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/src/lib.rs")).unwrap();
+    assert_eq!(exported_names(&source), EXPECTED_EXPORTS);
+}
+
+#[test]
+fn test_exports_resolve_from_the_crate_root() {
+    // Fails to compile if an export is renamed or moved behind a hidden module
+    #[allow(unused_imports)]
+    use phantom_rs::{
+        api, AttachWorktreeOptions, AttachWorktreeSuccess, CommandArgs, CommandConfig,
+        CommandExecutor, CommandExpectationBuilder, CommandOutput, CopyFilesSelection,
+        CreateWorktreeOptions, CreateWorktreeSuccess, DeleteOutcome, DeleteWorktreeOptions,
+        DeleteWorktreeSuccess, EnvMap, FileSystem, FileSystemExpectation, FileSystemOperation,
+        ForceOptions, ListOptions, ListWorktreesSuccess, MockCommandExecutor, MockFileSystem,
+        MockResult, OutputStream, Phantom, PhantomError, ProgressReporter, RealCommandExecutor,
+        RealFileSystem, Result, SpawnSuccess, WhereWorktreeSuccess, WorktreeInfo, WorktreeOrigin,
+    };
+
+    let phantom: Phantom<MockCommandExecutor, MockFileSystem> =
+        Phantom::new("/repo", MockCommandExecutor::new(), MockFileSystem::new());
+    assert_eq!(phantom.git_root(), std::path::Path::new("/repo"));
+}
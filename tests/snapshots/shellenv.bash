@@ -0,0 +1,14 @@
+# phantom shell integration for bash: adds `phantom cd <name>` and `phantom cd --fzf`
+# Add to ~/.bashrc: eval "$(phantom shellenv bash)"
+phantom() {
+    if [ "$1" = "cd" ]; then
+        shift
+        local dir
+        dir="$(command phantom where "$@")" || return
+        # Nothing is printed when an fzf selection is cancelled
+        [ -n "$dir" ] || return 0
+        cd -- "$dir"
+    else
+        command phantom "$@"
+    fi
+}
@@ -0,0 +1,13 @@
+# phantom shell integration for fish: adds `phantom cd <name>` and `phantom cd --fzf`
+# Add to ~/.config/fish/config.fish: phantom shellenv fish | source
+function phantom --wraps phantom --description 'phantom, plus `phantom cd <name>`'
+    if test "$argv[1]" = cd
+        set -e argv[1]
+        set -l dir (command phantom where $argv); or return
+        # Nothing is printed when an fzf selection is cancelled
+        test -n "$dir"; or return 0
+        cd $dir
+    else
+        command phantom $argv
+    end
+end